@@ -0,0 +1,275 @@
+//! Reading and writing cookie files.
+//!
+//! Two formats are supported: a JSON array of cookie objects, as written by
+//! most browser extensions, and the Netscape `cookies.txt` format used by
+//! curl and wget.
+
+use crate::protocol::Cookie;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// The header line of a Netscape cookie file.
+const NETSCAPE_HEADER: &str = "# Netscape HTTP Cookie File";
+
+/// Domain prefix marking an HTTP-only cookie in a Netscape cookie file.
+const HTTP_ONLY_PREFIX: &str = "#HttpOnly_";
+
+#[derive(Debug, Error)]
+pub enum CookieFileError {
+    #[error("Unknown cookie file format '{0}' (expected json or netscape)")]
+    UnknownFormat(String),
+
+    #[error("Invalid JSON cookie file: {0}")]
+    Json(#[from] serde_json::Error),
+
+    #[error("Invalid cookie on line {line}: {reason}")]
+    Netscape { line: usize, reason: String },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CookieFormat {
+    Json,
+    Netscape,
+}
+
+impl CookieFormat {
+    /// Pick the format from an explicit `--format` name, falling back to the
+    /// file extension: `.json` is JSON, anything else (`cookies.txt`) Netscape.
+    pub fn detect(path: &str, format: Option<&str>) -> Result<Self, CookieFileError> {
+        match format.map(str::to_lowercase).as_deref() {
+            Some("json") => Ok(Self::Json),
+            Some("netscape") | Some("txt") => Ok(Self::Netscape),
+            Some(other) => Err(CookieFileError::UnknownFormat(other.to_string())),
+            None if path.to_lowercase().ends_with(".json") => Ok(Self::Json),
+            None => Ok(Self::Netscape),
+        }
+    }
+}
+
+/// A cookie as it appears in a JSON cookie file.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct JsonCookie {
+    name: String,
+    value: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    domain: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    path: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    expires: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    http_only: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    secure: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    same_site: Option<String>,
+}
+
+impl From<&Cookie> for JsonCookie {
+    fn from(c: &Cookie) -> Self {
+        Self {
+            name: c.name.clone(),
+            value: c.value.clone(),
+            domain: c.domain.clone(),
+            path: c.path.clone(),
+            expires: c.expires,
+            http_only: c.http_only,
+            secure: c.secure,
+            same_site: c.same_site.clone(),
+        }
+    }
+}
+
+impl From<JsonCookie> for Cookie {
+    fn from(c: JsonCookie) -> Self {
+        Self {
+            name: c.name,
+            value: c.value,
+            domain: c.domain,
+            path: c.path,
+            expires: c.expires,
+            http_only: c.http_only,
+            secure: c.secure,
+            same_site: c.same_site,
+        }
+    }
+}
+
+/// Serialize cookies in the given format.
+pub fn write_cookies(cookies: &[Cookie], format: CookieFormat) -> Result<String, CookieFileError> {
+    match format {
+        CookieFormat::Json => {
+            let cookies: Vec<JsonCookie> = cookies.iter().map(JsonCookie::from).collect();
+            Ok(serde_json::to_string_pretty(&cookies)?)
+        }
+        CookieFormat::Netscape => {
+            let mut out = format!("{}\n", NETSCAPE_HEADER);
+            for c in cookies {
+                let domain = c.domain.as_deref().unwrap_or_default();
+                // Session cookies (no or negative expiry) are written as 0.
+                let expires = c.expires.filter(|e| *e > 0.0).unwrap_or(0.0) as i64;
+                out.push_str(&format!(
+                    "{}{}\t{}\t{}\t{}\t{}\t{}\t{}\n",
+                    if c.http_only == Some(true) {
+                        HTTP_ONLY_PREFIX
+                    } else {
+                        ""
+                    },
+                    domain,
+                    netscape_bool(domain.starts_with('.')),
+                    c.path.as_deref().unwrap_or("/"),
+                    netscape_bool(c.secure == Some(true)),
+                    expires,
+                    c.name,
+                    c.value
+                ));
+            }
+            Ok(out)
+        }
+    }
+}
+
+/// Parse cookies written in the given format.
+pub fn read_cookies(text: &str, format: CookieFormat) -> Result<Vec<Cookie>, CookieFileError> {
+    match format {
+        CookieFormat::Json => {
+            let cookies: Vec<JsonCookie> = serde_json::from_str(text)?;
+            Ok(cookies.into_iter().map(Cookie::from).collect())
+        }
+        CookieFormat::Netscape => text
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| {
+                let line = line.trim();
+                !line.is_empty() && (!line.starts_with('#') || line.starts_with(HTTP_ONLY_PREFIX))
+            })
+            .map(|(i, line)| parse_netscape_line(line, i + 1))
+            .collect(),
+    }
+}
+
+fn netscape_bool(value: bool) -> &'static str {
+    if value { "TRUE" } else { "FALSE" }
+}
+
+fn parse_netscape_line(line: &str, line_no: usize) -> Result<Cookie, CookieFileError> {
+    let invalid = |reason: String| CookieFileError::Netscape {
+        line: line_no,
+        reason,
+    };
+
+    let fields: Vec<&str> = line.trim_end_matches(['\r', '\n']).split('\t').collect();
+    // The value may be empty, which leaves six fields.
+    if fields.len() < 6 || fields.len() > 7 {
+        return Err(invalid(format!(
+            "expected 7 tab-separated fields, found {}",
+            fields.len()
+        )));
+    }
+    let (domain, http_only) = match fields[0].strip_prefix(HTTP_ONLY_PREFIX) {
+        Some(domain) => (domain, true),
+        None => (fields[0], false),
+    };
+    let expires: i64 = fields[4]
+        .trim()
+        .parse()
+        .map_err(|_| invalid(format!("expiry '{}' is not a number", fields[4])))?;
+
+    Ok(Cookie {
+        name: fields[5].to_string(),
+        value: fields.get(6).copied().unwrap_or_default().to_string(),
+        domain: Some(domain.to_string()),
+        path: Some(fields[2].to_string()),
+        expires: (expires > 0).then_some(expires as f64),
+        http_only: Some(http_only),
+        secure: Some(fields[3].eq_ignore_ascii_case("TRUE")),
+        same_site: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cookies() -> Vec<Cookie> {
+        vec![
+            Cookie {
+                name: "session".into(),
+                value: "abc123".into(),
+                domain: Some(".example.com".into()),
+                path: Some("/".into()),
+                expires: None,
+                http_only: Some(true),
+                secure: Some(true),
+                same_site: None,
+            },
+            Cookie {
+                name: "theme".into(),
+                value: "".into(),
+                domain: Some("app.example.com".into()),
+                path: Some("/settings".into()),
+                expires: Some(1_900_000_000.0),
+                http_only: Some(false),
+                secure: Some(false),
+                same_site: None,
+            },
+        ]
+    }
+
+    fn round_trip(format: CookieFormat) -> Vec<Cookie> {
+        let text = write_cookies(&cookies(), format).unwrap();
+        read_cookies(&text, format).unwrap()
+    }
+
+    #[test]
+    fn test_json_round_trip() {
+        let text = write_cookies(&cookies(), CookieFormat::Json).unwrap();
+        assert!(text.contains("\"httpOnly\": true"), "{}", text);
+        assert_eq!(
+            serde_json::to_value(round_trip(CookieFormat::Json)).unwrap(),
+            serde_json::to_value(cookies()).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_netscape_round_trip() {
+        let text = write_cookies(&cookies(), CookieFormat::Netscape).unwrap();
+        assert_eq!(
+            text,
+            "# Netscape HTTP Cookie File\n\
+             #HttpOnly_.example.com\tTRUE\t/\tTRUE\t0\tsession\tabc123\n\
+             app.example.com\tFALSE\t/settings\tFALSE\t1900000000\ttheme\t\n"
+        );
+        assert_eq!(
+            serde_json::to_value(round_trip(CookieFormat::Netscape)).unwrap(),
+            serde_json::to_value(cookies()).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_netscape_rejects_malformed_lines() {
+        let text = "# comment\n\nexample.com\tFALSE\t/\tFALSE\tsoon\tid\t1\n";
+        match read_cookies(text, CookieFormat::Netscape) {
+            Err(CookieFileError::Netscape { line, .. }) => assert_eq!(line, 3),
+            other => panic!("Expected a line error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_format_detection() {
+        assert_eq!(
+            CookieFormat::detect("cookies.JSON", None).unwrap(),
+            CookieFormat::Json
+        );
+        assert_eq!(
+            CookieFormat::detect("cookies.txt", None).unwrap(),
+            CookieFormat::Netscape
+        );
+        assert_eq!(
+            CookieFormat::detect("cookies.txt", Some("json")).unwrap(),
+            CookieFormat::Json
+        );
+        assert!(CookieFormat::detect("cookies.txt", Some("yaml")).is_err());
+    }
+}
@@ -1,4 +1,8 @@
-use crate::protocol::{ChangeType, ElementChange, ScannerData, ScannerProtocolResponse};
+use crate::protocol::{
+    AvailabilityStatus, ChangeType, Element, ElementChange, IntentAvailability, PageChanges,
+    ScanResult, ScannerData, ScannerProtocolResponse,
+};
+use std::collections::{HashMap, HashSet};
 
 /// Default sensitive field names that should be masked in output.
 const DEFAULT_SENSITIVE_FIELDS: &[&str] = &[
@@ -19,73 +23,8 @@ pub fn format_response(resp: &ScannerProtocolResponse) -> String {
                 let mut output = format!("@ {} \"{}\"\n", scan.page.url, scan.page.title);
 
                 for el in &scan.elements {
-                    // e.g. [1] input/email "Username" {required}
-                    let type_str = if let Some(role) = &el.role {
-                        format!("{}/{}", el.element_type, role)
-                    } else {
-                        el.element_type.clone()
-                    };
-
-                    let label = el.text.clone().or(el.label.clone()).unwrap_or_default();
-
-                    // Build state flags
-                    let mut flags = Vec::new();
-                    if el.state.checked {
-                        flags.push("checked");
-                    }
-                    if el.state.selected {
-                        flags.push("selected");
-                    }
-                    if el.state.disabled {
-                        flags.push("disabled");
-                    }
-                    if el.state.readonly {
-                        flags.push("readonly");
-                    }
-
-                    let flags_str = if flags.is_empty() {
-                        String::new()
-                    } else {
-                        format!(" {{{}}}", flags.join(", "))
-                    };
-
-                    // Add value suffix if present
-                    let value_suffix = if let Some(ref val) = el.value
-                        && !val.is_empty()
-                    {
-                        let display_val = mask_sensitive(val, &el.element_type, &[]);
-                        format!(" = {:?}", display_val)
-                    } else if el.element_type == "checkbox" || el.element_type == "radio" {
-                        // Show checked state as value
-                        if el.state.checked {
-                            " = checked".to_string()
-                        } else {
-                            String::new()
-                        }
-                    } else {
-                        String::new()
-                    };
-
-                    // Format with or without position data based on full_mode
-                    if scan.full_mode {
-                        output.push_str(&format!(
-                            "[{}] {} {:?} @ ({:.0},{:.0}) {:.0}x{:.0}{}{}\n",
-                            el.id,
-                            type_str,
-                            label,
-                            el.rect.x,
-                            el.rect.y,
-                            el.rect.width,
-                            el.rect.height,
-                            flags_str,
-                            value_suffix
-                        ));
-                    } else {
-                        output.push_str(&format!(
-                            "[{}] {} {:?}{}{}\n",
-                            el.id, type_str, label, flags_str, value_suffix
-                        ));
-                    }
+                    output.push_str(&format_element(el, scan.full_mode));
+                    output.push('\n');
                 }
 
                 if let Some(patterns) = &scan.patterns {
@@ -127,15 +66,16 @@ pub fn format_response(resp: &ScannerProtocolResponse) -> String {
                 if let Some(changes) = &scan.changes
                     && !changes.is_empty()
                 {
-                    output.push_str("\n\n# changes\n");
-                    for change in changes {
-                        output.push_str(&format_change(change));
-                    }
+                    output.push_str("\n\n");
+                    output.push_str(&format_changes(changes));
                 }
 
                 output
             }
-            ScannerData::Value(v) => format!("Value: {}", v),
+            ScannerData::Value(v) => match v.get("html").and_then(|h| h.as_str()) {
+                Some(html) => format_html(v.get("selector").and_then(|s| s.as_str()), html),
+                None => format_value(v),
+            },
             ScannerData::Action(a) => {
                 let mut output = format!("ok {}\n", a.message.as_deref().unwrap_or("action"));
 
@@ -163,40 +103,427 @@ pub fn format_response(resp: &ScannerProtocolResponse) -> String {
     }
 }
 
+/// Longest string value printed in full; anything longer is cut short.
+const MAX_VALUE_CHARS: usize = 1000;
+
+/// Render the HTML returned by `html`, untruncated, headed by the selector
+/// it was scoped to.
+fn format_html(selector: Option<&str>, html: &str) -> String {
+    let heading = match selector {
+        Some(selector) => format!("HTML of \"{}\":", selector),
+        None => "HTML:".to_string(),
+    };
+    if html.is_empty() {
+        format!("{} (empty)", heading)
+    } else {
+        format!("{}\n{}", heading, html)
+    }
+}
+
+/// Render a value returned by the scanner: objects and arrays indented over
+/// several lines, scalars inline, and long strings truncated.
+fn format_value(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::Object(_) | serde_json::Value::Array(_) => format!(
+            "Value:\n{}",
+            serde_json::to_string_pretty(value).unwrap_or_else(|_| value.to_string())
+        ),
+        serde_json::Value::String(text) => {
+            let total = text.chars().count();
+            if total <= MAX_VALUE_CHARS {
+                return format!("Value: {}", text);
+            }
+            let kept: String = text.chars().take(MAX_VALUE_CHARS).collect();
+            format!(
+                "Value: {}\u{2026}({} more chars)\n# use eval to read the full value",
+                kept,
+                total - MAX_VALUE_CHARS
+            )
+        }
+        other => format!("Value: {}", other),
+    }
+}
+
+/// One element as a single OIL line, without the trailing newline.
+fn format_element(el: &Element, full_mode: bool) -> String {
+    // e.g. [1] input/email "Username" {required}
+    let type_str = if let Some(role) = &el.role {
+        format!("{}/{}", el.element_type, role)
+    } else {
+        el.element_type.clone()
+    };
+
+    let label = el.text.clone().or(el.label.clone()).unwrap_or_default();
+
+    // Build state flags
+    let mut flags = Vec::new();
+    if el.state.checked {
+        flags.push("checked");
+    }
+    if el.state.selected {
+        flags.push("selected");
+    }
+    if el.state.disabled {
+        flags.push("disabled");
+    }
+    if el.state.readonly {
+        flags.push("readonly");
+    }
+
+    let flags_str = if flags.is_empty() {
+        String::new()
+    } else {
+        format!(" {{{}}}", flags.join(", "))
+    };
+
+    // Add value suffix if present
+    let value_suffix = if let Some(ref val) = el.value
+        && !val.is_empty()
+    {
+        let display_val = mask_sensitive(val, &el.element_type, &[]);
+        format!(" = {:?}", display_val)
+    } else if el.element_type == "checkbox" || el.element_type == "radio" {
+        // Show checked state as value
+        if el.state.checked {
+            " = checked".to_string()
+        } else {
+            String::new()
+        }
+    } else {
+        String::new()
+    };
+
+    let count_suffix = match el.count {
+        Some(n) if n > 1 => format!(" ×{}", n),
+        _ => String::new(),
+    };
+
+    // Format with or without position data based on full_mode
+    if full_mode {
+        format!(
+            "[{}] {} {:?} @ ({:.0},{:.0}) {:.0}x{:.0}{}{}{}",
+            el.id,
+            type_str,
+            label,
+            el.rect.x,
+            el.rect.y,
+            el.rect.width,
+            el.rect.height,
+            count_suffix,
+            flags_str,
+            value_suffix
+        )
+    } else {
+        format!(
+            "[{}] {} {:?}{}{}{}",
+            el.id, type_str, label, count_suffix, flags_str, value_suffix
+        )
+    }
+}
+
+/// Render a scan as an indented element tree built from `Element.children`,
+/// two spaces per level. Elements no other element lists as a child are the
+/// roots. `depth` limits how many levels are printed; `None` prints them all.
+pub fn format_scan_tree(scan: &ScanResult, depth: Option<usize>) -> String {
+    let by_id: HashMap<u32, &Element> = scan.elements.iter().map(|el| (el.id, el)).collect();
+    let nested: HashSet<u32> = scan
+        .elements
+        .iter()
+        .flat_map(|el| el.children.iter().copied())
+        .collect();
+
+    let mut output = format!("@ {} \"{}\"\n", scan.page.url, scan.page.title);
+    let mut visited = HashSet::new();
+    for root in scan.elements.iter().filter(|el| !nested.contains(&el.id)) {
+        push_tree_node(
+            &mut output,
+            root,
+            &by_id,
+            0,
+            depth,
+            scan.full_mode,
+            &mut visited,
+        );
+    }
+    output
+}
+
+fn push_tree_node(
+    output: &mut String,
+    el: &Element,
+    by_id: &HashMap<u32, &Element>,
+    level: usize,
+    depth: Option<usize>,
+    full_mode: bool,
+    visited: &mut HashSet<u32>,
+) {
+    if depth.is_some_and(|d| level >= d) || !visited.insert(el.id) {
+        return;
+    }
+    output.push_str(&"  ".repeat(level));
+    output.push_str(&format_element(el, full_mode));
+    output.push('\n');
+    for child in el.children.iter().filter_map(|id| by_id.get(id)) {
+        push_tree_node(output, child, by_id, level + 1, depth, full_mode, visited);
+    }
+}
+
+/// Turn a scanned table, its `headers` row and a grid of body `rows`, into
+/// row objects keyed by header text. Blank headers, and every column of a
+/// table without headers, are keyed `col_0`, `col_1`, ...; a repeated header
+/// gets a `_2`, `_3`, ... suffix. `columns` keeps the keys in table order.
+pub fn table_records(table: &serde_json::Value) -> serde_json::Value {
+    let rows = table["rows"]
+        .as_array()
+        .map(Vec::as_slice)
+        .unwrap_or_default();
+    let headers = table["headers"].as_array().map(Vec::as_slice);
+    let width = headers.map(<[serde_json::Value]>::len).unwrap_or_else(|| {
+        rows.iter()
+            .map(|row| row.as_array().map_or(0, Vec::len))
+            .max()
+            .unwrap_or(0)
+    });
+
+    let mut seen: HashMap<String, usize> = HashMap::new();
+    let columns: Vec<String> = (0..width)
+        .map(|i| {
+            let header = headers
+                .and_then(|h| h[i].as_str())
+                .map(str::trim)
+                .filter(|h| !h.is_empty())
+                .map_or_else(|| format!("col_{}", i), str::to_string);
+            let count = seen.entry(header.clone()).or_insert(0);
+            *count += 1;
+            if *count == 1 {
+                header
+            } else {
+                format!("{}_{}", header, count)
+            }
+        })
+        .collect();
+
+    let records: Vec<serde_json::Value> = rows
+        .iter()
+        .map(|row| {
+            let cells = row.as_array().map(Vec::as_slice).unwrap_or_default();
+            let record = columns
+                .iter()
+                .enumerate()
+                .map(|(i, column)| {
+                    let cell = cells.get(i).cloned().unwrap_or_else(|| "".into());
+                    (column.clone(), cell)
+                })
+                .collect();
+            serde_json::Value::Object(record)
+        })
+        .collect();
+
+    serde_json::json!({ "id": table["id"], "columns": columns, "rows": records })
+}
+
+/// Render extraction results as CSV: a header row of field names, then one
+/// line per item. Tables made by [`table_records`] each get their own header
+/// in column order, with a blank line between tables.
+pub fn format_extract_csv(results: &serde_json::Value) -> String {
+    let items = results.as_array().map(Vec::as_slice).unwrap_or_default();
+    let is_table = |item: &serde_json::Value| item["columns"].is_array() && item["rows"].is_array();
+
+    if !items.is_empty() && items.iter().all(is_table) {
+        return items
+            .iter()
+            .map(|table| {
+                let columns: Vec<String> = table["columns"]
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .map(csv_text)
+                    .collect();
+                let rows = table["rows"]
+                    .as_array()
+                    .map(Vec::as_slice)
+                    .unwrap_or_default();
+                records_csv(&columns, rows)
+            })
+            .collect::<Vec<_>>()
+            .join("\n\n");
+    }
+
+    let Some(header) = items.first().and_then(|item| item.as_object()) else {
+        return String::new();
+    };
+    let fields: Vec<String> = header.keys().cloned().collect();
+    records_csv(&fields, items)
+}
+
+fn records_csv(fields: &[String], records: &[serde_json::Value]) -> String {
+    std::iter::once(csv_line(fields.iter().cloned()))
+        .chain(records.iter().map(|record| {
+            csv_line(
+                fields
+                    .iter()
+                    .map(|f| record.get(f.as_str()).map(csv_text).unwrap_or_default()),
+            )
+        }))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn csv_text(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::String(s) => s.clone(),
+        serde_json::Value::Null => String::new(),
+        other => other.to_string(),
+    }
+}
+
+fn csv_line(cells: impl Iterator<Item = String>) -> String {
+    cells
+        .map(|text| {
+            if text.contains([',', '"', '\n', '\r']) {
+                format!("\"{}\"", text.replace('"', "\"\""))
+            } else {
+                text
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Mask every quoted literal on a command line that mentions a sensitive
+/// field, pulls a value from the environment with `$ENV{...}` or marks its
+/// value `--secret`.
 pub fn mask_sensitive_log(log: &str) -> String {
-    let mut masked = log.to_string();
+    mask_sensitive_log_with(log, &[])
+}
+
+/// [`mask_sensitive_log`] with `sensitive_fields` treated as sensitive on top
+/// of the default names.
+pub fn mask_sensitive_log_with(log: &str, sensitive_fields: &[String]) -> String {
     let lower_log = log.to_lowercase();
+    let sensitive = log.contains("$ENV{")
+        || log.contains("--secret")
+        || DEFAULT_SENSITIVE_FIELDS
+            .iter()
+            .any(|key| lower_log.contains(key))
+        || sensitive_fields
+            .iter()
+            .any(|key| !key.is_empty() && lower_log.contains(&key.to_lowercase()));
+    if !sensitive {
+        return log.to_string();
+    }
 
-    for key in DEFAULT_SENSITIVE_FIELDS {
-        if lower_log.contains(key) {
-            if let Some(start) = masked.find('"')
-                && let Some(end) = masked[start + 1..].rfind('"')
-            {
-                masked.replace_range(start + 1..start + 1 + end, "********");
+    // Rebuilt rather than edited in place, so masking one literal cannot
+    // shift the offsets of the next.
+    let mut masked = String::with_capacity(log.len());
+    let mut chars = log.chars();
+    while let Some(c) = chars.next() {
+        masked.push(c);
+        if c != '"' {
+            continue;
+        }
+        // Skip to the closing quote, past any escaped ones
+        masked.push_str("********");
+        while let Some(c) = chars.next() {
+            match c {
+                '\\' => {
+                    chars.next();
+                }
+                '"' => {
+                    masked.push('"');
+                    break;
+                }
+                _ => {}
             }
-            break;
         }
     }
     masked
 }
 
-pub fn mask_sensitive(value: &str, field_name: &str, sensitive_fields: &[String]) -> String {
+/// Whether a field name refers to a secret, using the default list plus any extras.
+pub fn is_sensitive_field(field_name: &str, sensitive_fields: &[String]) -> bool {
     let lower_field = field_name.to_lowercase();
 
-    let is_sensitive = sensitive_fields
+    sensitive_fields
         .iter()
         .any(|f| lower_field.contains(&f.to_lowercase()))
         || DEFAULT_SENSITIVE_FIELDS
             .iter()
-            .any(|f| lower_field.contains(*f));
+            .any(|f| lower_field.contains(*f))
+}
 
-    if is_sensitive {
+pub fn mask_sensitive(value: &str, field_name: &str, sensitive_fields: &[String]) -> String {
+    if is_sensitive_field(field_name, sensitive_fields) {
         "••••••••".to_string()
     } else {
         value.to_string()
     }
 }
 
+/// Render the differences between two scans, one section per kind of
+/// change, or a single line when there are none.
+pub fn format_page_changes(changes: &PageChanges) -> String {
+    if changes.is_empty() {
+        return "# changes
+No changes since the last scan"
+            .to_string();
+    }
+    let mut output = String::from("# changes");
+    if let Some(url) = &changes.url {
+        output.push_str(&format!("\nurl: {}", url));
+    }
+    if let Some(title) = &changes.title {
+        output.push_str(&format!("\ntitle: {}", title));
+    }
+    for (heading, marker, lines) in [
+        ("added", '+', &changes.added),
+        ("removed", '-', &changes.removed),
+        ("changed", '~', &changes.changed),
+    ] {
+        if !lines.is_empty() {
+            output.push_str(&format!("\n{}:", heading));
+            for line in lines {
+                output.push_str(&format!("\n{} {}", marker, line));
+            }
+        }
+    }
+    output
+}
+
+/// Explain why an intent cannot run on the current page and what to do
+/// about it.
+pub fn format_intent_guidance(availability: &IntentAvailability) -> String {
+    let name = &availability.name;
+    let reason = availability
+        .trigger_reason
+        .as_deref()
+        .map(|r| format!(" ({})", r))
+        .unwrap_or_default();
+    match availability.status {
+        AvailabilityStatus::Ready => format!("Intent '{}' is ready", name),
+        AvailabilityStatus::NavigateRequired => format!(
+            "Intent '{}' does not run on this page{}\nHint: navigate to a page it is meant for, then retry",
+            name, reason
+        ),
+        AvailabilityStatus::MissingPattern => format!(
+            "Intent '{}' needs something this page does not show{}\nHint: navigate to a page with it, or run 'observe' to see what was detected",
+            name, reason
+        ),
+        AvailabilityStatus::Unavailable => {
+            format!("Intent '{}' is unavailable{}", name, reason)
+        }
+    }
+}
+
+/// Render a `# changes` section with one line per element change.
+pub fn format_changes(changes: &[ElementChange]) -> String {
+    let mut output = String::from("# changes\n");
+    for change in changes {
+        output.push_str(&format_change(change));
+    }
+    output
+}
+
 /// Format a single element change for display.
 fn format_change(change: &ElementChange) -> String {
     let id = change.id;
@@ -211,3 +538,138 @@ fn format_change(change: &ElementChange) -> String {
         ChangeType::PositionChanged => format!("~ [{}] moved\n", id),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn value_output(value: serde_json::Value) -> String {
+        format_response(&ScannerProtocolResponse::Ok {
+            data: Box::new(ScannerData::Value(value)),
+            warnings: vec![],
+        })
+    }
+
+    #[test]
+    fn test_value_object_is_indented() {
+        assert_eq!(
+            value_output(json!({"name": "Ada", "age": 36})),
+            "Value:\n{\n  \"age\": 36,\n  \"name\": \"Ada\"\n}"
+        );
+    }
+
+    #[test]
+    fn test_value_array_is_indented() {
+        assert_eq!(value_output(json!(["a", 1])), "Value:\n[\n  \"a\",\n  1\n]");
+    }
+
+    #[test]
+    fn test_value_long_string_is_truncated() {
+        let output = value_output(json!("x".repeat(MAX_VALUE_CHARS + 25)));
+        let (first, hint) = output.split_once('\n').unwrap();
+        assert_eq!(
+            first,
+            format!(
+                "Value: {}\u{2026}(25 more chars)",
+                "x".repeat(MAX_VALUE_CHARS)
+            )
+        );
+        assert!(hint.contains("eval"));
+
+        assert_eq!(value_output(json!("short")), "Value: short");
+    }
+
+    #[test]
+    fn test_scoped_html_is_printed_under_its_selector() {
+        assert_eq!(
+            value_output(json!({
+                "html": "<main id=\"main\"><p>Hi</p></main>",
+                "selector": "#main"
+            })),
+            "HTML of \"#main\":\n<main id=\"main\"><p>Hi</p></main>"
+        );
+        assert_eq!(
+            value_output(json!({ "html": "", "selector": "#empty" })),
+            "HTML of \"#empty\": (empty)"
+        );
+        let long = format!("<p>{}</p>", "x".repeat(MAX_VALUE_CHARS));
+        assert_eq!(
+            value_output(json!({ "html": long })),
+            format!("HTML:\n{}", long)
+        );
+    }
+
+    #[test]
+    fn test_value_number_is_inline() {
+        assert_eq!(value_output(json!(42)), "Value: 42");
+        assert_eq!(value_output(json!(true)), "Value: true");
+    }
+
+    #[test]
+    fn test_mask_log_masks_every_quoted_literal() {
+        assert_eq!(
+            mask_sensitive_log(r#"login "ada@example.com" "hunter2" --password-field "pw""#),
+            r#"login "********" "********" --password-field "********""#
+        );
+        // An escaped quote does not end the literal early
+        assert_eq!(
+            mask_sensitive_log(r#"type password "a\"b" --delay 5"#),
+            r#"type password "********" --delay 5"#
+        );
+    }
+
+    #[test]
+    fn test_mask_log_extra_sensitive_fields() {
+        let line = r#"type "Member number" "12345678""#;
+        assert_eq!(mask_sensitive_log(line), line);
+        assert_eq!(
+            mask_sensitive_log_with(line, &["member number".to_string()]),
+            r#"type "********" "********""#
+        );
+    }
+
+    #[test]
+    fn test_mask_log_leaves_other_lines_alone() {
+        for line in [
+            r#"type "Search" "running shoes""#,
+            r#"click "Sign in""#,
+            "observe --full",
+        ] {
+            assert_eq!(mask_sensitive_log(line), line);
+        }
+    }
+
+    #[test]
+    fn test_table_with_headers_becomes_keyed_rows() {
+        let table = table_records(&json!({
+            "headers": ["Item", "Price", "Price"],
+            "rows": [["Tea", "$3", "€2.80"], ["Cake, lemon", "$4"]],
+            "id": 7
+        }));
+
+        assert_eq!(table["columns"], json!(["Item", "Price", "Price_2"]));
+        assert_eq!(
+            table["rows"][0],
+            json!({ "Item": "Tea", "Price": "$3", "Price_2": "€2.80" })
+        );
+        assert_eq!(table["rows"][1]["Price_2"], "");
+        assert_eq!(
+            format_extract_csv(&json!([table])),
+            "Item,Price,Price_2\nTea,$3,€2.80\n\"Cake, lemon\",$4,"
+        );
+    }
+
+    #[test]
+    fn test_table_without_headers_uses_column_numbers() {
+        let table = table_records(&json!({
+            "headers": null,
+            "rows": [["a", "b"], ["c", "d"]],
+            "id": 8
+        }));
+
+        assert_eq!(table["columns"], json!(["col_0", "col_1"]));
+        assert_eq!(table["rows"][1], json!({ "col_0": "c", "col_1": "d" }));
+        assert_eq!(format_extract_csv(&json!([table])), "col_0,col_1\na,b\nc,d");
+    }
+}
@@ -1,6 +1,6 @@
 use crate::intent::definition::{
-    ActionStep, ActionType, IntentDefinition, IntentOptions, IntentTier, IntentTriggers, MatchType,
-    Step, TargetKind, TargetSpec, TryDef, TryStepWrapper,
+    ActionStep, ActionType, Condition, IntentDefinition, IntentOptions, IntentTier, IntentTriggers,
+    MatchType, RetryConfig, Step, TargetKind, TargetSpec, TryDef, TryStepWrapper,
 };
 use std::collections::HashMap;
 
@@ -17,17 +17,26 @@ pub enum ParseError {
 /// Syntax Example:
 /// define intent_name:
 ///   description: "Description"
+///   patterns: login_form, cookie_banner
+///   pre_conditions:
+///     - url contains "/checkout"
 ///   steps:
 ///     - click "Button"
 ///     - type "Input" "Value"
+///       on_success:
+///         - click "Next"
+///
+/// An `on_success:` or `on_error:` line indented under a step starts that
+/// step's handler; the handler's own `- ` lines follow, indented deeper.
 pub fn parse_define(input: &str) -> Result<IntentDefinition, ParseError> {
     // Basic line-based parsing for MVP
-    let mut lines = input.lines().map(|s| s.trim()).filter(|s| !s.is_empty());
+    let mut lines = input.lines().filter(|s| !s.trim().is_empty());
 
     // 1. Header: "define <name>:"
     let header = lines
         .next()
-        .ok_or(ParseError::Syntax("Empty input".into()))?;
+        .ok_or(ParseError::Syntax("Empty input".into()))?
+        .trim();
     if !header.starts_with("define ") || !header.ends_with(':') {
         return Err(ParseError::Syntax(
             "Expected 'define <name>:' header".into(),
@@ -53,6 +62,7 @@ pub fn parse_define(input: &str) -> Result<IntentDefinition, ParseError> {
                 required: true,
                 default: None,
                 description: String::new(),
+                allowed: vec![],
             })
             .collect();
 
@@ -63,20 +73,62 @@ pub fn parse_define(input: &str) -> Result<IntentDefinition, ParseError> {
 
     let mut steps = Vec::new();
     let mut description = String::new();
+    let mut patterns = Vec::new();
+    let mut pre_conditions = Vec::new();
 
     // State machine for blocks
-    let mut current_block = ""; // "description", "steps"
+    let mut current_block = ""; // "description", "pre_conditions", "steps"
+    // Handler of the last step being filled: its name and the indentation of
+    // the line that opened it
+    let mut handler: Option<(&str, usize)> = None;
 
-    for line in lines {
-        if line.starts_with("description:") {
+    for raw in lines {
+        let line = raw.trim();
+        let indent = raw.len() - raw.trim_start().len();
+        if handler.is_some_and(|(_, opened_at)| indent <= opened_at) {
+            handler = None;
+        }
+
+        if current_block == "steps" && matches!(line, "on_success:" | "on_error:") {
+            if !matches!(steps.last(), Some(Step::Action(_))) {
+                return Err(ParseError::Syntax(format!(
+                    "'{}' must follow an action step",
+                    line
+                )));
+            }
+            handler = Some((line.trim_end_matches(':'), indent));
+        } else if let Some((name, _)) = handler
+            && let Some(cmd) = line.strip_prefix("- ")
+        {
+            let step = parse_step_shorthand(cmd.trim())?;
+            if let Some(Step::Action(action)) = steps.last_mut() {
+                let list = if name == "on_success" {
+                    &mut action.on_success
+                } else {
+                    &mut action.on_error
+                };
+                list.get_or_insert_with(Vec::new).push(step);
+            }
+        } else if line.starts_with("description:") {
             description = line
                 .trim_start_matches("description:")
                 .trim()
                 .trim_matches('"')
                 .to_string();
             current_block = "description";
+        } else if let Some(list) = line.strip_prefix("patterns:") {
+            patterns = list
+                .split(',')
+                .map(|p| p.trim().to_string())
+                .filter(|p| !p.is_empty())
+                .collect();
+            current_block = "patterns";
+        } else if line.starts_with("pre_conditions:") {
+            current_block = "pre_conditions";
         } else if line.starts_with("steps:") {
             current_block = "steps";
+        } else if line.starts_with("- ") && current_block == "pre_conditions" {
+            pre_conditions.push(parse_condition(line.trim_start_matches("- ").trim())?);
         } else if line.starts_with("- ") && current_block == "steps" {
             let cmd = line.trim_start_matches("- ").trim();
             steps.push(parse_step_shorthand(cmd)?);
@@ -89,10 +141,12 @@ pub fn parse_define(input: &str) -> Result<IntentDefinition, ParseError> {
         version: "1.0".to_string(), // Default version
         tier: IntentTier::Discovered,
         triggers: IntentTriggers {
+            patterns,
             keywords: vec![], // Parse keywords later?
             ..Default::default()
         },
         parameters: params_vec,
+        pre_conditions,
         steps,
         flow: None,
         success: None,
@@ -125,7 +179,20 @@ fn parse_step_shorthand(cmd: &str) -> Result<Step, ParseError> {
     parse_single_step(cmd)
 }
 
+/// Parses one step, honouring trailing `--timeout <duration>`,
+/// `--retry <attempts>` and `--retry-delay <duration>` overrides
+/// (e.g. `click "Pay" --timeout 5s --retry 2`).
 fn parse_single_step(cmd: &str) -> Result<Step, ParseError> {
+    let (cmd, overrides) = split_overrides(cmd)?;
+    let mut step = parse_action_step(cmd)?;
+    if let Step::Action(action) = &mut step {
+        action.timeout_ms = overrides.timeout_ms;
+        action.retry = overrides.retry;
+    }
+    Ok(step)
+}
+
+fn parse_action_step(cmd: &str) -> Result<Step, ParseError> {
     let parts: Vec<&str> = cmd.split_whitespace().collect();
     if parts.is_empty() {
         return Err(ParseError::Syntax("Empty step".into()));
@@ -137,6 +204,8 @@ fn parse_single_step(cmd: &str) -> Result<Step, ParseError> {
             let target = parse_quoted_arg(cmd)
                 .ok_or(ParseError::Syntax("Missing target for click".into()))?;
             Ok(Step::Action(ActionStep {
+                timeout_ms: None,
+                retry: None,
                 action: ActionType::Click,
                 target: Some(TargetSpec {
                     kind: TargetKind::Text {
@@ -147,6 +216,7 @@ fn parse_single_step(cmd: &str) -> Result<Step, ParseError> {
                 }),
                 options: HashMap::new(),
                 on_error: None,
+                on_success: None,
             }))
         }
         "type" => {
@@ -171,6 +241,8 @@ fn parse_single_step(cmd: &str) -> Result<Step, ParseError> {
                 options.insert("text".to_string(), serde_json::Value::String(value));
 
                 Ok(Step::Action(ActionStep {
+                    timeout_ms: None,
+                    retry: None,
                     action: ActionType::Type,
                     target: Some(TargetSpec {
                         kind: TargetKind::Text {
@@ -181,6 +253,7 @@ fn parse_single_step(cmd: &str) -> Result<Step, ParseError> {
                     }),
                     options,
                     on_error: None,
+                    on_success: None,
                 }))
             } else {
                 // Potential role syntax: type email "value"
@@ -200,6 +273,8 @@ fn parse_single_step(cmd: &str) -> Result<Step, ParseError> {
                 options.insert("text".to_string(), serde_json::Value::String(value));
 
                 Ok(Step::Action(ActionStep {
+                    timeout_ms: None,
+                    retry: None,
                     action: ActionType::Type,
                     target: Some(TargetSpec {
                         kind: TargetKind::Role {
@@ -209,6 +284,7 @@ fn parse_single_step(cmd: &str) -> Result<Step, ParseError> {
                     }),
                     options,
                     on_error: None,
+                    on_success: None,
                 }))
             }
         }
@@ -228,6 +304,8 @@ fn parse_single_step(cmd: &str) -> Result<Step, ParseError> {
             );
 
             Ok(Step::Action(ActionStep {
+                timeout_ms: None,
+                retry: None,
                 action: ActionType::Wait,
                 target: Some(TargetSpec {
                     kind: TargetKind::Text {
@@ -238,12 +316,117 @@ fn parse_single_step(cmd: &str) -> Result<Step, ParseError> {
                 }),
                 options,
                 on_error: None,
+                on_success: None,
             }))
         }
         _ => Err(ParseError::Syntax(format!("Unknown action: {}", parts[0]))),
     }
 }
 
+/// Parses one condition line: `url contains "..."`, `url matches "..."`,
+/// `visible "..."`, `hidden "..."`, `text contains "..."` or
+/// `pattern <name>`.
+fn parse_condition(line: &str) -> Result<Condition, ParseError> {
+    let quoted = || {
+        parse_quoted_arg(line).ok_or_else(|| {
+            ParseError::Syntax(format!("Missing quoted value in condition: {}", line))
+        })
+    };
+    let text_target = |text: String| TargetSpec {
+        kind: TargetKind::Text {
+            text,
+            match_type: MatchType::Contains,
+        },
+        fallback: None,
+    };
+
+    let words: Vec<&str> = line.split_whitespace().take(2).collect();
+    match words.as_slice() {
+        ["url", "contains", ..] => Ok(Condition::UrlContains(vec![quoted()?])),
+        ["url", "matches", ..] => Ok(Condition::UrlMatches(quoted()?)),
+        ["text", "contains", ..] => Ok(Condition::TextContains {
+            text: quoted()?,
+            within: None,
+        }),
+        ["visible", ..] => Ok(Condition::Visible(text_target(quoted()?))),
+        ["hidden", ..] => Ok(Condition::Hidden(text_target(quoted()?))),
+        ["pattern", name] => Ok(Condition::PatternExists(name.to_string())),
+        _ => Err(ParseError::Syntax(format!("Unknown condition: {}", line))),
+    }
+}
+
+/// Per-step overrides that can trail a step command.
+#[derive(Debug, Default)]
+struct StepOverrides {
+    timeout_ms: Option<u64>,
+    retry: Option<RetryConfig>,
+}
+
+/// Splits trailing `--timeout`, `--retry` and `--retry-delay` flags off a
+/// step command, in any order. Flags inside a quoted argument are left alone.
+fn split_overrides(cmd: &str) -> Result<(&str, StepOverrides), ParseError> {
+    let mut rest = cmd.trim_end();
+    let mut overrides = StepOverrides::default();
+    let mut retry_delay_ms = None;
+
+    while let Some((head, value)) = rest.rsplit_once(' ') {
+        let Some((before, flag)) = head.trim_end().rsplit_once(' ') else {
+            break;
+        };
+        if before.matches('"').count() % 2 != 0 {
+            break;
+        }
+        match flag {
+            "--timeout" => {
+                let ms = parse_duration_ms(value).ok_or_else(|| {
+                    ParseError::Syntax(format!("Invalid step timeout: {}", value))
+                })?;
+                overrides.timeout_ms = Some(ms);
+            }
+            "--retry" => {
+                let attempts = value
+                    .parse::<usize>()
+                    .ok()
+                    .filter(|n| *n > 0)
+                    .ok_or_else(|| {
+                        ParseError::Syntax(format!("Invalid retry attempts: {}", value))
+                    })?;
+                overrides.retry = Some(RetryConfig::attempts(attempts));
+            }
+            "--retry-delay" => {
+                let ms = parse_duration_ms(value)
+                    .ok_or_else(|| ParseError::Syntax(format!("Invalid retry delay: {}", value)))?;
+                retry_delay_ms = Some(ms);
+            }
+            _ => break,
+        }
+        rest = before.trim_end();
+    }
+
+    if let Some(ms) = retry_delay_ms {
+        let retry = overrides
+            .retry
+            .as_mut()
+            .ok_or_else(|| ParseError::Syntax("--retry-delay requires --retry".into()))?;
+        retry.delay_ms = ms;
+    }
+    Ok((rest, overrides))
+}
+
+/// Parses `500ms`, `5s`, `2m` or a bare millisecond count.
+fn parse_duration_ms(value: &str) -> Option<u64> {
+    if let Some(ms) = value.strip_suffix("ms") {
+        return ms.parse().ok();
+    }
+    if let Some(s) = value.strip_suffix('s') {
+        return s.parse::<u64>().ok()?.checked_mul(1000);
+    }
+    if let Some(m) = value.strip_suffix('m') {
+        return m.parse::<u64>().ok()?.checked_mul(60_000);
+    }
+    value.parse().ok()
+}
+
 /// Extracts content inside first pair of quotes.
 fn parse_quoted_arg(s: &str) -> Option<String> {
     let start = s.find('"')?;
@@ -1,3 +1,4 @@
+use crate::protocol::DetectedPatterns;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::collections::HashMap;
@@ -135,6 +136,10 @@ pub struct IntentDefinition {
     pub triggers: IntentTriggers,
     #[serde(default)]
     pub parameters: Vec<ParameterDef>,
+    /// Checked against the first scan; the intent fails before any step runs
+    /// unless every one holds.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pre_conditions: Vec<Condition>,
     /// Steps for single-page intents. Either `steps` or `flow` should be provided.
     #[serde(default)]
     pub steps: Vec<Step>,
@@ -159,6 +164,37 @@ pub struct IntentTriggers {
     pub urls: Vec<String>,
 }
 
+impl IntentTriggers {
+    /// Warnings for trigger patterns the scanner never reports, which would
+    /// keep the intent from ever becoming available.
+    pub fn pattern_warnings(&self) -> Vec<String> {
+        self.patterns
+            .iter()
+            .filter(|p| !DetectedPatterns::NAMES.contains(&p.as_str()))
+            .map(|p| {
+                let closest = DetectedPatterns::NAMES
+                    .iter()
+                    .map(|known| (known, strsim::levenshtein(p, known)))
+                    .min_by_key(|(_, distance)| *distance)
+                    .filter(|(_, distance)| *distance <= 3);
+                match closest {
+                    Some((known, _)) => {
+                        format!(
+                            "Unknown trigger pattern '{}' (did you mean '{}'?)",
+                            p, known
+                        )
+                    }
+                    None => format!(
+                        "Unknown trigger pattern '{}' (known patterns: {})",
+                        p,
+                        DetectedPatterns::NAMES.join(", ")
+                    ),
+                }
+            })
+            .collect()
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ParameterDef {
     pub name: String,
@@ -170,6 +206,55 @@ pub struct ParameterDef {
     pub default: Option<Value>,
     #[serde(default)]
     pub description: String,
+    /// Permitted values. Empty means any value of `param_type` is accepted.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allowed: Vec<Value>,
+}
+
+impl ParameterDef {
+    /// Convert a raw command-line value to this parameter's type and check it
+    /// against the allowed values.
+    pub fn parse_value(&self, raw: &str) -> Result<Value, String> {
+        let value = match self.param_type {
+            ParamType::String => Value::String(raw.to_string()),
+            ParamType::Number => raw
+                .parse::<i64>()
+                .map(Value::from)
+                .or_else(|_| raw.parse::<f64>().map(Value::from))
+                .map_err(|_| format!("expected a number, got '{}'", raw))?,
+            ParamType::Boolean => match raw.to_ascii_lowercase().as_str() {
+                "true" | "yes" | "1" => Value::Bool(true),
+                "false" | "no" | "0" => Value::Bool(false),
+                _ => return Err(format!("expected true or false, got '{}'", raw)),
+            },
+            ParamType::Object | ParamType::Array => {
+                let parsed: Value = serde_json::from_str(raw)
+                    .map_err(|e| format!("expected JSON, got '{}' ({})", raw, e))?;
+                let matches_type = match self.param_type {
+                    ParamType::Object => parsed.is_object(),
+                    _ => parsed.is_array(),
+                };
+                if !matches_type {
+                    return Err(format!(
+                        "expected a JSON {:?}, got '{}'",
+                        self.param_type, raw
+                    ));
+                }
+                parsed
+            }
+        };
+        self.check_allowed(&value)?;
+        Ok(value)
+    }
+
+    /// Check a value against `allowed`. Any value passes when the list is empty.
+    pub fn check_allowed(&self, value: &Value) -> Result<(), String> {
+        if self.allowed.is_empty() || self.allowed.contains(value) {
+            return Ok(());
+        }
+        let choices: Vec<String> = self.allowed.iter().map(|v| v.to_string()).collect();
+        Err(format!("must be one of {}", choices.join(", ")))
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -189,6 +274,7 @@ pub enum Step {
     Branch(BranchStepWrapper),
     Loop(LoopStepWrapper),
     Try(TryStepWrapper),
+    Parallel(ParallelStepWrapper),
     Checkpoint(CheckpointStepWrapper),
 }
 
@@ -199,6 +285,17 @@ pub struct ActionStep {
     pub target: Option<TargetSpec>,
     #[serde(default)]
     pub on_error: Option<Vec<Step>>,
+    /// Steps run once this step has succeeded, after any retries and before
+    /// the next step. The intent's success conditions are verified only
+    /// after all steps, so they see what these steps did.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub on_success: Option<Vec<Step>>,
+    /// Per-step limit for backend calls; falls back to `IntentOptions.timeout`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout_ms: Option<u64>,
+    /// Retry policy for this step; overrides `IntentOptions.retry`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retry: Option<RetryConfig>,
     #[serde(flatten)]
     pub options: HashMap<String, Value>,
 }
@@ -244,6 +341,20 @@ pub struct TryDef {
     pub catch: Vec<Step>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ParallelStepWrapper {
+    pub parallel: ParallelDef,
+}
+
+/// Branches that are dispatched concurrently. Every branch must succeed for
+/// the step to succeed. Variables written by the branches are merged once all
+/// of them finish; when two branches write the same variable, the branch
+/// declared last wins.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ParallelDef {
+    pub steps: Vec<Step>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CheckpointStepWrapper {
     pub checkpoint: String,
@@ -268,6 +379,45 @@ pub enum ActionType {
     GoBack,
     GoForward,
     Refresh,
+    /// Any other name, run by the handler registered under it on the
+    /// executor (e.g. `upload_file`).
+    #[serde(untagged)]
+    Custom(String),
+}
+
+impl ActionType {
+    /// The built-in action names, as written in intent files.
+    pub const BUILT_IN: &'static [&'static str] = &[
+        "click",
+        "type",
+        "select",
+        "check",
+        "uncheck",
+        "clear",
+        "scroll",
+        "wait",
+        "fill_form",
+        "intent",
+        "execute",
+        "navigate",
+        "go_back",
+        "go_forward",
+        "refresh",
+    ];
+
+    /// For a custom action, the built-in it is most likely a misspelling of:
+    /// one typo away (`clik`) or differing only in case and separators
+    /// (`fillForm`).
+    pub fn near_miss(&self) -> Option<&'static str> {
+        let ActionType::Custom(name) = self else {
+            return None;
+        };
+        let name = name.to_lowercase().replace('-', "_");
+        Self::BUILT_IN.iter().copied().find(|builtin| {
+            name.replace('_', "") == builtin.replace('_', "")
+                || strsim::damerau_levenshtein(&name, builtin) <= 1
+        })
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -278,6 +428,40 @@ pub struct TargetSpec {
     pub fallback: Option<Box<TargetSpec>>,
 }
 
+impl TargetSpec {
+    /// The target as it would be written in OIL, e.g. `"Pay" near "Total"`.
+    pub fn describe(&self) -> String {
+        match &self.kind {
+            TargetKind::Pattern { pattern } => pattern.clone(),
+            TargetKind::Role { role } => role.clone(),
+            TargetKind::Text { text, .. } => format!("{:?}", text),
+            TargetKind::Selector { selector } => format!("css({:?})", selector),
+            TargetKind::Id { id } => id.to_string(),
+            TargetKind::Near { near: t, anchor: a } => {
+                format!("{} near {}", t.describe(), a.describe())
+            }
+            TargetKind::Inside {
+                inside: t,
+                container: a,
+            } => format!("{} inside {}", t.describe(), a.describe()),
+            TargetKind::After {
+                after: t,
+                anchor: a,
+            } => {
+                format!("{} after {}", t.describe(), a.describe())
+            }
+            TargetKind::Before {
+                before: t,
+                anchor: a,
+            } => format!("{} before {}", t.describe(), a.describe()),
+            TargetKind::Contains {
+                contains: t,
+                content: a,
+            } => format!("{} contains {}", t.describe(), a.describe()),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum TargetKind {
@@ -344,7 +528,13 @@ pub enum Condition {
     Visible(TargetSpec),
     Hidden(TargetSpec),
     UrlContains(Vec<String>),
+    /// The page URL matches the regex.
     UrlMatches(String),
+    /// The text of the element `target` resolves to matches the regex `pattern`.
+    Matches {
+        target: TargetSpec,
+        pattern: String,
+    },
     TextContains {
         text: String,
         within: Option<TargetSpec>,
@@ -359,6 +549,52 @@ pub enum Condition {
     Any(Vec<Condition>),
 }
 
+impl Condition {
+    /// A short description for error messages, e.g. `url contains "/checkout"`.
+    pub fn describe(&self) -> String {
+        let join = |conditions: &[Condition], sep: &str| {
+            conditions
+                .iter()
+                .map(Condition::describe)
+                .collect::<Vec<_>>()
+                .join(sep)
+        };
+        match self {
+            Condition::PatternExists(p) => format!("pattern {}", p),
+            Condition::PatternGone(p) => format!("no pattern {}", p),
+            Condition::Visible(t) => format!("visible {}", t.describe()),
+            Condition::Hidden(t) => format!("hidden {}", t.describe()),
+            Condition::UrlContains(parts) => {
+                let parts: Vec<_> = parts.iter().map(|p| format!("{:?}", p)).collect();
+                format!("url contains {}", parts.join(" or "))
+            }
+            Condition::UrlMatches(re) => format!("url matches {:?}", re),
+            Condition::Matches { target, pattern } => {
+                format!("text of {} matches {:?}", target.describe(), pattern)
+            }
+            Condition::TextContains { text, within } => match within {
+                Some(t) => format!("text {:?} within {}", text, t.describe()),
+                None => format!("text contains {:?}", text),
+            },
+            Condition::Count { selector, min, max } => {
+                let mut bounds = Vec::new();
+                if let Some(min) = min {
+                    bounds.push(format!("at least {}", min));
+                }
+                if let Some(max) = max {
+                    bounds.push(format!("at most {}", max));
+                }
+                format!("count {:?} {}", selector, bounds.join(" and "))
+                    .trim_end()
+                    .to_string()
+            }
+            Condition::Expression(e) => format!("expression {:?}", e),
+            Condition::All(c) => format!("({})", join(c, " and ")),
+            Condition::Any(c) => format!("({})", join(c, " or ")),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SuccessCondition {
     pub conditions: Vec<Condition>,
@@ -375,26 +611,55 @@ pub struct FailureCondition {
 pub struct IntentOptions {
     #[serde(default = "default_timeout")]
     pub timeout: u64, // milliseconds
-    #[serde(default)]
-    pub retry: RetryConfig,
+    /// Retry policy for steps without their own; falls back to the engine
+    /// default when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retry: Option<RetryConfig>,
     #[serde(default)]
     pub checkpoint: bool,
+    /// Time budget for the whole run, retries included, in milliseconds.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub total_timeout_ms: Option<u64>,
+    /// How often waits re-check the page, in milliseconds. Values below
+    /// [`MIN_POLL_INTERVAL_MS`] are raised to it.
+    #[serde(default = "default_poll_interval_ms")]
+    pub poll_interval_ms: u64,
+    /// Capture a screenshot when the run fails or only partly completes.
+    #[serde(default)]
+    pub screenshot_on_error: bool,
+}
+
+impl IntentOptions {
+    /// `poll_interval_ms`, kept from going below [`MIN_POLL_INTERVAL_MS`].
+    pub fn poll_interval(&self) -> u64 {
+        self.poll_interval_ms.max(MIN_POLL_INTERVAL_MS)
+    }
 }
 
 impl Default for IntentOptions {
     fn default() -> Self {
         Self {
             timeout: default_timeout(),
-            retry: RetryConfig::default(),
+            retry: None,
             checkpoint: false,
+            total_timeout_ms: None,
+            poll_interval_ms: default_poll_interval_ms(),
+            screenshot_on_error: false,
         }
     }
 }
 
+/// The shortest poll interval allowed, so a wait cannot flood the backend.
+pub const MIN_POLL_INTERVAL_MS: u64 = 50;
+
 fn default_timeout() -> u64 {
     30000
 }
 
+fn default_poll_interval_ms() -> u64 {
+    500
+}
+
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct RetryConfig {
     #[serde(default = "default_max_attempts")]
@@ -403,6 +668,21 @@ pub struct RetryConfig {
     pub delay_ms: u64,
     #[serde(default = "default_backoff_multiplier")]
     pub backoff_multiplier: f64,
+    /// Spread each delay by up to ±20% so parallel runs do not retry in step.
+    #[serde(default)]
+    pub jitter: bool,
+}
+
+impl RetryConfig {
+    /// `max_attempts` tries with the default delay and backoff.
+    pub fn attempts(max_attempts: usize) -> Self {
+        Self {
+            max_attempts,
+            delay_ms: default_delay_ms(),
+            backoff_multiplier: default_backoff_multiplier(),
+            jitter: false,
+        }
+    }
 }
 
 fn default_max_attempts() -> usize {
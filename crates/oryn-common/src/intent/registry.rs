@@ -1,6 +1,35 @@
-use crate::intent::definition::{IntentDefinition, IntentTier};
+use crate::intent::definition::{IntentDefinition, IntentTier, IntentTriggers, ParameterDef};
+use serde::Serialize;
 use std::collections::HashMap;
 
+/// Public description of an intent, as exposed to tooling by `intents --json`.
+///
+/// This is the subset of an [`IntentDefinition`] a caller needs to invoke the
+/// intent (e.g. to build a form for its parameters); steps are omitted.
+#[derive(Debug, Clone, Serialize)]
+pub struct IntentCatalogEntry<'a> {
+    pub name: &'a str,
+    pub version: &'a str,
+    pub tier: &'a IntentTier,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<&'a str>,
+    pub parameters: &'a [ParameterDef],
+    pub triggers: &'a IntentTriggers,
+}
+
+impl<'a> From<&'a IntentDefinition> for IntentCatalogEntry<'a> {
+    fn from(def: &'a IntentDefinition) -> Self {
+        Self {
+            name: &def.name,
+            version: &def.version,
+            tier: &def.tier,
+            description: def.description.as_deref(),
+            parameters: &def.parameters,
+            triggers: &def.triggers,
+        }
+    }
+}
+
 /// Registry for managing all available intents in the system.
 ///
 /// The registry handles intent storage, lookup priorities, and
@@ -46,6 +75,13 @@ impl IntentRegistry {
         true
     }
 
+    /// Like [`register`](Self::register), but also returns warnings about the
+    /// definition's triggers. Use this for user-supplied intents.
+    pub fn register_checked(&mut self, definition: IntentDefinition) -> (bool, Vec<String>) {
+        let warnings = definition.triggers.pattern_warnings();
+        (self.register(definition), warnings)
+    }
+
     /// Unregister an intent by name.
     /// Returns true if the intent was removed.
     pub fn unregister(&mut self, name: &str) -> bool {
@@ -74,6 +110,17 @@ impl IntentRegistry {
         self.intents.values().collect()
     }
 
+    /// Describe every registered intent, sorted by name for stable output.
+    pub fn catalog(&self) -> Vec<IntentCatalogEntry<'_>> {
+        let mut entries: Vec<_> = self
+            .intents
+            .values()
+            .map(IntentCatalogEntry::from)
+            .collect();
+        entries.sort_by(|a, b| a.name.cmp(b.name));
+        entries
+    }
+
     /// Find all intents that are triggered by the given pattern.
     pub fn get_by_pattern(&self, pattern: &str) -> Vec<&IntentDefinition> {
         self.patterns_to_intents
@@ -118,6 +165,7 @@ mod tests {
             tier,
             triggers: Default::default(),
             parameters: vec![],
+            pre_conditions: vec![],
             steps: vec![],
             flow: None,
             success: None,
@@ -145,4 +193,21 @@ mod tests {
         assert!(registry.register(loaded));
         assert_eq!(registry.get("login").unwrap().tier, IntentTier::Loaded);
     }
+
+    #[test]
+    fn test_register_checked_warns_on_unknown_pattern() {
+        let mut registry = IntentRegistry::new();
+        let intent = crate::intent::define_parser::parse_define(
+            "define login:\n  patterns: login_frm, cookie_banner\n  steps:\n    - click \"Sign in\"",
+        )
+        .unwrap();
+
+        let (registered, warnings) = registry.register_checked(intent);
+
+        assert!(registered);
+        assert_eq!(
+            warnings,
+            vec!["Unknown trigger pattern 'login_frm' (did you mean 'login_form'?)".to_string()]
+        );
+    }
 }
@@ -1,6 +1,6 @@
-use crate::command::Target;
 use crate::intent::definition::{Condition, TargetKind};
 use crate::protocol::ScanResult;
+use crate::resolver::Target;
 use crate::resolver::{ResolutionStrategy, ResolverContext, resolve_target};
 use async_recursion::async_recursion;
 use regex::Regex;
@@ -166,14 +166,21 @@ impl Verifier {
                 let url = &context.scan_result.page.url;
                 Ok(substrings.iter().any(|s| url.contains(s)))
             }
-            Condition::UrlMatches(regex) => {
-                match Regex::new(regex) {
-                    Ok(re) => Ok(re.is_match(&context.scan_result.page.url)),
-                    Err(_) => {
-                        // Fallback to contains for invalid regex
-                        Ok(context.scan_result.page.url.contains(regex))
-                    }
-                }
+            Condition::UrlMatches(pattern) => {
+                Ok(compile_pattern(pattern)?.is_match(&context.scan_result.page.url))
+            }
+            Condition::Matches { target, pattern } => {
+                let re = compile_pattern(pattern)?;
+                let Some(Target::Id(id)) = context.resolve_target_exists(target)? else {
+                    return Ok(false);
+                };
+                let text = context
+                    .scan_result
+                    .elements
+                    .iter()
+                    .find(|e| e.id as usize == id)
+                    .and_then(|el| el.text.as_deref().or(el.label.as_deref()));
+                Ok(text.is_some_and(|t| re.is_match(t)))
             }
             Condition::TextContains { text, within } => {
                 if let Some(target_spec) = within {
@@ -240,6 +247,13 @@ impl Verifier {
     }
 }
 
+/// Compile a condition's regex, reporting a bad pattern instead of treating
+/// it as a failed check.
+fn compile_pattern(pattern: &str) -> Result<Regex, VerificationError> {
+    Regex::new(pattern)
+        .map_err(|e| VerificationError::Error(format!("Invalid regex '{}': {}", pattern, e)))
+}
+
 /// Determines if a JSON value is "truthy" for condition evaluation.
 fn is_truthy(value: &Value) -> bool {
     match value {
@@ -251,3 +265,102 @@ fn is_truthy(value: &Value) -> bool {
         Value::Null => false,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::{
+        Element, ElementState, PageInfo, Rect, ScanStats, ScrollInfo, ViewportInfo,
+    };
+
+    fn dashboard_scan() -> ScanResult {
+        ScanResult {
+            page: PageInfo {
+                url: "https://app.example.com/dashboard?tab=home".into(),
+                title: "Dashboard".into(),
+                viewport: ViewportInfo::default(),
+                scroll: ScrollInfo::default(),
+                ready_state: None,
+            },
+            elements: vec![Element {
+                id: 1,
+                element_type: "h1".into(),
+                role: Some("heading".into()),
+                text: Some("Dashboard - Acme".into()),
+                label: None,
+                value: None,
+                placeholder: None,
+                selector: "h1".into(),
+                xpath: None,
+                rect: Rect {
+                    x: 0.0,
+                    y: 0.0,
+                    width: 400.0,
+                    height: 40.0,
+                },
+                attributes: HashMap::new(),
+                state: ElementState::default(),
+                children: vec![],
+                count: None,
+                tab_index: None,
+                focusable: false,
+            }],
+            stats: ScanStats {
+                total: 1,
+                scanned: 1,
+                iframes: None,
+            },
+            patterns: None,
+            changes: None,
+            available_intents: None,
+            full_mode: false,
+            settings_applied: None,
+            timing: None,
+        }
+    }
+
+    fn condition(value: Value) -> Condition {
+        serde_json::from_value(value).unwrap()
+    }
+
+    async fn verify(cond: &Condition) -> Result<bool, VerificationError> {
+        let scan = dashboard_scan();
+        Verifier::new()
+            .verify(cond, &VerifierContext::new(&scan))
+            .await
+    }
+
+    #[tokio::test]
+    async fn test_success_condition_title_matches() {
+        let matches = condition(serde_json::json!({
+            "matches": { "target": { "role": "heading" }, "pattern": "^Dashboard" }
+        }));
+        assert!(verify(&matches).await.unwrap());
+
+        let mismatch = condition(serde_json::json!({
+            "matches": { "target": { "role": "heading" }, "pattern": "^Settings" }
+        }));
+        assert!(!verify(&mismatch).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_url_matches() {
+        let cond = condition(serde_json::json!({ "url_matches": "/dashboard(\\?|$)" }));
+        assert!(verify(&cond).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_invalid_regex_is_an_error() {
+        for cond in [
+            condition(serde_json::json!({ "url_matches": "(unclosed" })),
+            condition(serde_json::json!({
+                "matches": { "target": { "role": "heading" }, "pattern": "[a-" }
+            })),
+        ] {
+            assert!(matches!(
+                verify(&cond).await,
+                Err(VerificationError::Error(msg)) if msg.contains("Invalid regex")
+            ));
+        }
+    }
+}
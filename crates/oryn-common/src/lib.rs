@@ -1,3 +1,4 @@
+pub mod cookie_file;
 pub mod error;
 pub mod error_mapping;
 pub mod formatter;
@@ -8,4 +9,5 @@ pub mod intent {
     pub mod define_parser;
     pub mod definition;
     pub mod registry;
+    pub mod verifier;
 }
@@ -55,6 +55,7 @@ pub enum ScannerAction {
     GetText(GetTextRequest),
     #[serde(rename = "get_html")]
     GetHtml(GetHtmlRequest),
+    Highlight(HighlightRequest),
 }
 
 /// Actions executed by the browser automation driver (Puppeteer/Selenium equivalent).
@@ -71,6 +72,8 @@ pub enum BrowserAction {
     Frame(FrameRequest),
     Dialog(DialogRequest),
     Press(PressRequest),
+    Intercept(InterceptRequest),
+    Requests(RequestsRequest),
 }
 
 /// Actions managed by the session manager (Cookies, Storage, etc.).
@@ -96,6 +99,8 @@ pub enum MetaAction {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NavigateRequest {
     pub url: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub referer: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -144,7 +149,7 @@ pub enum MouseButton {
     Right,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct TypeRequest {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub id: Option<u32>,
@@ -157,6 +162,44 @@ pub struct TypeRequest {
     pub submit: bool,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub delay: Option<u64>,
+    /// The text is a secret: the scanner leaves it out of its response, and
+    /// `Debug` and [`redact_secret_text`] leave it out of logs.
+    #[serde(default)]
+    pub secret: bool,
+}
+
+impl std::fmt::Debug for TypeRequest {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TypeRequest")
+            .field("id", &self.id)
+            .field("selector", &self.selector)
+            .field("text", &if self.secret { SECRET_MASK } else { &self.text })
+            .field("clear", &self.clear)
+            .field("submit", &self.submit)
+            .field("delay", &self.delay)
+            .field("secret", &self.secret)
+            .finish()
+    }
+}
+
+/// What a secret's text is replaced with in logs.
+pub const SECRET_MASK: &str = "********";
+
+/// Mask the `text` of every request in a serialized command, batch or action
+/// marked `secret`, so the JSON can be logged.
+pub fn redact_secret_text(value: &mut serde_json::Value) {
+    match value {
+        serde_json::Value::Object(map) => {
+            if map.get("secret") == Some(&serde_json::Value::Bool(true))
+                && let Some(text) = map.get_mut("text")
+            {
+                *text = SECRET_MASK.into();
+            }
+            map.values_mut().for_each(redact_secret_text);
+        }
+        serde_json::Value::Array(items) => items.iter_mut().for_each(redact_secret_text),
+        _ => {}
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -164,6 +207,17 @@ pub struct ScrollRequest {
     pub id: Option<u32>, // None = window
     pub direction: ScrollDirection,
     pub amount: Option<String>, // "page", "half", "100px"
+    #[serde(default)]
+    pub behavior: ScrollBehavior,
+}
+
+/// How the scanner moves to the new scroll position.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ScrollBehavior {
+    #[default]
+    Instant,
+    Smooth,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -175,6 +229,8 @@ pub enum ScrollDirection {
     Right,
     Top,
     Bottom,
+    /// Bring the element given by `id` to the middle of the viewport.
+    IntoView,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -190,12 +246,38 @@ pub struct WaitRequest {
     pub expression: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub count: Option<u64>,
+    /// How a `count` wait compares the number of matches with `count`: one
+    /// of `>=` (when omitted), `>`, `<=`, `<` or `==`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub compare: Option<String>,
     #[serde(
         skip_serializing_if = "Option::is_none",
         rename = "timeout",
         alias = "timeout_ms"
     )]
     pub timeout: Option<u64>,
+    /// How often the scanner re-checks the condition, in milliseconds.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub poll_interval: Option<u64>,
+}
+
+impl WaitRequest {
+    /// A short description of what is being waited for, e.g. `visible #3`.
+    pub fn describe(&self) -> String {
+        let subject = if let Some(id) = self.id {
+            Some(format!("#{}", id))
+        } else if let Some(selector) = &self.selector {
+            Some(format!("css({})", selector))
+        } else if let Some(text) = &self.text {
+            Some(format!("\"{}\"", text))
+        } else {
+            self.expression.clone()
+        };
+        match subject {
+            Some(s) => format!("{} {}", self.condition, s),
+            None => self.condition.clone(),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -226,6 +308,26 @@ pub struct SubmitRequest {
     pub selector: Option<String>,
 }
 
+/// Draw an outline over an element, or remove every outline with `clear`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HighlightRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub selector: Option<String>,
+    /// CSS color of the outline; the scanner picks one when unset.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+    /// How long the outline stays; the scanner's default when unset.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duration_ms: Option<u64>,
+    /// Keep the outline until it is cleared.
+    #[serde(default)]
+    pub persist: bool,
+    #[serde(default)]
+    pub clear: bool,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HoverRequest {
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -253,7 +355,10 @@ pub struct ClearRequest {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ExecuteRequest {
     pub script: String,
-    pub args: Vec<serde_json::Value>,
+    /// Bound to `args` inside the script: an array of positional values or
+    /// an object of named ones.
+    #[serde(default)]
+    pub args: serde_json::Value,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -328,6 +433,10 @@ pub enum ScannerData {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ScanResult {
     pub page: PageInfo,
+    /// Scanned elements in document order, with shadow-root contents in place
+    /// of their hosts and each iframe followed by its contents. Element IDs
+    /// persist across scans and so say nothing about order. The resolver's
+    /// `First` strategy and its tie-breaks depend on this order.
     pub elements: Vec<Element>,
     pub stats: ScanStats,
     #[serde(default, skip_serializing_if = "Option::is_none")]
@@ -344,6 +453,124 @@ pub struct ScanResult {
     pub timing: Option<serde_json::Value>,
 }
 
+impl ScanResult {
+    /// Collapse repeated identical elements (same type, role, text, label,
+    /// placeholder, value, checked and disabled state, and rect size) into the
+    /// first occurrence, recording how many were merged in `Element::count`.
+    /// Virtualized lists otherwise report dozens of indistinguishable rows.
+    pub fn dedup_elements(&mut self) {
+        /// Everything `observe` shows of an element, so only elements that
+        /// print the same are merged.
+        #[derive(PartialEq, Eq, Hash)]
+        struct DedupKey {
+            element_type: String,
+            role: Option<String>,
+            text: Option<String>,
+            label: Option<String>,
+            placeholder: Option<String>,
+            value: Option<String>,
+            checked: bool,
+            disabled: bool,
+            width: u32,
+            height: u32,
+        }
+        // -> index into `kept`
+        let mut seen: HashMap<DedupKey, usize> = HashMap::new();
+        let mut kept: Vec<Element> = Vec::with_capacity(self.elements.len());
+
+        for el in self.elements.drain(..) {
+            let key = DedupKey {
+                element_type: el.element_type.clone(),
+                role: el.role.clone(),
+                text: el.text.clone(),
+                label: el.label.clone(),
+                placeholder: el.placeholder.clone(),
+                value: el.value.clone(),
+                checked: el.state.checked,
+                disabled: el.state.disabled,
+                width: el.rect.width.to_bits(),
+                height: el.rect.height.to_bits(),
+            };
+            match seen.get(&key) {
+                Some(&idx) => {
+                    let rep = &mut kept[idx];
+                    rep.count = Some(rep.count.unwrap_or(1) + 1);
+                }
+                None => {
+                    seen.insert(key, kept.len());
+                    kept.push(el);
+                }
+            }
+        }
+
+        self.elements = kept;
+    }
+
+    /// What changed since `previous`. Elements are matched by selector, since
+    /// ids are reassigned on every scan; one on both pages counts as changed
+    /// when its text or value differs.
+    pub fn diff_from(&self, previous: &ScanResult) -> PageChanges {
+        let mut before: HashMap<&str, &Element> = HashMap::new();
+        for el in &previous.elements {
+            before.entry(el.selector.as_str()).or_insert(el);
+        }
+        let mut after: HashMap<&str, &Element> = HashMap::new();
+        for el in &self.elements {
+            after.entry(el.selector.as_str()).or_insert(el);
+        }
+
+        let mut changes = PageChanges::default();
+        if self.page.url != previous.page.url {
+            changes.url = Some(self.page.url.clone());
+        }
+        if self.page.title != previous.page.title {
+            changes.title = Some(self.page.title.clone());
+        }
+        for el in &self.elements {
+            match before.get(el.selector.as_str()) {
+                None => changes.added.push(el.describe()),
+                Some(old) => {
+                    if old.text != el.text {
+                        changes.changed.push(format!(
+                            "{} text: {:?} → {:?}",
+                            el.describe(),
+                            old.text.as_deref().unwrap_or(""),
+                            el.text.as_deref().unwrap_or("")
+                        ));
+                    }
+                    if old.value != el.value {
+                        changes.changed.push(format!(
+                            "{} value: {:?} → {:?}",
+                            el.describe(),
+                            old.value.as_deref().unwrap_or(""),
+                            el.value.as_deref().unwrap_or("")
+                        ));
+                    }
+                }
+            }
+        }
+        for el in &previous.elements {
+            if !after.contains_key(el.selector.as_str()) {
+                changes.removed.push(el.describe());
+            }
+        }
+        changes
+    }
+
+    /// Keep only the elements Tab reaches, in the order it reaches them:
+    /// positive `tab_index` values first in ascending order, then the
+    /// remaining focusable elements in document order.
+    pub fn sort_by_focus_order(&mut self) {
+        self.elements
+            .retain(|el| el.focusable && el.tab_index.is_some_and(|i| i >= 0));
+        // Stable, so equal keys keep document order
+        self.elements.sort_by_key(|el| match el.tab_index {
+            Some(i) if i > 0 => (0, i),
+            _ => (1, 0),
+        });
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct IntentAvailability {
     pub name: String,
@@ -378,6 +605,29 @@ pub struct DetectedPatterns {
     pub cookie_banner: Option<CookieBannerPattern>,
 }
 
+impl DetectedPatterns {
+    /// Pattern names usable in intent triggers, one per detector.
+    pub const NAMES: &'static [&'static str] = &[
+        "login_form",
+        "search_box",
+        "pagination",
+        "modal",
+        "cookie_banner",
+    ];
+
+    /// Whether the named pattern was detected. Unknown names never match.
+    pub fn has(&self, name: &str) -> bool {
+        match name {
+            "login_form" => self.login.is_some(),
+            "search_box" => self.search.is_some(),
+            "pagination" => self.pagination.is_some(),
+            "modal" => self.modal.is_some(),
+            "cookie_banner" => self.cookie_banner.is_some(),
+            _ => false,
+        }
+    }
+}
+
 /// Login form pattern.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LoginPattern {
@@ -455,6 +705,19 @@ pub struct PageChanges {
     pub removed: Vec<String>, // Selectors/Descriptions
     #[serde(default)]
     pub added: Vec<String>,
+    /// Elements on both pages whose text or value differs.
+    #[serde(default)]
+    pub changed: Vec<String>,
+}
+
+impl PageChanges {
+    pub fn is_empty(&self) -> bool {
+        self.url.is_none()
+            && self.title.is_none()
+            && self.added.is_empty()
+            && self.removed.is_empty()
+            && self.changed.is_empty()
+    }
 }
 
 /// Type of change detected.
@@ -529,6 +792,31 @@ pub struct Element {
 
     #[serde(default)]
     pub children: Vec<u32>, // IDs of children
+
+    /// Number of identical elements this one stands for after scan deduplication.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub count: Option<usize>,
+
+    /// Effective `tabIndex`: 0 for elements that are focusable in document
+    /// order, positive for ones that come first, and -1 for ones reachable
+    /// only by script.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tab_index: Option<i32>,
+    /// Whether the element can take keyboard focus.
+    #[serde(default)]
+    pub focusable: bool,
+}
+
+impl Element {
+    /// Short one-line description, e.g. `[3] button "Sign in"`.
+    pub fn describe(&self) -> String {
+        match self.text.as_deref().or(self.label.as_deref()) {
+            Some(name) if !name.is_empty() => {
+                format!("[{}] {} {:?}", self.id, self.element_type, name)
+            }
+            _ => format!("[{}] {} {}", self.id, self.element_type, self.selector),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -621,6 +909,9 @@ pub struct Cookie {
     pub http_only: Option<bool>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub secure: Option<bool>,
+    /// `Strict`, `Lax` or `None`, when the backend reports it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub same_site: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -631,6 +922,16 @@ pub struct TabInfo {
     pub active: bool,
 }
 
+/// A frame on the current page, numbered in document order.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FrameInfo {
+    pub index: usize,
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub url: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct ForwardRequest {}
 
@@ -645,14 +946,28 @@ pub struct ScreenshotRequest {
     pub format: Option<String>, // "png", "jpeg"
     pub selector: Option<String>,
     pub fullpage: bool,
+    #[serde(default)]
+    pub wait_stable: bool,
+    /// Page region to capture, in CSS pixels.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub clip: Option<Rect>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PdfRequest {
     pub path: String,
-    pub format: Option<String>, // "A4", "Letter"
+    #[serde(flatten)]
+    pub options: PdfOptions,
+}
+
+/// Page layout for a PDF. Options left unset keep the browser's defaults.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PdfOptions {
+    pub format: Option<String>, // "A4", "Letter", "Legal", "Tabloid"
+    #[serde(default)]
     pub landscape: bool,
-    pub margin: Option<String>,
+    /// Margin on every side, in millimetres.
+    pub margin: Option<f32>,
     pub scale: Option<f32>,
 }
 
@@ -666,8 +981,20 @@ pub struct TabRequest {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FrameRequest {
-    pub action: String,         // "switch"
-    pub target: Option<String>, // "main", "parent", "iframe_selector"
+    pub action: String,         // "list", "switch"
+    pub target: Option<String>, // "main", "parent"
+    /// Switch to the frame with this `name` attribute.
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Switch to the first frame whose URL contains this text.
+    #[serde(default)]
+    pub url: Option<String>,
+    /// Switch to the frame whose `<iframe>` element matches this CSS selector.
+    #[serde(default)]
+    pub selector: Option<String>,
+    /// Switch to the frame whose `<iframe>` element has this id in the last scan.
+    #[serde(default)]
+    pub element: Option<u32>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -682,12 +1009,155 @@ pub struct PressRequest {
     pub modifiers: Vec<String>,
 }
 
+/// Add a network interception rule, or drop rules by pattern.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum InterceptRequest {
+    /// Apply `rule` to requests from now on, replacing any rule with the same
+    /// pattern. A mock with `body_file` gets its body from that file first.
+    Add {
+        rule: InterceptRule,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        body_file: Option<String>,
+    },
+    /// Drop the rule for one pattern, or every rule.
+    Clear {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pattern: Option<String>,
+    },
+}
+
+/// What to do with requests whose URL matches `url_pattern`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InterceptRule {
+    pub url_pattern: String,
+    pub action: InterceptAction,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum InterceptAction {
+    /// Fail the request as if blocked by the client.
+    Block,
+    /// Answer with this response without contacting the server.
+    Mock {
+        status: u16,
+        body: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        content_type: Option<String>,
+    },
+    /// Let the request through unchanged; it is only recorded.
+    Continue,
+}
+
+impl InterceptRule {
+    /// A pattern with `*` or `?` wildcards must match the whole URL; one
+    /// without matches any URL containing it.
+    pub fn matches(&self, url: &str) -> bool {
+        let pattern = &self.url_pattern;
+        if pattern.contains(['*', '?']) {
+            wildcard_match(pattern.as_bytes(), url.as_bytes())
+        } else {
+            url.contains(pattern.as_str())
+        }
+    }
+
+    /// The pattern in the form Chrome's Fetch domain takes, where wildcards
+    /// always span the whole URL.
+    pub fn fetch_pattern(&self) -> String {
+        if self.url_pattern.contains(['*', '?']) {
+            self.url_pattern.clone()
+        } else {
+            format!("*{}*", self.url_pattern)
+        }
+    }
+}
+
+/// The content type a mock without one is served as: `application/json`
+/// when the body parses as JSON, `text/plain` otherwise.
+pub fn guess_content_type(body: &str) -> &'static str {
+    if serde_json::from_str::<serde_json::Value>(body).is_ok() {
+        "application/json"
+    } else {
+        "text/plain"
+    }
+}
+
+/// `*` matches any run of characters, `?` any single one.
+fn wildcard_match(pattern: &[u8], text: &[u8]) -> bool {
+    let (mut p, mut t) = (0, 0);
+    let mut backtrack = None;
+    while t < text.len() {
+        match pattern.get(p) {
+            Some(b'*') => {
+                backtrack = Some((p, t));
+                p += 1;
+            }
+            Some(&c) if c == b'?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match backtrack {
+                Some((star, matched)) => {
+                    p = star + 1;
+                    t = matched + 1;
+                    backtrack = Some((star, matched + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == b'*')
+}
+
+/// List the network requests the backend has recorded, newest last.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RequestsRequest {
+    /// Only requests whose URL contains this text.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub filter: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub method: Option<String>,
+    /// Only the most recent `last` requests.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last: Option<usize>,
+}
+
+/// A network request the page made, as the backend recorded it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NetworkRequest {
+    pub method: String,
+    pub url: String,
+    /// Response status; absent while in flight or when the request failed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<u16>,
+    /// Set when an intercept rule blocked or mocked the request.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub intercepted: Option<Interception>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Interception {
+    Blocked,
+    Mocked,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CookieRequest {
-    pub action: String, // "get", "set", "delete", "clear", "list"
+    pub action: String, // "get", "get_all", "set", "delete", "clear", "list", "export", "import"
     pub name: Option<String>,
     pub value: Option<String>,
     pub domain: Option<String>,
+    /// Answer `get` and `get_all` with the cookies as JSON.
+    #[serde(default)]
+    pub json: bool,
+    /// Cookie file to `export` to or `import` from.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file: Option<String>,
+    /// `json` or `netscape`; detected from the file extension when absent.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub format: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
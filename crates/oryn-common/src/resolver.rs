@@ -9,6 +9,7 @@
 
 use crate::protocol::{Element, ScanResult};
 use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
 use thiserror::Error;
 
 /// Represents a target element in the UI.
@@ -18,8 +19,15 @@ pub enum Target {
     Id(usize),
     /// A semantic text match (e.g., "Sign in").
     Text(String),
+    /// A text match restricted to exact, prefix or suffix matches
+    /// (e.g., `="Save"`).
+    TextMatch(TextMatch),
     /// A semantic role (e.g., email, submit).
     Role(String),
+    /// A test hook attribute value, matched exactly (e.g., `testid "submit-btn"`).
+    TestId(String),
+    /// An element matching both a role and a text (e.g., `button "Submit"`).
+    RoleText { role: String, text: String },
     /// A raw CSS or XPath selector.
     Selector(String),
     /// Relational: Target is near another target.
@@ -47,21 +55,136 @@ pub enum Target {
         target: Box<Target>,
         content: Box<Target>,
     },
+    /// Relational: Target lies between two anchor targets.
+    Between {
+        target: Box<Target>,
+        start_anchor: Box<Target>,
+        end_anchor: Box<Target>,
+    },
+    /// Target, keeping only the matches in the given state.
+    State {
+        target: Box<Target>,
+        state: StateFilter,
+    },
     /// Placeholder indicating target should be inferred.
     Infer,
 }
 
+/// How a text target is compared with an element's text.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TextMatchMode {
+    /// Whole or partial matches, as for a plain `"text"` target.
+    #[default]
+    Contains,
+    /// `="text"`: only whole matches.
+    Exact,
+    /// `^"text"`: the element's text starts with the value.
+    Prefix,
+    /// `$"text"`: the element's text ends with the value.
+    Suffix,
+}
+
+impl TextMatchMode {
+    /// The OIL prefix that selects this mode.
+    pub fn sigil(self) -> &'static str {
+        match self {
+            TextMatchMode::Contains => "",
+            TextMatchMode::Exact => "=",
+            TextMatchMode::Prefix => "^",
+            TextMatchMode::Suffix => "$",
+        }
+    }
+
+    /// Score `field` against already normalized target text: `exact` for a
+    /// whole match, `partial` for the partial match this mode accepts.
+    fn score(self, field: &str, normalized: &str, exact: i32, partial: i32) -> i32 {
+        let field = normalize_text(field);
+        if field == normalized {
+            return exact;
+        }
+        let accepted = match self {
+            TextMatchMode::Contains => field.contains(normalized),
+            TextMatchMode::Exact => false,
+            TextMatchMode::Prefix => field.starts_with(normalized),
+            TextMatchMode::Suffix => field.ends_with(normalized),
+        };
+        if accepted { partial } else { 0 }
+    }
+}
+
+/// A text target with an explicit match mode, e.g. `="Save"`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TextMatch {
+    pub value: String,
+    pub mode: TextMatchMode,
+}
+
+impl std::fmt::Display for TextMatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}\"{}\"", self.mode.sigil(), self.value)
+    }
+}
+
+/// States a target's element must be in, e.g. `click "Submit" --enabled`.
+/// Unset fields are not checked.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StateFilter {
+    pub enabled: Option<bool>,
+    pub checked: Option<bool>,
+    pub visible: Option<bool>,
+    pub focused: Option<bool>,
+}
+
+impl StateFilter {
+    /// Whether the element is in every state the filter asks for.
+    pub fn matches(&self, elem: &Element) -> bool {
+        let state = &elem.state;
+        let visible = !state.hidden && elem.rect.width > 0.0 && elem.rect.height > 0.0;
+        // Only checkboxes and radios report `unchecked`, so other elements
+        // are neither checked nor unchecked.
+        let checked = if state.checked {
+            Some(true)
+        } else if state.unchecked {
+            Some(false)
+        } else {
+            None
+        };
+        self.enabled.is_none_or(|want| want != state.disabled)
+            && self.checked.is_none_or(|want| checked == Some(want))
+            && self.visible.is_none_or(|want| want == visible)
+            && self.focused.is_none_or(|want| want == state.focused)
+    }
+}
+
+impl std::fmt::Display for StateFilter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let pick = |value: Option<bool>, yes: &'static str, no: &'static str| {
+            value.map(|v| if v { yes } else { no })
+        };
+        let states: Vec<&str> = [
+            pick(self.enabled, "enabled", "disabled"),
+            pick(self.checked, "checked", "unchecked"),
+            pick(self.visible, "visible", "hidden"),
+            pick(self.focused, "focused", "unfocused"),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+        write!(f, "{}", states.join(", "))
+    }
+}
+
 /// Errors that can occur during target resolution.
 #[derive(Error, Debug, Clone)]
 pub enum ResolverError {
     #[error("No element matches target: {0}")]
     NoMatch(String),
 
-    #[error("Ambiguous target '{target}' matches {count} elements: {candidates:?}")]
+    #[error("{}", format_candidates(.target, .candidates))]
     AmbiguousMatch {
         target: String,
         count: usize,
-        candidates: Vec<u32>,
+        candidates: Vec<AmbiguityCandidate>,
     },
 
     #[error("Stale context: scan result is too old or missing")]
@@ -71,6 +194,61 @@ pub enum ResolverError {
     RelationalError(String),
 }
 
+/// One of several elements an ambiguous target matched.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AmbiguityCandidate {
+    pub id: u32,
+    pub text: String,
+    pub selector: String,
+}
+
+/// Longest candidate text shown before it is cut off with an ellipsis.
+const CANDIDATE_TEXT_LIMIT: usize = 40;
+
+impl AmbiguityCandidate {
+    /// Describe a scanned element by its visible text, falling back to its
+    /// label or placeholder.
+    pub fn from_element(element: &Element) -> Self {
+        let text = [&element.text, &element.label, &element.placeholder]
+            .into_iter()
+            .flatten()
+            .map(|s| s.trim())
+            .find(|s| !s.is_empty())
+            .unwrap_or_default();
+        let text = if text.chars().count() > CANDIDATE_TEXT_LIMIT {
+            let cut: String = text.chars().take(CANDIDATE_TEXT_LIMIT).collect();
+            format!("{}…", cut)
+        } else {
+            text.to_string()
+        };
+        Self {
+            id: element.id,
+            text,
+            selector: element.selector.clone(),
+        }
+    }
+}
+
+/// Render the numbered candidate list shown when `target` is ambiguous.
+/// Choices are numbered from 1 in the order given.
+pub fn format_candidates(target: &str, candidates: &[AmbiguityCandidate]) -> String {
+    let mut out = format!(
+        "Ambiguous target \"{}\" matches {} elements:",
+        target,
+        candidates.len()
+    );
+    for (i, c) in candidates.iter().enumerate() {
+        out.push_str(&format!(
+            "\n  {}) [{}] \"{}\" {}",
+            i + 1,
+            c.id,
+            c.text,
+            c.selector
+        ));
+    }
+    out
+}
+
 /// Strategy for handling multiple matches.
 #[derive(Debug, Clone, Copy, Default)]
 pub enum ResolutionStrategy {
@@ -87,23 +265,108 @@ pub enum ResolutionStrategy {
     PreferClickable,
     /// Prefer checkable elements (for check/uncheck commands).
     PreferCheckable,
+    /// Also consider hidden and zero-size elements; otherwise behaves like `First`.
+    IncludeHidden,
+    /// Report ties as `AmbiguousMatch` like `Unique`, for callers that can
+    /// ask the user to pick one of the candidates.
+    Interactive,
+}
+
+/// Weights the text resolver gives each way an element can match a target.
+///
+/// Most sources have an exact weight and a lower one for a contains match.
+/// The defaults rank visible text first; sites that label controls more
+/// reliably than they text them can raise `aria_label_exact` above
+/// `text_exact` instead.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScoringConfig {
+    pub text_exact: i32,
+    pub text_contains: i32,
+    pub label_exact: i32,
+    pub label_contains: i32,
+    pub id_exact: i32,
+    pub id_contains: i32,
+    pub name_exact: i32,
+    pub name_contains: i32,
+    pub placeholder_exact: i32,
+    pub placeholder_contains: i32,
+    pub value_exact: i32,
+    pub aria_label_exact: i32,
+    pub aria_label_contains: i32,
+    pub title_exact: i32,
+    /// Test hooks are deliberate, so by default they beat any text match.
+    pub test_id: i32,
+    /// A form field whose input type or autocomplete hint means what an
+    /// intent's field key asks for, as `type="email"` does for `email`.
+    pub field_purpose: i32,
+}
+
+impl Default for ScoringConfig {
+    fn default() -> Self {
+        Self {
+            text_exact: 100,
+            text_contains: 50,
+            label_exact: 90,
+            label_contains: 45,
+            id_exact: 88,
+            id_contains: 44,
+            name_exact: 86,
+            name_contains: 43,
+            placeholder_exact: 80,
+            placeholder_contains: 40,
+            value_exact: 70,
+            aria_label_exact: 85,
+            aria_label_contains: 42,
+            title_exact: 75,
+            test_id: 120,
+            field_purpose: 75,
+        }
+    }
 }
 
 /// Context for resolving semantic targets.
 ///
 /// Built from a `ScanResult` and used to resolve targets until the next scan.
+/// Elements keep the scan's document order. Ties between equally good
+/// matches are broken by position on the page instead, since that order
+/// differs between backends.
+///
+/// A context goes stale once the page has moved to another URL or, when a
+/// `max_age` is set, once its scan is older than that. Callers that know the
+/// current page check [`ResolverContext::ensure_fresh`] and re-scan first.
 #[derive(Debug, Clone)]
 pub struct ResolverContext {
     elements: Vec<Element>,
     url: String,
+    include_hidden: bool,
+    scoring: ScoringConfig,
+    generation: u64,
+    scanned_at: Option<Instant>,
+    max_age: Option<Duration>,
 }
 
 impl ResolverContext {
     /// Create a new resolver context from a scan result.
+    ///
+    /// Scans taken with `observe --hidden` report `include_hidden` in their
+    /// applied settings; such contexts keep hidden elements resolvable.
     pub fn new(scan_result: &ScanResult) -> Self {
+        let include_hidden = scan_result
+            .settings_applied
+            .as_ref()
+            .and_then(|s| s.get("include_hidden"))
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+
         Self {
             elements: scan_result.elements.clone(),
             url: scan_result.page.url.clone(),
+            include_hidden,
+            scoring: ScoringConfig::default(),
+            generation: 0,
+            scanned_at: None,
+            max_age: None,
         }
     }
 
@@ -112,9 +375,82 @@ impl ResolverContext {
         Self {
             elements: vec![],
             url: String::new(),
+            include_hidden: false,
+            scoring: ScoringConfig::default(),
+            generation: 0,
+            scanned_at: None,
+            max_age: None,
+        }
+    }
+
+    /// Allow or forbid resolution to hidden and zero-size elements.
+    pub fn with_hidden(mut self, include_hidden: bool) -> Self {
+        self.include_hidden = include_hidden;
+        self
+    }
+
+    /// Score text matches with `scoring` instead of the default weights.
+    pub fn with_scoring(mut self, scoring: ScoringConfig) -> Self {
+        self.scoring = scoring;
+        self
+    }
+
+    /// Number the context with the scan it was built from. Generations only
+    /// grow, so a higher one is always the more recent scan.
+    pub fn with_generation(mut self, generation: u64) -> Self {
+        self.generation = generation;
+        self
+    }
+
+    /// Record when the scan was taken, for `max_age` checks. Without it the
+    /// context never ages.
+    pub fn with_scanned_at(mut self, scanned_at: Instant) -> Self {
+        self.scanned_at = Some(scanned_at);
+        self
+    }
+
+    /// Treat the context as stale once its scan is older than `max_age`.
+    pub fn with_max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = Some(max_age);
+        self
+    }
+
+    /// The generation of the scan this context was built from.
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// Whether the context no longer describes the page at `current_url`:
+    /// the page has navigated elsewhere, or the scan is older than `max_age`.
+    pub fn is_stale(&self, current_url: &str) -> bool {
+        if !current_url.is_empty() && current_url != self.url {
+            return true;
+        }
+        match (self.scanned_at, self.max_age) {
+            (Some(scanned_at), Some(max_age)) => scanned_at.elapsed() > max_age,
+            _ => false,
+        }
+    }
+
+    /// Fail with `StaleContext` when the context is stale for `current_url`.
+    pub fn ensure_fresh(&self, current_url: &str) -> Result<(), ResolverError> {
+        if self.is_stale(current_url) {
+            Err(ResolverError::StaleContext)
+        } else {
+            Ok(())
         }
     }
 
+    /// The weights text matches are scored with.
+    pub fn scoring(&self) -> &ScoringConfig {
+        &self.scoring
+    }
+
+    /// Whether hidden and zero-size elements are resolvable in this context.
+    pub fn includes_hidden(&self) -> bool {
+        self.include_hidden
+    }
+
     /// Check if the context has any elements.
     pub fn is_empty(&self) -> bool {
         self.elements.is_empty()
@@ -164,21 +500,25 @@ pub fn resolve_target(
             }
         }
 
-        // Text matching: element.text, label, placeholder, value
-        Target::Text(text) => resolve_by_text(text, ctx, strategy),
-
-        // Role matching: element.role
-        Target::Role(role) => resolve_by_role(role, ctx, strategy),
-
         // CSS/XPath selector: pass through (scanner handles it)
         Target::Selector(_) => Ok(target.clone()),
 
-        // Relational: resolve recursively
-        Target::Near { target, anchor } => resolve_near(target, anchor, ctx, strategy),
-        Target::Inside { target, container } => resolve_inside(target, container, ctx, strategy),
-        Target::After { target, anchor } => resolve_after(target, anchor, ctx, strategy),
-        Target::Before { target, anchor } => resolve_before(target, anchor, ctx, strategy),
-        Target::Contains { target, content } => resolve_contains(target, content, ctx, strategy),
+        // Text, role and relational targets: score candidates, then pick one
+        Target::Text(_)
+        | Target::TextMatch(_)
+        | Target::Role(_)
+        | Target::TestId(_)
+        | Target::RoleText { .. }
+        | Target::Near { .. }
+        | Target::Inside { .. }
+        | Target::After { .. }
+        | Target::Before { .. }
+        | Target::Contains { .. }
+        | Target::Between { .. }
+        | Target::State { .. } => {
+            let matches = scored_matches(target, ctx, strategy)?;
+            select_match(&matches, &describe_target(target), strategy, Some(ctx))
+        }
 
         // Inference should be handled by ResolutionEngine, not here
         Target::Infer => Err(ResolverError::NoMatch(
@@ -187,84 +527,222 @@ pub fn resolve_target(
     }
 }
 
+/// Resolve a target to every matching element ID.
+///
+/// IDs are ordered by descending score, with ties kept in document order.
+/// Relational targets return all inner candidates that pass their geometric
+/// filter. `Target::Selector` passes through unchanged as the only entry.
+pub fn resolve_all(target: &Target, ctx: &ResolverContext) -> Result<Vec<Target>, ResolverError> {
+    match target {
+        Target::Id(_) => resolve_target(target, ctx, ResolutionStrategy::First).map(|t| vec![t]),
+        Target::Selector(_) => Ok(vec![target.clone()]),
+        Target::Infer => Err(ResolverError::NoMatch(
+            "Target inference not supported in legacy resolver".into(),
+        )),
+        _ => {
+            let mut matches = scored_matches(target, ctx, ResolutionStrategy::First)?;
+            if matches.is_empty() {
+                return Err(ResolverError::NoMatch(describe_target(target)));
+            }
+            // Stable sort keeps document order among equal scores
+            matches.sort_by_key(|m| std::cmp::Reverse(m.1));
+            Ok(matches
+                .into_iter()
+                .map(|(id, _)| Target::Id(id as usize))
+                .collect())
+        }
+    }
+}
+
+/// Every element `target` matches with its score, in the order
+/// [`resolve_target`] ranks them under `strategy`: scores include the
+/// strategy's command preferences, and ties are broken the same way, so the
+/// first entry is the element it would pick.
+///
+/// An ID target yields itself with a score of zero. Selectors are matched by
+/// the scanner rather than scored here, so they are rejected.
+pub fn resolve_all_scored(
+    target: &Target,
+    ctx: &ResolverContext,
+    strategy: ResolutionStrategy,
+) -> Result<Vec<(u32, i32)>, ResolverError> {
+    match target {
+        Target::Id(id) => {
+            resolve_target(target, ctx, ResolutionStrategy::First)?;
+            Ok(vec![(*id as u32, 0)])
+        }
+        Target::Selector(_) => Err(ResolverError::NoMatch(format!(
+            "{}: selectors are matched by the scanner, not scored",
+            describe_target(target)
+        ))),
+        Target::Infer => Err(ResolverError::NoMatch(
+            "Target inference not supported in legacy resolver".into(),
+        )),
+        _ => {
+            let matches = scored_matches(target, ctx, strategy)?;
+            if matches.is_empty() {
+                return Err(ResolverError::NoMatch(describe_target(target)));
+            }
+            Ok(rank_matches(&matches, strategy, Some(ctx)))
+        }
+    }
+}
+
+/// Score the candidates for a text, role or relational target.
+fn scored_matches(
+    target: &Target,
+    ctx: &ResolverContext,
+    strategy: ResolutionStrategy,
+) -> Result<Vec<(u32, i32)>, ResolverError> {
+    match target {
+        // Text matching: element.text, label, placeholder, value
+        Target::Text(text) => resolve_by_text(text, TextMatchMode::Contains, ctx, strategy),
+        Target::TextMatch(m) => resolve_by_text(&m.value, m.mode, ctx, strategy),
+
+        // Role matching: element.role
+        Target::Role(role) => resolve_by_role(role, ctx, strategy),
+
+        // Test hooks: data-testid and friends, exact only
+        Target::TestId(test_id) => Ok(resolve_by_test_id(test_id, ctx, strategy)),
+
+        // Role and text together: only elements matching both
+        Target::RoleText { role, text } => resolve_by_role_text(role, text, ctx, strategy),
+
+        // Relational: resolve recursively
+        Target::Near { target, anchor } => resolve_near(target, anchor, ctx),
+        Target::Inside { target, container } => resolve_inside(target, container, ctx),
+        Target::After { target, anchor } => resolve_after(target, anchor, ctx),
+        Target::Before { target, anchor } => resolve_before(target, anchor, ctx),
+        Target::Contains { target, content } => resolve_contains(target, content, ctx),
+        Target::Between {
+            target,
+            start_anchor,
+            end_anchor,
+        } => resolve_between(target, start_anchor, end_anchor, ctx),
+
+        // Drop candidates in the wrong state before one is picked
+        Target::State { target, state } => {
+            let candidates = match target.as_ref() {
+                Target::Id(id) => vec![(*id as u32, 0)],
+                Target::Selector(_) => {
+                    return Err(ResolverError::NoMatch(format!(
+                        "{} ({}): element state cannot be checked for a selector",
+                        describe_target(target),
+                        state
+                    )));
+                }
+                inner => scored_matches(inner, ctx, strategy)?,
+            };
+            Ok(candidates
+                .into_iter()
+                .filter(|(id, _)| ctx.get_element(*id).is_some_and(|e| state.matches(e)))
+                .collect())
+        }
+
+        // Handled directly by the callers
+        Target::Id(_) | Target::Selector(_) | Target::Infer => Ok(vec![]),
+    }
+}
+
+/// Human-readable description of a target for error messages.
+fn describe_target(target: &Target) -> String {
+    match target {
+        Target::Text(text) => text.clone(),
+        Target::TextMatch(m) => m.to_string(),
+        Target::Role(role) => role.clone(),
+        Target::TestId(test_id) => format!("testid {}", test_id),
+        Target::RoleText { role, text } => format!("{} \"{}\"", role, text),
+        Target::Near { target, anchor } => format!("{:?} near {:?}", target, anchor),
+        Target::Inside { target, container } => format!("{:?} inside {:?}", target, container),
+        Target::After { target, anchor } => format!("{:?} after {:?}", target, anchor),
+        Target::Before { target, anchor } => format!("{:?} before {:?}", target, anchor),
+        Target::Contains { target, content } => format!("{:?} contains {:?}", target, content),
+        Target::Between {
+            target,
+            start_anchor,
+            end_anchor,
+        } => format!(
+            "{:?} between {:?} and {:?}",
+            target, start_anchor, end_anchor
+        ),
+        Target::State { target, state } => format!("{} ({})", describe_target(target), state),
+        other => format!("{:?}", other),
+    }
+}
+
 /// Resolve a text-based target.
 fn resolve_by_text(
     text: &str,
+    mode: TextMatchMode,
     ctx: &ResolverContext,
     strategy: ResolutionStrategy,
-) -> Result<Target, ResolverError> {
+) -> Result<Vec<(u32, i32)>, ResolverError> {
     let normalized = normalize_text(text);
+    let weights = ctx.scoring();
     let mut matches: Vec<(u32, i32)> = vec![]; // (id, score)
 
     for elem in ctx.elements() {
-        let mut score = 0;
+        if !is_resolvable(elem, ctx, strategy) {
+            continue;
+        }
 
-        // Exact text match (highest priority)
-        if let Some(ref elem_text) = elem.text {
-            if normalize_text(elem_text) == normalized {
-                score = 100;
-            } else if normalize_text(elem_text).contains(&normalized) {
-                score = 50;
+        let mut score = 0;
+        let mut consider = |field: Option<&String>, exact: i32, partial: i32| {
+            if let Some(field) = field {
+                score = score.max(mode.score(field, &normalized, exact, partial));
             }
-        }
+        };
+
+        // Exact text match (highest priority by default)
+        consider(
+            elem.text.as_ref(),
+            weights.text_exact,
+            weights.text_contains,
+        );
 
         // Label match
-        if let Some(ref label) = elem.label {
-            if normalize_text(label) == normalized {
-                score = score.max(90);
-            } else if normalize_text(label).contains(&normalized) {
-                score = score.max(45);
-            }
-        }
+        consider(
+            elem.label.as_ref(),
+            weights.label_exact,
+            weights.label_contains,
+        );
 
         // HTML id attribute match (for targets like "coupon-code")
-        if let Some(id_attr) = elem.attributes.get("id") {
-            if normalize_text(id_attr) == normalized {
-                score = score.max(88);
-            } else if normalize_text(id_attr).contains(&normalized) {
-                score = score.max(44);
-            }
-        }
+        consider(
+            elem.attributes.get("id"),
+            weights.id_exact,
+            weights.id_contains,
+        );
 
         // HTML name attribute match (for form inputs)
-        if let Some(name_attr) = elem.attributes.get("name") {
-            if normalize_text(name_attr) == normalized {
-                score = score.max(86);
-            } else if normalize_text(name_attr).contains(&normalized) {
-                score = score.max(43);
-            }
-        }
+        consider(
+            elem.attributes.get("name"),
+            weights.name_exact,
+            weights.name_contains,
+        );
 
         // Placeholder match
-        if let Some(ref placeholder) = elem.placeholder {
-            if normalize_text(placeholder) == normalized {
-                score = score.max(80);
-            } else if normalize_text(placeholder).contains(&normalized) {
-                score = score.max(40);
-            }
-        }
+        consider(
+            elem.placeholder.as_ref(),
+            weights.placeholder_exact,
+            weights.placeholder_contains,
+        );
 
-        // Value match (for inputs)
-        if let Some(ref value) = elem.value
-            && normalize_text(value) == normalized
-        {
-            score = score.max(70);
-        }
+        // Value match (for inputs), whole values only
+        consider(elem.value.as_ref(), weights.value_exact, 0);
 
         // aria-label attribute
-        if let Some(aria_label) = elem.attributes.get("aria-label") {
-            if normalize_text(aria_label) == normalized {
-                score = score.max(85);
-            } else if normalize_text(aria_label).contains(&normalized) {
-                score = score.max(42);
-            }
-        }
+        consider(
+            elem.attributes.get("aria-label"),
+            weights.aria_label_exact,
+            weights.aria_label_contains,
+        );
 
-        // title attribute
-        if let Some(title) = elem.attributes.get("title")
-            && normalize_text(title) == normalized
-        {
-            score = score.max(75);
+        // title attribute, whole titles only
+        consider(elem.attributes.get("title"), weights.title_exact, 0);
+
+        if has_test_id(elem, text.trim()) {
+            score = score.max(weights.test_id);
         }
 
         if score > 0 {
@@ -272,7 +750,113 @@ fn resolve_by_text(
         }
     }
 
-    select_match(&matches, text, strategy, Some(ctx))
+    // A fuzzy match would defeat an explicit match mode
+    if matches.is_empty() && mode == TextMatchMode::Contains {
+        matches = resolve_by_fuzzy_text(&normalized, ctx, strategy);
+    }
+
+    Ok(matches)
+}
+
+/// Attributes testers put on elements as stable hooks.
+pub const TEST_ID_ATTRIBUTES: [&str; 3] = ["data-testid", "data-test", "data-cy"];
+
+fn has_test_id(elem: &Element, test_id: &str) -> bool {
+    TEST_ID_ATTRIBUTES
+        .iter()
+        .any(|attr| elem.attributes.get(*attr).is_some_and(|v| v == test_id))
+}
+
+/// Resolve a `testid` target against the test hook attributes.
+fn resolve_by_test_id(
+    test_id: &str,
+    ctx: &ResolverContext,
+    strategy: ResolutionStrategy,
+) -> Vec<(u32, i32)> {
+    ctx.elements()
+        .iter()
+        .filter(|elem| is_resolvable(elem, ctx, strategy) && has_test_id(elem, test_id))
+        .map(|elem| (elem.id, ctx.scoring().test_id))
+        .collect()
+}
+
+/// Normalized Levenshtein ratio a fuzzy text match must exceed.
+const FUZZY_MATCH_THRESHOLD: f64 = 0.85;
+
+/// Highest score a fuzzy match can earn, kept below every contains match.
+const FUZZY_MATCH_MAX_SCORE: i32 = 30;
+
+/// Last-resort pass for near-miss spellings such as "Sign In" vs "Sign-In",
+/// comparing the target against text, label and aria-label.
+fn resolve_by_fuzzy_text(
+    normalized: &str,
+    ctx: &ResolverContext,
+    strategy: ResolutionStrategy,
+) -> Vec<(u32, i32)> {
+    let mut matches = vec![];
+
+    for elem in ctx.elements() {
+        if !is_resolvable(elem, ctx, strategy) {
+            continue;
+        }
+
+        let candidates = [
+            elem.text.as_ref(),
+            elem.label.as_ref(),
+            elem.attributes.get("aria-label"),
+        ];
+        let best = candidates
+            .into_iter()
+            .flatten()
+            .map(|candidate| strsim::normalized_levenshtein(normalized, &normalize_text(candidate)))
+            .fold(0.0, f64::max);
+
+        if best > FUZZY_MATCH_THRESHOLD {
+            let score = (best * FUZZY_MATCH_MAX_SCORE as f64).round() as i32;
+            matches.push((elem.id, score.min(FUZZY_MATCH_MAX_SCORE)));
+        }
+    }
+
+    matches
+}
+
+/// Accessibility roles and the element types that carry them implicitly, so
+/// `link` finds `<a>` elements that have no explicit role.
+const ROLE_SYNONYMS: &[(&str, &[&str])] = &[
+    ("link", &["a"]),
+    ("textbox", &["input", "textarea"]),
+    ("combobox", &["select"]),
+    ("listbox", &["select"]),
+    ("img", &["img"]),
+    ("heading", &["h1", "h2", "h3", "h4", "h5", "h6"]),
+    ("list", &["ul", "ol"]),
+    ("listitem", &["li"]),
+    ("table", &["table"]),
+    ("form", &["form"]),
+];
+
+/// Input types rendered as a plain text box. An input without a type is one.
+const TEXTBOX_INPUT_TYPES: &[&str] = &["text", "email", "search", "tel", "url"];
+
+/// Score for an element whose type implies the role, below every direct match.
+const ROLE_SYNONYM_SCORE: i32 = 75;
+
+/// Whether `elem`'s type implies `role` through [`ROLE_SYNONYMS`].
+fn has_implicit_role(elem: &Element, role: &str) -> bool {
+    let element_type = elem.element_type.to_lowercase();
+    let Some((_, types)) = ROLE_SYNONYMS.iter().find(|(r, _)| *r == role) else {
+        return false;
+    };
+    if !types.contains(&element_type.as_str()) {
+        return false;
+    }
+    if role == "textbox" && element_type == "input" {
+        return elem
+            .attributes
+            .get("type")
+            .is_none_or(|t| TEXTBOX_INPUT_TYPES.contains(&t.to_lowercase().as_str()));
+    }
+    true
 }
 
 /// Resolve a role-based target.
@@ -280,11 +864,15 @@ fn resolve_by_role(
     role: &str,
     ctx: &ResolverContext,
     strategy: ResolutionStrategy,
-) -> Result<Target, ResolverError> {
+) -> Result<Vec<(u32, i32)>, ResolverError> {
     let normalized_role = role.to_lowercase();
     let mut matches: Vec<(u32, i32)> = vec![];
 
     for elem in ctx.elements() {
+        if !is_resolvable(elem, ctx, strategy) {
+            continue;
+        }
+
         let mut score = 0;
 
         // Direct role match
@@ -320,6 +908,10 @@ fn resolve_by_role(
             score = score.max(95);
         }
 
+        if has_implicit_role(elem, &normalized_role) {
+            score = score.max(ROLE_SYNONYM_SCORE);
+        }
+
         // Special role mappings
         if normalized_role == "submit"
             && (elem.element_type == "button"
@@ -342,7 +934,27 @@ fn resolve_by_role(
         }
     }
 
-    select_match(&matches, role, strategy, Some(ctx))
+    Ok(matches)
+}
+
+/// Resolve a `role "text"` target to the elements both halves match,
+/// scored by the sum of their role and text scores.
+fn resolve_by_role_text(
+    role: &str,
+    text: &str,
+    ctx: &ResolverContext,
+    strategy: ResolutionStrategy,
+) -> Result<Vec<(u32, i32)>, ResolverError> {
+    let by_text = resolve_by_text(text, TextMatchMode::Contains, ctx, strategy)?;
+    Ok(resolve_by_role(role, ctx, strategy)?
+        .into_iter()
+        .filter_map(|(id, role_score)| {
+            by_text
+                .iter()
+                .find(|(text_id, _)| *text_id == id)
+                .map(|(_, text_score)| (id, role_score + text_score))
+        })
+        .collect())
 }
 
 /// Resolve `target near anchor`.
@@ -350,10 +962,9 @@ fn resolve_near(
     target: &Target,
     anchor: &Target,
     ctx: &ResolverContext,
-    strategy: ResolutionStrategy,
-) -> Result<Target, ResolverError> {
+) -> Result<Vec<(u32, i32)>, ResolverError> {
     // First resolve the anchor
-    let anchor_resolved = resolve_target(anchor, ctx, ResolutionStrategy::First)?;
+    let anchor_resolved = resolve_anchor(anchor, ctx)?;
     let anchor_id = match anchor_resolved {
         Target::Id(id) => id,
         _ => {
@@ -401,14 +1012,29 @@ fn resolve_near(
         })
         .collect();
 
-    scored.sort_by(|a, b| b.1.cmp(&a.1));
+    scored.sort_by_key(|b| std::cmp::Reverse(b.1));
 
-    select_match(
-        &scored,
-        &format!("{:?} near {:?}", target, anchor),
-        strategy,
-        Some(ctx),
-    )
+    Ok(scored)
+}
+
+/// Resolve the anchor, container or content of a relational target to an ID.
+///
+/// Selector anchors are matched against the scanned elements, taking the
+/// first in document order, since only an element's rect can be measured.
+fn resolve_anchor(anchor: &Target, ctx: &ResolverContext) -> Result<Target, ResolverError> {
+    let Target::Selector(selector) = anchor else {
+        return resolve_target(anchor, ctx, ResolutionStrategy::First);
+    };
+    match select_local(selector, ctx) {
+        Some(ids) => ids
+            .first()
+            .map(|id| Target::Id(*id as usize))
+            .ok_or_else(|| ResolverError::NoMatch(format!("css({})", selector))),
+        None => Err(ResolverError::RelationalError(format!(
+            "css({}) is too complex to match against the scan; use a tag, #id, .class or [attr=value] selector",
+            selector
+        ))),
+    }
 }
 
 /// Resolve `target inside container`.
@@ -416,10 +1042,9 @@ fn resolve_inside(
     target: &Target,
     container: &Target,
     ctx: &ResolverContext,
-    strategy: ResolutionStrategy,
-) -> Result<Target, ResolverError> {
+) -> Result<Vec<(u32, i32)>, ResolverError> {
     // Resolve container first
-    let container_resolved = resolve_target(container, ctx, ResolutionStrategy::First)?;
+    let container_resolved = resolve_anchor(container, ctx)?;
     let container_id = match container_resolved {
         Target::Id(id) => id,
         _ => {
@@ -457,12 +1082,7 @@ fn resolve_inside(
         )));
     }
 
-    select_match(
-        &inside,
-        &format!("{:?} inside {:?}", target, container),
-        strategy,
-        Some(ctx),
-    )
+    Ok(inside)
 }
 
 /// Resolve `target after anchor`.
@@ -470,9 +1090,8 @@ fn resolve_after(
     target: &Target,
     anchor: &Target,
     ctx: &ResolverContext,
-    strategy: ResolutionStrategy,
-) -> Result<Target, ResolverError> {
-    let anchor_resolved = resolve_target(anchor, ctx, ResolutionStrategy::First)?;
+) -> Result<Vec<(u32, i32)>, ResolverError> {
+    let anchor_resolved = resolve_anchor(anchor, ctx)?;
     let anchor_id = match anchor_resolved {
         Target::Id(id) => id,
         _ => {
@@ -517,12 +1136,7 @@ fn resolve_after(
         )));
     }
 
-    select_match(
-        &after,
-        &format!("{:?} after {:?}", target, anchor),
-        strategy,
-        Some(ctx),
-    )
+    Ok(after)
 }
 
 /// Resolve `target before anchor`.
@@ -530,9 +1144,8 @@ fn resolve_before(
     target: &Target,
     anchor: &Target,
     ctx: &ResolverContext,
-    strategy: ResolutionStrategy,
-) -> Result<Target, ResolverError> {
-    let anchor_resolved = resolve_target(anchor, ctx, ResolutionStrategy::First)?;
+) -> Result<Vec<(u32, i32)>, ResolverError> {
+    let anchor_resolved = resolve_anchor(anchor, ctx)?;
     let anchor_id = match anchor_resolved {
         Target::Id(id) => id,
         _ => {
@@ -575,12 +1188,7 @@ fn resolve_before(
         )));
     }
 
-    select_match(
-        &before,
-        &format!("{:?} before {:?}", target, anchor),
-        strategy,
-        Some(ctx),
-    )
+    Ok(before)
 }
 
 /// Resolve `target contains content`.
@@ -588,10 +1196,9 @@ fn resolve_contains(
     target: &Target,
     content: &Target,
     ctx: &ResolverContext,
-    strategy: ResolutionStrategy,
-) -> Result<Target, ResolverError> {
+) -> Result<Vec<(u32, i32)>, ResolverError> {
     // Resolve content first
-    let content_resolved = resolve_target(content, ctx, ResolutionStrategy::First)?;
+    let content_resolved = resolve_anchor(content, ctx)?;
     let content_id = match content_resolved {
         Target::Id(id) => id,
         _ => {
@@ -628,30 +1235,256 @@ fn resolve_contains(
         )));
     }
 
-    select_match(
-        &containing,
-        &format!("{:?} contains {:?}", target, content),
-        strategy,
-        Some(ctx),
-    )
+    Ok(containing)
+}
+
+/// Resolve `target between start_anchor and end_anchor`.
+///
+/// The band between the anchors is vertical (from the upper anchor's bottom
+/// edge to the lower anchor's top edge) unless the anchors share a row, in
+/// which case it runs horizontally between their facing edges. Candidates
+/// qualify when their center falls inside the band.
+fn resolve_between(
+    target: &Target,
+    start_anchor: &Target,
+    end_anchor: &Target,
+    ctx: &ResolverContext,
+) -> Result<Vec<(u32, i32)>, ResolverError> {
+    let mut anchors = Vec::with_capacity(2);
+    for anchor in [start_anchor, end_anchor] {
+        let anchor_id = match resolve_anchor(anchor, ctx)? {
+            Target::Id(id) => id,
+            _ => {
+                return Err(ResolverError::RelationalError(
+                    "Anchor must resolve to ID".into(),
+                ));
+            }
+        };
+        let anchor_elem = ctx.get_element(anchor_id as u32).ok_or_else(|| {
+            ResolverError::NoMatch(format!("Anchor element {} not found", anchor_id))
+        })?;
+        anchors.push(&anchor_elem.rect);
+    }
+    let (a, b) = (anchors[0], anchors[1]);
+
+    let same_row = a.y < b.y + b.height && b.y < a.y + a.height;
+    let (band_start, band_end) = if same_row {
+        let (left, right) = if a.x <= b.x { (a, b) } else { (b, a) };
+        (left.x + left.width, right.x)
+    } else {
+        let (top, bottom) = if a.y <= b.y { (a, b) } else { (b, a) };
+        (top.y + top.height, bottom.y)
+    };
+
+    let candidates = get_matching_candidates(target, ctx)?;
+
+    let between: Vec<(u32, i32)> = candidates
+        .iter()
+        .filter_map(|&id| {
+            ctx.get_element(id).and_then(|elem| {
+                let center = if same_row {
+                    elem.rect.x + elem.rect.width / 2.0
+                } else {
+                    elem.rect.y + elem.rect.height / 2.0
+                };
+                if center > band_start && center < band_end {
+                    Some((id, 100))
+                } else {
+                    None
+                }
+            })
+        })
+        .collect();
+
+    if between.is_empty() {
+        return Err(ResolverError::NoMatch(format!(
+            "{:?} between {:?} and {:?}",
+            target, start_anchor, end_anchor
+        )));
+    }
+
+    Ok(between)
 }
 
 // Helper functions
 
-/// Normalize text for comparison (lowercase, trim, collapse whitespace).
-fn normalize_text(text: &str) -> String {
-    text.to_lowercase()
-        .split_whitespace()
-        .collect::<Vec<_>>()
-        .join(" ")
+/// Normalize text for comparison (lowercase, trim, collapse whitespace).
+fn normalize_text(text: &str) -> String {
+    collapse_whitespace(&text.to_lowercase())
+}
+
+/// Trim text and collapse internal whitespace runs into single spaces.
+pub fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Whether an element may be picked by semantic resolution.
+///
+/// Hidden and zero-size elements are skipped unless the context came from a
+/// `--hidden` scan or the caller asked for `ResolutionStrategy::IncludeHidden`.
+fn is_resolvable(elem: &Element, ctx: &ResolverContext, strategy: ResolutionStrategy) -> bool {
+    if ctx.includes_hidden() || matches!(strategy, ResolutionStrategy::IncludeHidden) {
+        return true;
+    }
+    !elem.state.hidden && elem.rect.width > 0.0 && elem.rect.height > 0.0
+}
+
+/// Check if inner rect is inside outer rect.
+fn is_inside(inner: &crate::protocol::Rect, outer: &crate::protocol::Rect) -> bool {
+    inner.x >= outer.x
+        && inner.y >= outer.y
+        && inner.x + inner.width <= outer.x + outer.width
+        && inner.y + inner.height <= outer.y + outer.height
+}
+
+/// Attributes the scanner records on every element, so a selector on one of
+/// them can be decided from the scan alone. Keep in sync with
+/// `getElementAttributes` in scanner.js.
+const SCANNED_ATTRIBUTES: &[&str] = &[
+    "id",
+    "class",
+    "href",
+    "src",
+    "placeholder",
+    "name",
+    "autocomplete",
+    "aria-label",
+    "aria-labelledby",
+    "aria-hidden",
+    "aria-disabled",
+    "aria-describedby",
+    "for",
+    "title",
+    "tabindex",
+];
+
+/// One part of a compound selector such as `button.primary[name=save]`.
+#[derive(Debug, PartialEq)]
+enum SimpleSelector {
+    Tag(String),
+    Id(String),
+    Class(String),
+    Attribute { name: String, value: Option<String> },
+}
+
+/// IDs of the scanned elements matching `selector`, in document order.
+///
+/// Only comma-separated lists of compound selectors built from a tag, `#id`,
+/// `.class`, `[attr]` and `[attr=value]` are understood, plus the exact
+/// selector the scanner generated for an element. Anything else -
+/// combinators, pseudo-classes, XPath - returns `None`, as does an attribute
+/// the scanner does not record.
+fn select_local(selector: &str, ctx: &ResolverContext) -> Option<Vec<u32>> {
+    let Some(compounds) = selector
+        .split(',')
+        .map(parse_compound_selector)
+        .collect::<Option<Vec<_>>>()
+    else {
+        // The scanner's own generated selector still identifies its element
+        let ids: Vec<u32> = ctx
+            .elements()
+            .iter()
+            .filter(|e| e.selector == selector)
+            .map(|e| e.id)
+            .collect();
+        return (!ids.is_empty()).then_some(ids);
+    };
+    Some(
+        ctx.elements()
+            .iter()
+            .filter(|e| {
+                compounds
+                    .iter()
+                    .any(|parts| parts.iter().all(|part| simple_selector_matches(part, e)))
+            })
+            .map(|e| e.id)
+            .collect(),
+    )
+}
+
+fn parse_compound_selector(compound: &str) -> Option<Vec<SimpleSelector>> {
+    fn ident(rest: &str) -> (&str, &str) {
+        let end = rest
+            .find(|c: char| !(c.is_alphanumeric() || c == '-' || c == '_'))
+            .unwrap_or(rest.len());
+        rest.split_at(end)
+    }
+
+    let mut rest = compound.trim();
+    let mut parts = Vec::new();
+    if let Some(after) = rest.strip_prefix('*') {
+        rest = after;
+    } else {
+        let (tag, after) = ident(rest);
+        if !tag.is_empty() {
+            parts.push(SimpleSelector::Tag(tag.to_lowercase()));
+            rest = after;
+        }
+    }
+
+    while let Some(c) = rest.chars().next() {
+        rest = &rest[1..];
+        let part = match c {
+            '#' | '.' => {
+                let (name, after) = ident(rest);
+                if name.is_empty() {
+                    return None;
+                }
+                rest = after;
+                if c == '#' {
+                    SimpleSelector::Id(name.to_string())
+                } else {
+                    SimpleSelector::Class(name.to_string())
+                }
+            }
+            '[' => {
+                let end = rest.find(']')?;
+                let (inner, after) = (&rest[..end], &rest[end + 1..]);
+                rest = after;
+                let (name, value) = match inner.split_once('=') {
+                    Some((name, value)) => {
+                        let value = value.trim();
+                        let value = value
+                            .strip_prefix('"')
+                            .and_then(|v| v.strip_suffix('"'))
+                            .or_else(|| value.strip_prefix('\'').and_then(|v| v.strip_suffix('\'')))
+                            .unwrap_or(value);
+                        (name.trim(), Some(value.to_string()))
+                    }
+                    None => (inner.trim(), None),
+                };
+                let (checked, leftover) = ident(name);
+                if checked.is_empty() || !leftover.is_empty() {
+                    // `~=`, `^=` and friends
+                    return None;
+                }
+                let name = name.to_lowercase();
+                if !name.starts_with("data-") && !SCANNED_ATTRIBUTES.contains(&name.as_str()) {
+                    return None;
+                }
+                SimpleSelector::Attribute { name, value }
+            }
+            _ => return None,
+        };
+        parts.push(part);
+    }
+
+    (!parts.is_empty()).then_some(parts)
 }
 
-/// Check if inner rect is inside outer rect.
-fn is_inside(inner: &crate::protocol::Rect, outer: &crate::protocol::Rect) -> bool {
-    inner.x >= outer.x
-        && inner.y >= outer.y
-        && inner.x + inner.width <= outer.x + outer.width
-        && inner.y + inner.height <= outer.y + outer.height
+fn simple_selector_matches(part: &SimpleSelector, elem: &Element) -> bool {
+    match part {
+        SimpleSelector::Tag(tag) => elem.element_type.eq_ignore_ascii_case(tag),
+        SimpleSelector::Id(id) => elem.attributes.get("id") == Some(id),
+        SimpleSelector::Class(class) => elem
+            .attributes
+            .get("class")
+            .is_some_and(|classes| classes.split_whitespace().any(|c| c == class)),
+        SimpleSelector::Attribute { name, value } => match value {
+            Some(value) => elem.attributes.get(name) == Some(value),
+            None => elem.attributes.contains_key(name),
+        },
+    }
 }
 
 /// Get element IDs matching a target pattern (without full resolution).
@@ -691,6 +1524,20 @@ fn get_matching_candidates(
                 .map(|e| e.id)
                 .collect())
         }
+        Target::TextMatch(m) => {
+            Ok(
+                resolve_by_text(&m.value, m.mode, ctx, ResolutionStrategy::IncludeHidden)?
+                    .into_iter()
+                    .map(|(id, _)| id)
+                    .collect(),
+            )
+        }
+        Target::TestId(test_id) => Ok(ctx
+            .elements()
+            .iter()
+            .filter(|e| has_test_id(e, test_id))
+            .map(|e| e.id)
+            .collect()),
         Target::Role(role) => {
             let normalized = role.to_lowercase();
             Ok(ctx
@@ -710,16 +1557,31 @@ fn get_matching_candidates(
                 .map(|e| e.id)
                 .collect())
         }
-        Target::Selector(_) => {
-            // Can't resolve selector without browser - return all elements
-            Ok(ctx.elements().iter().map(|e| e.id).collect())
+        Target::RoleText { role, text } => {
+            Ok(
+                resolve_by_role_text(role, text, ctx, ResolutionStrategy::IncludeHidden)?
+                    .into_iter()
+                    .map(|(id, _)| id)
+                    .collect(),
+            )
         }
+        Target::Selector(selector) => Ok(match select_local(selector, ctx) {
+            Some(ids) => ids,
+            // Beyond what can be checked from the scan - leave it unfiltered
+            None => ctx.elements().iter().map(|e| e.id).collect(),
+        }),
         // For relational targets, get candidates from the inner target
         Target::Near { target, .. }
         | Target::Inside { target, .. }
         | Target::After { target, .. }
         | Target::Before { target, .. }
-        | Target::Contains { target, .. } => get_matching_candidates(target, ctx),
+        | Target::Contains { target, .. }
+        | Target::Between { target, .. } => get_matching_candidates(target, ctx),
+
+        Target::State { target, state } => Ok(get_matching_candidates(target, ctx)?
+            .into_iter()
+            .filter(|id| ctx.get_element(*id).is_some_and(|e| state.matches(e)))
+            .collect()),
 
         Target::Infer => Ok(vec![]),
     }
@@ -798,6 +1660,53 @@ fn select_match(
         return Err(ResolverError::NoMatch(target_desc.to_string()));
     }
 
+    let sorted = rank_matches(matches, strategy, ctx);
+    match strategy {
+        ResolutionStrategy::Unique | ResolutionStrategy::Interactive => {
+            match tied_for_first(&sorted, target_desc, ctx) {
+                Some(ambiguity) => Err(ambiguity),
+                None => Ok(Target::Id(sorted[0].0 as usize)),
+            }
+        }
+        // First, Best, and Prefer* strategies all return the highest scoring element
+        _ => Ok(Target::Id(sorted[0].0 as usize)),
+    }
+}
+
+/// Fail with `AmbiguousMatch` when the best candidates for `target` tie, as
+/// `Unique` would, but ranked the way `strategy` ranks them: a preference for
+/// clickable elements keeps a heading from tying with a button.
+///
+/// IDs and selectors are never ambiguous. A target that matches nothing
+/// fails with `NoMatch`.
+pub fn ensure_unique(
+    target: &Target,
+    ctx: &ResolverContext,
+    strategy: ResolutionStrategy,
+) -> Result<(), ResolverError> {
+    if matches!(target, Target::Id(_) | Target::Selector(_) | Target::Infer) {
+        return Ok(());
+    }
+    let matches = scored_matches(target, ctx, strategy)?;
+    let target_desc = describe_target(target);
+    if matches.is_empty() {
+        return Err(ResolverError::NoMatch(target_desc));
+    }
+    let sorted = rank_matches(&matches, strategy, Some(ctx));
+    match tied_for_first(&sorted, &target_desc, Some(ctx)) {
+        Some(ambiguity) => Err(ambiguity),
+        None => Ok(()),
+    }
+}
+
+/// Order candidates best first, as `select_match` picks them: the strategy's
+/// command preferences are added to the scores and ties are broken by
+/// [`tie_break_key`].
+fn rank_matches(
+    matches: &[(u32, i32)],
+    strategy: ResolutionStrategy,
+    ctx: Option<&ResolverContext>,
+) -> Vec<(u32, i32)> {
     // Apply command preferences if context is available and strategy requires it
     let mut sorted = matches.to_vec();
     if let Some(context) = ctx {
@@ -811,30 +1720,67 @@ fn select_match(
         }
     }
 
-    // Sort by score descending
-    sorted.sort_by(|a, b| b.1.cmp(&a.1));
-
-    match strategy {
-        ResolutionStrategy::Unique => {
-            if sorted.len() > 1 && sorted[0].1 == sorted[1].1 {
-                // Top scores are tied - ambiguous
-                let candidates: Vec<u32> = sorted
-                    .iter()
-                    .filter(|m| m.1 == sorted[0].1)
-                    .map(|m| m.0)
-                    .collect();
-                Err(ResolverError::AmbiguousMatch {
-                    target: target_desc.to_string(),
-                    count: candidates.len(),
-                    candidates,
-                })
-            } else {
-                Ok(Target::Id(sorted[0].0 as usize))
-            }
+    // Sort by score descending, breaking ties deterministically
+    match ctx {
+        Some(context) => {
+            sorted.sort_by_cached_key(|m| (std::cmp::Reverse(m.1), tie_break_key(context, m.0)))
         }
-        // First, Best, and Prefer* strategies all return the highest scoring element
-        _ => Ok(Target::Id(sorted[0].0 as usize)),
+        None => sorted.sort_by_key(|m| (std::cmp::Reverse(m.1), m.0)),
+    }
+    sorted
+}
+
+/// The `AmbiguousMatch` error for ranked candidates whose top scores are
+/// tied, listing every candidate with the top score.
+fn tied_for_first(
+    sorted: &[(u32, i32)],
+    target_desc: &str,
+    ctx: Option<&ResolverContext>,
+) -> Option<ResolverError> {
+    if sorted.len() < 2 || sorted[0].1 != sorted[1].1 {
+        return None;
     }
+    let candidates: Vec<AmbiguityCandidate> = sorted
+        .iter()
+        .filter(|m| m.1 == sorted[0].1)
+        .map(|m| {
+            ctx.and_then(|c| c.get_element(m.0))
+                .map(AmbiguityCandidate::from_element)
+                .unwrap_or_else(|| AmbiguityCandidate {
+                    id: m.0,
+                    text: String::new(),
+                    selector: String::new(),
+                })
+        })
+        .collect();
+    Some(ResolverError::AmbiguousMatch {
+        target: target_desc.to_string(),
+        count: candidates.len(),
+        candidates,
+    })
+}
+
+/// Ordering among equally scored candidates: elements with an accessible name
+/// (label or `aria-label`) first, then shallower elements, then the topmost,
+/// then the leftmost, then the lowest id. Positions are compared in whole
+/// pixels so sub-pixel layout differences between backends do not matter.
+fn tie_break_key(ctx: &ResolverContext, id: u32) -> (bool, usize, i64, i64, u32) {
+    let Some(elem) = ctx.get_element(id) else {
+        return (true, usize::MAX, i64::MAX, i64::MAX, id);
+    };
+    let named = |name: Option<&String>| name.is_some_and(|n| !n.trim().is_empty());
+    let has_name = named(elem.label.as_ref()) || named(elem.attributes.get("aria-label"));
+    // The xpath has one step per ancestor; without it depth is unknown.
+    let depth = elem.xpath.as_deref().map_or(usize::MAX, |x| {
+        x.split('/').filter(|s| !s.is_empty()).count()
+    });
+    (
+        !has_name,
+        depth,
+        elem.rect.y.round() as i64,
+        elem.rect.x.round() as i64,
+        id,
+    )
 }
 
 /// Minimum similarity score (0.0-1.0) required to include an element in suggestions.
@@ -937,6 +1883,9 @@ mod tests {
             attributes: HashMap::new(),
             state: ElementState::default(),
             children: vec![],
+            count: None,
+            tab_index: None,
+            focusable: false,
         }
     }
 
@@ -1074,6 +2023,9 @@ mod tests {
             attributes: HashMap::new(),
             state: ElementState::default(),
             children: vec![],
+            count: None,
+            tab_index: None,
+            focusable: false,
         }
     }
 
@@ -1206,6 +2158,164 @@ mod tests {
         assert!(matches!(result, Ok(Target::Id(2))));
     }
 
+    #[test]
+    fn test_resolve_text_match_modes() {
+        let ctx = make_context(vec![make_element(
+            1,
+            Some("Save and Close"),
+            None,
+            "button",
+        )]);
+        let text_match = |value: &str, mode| {
+            Target::TextMatch(TextMatch {
+                value: value.into(),
+                mode,
+            })
+        };
+
+        // The default accepts a containing match, exact mode does not
+        let result = resolve_target(
+            &Target::Text("Save".into()),
+            &ctx,
+            ResolutionStrategy::First,
+        );
+        assert!(matches!(result, Ok(Target::Id(1))));
+        let result = resolve_target(
+            &text_match("Save", TextMatchMode::Exact),
+            &ctx,
+            ResolutionStrategy::First,
+        );
+        assert!(matches!(result, Err(ResolverError::NoMatch(_))));
+        let result = resolve_target(
+            &text_match("save and close", TextMatchMode::Exact),
+            &ctx,
+            ResolutionStrategy::First,
+        );
+        assert!(matches!(result, Ok(Target::Id(1))));
+
+        // Prefix and suffix only accept their own end of the text
+        for (value, mode, found) in [
+            ("Save", TextMatchMode::Prefix, true),
+            ("Close", TextMatchMode::Prefix, false),
+            ("Close", TextMatchMode::Suffix, true),
+            ("Save", TextMatchMode::Suffix, false),
+            ("and", TextMatchMode::Prefix, false),
+        ] {
+            let result = resolve_target(&text_match(value, mode), &ctx, ResolutionStrategy::First);
+            assert_eq!(result.is_ok(), found, "{:?} {:?}", mode, value);
+        }
+    }
+
+    #[test]
+    fn test_resolve_exact_text_match_skips_fuzzy_fallback() {
+        let ctx = make_context(vec![make_element(1, Some("Sign-In"), None, "button")]);
+        let result = resolve_target(
+            &Target::TextMatch(TextMatch {
+                value: "Sign In".into(),
+                mode: TextMatchMode::Exact,
+            }),
+            &ctx,
+            ResolutionStrategy::First,
+        );
+        assert!(matches!(result, Err(ResolverError::NoMatch(_))));
+    }
+
+    #[test]
+    fn test_resolve_text_fuzzy_punctuation() {
+        let ctx = make_context(vec![
+            make_element(1, Some("Sign-In"), None, "button"),
+            make_element(2, Some("Register"), None, "button"),
+        ]);
+        let result = resolve_target(
+            &Target::Text("Sign In".into()),
+            &ctx,
+            ResolutionStrategy::First,
+        );
+        assert!(matches!(result, Ok(Target::Id(1))));
+
+        let ctx = make_context(vec![make_element(1, Some("SignIn"), None, "button")]);
+        let result = resolve_target(
+            &Target::Text("Sign In".into()),
+            &ctx,
+            ResolutionStrategy::First,
+        );
+        assert!(matches!(result, Ok(Target::Id(1))));
+    }
+
+    #[test]
+    fn test_resolve_text_fuzzy_rejects_different_words() {
+        let ctx = make_context(vec![make_element(1, Some("Cancel"), None, "button")]);
+        let result = resolve_target(
+            &Target::Text("Submit".into()),
+            &ctx,
+            ResolutionStrategy::First,
+        );
+        assert!(matches!(result, Err(ResolverError::NoMatch(_))));
+    }
+
+    #[test]
+    fn test_resolve_text_fuzzy_never_beats_contains() {
+        let ctx = make_context(vec![
+            make_element(1, Some("Sign-In"), None, "button"),
+            make_element(2, Some("Sign In with Google"), None, "button"),
+        ]);
+        let result = resolve_target(
+            &Target::Text("Sign In".into()),
+            &ctx,
+            ResolutionStrategy::First,
+        );
+        assert!(matches!(result, Ok(Target::Id(2))));
+    }
+
+    #[test]
+    fn test_resolve_test_id_outranks_text_match() {
+        let mut hooked = make_element(2, Some("Go"), None, "button");
+        hooked
+            .attributes
+            .insert("data-testid".into(), "submit".into());
+        let ctx = make_context(vec![
+            make_element(1, Some("Submit"), None, "button"),
+            hooked,
+        ]);
+
+        let result = resolve_target(
+            &Target::TestId("submit".into()),
+            &ctx,
+            ResolutionStrategy::Unique,
+        );
+        assert!(matches!(result, Ok(Target::Id(2))));
+
+        // A plain text target that happens to equal the hook still prefers it.
+        let result = resolve_target(
+            &Target::Text("submit".into()),
+            &ctx,
+            ResolutionStrategy::Unique,
+        );
+        assert!(matches!(result, Ok(Target::Id(2))));
+    }
+
+    #[test]
+    fn test_resolve_test_id_is_exact_across_attributes() {
+        let mut cy = make_element(1, Some("Checkout"), None, "button");
+        cy.attributes
+            .insert("data-cy".into(), "checkout-btn".into());
+        let ctx = make_context(vec![cy]);
+
+        let result = resolve_target(
+            &Target::TestId("checkout-btn".into()),
+            &ctx,
+            ResolutionStrategy::First,
+        );
+        assert!(matches!(result, Ok(Target::Id(1))));
+
+        let result = resolve_target(
+            &Target::TestId("checkout".into()),
+            &ctx,
+            ResolutionStrategy::First,
+        );
+        assert!(matches!(result, Err(ResolverError::NoMatch(_))));
+    }
+
     // ============================================================
     // Role Resolution Tests
     // ============================================================
@@ -1261,44 +2371,270 @@ mod tests {
             &ctx,
             ResolutionStrategy::First,
         );
-        assert!(matches!(result, Ok(Target::Id(1))));
+        assert!(matches!(result, Ok(Target::Id(1))));
+    }
+
+    #[test]
+    fn test_resolve_role_disabled_penalty() {
+        // Element 1 is disabled, Element 2 is not
+        let mut elem1 = make_element(1, None, Some("textbox"), "input");
+        elem1
+            .attributes
+            .insert("type".to_string(), "email".to_string());
+        elem1.state.disabled = true;
+
+        let mut elem2 = make_element(2, None, Some("textbox"), "input");
+        elem2
+            .attributes
+            .insert("type".to_string(), "email".to_string());
+        elem2.state.disabled = false;
+
+        let ctx = make_context(vec![elem1, elem2]);
+        let result = resolve_target(
+            &Target::Role("email".into()),
+            &ctx,
+            ResolutionStrategy::First,
+        );
+        // Should prefer enabled element (element 2) due to disabled penalty
+        assert!(matches!(result, Ok(Target::Id(2))));
+    }
+
+    #[test]
+    fn test_resolve_role_case_insensitive() {
+        let elem = make_element(1, Some("Click"), None, "button");
+        let ctx = make_context(vec![elem]);
+        let result = resolve_target(
+            &Target::Role("BUTTON".into()),
+            &ctx,
+            ResolutionStrategy::First,
+        );
+        assert!(matches!(result, Ok(Target::Id(1))));
+    }
+
+    #[test]
+    fn test_resolve_role_synonym_link_matches_anchor() {
+        let ctx = make_context(vec![
+            make_element(1, Some("Intro"), None, "p"),
+            make_element(2, Some("Docs"), None, "a"),
+        ]);
+        let result = resolve_target(
+            &Target::Role("link".into()),
+            &ctx,
+            ResolutionStrategy::First,
+        );
+        assert!(matches!(result, Ok(Target::Id(2))));
+    }
+
+    #[test]
+    fn test_resolve_role_synonym_combobox_matches_select() {
+        let ctx = make_context(vec![make_element(1, None, None, "select")]);
+        let result = resolve_target(
+            &Target::Role("combobox".into()),
+            &ctx,
+            ResolutionStrategy::First,
+        );
+        assert!(matches!(result, Ok(Target::Id(1))));
+    }
+
+    #[test]
+    fn test_resolve_role_synonym_textbox_skips_non_text_inputs() {
+        let mut checkbox = make_element(1, None, None, "input");
+        checkbox
+            .attributes
+            .insert("type".to_string(), "checkbox".to_string());
+        let untyped = make_element(2, None, None, "input");
+        let ctx = make_context(vec![checkbox, untyped]);
+
+        let result = resolve_by_role("textbox", &ctx, ResolutionStrategy::First).unwrap();
+        assert_eq!(result, vec![(2, ROLE_SYNONYM_SCORE)]);
+    }
+
+    #[test]
+    fn test_resolve_role_exact_aria_role_outranks_synonym() {
+        let anchor = make_element(1, Some("Home"), None, "a");
+        let mut div = make_element(2, Some("Home"), None, "div");
+        div.attributes
+            .insert("role".to_string(), "link".to_string());
+        let ctx = make_context(vec![anchor, div]);
+
+        let result = resolve_target(&Target::Role("link".into()), &ctx, ResolutionStrategy::Best);
+        assert!(matches!(result, Ok(Target::Id(2))));
+    }
+
+    #[test]
+    fn test_resolve_role_text_prefers_the_button_over_a_heading() {
+        // The heading matches "Submit" exactly and comes first in the document
+        let ctx = make_context(vec![
+            make_element(1, Some("Submit"), None, "h2"),
+            make_element(2, Some("Submit order"), None, "button"),
+        ]);
+
+        let text = resolve_target(
+            &Target::Text("Submit".into()),
+            &ctx,
+            ResolutionStrategy::Best,
+        );
+        assert!(matches!(text, Ok(Target::Id(1))));
+
+        let target = Target::RoleText {
+            role: "button".into(),
+            text: "Submit".into(),
+        };
+        let result = resolve_target(&target, &ctx, ResolutionStrategy::Unique);
+        assert!(matches!(result, Ok(Target::Id(2))));
+        // Scored by the sum: element type match plus contains-text match
+        assert_eq!(
+            resolve_by_role_text("button", "Submit", &ctx, ResolutionStrategy::First).unwrap(),
+            vec![(2, 80 + 50)]
+        );
+    }
+
+    #[test]
+    fn test_resolve_role_text_requires_both_to_match() {
+        let ctx = make_context(vec![
+            make_element(1, Some("Submit"), None, "h2"),
+            make_element(2, Some("Cancel"), None, "button"),
+        ]);
+        let target = Target::RoleText {
+            role: "button".into(),
+            text: "Submit".into(),
+        };
+        match resolve_target(&target, &ctx, ResolutionStrategy::First) {
+            Err(ResolverError::NoMatch(desc)) => assert_eq!(desc, "button \"Submit\""),
+            other => panic!("Expected no match, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_tied_candidates_prefer_an_accessible_name() {
+        let plain = make_element(1, Some("Save"), None, "button");
+        let mut labeled = make_element(2, Some("Save"), None, "button");
+        labeled
+            .attributes
+            .insert("aria-label".into(), "Save changes".into());
+        let ctx = make_context(vec![plain, labeled]);
+
+        let result = resolve_target(&Target::Text("Save".into()), &ctx, ResolutionStrategy::Best);
+        assert_eq!(result.unwrap(), Target::Id(2));
+    }
+
+    #[test]
+    fn test_tied_candidates_prefer_the_topmost_then_lowest_id() {
+        let lower = make_element_at(1, Some("Save"), None, "button", 10.0, 400.0, 80.0, 30.0);
+        let upper = make_element_at(2, Some("Save"), None, "button", 10.0, 120.0, 80.0, 30.0);
+        let target = Target::Text("Save".into());
+
+        // Whichever order the backend scanned them in, the upper one wins.
+        for elements in [
+            vec![lower.clone(), upper.clone()],
+            vec![upper.clone(), lower.clone()],
+        ] {
+            let ctx = make_context(elements);
+            for strategy in [ResolutionStrategy::First, ResolutionStrategy::Best] {
+                assert_eq!(
+                    resolve_target(&target, &ctx, strategy).unwrap(),
+                    Target::Id(2)
+                );
+            }
+        }
+
+        // Side by side, the leftmost; at the same spot, the lowest id.
+        let right = make_element_at(3, Some("Save"), None, "button", 300.0, 120.0, 80.0, 30.0);
+        let ctx = make_context(vec![right, upper.clone()]);
+        assert_eq!(
+            resolve_target(&target, &ctx, ResolutionStrategy::First).unwrap(),
+            Target::Id(2)
+        );
+        let mut twin = upper.clone();
+        twin.id = 7;
+        let ctx = make_context(vec![twin, upper]);
+        assert_eq!(
+            resolve_target(&target, &ctx, ResolutionStrategy::First).unwrap(),
+            Target::Id(2)
+        );
     }
 
     #[test]
-    fn test_resolve_role_disabled_penalty() {
-        // Element 1 is disabled, Element 2 is not
-        let mut elem1 = make_element(1, None, Some("textbox"), "input");
-        elem1
-            .attributes
-            .insert("type".to_string(), "email".to_string());
-        elem1.state.disabled = true;
-
-        let mut elem2 = make_element(2, None, Some("textbox"), "input");
-        elem2
-            .attributes
-            .insert("type".to_string(), "email".to_string());
-        elem2.state.disabled = false;
+    fn test_state_filter_picks_the_enabled_button() {
+        let mut disabled = make_element(1, Some("Submit"), None, "button");
+        disabled.state.disabled = true;
+        let enabled = make_element(2, Some("Submit"), None, "button");
+        let ctx = make_context(vec![disabled, enabled]);
+        let filtered = |state: StateFilter| Target::State {
+            target: Box::new(Target::Text("Submit".into())),
+            state,
+        };
 
-        let ctx = make_context(vec![elem1, elem2]);
         let result = resolve_target(
-            &Target::Role("email".into()),
+            &filtered(StateFilter {
+                enabled: Some(true),
+                ..Default::default()
+            }),
             &ctx,
             ResolutionStrategy::First,
         );
-        // Should prefer enabled element (element 2) due to disabled penalty
         assert!(matches!(result, Ok(Target::Id(2))));
-    }
 
-    #[test]
-    fn test_resolve_role_case_insensitive() {
-        let elem = make_element(1, Some("Click"), None, "button");
-        let ctx = make_context(vec![elem]);
         let result = resolve_target(
-            &Target::Role("BUTTON".into()),
+            &filtered(StateFilter {
+                enabled: Some(false),
+                ..Default::default()
+            }),
             &ctx,
             ResolutionStrategy::First,
         );
         assert!(matches!(result, Ok(Target::Id(1))));
+
+        match resolve_target(
+            &filtered(StateFilter {
+                checked: Some(true),
+                ..Default::default()
+            }),
+            &ctx,
+            ResolutionStrategy::First,
+        ) {
+            Err(ResolverError::NoMatch(desc)) => assert_eq!(desc, "Submit (checked)"),
+            other => panic!("Expected no match, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_state_filter_checks_an_id_target() {
+        let mut disabled = make_element(1, Some("Submit"), None, "button");
+        disabled.state.disabled = true;
+        let ctx = make_context(vec![disabled]);
+        let target = Target::State {
+            target: Box::new(Target::Id(1)),
+            state: StateFilter {
+                enabled: Some(true),
+                ..Default::default()
+            },
+        };
+        assert!(matches!(
+            resolve_target(&target, &ctx, ResolutionStrategy::First),
+            Err(ResolverError::NoMatch(_))
+        ));
+    }
+
+    #[test]
+    fn test_scoring_config_can_rank_aria_label_above_text() {
+        let button = make_element(1, Some("Search"), None, "button");
+        let mut input = make_element(2, None, None, "input");
+        input
+            .attributes
+            .insert("aria-label".to_string(), "Search".to_string());
+        let ctx = make_context(vec![button, input]);
+        let target = Target::Text("Search".into());
+
+        let result = resolve_target(&target, &ctx, ResolutionStrategy::Best);
+        assert!(matches!(result, Ok(Target::Id(1))));
+
+        let ctx = ctx.with_scoring(ScoringConfig {
+            aria_label_exact: 110,
+            ..ScoringConfig::default()
+        });
+        let result = resolve_target(&target, &ctx, ResolutionStrategy::Best);
+        assert!(matches!(result, Ok(Target::Id(2))));
     }
 
     // ============================================================
@@ -1337,6 +2673,27 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_ambiguous_match_lists_candidate_selectors() {
+        let ctx = make_context(vec![
+            make_element(1, Some("Submit"), None, "button"),
+            make_element(2, Some("Submit"), None, "button"),
+        ]);
+        let err = resolve_target(
+            &Target::Text("Submit".into()),
+            &ctx,
+            ResolutionStrategy::Unique,
+        )
+        .unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            "Ambiguous target \"Submit\" matches 2 elements:\n\
+             \x20 1) [1] \"Submit\" #elem-1\n\
+             \x20 2) [2] \"Submit\" #elem-2"
+        );
+    }
+
     #[test]
     fn test_strategy_unique_different_scores_success() {
         // Element 1 has exact match (100 pts), Element 2 has contains match (50 pts)
@@ -1367,6 +2724,49 @@ mod tests {
         assert!(matches!(result, Ok(Target::Id(2))));
     }
 
+    #[test]
+    fn test_strategy_first_picks_the_topmost_match() {
+        // IDs persist across scans, so the topmost match need not have the
+        // lowest id
+        let row = |id, y| make_element_at(id, Some("Delete"), None, "button", 10.0, y, 80.0, 30.0);
+        let ctx = make_context(vec![row(7, 40.0), row(3, 80.0), row(5, 120.0)]);
+        let target = Target::Text("Delete".into());
+
+        let result = resolve_target(&target, &ctx, ResolutionStrategy::First);
+        assert_eq!(result.unwrap(), Target::Id(7));
+        assert_eq!(
+            resolve_all(&target, &ctx).unwrap(),
+            vec![Target::Id(7), Target::Id(3), Target::Id(5)]
+        );
+    }
+
+    #[test]
+    fn test_ensure_unique_ranks_with_the_command_preference() {
+        let heading = make_element_at(1, Some("Submit"), None, "h2", 10.0, 10.0, 200.0, 30.0);
+        let button = make_element_at(2, Some("Submit"), None, "button", 10.0, 80.0, 80.0, 30.0);
+        let ctx = make_context(vec![heading, button]);
+        let target = Target::Text("Submit".into());
+
+        assert!(ensure_unique(&target, &ctx, ResolutionStrategy::PreferClickable).is_ok());
+        assert!(matches!(
+            ensure_unique(&target, &ctx, ResolutionStrategy::Unique),
+            Err(ResolverError::AmbiguousMatch { count: 2, .. })
+        ));
+
+        let save = |id, y| make_element_at(id, Some("Save"), None, "button", 10.0, y, 80.0, 30.0);
+        let ctx = make_context(vec![save(3, 0.0), save(7, 400.0)]);
+        let Err(ResolverError::AmbiguousMatch { candidates, .. }) = ensure_unique(
+            &Target::Text("Save".into()),
+            &ctx,
+            ResolutionStrategy::PreferClickable,
+        ) else {
+            panic!("two identical buttons should be ambiguous");
+        };
+        let ids: Vec<u32> = candidates.iter().map(|c| c.id).collect();
+        assert_eq!(ids, vec![3, 7]);
+        assert!(ensure_unique(&Target::Id(3), &ctx, ResolutionStrategy::PreferClickable).is_ok());
+    }
+
     // ============================================================
     // Relational Resolution: resolve_near
     // ============================================================
@@ -1537,6 +2937,127 @@ mod tests {
         assert!(matches!(result, Ok(Target::Id(3))));
     }
 
+    // ============================================================
+    // Relational Resolution: selector anchors
+    // ============================================================
+
+    fn with_class(mut elem: Element, class: &str) -> Element {
+        elem.attributes.insert("class".into(), class.into());
+        elem
+    }
+
+    #[test]
+    fn test_resolve_near_css_anchor() {
+        // The toolbar's Save comes second in the document; the footer's is first
+        let footer_save = make_element_at(1, Some("Save"), None, "button", 0.0, 800.0, 100.0, 30.0);
+        let toolbar = with_class(
+            make_element_at(2, None, None, "div", 0.0, 0.0, 600.0, 40.0),
+            "toolbar sticky",
+        );
+        let toolbar_save = make_element_at(3, Some("Save"), None, "button", 10.0, 5.0, 100.0, 30.0);
+        let ctx = make_context(vec![footer_save, toolbar, toolbar_save]);
+
+        let result = resolve_target(
+            &Target::Near {
+                target: Box::new(Target::Text("Save".into())),
+                anchor: Box::new(Target::Selector(".toolbar".into())),
+            },
+            &ctx,
+            ResolutionStrategy::First,
+        );
+        assert_eq!(result.unwrap(), Target::Id(3));
+
+        let result = resolve_target(
+            &Target::Near {
+                target: Box::new(Target::Text("Save".into())),
+                anchor: Box::new(Target::Selector(".sidebar".into())),
+            },
+            &ctx,
+            ResolutionStrategy::First,
+        );
+        assert!(matches!(result, Err(ResolverError::NoMatch(_))));
+    }
+
+    #[test]
+    fn test_resolve_inside_css_container() {
+        let mut checkout = make_element_at(1, None, None, "form", 0.0, 0.0, 500.0, 200.0);
+        checkout.attributes.insert("id".into(), "checkout".into());
+        let newsletter = make_element_at(2, None, None, "form", 0.0, 300.0, 500.0, 200.0);
+        let pay = make_element_at(3, Some("Submit"), None, "button", 20.0, 100.0, 100.0, 30.0);
+        let subscribe =
+            make_element_at(4, Some("Submit"), None, "button", 20.0, 400.0, 100.0, 30.0);
+        let ctx = make_context(vec![checkout, newsletter, pay, subscribe]);
+
+        let result = resolve_all(
+            &Target::Inside {
+                target: Box::new(Target::Text("Submit".into())),
+                container: Box::new(Target::Selector("form#checkout".into())),
+            },
+            &ctx,
+        );
+        assert_eq!(result.unwrap(), vec![Target::Id(3)]);
+    }
+
+    #[test]
+    fn test_css_candidates_narrow_a_relational_target() {
+        let label = make_element_at(1, Some("Coupon"), None, "label", 0.0, 0.0, 100.0, 30.0);
+        let nearest = make_element_at(2, None, None, "button", 110.0, 0.0, 100.0, 30.0);
+        let mut apply = make_element_at(3, None, None, "button", 300.0, 0.0, 100.0, 30.0);
+        apply.attributes.insert("name".into(), "apply".into());
+        let ctx = make_context(vec![label, nearest, apply]);
+        let near = |selector: &str| Target::Near {
+            target: Box::new(Target::Selector(selector.into())),
+            anchor: Box::new(Target::Text("Coupon".into())),
+        };
+
+        let result = resolve_target(
+            &near("button[name=\"apply\"]"),
+            &ctx,
+            ResolutionStrategy::First,
+        );
+        assert_eq!(result.unwrap(), Target::Id(3));
+
+        // Beyond the local matcher, every element stays a candidate
+        let result = resolve_target(&near("div > button"), &ctx, ResolutionStrategy::First);
+        assert_eq!(result.unwrap(), Target::Id(1));
+    }
+
+    #[test]
+    fn test_local_selector_support() {
+        let mut save = make_element(1, Some("Save"), None, "button");
+        save.attributes
+            .insert("class".into(), "btn btn-primary".into());
+        save.attributes.insert("data-action".into(), "save".into());
+        let ctx = make_context(vec![save, make_element(2, Some("Cancel"), None, "button")]);
+
+        for (selector, expected) in [
+            ("button", Some(vec![1, 2])),
+            ("BUTTON.btn-primary", Some(vec![1])),
+            (".btn.btn-primary", Some(vec![1])),
+            ("[data-action='save']", Some(vec![1])),
+            ("*[data-action]", Some(vec![1])),
+            ("a, .btn", Some(vec![1])),
+            (".btn-", Some(vec![])),
+            ("#elem-2", Some(vec![])),
+            ("button:first-child", None),
+            ("div button", None),
+            ("[type=submit]", None),
+            ("[class^=btn]", None),
+        ] {
+            assert_eq!(select_local(selector, &ctx), expected, "{}", selector);
+        }
+
+        // The scanner's generated selector identifies its element even when
+        // it is beyond the local matcher
+        let mut nested = make_element(3, Some("Go"), None, "button");
+        nested.selector = "main > button:nth-of-type(2)".into();
+        let ctx = make_context(vec![nested]);
+        assert_eq!(
+            select_local("main > button:nth-of-type(2)", &ctx),
+            Some(vec![3])
+        );
+    }
+
     // ============================================================
     // Relational Resolution: resolve_after
     // ============================================================
@@ -1764,6 +3285,276 @@ mod tests {
         assert!(matches!(result, Ok(Target::Id(1))) || matches!(result, Ok(Target::Id(2))));
     }
 
+    // ============================================================
+    // resolve_all Tests
+    // ============================================================
+
+    #[test]
+    fn test_resolve_all_orders_by_score_then_document_order() {
+        let ctx = make_context(vec![
+            make_element(1, Some("Delete row"), None, "button"),
+            make_element(2, Some("Delete"), None, "button"),
+            make_element(3, Some("Delete all"), None, "button"),
+            make_element(4, Some("Keep"), None, "button"),
+            make_element(5, Some("Delete"), None, "button"),
+        ]);
+        let result = resolve_all(&Target::Text("Delete".into()), &ctx).unwrap();
+        assert_eq!(
+            result,
+            vec![Target::Id(2), Target::Id(5), Target::Id(1), Target::Id(3)]
+        );
+    }
+
+    #[test]
+    fn test_resolve_all_relational_applies_filter() {
+        let container = make_element_at(1, Some("Cart"), None, "div", 0.0, 0.0, 400.0, 200.0);
+        let inside_a = make_element_at(2, Some("Remove"), None, "button", 10.0, 10.0, 80.0, 30.0);
+        let inside_b = make_element_at(3, Some("Remove"), None, "button", 10.0, 60.0, 80.0, 30.0);
+        let outside = make_element_at(4, Some("Remove"), None, "button", 10.0, 300.0, 80.0, 30.0);
+        let ctx = make_context(vec![container, inside_a, inside_b, outside]);
+
+        let result = resolve_all(
+            &Target::Inside {
+                target: Box::new(Target::Text("Remove".into())),
+                container: Box::new(Target::Text("Cart".into())),
+            },
+            &ctx,
+        )
+        .unwrap();
+        assert_eq!(result, vec![Target::Id(2), Target::Id(3)]);
+    }
+
+    #[test]
+    fn test_resolve_all_no_match() {
+        let ctx = make_context(vec![make_element(1, Some("Keep"), None, "button")]);
+        let result = resolve_all(&Target::Text("Delete".into()), &ctx);
+        assert!(matches!(result, Err(ResolverError::NoMatch(_))));
+    }
+
+    #[test]
+    fn test_resolve_all_id_and_selector_passthrough() {
+        let ctx = make_context(vec![make_element(1, Some("Keep"), None, "button")]);
+        assert_eq!(
+            resolve_all(&Target::Id(1), &ctx).unwrap(),
+            vec![Target::Id(1)]
+        );
+        assert!(resolve_all(&Target::Id(9), &ctx).is_err());
+
+        let sel = Target::Selector(".row".into());
+        assert_eq!(resolve_all(&sel, &ctx).unwrap(), vec![sel]);
+    }
+
+    // ============================================================
+    // Visibility Filtering Tests
+    // ============================================================
+
+    fn hidden_and_visible_save() -> Vec<Element> {
+        let mut hidden = make_element(1, Some("Save"), None, "button");
+        hidden.state.hidden = true;
+        let mut collapsed = make_element(2, Some("Save"), None, "button");
+        collapsed.rect.width = 0.0;
+        let visible = make_element(3, Some("Save"), None, "button");
+        vec![hidden, collapsed, visible]
+    }
+
+    #[test]
+    fn test_visible_duplicate_wins_over_hidden() {
+        let ctx = make_context(hidden_and_visible_save());
+        let result = resolve_target(
+            &Target::Text("Save".into()),
+            &ctx,
+            ResolutionStrategy::Unique,
+        );
+        assert!(matches!(result, Ok(Target::Id(3))));
+
+        let result = resolve_target(
+            &Target::Role("button".into()),
+            &ctx,
+            ResolutionStrategy::Unique,
+        );
+        assert!(matches!(result, Ok(Target::Id(3))));
+    }
+
+    #[test]
+    fn test_only_hidden_matches_is_no_match() {
+        let mut elements = hidden_and_visible_save();
+        elements.pop();
+        let ctx = make_context(elements);
+        let result = resolve_target(
+            &Target::Text("Save".into()),
+            &ctx,
+            ResolutionStrategy::First,
+        );
+        assert!(matches!(result, Err(ResolverError::NoMatch(_))));
+    }
+
+    #[test]
+    fn test_include_hidden_strategy_opts_out() {
+        let ctx = make_context(hidden_and_visible_save());
+        let result = resolve_target(
+            &Target::Text("Save".into()),
+            &ctx,
+            ResolutionStrategy::IncludeHidden,
+        );
+        assert!(matches!(result, Ok(Target::Id(1))));
+    }
+
+    #[test]
+    fn test_hidden_scan_context_opts_out() {
+        let empty = make_context(vec![]);
+        assert!(!empty.includes_hidden());
+        assert!(empty.with_hidden(true).includes_hidden());
+
+        let ctx = make_context(hidden_and_visible_save()).with_hidden(true);
+        let result = resolve_target(
+            &Target::Text("Save".into()),
+            &ctx,
+            ResolutionStrategy::Unique,
+        );
+        assert!(matches!(
+            result,
+            Err(ResolverError::AmbiguousMatch { count: 3, .. })
+        ));
+    }
+
+    #[test]
+    fn test_context_reads_include_hidden_from_scan_settings() {
+        let scan = ScanResult {
+            page: PageInfo {
+                url: "https://example.com".to_string(),
+                title: "Test".to_string(),
+                viewport: ViewportInfo::default(),
+                scroll: ScrollInfo::default(),
+                ready_state: None,
+            },
+            elements: hidden_and_visible_save(),
+            stats: ScanStats {
+                total: 3,
+                scanned: 3,
+                iframes: None,
+            },
+            patterns: None,
+            changes: None,
+            available_intents: None,
+            full_mode: false,
+            settings_applied: Some(serde_json::json!({ "include_hidden": true })),
+            timing: None,
+        };
+        assert!(ResolverContext::new(&scan).includes_hidden());
+    }
+
+    #[test]
+    fn test_context_goes_stale_on_url_change_or_age() {
+        let ctx = make_context(vec![make_element(1, Some("Next"), None, "button")]);
+        assert!(!ctx.is_stale("https://example.com"));
+        // An unknown current page is no reason to re-scan.
+        assert!(!ctx.is_stale(""));
+        assert!(matches!(
+            ctx.ensure_fresh("https://example.com/page/2"),
+            Err(ResolverError::StaleContext)
+        ));
+
+        let scanned_at = Instant::now() - Duration::from_secs(10);
+        let aged = ctx.clone().with_scanned_at(scanned_at);
+        assert!(!aged.is_stale("https://example.com"));
+        assert!(
+            aged.clone()
+                .with_max_age(Duration::from_secs(5))
+                .is_stale("https://example.com")
+        );
+        assert!(
+            !aged
+                .with_max_age(Duration::from_secs(60))
+                .is_stale("https://example.com")
+        );
+    }
+
+    // ============================================================
+    // Between Resolution Tests
+    // ============================================================
+
+    fn stacked_form() -> ResolverContext {
+        // Three stacked inputs with labels interleaved:
+        // input(1) / "First name"(2) / input(3) / "Last name"(4) / input(5)
+        make_context(vec![
+            make_element_at(1, None, None, "input", 0.0, 0.0, 200.0, 30.0),
+            make_element_at(2, Some("First name"), None, "label", 0.0, 50.0, 100.0, 20.0),
+            make_element_at(3, None, None, "input", 0.0, 80.0, 200.0, 30.0),
+            make_element_at(4, Some("Last name"), None, "label", 0.0, 130.0, 100.0, 20.0),
+            make_element_at(5, None, None, "input", 0.0, 160.0, 200.0, 30.0),
+        ])
+    }
+
+    fn between(target: Target, start: &str, end: &str) -> Target {
+        Target::Between {
+            target: Box::new(target),
+            start_anchor: Box::new(Target::Text(start.into())),
+            end_anchor: Box::new(Target::Text(end.into())),
+        }
+    }
+
+    #[test]
+    fn test_resolve_between_vertical_band() {
+        let ctx = stacked_form();
+        let target = between(Target::Role("input".into()), "First name", "Last name");
+        let result = resolve_target(&target, &ctx, ResolutionStrategy::Unique);
+        assert!(matches!(result, Ok(Target::Id(3))));
+    }
+
+    #[test]
+    fn test_resolve_between_anchor_order_irrelevant() {
+        let ctx = stacked_form();
+        let target = between(Target::Role("input".into()), "Last name", "First name");
+        let result = resolve_target(&target, &ctx, ResolutionStrategy::First);
+        assert!(matches!(result, Ok(Target::Id(3))));
+    }
+
+    #[test]
+    fn test_resolve_between_horizontal_band() {
+        // Anchors on the same row: the band runs left-to-right between them
+        let ctx = make_context(vec![
+            make_element_at(1, Some("Prev"), None, "button", 0.0, 0.0, 50.0, 30.0),
+            make_element_at(2, Some("Page"), None, "a", 70.0, 0.0, 30.0, 30.0),
+            make_element_at(3, Some("Next"), None, "button", 120.0, 0.0, 50.0, 30.0),
+            make_element_at(4, Some("Page"), None, "a", 200.0, 0.0, 30.0, 30.0),
+        ]);
+        let target = between(Target::Text("Page".into()), "Prev", "Next");
+        let result = resolve_target(&target, &ctx, ResolutionStrategy::Unique);
+        assert!(matches!(result, Ok(Target::Id(2))));
+    }
+
+    #[test]
+    fn test_resolve_between_respects_unique_strategy() {
+        let ctx = make_context(vec![
+            make_element_at(1, Some("Start"), None, "label", 0.0, 0.0, 100.0, 20.0),
+            make_element_at(2, None, None, "input", 0.0, 30.0, 200.0, 30.0),
+            make_element_at(3, None, None, "input", 0.0, 70.0, 200.0, 30.0),
+            make_element_at(4, Some("End"), None, "label", 0.0, 120.0, 100.0, 20.0),
+        ]);
+        let target = between(Target::Role("input".into()), "Start", "End");
+
+        let result = resolve_target(&target, &ctx, ResolutionStrategy::Unique);
+        assert!(matches!(
+            result,
+            Err(ResolverError::AmbiguousMatch { count: 2, .. })
+        ));
+
+        let result = resolve_target(&target, &ctx, ResolutionStrategy::First);
+        assert!(matches!(result, Ok(Target::Id(2))));
+    }
+
+    #[test]
+    fn test_resolve_between_empty_band() {
+        let ctx = make_context(vec![
+            make_element_at(1, Some("Start"), None, "label", 0.0, 0.0, 100.0, 20.0),
+            make_element_at(2, Some("End"), None, "label", 0.0, 30.0, 100.0, 20.0),
+            make_element_at(3, None, None, "input", 0.0, 60.0, 200.0, 30.0),
+        ]);
+        let target = between(Target::Role("input".into()), "Start", "End");
+        let result = resolve_target(&target, &ctx, ResolutionStrategy::First);
+        assert!(matches!(result, Err(ResolverError::NoMatch(_))));
+    }
+
     // ============================================================
     // Edge Cases
     // ============================================================
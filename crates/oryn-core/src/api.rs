@@ -104,6 +104,7 @@ fn resolve_command_targets(cmd: &Command, scan: &ScanResult) -> Result<Command,
             resolver::Target::Id(id) => Ok(Target {
                 atomic: TargetAtomic::Id(id),
                 relation: None,
+                state: None,
             }),
             // If not resolved to ID, return original target (for selectors, etc.)
             _ => Ok(target.clone()),
@@ -125,10 +126,11 @@ fn resolve_command_targets(cmd: &Command, scan: &ScanResult) -> Result<Command,
         })),
         Command::Select(c) => Ok(Command::Select(crate::ast::SelectCmd {
             target: resolve_target(&c.target)?,
-            value: c.value.clone(),
+            ..c.clone()
         })),
         Command::Hover(c) => Ok(Command::Hover(crate::ast::HoverCmd {
             target: resolve_target(&c.target)?,
+            ..c.clone()
         })),
         Command::Focus(c) => Ok(Command::Focus(crate::ast::FocusCmd {
             target: resolve_target(&c.target)?,
@@ -139,6 +141,7 @@ fn resolve_command_targets(cmd: &Command, scan: &ScanResult) -> Result<Command,
         Command::Text(c) => Ok(Command::Text(crate::ast::TextCmd {
             target: c.target.as_ref().map(resolve_target).transpose()?,
             selector: c.selector.clone(),
+            mode: c.mode,
         })),
         Command::Uncheck(c) => Ok(Command::Uncheck(crate::ast::UncheckCmd {
             target: resolve_target(&c.target)?,
@@ -1,3 +1,4 @@
+pub use oryn_common::resolver::{StateFilter, TextMatch, TextMatchMode};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -27,6 +28,7 @@ pub enum Command {
     Title,
     Screenshot(ScreenshotCmd),
     Box(BoxCmd),
+    Resolve(ResolveCmd),
 
     // Actions
     Click(ClickCmd),
@@ -46,6 +48,7 @@ pub enum Command {
 
     // Wait
     Wait(WaitCmd),
+    Assert(AssertCmd),
 
     // Extract
     Extract(ExtractCmd),
@@ -57,6 +60,7 @@ pub enum Command {
     Session(SessionMgmtCmd),
     State(StateCmd),
     Headers(HeadersCmd),
+    Reset(ResetCmd),
 
     // Tabs
     Tabs,
@@ -107,8 +111,30 @@ pub enum Command {
     // Utility
     Pdf(PdfCmd),
     Learn(LearnCmd),
+    Eval(EvalCmd),
+    Set(SetCmd),
     Exit,
     Help(HelpCmd),
+    Repeat(RepeatCmd),
+}
+
+impl Command {
+    /// The element target of commands that act on a single element, for
+    /// rewriting it before resolution.
+    pub fn target_mut(&mut self) -> Option<&mut Target> {
+        match self {
+            Command::Click(cmd) => Some(&mut cmd.target),
+            Command::Type(cmd) => Some(&mut cmd.target),
+            Command::Check(cmd) => Some(&mut cmd.target),
+            Command::Uncheck(cmd) => Some(&mut cmd.target),
+            Command::Clear(cmd) => Some(&mut cmd.target),
+            Command::Focus(cmd) => Some(&mut cmd.target),
+            Command::Hover(cmd) => Some(&mut cmd.target),
+            Command::Select(cmd) => Some(&mut cmd.target),
+            Command::Submit(cmd) => cmd.target.as_mut(),
+            _ => None,
+        }
+    }
 }
 
 // --- Navigation ---
@@ -117,7 +143,14 @@ pub enum Command {
 pub struct GotoCmd {
     pub url: String,
     pub headers: Option<String>,
+    pub referer: Option<String>,
+    /// Condition to wait for after the page loads, bounded by `timeout`.
+    pub until: Option<WaitCondition>,
     pub timeout: Option<String>,
+    /// Push the URL onto the history of the current page and fire `popstate`
+    /// so a single-page app routes to it, without a document load.
+    #[serde(default)]
+    pub spa: bool,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -127,7 +160,7 @@ pub struct RefreshCmd {
 
 // --- Observation ---
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct ObserveCmd {
     pub full: bool,
     pub minimal: bool,
@@ -135,6 +168,21 @@ pub struct ObserveCmd {
     pub hidden: bool,
     pub positions: bool,
     pub diff: bool,
+    /// Report repeated identical elements individually instead of collapsed.
+    pub no_dedup: bool,
+    /// Print elements nested under their parents instead of as a flat list.
+    pub tree: bool,
+    /// Levels of the tree to print; all of them when unset.
+    pub depth: Option<usize>,
+    /// List only the elements Tab reaches, in the order it reaches them.
+    #[serde(default)]
+    pub focus_order: bool,
+    /// Keep re-scanning and print only what changed, until interrupted.
+    #[serde(default)]
+    pub monitor: bool,
+    /// Time between monitor scans, as written (`1000`, `2s`).
+    #[serde(default)]
+    pub interval: Option<String>,
     pub near: Option<String>,
     pub timeout: Option<String>,
 }
@@ -142,12 +190,39 @@ pub struct ObserveCmd {
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct HtmlCmd {
     pub selector: Option<String>,
+    /// Return the element's contents (`innerHTML`) rather than the element
+    /// itself (`outerHTML`).
+    #[serde(default)]
+    pub inner: bool,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct TextCmd {
     pub selector: Option<String>,
     pub target: Option<Target>,
+    pub mode: TextMode,
+}
+
+/// Whitespace handling applied to text returned by `text`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TextMode {
+    /// Return the text exactly as captured by the scanner.
+    Raw,
+    /// Strip leading and trailing whitespace only.
+    Trim,
+    /// Trim and collapse internal whitespace runs into single spaces.
+    #[default]
+    Collapse,
+}
+
+impl TextMode {
+    pub fn apply(&self, text: &str) -> String {
+        match self {
+            TextMode::Raw => text.to_string(),
+            TextMode::Trim => text.trim().to_string(),
+            TextMode::Collapse => oryn_common::resolver::collapse_whitespace(text),
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -155,7 +230,18 @@ pub struct ScreenshotCmd {
     pub output: Option<String>,
     pub format: Option<String>,
     pub fullpage: bool,
+    /// Wait for animations and DOM mutations to settle before capturing.
+    pub wait_stable: bool,
     pub target: Option<Target>,
+    /// Page region to capture, as `(x, y, width, height)` in CSS pixels.
+    #[serde(default)]
+    pub clip: Option<(f32, f32, f32, f32)>,
+    /// Capture only the target element's box.
+    #[serde(default)]
+    pub element_only: bool,
+    /// Margin in CSS pixels added around the element box.
+    #[serde(default)]
+    pub padding: Option<f32>,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -163,11 +249,19 @@ pub struct BoxCmd {
     pub target: Target,
 }
 
+/// Lists the elements a target would resolve to, without acting on any.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ResolveCmd {
+    pub target: Target,
+}
+
 // --- Actions ---
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ClickCmd {
     pub target: Target,
+    /// Click every element the target matches, top to bottom, instead of the best one.
+    pub all: bool,
     pub double: bool,
     pub right: bool,
     pub middle: bool,
@@ -176,6 +270,9 @@ pub struct ClickCmd {
     pub shift: bool,
     pub alt: bool,
     pub timeout: Option<String>,
+    /// Milliseconds to let the page settle afterwards before re-scanning.
+    #[serde(default)]
+    pub wait_after: Option<u64>,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -186,7 +283,16 @@ pub struct TypeCmd {
     pub enter: bool,
     pub delay: Option<f64>,
     pub clear: bool,
+    /// Press Tab after typing, moving focus on and firing the field's blur.
+    #[serde(default)]
+    pub then_tab: bool,
     pub timeout: Option<String>,
+    /// Milliseconds to let the page settle afterwards before re-scanning.
+    #[serde(default)]
+    pub wait_after: Option<u64>,
+    /// Keep the text out of outputs, dry runs and logs (`--secret`).
+    #[serde(default)]
+    pub secret: bool,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -213,6 +319,10 @@ pub struct KeyupCmd {
 pub struct SelectCmd {
     pub target: Target,
     pub value: String, // Treating number as string for simplicity often works, or use specific types
+    /// Drive a div-based dropdown (open, filter, click the option) instead of
+    /// a native `<select>`.
+    #[serde(default)]
+    pub custom: bool,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -228,6 +338,10 @@ pub struct UncheckCmd {
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct HoverCmd {
     pub target: Target,
+    /// How long to hold the hover before the next command runs, as written
+    /// (`500`, `2s`). The pointer stays put until the next action moves it.
+    #[serde(default)]
+    pub duration: Option<String>,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -240,6 +354,8 @@ pub struct ScrollCmd {
     pub direction: Option<String>, // up, down, left, right
     pub amount: Option<f64>,
     pub page: bool,
+    /// Animate the scroll; the command returns once the position settles.
+    pub smooth: bool,
     pub timeout: Option<String>,
     pub target: Option<Target>,
 }
@@ -258,18 +374,112 @@ pub enum WaitCondition {
     Navigation,
     Ready,
     Visible(Target),
+    /// Visible, for whichever of the targets shows first.
+    AnyOf(Vec<Target>),
+    /// `all (...)`: every condition holds in the same check.
+    All(Vec<WaitCondition>),
+    /// `any (...)`: at least one condition holds.
+    Any(Vec<WaitCondition>),
     Hidden(Target),
     Exists(String),
     Gone(String),
     Url(String),
     Until(String),
-    Items { selector: String, count: f64 },
+    /// `items "<selector>" [op] <count>`: the number of elements matching
+    /// the selector compares true against the count.
+    Items(ItemsCond),
+    /// `until text of <target> == "..."`: re-scan until the element's text
+    /// compares true against `expected`.
+    TextOf {
+        target: Target,
+        comparison: TextComparison,
+        expected: String,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ItemsCond {
+    pub selector: String,
+    pub op: CountComparison,
+    pub count: u64,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CountComparison {
+    /// `>=`, and what a bare count means.
+    #[default]
+    AtLeast,
+    /// `<=`
+    AtMost,
+    /// `>`
+    MoreThan,
+    /// `<`
+    FewerThan,
+    /// `==`
+    Equals,
+}
+
+impl CountComparison {
+    pub fn matches(self, actual: u64, expected: u64) -> bool {
+        match self {
+            CountComparison::AtLeast => actual >= expected,
+            CountComparison::AtMost => actual <= expected,
+            CountComparison::MoreThan => actual > expected,
+            CountComparison::FewerThan => actual < expected,
+            CountComparison::Equals => actual == expected,
+        }
+    }
+
+    /// The operator as written in OIL.
+    pub fn symbol(self) -> &'static str {
+        match self {
+            CountComparison::AtLeast => ">=",
+            CountComparison::AtMost => "<=",
+            CountComparison::MoreThan => ">",
+            CountComparison::FewerThan => "<",
+            CountComparison::Equals => "==",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TextComparison {
+    Equals,
+    Contains,
+}
+
+impl TextComparison {
+    pub fn matches(self, actual: &str, expected: &str) -> bool {
+        match self {
+            TextComparison::Equals => actual.trim() == expected,
+            TextComparison::Contains => actual.contains(expected),
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct WaitCmd {
     pub condition: WaitCondition,
     pub timeout: Option<String>,
+    /// How long `wait idle` needs the network to stay quiet.
+    pub quiet: Option<String>,
+}
+
+/// A check against the current page that fails the script when it does not
+/// hold.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AssertCmd {
+    pub condition: AssertCondition,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum AssertCondition {
+    /// The target resolves to an element of a fresh scan.
+    Visible(Target),
+    /// The text of the element the target resolves to contains `expected`.
+    TextContains { target: Target, expected: String },
+    /// The page URL matches the regex.
+    UrlMatches(String),
 }
 
 // --- Extract ---
@@ -289,6 +499,9 @@ pub struct ExtractCmd {
     pub what: ExtractWhat,
     pub selector: Option<String>,
     pub format: Option<String>,
+    /// Keep only links on the page's own origin (`extract links`).
+    #[serde(default)]
+    pub same_origin: bool,
 }
 
 // --- Session ---
@@ -296,15 +509,33 @@ pub struct ExtractCmd {
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct CookiesCmd {
     pub action: CookiesAction,
+    /// Print `get` results as JSON with every cookie attribute.
+    #[serde(default)]
+    pub json: bool,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum CookiesAction {
     List,
     Get(String),
-    Set { name: String, value: String },
+    /// Every cookie the browser holds, across domains.
+    GetAll,
+    Set {
+        name: String,
+        value: String,
+    },
     Delete(String),
     Clear,
+    /// Write every cookie to a file, as JSON or Netscape `cookies.txt`.
+    Export {
+        path: String,
+        format: Option<String>,
+    },
+    /// Set every cookie read from a JSON or Netscape cookie file.
+    Import {
+        path: String,
+        format: Option<String>,
+    },
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -323,6 +554,26 @@ pub enum StorageAction {
     Clear,
 }
 
+/// State categories cleared by `reset`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ResetCmd {
+    pub cookies: bool,
+    pub storage: bool,
+    pub requests: bool,
+    pub console: bool,
+}
+
+impl ResetCmd {
+    pub fn all() -> Self {
+        Self {
+            cookies: true,
+            storage: true,
+            requests: true,
+            console: true,
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct SessionMgmtCmd {
     pub action: Option<SessionAction>,
@@ -333,6 +584,10 @@ pub enum SessionAction {
     New { name: String, mode: Option<String> },
     Close(String),
     Switch(String),
+    Save(String),
+    Load(String),
+    List,
+    Delete(String),
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -362,11 +617,18 @@ pub struct HeadersCmd {
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum HeadersAction {
+    /// `headers set [<domain>] <json>`
     Set {
         domain: Option<String>,
         json: String,
-    }, // Argument might be (domain, string) or just string
+    },
+    /// `headers set <name> <value>`
+    SetHeader {
+        name: String,
+        value: String,
+    },
     Clear(Option<String>),
+    List,
     Show(String), // The `domain_name` case
 }
 
@@ -427,6 +689,8 @@ pub struct PackActionCmd {
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct IntentsCmd {
     pub session: bool,
+    /// Emit the full catalog as JSON for tooling.
+    pub json: bool,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -442,31 +706,64 @@ pub struct UndefineCmd {
 pub struct ExportCmd {
     pub name: String,
     pub out: Option<String>,
+    /// `yaml` or `json`; picked from the `--out` extension when absent.
+    pub format: Option<String>,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct RunCmd {
     pub name: String,
+    /// `--name value` pairs in order. Positional values have an empty name and
+    /// bind to the intent's parameters in declaration order.
     pub params: Vec<(String, String)>,
+    /// Intents chained with `then`, run in order after this one and sharing
+    /// its parameters and extracted results.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub then: Vec<RunCmd>,
+    /// Keep running the chain after an intent fails (`--continue`).
+    #[serde(default)]
+    pub keep_going: bool,
+    /// Print the results as JSON and report how they ended in the exit code
+    /// (`--json`).
+    #[serde(default)]
+    pub json: bool,
+    /// Capture the page when an intent fails (`--screenshot-on-error`).
+    #[serde(default)]
+    pub screenshot_on_error: bool,
 }
 
 // --- Network ---
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-pub struct InterceptCmd {
-    pub rule: InterceptRule,
+pub enum InterceptCmd {
+    /// Add a rule, replacing any rule with the same pattern.
+    Rule(InterceptRule),
+    /// Drop the rule for one pattern, or every rule.
+    Clear(Option<String>),
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-pub enum InterceptRule {
-    Clear(Option<String>),
-    Set {
-        pattern: String,
-        block: bool,
-        respond: Option<String>,
-        respond_file: Option<String>,
-        status: Option<f64>,
+pub struct InterceptRule {
+    pub url_pattern: String,
+    pub action: InterceptAction,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum InterceptAction {
+    Block,
+    Mock {
+        status: u16,
+        body: String,
+        content_type: Option<String>,
+    },
+    /// A mock whose body is read from a file when the rule is set.
+    MockFile {
+        status: u16,
+        path: String,
+        content_type: Option<String>,
     },
+    /// Let matching requests through; they are only recorded.
+    Continue,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -503,6 +800,10 @@ pub struct FrameSwitchCmd {
 pub enum FrameTarget {
     Main,
     Parent,
+    /// `frame "checkout"`: the frame whose `name` attribute matches.
+    Name(String),
+    /// `frame url "stripe.com"`: the first frame whose URL contains the text.
+    UrlContains(String),
     Target(Target),
 }
 
@@ -560,6 +861,8 @@ pub struct HighlightCmd {
     pub target: Option<Target>,
     pub duration: Option<String>,
     pub color: Option<String>,
+    /// Keep the overlay until `highlight clear` instead of for `duration`.
+    pub persist: bool,
 }
 
 // --- Utility ---
@@ -569,7 +872,9 @@ pub struct PdfCmd {
     pub path: String,
     pub format: Option<String>,
     pub landscape: bool,
-    pub margin: Option<String>,
+    /// Margin on every side, in millimetres.
+    pub margin: Option<f32>,
+    pub scale: Option<f32>,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -578,17 +883,49 @@ pub struct LearnCmd {
     pub name: Option<String>,
 }
 
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EvalCmd {
+    pub script: String,
+    /// `--arg name=value` pairs, in the order given.
+    pub args: Vec<(String, EvalArg)>,
+}
+
+/// `set name = eval "..."`: run the script and keep its return value as a
+/// variable.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SetCmd {
+    pub name: String,
+    pub eval: EvalCmd,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum EvalArg {
+    Value(serde_json::Value),
+    /// A `$name` reference, resolved by the executor before dispatch.
+    Variable(String),
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct HelpCmd {
     pub topic: Option<String>,
 }
 
+/// `repeat <n> { ... }`: run the block's commands `count` times in order.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RepeatCmd {
+    pub count: usize,
+    pub body: Vec<Command>,
+}
+
 // --- Target ---
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Target {
     pub atomic: TargetAtomic,
     pub relation: Option<Box<TargetRelation>>,
+    /// States the whole target's element must be in.
+    #[serde(default)]
+    pub state: Option<StateFilter>,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -604,14 +941,122 @@ pub enum RelationKind {
     After,
     Before,
     Contains,
+    /// `between <start> and <end>`: the relation's target is the start
+    /// anchor, the end anchor is carried here.
+    Between(Box<Target>),
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum TargetAtomic {
     Id(usize),
     Text(String),
+    TextMatch(TextMatch),                     // ="Save", ^"Save" or $"Close"
     Selector { kind: String, value: String }, // css or xpath
     Role(String),
+    TestId(String),                          // data-testid, data-test or data-cy
+    RoleText { role: String, text: String }, // button "Submit"
+}
+
+// --- Builder ---
+
+/// Fluent construction of targets for Rust callers, mirroring OIL syntax:
+///
+/// ```
+/// use oryn_core::ast::Target;
+///
+/// // click "Submit" inside "Checkout"
+/// let target = Target::text("Submit").inside(Target::text("Checkout"));
+/// ```
+///
+/// Relations chain right-associatively, as in OIL: `a.near(b).inside(c)`
+/// builds `a near (b inside c)`.
+impl Target {
+    pub fn id(id: usize) -> Self {
+        Self::atomic(TargetAtomic::Id(id))
+    }
+
+    pub fn text(text: impl Into<String>) -> Self {
+        Self::atomic(TargetAtomic::Text(text.into()))
+    }
+
+    /// A text target with an explicit match mode, e.g. `="Save"`.
+    pub fn text_matching(text: impl Into<String>, mode: TextMatchMode) -> Self {
+        Self::atomic(TargetAtomic::TextMatch(TextMatch {
+            value: text.into(),
+            mode,
+        }))
+    }
+
+    pub fn role(role: impl Into<String>) -> Self {
+        Self::atomic(TargetAtomic::Role(role.into()))
+    }
+
+    pub fn role_text(role: impl Into<String>, text: impl Into<String>) -> Self {
+        Self::atomic(TargetAtomic::RoleText {
+            role: role.into(),
+            text: text.into(),
+        })
+    }
+
+    pub fn test_id(test_id: impl Into<String>) -> Self {
+        Self::atomic(TargetAtomic::TestId(test_id.into()))
+    }
+
+    pub fn css(selector: impl Into<String>) -> Self {
+        Self::atomic(TargetAtomic::Selector {
+            kind: "css".into(),
+            value: selector.into(),
+        })
+    }
+
+    pub fn xpath(selector: impl Into<String>) -> Self {
+        Self::atomic(TargetAtomic::Selector {
+            kind: "xpath".into(),
+            value: selector.into(),
+        })
+    }
+
+    pub fn near(self, anchor: Target) -> Self {
+        self.relate(RelationKind::Near, anchor)
+    }
+
+    pub fn inside(self, container: Target) -> Self {
+        self.relate(RelationKind::Inside, container)
+    }
+
+    pub fn after(self, anchor: Target) -> Self {
+        self.relate(RelationKind::After, anchor)
+    }
+
+    pub fn before(self, anchor: Target) -> Self {
+        self.relate(RelationKind::Before, anchor)
+    }
+
+    pub fn contains(self, content: Target) -> Self {
+        self.relate(RelationKind::Contains, content)
+    }
+
+    pub fn between(self, start: Target, end: Target) -> Self {
+        self.relate(RelationKind::Between(Box::new(end)), start)
+    }
+
+    fn atomic(atomic: TargetAtomic) -> Self {
+        Self {
+            atomic,
+            relation: None,
+            state: None,
+        }
+    }
+
+    /// Attach a relation to the innermost target of the chain.
+    fn relate(mut self, kind: RelationKind, target: Target) -> Self {
+        let mut current = &mut self;
+        while current.relation.is_some() {
+            current = &mut current.relation.as_mut().unwrap().target;
+        }
+        current.relation = Some(Box::new(TargetRelation { kind, target }));
+        self
+    }
 }
 
 // --- Conversion to/from oryn_common::resolver::Target ---
@@ -621,10 +1066,27 @@ impl Target {
     pub fn to_resolver_target(&self) -> oryn_common::resolver::Target {
         use oryn_common::resolver::Target as ResolverTarget;
 
+        if let Some(state) = self.state {
+            let unfiltered = Target {
+                state: None,
+                ..self.clone()
+            };
+            return ResolverTarget::State {
+                target: Box::new(unfiltered.to_resolver_target()),
+                state,
+            };
+        }
+
         let base = match &self.atomic {
             TargetAtomic::Id(id) => ResolverTarget::Id(*id),
             TargetAtomic::Text(text) => ResolverTarget::Text(text.clone()),
+            TargetAtomic::TextMatch(m) => ResolverTarget::TextMatch(m.clone()),
             TargetAtomic::Role(role) => ResolverTarget::Role(role.clone()),
+            TargetAtomic::TestId(test_id) => ResolverTarget::TestId(test_id.clone()),
+            TargetAtomic::RoleText { role, text } => ResolverTarget::RoleText {
+                role: role.clone(),
+                text: text.clone(),
+            },
             TargetAtomic::Selector { value, .. } => ResolverTarget::Selector(value.clone()),
         };
 
@@ -635,7 +1097,7 @@ impl Target {
         let base_box = Box::new(base);
         let related_box = Box::new(relation.target.to_resolver_target());
 
-        match relation.kind {
+        match &relation.kind {
             RelationKind::Near => ResolverTarget::Near {
                 target: base_box,
                 anchor: related_box,
@@ -656,6 +1118,11 @@ impl Target {
                 target: base_box,
                 content: related_box,
             },
+            RelationKind::Between(end) => ResolverTarget::Between {
+                target: base_box,
+                start_anchor: related_box,
+                end_anchor: Box::new(end.to_resolver_target()),
+            },
         }
     }
 
@@ -667,6 +1134,7 @@ impl Target {
             Target {
                 atomic,
                 relation: None,
+                state: None,
             }
         }
 
@@ -683,13 +1151,20 @@ impl Target {
                     kind,
                     target: related_target,
                 })),
+                state: None,
             }
         }
 
         match resolver_target {
             ResolverTarget::Id(id) => simple(TargetAtomic::Id(*id)),
             ResolverTarget::Text(text) => simple(TargetAtomic::Text(text.clone())),
+            ResolverTarget::TextMatch(m) => simple(TargetAtomic::TextMatch(m.clone())),
             ResolverTarget::Role(role) => simple(TargetAtomic::Role(role.clone())),
+            ResolverTarget::TestId(test_id) => simple(TargetAtomic::TestId(test_id.clone())),
+            ResolverTarget::RoleText { role, text } => simple(TargetAtomic::RoleText {
+                role: role.clone(),
+                text: text.clone(),
+            }),
             ResolverTarget::Selector(sel) => simple(TargetAtomic::Selector {
                 kind: "css".to_string(),
                 value: sel.clone(),
@@ -710,6 +1185,19 @@ impl Target {
             ResolverTarget::Contains { target, content } => {
                 with_relation(target, content, RelationKind::Contains)
             }
+            ResolverTarget::Between {
+                target,
+                start_anchor,
+                end_anchor,
+            } => with_relation(
+                target,
+                start_anchor,
+                RelationKind::Between(Box::new(Target::from_resolver_target(end_anchor))),
+            ),
+            ResolverTarget::State { target, state } => Target {
+                state: Some(*state),
+                ..Target::from_resolver_target(target)
+            },
         }
     }
 }
@@ -0,0 +1,118 @@
+//! `$ENV{VAR}` interpolation, so scripts can take credentials and hosts from
+//! the process environment instead of hardcoding them.
+//!
+//! Expansion runs on the parsed command, after quoting has been resolved, and
+//! only touches the values that are sent to the page: `type` text, `goto`
+//! URLs and `run` parameters. The source line keeps the reference, so logging
+//! the script never reveals the expanded value.
+
+use crate::ast::Command;
+use regex::{Captures, Regex};
+use std::sync::OnceLock;
+
+fn env_ref() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"\$ENV\{([A-Za-z_][A-Za-z0-9_]*)\}").unwrap())
+}
+
+/// Expand `$ENV{VAR}` references from the process environment.
+///
+/// Returns the names of variables that are not set; their references are left
+/// as written.
+pub fn expand_env(cmd: &mut Command) -> Vec<String> {
+    expand_env_with(cmd, |name| std::env::var(name).ok())
+}
+
+/// Expand `$ENV{VAR}` references using `lookup` instead of the process
+/// environment.
+pub fn expand_env_with(cmd: &mut Command, lookup: impl Fn(&str) -> Option<String>) -> Vec<String> {
+    let mut missing = Vec::new();
+    let mut expand = |value: &mut String| {
+        if !value.contains("$ENV{") {
+            return;
+        }
+        let expanded = env_ref().replace_all(value, |caps: &Captures| match lookup(&caps[1]) {
+            Some(v) => v,
+            None => {
+                if !missing.iter().any(|m| m == &caps[1]) {
+                    missing.push(caps[1].to_string());
+                }
+                caps[0].to_string()
+            }
+        });
+        *value = expanded.into_owned();
+    };
+
+    match cmd {
+        Command::Type(c) => expand(&mut c.text),
+        Command::Goto(c) => expand(&mut c.url),
+        Command::Run(c) => {
+            c.params.iter_mut().for_each(|(_, v)| expand(v));
+            c.then
+                .iter_mut()
+                .flat_map(|stage| stage.params.iter_mut())
+                .for_each(|(_, v)| expand(v));
+        }
+        _ => {}
+    }
+    missing
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse;
+    use oryn_common::formatter::mask_sensitive_log;
+
+    fn parse_one(input: &str) -> Command {
+        parse(input).unwrap().lines.remove(0).command.unwrap()
+    }
+
+    #[test]
+    fn test_type_text_expands_from_environment() {
+        std::env::set_var("ORYN_INTERPOLATE_TEST_USER", "alice@example.com");
+        let raw = r#"type email "$ENV{ORYN_INTERPOLATE_TEST_USER}""#;
+
+        let mut cmd = parse_one(raw);
+        assert!(expand_env(&mut cmd).is_empty());
+        match cmd {
+            Command::Type(c) => assert_eq!(c.text, "alice@example.com"),
+            other => panic!("Expected type command, got {:?}", other),
+        }
+
+        let logged = mask_sensitive_log(raw);
+        assert!(!logged.contains("alice@example.com"));
+        assert!(!logged.contains("ORYN_INTERPOLATE_TEST_USER"));
+        assert_eq!(logged, r#"type email "********""#);
+    }
+
+    #[test]
+    fn test_goto_and_run_params_expand() {
+        let lookup = |name: &str| (name == "HOST").then(|| "staging.example.com".to_string());
+
+        let mut cmd = parse_one(r#"goto "https://$ENV{HOST}/login""#);
+        expand_env_with(&mut cmd, lookup);
+        match cmd {
+            Command::Goto(c) => assert_eq!(c.url, "https://staging.example.com/login"),
+            other => panic!("Expected goto command, got {:?}", other),
+        }
+
+        let mut cmd = parse_one(r#"run open_site --host "$ENV{HOST}""#);
+        expand_env_with(&mut cmd, lookup);
+        match cmd {
+            Command::Run(c) => assert_eq!(c.params[0].1, "staging.example.com"),
+            other => panic!("Expected run command, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_unknown_variable_is_left_and_reported() {
+        let mut cmd = parse_one(r#"type 3 "$ENV{NOPE}-$ENV{NOPE}""#);
+        let missing = expand_env_with(&mut cmd, |_| None);
+        assert_eq!(missing, vec!["NOPE".to_string()]);
+        match cmd {
+            Command::Type(c) => assert_eq!(c.text, "$ENV{NOPE}-$ENV{NOPE}"),
+            other => panic!("Expected type command, got {:?}", other),
+        }
+    }
+}
@@ -1,5 +1,6 @@
 pub mod api;
 pub mod ast;
+pub mod interpolate;
 pub mod normalizer;
 pub mod parser;
 pub mod resolution;
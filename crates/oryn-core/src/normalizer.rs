@@ -4,6 +4,7 @@ pub fn normalize(input: &str) -> String {
     let mut normalized_lines = Vec::new();
 
     for line in input.lines() {
+        let line = fold_typography(line);
         let trimmed = line.trim();
         if trimmed.is_empty() {
             continue;
@@ -34,6 +35,68 @@ pub fn normalize(input: &str) -> String {
     normalized_lines.join("\n")
 }
 
+/// Replace typographic punctuation that editors and web pages substitute for
+/// ASCII: smart quotes become straight quotes, dashes become `--`, `…`
+/// becomes `...` and non-breaking spaces become plain spaces. Text inside
+/// straight-quoted strings is left untouched, as is the content between smart
+/// quotes (only the delimiters are replaced). A lone `’` outside quotes is an
+/// apostrophe and is kept.
+fn fold_typography(line: &str) -> String {
+    let mut out = String::with_capacity(line.len());
+    // The straight quote that closes the current string, and the characters
+    // accepted as its closing delimiter.
+    let mut open: Option<(char, &[char])> = None;
+    let mut escaped = false;
+
+    for c in line.chars() {
+        if escaped {
+            escaped = false;
+            out.push(c);
+            continue;
+        }
+        if c == '\\' {
+            escaped = true;
+            out.push(c);
+            continue;
+        }
+
+        if let Some((straight, closers)) = open {
+            if closers.contains(&c) {
+                out.push(straight);
+                open = None;
+            } else {
+                out.push(c);
+            }
+            continue;
+        }
+
+        match c {
+            '"' => {
+                open = Some(('"', &['"']));
+                out.push(c);
+            }
+            '\'' => {
+                open = Some(('\'', &['\'']));
+                out.push(c);
+            }
+            '\u{201C}' | '\u{201D}' | '\u{201E}' => {
+                open = Some(('"', &['\u{201C}', '\u{201D}', '"']));
+                out.push('"');
+            }
+            '\u{2018}' => {
+                open = Some(('\'', &['\u{2019}', '\u{2018}', '\'']));
+                out.push('\'');
+            }
+            '\u{2013}' | '\u{2014}' => out.push_str("--"),
+            '\u{2026}' => out.push_str("..."),
+            '\u{00A0}' | '\u{2007}' | '\u{202F}' => out.push(' '),
+            _ => out.push(c),
+        }
+    }
+
+    out
+}
+
 fn split_comment(line: &str) -> (&str, Option<&str>) {
     let mut in_quote = None;
     let mut escaped = false;
@@ -71,6 +134,10 @@ fn split_comment(line: &str) -> (&str, Option<&str>) {
 }
 
 fn normalize_command_part(input: &str) -> String {
+    if let Some(repeat) = normalize_repeat(input) {
+        return repeat;
+    }
+
     let tokens = tokenize_and_normalize(input);
 
     if tokens.is_empty() {
@@ -79,7 +146,10 @@ fn normalize_command_part(input: &str) -> String {
 
     let mut iter = tokens.into_iter();
     let first = iter.next().unwrap();
-    let lower_first = first.to_lowercase();
+    let mut lower_first = first.to_lowercase();
+    if let Ok(Some(keyword)) = expand_command_abbreviation(&lower_first) {
+        lower_first = keyword.to_string();
+    }
 
     let (verb, args) = match lower_first.as_str() {
         "navigate" | "nav" => ("goto".to_string(), iter.collect::<Vec<_>>()),
@@ -246,7 +316,32 @@ fn normalize_command_part(input: &str) -> String {
                         arg
                     }
                 }
+                // `frame checkout`, `frame url stripe.com`: names and URLs are text
+                "frame" if normalized_args.is_empty() || normalized_args == ["url"] => {
+                    let keyword = normalized_args.is_empty()
+                        && matches!(arg.to_lowercase().as_str(), "main" | "parent" | "url");
+                    if keyword {
+                        arg.to_lowercase()
+                    } else if arg.starts_with('"') || should_not_quote_target(&arg) {
+                        arg
+                    } else {
+                        format!("\"{}\"", arg)
+                    }
+                }
                 "wait" => arg, // don't touch options
+                "assert" => arg,
+
+                // `="Save"`, `^"Save"` and `$"Close"`: quoted text with a match mode
+                _ if is_text_match_target(&arg) => arg,
+
+                // `testid "submit-btn"`: the keyword stays bare before a quoted id
+                _ if arg == "testid"
+                    && arg_iter
+                        .peek()
+                        .is_some_and(|next| next.starts_with('"') || next.starts_with('\'')) =>
+                {
+                    arg
+                }
 
                 // Check if previous arg was an option that expects a numeric/duration value
                 _ if !normalized_args.is_empty()
@@ -263,10 +358,10 @@ fn normalize_command_part(input: &str) -> String {
 
                 // Relational keywords - auto-quote following bare words
                 _ if !normalized_args.is_empty()
-                    && matches!(
+                    && (matches!(
                         normalized_args.last().unwrap().to_lowercase().as_str(),
-                        "inside" | "near" | "after" | "before" | "contains"
-                    ) =>
+                        "inside" | "near" | "after" | "before" | "contains" | "between"
+                    ) || is_between_end_anchor(&normalized_args)) =>
                 {
                     // Auto-quote if not already quoted/id/selector
                     if !arg.starts_with('"')
@@ -283,6 +378,18 @@ fn normalize_command_part(input: &str) -> String {
                     }
                 }
 
+                // `click all "Remove"`: only a quoted target makes `all` the
+                // keyword, so bare text like `click All Items` still matches
+                "click"
+                    if normalized_args.is_empty()
+                        && arg == "all"
+                        && arg_iter.peek().is_some_and(|next| {
+                            next.starts_with('"') || next.starts_with('\'')
+                        }) =>
+                {
+                    arg
+                }
+
                 // Commands that expect text targets - auto-quote bare words
                 "click" | "hover" | "focus" | "check" | "uncheck" => {
                     if normalized_args.is_empty()
@@ -304,7 +411,12 @@ fn normalize_command_part(input: &str) -> String {
                                 if peek.starts_with('-')
                                     || matches!(
                                         peek.to_lowercase().as_str(),
-                                        "inside" | "near" | "after" | "before" | "contains"
+                                        "inside"
+                                            | "near"
+                                            | "after"
+                                            | "before"
+                                            | "contains"
+                                            | "between"
                                     )
                                 {
                                     break;
@@ -356,7 +468,7 @@ fn normalize_command_part(input: &str) -> String {
                         if should_not_quote_target(&arg)
                             || matches!(
                                 arg.to_lowercase().as_str(),
-                                "inside" | "near" | "after" | "before" | "contains"
+                                "inside" | "near" | "after" | "before" | "contains" | "between"
                             )
                         {
                             arg
@@ -404,7 +516,7 @@ fn normalize_command_part(input: &str) -> String {
             let has_relational_after = normalized_args.iter().skip(idx + 1).any(|arg| {
                 matches!(
                     arg.to_lowercase().as_str(),
-                    "inside" | "near" | "after" | "before" | "contains"
+                    "inside" | "near" | "after" | "before" | "contains" | "between"
                 )
             });
 
@@ -433,6 +545,182 @@ fn normalize_command_part(input: &str) -> String {
         .join(" ")
 }
 
+/// Command keywords, as the grammar spells them.
+const COMMAND_KEYWORDS: &[&str] = &[
+    "accept_cookies",
+    "assert",
+    "back",
+    "box",
+    "check",
+    "clear",
+    "click",
+    "console",
+    "cookies",
+    "define",
+    "device",
+    "devices",
+    "dialog",
+    "dismiss",
+    "errors",
+    "eval",
+    "exit",
+    "export",
+    "extract",
+    "focus",
+    "forward",
+    "frame",
+    "frames",
+    "goto",
+    "headers",
+    "help",
+    "highlight",
+    "hover",
+    "html",
+    "intents",
+    "intercept",
+    "keydown",
+    "keys",
+    "keyup",
+    "learn",
+    "login",
+    "media",
+    "observe",
+    "pack",
+    "packs",
+    "pdf",
+    "press",
+    "record",
+    "refresh",
+    "repeat",
+    "requests",
+    "reset",
+    "resolve",
+    "run",
+    "screenshot",
+    "scroll",
+    "search",
+    "select",
+    "session",
+    "sessions",
+    "set",
+    "state",
+    "storage",
+    "submit",
+    "tab",
+    "tabs",
+    "text",
+    "title",
+    "trace",
+    "type",
+    "uncheck",
+    "undefine",
+    "url",
+    "viewport",
+    "wait",
+];
+
+/// Words the normalizer rewrites to a command itself (`nav` -> `goto`), which
+/// must not be read as abbreviations of something else.
+const COMMAND_SYNONYMS: &[&str] = &["navigate", "nav", "go", "scan", "quit", "accept"];
+
+/// Built-in short forms for commands whose prefix is ambiguous or that are
+/// not prefixes at all. These are fixed, unlike user aliases.
+const COMMAND_ABBREVIATIONS: &[(&str, &str)] =
+    &[("cl", "click"), ("ty", "type"), ("ss", "screenshot")];
+
+/// Expand an abbreviated command word: an entry of the abbreviation table, or
+/// a prefix of exactly one command keyword. When every matching keyword
+/// starts with the shortest one (`sess` -> `session`/`sessions`), that one is
+/// taken.
+///
+/// Returns `Ok(None)` for full keywords and for words that match nothing, and
+/// the candidates when the prefix is ambiguous.
+pub fn expand_command_abbreviation(word: &str) -> Result<Option<&'static str>, Vec<&'static str>> {
+    if word.is_empty()
+        || !word.chars().all(|c| c.is_ascii_lowercase() || c == '_')
+        || COMMAND_KEYWORDS.contains(&word)
+        || COMMAND_SYNONYMS.contains(&word)
+    {
+        return Ok(None);
+    }
+    if let Some((_, keyword)) = COMMAND_ABBREVIATIONS.iter().find(|(abbr, _)| *abbr == word) {
+        return Ok(Some(keyword));
+    }
+
+    let candidates: Vec<&'static str> = COMMAND_KEYWORDS
+        .iter()
+        .copied()
+        .filter(|keyword| keyword.starts_with(word))
+        .collect();
+    match candidates.as_slice() {
+        [] => Ok(None),
+        [only] => Ok(Some(only)),
+        [shortest, rest @ ..] if rest.iter().all(|k| k.starts_with(shortest)) => Ok(Some(shortest)),
+        _ => Err(candidates),
+    }
+}
+
+/// Normalize `repeat <n> { a; b }` by normalizing each command in the block
+/// on its own. Returns `None` for anything that is not a repeat block.
+fn normalize_repeat(input: &str) -> Option<String> {
+    let input = input.trim();
+    let (verb, rest) = input.split_once(char::is_whitespace)?;
+    if !verb.eq_ignore_ascii_case("repeat") {
+        return None;
+    }
+    let (count, block) = rest.trim_start().split_once('{')?;
+    let body = block.trim_end().strip_suffix('}')?;
+    let commands: Vec<String> = split_top_level(body, ';')
+        .into_iter()
+        .map(str::trim)
+        .filter(|command| !command.is_empty())
+        .map(normalize_command_part)
+        .collect();
+    Some(format!(
+        "repeat {} {{ {} }}",
+        count.trim(),
+        commands.join(" ; ")
+    ))
+}
+
+/// Split on `sep` where it is outside quotes and braces.
+fn split_top_level(s: &str, sep: char) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut start = 0;
+    let mut depth = 0;
+    let mut in_quote = None;
+    let mut escaped = false;
+
+    for (idx, c) in s.char_indices() {
+        if escaped {
+            escaped = false;
+            continue;
+        }
+        if c == '\\' {
+            escaped = true;
+            continue;
+        }
+        if let Some(q) = in_quote {
+            if c == q {
+                in_quote = None;
+            }
+            continue;
+        }
+        match c {
+            '"' | '\'' => in_quote = Some(c),
+            '{' => depth += 1,
+            '}' => depth -= 1,
+            _ if c == sep && depth == 0 => {
+                parts.push(&s[start..idx]);
+                start = idx + c.len_utf8();
+            }
+            _ => {}
+        }
+    }
+    parts.push(&s[start..]);
+    parts
+}
+
 fn count_balance(s: &str) -> i32 {
     s.chars().fold(0, |acc, c| match c {
         '{' => acc + 1,
@@ -603,17 +891,56 @@ pub fn find_invalid_digit_hash(input: &str) -> Option<usize> {
     None
 }
 
+fn is_text_match_target(s: &str) -> bool {
+    let mut chars = s.chars();
+    matches!(chars.next(), Some('=' | '^' | '$')) && chars.next() == Some('"')
+}
+
 fn should_not_quote_value(s: &str) -> bool {
     is_number(s) || is_duration(s)
 }
 
+/// True when the next word is the end anchor of `between <start> and <end>`.
+fn is_between_end_anchor(args: &[String]) -> bool {
+    matches!(args, [.., between, _, and] if between.eq_ignore_ascii_case("between") && and.eq_ignore_ascii_case("and"))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_command_abbreviations_expand() {
+        assert_eq!(normalize("cl \"X\""), "click \"X\"");
+        assert_eq!(normalize("ty \"Name\" Ada"), "type \"Name\" \"Ada\"");
+        assert_eq!(normalize("obs --full"), "observe --full");
+        assert_eq!(normalize("sess"), "session");
+        // Full keywords and the normalizer's own synonyms are left alone
+        assert_eq!(normalize("clear 5"), "clear 5");
+        assert_eq!(normalize("nav example.com"), "goto example.com");
+    }
+
+    #[test]
+    fn test_ambiguous_abbreviation_is_not_expanded() {
+        assert_eq!(
+            expand_command_abbreviation("c"),
+            Err(vec!["check", "clear", "click", "console", "cookies"])
+        );
+        assert_eq!(normalize("c 5"), "c 5");
+        assert_eq!(expand_command_abbreviation("zz"), Ok(None));
+    }
+
     #[test]
     fn test_auto_quote_click() {
         assert_eq!(normalize("click store"), "click \"store\"");
+        assert_eq!(normalize("click all \"Remove\""), "click all \"Remove\"");
+        assert_eq!(normalize("click All Items"), "click \"All Items\"");
+        assert_eq!(normalize("click all"), "click \"all\"");
+        assert_eq!(
+            normalize("click testid \"submit-btn\""),
+            "click testid \"submit-btn\""
+        );
+        assert_eq!(normalize("click testid"), "click \"testid\"");
         assert_eq!(normalize("click Add to Cart"), "click \"Add to Cart\"");
         assert_eq!(
             normalize("click Continue --force"),
@@ -641,6 +968,74 @@ mod tests {
         assert_eq!(normalize("click 'Submit'"), "click \"Submit\""); // single -> double
     }
 
+    #[test]
+    fn test_smart_quotes_become_straight() {
+        assert_eq!(
+            normalize("click \u{201C}Sign In\u{201D}"),
+            "click \"Sign In\""
+        );
+        assert_eq!(
+            normalize("click \u{2018}Sign In\u{2019}"),
+            "click \"Sign In\""
+        );
+        assert_eq!(
+            normalize("type email \u{201C}it\u{2019}s me\u{201D}"),
+            "type email \"it\u{2019}s me\""
+        );
+    }
+
+    #[test]
+    fn test_dashes_and_ellipsis_outside_quotes() {
+        assert_eq!(normalize("observe \u{2014}full"), "observe --full");
+        assert_eq!(normalize("observe \u{2013}full"), "observe --full");
+        assert_eq!(
+            normalize("click \"Load more\u{2026}\" \u{2014}force"),
+            "click \"Load more\u{2026}\" --force"
+        );
+        assert_eq!(
+            normalize("type 3 \"2020\u{2013}2024 \u{2014} done\""),
+            "type 3 \"2020\u{2013}2024 \u{2014} done\""
+        );
+    }
+
+    #[test]
+    fn test_non_breaking_spaces_outside_quotes() {
+        assert_eq!(
+            normalize("click\u{00A0}\"Sign In\"\u{202F}--force"),
+            "click \"Sign In\" --force"
+        );
+        assert_eq!(
+            normalize("type email \"a\u{00A0}b\""),
+            "type email \"a\u{00A0}b\""
+        );
+        assert_eq!(normalize("\u{00A0}\u{00A0}observe"), "observe");
+    }
+
+    #[test]
+    fn test_dont_quote_text_match_modifiers() {
+        assert_eq!(normalize("click =\"Save\""), "click =\"Save\"");
+        assert_eq!(normalize("click ^'Save'"), "click ^\"Save\"");
+        assert_eq!(
+            normalize("type $\u{201C}Email\u{201D} me@example.com"),
+            "type $\"Email\" \"me@example.com\""
+        );
+    }
+
+    #[test]
+    fn test_frame_names_and_urls_are_quoted() {
+        assert_eq!(normalize("frame checkout"), "frame \"checkout\"");
+        assert_eq!(
+            normalize("frame URL stripe.com"),
+            "frame url \"stripe.com\""
+        );
+        assert_eq!(normalize("frame main"), "frame main");
+        assert_eq!(normalize("frame 2"), "frame 2");
+        assert_eq!(
+            normalize("frame Modal near Checkout"),
+            "frame \"Modal\" near \"Checkout\""
+        );
+    }
+
     #[test]
     fn test_dont_quote_selectors() {
         assert_eq!(normalize("click css(.button)"), "click css(\".button\")");
@@ -658,6 +1053,18 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_between_keywords() {
+        assert_eq!(
+            normalize("click Nickname between First and Last"),
+            "click \"Nickname\" between \"First\" and \"Last\""
+        );
+        assert_eq!(
+            normalize("click Terms and Conditions"),
+            "click \"Terms and Conditions\""
+        );
+    }
+
     #[test]
     fn test_dont_quote_numeric_ids() {
         assert_eq!(normalize("click 5"), "click 5");
@@ -1,5 +1,5 @@
 use super::ast::*;
-use super::normalizer::find_invalid_digit_hash;
+use super::normalizer::{expand_command_abbreviation, find_invalid_digit_hash};
 use pest::iterators::Pair;
 use pest::Parser;
 use pest_derive::Parser;
@@ -19,12 +19,19 @@ pub enum ParseError {
     InvalidInteger(std::num::ParseIntError),
     #[error("Invalid float: {0}")]
     InvalidFloat(std::num::ParseFloatError),
+    #[error("Conflicting flags: {0}")]
+    ConflictingFlags(String),
     #[error("Unexpected input at position {position}: found '{found}'\nHint: {hint}")]
     UnexpectedInput {
         position: usize,
         found: String,
         hint: String,
     },
+    #[error("Ambiguous command '{prefix}': could be {}", .candidates.join(", "))]
+    AmbiguousCommand {
+        prefix: String,
+        candidates: Vec<String>,
+    },
 }
 
 pub fn parse(input: &str) -> Result<Script, ParseError> {
@@ -32,6 +39,7 @@ pub fn parse(input: &str) -> Result<Script, ParseError> {
     // This gives better error messages than generic Pest errors
     for line in input.lines() {
         validate_no_invalid_hash_after_id(line)?;
+        validate_command_word(line)?;
     }
 
     let mut pairs = OilParser::parse(Rule::oil_input, input)?;
@@ -69,9 +77,14 @@ fn parse_line(pair: Pair<Rule>) -> Result<Line, ParseError> {
                 comment = Some(inner.as_str().trim_start_matches('#').to_string());
             }
             _ => {
-                // Silent `command` rule passes through the specific command rule directly
-                if let Ok(cmd) = parse_command(inner.clone()) {
-                    command = Some(cmd);
+                // Silent `command` rule passes through the specific command rule
+                // directly; anything that is not a command is skipped, but errors
+                // from a recognized command (e.g. conflicting flags) surface.
+                let rule = inner.as_rule();
+                match parse_command(inner) {
+                    Ok(cmd) => command = Some(cmd),
+                    Err(ParseError::UnknownRule(unknown)) if unknown == rule => {}
+                    Err(err) => return Err(err),
                 }
             }
         }
@@ -99,6 +112,21 @@ fn validate_no_invalid_hash_after_id(line: &str) -> Result<(), ParseError> {
     })
 }
 
+/// Rejects a command word that abbreviates more than one command, naming
+/// them, instead of leaving it to a generic syntax error.
+fn validate_command_word(line: &str) -> Result<(), ParseError> {
+    let Some(word) = line.split_whitespace().next() else {
+        return Ok(());
+    };
+    match expand_command_abbreviation(word) {
+        Err(candidates) => Err(ParseError::AmbiguousCommand {
+            prefix: word.to_string(),
+            candidates: candidates.into_iter().map(String::from).collect(),
+        }),
+        Ok(_) => Ok(()),
+    }
+}
+
 fn parse_command(pair: Pair<Rule>) -> Result<Command, ParseError> {
     match pair.as_rule() {
         // Navigation
@@ -115,6 +143,7 @@ fn parse_command(pair: Pair<Rule>) -> Result<Command, ParseError> {
         Rule::title_cmd => Ok(Command::Title),
         Rule::screenshot_cmd => Ok(Command::Screenshot(parse_screenshot(pair)?)),
         Rule::box_cmd => Ok(Command::Box(parse_box(pair)?)),
+        Rule::resolve_cmd => Ok(Command::Resolve(parse_resolve(pair)?)),
 
         // Actions
         Rule::click_cmd => Ok(Command::Click(parse_click(pair)?)),
@@ -134,6 +163,7 @@ fn parse_command(pair: Pair<Rule>) -> Result<Command, ParseError> {
 
         // Wait
         Rule::wait_cmd => Ok(Command::Wait(parse_wait(pair)?)),
+        Rule::assert_cmd => Ok(Command::Assert(parse_assert(pair)?)),
 
         // Extract
         Rule::extraction_cmd => Ok(Command::Extract(parse_extract(pair)?)),
@@ -145,6 +175,7 @@ fn parse_command(pair: Pair<Rule>) -> Result<Command, ParseError> {
         Rule::session_mgmt_cmd => Ok(Command::Session(parse_session_mgmt(pair)?)),
         Rule::state_cmd => Ok(Command::State(parse_state(pair)?)),
         Rule::headers_cmd => Ok(Command::Headers(parse_headers(pair)?)),
+        Rule::reset_cmd => Ok(Command::Reset(parse_reset(pair))),
 
         // Tabs
         Rule::tabs_cmd => Ok(Command::Tabs),
@@ -195,8 +226,11 @@ fn parse_command(pair: Pair<Rule>) -> Result<Command, ParseError> {
         // Utility
         Rule::pdf_cmd => Ok(Command::Pdf(parse_pdf(pair)?)),
         Rule::learn_cmd => Ok(Command::Learn(parse_learn(pair)?)),
+        Rule::eval_cmd => Ok(Command::Eval(parse_eval(pair)?)),
+        Rule::set_cmd => Ok(Command::Set(parse_set(pair)?)),
         Rule::exit_cmd => Ok(Command::Exit),
         Rule::help_cmd => Ok(Command::Help(parse_help(pair)?)),
+        Rule::repeat_cmd => Ok(Command::Repeat(parse_repeat(pair)?)),
 
         _ => Err(ParseError::UnknownRule(pair.as_rule())),
     }
@@ -207,7 +241,10 @@ fn parse_command(pair: Pair<Rule>) -> Result<Command, ParseError> {
 fn parse_goto(pair: Pair<Rule>) -> Result<GotoCmd, ParseError> {
     let mut url = String::new();
     let mut headers = None;
+    let mut referer = None;
+    let mut until = None;
     let mut timeout = None;
+    let mut spa = false;
 
     for inner in pair.into_inner() {
         match inner.as_rule() {
@@ -218,14 +255,24 @@ fn parse_goto(pair: Pair<Rule>) -> Result<GotoCmd, ParseError> {
                     .find(|opt| opt.as_rule() == Rule::string_value)
                     .map(parse_string);
             }
+            Rule::referer_opt => referer = inner.into_inner().next().map(parse_string),
+            Rule::goto_until => {
+                let condition = inner.as_str().trim_start_matches("--until").trim();
+                let inners: Vec<Pair<Rule>> = inner.clone().into_inner().collect();
+                until = Some(parse_wait_condition(condition, &inners)?);
+            }
             Rule::timeout_opt => timeout = Some(parse_timeout(inner)?),
+            Rule::goto_spa => spa = true,
             _ => {}
         }
     }
     Ok(GotoCmd {
         url,
         headers,
+        referer,
+        until,
         timeout,
+        spa,
     })
 }
 
@@ -235,16 +282,7 @@ fn parse_refresh(pair: Pair<Rule>) -> Result<RefreshCmd, ParseError> {
 }
 
 fn parse_observe(pair: Pair<Rule>) -> Result<ObserveCmd, ParseError> {
-    let mut cmd = ObserveCmd {
-        full: false,
-        minimal: false,
-        viewport: false,
-        hidden: false,
-        positions: false,
-        diff: false,
-        near: None,
-        timeout: None,
-    };
+    let mut cmd = ObserveCmd::default();
 
     for inner in pair.into_inner() {
         match inner.as_rule() {
@@ -252,6 +290,13 @@ fn parse_observe(pair: Pair<Rule>) -> Result<ObserveCmd, ParseError> {
                 cmd.near = Some(parse_string(inner.into_inner().next().unwrap()));
             }
             Rule::timeout_opt => cmd.timeout = Some(parse_timeout(inner)?),
+            Rule::depth_opt => {
+                let value = inner.into_inner().next().unwrap();
+                cmd.depth = Some(value.as_str().parse().map_err(ParseError::InvalidInteger)?);
+            }
+            Rule::interval_opt => {
+                cmd.interval = Some(inner.into_inner().next().unwrap().as_str().to_string());
+            }
             _ => match inner.as_str() {
                 "--full" => cmd.full = true,
                 "--minimal" => cmd.minimal = true,
@@ -259,6 +304,10 @@ fn parse_observe(pair: Pair<Rule>) -> Result<ObserveCmd, ParseError> {
                 "--hidden" => cmd.hidden = true,
                 "--positions" => cmd.positions = true,
                 "--diff" => cmd.diff = true,
+                "--no-dedup" => cmd.no_dedup = true,
+                "--tree" => cmd.tree = true,
+                "--monitor" | "--changes-only" => cmd.monitor = true,
+                "--focus-order" => cmd.focus_order = true,
                 _ => {}
             },
         }
@@ -267,65 +316,119 @@ fn parse_observe(pair: Pair<Rule>) -> Result<ObserveCmd, ParseError> {
 }
 
 fn parse_html(pair: Pair<Rule>) -> Result<HtmlCmd, ParseError> {
-    let mut selector = None;
+    let mut cmd = HtmlCmd {
+        selector: None,
+        inner: false,
+    };
     for inner in pair.into_inner() {
-        if inner.as_rule() == Rule::selector_opt {
-            selector = Some(parse_string(inner.into_inner().next().unwrap()));
+        match inner.as_rule() {
+            Rule::selector_opt => {
+                cmd.selector = Some(parse_string(inner.into_inner().next().unwrap()));
+            }
+            Rule::html_inner_flag => cmd.inner = true,
+            _ => {}
         }
     }
-    Ok(HtmlCmd { selector })
+    Ok(cmd)
 }
 
 fn parse_text(pair: Pair<Rule>) -> Result<TextCmd, ParseError> {
     let mut selector = None;
     let mut target = None;
+    let mut mode = TextMode::default();
     for inner in pair.into_inner() {
         match inner.as_rule() {
             Rule::selector_opt => selector = Some(parse_string(inner.into_inner().next().unwrap())),
+            Rule::text_mode_opt => {
+                mode = match inner.as_str() {
+                    "--raw" => TextMode::Raw,
+                    "--trim" => TextMode::Trim,
+                    _ => TextMode::Collapse,
+                }
+            }
             Rule::target => target = Some(parse_target(inner)?),
             _ => {}
         }
     }
-    Ok(TextCmd { selector, target })
+    Ok(TextCmd {
+        selector,
+        target,
+        mode,
+    })
 }
 
 fn parse_screenshot(pair: Pair<Rule>) -> Result<ScreenshotCmd, ParseError> {
     let mut output = None;
     let mut format = None;
     let mut fullpage = false;
+    let mut wait_stable = false;
     let mut target = None;
+    let mut clip = None;
+    let mut element_only = false;
+    let mut padding = None;
 
     for inner in pair.into_inner() {
         match inner.as_rule() {
             Rule::output_opt => output = Some(parse_file_path(inner.into_inner().next().unwrap())),
+            Rule::clip_opt => clip = Some(parse_clip(inner.into_inner().next().unwrap())?),
+            Rule::padding_opt => {
+                padding = Some(
+                    inner
+                        .into_inner()
+                        .next()
+                        .unwrap()
+                        .as_str()
+                        .parse::<f32>()
+                        .map_err(ParseError::InvalidFloat)?,
+                )
+            }
             Rule::format_opt => {
                 format = Some(inner.into_inner().next().unwrap().as_str().to_string())
             }
             Rule::target => target = Some(parse_target(inner)?),
-            _ => {
-                if inner.as_str() == "--fullpage" {
-                    fullpage = true;
-                }
-            }
+            _ => match inner.as_str() {
+                "--fullpage" => fullpage = true,
+                "--wait-stable" => wait_stable = true,
+                "--element-only" => element_only = true,
+                _ => {}
+            },
         }
     }
     Ok(ScreenshotCmd {
         output,
         format,
         fullpage,
+        wait_stable,
         target,
+        clip,
+        element_only,
+        padding,
     })
 }
 
+fn parse_clip(pair: Pair<Rule>) -> Result<(f32, f32, f32, f32), ParseError> {
+    let values = pair
+        .into_inner()
+        .map(|n| n.as_str().parse::<f32>().map_err(ParseError::InvalidFloat))
+        .collect::<Result<Vec<f32>, ParseError>>()?;
+    Ok((values[0], values[1], values[2], values[3]))
+}
+
 fn parse_box(pair: Pair<Rule>) -> Result<BoxCmd, ParseError> {
     let target = parse_target(pair.into_inner().next().unwrap())?;
     Ok(BoxCmd { target })
 }
 
+fn parse_resolve(pair: Pair<Rule>) -> Result<ResolveCmd, ParseError> {
+    let target = parse_target(pair.into_inner().next().unwrap())?;
+    Ok(ResolveCmd { target })
+}
+
 // --- Action Parsers ---
 
 fn parse_click(pair: Pair<Rule>) -> Result<ClickCmd, ParseError> {
     let mut target = None;
+    let mut all = false;
     let mut double = false;
     let mut right = false;
     let mut middle = false;
@@ -334,11 +437,14 @@ fn parse_click(pair: Pair<Rule>) -> Result<ClickCmd, ParseError> {
     let mut shift = false;
     let mut alt = false;
     let mut timeout = None;
+    let mut wait_after = None;
 
     for inner in pair.into_inner() {
         match inner.as_rule() {
             Rule::target => target = Some(parse_target(inner)?),
             Rule::timeout_opt => timeout = Some(parse_timeout(inner)?),
+            Rule::wait_after_opt => wait_after = Some(parse_wait_after(inner)?),
+            Rule::click_all | Rule::click_all_flag => all = true,
             _ => match inner.as_str() {
                 "--double" => double = true,
                 "--right" => right = true,
@@ -351,8 +457,25 @@ fn parse_click(pair: Pair<Rule>) -> Result<ClickCmd, ParseError> {
             },
         }
     }
+
+    // A click presses one button; a double click is only defined for the
+    // primary button. Repeating the same flag is harmless.
+    if right && middle {
+        return Err(ParseError::ConflictingFlags(
+            "--right and --middle both pick the mouse button; use only one".into(),
+        ));
+    }
+    if double && (right || middle) {
+        let button = if right { "--right" } else { "--middle" };
+        return Err(ParseError::ConflictingFlags(format!(
+            "--double only applies to the left button and cannot be combined with {}",
+            button
+        )));
+    }
+
     Ok(ClickCmd {
         target: target.unwrap(), // TODO: Error if missing (grammar enforces it though)
+        all,
         double,
         right,
         middle,
@@ -361,6 +484,7 @@ fn parse_click(pair: Pair<Rule>) -> Result<ClickCmd, ParseError> {
         shift,
         alt,
         timeout,
+        wait_after,
     })
 }
 
@@ -370,21 +494,28 @@ fn parse_type(pair: Pair<Rule>) -> Result<TypeCmd, ParseError> {
     let mut append = false;
     let mut enter = false;
     let mut clear = false;
+    let mut then_tab = false;
+    let mut secret = false;
     let mut delay = None;
     let mut timeout = None;
+    let mut wait_after = None;
 
     for inner in pair.into_inner() {
         match inner.as_rule() {
             Rule::target => target = Some(parse_target(inner)?),
             Rule::string_value => text = parse_string(inner),
             Rule::timeout_opt => timeout = Some(parse_timeout(inner)?),
+            Rule::wait_after_opt => wait_after = Some(parse_wait_after(inner)?),
             Rule::number => delay = Some(parse_number(inner)?),
-            _ => match inner.as_str() {
+            Rule::type_flag => match inner.as_str() {
                 "--append" => append = true,
                 "--enter" => enter = true,
                 "--clear" => clear = true,
+                "--then-tab" => then_tab = true,
+                "--secret" => secret = true,
                 _ => {}
             },
+            _ => {}
         }
     }
     Ok(TypeCmd {
@@ -394,7 +525,10 @@ fn parse_type(pair: Pair<Rule>) -> Result<TypeCmd, ParseError> {
         enter,
         delay,
         clear,
+        then_tab,
         timeout,
+        wait_after,
+        secret,
     })
 }
 
@@ -441,7 +575,14 @@ fn parse_select(pair: Pair<Rule>) -> Result<SelectCmd, ParseError> {
     } else {
         val_pair.as_str().to_string()
     };
-    Ok(SelectCmd { target, value })
+    let custom = inner
+        .next()
+        .is_some_and(|p| p.as_rule() == Rule::select_custom);
+    Ok(SelectCmd {
+        target,
+        value,
+        custom,
+    })
 }
 
 fn parse_check(pair: Pair<Rule>) -> Result<CheckCmd, ParseError> {
@@ -455,8 +596,12 @@ fn parse_uncheck(pair: Pair<Rule>) -> Result<UncheckCmd, ParseError> {
 }
 
 fn parse_hover(pair: Pair<Rule>) -> Result<HoverCmd, ParseError> {
-    let target = parse_target(pair.into_inner().next().unwrap())?;
-    Ok(HoverCmd { target })
+    let mut inner = pair.into_inner();
+    let target = parse_target(inner.next().unwrap())?;
+    let duration = inner
+        .next()
+        .map(|d| d.into_inner().next().unwrap().as_str().to_string());
+    Ok(HoverCmd { target, duration })
 }
 
 fn parse_focus(pair: Pair<Rule>) -> Result<FocusCmd, ParseError> {
@@ -469,6 +614,7 @@ fn parse_scroll(pair: Pair<Rule>) -> Result<ScrollCmd, ParseError> {
         direction: None,
         amount: None,
         page: false,
+        smooth: false,
         timeout: None,
         target: None,
     };
@@ -478,11 +624,9 @@ fn parse_scroll(pair: Pair<Rule>) -> Result<ScrollCmd, ParseError> {
             Rule::number => cmd.amount = Some(parse_number(inner)?),
             Rule::target => cmd.target = Some(parse_target(inner)?),
             Rule::timeout_opt => cmd.timeout = Some(parse_timeout(inner)?),
-            _ => {
-                if inner.as_str() == "--page" {
-                    cmd.page = true;
-                }
-            }
+            Rule::scroll_page => cmd.page = true,
+            Rule::scroll_smooth => cmd.smooth = true,
+            _ => {}
         }
     }
     Ok(cmd)
@@ -508,6 +652,44 @@ fn parse_wait(pair: Pair<Rule>) -> Result<WaitCmd, ParseError> {
         .map(|p| parse_timeout(p.clone()))
         .transpose()?;
 
+    let quiet = inners
+        .iter()
+        .find(|p| p.as_rule() == Rule::quiet_opt)
+        .map(|p| parse_timeout(p.clone()))
+        .transpose()?;
+
+    Ok(WaitCmd {
+        condition: parse_wait_condition(lower_text, &inners)?,
+        timeout,
+        quiet,
+    })
+}
+
+fn parse_assert(pair: Pair<Rule>) -> Result<AssertCmd, ParseError> {
+    let check = pair.into_inner().next().unwrap();
+    let rule = check.as_rule();
+    let mut parts = check.into_inner();
+    let condition = match rule {
+        Rule::assert_visible => AssertCondition::Visible(parse_target(parts.next().unwrap())?),
+        Rule::assert_text => AssertCondition::TextContains {
+            target: Target {
+                atomic: parse_target_atomic(parts.next().unwrap())?,
+                relation: None,
+                state: None,
+            },
+            expected: parse_string(parts.next().unwrap()),
+        },
+        _ => AssertCondition::UrlMatches(parse_string(parts.next().unwrap())),
+    };
+    Ok(AssertCmd { condition })
+}
+
+/// Build a wait condition from its source text, starting at the condition
+/// keyword, and the pairs the grammar produced for it.
+fn parse_wait_condition(
+    lower_text: &str,
+    inners: &[Pair<Rule>],
+) -> Result<WaitCondition, ParseError> {
     let find_target = || inners.iter().find(|p| p.as_rule() == Rule::target);
     let find_string = || inners.iter().find(|p| p.as_rule() == Rule::string_value);
 
@@ -519,6 +701,28 @@ fn parse_wait(pair: Pair<Rule>) -> Result<WaitCmd, ParseError> {
         WaitCondition::Navigation
     } else if lower_text.starts_with("ready") {
         WaitCondition::Ready
+    } else if let Some(group) = inners.iter().find(|p| p.as_rule() == Rule::wait_group) {
+        let conditions = group
+            .clone()
+            .into_inner()
+            .map(|sub| {
+                let inners: Vec<Pair<Rule>> = sub.clone().into_inner().collect();
+                parse_wait_condition(sub.as_str(), &inners)
+            })
+            .collect::<Result<_, _>>()?;
+        if lower_text.starts_with("all") {
+            WaitCondition::All(conditions)
+        } else {
+            WaitCondition::Any(conditions)
+        }
+    } else if let Some(any_of) = inners.iter().find(|p| p.as_rule() == Rule::wait_any_of) {
+        WaitCondition::AnyOf(
+            any_of
+                .clone()
+                .into_inner()
+                .map(parse_target)
+                .collect::<Result<_, _>>()?,
+        )
     } else if lower_text.starts_with("visible") {
         find_target()
             .map(|t| parse_target(t.clone()))
@@ -543,38 +747,64 @@ fn parse_wait(pair: Pair<Rule>) -> Result<WaitCmd, ParseError> {
         find_string()
             .map(|s| WaitCondition::Url(parse_string(s.clone())))
             .unwrap_or(WaitCondition::Load)
+    } else if let Some(text_of) = inners.iter().find(|p| p.as_rule() == Rule::wait_text_of) {
+        let mut parts = text_of.clone().into_inner();
+        let target = Target {
+            atomic: parse_target_atomic(parts.next().unwrap())?,
+            relation: None,
+            state: None,
+        };
+        let comparison = match parts.next().unwrap().as_str() {
+            "==" => TextComparison::Equals,
+            _ => TextComparison::Contains,
+        };
+        WaitCondition::TextOf {
+            target,
+            comparison,
+            expected: parse_string(parts.next().unwrap()),
+        }
     } else if lower_text.starts_with("until") {
         find_string()
             .map(|s| WaitCondition::Until(parse_string(s.clone())))
             .unwrap_or(WaitCondition::Load)
-    } else if lower_text.starts_with("items") {
-        let selector = find_string().map(|s| parse_string(s.clone()));
-        let count = inners
-            .iter()
-            .find(|p| p.as_rule() == Rule::number)
-            .map(|n| parse_number(n.clone()))
-            .transpose()?;
-        match (selector, count) {
-            (Some(s), Some(c)) => WaitCondition::Items {
-                selector: s,
-                count: c,
-            },
-            _ => WaitCondition::Load,
+    } else if let Some(items) = inners.iter().find(|p| p.as_rule() == Rule::wait_items) {
+        let mut cond = ItemsCond {
+            selector: String::new(),
+            op: CountComparison::AtLeast,
+            count: 0,
+        };
+        for part in items.clone().into_inner() {
+            match part.as_rule() {
+                Rule::string_value => cond.selector = parse_string(part),
+                Rule::count_comparison => {
+                    cond.op = match part.as_str() {
+                        "<=" => CountComparison::AtMost,
+                        ">" => CountComparison::MoreThan,
+                        "<" => CountComparison::FewerThan,
+                        "==" => CountComparison::Equals,
+                        _ => CountComparison::AtLeast,
+                    }
+                }
+                Rule::number => cond.count = parse_number(part)?.max(0.0).round() as u64,
+                _ => {}
+            }
         }
+        WaitCondition::Items(cond)
     } else {
         WaitCondition::Load
     };
-
-    Ok(WaitCmd { condition, timeout })
+    Ok(condition)
 }
 
 fn parse_extract(pair: Pair<Rule>) -> Result<ExtractCmd, ParseError> {
     let mut what = ExtractWhat::Text;
     let mut selector = None;
     let mut format = None;
+    let mut same_origin = false;
 
     for inner in pair.into_inner() {
         match inner.as_rule() {
+            Rule::same_origin_flag => same_origin = true,
             Rule::extract_css => {
                 what = ExtractWhat::Css(parse_string(inner.into_inner().next().unwrap()));
             }
@@ -582,28 +812,35 @@ fn parse_extract(pair: Pair<Rule>) -> Result<ExtractCmd, ParseError> {
                 selector = Some(parse_string(inner.into_inner().next().unwrap()));
             }
             Rule::output_format => format = Some(inner.as_str().to_string()),
-            _ => {
+            Rule::extract_source => {
                 what = match inner.as_str() {
                     "links" => ExtractWhat::Links,
                     "images" => ExtractWhat::Images,
                     "tables" => ExtractWhat::Tables,
                     "meta" => ExtractWhat::Meta,
-                    _ => what,
+                    _ => ExtractWhat::Text,
                 };
             }
+            _ => {}
         }
     }
+    if same_origin && what != ExtractWhat::Links {
+        return Err(ParseError::ConflictingFlags(
+            "--same-origin only applies to extract links".into(),
+        ));
+    }
     Ok(ExtractCmd {
         what,
         selector,
         format,
+        same_origin,
     })
 }
 
 // --- Common Helpers ---
 
 fn parse_target(pair: Pair<Rule>) -> Result<Target, ParseError> {
-    // target = { target_atomic ~ (WSP+ ~ relation ~ WSP+ ~ target_atomic)* }
+    // target = { target_atomic ~ (WSP+ ~ (between_relation | relation ~ WSP+ ~ target_atomic))* }
     // This is flat. We need to build right-associative chain.
     // A near B inside C -> A near (B inside C)
 
@@ -612,7 +849,34 @@ fn parse_target(pair: Pair<Rule>) -> Result<Target, ParseError> {
 
     // Collect rest: (relation, atomic) pairs
     let mut rest = Vec::new();
+    let mut state: Option<StateFilter> = None;
     while let Some(rel) = inners.next() {
+        if rel.as_rule() == Rule::target_state {
+            let filter = state.get_or_insert_with(StateFilter::default);
+            match rel.as_str() {
+                "--enabled" => filter.enabled = Some(true),
+                "--disabled" => filter.enabled = Some(false),
+                "--checked" => filter.checked = Some(true),
+                "--unchecked" => filter.checked = Some(false),
+                "--visible" => filter.visible = Some(true),
+                "--hidden" => filter.visible = Some(false),
+                _ => filter.focused = Some(true),
+            }
+            continue;
+        }
+        if rel.as_rule() == Rule::between_relation {
+            // between_relation = { "between" ~ start ~ "and" ~ end }
+            let mut anchors = rel.into_inner();
+            let start = parse_target_atomic(anchors.next().unwrap())?;
+            let end = Target {
+                atomic: parse_target_atomic(anchors.next().unwrap())?,
+                relation: None,
+                state: None,
+            };
+            rest.push((RelationKind::Between(Box::new(end)), start));
+            continue;
+        }
+
         let atom = inners.next().unwrap(); // Must exist
                                            // rel is `relation` rule
         let kind = match rel.as_str() {
@@ -653,6 +917,7 @@ fn parse_target(pair: Pair<Rule>) -> Result<Target, ParseError> {
             return Target {
                 atomic: head,
                 relation: None,
+                state: None,
             };
         }
 
@@ -665,6 +930,7 @@ fn parse_target(pair: Pair<Rule>) -> Result<Target, ParseError> {
                 kind: rel,
                 target: build_chain(next_atomic, next_tail),
             })),
+            state: None,
         }
     }
 
@@ -680,19 +946,44 @@ fn parse_target(pair: Pair<Rule>) -> Result<Target, ParseError> {
     // So A is near B. And B is inside C.
     // Yes.
 
-    Ok(build_chain(first, rest))
+    Ok(Target {
+        state,
+        ..build_chain(first, rest)
+    })
 }
 
 fn parse_target_atomic(pair: Pair<Rule>) -> Result<TargetAtomic, ParseError> {
-    // target_atomic = _{ target_selector | target_role | target_id | target_text }
+    // target_atomic = _{ target_selector | target_testid | target_role_text | target_role | target_id | target_text }
 
     // inner is the specific rule
     match pair.as_rule() {
         Rule::target_id => Ok(TargetAtomic::Id(pair.as_str().parse().unwrap())),
-        Rule::target_text => Ok(TargetAtomic::Text(parse_string(
+        Rule::target_text => {
+            let mut inner = pair.into_inner();
+            let first = inner.next().unwrap();
+            if first.as_rule() != Rule::text_match_mode {
+                return Ok(TargetAtomic::Text(parse_string(first)));
+            }
+            let mode = match first.as_str() {
+                "=" => TextMatchMode::Exact,
+                "^" => TextMatchMode::Prefix,
+                _ => TextMatchMode::Suffix,
+            };
+            Ok(TargetAtomic::TextMatch(TextMatch {
+                value: parse_string(inner.next().unwrap()),
+                mode,
+            }))
+        }
+        Rule::target_role => Ok(TargetAtomic::Role(pair.as_str().to_string())),
+        Rule::target_role_text => {
+            let mut inner = pair.into_inner();
+            let role = inner.next().unwrap().as_str().to_string();
+            let text = parse_string(inner.next().unwrap());
+            Ok(TargetAtomic::RoleText { role, text })
+        }
+        Rule::target_testid => Ok(TargetAtomic::TestId(parse_string(
             pair.into_inner().next().unwrap(),
         ))),
-        Rule::target_role => Ok(TargetAtomic::Role(pair.as_str().to_string())),
         Rule::target_selector => {
             // css(...) or xpath(...)
             let text = pair.as_str();
@@ -747,11 +1038,17 @@ fn parse_target_atomic(pair: Pair<Rule>) -> Result<TargetAtomic, ParseError> {
 
 fn parse_cookies(pair: Pair<Rule>) -> Result<CookiesCmd, ParseError> {
     let inner = pair.into_inner().next().unwrap();
+    let mut json = false;
     let action = match inner.as_rule() {
         Rule::cookies_list => CookiesAction::List,
         Rule::cookies_get => {
-            let name = parse_name_value(inner.into_inner().next().unwrap());
-            CookiesAction::Get(name)
+            let mut inners = inner.into_inner();
+            let which = inners.next().unwrap();
+            json = inners.next().is_some();
+            match which.as_rule() {
+                Rule::cookies_all => CookiesAction::GetAll,
+                _ => CookiesAction::Get(parse_name_value(which)),
+            }
         }
         Rule::cookies_set => {
             let mut inners = inner.into_inner();
@@ -764,9 +1061,23 @@ fn parse_cookies(pair: Pair<Rule>) -> Result<CookiesCmd, ParseError> {
             CookiesAction::Delete(name)
         }
         Rule::cookies_clear => CookiesAction::Clear,
+        Rule::cookies_export | Rule::cookies_import => {
+            let export = inner.as_rule() == Rule::cookies_export;
+            let mut inners = inner.into_inner();
+            let path = parse_file_path(inners.next().unwrap());
+            let format = inners
+                .next()
+                .and_then(|f| f.into_inner().next())
+                .map(|name| name.as_str().to_string());
+            if export {
+                CookiesAction::Export { path, format }
+            } else {
+                CookiesAction::Import { path, format }
+            }
+        }
         _ => return Err(ParseError::UnknownRule(inner.as_rule())),
     };
-    Ok(CookiesCmd { action })
+    Ok(CookiesCmd { action, json })
 }
 fn parse_storage(_pair: Pair<Rule>) -> Result<StorageCmd, ParseError> {
     Ok(StorageCmd {
@@ -775,9 +1086,46 @@ fn parse_storage(_pair: Pair<Rule>) -> Result<StorageCmd, ParseError> {
         session: false,
     })
 } // Stub
-fn parse_session_mgmt(_pair: Pair<Rule>) -> Result<SessionMgmtCmd, ParseError> {
-    Ok(SessionMgmtCmd { action: None })
-} // Stub
+fn parse_reset(pair: Pair<Rule>) -> ResetCmd {
+    let mut cmd = ResetCmd::default();
+    for flag in pair.into_inner() {
+        match flag.as_str() {
+            "--cookies" => cmd.cookies = true,
+            "--storage" => cmd.storage = true,
+            "--requests" => cmd.requests = true,
+            "--console" => cmd.console = true,
+            _ => cmd = ResetCmd::all(),
+        }
+    }
+    if cmd == ResetCmd::default() {
+        ResetCmd::all()
+    } else {
+        cmd
+    }
+}
+fn parse_session_mgmt(pair: Pair<Rule>) -> Result<SessionMgmtCmd, ParseError> {
+    let Some(inner) = pair.into_inner().next() else {
+        return Ok(SessionMgmtCmd { action: None });
+    };
+    let action = match inner.as_rule() {
+        Rule::session_new => {
+            let mut inners = inner.into_inner();
+            let name = inners.next().unwrap().as_str().to_string();
+            let mode = inners.next().map(|m| m.as_str().to_string());
+            SessionAction::New { name, mode }
+        }
+        Rule::session_close => SessionAction::Close(first_inner_str(inner)),
+        Rule::session_save => SessionAction::Save(first_inner_str(inner)),
+        Rule::session_load => SessionAction::Load(first_inner_str(inner)),
+        Rule::session_list => SessionAction::List,
+        Rule::session_delete => SessionAction::Delete(first_inner_str(inner)),
+        Rule::identifier => SessionAction::Switch(inner.as_str().to_string()),
+        _ => return Err(ParseError::UnknownRule(inner.as_rule())),
+    };
+    Ok(SessionMgmtCmd {
+        action: Some(action),
+    })
+}
 fn parse_state(_pair: Pair<Rule>) -> Result<StateCmd, ParseError> {
     Ok(StateCmd {
         action: StateAction::Load {
@@ -787,9 +1135,46 @@ fn parse_state(_pair: Pair<Rule>) -> Result<StateCmd, ParseError> {
         },
     })
 } // Stub
-fn parse_headers(_pair: Pair<Rule>) -> Result<HeadersCmd, ParseError> {
-    Ok(HeadersCmd { action: None })
-} // Stub
+fn parse_headers(pair: Pair<Rule>) -> Result<HeadersCmd, ParseError> {
+    let Some(inner) = pair.into_inner().next() else {
+        return Ok(HeadersCmd {
+            action: Some(HeadersAction::List),
+        });
+    };
+    let action = match inner.as_rule() {
+        Rule::headers_set => {
+            let mut args = inner.into_inner().next().unwrap().into_inner();
+            let first = args.next().unwrap();
+            match args.next() {
+                None => HeadersAction::Set {
+                    domain: None,
+                    json: parse_string(first),
+                },
+                Some(value) => {
+                    let key = parse_name_value(first);
+                    let value = parse_string(value);
+                    if value.trim_start().starts_with('{') {
+                        HeadersAction::Set {
+                            domain: Some(key),
+                            json: value,
+                        }
+                    } else {
+                        HeadersAction::SetHeader { name: key, value }
+                    }
+                }
+            }
+        }
+        Rule::headers_clear => {
+            HeadersAction::Clear(inner.into_inner().next().map(parse_name_value))
+        }
+        Rule::headers_list => HeadersAction::List,
+        Rule::domain_name => HeadersAction::Show(parse_name_value(inner)),
+        _ => return Err(ParseError::UnknownRule(inner.as_rule())),
+    };
+    Ok(HeadersCmd {
+        action: Some(action),
+    })
+}
 fn parse_tab_action(pair: Pair<Rule>) -> Result<TabActionCmd, ParseError> {
     let inner = pair.into_inner().next().unwrap();
     let action = match inner.as_rule() {
@@ -822,11 +1207,8 @@ fn parse_login(pair: Pair<Rule>) -> Result<LoginCmd, ParseError> {
         match inner.as_rule() {
             Rule::timeout_opt => cmd.timeout = Some(parse_timeout(inner)?),
             Rule::duration => cmd.wait = Some(inner.as_str().to_string()),
-            _ => {
-                if inner.as_str() == "--no-submit" {
-                    cmd.no_submit = true;
-                }
-            }
+            Rule::no_submit_flag => cmd.no_submit = true,
+            _ => {}
         }
     }
 
@@ -884,11 +1266,8 @@ fn parse_scroll_until(pair: Pair<Rule>) -> Result<ScrollUntilCmd, ParseError> {
             Rule::target => target = Some(parse_target(inner)?),
             Rule::number => amount = Some(parse_number(inner)?),
             Rule::timeout_opt => timeout = Some(parse_timeout(inner)?),
-            _ => {
-                if inner.as_str() == "--page" {
-                    page = true;
-                }
-            }
+            Rule::scroll_page => page = true,
+            _ => {}
         }
     }
 
@@ -900,8 +1279,19 @@ fn parse_scroll_until(pair: Pair<Rule>) -> Result<ScrollUntilCmd, ParseError> {
         timeout,
     })
 }
-fn parse_intents(_pair: Pair<Rule>) -> Result<IntentsCmd, ParseError> {
-    Ok(IntentsCmd { session: false })
+fn parse_intents(pair: Pair<Rule>) -> Result<IntentsCmd, ParseError> {
+    let mut cmd = IntentsCmd {
+        session: false,
+        json: false,
+    };
+    for flag in pair.into_inner() {
+        match flag.as_str() {
+            "--session" => cmd.session = true,
+            "--json" => cmd.json = true,
+            _ => {}
+        }
+    }
+    Ok(cmd)
 }
 fn parse_define(_pair: Pair<Rule>) -> Result<DefineCmd, ParseError> {
     Ok(DefineCmd { name: "".into() })
@@ -909,17 +1299,83 @@ fn parse_define(_pair: Pair<Rule>) -> Result<DefineCmd, ParseError> {
 fn parse_undefine(_pair: Pair<Rule>) -> Result<UndefineCmd, ParseError> {
     Ok(UndefineCmd { name: "".into() })
 }
-fn parse_export(_pair: Pair<Rule>) -> Result<ExportCmd, ParseError> {
-    Ok(ExportCmd {
-        name: "".into(),
+fn parse_export(pair: Pair<Rule>) -> Result<ExportCmd, ParseError> {
+    let mut cmd = ExportCmd {
+        name: String::new(),
         out: None,
-    })
+        format: None,
+    };
+    for inner in pair.into_inner() {
+        match inner.as_rule() {
+            Rule::identifier => cmd.name = inner.as_str().to_string(),
+            Rule::export_opt => {
+                let opt = inner.into_inner().next().unwrap();
+                let value = opt.into_inner().next().unwrap();
+                match value.as_rule() {
+                    Rule::file_path => cmd.out = Some(parse_file_path(value)),
+                    _ => cmd.format = Some(value.as_str().to_string()),
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(cmd)
 }
-fn parse_run(_pair: Pair<Rule>) -> Result<RunCmd, ParseError> {
-    Ok(RunCmd {
-        name: "".into(),
-        params: vec![],
-    })
+fn parse_run(pair: Pair<Rule>) -> Result<RunCmd, ParseError> {
+    let mut stages = Vec::new();
+    let mut keep_going = false;
+    let mut json = false;
+    let mut screenshot_on_error = false;
+    for inner in pair.into_inner() {
+        match inner.as_rule() {
+            Rule::run_stage => stages.push(parse_run_stage(inner)),
+            Rule::run_continue => keep_going = true,
+            Rule::run_json => json = true,
+            Rule::run_screenshot => screenshot_on_error = true,
+            _ => {}
+        }
+    }
+    let mut run = stages.remove(0);
+    run.then = stages;
+    run.keep_going = keep_going;
+    run.json = json;
+    run.screenshot_on_error = screenshot_on_error;
+    Ok(run)
+}
+
+fn parse_run_stage(pair: Pair<Rule>) -> RunCmd {
+    let mut inner = pair.into_inner();
+    let name = inner.next().unwrap().as_str().to_string();
+    let mut params = Vec::new();
+    for param in inner {
+        match param.as_rule() {
+            Rule::named_param | Rule::param_pair => {
+                let mut parts = param.into_inner();
+                let key = parts.next().unwrap().as_str().to_string();
+                params.push((key, parse_param_value(parts.next().unwrap())));
+            }
+            Rule::param_value => params.push((String::new(), parse_param_value(param))),
+            _ => {}
+        }
+    }
+    RunCmd {
+        name,
+        params,
+        then: Vec::new(),
+        keep_going: false,
+        json: false,
+        screenshot_on_error: false,
+    }
+}
+
+fn parse_param_value(pair: Pair<Rule>) -> String {
+    // param_value = { string_value | number | identifier }
+    let inner = pair.into_inner().next().unwrap();
+    if inner.as_rule() == Rule::string_value {
+        parse_string(inner)
+    } else {
+        inner.as_str().to_string()
+    }
 }
 fn parse_pack_action(_pair: Pair<Rule>) -> Result<PackActionCmd, ParseError> {
     Ok(PackActionCmd {
@@ -927,17 +1383,113 @@ fn parse_pack_action(_pair: Pair<Rule>) -> Result<PackActionCmd, ParseError> {
         name: "".into(),
     })
 }
-fn parse_intercept(_pair: Pair<Rule>) -> Result<InterceptCmd, ParseError> {
-    Ok(InterceptCmd {
-        rule: InterceptRule::Clear(None),
-    })
+fn parse_intercept(pair: Pair<Rule>) -> Result<InterceptCmd, ParseError> {
+    let inner = pair.into_inner().next().unwrap();
+    let rule = inner.as_rule();
+    let mut parts = inner.into_inner();
+    if rule == Rule::intercept_clear {
+        return Ok(InterceptCmd::Clear(parts.next().map(parse_string)));
+    }
+
+    let url_pattern = parse_string(parts.next().unwrap());
+    let action = match rule {
+        Rule::intercept_block => InterceptAction::Block,
+        Rule::intercept_continue => InterceptAction::Continue,
+        Rule::intercept_mock => {
+            let status = parse_status(parts.next().unwrap())?;
+            let mut body = String::new();
+            let mut content_type = None;
+            for part in parts {
+                match part.as_rule() {
+                    Rule::mock_body => {
+                        let value = part.into_inner().next().unwrap();
+                        body = match value.as_rule() {
+                            Rule::string_value => parse_string(value),
+                            _ => value.into_inner().next().unwrap().as_str().to_string(),
+                        };
+                    }
+                    Rule::content_type_opt => {
+                        content_type = Some(parse_string(part.into_inner().next().unwrap()))
+                    }
+                    _ => {}
+                }
+            }
+            InterceptAction::Mock {
+                status,
+                body,
+                content_type,
+            }
+        }
+        // Flag form: no flags records only and --block stands alone;
+        // --respond, --respond-file or --status mock (200 unless --status
+        // says otherwise).
+        _ => {
+            let mut block = false;
+            let mut status = None;
+            let mut body = None;
+            let mut file = None;
+            for opt in parts {
+                match opt.as_rule() {
+                    Rule::intercept_block_flag => block = true,
+                    Rule::intercept_respond => {
+                        body = Some(parse_string(opt.into_inner().next().unwrap()))
+                    }
+                    Rule::intercept_respond_file => {
+                        file = Some(parse_file_path(opt.into_inner().next().unwrap()))
+                    }
+                    Rule::intercept_status => {
+                        status = Some(parse_status(opt.into_inner().next().unwrap())?)
+                    }
+                    _ => {}
+                }
+            }
+            if block && (status.is_some() || body.is_some() || file.is_some()) {
+                return Err(ParseError::ConflictingFlags(
+                    "--block fails the request, so it takes no --status, --respond or --respond-file"
+                        .into(),
+                ));
+            }
+            match (block, file, body, status) {
+                (true, ..) => InterceptAction::Block,
+                (_, Some(path), _, status) => InterceptAction::MockFile {
+                    status: status.unwrap_or(200),
+                    path,
+                    content_type: None,
+                },
+                (_, None, None, None) => InterceptAction::Continue,
+                (_, None, body, status) => InterceptAction::Mock {
+                    status: status.unwrap_or(200),
+                    body: body.unwrap_or_default(),
+                    content_type: None,
+                },
+            }
+        }
+    };
+    Ok(InterceptCmd::Rule(InterceptRule {
+        url_pattern,
+        action,
+    }))
+}
+fn parse_status(pair: Pair<Rule>) -> Result<u16, ParseError> {
+    pair.as_str().parse().map_err(ParseError::InvalidInteger)
 }
-fn parse_requests(_pair: Pair<Rule>) -> Result<RequestsCmd, ParseError> {
-    Ok(RequestsCmd {
+fn parse_requests(pair: Pair<Rule>) -> Result<RequestsCmd, ParseError> {
+    let mut cmd = RequestsCmd {
         filter: None,
         method: None,
         last: None,
-    })
+    };
+    for opt in pair.into_inner() {
+        let rule = opt.as_rule();
+        let value = opt.into_inner().next().unwrap();
+        match rule {
+            Rule::requests_filter => cmd.filter = Some(parse_string(value)),
+            Rule::requests_method => cmd.method = Some(value.as_str().to_string()),
+            Rule::requests_last => cmd.last = Some(parse_number(value)?),
+            _ => {}
+        }
+    }
+    Ok(cmd)
 }
 fn parse_console(_pair: Pair<Rule>) -> Result<ConsoleCmd, ParseError> {
     Ok(ConsoleCmd {
@@ -953,10 +1505,27 @@ fn parse_errors(_pair: Pair<Rule>) -> Result<ErrorsCmd, ParseError> {
         last: None,
     })
 }
-fn parse_frame(_pair: Pair<Rule>) -> Result<FrameSwitchCmd, ParseError> {
-    Ok(FrameSwitchCmd {
-        target: FrameTarget::Main,
-    })
+fn parse_frame(pair: Pair<Rule>) -> Result<FrameSwitchCmd, ParseError> {
+    let inner = pair.into_inner().next().unwrap();
+    let target = match inner.as_rule() {
+        Rule::frame_keyword if inner.as_str() == "main" => FrameTarget::Main,
+        Rule::frame_keyword => FrameTarget::Parent,
+        Rule::frame_url => {
+            FrameTarget::UrlContains(parse_string(inner.into_inner().next().unwrap()))
+        }
+        Rule::frame_name => {
+            let name = parse_string(inner.into_inner().next().unwrap());
+            // `frame "#payment"` names the iframe by selector, as frame names
+            // never start with `#` or `.` in practice.
+            if name.starts_with('#') || name.starts_with('.') {
+                FrameTarget::Target(Target::css(name))
+            } else {
+                FrameTarget::Name(name)
+            }
+        }
+        _ => FrameTarget::Target(parse_target(inner)?),
+    };
+    Ok(FrameSwitchCmd { target })
 }
 fn parse_dialog(_pair: Pair<Rule>) -> Result<DialogCmd, ParseError> {
     Ok(DialogCmd {
@@ -991,41 +1560,53 @@ fn parse_record(_pair: Pair<Rule>) -> Result<RecordCmd, ParseError> {
         quality: None,
     })
 }
-fn parse_highlight(_pair: Pair<Rule>) -> Result<HighlightCmd, ParseError> {
-    Ok(HighlightCmd {
+fn parse_highlight(pair: Pair<Rule>) -> Result<HighlightCmd, ParseError> {
+    let mut cmd = HighlightCmd {
         clear: false,
         target: None,
         duration: None,
         color: None,
-    })
+        persist: false,
+    };
+    for inner in pair.into_inner() {
+        match inner.as_rule() {
+            Rule::highlight_clear => cmd.clear = true,
+            Rule::target => cmd.target = Some(parse_target(inner)?),
+            Rule::highlight_duration => {
+                cmd.duration = Some(inner.into_inner().next().unwrap().as_str().to_string())
+            }
+            Rule::highlight_color => {
+                let value = inner.into_inner().next().unwrap();
+                cmd.color = Some(match value.as_rule() {
+                    Rule::string_value => parse_string(value),
+                    _ => value.as_str().to_string(),
+                });
+            }
+            Rule::highlight_persist => cmd.persist = true,
+            _ => {}
+        }
+    }
+    Ok(cmd)
 }
 fn parse_pdf(pair: Pair<Rule>) -> Result<PdfCmd, ParseError> {
     let mut path = String::new();
     let mut format = None;
     let mut landscape = false;
     let mut margin = None;
+    let mut scale = None;
 
     for inner in pair.into_inner() {
         match inner.as_rule() {
             Rule::file_path => path = parse_file_path(inner),
             Rule::paper_format => format = Some(inner.as_str().to_string()),
-            Rule::number | Rule::string_value => {
-                // Assume this is margin, as format and path are handled.
-                // margin takes number or string.
-                if margin.is_none() {
-                    // Check if it's number
-                    if inner.as_rule() == Rule::number {
-                        margin = Some(inner.as_str().to_string());
-                    } else {
-                        margin = Some(parse_string(inner));
-                    }
-                }
+            Rule::pdf_landscape => landscape = true,
+            Rule::pdf_margin => {
+                margin = Some(parse_number(inner.into_inner().next().unwrap())? as f32)
             }
-            _ => {
-                if inner.as_str() == "--landscape" {
-                    landscape = true;
-                }
+            Rule::pdf_scale => {
+                scale = Some(parse_number(inner.into_inner().next().unwrap())? as f32)
             }
+            _ => {}
         }
     }
     Ok(PdfCmd {
@@ -1033,6 +1614,7 @@ fn parse_pdf(pair: Pair<Rule>) -> Result<PdfCmd, ParseError> {
         format,
         landscape,
         margin,
+        scale,
     })
 }
 fn parse_learn(_pair: Pair<Rule>) -> Result<LearnCmd, ParseError> {
@@ -1041,10 +1623,61 @@ fn parse_learn(_pair: Pair<Rule>) -> Result<LearnCmd, ParseError> {
         name: None,
     })
 }
+fn parse_eval(pair: Pair<Rule>) -> Result<EvalCmd, ParseError> {
+    let mut inner = pair.into_inner();
+    let script = parse_string(inner.next().unwrap());
+    let mut args = Vec::new();
+    for arg in inner {
+        let mut parts = arg.into_inner();
+        let name = parts.next().unwrap().as_str().to_string();
+        let value = parts.next().unwrap().into_inner().next().unwrap();
+        let value = match value.as_rule() {
+            Rule::string_value => EvalArg::Value(serde_json::Value::String(parse_string(value))),
+            Rule::number => match value.as_str().parse::<i64>() {
+                Ok(n) => EvalArg::Value(n.into()),
+                Err(_) => EvalArg::Value(parse_number(value)?.into()),
+            },
+            Rule::variable_ref => EvalArg::Variable(value.as_str()[1..].to_string()),
+            _ => match value.as_str() {
+                "true" => EvalArg::Value(serde_json::Value::Bool(true)),
+                "false" => EvalArg::Value(serde_json::Value::Bool(false)),
+                "null" => EvalArg::Value(serde_json::Value::Null),
+                other => EvalArg::Value(serde_json::Value::String(other.to_string())),
+            },
+        };
+        args.push((name, value));
+    }
+    Ok(EvalCmd { script, args })
+}
+
+fn parse_set(pair: Pair<Rule>) -> Result<SetCmd, ParseError> {
+    let mut inner = pair.into_inner();
+    let name = inner.next().unwrap().as_str().to_string();
+    let eval = parse_eval(inner.next().unwrap())?;
+    Ok(SetCmd { name, eval })
+}
+
 fn parse_help(_pair: Pair<Rule>) -> Result<HelpCmd, ParseError> {
     Ok(HelpCmd { topic: None })
 }
 
+fn parse_repeat(pair: Pair<Rule>) -> Result<RepeatCmd, ParseError> {
+    let mut inner = pair.into_inner();
+    let count = inner
+        .next()
+        .unwrap()
+        .as_str()
+        .parse()
+        .map_err(ParseError::InvalidInteger)?;
+    let body = inner
+        .next()
+        .unwrap()
+        .into_inner()
+        .map(parse_command)
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(RepeatCmd { count, body })
+}
+
 // Helpers
 
 fn parse_string(pair: Pair<Rule>) -> String {
@@ -1083,11 +1716,28 @@ fn parse_number(pair: Pair<Rule>) -> Result<f64, ParseError> {
 }
 
 fn parse_timeout(pair: Pair<Rule>) -> Result<String, ParseError> {
-    // timeout_opt = { "--timeout" ~ WSP+ ~ duration }
+    // timeout_opt = { "--timeout" ~ WSP+ ~ duration }, and likewise quiet_opt
     // return duration string
     Ok(pair.into_inner().next().unwrap().as_str().to_string())
 }
 
+fn parse_wait_after(pair: Pair<Rule>) -> Result<u64, ParseError> {
+    // wait_after_value = digits with an optional ms/s/m unit
+    let value = pair.into_inner().next().unwrap().as_str();
+    let unit_at = value
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(value.len());
+    let amount: u64 = value[..unit_at]
+        .parse()
+        .map_err(ParseError::InvalidInteger)?;
+    let scale = match &value[unit_at..] {
+        "s" => 1000,
+        "m" => 60_000,
+        _ => 1,
+    };
+    Ok(amount.saturating_mul(scale))
+}
+
 fn parse_file_path(pair: Pair<Rule>) -> String {
     // file_path = { string_value | path_bare }
     let inner = pair.into_inner().next().unwrap();
@@ -1097,6 +1747,9 @@ fn parse_file_path(pair: Pair<Rule>) -> String {
         inner.as_str().to_string()
     }
 }
+fn first_inner_str(pair: Pair<Rule>) -> String {
+    pair.into_inner().next().unwrap().as_str().to_string()
+}
 fn parse_name_value(pair: Pair<Rule>) -> String {
     // name_value = { identifier | string_value }
     let inner = pair.into_inner().next().unwrap();
@@ -1107,6 +1760,1330 @@ fn parse_name_value(pair: Pair<Rule>) -> String {
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_text_cmd(input: &str) -> TextCmd {
+        let script = parse(input).expect("parse failed");
+        match script.lines[0].command.clone() {
+            Some(Command::Text(cmd)) => cmd,
+            other => panic!("Expected text command, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_text_defaults_to_collapse() {
+        assert_eq!(parse_text_cmd("text").mode, TextMode::Collapse);
+    }
+
+    #[test]
+    fn test_text_mode_flags() {
+        assert_eq!(parse_text_cmd("text --raw").mode, TextMode::Raw);
+        assert_eq!(parse_text_cmd("text --trim").mode, TextMode::Trim);
+        assert_eq!(
+            parse_text_cmd(r#"text --selector "main" --collapse"#).mode,
+            TextMode::Collapse
+        );
+
+        let cmd = parse_text_cmd(r#"text "Intro" --raw"#);
+        assert_eq!(cmd.mode, TextMode::Raw);
+        assert!(cmd.target.is_some());
+    }
+
+    #[test]
+    fn test_between_relation() {
+        let script = parse(&crate::normalize(
+            r#"click "input" between "First" and "Last""#,
+        ))
+        .expect("parse failed");
+        let Some(Command::Click(cmd)) = script.lines[0].command.clone() else {
+            panic!("Expected click command");
+        };
+        assert_eq!(cmd.target.atomic, TargetAtomic::Text("input".into()));
+        let relation = cmd.target.relation.expect("relation");
+        assert_eq!(relation.target.atomic, TargetAtomic::Text("First".into()));
+        let RelationKind::Between(end) = relation.kind else {
+            panic!("Expected between relation, got {:?}", relation.kind);
+        };
+        assert_eq!(end.atomic, TargetAtomic::Text("Last".into()));
+
+        let resolver_target = Target {
+            atomic: TargetAtomic::Text("input".into()),
+            relation: Some(Box::new(TargetRelation {
+                kind: RelationKind::Between(end),
+                target: relation.target,
+            })),
+            state: None,
+        }
+        .to_resolver_target();
+        assert!(matches!(
+            resolver_target,
+            oryn_common::resolver::Target::Between { .. }
+        ));
+    }
+
+    #[test]
+    fn test_text_match_modifiers() {
+        for (input, mode) in [
+            (r#"click ="Save""#, TextMatchMode::Exact),
+            (r#"click ^"Save""#, TextMatchMode::Prefix),
+            (r#"click $"Save""#, TextMatchMode::Suffix),
+        ] {
+            let script = parse(input).unwrap();
+            let Some(Command::Click(cmd)) = &script.lines[0].command else {
+                panic!("Expected click command for {}", input);
+            };
+            assert_eq!(cmd.target, Target::text_matching("Save", mode));
+        }
+
+        // Without a modifier the target keeps contains matching
+        let script = parse(r#"click "Save" near ="Name""#).unwrap();
+        let Some(Command::Click(cmd)) = &script.lines[0].command else {
+            panic!("Expected click command");
+        };
+        assert_eq!(
+            cmd.target,
+            Target::text("Save").near(Target::text_matching("Name", TextMatchMode::Exact))
+        );
+
+        // The modifier must touch the quote
+        assert!(parse(r#"click = "Save""#).is_err());
+    }
+
+    #[test]
+    fn test_frame_targets() {
+        let frame = |input: &str| match parse(input).unwrap().lines[0].command.clone() {
+            Some(Command::Frame(cmd)) => cmd.target,
+            other => panic!("Expected frame command for {}, got {:?}", input, other),
+        };
+        assert_eq!(frame("frame main"), FrameTarget::Main);
+        assert_eq!(frame("frame parent"), FrameTarget::Parent);
+        assert_eq!(
+            frame(r#"frame "checkout""#),
+            FrameTarget::Name("checkout".into())
+        );
+        assert_eq!(
+            frame(r#"frame url "stripe.com""#),
+            FrameTarget::UrlContains("stripe.com".into())
+        );
+        assert_eq!(frame("frame 3"), FrameTarget::Target(Target::id(3)));
+        assert_eq!(
+            frame(r##"frame "#iframe-id""##),
+            FrameTarget::Target(Target::css("#iframe-id"))
+        );
+        assert_eq!(
+            frame(r#"frame "Modal" near "Checkout""#),
+            FrameTarget::Target(Target::text("Modal").near(Target::text("Checkout")))
+        );
+        assert!(matches!(
+            parse("frames").unwrap().lines[0].command,
+            Some(Command::Frames)
+        ));
+    }
+
+    #[test]
+    fn test_highlight_options() {
+        let highlight = |input: &str| match parse(input).unwrap().lines[0].command.clone() {
+            Some(Command::Highlight(cmd)) => cmd,
+            other => panic!("Expected highlight command for {}, got {:?}", input, other),
+        };
+
+        let cmd = highlight(r#"highlight "Submit" --color red --duration 2000 --persist"#);
+        assert_eq!(cmd.target, Some(Target::text("Submit")));
+        assert_eq!(cmd.color.as_deref(), Some("red"));
+        assert_eq!(cmd.duration.as_deref(), Some("2000"));
+        assert!(cmd.persist);
+        assert!(!cmd.clear);
+
+        let cmd = highlight(r##"highlight 4 --duration 2s --color "#ff8800""##);
+        assert_eq!(cmd.target, Some(Target::id(4)));
+        assert_eq!(cmd.color.as_deref(), Some("#ff8800"));
+        assert_eq!(cmd.duration.as_deref(), Some("2s"));
+        assert!(!cmd.persist);
+
+        let cmd = highlight("highlight clear");
+        assert!(cmd.clear);
+        assert_eq!(cmd.target, None);
+    }
+
+    #[test]
+    fn test_hover_duration() {
+        let hover = |input: &str| match parse(input).unwrap().lines[0].command.clone() {
+            Some(Command::Hover(cmd)) => cmd,
+            other => panic!("Expected hover command for {}, got {:?}", input, other),
+        };
+
+        let cmd = hover(r#"hover "Menu" --duration 500"#);
+        assert_eq!(cmd.target, Target::text("Menu"));
+        assert_eq!(cmd.duration.as_deref(), Some("500"));
+
+        assert_eq!(
+            hover("hover 5 --duration 2s").duration.as_deref(),
+            Some("2s")
+        );
+        assert_eq!(hover("hover 5").duration, None);
+
+        // Only whole, non-negative amounts can be held for.
+        assert!(parse("hover 5 --duration 1.5").is_err());
+        assert!(parse("hover 5 --duration -5").is_err());
+        assert!(parse("hover 5 --duration 1.5s").is_err());
+    }
+
+    #[test]
+    fn test_target_state_suffix() {
+        let script = parse(r#"click "Submit" --enabled --visible --double"#).unwrap();
+        let Some(Command::Click(cmd)) = &script.lines[0].command else {
+            panic!("Expected click command");
+        };
+        assert!(cmd.double);
+        assert_eq!(cmd.target.atomic, TargetAtomic::Text("Submit".into()));
+        assert_eq!(
+            cmd.target.state,
+            Some(StateFilter {
+                enabled: Some(true),
+                visible: Some(true),
+                ..Default::default()
+            })
+        );
+        assert!(matches!(
+            cmd.target.to_resolver_target(),
+            oryn_common::resolver::Target::State { .. }
+        ));
+
+        // The state applies to the whole chain, not the anchor
+        let script = parse(r#"check "Remember me" near "Password" --unchecked"#).unwrap();
+        let Some(Command::Check(cmd)) = &script.lines[0].command else {
+            panic!("Expected check command");
+        };
+        assert_eq!(cmd.target.state.and_then(|s| s.checked), Some(false));
+        assert_eq!(cmd.target.relation.as_ref().unwrap().target.state, None);
+
+        let script = parse(r#"click "Submit""#).unwrap();
+        let Some(Command::Click(cmd)) = &script.lines[0].command else {
+            panic!("Expected click command");
+        };
+        assert_eq!(cmd.target.state, None);
+    }
+
+    #[test]
+    fn test_intents_flags() {
+        let parse_intents_cmd = |input: &str| match parse(input).unwrap().lines[0].command.clone() {
+            Some(Command::Intents(cmd)) => cmd,
+            other => panic!("Expected intents command, got {:?}", other),
+        };
+
+        let plain = parse_intents_cmd("intents");
+        assert!(!plain.json && !plain.session);
+
+        let both = parse_intents_cmd("intents --session --json");
+        assert!(both.json && both.session);
+    }
+
+    #[test]
+    fn test_headers_subcommands() {
+        let headers_action = |input: &str| match parse(input).unwrap().lines[0].command.clone() {
+            Some(Command::Headers(cmd)) => cmd.action.unwrap(),
+            other => panic!("Expected headers command, got {:?}", other),
+        };
+
+        assert_eq!(
+            headers_action(r#"headers set X-Token "abc""#),
+            HeadersAction::SetHeader {
+                name: "X-Token".into(),
+                value: "abc".into(),
+            }
+        );
+        assert_eq!(
+            headers_action(r#"headers set "{\"X-Token\": \"abc\"}""#),
+            HeadersAction::Set {
+                domain: None,
+                json: r#"{"X-Token": "abc"}"#.into(),
+            }
+        );
+        assert_eq!(
+            headers_action(r#"headers set "example.com" "{\"X-Token\": \"abc\"}""#),
+            HeadersAction::Set {
+                domain: Some("example.com".into()),
+                json: r#"{"X-Token": "abc"}"#.into(),
+            }
+        );
+        assert_eq!(headers_action("headers list"), HeadersAction::List);
+        assert_eq!(headers_action("headers"), HeadersAction::List);
+        assert_eq!(headers_action("headers clear"), HeadersAction::Clear(None));
+        assert_eq!(
+            headers_action(r#"headers clear "example.com""#),
+            HeadersAction::Clear(Some("example.com".into()))
+        );
+    }
+
+    #[test]
+    fn test_session_subcommands() {
+        let session_action = |input: &str| match parse(input).unwrap().lines[0].command.clone() {
+            Some(Command::Session(cmd)) => cmd.action,
+            other => panic!("Expected session command, got {:?}", other),
+        };
+
+        assert_eq!(
+            session_action("session save work"),
+            Some(SessionAction::Save("work".into()))
+        );
+        assert_eq!(
+            session_action("session load work"),
+            Some(SessionAction::Load("work".into()))
+        );
+        assert_eq!(session_action("session list"), Some(SessionAction::List));
+        assert_eq!(
+            session_action("session delete work"),
+            Some(SessionAction::Delete("work".into()))
+        );
+        assert_eq!(
+            session_action("session new scratch --mode headless"),
+            Some(SessionAction::New {
+                name: "scratch".into(),
+                mode: Some("headless".into()),
+            })
+        );
+        assert_eq!(
+            session_action("session work"),
+            Some(SessionAction::Switch("work".into()))
+        );
+        assert_eq!(session_action("session"), None);
+    }
+
+    fn parse_click_cmd(input: &str) -> Result<ClickCmd, ParseError> {
+        match parse(input)?.lines.remove(0).command {
+            Some(Command::Click(cmd)) => Ok(cmd),
+            other => panic!("Expected click command, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_click_single_button_flags() {
+        let cmd = parse_click_cmd("click 5 --double").unwrap();
+        assert!(cmd.double && !cmd.right && !cmd.middle);
+
+        let cmd = parse_click_cmd("click 5 --right --ctrl").unwrap();
+        assert!(cmd.right && cmd.ctrl && !cmd.double);
+
+        let cmd = parse_click_cmd("click 5 --middle --middle").unwrap();
+        assert!(cmd.middle && !cmd.right);
+
+        let cmd = parse_click_cmd("click 5").unwrap();
+        assert!(!cmd.double && !cmd.right && !cmd.middle);
+    }
+
+    #[test]
+    fn test_wait_after_flag() {
+        assert_eq!(parse_click_cmd("click 5").unwrap().wait_after, None);
+        assert_eq!(
+            parse_click_cmd("click 5 --wait-after 300")
+                .unwrap()
+                .wait_after,
+            Some(300)
+        );
+        assert_eq!(
+            parse_click_cmd(r#"click "Save" --wait-after 2s --force"#)
+                .unwrap()
+                .wait_after,
+            Some(2000)
+        );
+
+        match parse(r#"type "Search" "shoes" --enter --wait-after 250ms"#)
+            .unwrap()
+            .lines
+            .remove(0)
+            .command
+        {
+            Some(Command::Type(cmd)) => {
+                assert!(cmd.enter);
+                assert_eq!(cmd.wait_after, Some(250));
+            }
+            other => panic!("Expected type command, got {:?}", other),
+        }
+
+        assert!(parse("click 5 --wait-after soon").is_err());
+    }
+
+    #[test]
+    fn test_ambiguous_command_lists_candidates() {
+        let err = parse(&crate::normalize("c \"X\"")).unwrap_err();
+        match &err {
+            ParseError::AmbiguousCommand { prefix, candidates } => {
+                assert_eq!(prefix, "c");
+                assert!(candidates.iter().any(|c| c == "click"));
+                assert!(candidates.iter().any(|c| c == "check"));
+                assert!(candidates.iter().any(|c| c == "clear"));
+            }
+            other => panic!("Expected an ambiguous command, got {:?}", other),
+        }
+        assert!(err.to_string().contains("could be check, clear, click"));
+
+        assert!(matches!(
+            parse(&crate::normalize("cl \"X\"")).unwrap().lines[0].command,
+            Some(Command::Click(_))
+        ));
+    }
+
+    #[test]
+    fn test_select_custom_flag() {
+        let select = |input: &str| match parse(input).unwrap().lines.remove(0).command {
+            Some(Command::Select(cmd)) => cmd,
+            other => panic!("Expected select command, got {:?}", other),
+        };
+
+        let native = select(r#"select "Country" "Canada""#);
+        assert!(!native.custom);
+
+        let custom = select(r#"select "Country" "Canada" --custom"#);
+        assert!(custom.custom);
+        assert_eq!(custom.value, "Canada");
+    }
+
+    #[test]
+    fn test_click_conflicting_flags_rejected() {
+        for input in [
+            "click 5 --right --middle",
+            "click 5 --double --right",
+            "click 5 --middle --double",
+        ] {
+            let err = parse_click_cmd(input).unwrap_err();
+            assert!(
+                matches!(err, ParseError::ConflictingFlags(_)),
+                "{}: {:?}",
+                input,
+                err
+            );
+        }
+        let msg = parse_click_cmd("click 5 --right --middle")
+            .unwrap_err()
+            .to_string();
+        assert!(msg.contains("--right") && msg.contains("--middle"));
+    }
+
+    #[test]
+    fn test_scroll_flags() {
+        let script = parse("scroll down --smooth --amount 400").unwrap();
+        match &script.lines[0].command {
+            Some(Command::Scroll(cmd)) => {
+                assert!(cmd.smooth);
+                assert_eq!(cmd.direction.as_deref(), Some("down"));
+                assert_eq!(cmd.amount, Some(400.0));
+            }
+            other => panic!("Expected scroll command, got {:?}", other),
+        }
+
+        match &parse("scroll up --page").unwrap().lines[0].command {
+            Some(Command::Scroll(cmd)) => assert!(cmd.page && !cmd.smooth),
+            other => panic!("Expected scroll command, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_wait_until_text_of() {
+        let script = parse(r#"wait until text of "Status" == "Complete" --timeout 5s"#).unwrap();
+        match &script.lines[0].command {
+            Some(Command::Wait(cmd)) => {
+                assert_eq!(
+                    cmd.condition,
+                    WaitCondition::TextOf {
+                        target: Target {
+                            atomic: TargetAtomic::Text("Status".into()),
+                            relation: None,
+                            state: None,
+                        },
+                        comparison: TextComparison::Equals,
+                        expected: "Complete".into(),
+                    }
+                );
+                assert_eq!(cmd.timeout.as_deref(), Some("5s"));
+            }
+            other => panic!("Expected wait command, got {:?}", other),
+        }
+
+        match &parse(r#"wait until text of 12 contains "Done""#)
+            .unwrap()
+            .lines[0]
+            .command
+        {
+            Some(Command::Wait(WaitCmd {
+                condition:
+                    WaitCondition::TextOf {
+                        target,
+                        comparison: TextComparison::Contains,
+                        expected,
+                    },
+                ..
+            })) => {
+                assert_eq!(target.atomic, TargetAtomic::Id(12));
+                assert_eq!(expected, "Done");
+            }
+            other => panic!("Expected text-of wait, got {:?}", other),
+        }
+
+        // A plain expression still parses as before.
+        match &parse(r#"wait until "window.ready""#).unwrap().lines[0].command {
+            Some(Command::Wait(cmd)) => {
+                assert_eq!(cmd.condition, WaitCondition::Until("window.ready".into()))
+            }
+            other => panic!("Expected wait command, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_testid_target() {
+        match &parse(r#"click testid "submit-btn""#).unwrap().lines[0].command {
+            Some(Command::Click(cmd)) => {
+                assert_eq!(cmd.target.atomic, TargetAtomic::TestId("submit-btn".into()))
+            }
+            other => panic!("Expected click command, got {:?}", other),
+        }
+        match &parse(r#"click "testid""#).unwrap().lines[0].command {
+            Some(Command::Click(cmd)) => {
+                assert_eq!(cmd.target.atomic, TargetAtomic::Text("testid".into()))
+            }
+            other => panic!("Expected click command, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_goto_referer_and_until() {
+        let input = r#"goto "https://example.com/app" --referer "https://x" --until visible "Home" --timeout 5s"#;
+        match &parse(input).unwrap().lines[0].command {
+            Some(Command::Goto(cmd)) => {
+                assert_eq!(cmd.url, "https://example.com/app");
+                assert_eq!(cmd.referer.as_deref(), Some("https://x"));
+                assert_eq!(
+                    cmd.until,
+                    Some(WaitCondition::Visible(Target {
+                        atomic: TargetAtomic::Text("Home".into()),
+                        relation: None,
+                        state: None,
+                    }))
+                );
+                assert_eq!(cmd.timeout.as_deref(), Some("5s"));
+            }
+            other => panic!("Expected goto command, got {:?}", other),
+        }
+
+        match &parse(r#"goto example.com --until url "/dashboard""#)
+            .unwrap()
+            .lines[0]
+            .command
+        {
+            Some(Command::Goto(cmd)) => {
+                assert_eq!(cmd.referer, None);
+                assert_eq!(cmd.until, Some(WaitCondition::Url("/dashboard".into())));
+            }
+            other => panic!("Expected goto command, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_type_flags() {
+        let input = r#"type "Email" "a@b.com" --clear --then-tab --enter"#;
+        match &parse(input).unwrap().lines[0].command {
+            Some(Command::Type(cmd)) => {
+                assert_eq!(cmd.text, "a@b.com");
+                assert!(cmd.clear && cmd.then_tab && cmd.enter);
+                assert!(!cmd.append);
+            }
+            other => panic!("Expected type command, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_role_text_target() {
+        match &parse(r#"click button "Submit""#).unwrap().lines[0].command {
+            Some(Command::Click(cmd)) => {
+                assert_eq!(cmd.target, Target::role_text("button", "Submit"))
+            }
+            other => panic!("Expected click command, got {:?}", other),
+        }
+
+        match &parse(r#"type textbox "Email" "a@b.com""#).unwrap().lines[0].command {
+            Some(Command::Type(cmd)) => {
+                assert_eq!(cmd.target, Target::role_text("textbox", "Email"));
+                assert_eq!(cmd.text, "a@b.com");
+            }
+            other => panic!("Expected type command, got {:?}", other),
+        }
+
+        // A bare field role is still followed by the text to type
+        match &parse(r#"type email "a@b.com""#).unwrap().lines[0].command {
+            Some(Command::Type(cmd)) => assert_eq!(cmd.target, Target::role("email")),
+            other => panic!("Expected type command, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_observe_monitor() {
+        for (input, interval) in [
+            ("observe --monitor --interval 1000", Some("1000")),
+            ("observe --changes-only --interval 2s", Some("2s")),
+            ("observe --monitor", None),
+        ] {
+            match &parse(input).unwrap().lines[0].command {
+                Some(Command::Observe(cmd)) => {
+                    assert!(cmd.monitor, "{}", input);
+                    assert_eq!(cmd.interval.as_deref(), interval, "{}", input);
+                }
+                other => panic!("Expected observe command, got {:?}", other),
+            }
+        }
+    }
+
+    #[test]
+    fn test_reset_categories() {
+        let reset = |input: &str| match parse(input).unwrap().lines.remove(0).command {
+            Some(Command::Reset(cmd)) => cmd,
+            other => panic!("Expected reset command, got {:?}", other),
+        };
+
+        assert_eq!(reset("reset --all"), ResetCmd::all());
+        assert_eq!(reset("reset"), ResetCmd::all());
+        assert_eq!(
+            reset("reset --cookies --console"),
+            ResetCmd {
+                cookies: true,
+                console: true,
+                ..Default::default()
+            }
+        );
+        assert!(parse("reset --history").is_err());
+    }
+
+    #[test]
+    fn test_wait_any_of() {
+        for input in [
+            r#"wait any-of ["Enter code", "Dashboard"] --timeout 10s"#,
+            r#"wait any-of["Enter code","Dashboard"] --timeout 10s"#,
+        ] {
+            match &parse(input).unwrap().lines[0].command {
+                Some(Command::Wait(cmd)) => {
+                    let WaitCondition::AnyOf(targets) = &cmd.condition else {
+                        panic!("Expected any-of, got {:?}", cmd.condition);
+                    };
+                    assert_eq!(
+                        targets.iter().map(|t| t.atomic.clone()).collect::<Vec<_>>(),
+                        vec![
+                            TargetAtomic::Text("Enter code".into()),
+                            TargetAtomic::Text("Dashboard".into())
+                        ]
+                    );
+                    assert_eq!(cmd.timeout.as_deref(), Some("10s"));
+                }
+                other => panic!("Expected wait command, got {:?}", other),
+            }
+        }
+
+        match &parse(r#"wait any-of [css(".otp"), button "Continue"]"#)
+            .unwrap()
+            .lines[0]
+            .command
+        {
+            Some(Command::Wait(cmd)) => {
+                assert!(matches!(&cmd.condition, WaitCondition::AnyOf(t) if t.len() == 2))
+            }
+            other => panic!("Expected wait command, got {:?}", other),
+        }
+
+        assert!(parse("wait any-of []").is_err());
+    }
+
+    #[test]
+    fn test_wait_items_comparisons() {
+        let items = |input: &str| match parse(input).unwrap().lines[0].command.clone() {
+            Some(Command::Wait(WaitCmd {
+                condition: WaitCondition::Items(cond),
+                ..
+            })) => cond,
+            other => panic!("Expected wait items, got {:?}", other),
+        };
+        let cond = |op, count| ItemsCond {
+            selector: ".row".into(),
+            op,
+            count,
+        };
+
+        assert_eq!(
+            items(r#"wait items ".row" >= 5"#),
+            cond(CountComparison::AtLeast, 5)
+        );
+        assert_eq!(
+            items(r#"wait items ".row" <= 5"#),
+            cond(CountComparison::AtMost, 5)
+        );
+        assert_eq!(
+            items(r#"wait items ".row" > 5"#),
+            cond(CountComparison::MoreThan, 5)
+        );
+        assert_eq!(
+            items(r#"wait items ".row" < 5"#),
+            cond(CountComparison::FewerThan, 5)
+        );
+        assert_eq!(
+            items(r#"wait items ".row" == 0"#),
+            cond(CountComparison::Equals, 0)
+        );
+        // Without an operator the count is a minimum, and spacing is optional.
+        assert_eq!(
+            items(r#"wait items ".row" 5"#),
+            cond(CountComparison::AtLeast, 5)
+        );
+        assert_eq!(
+            items(r#"wait items ".row" >=5 --timeout 2s"#),
+            cond(CountComparison::AtLeast, 5)
+        );
+        assert!(parse(r#"wait items ".row" => 5"#).is_err());
+    }
+
+    #[test]
+    fn test_wait_all_and_any_groups() {
+        match &parse(r#"wait all (hidden ".spinner", items ".result" 1) --timeout 10s"#)
+            .unwrap()
+            .lines[0]
+            .command
+        {
+            Some(Command::Wait(cmd)) => {
+                assert_eq!(
+                    cmd.condition,
+                    WaitCondition::All(vec![
+                        WaitCondition::Hidden(Target {
+                            atomic: TargetAtomic::Text(".spinner".into()),
+                            relation: None,
+                            state: None,
+                        }),
+                        WaitCondition::Items(ItemsCond {
+                            selector: ".result".into(),
+                            op: CountComparison::AtLeast,
+                            count: 1,
+                        }),
+                    ])
+                );
+                assert_eq!(cmd.timeout.as_deref(), Some("10s"));
+            }
+            other => panic!("Expected wait command, got {:?}", other),
+        }
+
+        // Groups nest, and `any` is not mistaken for `any-of`.
+        match &parse(r#"wait any(url "/done",all(visible "Saved",gone ".toast"))"#)
+            .unwrap()
+            .lines[0]
+            .command
+        {
+            Some(Command::Wait(cmd)) => {
+                let WaitCondition::Any(conditions) = &cmd.condition else {
+                    panic!("Expected any, got {:?}", cmd.condition);
+                };
+                assert_eq!(conditions[0], WaitCondition::Url("/done".into()));
+                assert!(matches!(
+                    &conditions[1],
+                    WaitCondition::All(inner)
+                        if inner[1] == WaitCondition::Gone(".toast".into())
+                ));
+            }
+            other => panic!("Expected wait command, got {:?}", other),
+        }
+
+        assert!(parse("wait all ()").is_err());
+        assert!(parse(r#"wait any (visible "A""#).is_err());
+    }
+
+    #[test]
+    fn test_wait_idle_quiet_window() {
+        for input in [
+            "wait idle --quiet 200ms --timeout 5s",
+            "wait idle --timeout 5s --quiet 200ms",
+        ] {
+            match &parse(input).unwrap().lines[0].command {
+                Some(Command::Wait(cmd)) => {
+                    assert_eq!(cmd.condition, WaitCondition::Idle);
+                    assert_eq!(cmd.quiet.as_deref(), Some("200ms"));
+                    assert_eq!(cmd.timeout.as_deref(), Some("5s"));
+                }
+                other => panic!("Expected wait command, got {:?}", other),
+            }
+        }
+
+        match &parse("wait idle").unwrap().lines[0].command {
+            Some(Command::Wait(cmd)) => assert_eq!(cmd.quiet, None),
+            other => panic!("Expected wait command, got {:?}", other),
+        }
+        assert!(parse(r#"wait visible "Save" --quiet 1s"#).is_err());
+    }
+
+    #[test]
+    fn test_observe_tree_options() {
+        let script = parse("observe --tree --depth 2").unwrap();
+        match &script.lines[0].command {
+            Some(Command::Observe(cmd)) => {
+                assert!(cmd.tree);
+                assert_eq!(cmd.depth, Some(2));
+            }
+            other => panic!("Expected observe command, got {:?}", other),
+        }
+
+        match &parse("observe").unwrap().lines[0].command {
+            Some(Command::Observe(cmd)) => {
+                assert!(!cmd.tree);
+                assert_eq!(cmd.depth, None);
+            }
+            other => panic!("Expected observe command, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_screenshot_flags() {
+        let script = parse("screenshot --fullpage --wait-stable --output shot.png").unwrap();
+        match &script.lines[0].command {
+            Some(Command::Screenshot(cmd)) => {
+                assert!(cmd.fullpage);
+                assert!(cmd.wait_stable);
+                assert_eq!(cmd.output.as_deref(), Some("shot.png"));
+            }
+            other => panic!("Expected screenshot command, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_screenshot_clip() {
+        let script = parse("screenshot --clip 0,0,800,600 --output shot.png").unwrap();
+        match &script.lines[0].command {
+            Some(Command::Screenshot(cmd)) => {
+                assert_eq!(cmd.clip, Some((0.0, 0.0, 800.0, 600.0)));
+                assert_eq!(cmd.output.as_deref(), Some("shot.png"));
+                assert!(cmd.target.is_none());
+            }
+            other => panic!("Expected screenshot command, got {:?}", other),
+        }
+
+        let script = parse("screenshot --clip 10.5,20,300,150.25").unwrap();
+        match &script.lines[0].command {
+            Some(Command::Screenshot(cmd)) => {
+                assert_eq!(cmd.clip, Some((10.5, 20.0, 300.0, 150.25)))
+            }
+            other => panic!("Expected screenshot command, got {:?}", other),
+        }
+
+        assert!(parse("screenshot --clip 0,0,800").is_err());
+    }
+
+    #[test]
+    fn test_screenshot_element_only_padding() {
+        let script =
+            parse(r##"screenshot css("#card") --element-only --padding 10 --output card.png"##)
+                .unwrap();
+        match &script.lines[0].command {
+            Some(Command::Screenshot(cmd)) => {
+                assert!(cmd.element_only);
+                assert_eq!(cmd.padding, Some(10.0));
+                assert_eq!(cmd.output.as_deref(), Some("card.png"));
+                assert!(cmd.target.is_some());
+            }
+            other => panic!("Expected screenshot command, got {:?}", other),
+        }
+
+        let script = parse(r#"screenshot "Card""#).unwrap();
+        match &script.lines[0].command {
+            Some(Command::Screenshot(cmd)) => {
+                assert!(!cmd.element_only);
+                assert_eq!(cmd.padding, None);
+            }
+            other => panic!("Expected screenshot command, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_cookies_get_json_and_all() {
+        let cookies = |input: &str| match parse(input).unwrap().lines[0].command.clone() {
+            Some(Command::Cookies(cmd)) => cmd,
+            other => panic!("Expected cookies command, got {:?}", other),
+        };
+
+        let cmd = cookies("cookies get session");
+        assert_eq!(cmd.action, CookiesAction::Get("session".into()));
+        assert!(!cmd.json);
+
+        let cmd = cookies("cookies get session --json");
+        assert_eq!(cmd.action, CookiesAction::Get("session".into()));
+        assert!(cmd.json);
+
+        let cmd = cookies("cookies get --all --json");
+        assert_eq!(cmd.action, CookiesAction::GetAll);
+        assert!(cmd.json);
+
+        assert!(parse("cookies get --json").is_err());
+    }
+
+    #[test]
+    fn test_cookies_export_import() {
+        let action = |input: &str| match parse(input).unwrap().lines[0].command.clone() {
+            Some(Command::Cookies(cmd)) => cmd.action,
+            other => panic!("Expected cookies command, got {:?}", other),
+        };
+
+        assert_eq!(
+            action("cookies export cookies.txt"),
+            CookiesAction::Export {
+                path: "cookies.txt".into(),
+                format: None
+            }
+        );
+        assert_eq!(
+            action(r#"cookies import "saved cookies.dat" --format json"#),
+            CookiesAction::Import {
+                path: "saved cookies.dat".into(),
+                format: Some("json".into())
+            }
+        );
+        assert!(parse("cookies export cookies.txt --format yaml").is_err());
+    }
+
+    #[test]
+    fn test_export_intent() {
+        let export = |input: &str| match parse(input).unwrap().lines[0].command.clone() {
+            Some(Command::Export(cmd)) => cmd,
+            other => panic!("Expected export command, got {:?}", other),
+        };
+
+        assert_eq!(
+            export("export login"),
+            ExportCmd {
+                name: "login".into(),
+                out: None,
+                format: None,
+            }
+        );
+        assert_eq!(
+            export(r#"export login --format json --out "my login.json""#),
+            ExportCmd {
+                name: "login".into(),
+                out: Some("my login.json".into()),
+                format: Some("json".into()),
+            }
+        );
+        assert!(parse("export login --format xml").is_err());
+    }
+
+    fn intercept(input: &str) -> InterceptCmd {
+        match parse(input).unwrap().lines[0].command.clone() {
+            Some(Command::Intercept(cmd)) => cmd,
+            other => panic!("Expected intercept command, got {:?}", other),
+        }
+    }
+
+    fn rule(url_pattern: &str, action: InterceptAction) -> InterceptCmd {
+        InterceptCmd::Rule(InterceptRule {
+            url_pattern: url_pattern.into(),
+            action,
+        })
+    }
+
+    #[test]
+    fn test_intercept_block() {
+        assert_eq!(
+            intercept(r#"intercept block "*.png""#),
+            rule("*.png", InterceptAction::Block)
+        );
+        assert_eq!(
+            intercept(r#"intercept "*/ads/*" --block"#),
+            rule("*/ads/*", InterceptAction::Block)
+        );
+        assert_eq!(
+            intercept(r#"intercept continue "/api/""#),
+            rule("/api/", InterceptAction::Continue)
+        );
+        assert!(matches!(
+            parse(r#"intercept "*/ads/*" --block --status 404"#),
+            Err(ParseError::ConflictingFlags(_))
+        ));
+        assert!(matches!(
+            parse(r#"intercept "*/ads/*" --respond "{}" --block"#),
+            Err(ParseError::ConflictingFlags(_))
+        ));
+        assert!(parse("intercept block").is_err());
+    }
+
+    #[test]
+    fn test_intercept_mock() {
+        assert_eq!(
+            intercept(r#"intercept mock "/api/user" 200 '{"name":"x"}'"#),
+            rule(
+                "/api/user",
+                InterceptAction::Mock {
+                    status: 200,
+                    body: r#"{"name":"x"}"#.into(),
+                    content_type: None,
+                }
+            )
+        );
+        assert_eq!(
+            intercept(r#"intercept mock "/gone" 404 "Not here" --content-type "text/html""#),
+            rule(
+                "/gone",
+                InterceptAction::Mock {
+                    status: 404,
+                    body: "Not here".into(),
+                    content_type: Some("text/html".into()),
+                }
+            )
+        );
+        assert_eq!(
+            intercept(r#"intercept mock "/empty" 204"#),
+            rule(
+                "/empty",
+                InterceptAction::Mock {
+                    status: 204,
+                    body: String::new(),
+                    content_type: None,
+                }
+            )
+        );
+        assert_eq!(
+            intercept(r#"intercept "/data" --status 418"#),
+            rule(
+                "/data",
+                InterceptAction::Mock {
+                    status: 418,
+                    body: String::new(),
+                    content_type: None,
+                }
+            )
+        );
+        assert_eq!(
+            intercept(r#"intercept "/data" --respond-file resp.json"#),
+            rule(
+                "/data",
+                InterceptAction::MockFile {
+                    status: 200,
+                    path: "resp.json".into(),
+                    content_type: None,
+                }
+            )
+        );
+        assert!(parse(r#"intercept mock "/api/user" 2000"#).is_err());
+    }
+
+    #[test]
+    fn test_intercept_clear_and_requests() {
+        assert_eq!(intercept("intercept clear"), InterceptCmd::Clear(None));
+        assert_eq!(
+            intercept(r#"intercept clear "*.png""#),
+            InterceptCmd::Clear(Some("*.png".into()))
+        );
+
+        let cmd = match parse(r#"requests --filter "/api" --method POST --last 5"#)
+            .unwrap()
+            .lines[0]
+            .command
+            .clone()
+        {
+            Some(Command::Requests(cmd)) => cmd,
+            other => panic!("Expected requests command, got {:?}", other),
+        };
+        assert_eq!(cmd.filter.as_deref(), Some("/api"));
+        assert_eq!(cmd.method.as_deref(), Some("POST"));
+        assert_eq!(cmd.last, Some(5.0));
+    }
+
+    #[test]
+    fn test_extract_sources() {
+        let extract = |input: &str| match parse(input).unwrap().lines[0].command.clone() {
+            Some(Command::Extract(cmd)) => cmd,
+            other => panic!("Expected extract command, got {:?}", other),
+        };
+
+        assert_eq!(extract("extract links").what, ExtractWhat::Links);
+        assert_eq!(extract("extract text").what, ExtractWhat::Text);
+        let cmd = extract("extract tables --format csv");
+        assert_eq!(cmd.what, ExtractWhat::Tables);
+        assert_eq!(cmd.format.as_deref(), Some("csv"));
+        let cmd = extract(r##"extract tables --selector "#orders" --format json"##);
+        assert_eq!(cmd.what, ExtractWhat::Tables);
+        assert_eq!(cmd.selector.as_deref(), Some("#orders"));
+        assert_eq!(cmd.format.as_deref(), Some("json"));
+        assert!(!cmd.same_origin);
+        assert!(extract("extract links --same-origin --format json").same_origin);
+        assert!(matches!(
+            parse("extract images --same-origin"),
+            Err(ParseError::ConflictingFlags(_))
+        ));
+    }
+
+    #[test]
+    fn test_set_from_eval() {
+        for input in [
+            r#"set y = eval "return window.scrollY""#,
+            r#"set y=eval "return window.scrollY""#,
+        ] {
+            match &parse(input).unwrap().lines[0].command {
+                Some(Command::Set(cmd)) => {
+                    assert_eq!(cmd.name, "y");
+                    assert_eq!(cmd.eval.script, "return window.scrollY");
+                    assert!(cmd.eval.args.is_empty());
+                }
+                other => panic!("Expected set command, got {:?}", other),
+            }
+        }
+
+        let script = parse(r#"set total = eval "return args.a + 1" --arg a=$count"#).unwrap();
+        match &script.lines[0].command {
+            Some(Command::Set(cmd)) => assert_eq!(
+                cmd.eval.args,
+                vec![("a".to_string(), EvalArg::Variable("count".into()))]
+            ),
+            other => panic!("Expected set command, got {:?}", other),
+        }
+
+        assert!(parse(r#"set y = "42""#).is_err());
+    }
+
+    #[test]
+    fn test_eval_args() {
+        let script =
+            parse(r#"eval "return args.x * 2" --arg x=$count --arg label="a b" --arg n=1.5"#)
+                .unwrap();
+        match &script.lines[0].command {
+            Some(Command::Eval(cmd)) => {
+                assert_eq!(cmd.script, "return args.x * 2");
+                assert_eq!(
+                    cmd.args,
+                    vec![
+                        ("x".to_string(), EvalArg::Variable("count".into())),
+                        ("label".to_string(), EvalArg::Value("a b".into())),
+                        ("n".to_string(), EvalArg::Value(1.5.into())),
+                    ]
+                );
+            }
+            other => panic!("Expected eval command, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_run_params() {
+        let script = parse(r#"run login --username "a@b.c" secret 30"#).unwrap();
+        let Some(Command::Run(cmd)) = script.lines[0].command.clone() else {
+            panic!("Expected run command");
+        };
+        assert_eq!(cmd.name, "login");
+        assert_eq!(
+            cmd.params,
+            vec![
+                ("username".to_string(), "a@b.c".to_string()),
+                (String::new(), "secret".to_string()),
+                (String::new(), "30".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_run_chain() {
+        let script =
+            parse("run accept_cookies then login alice then search --param query=shoes").unwrap();
+        let Some(Command::Run(cmd)) = script.lines[0].command.clone() else {
+            panic!("Expected run command");
+        };
+        assert_eq!(cmd.name, "accept_cookies");
+        assert!(cmd.params.is_empty());
+        assert!(!cmd.keep_going);
+        let stages: Vec<(&str, &[(String, String)])> = cmd
+            .then
+            .iter()
+            .map(|s| (s.name.as_str(), s.params.as_slice()))
+            .collect();
+        assert_eq!(
+            stages,
+            vec![
+                ("login", &[(String::new(), "alice".to_string())][..]),
+                ("search", &[("query".to_string(), "shoes".to_string())][..]),
+            ]
+        );
+
+        let script = parse("run a then b --continue").unwrap();
+        let Some(Command::Run(cmd)) = script.lines[0].command.clone() else {
+            panic!("Expected run command");
+        };
+        assert!(cmd.keep_going);
+        assert_eq!(cmd.then.len(), 1);
+        assert!(cmd.then[0].params.is_empty());
+    }
+
+    #[test]
+    fn test_run_json_flag() {
+        let script = parse(r#"run login --username alice --json"#).unwrap();
+        let Some(Command::Run(cmd)) = script.lines[0].command.clone() else {
+            panic!("Expected run command");
+        };
+        assert!(cmd.json);
+        assert_eq!(
+            cmd.params,
+            vec![("username".to_string(), "alice".to_string())]
+        );
+
+        let script = parse("run a then b --json --continue").unwrap();
+        let Some(Command::Run(cmd)) = script.lines[0].command.clone() else {
+            panic!("Expected run command");
+        };
+        assert!(cmd.json && cmd.keep_going);
+        assert!(cmd.then[0].params.is_empty());
+    }
+
+    #[test]
+    fn test_type_secret_flag() {
+        let script = parse(r#"type "Password" "hunter2" --secret --enter"#).unwrap();
+        let Some(Command::Type(cmd)) = script.lines[0].command.clone() else {
+            panic!("Expected type command");
+        };
+        assert!(cmd.secret && cmd.enter);
+        assert_eq!(cmd.text, "hunter2");
+
+        let script = parse(r#"type "Password" "hunter2""#).unwrap();
+        let Some(Command::Type(cmd)) = script.lines[0].command.clone() else {
+            panic!("Expected type command");
+        };
+        assert!(!cmd.secret);
+    }
+
+    #[test]
+    fn test_run_screenshot_on_error_flag() {
+        let script = parse("run checkout --item 42 --screenshot-on-error").unwrap();
+        let Some(Command::Run(cmd)) = script.lines[0].command.clone() else {
+            panic!("Expected run command");
+        };
+        assert!(cmd.screenshot_on_error);
+        assert!(!cmd.json);
+        assert_eq!(cmd.params, vec![("item".to_string(), "42".to_string())]);
+    }
+
+    #[test]
+    fn test_repeat_blocks() {
+        let repeat = |input: &str| match parse(&crate::normalizer::normalize(input)).unwrap().lines
+            [0]
+        .command
+        .clone()
+        {
+            Some(Command::Repeat(cmd)) => cmd,
+            other => panic!("Expected repeat command, got {:?}", other),
+        };
+
+        let cmd = repeat("repeat 3 { scroll down }");
+        assert_eq!(cmd.count, 3);
+        assert_eq!(cmd.body.len(), 1);
+        assert!(
+            matches!(&cmd.body[0], Command::Scroll(s) if s.direction.as_deref() == Some("down"))
+        );
+
+        let cmd = repeat(r#"repeat 2 { click "Next"; repeat 4 { scroll down; press Enter } }"#);
+        assert_eq!(cmd.count, 2);
+        assert_eq!(cmd.body.len(), 2);
+        assert!(matches!(cmd.body[0], Command::Click(_)));
+        let Command::Repeat(inner) = &cmd.body[1] else {
+            panic!("Expected a nested repeat, got {:?}", cmd.body[1]);
+        };
+        assert_eq!(inner.count, 4);
+        assert!(matches!(inner.body[0], Command::Scroll(_)));
+        assert!(matches!(inner.body[1], Command::Press(_)));
+
+        // A `;` inside a quoted string does not split the block
+        let cmd = repeat(r#"repeat 2 { type "Search" "a; b" }"#);
+        assert_eq!(cmd.body.len(), 1);
+
+        assert!(parse("repeat 3 { }").is_err());
+        assert!(parse("repeat { scroll down }").is_err());
+        assert!(parse("repeat 3 { scroll down").is_err());
+    }
+
+    #[test]
+    fn test_pdf_options() {
+        let pdf = |input: &str| match parse(input).unwrap().lines[0].command.clone() {
+            Some(Command::Pdf(cmd)) => cmd,
+            other => panic!("Expected pdf command, got {:?}", other),
+        };
+
+        let cmd = pdf("pdf out.pdf --landscape --format A4 --margin 10");
+        assert_eq!(cmd.path, "out.pdf");
+        assert!(cmd.landscape);
+        assert_eq!(cmd.format.as_deref(), Some("A4"));
+        assert_eq!(cmd.margin, Some(10.0));
+        assert_eq!(cmd.scale, None);
+
+        let cmd = pdf("pdf out.pdf --scale 0.5 --margin 12.5");
+        assert!(!cmd.landscape);
+        assert_eq!(cmd.format, None);
+        assert_eq!(cmd.margin, Some(12.5));
+        assert_eq!(cmd.scale, Some(0.5));
+
+        let cmd = pdf("pdf out.pdf");
+        assert_eq!(
+            (cmd.landscape, cmd.format, cmd.margin, cmd.scale),
+            (false, None, None, None)
+        );
+
+        assert!(parse(r#"pdf out.pdf --margin "1cm""#).is_err());
+    }
+
+    #[test]
+    fn test_resolve_command() {
+        let script = parse(r#"resolve "Sign in""#).unwrap();
+        let Some(Command::Resolve(cmd)) = script.lines[0].command.clone() else {
+            panic!("Expected resolve command");
+        };
+        assert_eq!(cmd.target.atomic, TargetAtomic::Text("Sign in".into()));
+
+        let script = parse(r#"resolve "Submit" near "Email""#).unwrap();
+        let Some(Command::Resolve(cmd)) = script.lines[0].command.clone() else {
+            panic!("Expected resolve command");
+        };
+        assert!(cmd.target.relation.is_some());
+
+        assert!(parse("resolve").is_err());
+    }
+
+    #[test]
+    fn test_assert_conditions() {
+        let condition = |input: &str| match parse(input).unwrap().lines[0].command.clone() {
+            Some(Command::Assert(cmd)) => cmd.condition,
+            other => panic!("Expected assert command, got {:?}", other),
+        };
+
+        assert_eq!(
+            condition(r#"assert visible "Welcome""#),
+            AssertCondition::Visible(Target {
+                atomic: TargetAtomic::Text("Welcome".into()),
+                relation: None,
+                state: None,
+            })
+        );
+        assert_eq!(
+            condition(r#"assert text "Total" contains "42""#),
+            AssertCondition::TextContains {
+                target: Target {
+                    atomic: TargetAtomic::Text("Total".into()),
+                    relation: None,
+                    state: None,
+                },
+                expected: "42".into(),
+            }
+        );
+        assert_eq!(
+            condition(r#"assert url matches "/checkout""#),
+            AssertCondition::UrlMatches("/checkout".into())
+        );
+
+        assert!(parse(r#"assert text "Total" "42""#).is_err());
+        assert!(parse("assert").is_err());
+    }
+
+    #[test]
+    fn test_text_mode_apply() {
+        let text = "  a \n\t b  ";
+        assert_eq!(TextMode::Raw.apply(text), text);
+        assert_eq!(TextMode::Trim.apply(text), "a \n\t b");
+        assert_eq!(TextMode::Collapse.apply(text), "a b");
+    }
+}
+
 #[cfg(test)]
 mod comment_validation_tests {
     use super::*;
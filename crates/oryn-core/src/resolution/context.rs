@@ -1,4 +1,5 @@
 use oryn_common::protocol::{DetectedPatterns, Element, Rect, ScanResult};
+use oryn_common::resolver::ScoringConfig;
 
 /// All context available for resolution decisions.
 pub struct ResolutionContext<'a> {
@@ -13,6 +14,9 @@ pub struct ResolutionContext<'a> {
 
     /// Recent command history (for context)
     history: Vec<RecentCommand>,
+
+    /// Weights for scoring text matches
+    scoring: ScoringConfig,
 }
 
 #[derive(Debug, Clone)]
@@ -28,6 +32,7 @@ impl<'a> ResolutionContext<'a> {
             focused: None,
             scope: None,
             history: vec![],
+            scoring: ScoringConfig::default(),
         }
     }
 
@@ -36,6 +41,11 @@ impl<'a> ResolutionContext<'a> {
         self
     }
 
+    pub fn with_scoring(mut self, scoring: ScoringConfig) -> Self {
+        self.scoring = scoring;
+        self
+    }
+
     /// Create a scoped context for resolution within a container.
     pub fn scoped_to(&self, container_id: u32) -> ResolutionContext<'_> {
         ResolutionContext {
@@ -43,6 +53,7 @@ impl<'a> ResolutionContext<'a> {
             focused: self.focused,
             scope: Some(container_id),
             history: self.history.clone(),
+            scoring: self.scoring.clone(),
         }
     }
 
@@ -84,7 +95,7 @@ impl<'a> ResolutionContext<'a> {
     }
 
     pub fn to_resolver_context(&self) -> oryn_common::resolver::ResolverContext {
-        oryn_common::resolver::ResolverContext::new(self.scan)
+        oryn_common::resolver::ResolverContext::new(self.scan).with_scoring(self.scoring.clone())
     }
 }
 
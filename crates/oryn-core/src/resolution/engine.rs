@@ -14,7 +14,7 @@ use super::{
 };
 use crate::ast;
 use oryn_common::protocol::ScanResult;
-use oryn_common::resolver::{self, Target};
+use oryn_common::resolver::{self, ScoringConfig, Target, TextMatch};
 
 use async_recursion::async_recursion;
 
@@ -65,7 +65,17 @@ impl ResolutionEngine {
         scan: &ScanResult,
         selector_resolver: &mut S,
     ) -> Result<ast::Command, ResolutionError> {
-        let ctx = ResolutionContext::new(scan);
+        Self::resolve_with_scoring(cmd, scan, ScoringConfig::default(), selector_resolver).await
+    }
+
+    /// Like [`Self::resolve`], scoring text matches with `scoring`.
+    pub async fn resolve_with_scoring<S: SelectorResolver + Send>(
+        cmd: ast::Command,
+        scan: &ScanResult,
+        scoring: ScoringConfig,
+        selector_resolver: &mut S,
+    ) -> Result<ast::Command, ResolutionError> {
+        let ctx = ResolutionContext::new(scan).with_scoring(scoring);
 
         Self::resolve_command(cmd, &ctx, selector_resolver).await
     }
@@ -81,12 +91,14 @@ impl ResolutionEngine {
             ast::Target {
                 atomic: ast::TargetAtomic::Id(id as usize),
                 relation: None,
+                state: None,
             }
         }
 
         fn make_click_cmd(id: u32) -> ast::Command {
             ast::Command::Click(ast::ClickCmd {
                 target: make_id_target(id),
+                all: false,
                 double: false,
                 right: false,
                 middle: false,
@@ -95,6 +107,7 @@ impl ResolutionEngine {
                 shift: false,
                 alt: false,
                 timeout: None,
+                wait_after: None,
             })
         }
 
@@ -166,6 +179,13 @@ impl ResolutionEngine {
                 Ok(ast::Command::Hover(cmd))
             }
 
+            ast::Command::Highlight(mut cmd) => {
+                if let Some(target) = &cmd.target {
+                    cmd.target = Some(make_id_target(resolve_target_to_id!(target)));
+                }
+                Ok(ast::Command::Highlight(cmd))
+            }
+
             ast::Command::Select(mut cmd) => {
                 cmd.target = make_id_target(resolve_target_to_id!(&cmd.target));
                 Ok(ast::Command::Select(cmd))
@@ -262,11 +282,25 @@ impl ResolutionEngine {
             }
 
             // Semantic targets - use existing resolver
-            Target::Text(s) | Target::Role(s) => {
+            Target::Text(s)
+            | Target::TextMatch(TextMatch { value: s, .. })
+            | Target::Role(s)
+            | Target::TestId(s)
+            | Target::RoleText { text: s, .. } => {
                 let strategy = requirement.to_strategy();
                 let resolved_id =
                     match resolver::resolve_target(target, &ctx.to_resolver_context(), strategy) {
                         Ok(Target::Id(id)) => Some(id as u32),
+                        // Test ids are exact hooks, a role narrows the text and a
+                        // match mode restricts it; a loose text search would defeat
+                        // any of them
+                        _ if matches!(
+                            target,
+                            Target::TestId(_) | Target::RoleText { .. } | Target::TextMatch(_)
+                        ) =>
+                        {
+                            None
+                        }
                         _ => Self::find_element_by_text_or_selector(ctx, s),
                     };
 
@@ -348,12 +382,14 @@ impl ResolutionEngine {
                 }
             }
 
-            // Relational targets - use existing resolver (already recursive)
+            // Relational and state-filtered targets - use existing resolver (already recursive)
             Target::Near { .. }
             | Target::Inside { .. }
             | Target::After { .. }
             | Target::Before { .. }
-            | Target::Contains { .. } => {
+            | Target::Contains { .. }
+            | Target::Between { .. }
+            | Target::State { .. } => {
                 let strategy = requirement.to_strategy();
                 match resolver::resolve_target(target, &ctx.to_resolver_context(), strategy) {
                     Ok(Target::Id(id)) => Ok(id as u32),
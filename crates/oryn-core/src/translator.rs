@@ -1,12 +1,18 @@
-use crate::ast::{Command, ExtractWhat, Target, TargetAtomic, WaitCondition};
+use crate::ast::{
+    ClickCmd, Command, CountComparison, EvalArg, ExtractWhat, FrameTarget, Target, TargetAtomic,
+    WaitCondition,
+};
 use oryn_common::protocol::{
     AcceptRequest, Action, BackRequest, BrowserAction, CheckRequest, ClearRequest, ClickRequest,
     CookieRequest, DismissRequest, ExecuteRequest, ExtractRequest, FocusRequest, ForwardRequest,
-    GetHtmlRequest, GetTextRequest, HoverRequest, LoginRequest, MouseButton, NavigateRequest,
-    PdfRequest, RefreshRequest, ScanRequest, ScannerAction, ScreenshotRequest, ScrollDirection,
-    ScrollRequest, SearchRequest, SelectRequest, SessionAction, SubmitRequest, TabRequest,
-    TypeRequest, WaitRequest,
+    FrameRequest, GetHtmlRequest, GetTextRequest, HeadersRequest, HighlightRequest, HoverRequest,
+    InterceptAction, InterceptRequest, InterceptRule, LoginRequest, MouseButton, NavigateRequest,
+    PdfOptions, PdfRequest, Rect, RefreshRequest, RequestsRequest, ScanRequest, ScannerAction,
+    ScreenshotRequest, ScrollBehavior, ScrollDirection, ScrollRequest, SearchRequest,
+    SelectRequest, SessionAction, SubmitRequest, TabRequest, TypeRequest, WaitRequest,
 };
+use oryn_common::resolver::{self, ResolverContext, ResolverError};
+use std::collections::HashMap;
 use thiserror::Error;
 
 /// Normalize a URL by adding https:// if no protocol is specified
@@ -45,6 +51,8 @@ pub enum TranslationError {
     UnsupportedTarget(String),
     #[error("Unsupported command: {0}")]
     Unsupported(String),
+    #[error("Resolution error: {0}")]
+    Resolution(#[from] ResolverError),
 }
 
 struct ActionTarget {
@@ -121,13 +129,18 @@ fn extract_wait_target(target: &Target) -> Result<WaitTarget, TranslationError>
             selector: None,
             text: Some(text.clone()),
         }),
+        TargetAtomic::TextMatch(m) => Err(TranslationError::UnsupportedTarget(format!(
+            "Wait does not support match modes ({}); use plain text",
+            m
+        ))),
         _ => Err(TranslationError::InvalidTarget(
             "Wait requires ID, CSS selector, or text target".into(),
         )),
     }
 }
 
-fn parse_duration_ms(value: &str) -> Option<u64> {
+/// Parses `500ms`, `5s`, `2m` or a bare millisecond count.
+pub fn parse_duration_ms(value: &str) -> Option<u64> {
     if let Some(ms) = value.strip_suffix("ms") {
         return ms.parse::<u64>().ok();
     }
@@ -140,11 +153,102 @@ fn parse_duration_ms(value: &str) -> Option<u64> {
     value.parse::<u64>().ok()
 }
 
+fn click_action(cmd: &ClickCmd, target: ActionTarget) -> Action {
+    let button = if cmd.right {
+        MouseButton::Right
+    } else if cmd.middle {
+        MouseButton::Middle
+    } else {
+        MouseButton::Left
+    };
+    Action::Scanner(ScannerAction::Click(ClickRequest {
+        id: target.id,
+        selector: target.selector,
+        button,
+        double: cmd.double,
+        modifiers: vec![], // TODO: extract from options if parser supported
+        force: cmd.force,
+    }))
+}
+
+/// Translate a command that may expand into several actions.
+///
+/// `click --all` resolves its target against `ctx` with
+/// [`resolver::resolve_all`] and yields one click per match, ordered top to
+/// bottom as the elements appear in the scan. A plain text target matches
+/// whole text only, as `="text"` would, so `click "Remove" --all` leaves
+/// "Remove all" alone; `^"Remove"` opts back into wider matches. Every other
+/// command yields the single action [`translate`] produces.
+pub fn translate_all(
+    command: &Command,
+    ctx: &ResolverContext,
+) -> Result<Vec<Action>, TranslationError> {
+    let Command::Click(cmd) = command else {
+        return translate(command).map(|action| vec![action]);
+    };
+    if !cmd.all {
+        return translate(command).map(|action| vec![action]);
+    }
+
+    let target = match cmd.target.to_resolver_target() {
+        resolver::Target::Text(value) => resolver::Target::TextMatch(resolver::TextMatch {
+            value,
+            mode: resolver::TextMatchMode::Exact,
+        }),
+        target => target,
+    };
+    let mut ids = Vec::new();
+    for target in resolver::resolve_all(&target, ctx)? {
+        match target {
+            resolver::Target::Id(id) => ids.push(id as u32),
+            _ => {
+                return Err(TranslationError::InvalidTarget(
+                    "click --all requires a text, role or relational target".into(),
+                ));
+            }
+        }
+    }
+    let position = |id: &u32| ctx.elements().iter().position(|e| e.id == *id);
+    ids.sort_by_key(position);
+
+    Ok(ids
+        .into_iter()
+        .map(|id| {
+            click_action(
+                cmd,
+                ActionTarget {
+                    id: Some(id),
+                    selector: None,
+                },
+            )
+        })
+        .collect())
+}
+
+/// Route a single-page app to `args.url`, resolved against the current page.
+/// Cross-origin URLs are rejected since `pushState` cannot change the origin.
+const SPA_ROUTE_SCRIPT: &str = r#"
+const target = new URL(args.url, window.location.href);
+if (target.origin !== window.location.origin) {
+    throw new Error('goto --spa cannot leave ' + window.location.origin);
+}
+window.history.pushState({}, '', target.href);
+window.dispatchEvent(new PopStateEvent('popstate', { state: {} }));
+return window.location.href;
+"#;
+
 pub fn translate(command: &Command) -> Result<Action, TranslationError> {
     match command {
         // --- Navigation ---
+        Command::Goto(cmd) if cmd.spa => {
+            Ok(Action::Scanner(ScannerAction::Execute(ExecuteRequest {
+                script: SPA_ROUTE_SCRIPT.into(),
+                args: serde_json::json!({ "url": cmd.url }),
+            })))
+        }
         Command::Goto(cmd) => Ok(Action::Browser(BrowserAction::Navigate(NavigateRequest {
             url: normalize_url(&cmd.url),
+            referer: cmd.referer.clone(),
         }))),
         Command::Back => Ok(Action::Browser(BrowserAction::Back(BackRequest::default()))),
         Command::Forward => Ok(Action::Browser(BrowserAction::Forward(
@@ -155,13 +259,13 @@ pub fn translate(command: &Command) -> Result<Action, TranslationError> {
         }))),
         Command::Url => Ok(Action::Scanner(ScannerAction::Execute(ExecuteRequest {
             script: "return window.location.href;".into(),
-            args: vec![],
+            args: serde_json::Value::Null,
         }))),
 
         // --- Observation ---
         Command::Observe(cmd) => Ok(Action::Scanner(ScannerAction::Scan(ScanRequest {
             max_elements: None, // TODO
-            monitor_changes: cmd.diff,
+            monitor_changes: cmd.diff || cmd.monitor,
             include_hidden: cmd.hidden,
             view_all: cmd.full,
             near: cmd.near.clone(),
@@ -170,43 +274,44 @@ pub fn translate(command: &Command) -> Result<Action, TranslationError> {
         }))),
         Command::Html(cmd) => Ok(Action::Scanner(ScannerAction::GetHtml(GetHtmlRequest {
             selector: cmd.selector.clone(),
-            outer: true,
+            outer: !cmd.inner,
         }))),
         Command::Text(cmd) => Ok(Action::Scanner(ScannerAction::GetText(GetTextRequest {
             selector: cmd.selector.clone(),
         }))),
         Command::Title => Ok(Action::Scanner(ScannerAction::Execute(ExecuteRequest {
             script: "return document.title;".into(),
-            args: vec![],
+            args: serde_json::Value::Null,
         }))),
+        Command::Screenshot(cmd) if cmd.element_only && cmd.target.is_none() => Err(
+            TranslationError::InvalidArgument("screenshot --element-only needs a target".into()),
+        ),
+        Command::Screenshot(cmd) if cmd.padding.is_some() && !cmd.element_only => {
+            Err(TranslationError::InvalidArgument(
+                "screenshot --padding only applies with --element-only".into(),
+            ))
+        }
         Command::Screenshot(cmd) => Ok(Action::Browser(BrowserAction::Screenshot(
             ScreenshotRequest {
                 output: cmd.output.clone(),
                 format: cmd.format.clone(),
                 selector: None, // target?
                 fullpage: cmd.fullpage,
+                wait_stable: cmd.wait_stable,
+                clip: cmd.clip.map(|(x, y, width, height)| Rect {
+                    x,
+                    y,
+                    width,
+                    height,
+                }),
             },
         ))),
 
         // --- Actions ---
-        Command::Click(cmd) => {
-            let target = extract_action_target(&cmd.target, "Click")?;
-            let button = if cmd.right {
-                MouseButton::Right
-            } else if cmd.middle {
-                MouseButton::Middle
-            } else {
-                MouseButton::Left
-            };
-            Ok(Action::Scanner(ScannerAction::Click(ClickRequest {
-                id: target.id,
-                selector: target.selector,
-                button,
-                double: cmd.double,
-                modifiers: vec![], // TODO: extract from options if parser supported
-                force: cmd.force,
-            })))
-        }
+        Command::Click(cmd) => Ok(click_action(
+            cmd,
+            extract_action_target(&cmd.target, "Click")?,
+        )),
         Command::Type(cmd) => {
             let target = extract_action_target(&cmd.target, "Type")?;
             Ok(Action::Scanner(ScannerAction::Type(TypeRequest {
@@ -216,6 +321,7 @@ pub fn translate(command: &Command) -> Result<Action, TranslationError> {
                 clear: cmd.clear,
                 submit: cmd.enter,
                 delay: cmd.delay.map(|d| d as u64),
+                secret: cmd.secret,
             })))
         }
         Command::Clear(cmd) => {
@@ -258,11 +364,46 @@ pub fn translate(command: &Command) -> Result<Action, TranslationError> {
         }
         Command::Hover(cmd) => {
             let target = extract_action_target(&cmd.target, "Hover")?;
+            // The executor holds the hover, so the duration goes no further,
+            // but an unreadable one is still rejected here.
+            if let Some(d) = &cmd.duration {
+                parse_duration_ms(d).ok_or_else(|| {
+                    TranslationError::InvalidArgument(format!("--duration {}", d))
+                })?;
+            }
             Ok(Action::Scanner(ScannerAction::Hover(HoverRequest {
                 id: target.id,
                 selector: target.selector,
             })))
         }
+        Command::Highlight(cmd) => {
+            let target = match &cmd.target {
+                Some(target) if !cmd.clear => extract_action_target(target, "Highlight")?,
+                _ => ActionTarget {
+                    id: None,
+                    selector: None,
+                },
+            };
+            let duration_ms = cmd
+                .duration
+                .as_deref()
+                .map(|d| {
+                    parse_duration_ms(d).ok_or_else(|| {
+                        TranslationError::InvalidArgument(format!("--duration {}", d))
+                    })
+                })
+                .transpose()?;
+            Ok(Action::Scanner(ScannerAction::Highlight(
+                HighlightRequest {
+                    id: target.id,
+                    selector: target.selector,
+                    color: cmd.color.clone(),
+                    duration_ms,
+                    persist: cmd.persist,
+                    clear: cmd.clear,
+                },
+            )))
+        }
         Command::Focus(cmd) => {
             let target = extract_action_target(&cmd.target, "Focus")?;
             Ok(Action::Scanner(ScannerAction::Focus(FocusRequest {
@@ -302,6 +443,11 @@ pub fn translate(command: &Command) -> Result<Action, TranslationError> {
                 } else {
                     None
                 }),
+                behavior: if cmd.smooth {
+                    ScrollBehavior::Smooth
+                } else {
+                    ScrollBehavior::Instant
+                },
             })))
         }
 
@@ -314,6 +460,7 @@ pub fn translate(command: &Command) -> Result<Action, TranslationError> {
             };
             let mut expression = None;
             let mut count = None;
+            let mut compare = None;
             let cond_str = match &cmd.condition {
                 WaitCondition::Visible(t) => {
                     target = extract_wait_target(t)?;
@@ -339,9 +486,13 @@ pub fn translate(command: &Command) -> Result<Action, TranslationError> {
                     expression = Some(s.clone());
                     "custom"
                 }
-                WaitCondition::Items { selector, count: c } => {
-                    target.selector = Some(selector.clone());
-                    count = Some(c.round() as u64);
+                WaitCondition::Items(items) => {
+                    target.selector = Some(items.selector.clone());
+                    count = Some(items.count);
+                    // At least is what the scanner assumes without one.
+                    if items.op != CountComparison::AtLeast {
+                        compare = Some(items.op.symbol().to_string());
+                    }
                     "count"
                 }
                 _ => "unknown",
@@ -353,7 +504,9 @@ pub fn translate(command: &Command) -> Result<Action, TranslationError> {
                 text: target.text,
                 expression,
                 count,
+                compare,
                 timeout: cmd.timeout.as_ref().and_then(|t| parse_duration_ms(t)),
+                poll_interval: None,
             })))
         }
 
@@ -379,18 +532,108 @@ pub fn translate(command: &Command) -> Result<Action, TranslationError> {
             let (action, name, value, domain) = match &cmd.action {
                 CA::List => ("list", None, None, None),
                 CA::Get(n) => ("get", Some(n.clone()), None, None),
+                CA::GetAll => ("get_all", None, None, None),
                 CA::Set { name, value } => ("set", Some(name.clone()), Some(value.clone()), None),
                 CA::Delete(n) => ("delete", Some(n.clone()), None, None),
                 CA::Clear => ("clear", None, None, None),
+                CA::Export { .. } => ("export", None, None, None),
+                CA::Import { .. } => ("import", None, None, None),
+            };
+            let (file, format) = match &cmd.action {
+                CA::Export { path, format } | CA::Import { path, format } => {
+                    (Some(path.clone()), format.clone())
+                }
+                _ => (None, None),
             };
             Ok(Action::Session(SessionAction::Cookie(CookieRequest {
                 action: action.into(),
                 name,
                 value,
                 domain,
+                json: cmd.json,
+                file,
+                format,
+            })))
+        }
+        Command::Headers(cmd) => {
+            use crate::ast::HeadersAction as HA;
+            let (action, headers, domain) = match cmd.action.as_ref().unwrap_or(&HA::List) {
+                HA::Set { domain, json } => {
+                    let headers: HashMap<String, String> =
+                        serde_json::from_str(json).map_err(|e| {
+                            TranslationError::InvalidArgument(format!("headers JSON: {}", e))
+                        })?;
+                    ("set", Some(headers), domain.clone())
+                }
+                HA::SetHeader { name, value } => (
+                    "set",
+                    Some(HashMap::from([(name.clone(), value.clone())])),
+                    None,
+                ),
+                HA::Clear(domain) => ("clear", None, domain.clone()),
+                HA::List => ("view", None, None),
+                HA::Show(domain) => ("view", None, Some(domain.clone())),
+            };
+            Ok(Action::Session(SessionAction::Headers(HeadersRequest {
+                action: action.into(),
+                headers,
+                domain,
             })))
         }
-        // ... (Storage, Headers, etc)
+        // ... (Storage, etc)
+
+        // --- Network ---
+        Command::Intercept(cmd) => {
+            use crate::ast::{InterceptAction as IA, InterceptCmd as IC};
+            let request = match cmd {
+                IC::Clear(pattern) => InterceptRequest::Clear {
+                    pattern: pattern.clone(),
+                },
+                IC::Rule(rule) => {
+                    let (action, body_file) = match &rule.action {
+                        IA::Block => (InterceptAction::Block, None),
+                        IA::Continue => (InterceptAction::Continue, None),
+                        IA::Mock {
+                            status,
+                            body,
+                            content_type,
+                        } => (
+                            InterceptAction::Mock {
+                                status: *status,
+                                body: body.clone(),
+                                content_type: content_type.clone(),
+                            },
+                            None,
+                        ),
+                        IA::MockFile {
+                            status,
+                            path,
+                            content_type,
+                        } => (
+                            InterceptAction::Mock {
+                                status: *status,
+                                body: String::new(),
+                                content_type: content_type.clone(),
+                            },
+                            Some(path.clone()),
+                        ),
+                    };
+                    InterceptRequest::Add {
+                        rule: InterceptRule {
+                            url_pattern: rule.url_pattern.clone(),
+                            action,
+                        },
+                        body_file,
+                    }
+                }
+            };
+            Ok(Action::Browser(BrowserAction::Intercept(request)))
+        }
+        Command::Requests(cmd) => Ok(Action::Browser(BrowserAction::Requests(RequestsRequest {
+            filter: cmd.filter.clone(),
+            method: cmd.method.clone(),
+            last: cmd.last.map(|n| n as usize),
+        }))),
 
         // --- Intents ---
         Command::Login(cmd) => Ok(Action::Scanner(ScannerAction::Login(LoginRequest {
@@ -430,6 +673,48 @@ pub fn translate(command: &Command) -> Result<Action, TranslationError> {
             index: None,
         }))),
 
+        Command::Frames => Ok(Action::Browser(BrowserAction::Frame(FrameRequest {
+            action: "list".into(),
+            target: None,
+            name: None,
+            url: None,
+            selector: None,
+            element: None,
+        }))),
+
+        Command::Frame(cmd) => {
+            let mut req = FrameRequest {
+                action: "switch".into(),
+                target: None,
+                name: None,
+                url: None,
+                selector: None,
+                element: None,
+            };
+            match &cmd.target {
+                FrameTarget::Main => req.target = Some("main".into()),
+                FrameTarget::Parent => req.target = Some("parent".into()),
+                FrameTarget::Name(name) => req.name = Some(name.clone()),
+                FrameTarget::UrlContains(url) => req.url = Some(url.clone()),
+                FrameTarget::Target(Target {
+                    atomic: TargetAtomic::Id(id),
+                    relation: None,
+                    state: None,
+                }) => req.element = Some(*id as u32),
+                FrameTarget::Target(Target {
+                    atomic: TargetAtomic::Selector { kind, value },
+                    relation: None,
+                    state: None,
+                }) if kind == "css" => req.selector = Some(value.clone()),
+                FrameTarget::Target(_) => {
+                    return Err(TranslationError::UnsupportedTarget(
+                        "frame switches by name (frame \"checkout\"), URL (frame url \"stripe.com\"), CSS selector (frame \"#payment\") or element id (frame 3)".into(),
+                    ));
+                }
+            }
+            Ok(Action::Browser(BrowserAction::Frame(req)))
+        }
+
         Command::Tab(cmd) => {
             // Ast TabActionCmd -> Protocol TabAction -> TabRequest
             let (action, url, tab_id, index) = match &cmd.action {
@@ -445,12 +730,37 @@ pub fn translate(command: &Command) -> Result<Action, TranslationError> {
             })))
         }
 
+        Command::Eval(cmd) => {
+            let mut args = serde_json::Map::new();
+            for (name, arg) in &cmd.args {
+                let value = match arg {
+                    EvalArg::Value(v) => v.clone(),
+                    EvalArg::Variable(var) => {
+                        return Err(TranslationError::InvalidArgument(format!(
+                            "unresolved variable ${} for --arg {}",
+                            var, name
+                        )));
+                    }
+                };
+                args.insert(name.clone(), value);
+            }
+            Ok(Action::Scanner(ScannerAction::Execute(ExecuteRequest {
+                script: cmd.script.clone(),
+                args: serde_json::Value::Object(args),
+            })))
+        }
+
+        // The executor stores the result; the request is the eval's own.
+        Command::Set(cmd) => translate(&Command::Eval(cmd.eval.clone())),
+
         Command::Pdf(cmd) => Ok(Action::Browser(BrowserAction::Pdf(PdfRequest {
             path: cmd.path.clone(),
-            format: cmd.format.clone(),
-            landscape: cmd.landscape,
-            margin: cmd.margin.clone(),
-            scale: None, // AST doesn't have scale? Protocol does. Default None.
+            options: PdfOptions {
+                format: cmd.format.clone(),
+                landscape: cmd.landscape,
+                margin: cmd.margin,
+                scale: cmd.scale,
+            },
         }))),
 
         _ => Err(TranslationError::Unsupported(format!("{:?}", command))),
@@ -461,6 +771,299 @@ pub fn translate(command: &Command) -> Result<Action, TranslationError> {
 mod tests {
     use super::*;
 
+    fn translate_headers(input: &str) -> HeadersRequest {
+        let script = crate::parser::parse(input).unwrap();
+        match translate(script.lines[0].command.as_ref().unwrap()).unwrap() {
+            Action::Session(SessionAction::Headers(req)) => req,
+            other => panic!("Expected headers request, got {:?}", other),
+        }
+    }
+
+    fn remove_buttons() -> ResolverContext {
+        use oryn_common::protocol::{
+            Element, ElementState, PageInfo, Rect, ScanResult, ScanStats, ScrollInfo, ViewportInfo,
+        };
+
+        let button = |id: u32, text: &str| Element {
+            id,
+            element_type: "button".into(),
+            role: Some("button".into()),
+            text: Some(text.into()),
+            label: None,
+            value: None,
+            placeholder: None,
+            selector: format!("#remove-{}", id),
+            xpath: None,
+            rect: Rect {
+                x: 0.0,
+                y: id as f32 * 40.0,
+                width: 80.0,
+                height: 30.0,
+            },
+            attributes: HashMap::new(),
+            state: ElementState::default(),
+            children: vec![],
+            count: None,
+            tab_index: None,
+            focusable: false,
+        };
+        ResolverContext::new(&ScanResult {
+            page: PageInfo {
+                url: "https://example.com/cart".into(),
+                title: "Cart".into(),
+                viewport: ViewportInfo::default(),
+                scroll: ScrollInfo::default(),
+                ready_state: None,
+            },
+            // "Remove all" only contains the text, so plain targets skip it.
+            elements: vec![
+                button(4, "Remove all"),
+                button(5, "Remove"),
+                button(6, "Checkout"),
+                button(7, "Remove"),
+            ],
+            stats: ScanStats {
+                total: 4,
+                scanned: 4,
+                iframes: None,
+            },
+            patterns: None,
+            changes: None,
+            available_intents: None,
+            full_mode: false,
+            settings_applied: None,
+            timing: None,
+        })
+    }
+
+    #[test]
+    fn test_translate_click_all_in_document_order() {
+        let ctx = remove_buttons();
+        for input in [r#"click all "Remove""#, r#"click "Remove" --all"#] {
+            let script = crate::parser::parse(input).unwrap();
+            let actions = translate_all(script.lines[0].command.as_ref().unwrap(), &ctx).unwrap();
+            let clicks: Vec<&ClickRequest> = actions
+                .iter()
+                .map(|a| match a {
+                    Action::Scanner(ScannerAction::Click(req)) => req,
+                    other => panic!("Expected click request, got {:?}", other),
+                })
+                .collect();
+            let ids: Vec<Option<u32>> = clicks.iter().map(|c| c.id).collect();
+            assert_eq!(ids, vec![Some(5), Some(7)], "{}", input);
+        }
+    }
+
+    #[test]
+    fn test_translate_click_all_prefix_includes_longer_text() {
+        let script = crate::parser::parse(r#"click ^"Remove" --all"#).unwrap();
+        let actions =
+            translate_all(script.lines[0].command.as_ref().unwrap(), &remove_buttons()).unwrap();
+        let ids: Vec<Option<u32>> = actions
+            .iter()
+            .map(|a| match a {
+                Action::Scanner(ScannerAction::Click(req)) => req.id,
+                other => panic!("Expected click request, got {:?}", other),
+            })
+            .collect();
+        assert_eq!(ids, vec![Some(4), Some(5), Some(7)]);
+    }
+
+    #[test]
+    fn test_translate_all_single_click_is_unchanged() {
+        let script = crate::parser::parse("click 5").unwrap();
+        let actions =
+            translate_all(script.lines[0].command.as_ref().unwrap(), &remove_buttons()).unwrap();
+        assert_eq!(actions.len(), 1);
+    }
+
+    #[test]
+    fn test_translate_highlight_options() {
+        let translate_highlight = |input: &str| {
+            let script = crate::parser::parse(input).unwrap();
+            match translate(script.lines[0].command.as_ref().unwrap()).unwrap() {
+                Action::Scanner(ScannerAction::Highlight(req)) => req,
+                other => panic!("Expected highlight request, got {:?}", other),
+            }
+        };
+
+        let req = translate_highlight("highlight 3 --color red --duration 2000");
+        assert_eq!(req.id, Some(3));
+        assert_eq!(req.color.as_deref(), Some("red"));
+        assert_eq!(req.duration_ms, Some(2000));
+        assert!(!req.persist);
+
+        let req = translate_highlight("highlight 3 --duration 2s --persist");
+        assert_eq!(req.duration_ms, Some(2000));
+        assert!(req.persist);
+
+        let req = translate_highlight("highlight clear");
+        assert!(req.clear);
+        assert_eq!(req.id, None);
+    }
+
+    #[test]
+    fn test_translate_intercept() {
+        let translate_intercept = |input: &str| {
+            let script = crate::parser::parse(input).unwrap();
+            match translate(script.lines[0].command.as_ref().unwrap()).unwrap() {
+                Action::Browser(BrowserAction::Intercept(req)) => req,
+                other => panic!("Expected intercept request, got {:?}", other),
+            }
+        };
+
+        let req = translate_intercept(r#"intercept mock "/api/user" 200 '{"name":"x"}'"#);
+        assert_eq!(
+            serde_json::to_value(Action::Browser(BrowserAction::Intercept(req))).unwrap(),
+            serde_json::json!({
+                "action": "intercept",
+                "op": "add",
+                "rule": {
+                    "url_pattern": "/api/user",
+                    "action": { "type": "mock", "status": 200, "body": r#"{"name":"x"}"# }
+                }
+            })
+        );
+
+        let req = translate_intercept(r#"intercept "/data" --respond-file resp.json --status 500"#);
+        let InterceptRequest::Add { rule, body_file } = req else {
+            panic!("Expected a rule, got {:?}", req);
+        };
+        assert_eq!(body_file.as_deref(), Some("resp.json"));
+        assert!(matches!(
+            rule.action,
+            InterceptAction::Mock { status: 500, .. }
+        ));
+
+        assert_eq!(
+            translate_intercept("intercept clear"),
+            InterceptRequest::Clear { pattern: None }
+        );
+    }
+
+    #[test]
+    fn test_translate_html_forwards_selector() {
+        let translate_html = |input: &str| {
+            let script = crate::parser::parse(input).unwrap();
+            match translate(script.lines[0].command.as_ref().unwrap()).unwrap() {
+                Action::Scanner(ScannerAction::GetHtml(req)) => req,
+                other => panic!("Expected get_html request, got {:?}", other),
+            }
+        };
+
+        let req = translate_html(r##"html --selector "#main""##);
+        assert_eq!(req.selector.as_deref(), Some("#main"));
+        assert!(req.outer);
+        assert_eq!(
+            serde_json::to_value(ScannerAction::GetHtml(req)).unwrap(),
+            serde_json::json!({ "action": "get_html", "selector": "#main", "outer": true })
+        );
+
+        let req = translate_html(r##"html --selector "#main" --inner"##);
+        assert!(!req.outer);
+
+        let req = translate_html("html");
+        assert_eq!(req.selector, None);
+        assert!(req.outer);
+    }
+
+    #[test]
+    fn test_translate_headers() {
+        let req = translate_headers(r#"headers set X-Token "abc""#);
+        assert_eq!(req.action, "set");
+        assert_eq!(
+            req.headers,
+            Some(HashMap::from([("X-Token".to_string(), "abc".to_string())]))
+        );
+        assert_eq!(req.domain, None);
+
+        let req = translate_headers(r#"headers set "example.com" "{\"A\": \"1\", \"B\": \"2\"}""#);
+        assert_eq!(req.action, "set");
+        assert_eq!(req.headers.unwrap().len(), 2);
+        assert_eq!(req.domain.as_deref(), Some("example.com"));
+
+        let req = translate_headers("headers list");
+        assert_eq!((req.action.as_str(), req.domain), ("view", None));
+
+        let req = translate_headers(r#"headers clear "example.com""#);
+        assert_eq!(req.action, "clear");
+        assert_eq!(req.domain.as_deref(), Some("example.com"));
+    }
+
+    #[test]
+    fn test_translate_headers_rejects_bad_json() {
+        let script = crate::parser::parse(r#"headers set "{not json}""#).unwrap();
+        let err = translate(script.lines[0].command.as_ref().unwrap()).unwrap_err();
+        assert!(matches!(err, TranslationError::InvalidArgument(_)));
+    }
+
+    #[test]
+    fn test_translate_screenshot_clip() {
+        let script =
+            crate::parser::parse("screenshot --clip 0,0,800,600 --output shot.png").unwrap();
+        match translate(script.lines[0].command.as_ref().unwrap()).unwrap() {
+            Action::Browser(BrowserAction::Screenshot(req)) => {
+                let clip = req.clip.expect("clip should reach the request");
+                assert_eq!(
+                    (clip.x, clip.y, clip.width, clip.height),
+                    (0.0, 0.0, 800.0, 600.0)
+                );
+                assert_eq!(req.output.as_deref(), Some("shot.png"));
+            }
+            other => panic!("Expected screenshot request, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_translate_screenshot_padding_needs_element_only() {
+        for line in [
+            "screenshot --element-only",
+            r#"screenshot "Card" --padding 10"#,
+        ] {
+            let script = crate::parser::parse(line).unwrap();
+            assert!(
+                matches!(
+                    translate(script.lines[0].command.as_ref().unwrap()),
+                    Err(TranslationError::InvalidArgument(_))
+                ),
+                "{} should be rejected",
+                line
+            );
+        }
+    }
+
+    #[test]
+    fn test_translate_wait_items_passes_the_comparison() {
+        let count = |line: &str| {
+            let script = crate::parser::parse(line).unwrap();
+            match translate(script.lines[0].command.as_ref().unwrap()).unwrap() {
+                Action::Scanner(ScannerAction::Wait(req)) => (req.count, req.compare),
+                other => panic!("Expected wait request, got {:?}", other),
+            }
+        };
+        assert_eq!(count(r#"wait items ".row" 5"#), (Some(5), None));
+        assert_eq!(
+            count(r#"wait items ".row" > 5"#),
+            (Some(5), Some(">".into()))
+        );
+        assert_eq!(
+            count(r#"wait items ".row" <= 5"#),
+            (Some(5), Some("<=".into()))
+        );
+    }
+
+    #[test]
+    fn test_translate_goto_spa_routes_in_page() {
+        let script = crate::parser::parse(r#"goto "/settings?tab=2" --spa"#).unwrap();
+        match translate(script.lines[0].command.as_ref().unwrap()).unwrap() {
+            Action::Scanner(ScannerAction::Execute(req)) => {
+                assert!(req.script.contains("history.pushState"));
+                assert_eq!(req.args, serde_json::json!({ "url": "/settings?tab=2" }));
+            }
+            other => panic!("Expected execute request, got {:?}", other),
+        }
+    }
+
     #[test]
     fn test_normalize_url_adds_https() {
         assert_eq!(normalize_url("google.com"), "https://google.com");
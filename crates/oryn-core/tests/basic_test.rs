@@ -1,4 +1,10 @@
+use oryn_common::protocol::{
+    Element, ElementState, PageInfo, Rect, ScanResult, ScanStats, ScrollInfo, ViewportInfo,
+};
+use oryn_common::resolver::{resolve_target, ResolutionStrategy, ResolverContext, Target};
+use oryn_core::ast::Command;
 use oryn_core::{normalize, parse};
+use std::collections::HashMap;
 
 #[test]
 fn test_parser_basic() {
@@ -17,3 +23,83 @@ fn test_parser_basic() {
 
     assert_eq!(script.lines.len(), 4); // Comment, goto, click, wait
 }
+
+#[test]
+fn test_pasted_typography_parses_like_ascii() {
+    let cases = [
+        ("click \u{201C}Sign in\u{201D}", "click \"Sign in\""),
+        (
+            "click\u{00A0}\u{2018}Submit\u{2019} \u{2014}force",
+            "click 'Submit' --force",
+        ),
+        (
+            "type email \u{201C}me@example.com\u{201D}\u{00A0}\u{2013}append",
+            "type email \"me@example.com\" --append",
+        ),
+        ("observe\u{00A0}\u{2014}full", "observe --full"),
+    ];
+
+    for (pasted, ascii) in cases {
+        let pasted_script = parse(&normalize(pasted)).expect("Failed to parse pasted input");
+        let ascii_script = parse(&normalize(ascii)).expect("Failed to parse ASCII input");
+        assert_eq!(pasted_script, ascii_script, "{}", pasted);
+    }
+}
+
+#[test]
+fn test_smart_quoted_target_matches_straight_text() {
+    let script = parse(&normalize("click \u{201C}Sign In\u{201D}")).expect("Failed to parse");
+    let target = match &script.lines[0].command {
+        Some(Command::Click(cmd)) => cmd.target.to_resolver_target(),
+        other => panic!("Expected click command, got {:?}", other),
+    };
+
+    let button = |id: u32, text: &str| Element {
+        id,
+        element_type: "button".into(),
+        role: None,
+        text: Some(text.into()),
+        label: None,
+        value: None,
+        placeholder: None,
+        selector: format!("#b{}", id),
+        xpath: None,
+        rect: Rect {
+            x: 0.0,
+            y: id as f32 * 40.0,
+            width: 100.0,
+            height: 30.0,
+        },
+        attributes: HashMap::new(),
+        state: ElementState::default(),
+        children: vec![],
+        count: None,
+        tab_index: None,
+        focusable: false,
+    };
+    let scan = ScanResult {
+        page: PageInfo {
+            url: "https://example.com".into(),
+            title: "Home".into(),
+            viewport: ViewportInfo::default(),
+            scroll: ScrollInfo::default(),
+            ready_state: None,
+        },
+        elements: vec![button(1, "Register"), button(2, "Sign In")],
+        stats: ScanStats {
+            total: 2,
+            scanned: 2,
+            iframes: None,
+        },
+        patterns: None,
+        changes: None,
+        available_intents: None,
+        full_mode: false,
+        settings_applied: None,
+        timing: None,
+    };
+
+    let ctx = ResolverContext::new(&scan);
+    let resolved = resolve_target(&target, &ctx, ResolutionStrategy::First);
+    assert!(matches!(resolved, Ok(Target::Id(2))), "{:?}", resolved);
+}
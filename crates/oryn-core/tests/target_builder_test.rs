@@ -0,0 +1,152 @@
+//! Target builder tests.
+//!
+//! Each relational target built in Rust must match what OIL parses to and
+//! resolve against a scan the same way.
+
+use oryn_common::protocol::{
+    Element, ElementState, PageInfo, Rect, ScanResult, ScanStats, ScrollInfo, ViewportInfo,
+};
+use oryn_common::resolver::{self, ResolutionStrategy, ResolverContext};
+use oryn_core::ast::{Command, Target};
+use oryn_core::{normalize, parse};
+use std::collections::HashMap;
+
+fn element(id: u32, text: Option<&str>, element_type: &str, rect: (f32, f32, f32, f32)) -> Element {
+    Element {
+        id,
+        element_type: element_type.into(),
+        role: None,
+        text: text.map(String::from),
+        label: None,
+        value: None,
+        placeholder: None,
+        selector: format!("#el-{}", id),
+        xpath: None,
+        rect: Rect {
+            x: rect.0,
+            y: rect.1,
+            width: rect.2,
+            height: rect.3,
+        },
+        attributes: HashMap::new(),
+        state: ElementState::default(),
+        children: vec![],
+        count: None,
+        tab_index: None,
+        focusable: false,
+    }
+}
+
+/// A checkout form with a label above each input, plus a stray button
+/// outside the form.
+fn checkout_page() -> ScanResult {
+    let elements = vec![
+        element(1, Some("Checkout"), "form", (0.0, 0.0, 400.0, 300.0)),
+        element(2, Some("Email"), "label", (10.0, 10.0, 100.0, 20.0)),
+        element(3, None, "input", (10.0, 40.0, 200.0, 30.0)),
+        element(4, Some("Card"), "label", (10.0, 90.0, 100.0, 20.0)),
+        element(5, None, "input", (10.0, 120.0, 200.0, 30.0)),
+        element(6, Some("Submit"), "button", (10.0, 200.0, 100.0, 30.0)),
+        element(7, Some("Submit"), "button", (600.0, 200.0, 100.0, 30.0)),
+    ];
+    ScanResult {
+        page: PageInfo {
+            url: "https://example.com/checkout".into(),
+            title: "Checkout".into(),
+            viewport: ViewportInfo::default(),
+            scroll: ScrollInfo::default(),
+            ready_state: None,
+        },
+        stats: ScanStats {
+            total: elements.len(),
+            scanned: elements.len(),
+            iframes: None,
+        },
+        elements,
+        patterns: None,
+        changes: None,
+        available_intents: None,
+        full_mode: false,
+        settings_applied: None,
+        timing: None,
+    }
+}
+
+fn parsed_click_target(oil: &str) -> Target {
+    let script = parse(&normalize(oil)).expect("OIL should parse");
+    match script.lines[0].command.clone() {
+        Some(Command::Click(cmd)) => cmd.target,
+        other => panic!("Expected click command, got {:?}", other),
+    }
+}
+
+fn resolve(target: &Target) -> usize {
+    let scan = checkout_page();
+    let ctx = ResolverContext::new(&scan);
+    match resolver::resolve_target(
+        &target.to_resolver_target(),
+        &ctx,
+        ResolutionStrategy::First,
+    ) {
+        Ok(resolver::Target::Id(id)) => id,
+        other => panic!("Expected a resolved id, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_builder_inside() {
+    let target = Target::text("Submit").inside(Target::text("Checkout"));
+    assert_eq!(
+        target,
+        parsed_click_target(r#"click "Submit" inside "Checkout""#)
+    );
+    assert_eq!(resolve(&target), 6);
+}
+
+#[test]
+fn test_builder_near() {
+    let target = Target::css("#el-5").near(Target::text("Card"));
+    assert_eq!(
+        target,
+        parsed_click_target(r##"click css("#el-5") near "Card""##)
+    );
+
+    let target = Target::role("input").near(Target::text("Card"));
+    assert_eq!(resolve(&target), 5);
+}
+
+#[test]
+fn test_builder_after_and_before() {
+    let after = Target::role("input").after(Target::text("Email"));
+    assert_eq!(resolve(&after), 3);
+
+    let before = Target::role("input").before(Target::text("Card"));
+    assert_eq!(resolve(&before), 3);
+}
+
+#[test]
+fn test_builder_contains() {
+    let target = Target::text("Checkout").contains(Target::text("Card"));
+    assert_eq!(
+        target,
+        parsed_click_target(r#"click "Checkout" contains "Card""#)
+    );
+    assert_eq!(resolve(&target), 1);
+}
+
+#[test]
+fn test_builder_between() {
+    let target = Target::role("input").between(Target::text("Email"), Target::text("Card"));
+    assert_eq!(resolve(&target), 3);
+}
+
+#[test]
+fn test_builder_chains_right_associatively() {
+    let target = Target::text("Submit")
+        .near(Target::text("Card"))
+        .inside(Target::text("Checkout"));
+    assert_eq!(
+        target,
+        parsed_click_target(r#"click "Submit" near "Card" inside "Checkout""#)
+    );
+}
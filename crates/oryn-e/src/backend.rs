@@ -1,10 +1,36 @@
 use crate::cog::{self, CogProcess};
 use crate::webdriver::WebDriverClient;
 use async_trait::async_trait;
-use oryn_engine::backend::{Backend, BackendError, NavigationResult};
-use oryn_engine::protocol::{ActionResult, ScannerAction, ScannerData, ScannerProtocolResponse};
+use fantoccini::Locator;
+use oryn_engine::backend::{Backend, BackendCapabilities, BackendError, NavigationResult};
+use oryn_engine::protocol::{
+    ActionResult, Rect, ScannerAction, ScannerData, ScannerProtocolResponse,
+};
+use oryn_engine::registry::BackendRegistry;
 use tracing::{info, warn};
 
+/// Counts the page's unfinished `fetch` and `XMLHttpRequest` calls, wrapping
+/// both on first use, and returns the count. WebDriver has no view of the
+/// network, so requests started before the wrappers went in are not seen.
+const PENDING_REQUESTS_SCRIPT: &str = r#"
+    if (window.__orynPending === undefined) {
+        window.__orynPending = 0;
+        const done = () => { window.__orynPending = Math.max(0, window.__orynPending - 1); };
+        const fetch = window.fetch;
+        window.fetch = function (...args) {
+            window.__orynPending++;
+            return fetch.apply(this, args).finally(done);
+        };
+        const send = XMLHttpRequest.prototype.send;
+        XMLHttpRequest.prototype.send = function (...args) {
+            window.__orynPending++;
+            this.addEventListener('loadend', done, { once: true });
+            return send.apply(this, args);
+        };
+    }
+    return window.__orynPending;
+"#;
+
 pub struct EmbeddedBackend {
     client: Option<WebDriverClient>,
     webdriver_url: Option<String>,
@@ -68,6 +94,17 @@ impl Default for EmbeddedBackend {
     }
 }
 
+/// Register the embedded backend as `embedded`. Options: `driver_url` to use
+/// an external WebDriver instead of launching COG.
+pub fn register(registry: &mut BackendRegistry) {
+    registry.register("embedded", |options| {
+        Ok(Box::new(match options.get("driver_url") {
+            Some(url) => EmbeddedBackend::with_url(url.to_string()),
+            None => EmbeddedBackend::new(),
+        }))
+    });
+}
+
 impl EmbeddedBackend {
     async fn get_navigation_result(
         client: &WebDriverClient,
@@ -130,6 +167,13 @@ impl Backend for EmbeddedBackend {
         Ok(())
     }
 
+    fn capabilities(&self) -> BackendCapabilities {
+        BackendCapabilities {
+            pdf: false,
+            ..BackendCapabilities::all()
+        }
+    }
+
     async fn is_ready(&self) -> bool {
         self.client.is_some()
     }
@@ -261,6 +305,39 @@ impl Backend for EmbeddedBackend {
         Ok(bytes)
     }
 
+    async fn screenshot_clip(&mut self, clip: &Rect) -> Result<Vec<u8>, BackendError> {
+        // WebDriver screenshots have no clip parameter, so lay a transparent
+        // box over the region and let the element screenshot do the cropping.
+        let client = self.client.as_mut().ok_or(BackendError::NotReady)?;
+        let place = r#"
+            const [x, y, width, height] = arguments;
+            const box = document.createElement('div');
+            box.id = '__oryn_clip';
+            box.style.cssText = `position:absolute;left:${x}px;top:${y}px;` +
+                `width:${width}px;height:${height}px;pointer-events:none;z-index:2147483647`;
+            document.documentElement.appendChild(box);
+        "#;
+        let args = vec![
+            clip.x.into(),
+            clip.y.into(),
+            clip.width.into(),
+            clip.height.into(),
+        ];
+        client
+            .client
+            .execute(place, args)
+            .await
+            .map_err(|e| BackendError::Other(format!("Screenshot failed: {}", e)))?;
+
+        let shot = match client.client.find(Locator::Id("__oryn_clip")).await {
+            Ok(region) => region.screenshot().await,
+            Err(e) => Err(e),
+        };
+        let cleanup = "document.getElementById('__oryn_clip')?.remove();";
+        let _ = client.client.execute(cleanup, vec![]).await;
+        shot.map_err(|e| BackendError::Other(format!("Screenshot failed: {}", e)))
+    }
+
     async fn get_cookies(&mut self) -> Result<Vec<oryn_engine::protocol::Cookie>, BackendError> {
         let client = self.client.as_mut().ok_or(BackendError::NotReady)?;
         let cookies = client
@@ -279,10 +356,42 @@ impl Backend for EmbeddedBackend {
                 expires: None, // fantoccini 0.19 Cookie doesn't easily expose expiry for all backends
                 http_only: c.http_only(),
                 secure: c.secure(),
+                same_site: c.same_site().map(|s| s.to_string()),
             })
             .collect())
     }
 
+    async fn set_cookie(
+        &mut self,
+        cookie: oryn_engine::protocol::Cookie,
+    ) -> Result<(), BackendError> {
+        let client = self.client.as_mut().ok_or(BackendError::NotReady)?;
+        // WebDriver cannot store an expired cookie; expiring one deletes it.
+        if cookie.expires.is_some_and(|e| e <= 0.0) {
+            return client
+                .client
+                .delete_cookie(&cookie.name)
+                .await
+                .map_err(|e| BackendError::Other(format!("Delete cookie failed: {}", e)));
+        }
+
+        let mut c = fantoccini::cookies::Cookie::new(cookie.name, cookie.value);
+        if let Some(domain) = cookie.domain {
+            c.set_domain(domain);
+        }
+        if let Some(path) = cookie.path {
+            c.set_path(path);
+        }
+        c.set_secure(cookie.secure);
+        c.set_http_only(cookie.http_only);
+        // Expiry is left unset, as in get_cookies, making this a session cookie.
+        client
+            .client
+            .add_cookie(c)
+            .await
+            .map_err(|e| BackendError::Other(format!("Set cookie failed: {}", e)))
+    }
+
     async fn get_tabs(&mut self) -> Result<Vec<oryn_engine::protocol::TabInfo>, BackendError> {
         let client = self.client.as_mut().ok_or(BackendError::NotReady)?;
         let handles = client
@@ -367,4 +476,17 @@ impl Backend for EmbeddedBackend {
 
         Ok(())
     }
+
+    async fn pending_requests(&mut self) -> Result<usize, BackendError> {
+        let client = self.client.as_mut().ok_or(BackendError::NotReady)?;
+        let count = client
+            .client
+            .execute(PENDING_REQUESTS_SCRIPT, vec![])
+            .await
+            .map_err(|e| BackendError::Other(format!("pending_requests failed: {}", e)))?;
+        count
+            .as_u64()
+            .map(|n| n as usize)
+            .ok_or_else(|| BackendError::Other(format!("Unexpected request count: {}", count)))
+    }
 }
@@ -1,7 +1,7 @@
 use clap::Parser as ClapParser;
 use oryn_e::backend::EmbeddedBackend;
 use oryn_engine::backend::Backend;
-use oryn_engine::cli::{self, FileErrorMode, FileOptions, OutputHandlers, ReplOptions};
+use oryn_engine::cli::{self, FileErrorMode, FileOptions, OutputHandlers, OutputMode, ReplOptions};
 use oryn_engine::executor::CommandExecutor;
 use tracing::{error, info};
 
@@ -62,11 +62,13 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         ctrl_c_message: Some("\nShutdown signal received."),
     };
 
+    let mut exit_code = 0;
     if let Some(file_path) = args.file {
-        cli::run_file(
+        exit_code = cli::run_file(
             &mut backend,
             &mut executor,
             output,
+            OutputMode::Human,
             &file_path,
             FileOptions {
                 stop_on_error: true,
@@ -75,9 +77,19 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         )
         .await?;
     } else {
-        cli::run_repl(&mut backend, &mut executor, output, repl_options).await?;
+        cli::run_repl(
+            &mut backend,
+            &mut executor,
+            output,
+            OutputMode::Human,
+            repl_options,
+        )
+        .await?;
     }
 
     backend.close().await?;
+    if exit_code != 0 {
+        std::process::exit(exit_code);
+    }
     Ok(())
 }
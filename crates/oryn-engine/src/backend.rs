@@ -1,6 +1,10 @@
 use async_trait::async_trait;
 pub use oryn_common::error::backend_error::BackendError;
-use oryn_common::protocol::{ScannerAction, ScannerProtocolResponse};
+use oryn_common::protocol::{
+    ExecuteRequest, FrameInfo, InterceptRule, NetworkRequest, PdfOptions, Rect, ScannerAction,
+    ScannerData, ScannerProtocolResponse,
+};
+use std::time::{Duration, Instant};
 
 #[derive(Debug, Clone, Default)]
 pub struct NavigationResult {
@@ -10,12 +14,62 @@ pub struct NavigationResult {
     pub status: u16,
 }
 
+/// Optional features a backend can provide.
+///
+/// The executor consults these before dispatching so that unsupported commands
+/// fail with guidance instead of a bare `NotSupported` from the backend.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BackendCapabilities {
+    pub pdf: bool,
+    pub cookies: bool,
+    pub tabs: bool,
+}
+
+impl BackendCapabilities {
+    /// Every optional feature available.
+    pub const fn all() -> Self {
+        Self {
+            pdf: true,
+            cookies: true,
+            tabs: true,
+        }
+    }
+}
+
+impl Default for BackendCapabilities {
+    fn default() -> Self {
+        Self::all()
+    }
+}
+
+/// Lists the page's `iframe` and `frame` elements in document order.
+const LIST_FRAMES_SCRIPT: &str = r#"
+return Array.from(document.querySelectorAll('iframe, frame')).map((frame, index) => ({
+    index,
+    name: frame.getAttribute('name') || '',
+    url: frame.src || '',
+}));
+"#;
+
 // Error definitions moved to oryn_common::error::backend_error
 
+/// Hands a backend on as `&mut dyn Backend` from code generic over
+/// `B: Backend + ?Sized`, where the compiler cannot unsize `B` itself.
+/// Implemented for every backend.
+pub trait AsDynBackend {
+    fn as_dyn_backend(&mut self) -> &mut dyn Backend;
+}
+
+impl<T: Backend> AsDynBackend for T {
+    fn as_dyn_backend(&mut self) -> &mut dyn Backend {
+        self
+    }
+}
+
 // Navigation Errors
 /// The Backend trait is the unified interface all three binaries must implement.
 #[async_trait]
-pub trait Backend: Send + Sync {
+pub trait Backend: AsDynBackend + Send + Sync {
     /// Launch the backend (start browser, connect to remote, etc.)
     async fn launch(&mut self) -> Result<(), BackendError>;
 
@@ -25,30 +79,87 @@ pub trait Backend: Send + Sync {
     /// Check if the backend is ready to accept commands.
     async fn is_ready(&self) -> bool;
 
+    /// Optional features supported by this backend.
+    fn capabilities(&self) -> BackendCapabilities {
+        BackendCapabilities::all()
+    }
+
     /// Navigate to a specific URL.
     async fn navigate(&mut self, url: &str) -> Result<NavigationResult, BackendError>;
 
+    /// Navigate to `url`, sending `referer` as the Referer header.
+    async fn navigate_with_referer(
+        &mut self,
+        _url: &str,
+        _referer: &str,
+    ) -> Result<NavigationResult, BackendError> {
+        Err(BackendError::NotSupported("navigate_with_referer".into()))
+    }
+
     /// Execute a raw scanner command.
     async fn execute_scanner(
         &mut self,
         command: ScannerAction,
     ) -> Result<ScannerProtocolResponse, BackendError>;
 
-    /// Execute a script in the browser context.
-    /// This is a convenience method that might wrap a scanner request or call the backend directly.
-    async fn execute_script(&mut self, _script: &str) -> Result<serde_json::Value, BackendError> {
-        // Default implementation via Scanner request?
-        // Or just return NotSupported by default.
-        // Since it's used by Execute action, it should probably be supported or optional.
-        // Let's make it return NotSupported by default.
-        Err(BackendError::NotSupported("execute_script".into()))
+    /// Execute several scanner commands, returning one response per command in
+    /// the same order. The default sends them one at a time; backends with a
+    /// costly round-trip can run the whole batch in a single call.
+    async fn execute_batch(
+        &mut self,
+        commands: Vec<ScannerAction>,
+    ) -> Result<Vec<ScannerProtocolResponse>, BackendError> {
+        let mut responses = Vec::with_capacity(commands.len());
+        for command in commands {
+            responses.push(self.execute_scanner(command).await?);
+        }
+        Ok(responses)
+    }
+
+    /// Execute a script in the browser context and return its result.
+    ///
+    /// `args` is passed to the script as the `args` object rather than spliced
+    /// into its source, so values never need quoting or escaping. The default
+    /// implementation sends a scanner `execute` request.
+    async fn execute_script(
+        &mut self,
+        script: &str,
+        args: serde_json::Map<String, serde_json::Value>,
+    ) -> Result<serde_json::Value, BackendError> {
+        let resp = self
+            .execute_scanner(ScannerAction::Execute(ExecuteRequest {
+                script: script.to_string(),
+                args: serde_json::Value::Object(args),
+            }))
+            .await?;
+        match resp {
+            ScannerProtocolResponse::Ok { data, .. } => match *data {
+                ScannerData::Value(mut value) => Ok(value
+                    .get_mut("result")
+                    .map(serde_json::Value::take)
+                    .unwrap_or(value)),
+                other => Err(BackendError::Scanner(format!(
+                    "Unexpected response to execute: {:?}",
+                    other
+                ))),
+            },
+            ScannerProtocolResponse::Error { message, .. } => {
+                Err(BackendError::ScriptError(message))
+            }
+        }
     }
 
     /// Capture a screenshot of the current viewport.
     async fn screenshot(&mut self) -> Result<Vec<u8>, BackendError>;
 
-    /// Generate a PDF of the current page.
-    async fn pdf(&mut self) -> Result<Vec<u8>, BackendError> {
+    /// Capture a screenshot of a page region, given in CSS pixels from the
+    /// top-left of the document.
+    async fn screenshot_clip(&mut self, _clip: &Rect) -> Result<Vec<u8>, BackendError> {
+        Err(BackendError::NotSupported("screenshot_clip".into()))
+    }
+
+    /// Generate a PDF of the current page laid out with `options`.
+    async fn pdf(&mut self, _options: &PdfOptions) -> Result<Vec<u8>, BackendError> {
         Err(BackendError::NotSupported("pdf".into()))
     }
 
@@ -57,6 +168,14 @@ pub trait Backend: Send + Sync {
         Err(BackendError::NotSupported("get_cookies".into()))
     }
 
+    /// Get every cookie the browser holds, whatever its domain. Backends that
+    /// only see the current page's cookies return those.
+    async fn get_all_cookies(
+        &mut self,
+    ) -> Result<Vec<oryn_common::protocol::Cookie>, BackendError> {
+        self.get_cookies().await
+    }
+
     /// Set a cookie in the current session.
     async fn set_cookie(
         &mut self,
@@ -70,6 +189,22 @@ pub trait Backend: Send + Sync {
         Err(BackendError::NotSupported("get_tabs".into()))
     }
 
+    /// List the frames on the current page in document order. The default
+    /// reads the page's frame elements through `execute_script`.
+    async fn get_frames(&mut self) -> Result<Vec<FrameInfo>, BackendError> {
+        let value = self
+            .execute_script(LIST_FRAMES_SCRIPT, serde_json::Map::new())
+            .await?;
+        serde_json::from_value(value)
+            .map_err(|e| BackendError::Scanner(format!("Unexpected frame list: {}", e)))
+    }
+
+    /// Send later scanner requests to the frame at `index` in
+    /// [`get_frames`](Backend::get_frames), or to the top document for `None`.
+    async fn switch_frame(&mut self, _index: Option<usize>) -> Result<(), BackendError> {
+        Err(BackendError::NotSupported("switch_frame".into()))
+    }
+
     /// Navigate back in browser history.
     async fn go_back(&mut self) -> Result<NavigationResult, BackendError> {
         Err(BackendError::NotSupported("go_back".into()))
@@ -89,4 +224,212 @@ pub trait Backend: Send + Sync {
     async fn press_key(&mut self, _key: &str, _modifiers: &[String]) -> Result<(), BackendError> {
         Err(BackendError::NotSupported("press_key".into()))
     }
+
+    /// Number of network requests the page has started but not finished.
+    ///
+    /// Backends that cannot observe the network leave this unsupported, and
+    /// `wait idle` falls back to the scanner's own idle heuristic.
+    async fn pending_requests(&mut self) -> Result<usize, BackendError> {
+        Err(BackendError::NotSupported("pending_requests".into()))
+    }
+
+    /// Forget the network requests recorded so far.
+    async fn clear_requests(&mut self) -> Result<(), BackendError> {
+        Err(BackendError::NotSupported("clear_requests".into()))
+    }
+
+    /// The network requests recorded so far, oldest first.
+    async fn get_requests(&mut self) -> Result<Vec<NetworkRequest>, BackendError> {
+        Err(BackendError::NotSupported("get_requests".into()))
+    }
+
+    /// Apply `rule` to the page's requests from now on, replacing any rule
+    /// with the same pattern.
+    async fn add_intercept(&mut self, _rule: InterceptRule) -> Result<(), BackendError> {
+        Err(BackendError::NotSupported("intercept".into()))
+    }
+
+    /// Drop the intercept rule for `pattern`, or every rule for `None`.
+    async fn clear_intercepts(&mut self, _pattern: Option<&str>) -> Result<(), BackendError> {
+        Err(BackendError::NotSupported("intercept".into()))
+    }
+
+    /// Forget the console messages recorded so far.
+    async fn clear_console(&mut self) -> Result<(), BackendError> {
+        Err(BackendError::NotSupported("clear_console".into()))
+    }
+}
+
+/// Wraps a backend so that scanner round-trips (`execute_scanner`,
+/// `execute_batch` and `execute_script`) start at least `interval` apart.
+/// Everything else is passed straight through.
+pub(crate) struct ThrottledBackend<'a, B: Backend + ?Sized> {
+    inner: &'a mut B,
+    interval: Duration,
+    last_request: Option<Instant>,
+}
+
+impl<'a, B: Backend + ?Sized> ThrottledBackend<'a, B> {
+    /// `last_request` carries the previous request's start over from an
+    /// earlier wrapper, so spacing holds across calls.
+    pub(crate) fn new(inner: &'a mut B, interval: Duration, last_request: Option<Instant>) -> Self {
+        Self {
+            inner,
+            interval,
+            last_request,
+        }
+    }
+
+    /// When the most recent scanner request started.
+    pub(crate) fn last_request(&self) -> Option<Instant> {
+        self.last_request
+    }
+
+    async fn wait_turn(&mut self) {
+        if let Some(last) = self.last_request {
+            let remaining = self.interval.saturating_sub(last.elapsed());
+            if !remaining.is_zero() {
+                tokio::time::sleep(remaining).await;
+            }
+        }
+        self.last_request = Some(Instant::now());
+    }
+}
+
+#[async_trait]
+impl<B: Backend + ?Sized> Backend for ThrottledBackend<'_, B> {
+    async fn launch(&mut self) -> Result<(), BackendError> {
+        self.inner.launch().await
+    }
+
+    async fn close(&mut self) -> Result<(), BackendError> {
+        self.inner.close().await
+    }
+
+    async fn is_ready(&self) -> bool {
+        self.inner.is_ready().await
+    }
+
+    fn capabilities(&self) -> BackendCapabilities {
+        self.inner.capabilities()
+    }
+
+    async fn navigate(&mut self, url: &str) -> Result<NavigationResult, BackendError> {
+        self.inner.navigate(url).await
+    }
+
+    async fn navigate_with_referer(
+        &mut self,
+        url: &str,
+        referer: &str,
+    ) -> Result<NavigationResult, BackendError> {
+        self.inner.navigate_with_referer(url, referer).await
+    }
+
+    async fn execute_scanner(
+        &mut self,
+        command: ScannerAction,
+    ) -> Result<ScannerProtocolResponse, BackendError> {
+        self.wait_turn().await;
+        self.inner.execute_scanner(command).await
+    }
+
+    async fn execute_batch(
+        &mut self,
+        commands: Vec<ScannerAction>,
+    ) -> Result<Vec<ScannerProtocolResponse>, BackendError> {
+        self.wait_turn().await;
+        self.inner.execute_batch(commands).await
+    }
+
+    async fn execute_script(
+        &mut self,
+        script: &str,
+        args: serde_json::Map<String, serde_json::Value>,
+    ) -> Result<serde_json::Value, BackendError> {
+        self.wait_turn().await;
+        self.inner.execute_script(script, args).await
+    }
+
+    async fn screenshot(&mut self) -> Result<Vec<u8>, BackendError> {
+        self.inner.screenshot().await
+    }
+
+    async fn screenshot_clip(&mut self, clip: &Rect) -> Result<Vec<u8>, BackendError> {
+        self.inner.screenshot_clip(clip).await
+    }
+
+    async fn pdf(&mut self, options: &PdfOptions) -> Result<Vec<u8>, BackendError> {
+        self.inner.pdf(options).await
+    }
+
+    async fn get_cookies(&mut self) -> Result<Vec<oryn_common::protocol::Cookie>, BackendError> {
+        self.inner.get_cookies().await
+    }
+
+    async fn get_all_cookies(
+        &mut self,
+    ) -> Result<Vec<oryn_common::protocol::Cookie>, BackendError> {
+        self.inner.get_all_cookies().await
+    }
+
+    async fn set_cookie(
+        &mut self,
+        cookie: oryn_common::protocol::Cookie,
+    ) -> Result<(), BackendError> {
+        self.inner.set_cookie(cookie).await
+    }
+
+    async fn get_tabs(&mut self) -> Result<Vec<oryn_common::protocol::TabInfo>, BackendError> {
+        self.inner.get_tabs().await
+    }
+
+    async fn get_frames(&mut self) -> Result<Vec<FrameInfo>, BackendError> {
+        self.wait_turn().await;
+        self.inner.get_frames().await
+    }
+
+    async fn switch_frame(&mut self, index: Option<usize>) -> Result<(), BackendError> {
+        self.inner.switch_frame(index).await
+    }
+
+    async fn go_back(&mut self) -> Result<NavigationResult, BackendError> {
+        self.inner.go_back().await
+    }
+
+    async fn go_forward(&mut self) -> Result<NavigationResult, BackendError> {
+        self.inner.go_forward().await
+    }
+
+    async fn refresh(&mut self) -> Result<NavigationResult, BackendError> {
+        self.inner.refresh().await
+    }
+
+    async fn press_key(&mut self, key: &str, modifiers: &[String]) -> Result<(), BackendError> {
+        self.inner.press_key(key, modifiers).await
+    }
+
+    async fn pending_requests(&mut self) -> Result<usize, BackendError> {
+        self.inner.pending_requests().await
+    }
+
+    async fn clear_requests(&mut self) -> Result<(), BackendError> {
+        self.inner.clear_requests().await
+    }
+
+    async fn get_requests(&mut self) -> Result<Vec<NetworkRequest>, BackendError> {
+        self.inner.get_requests().await
+    }
+
+    async fn add_intercept(&mut self, rule: InterceptRule) -> Result<(), BackendError> {
+        self.inner.add_intercept(rule).await
+    }
+
+    async fn clear_intercepts(&mut self, pattern: Option<&str>) -> Result<(), BackendError> {
+        self.inner.clear_intercepts(pattern).await
+    }
+
+    async fn clear_console(&mut self) -> Result<(), BackendError> {
+        self.inner.clear_console().await
+    }
 }
@@ -1,8 +1,20 @@
 use crate::backend::Backend;
-use crate::executor::CommandExecutor;
+use crate::executor::{
+    AmbiguityCandidate, CommandExecutor, Disambiguator, ParamPrompter, format_candidates,
+};
+use async_trait::async_trait;
+use oryn_common::intent::definition::ParameterDef;
+use oryn_core::ast::{Command, ObserveCmd};
+use oryn_core::translator::parse_duration_ms;
+use oryn_core::{normalize, parse};
+use serde_json::json;
 use std::error::Error;
+use std::future::Future;
 use std::io::{self, Write};
-use tokio::io::{AsyncBufReadExt, BufReader};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, BufReader, Lines};
+use tokio::sync::Mutex;
 
 #[derive(Clone, Copy)]
 pub struct OutputHandlers {
@@ -10,6 +22,19 @@ pub struct OutputHandlers {
     pub err: fn(&str),
 }
 
+/// How results and errors are written.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OutputMode {
+    /// Results as text on `out`, errors as text on `err`.
+    #[default]
+    Human,
+    /// Every result and error as one JSON object per line on `out`, tagged
+    /// `"type": "result"` or `"type": "error"`, so a program reading it
+    /// never has to tell the two apart from the text. Nothing else (no
+    /// banner or prompt) is written there.
+    Json,
+}
+
 pub enum FileErrorMode {
     Plain,
     WithLine,
@@ -28,47 +53,287 @@ pub struct ReplOptions<'a> {
     pub ctrl_c_message: Option<&'a str>,
 }
 
+/// The REPL's stdin, one line at a time. Prompts raised while a command runs
+/// read through it too, so no input is buffered where the other cannot see it.
+pub type StdinLines = Arc<Mutex<Lines<BufReader<tokio::io::Stdin>>>>;
+
+/// Read the next line of `lines`, or `None` at end of input.
+async fn next_stdin_line(lines: &StdinLines) -> Option<String> {
+    lines.lock().await.next_line().await.ok()?
+}
+
+/// Prompts on the terminal for missing intent parameters in the REPL.
+/// Sensitive values are read from the terminal without echo, on the blocking
+/// pool so a prompt waiting for input holds no runtime worker.
+pub struct StdinPrompter {
+    lines: StdinLines,
+}
+
+impl StdinPrompter {
+    pub fn new(lines: StdinLines) -> Self {
+        Self { lines }
+    }
+}
+
+#[async_trait]
+impl ParamPrompter for StdinPrompter {
+    async fn prompt(&mut self, param: &ParameterDef, sensitive: bool) -> Option<String> {
+        let mut label = param.name.clone();
+        if !param.allowed.is_empty() {
+            let choices: Vec<String> = param.allowed.iter().map(|v| v.to_string()).collect();
+            label.push_str(&format!(" [{}]", choices.join("|")));
+        }
+        if !param.description.is_empty() {
+            label.push_str(&format!(" ({})", param.description));
+        }
+        let label = format!("{}: ", label);
+
+        if sensitive {
+            let input = tokio::task::spawn_blocking(move || rpassword::prompt_password(label))
+                .await
+                .ok()?
+                .ok()?;
+            return Some(input.trim_end_matches(['\r', '\n']).to_string());
+        }
+        print!("{}", label);
+        io::stdout().flush().ok()?;
+        next_stdin_line(&self.lines).await
+    }
+
+    fn reject(&mut self, param: &ParameterDef, reason: &str) {
+        eprintln!("Invalid value for {}: {}", param.name, reason);
+    }
+}
+
+/// Lists the candidates of an ambiguous target in the REPL and reads the
+/// number of the intended one. An empty line cancels the command.
+pub struct StdinDisambiguator {
+    lines: StdinLines,
+}
+
+impl StdinDisambiguator {
+    pub fn new(lines: StdinLines) -> Self {
+        Self { lines }
+    }
+}
+
+#[async_trait]
+impl Disambiguator for StdinDisambiguator {
+    async fn choose(&mut self, target: &str, candidates: &[AmbiguityCandidate]) -> Option<u32> {
+        eprintln!("{}", format_candidates(target, candidates));
+        loop {
+            print!("Choose 1-{} (empty to cancel): ", candidates.len());
+            io::stdout().flush().ok()?;
+            let line = next_stdin_line(&self.lines).await?;
+            let line = line.trim();
+            if line.is_empty() {
+                return None;
+            }
+            match line.parse::<usize>() {
+                Ok(n) if (1..=candidates.len()).contains(&n) => return Some(candidates[n - 1].id),
+                _ => eprintln!("Enter a number between 1 and {}", candidates.len()),
+            }
+        }
+    }
+}
+
+/// Time between `observe --monitor` scans when no `--interval` is given.
+const DEFAULT_MONITOR_INTERVAL_MS: u64 = 1000;
+
+/// The `observe --monitor` command on `line`, if that is what it holds.
+fn monitor_command(line: &str) -> Option<ObserveCmd> {
+    let script = parse(&normalize(line)).ok()?;
+    match script.lines.into_iter().next()?.command? {
+        Command::Observe(cmd) if cmd.monitor => Some(cmd),
+        _ => None,
+    }
+}
+
+/// Write a command's output in `mode`.
+fn emit_result(output: OutputHandlers, mode: OutputMode, text: &str, exit_code: i32) {
+    match mode {
+        OutputMode::Human => (output.out)(text),
+        OutputMode::Json => (output.out)(
+            &json!({ "type": "result", "output": text, "exit_code": exit_code }).to_string(),
+        ),
+    }
+}
+
+/// Write why `command` (masked) failed in `mode`. `human` is the text shown
+/// in human mode; JSON carries the bare message.
+fn emit_error(output: OutputHandlers, mode: OutputMode, command: &str, message: &str, human: &str) {
+    match mode {
+        OutputMode::Human => (output.err)(human),
+        OutputMode::Json => (output.out)(
+            &json!({ "type": "error", "command": command, "message": message }).to_string(),
+        ),
+    }
+}
+
+/// Re-scan the page every `--interval` and print only what changed, until
+/// `stop` resolves. The first scan is the baseline and prints nothing.
+pub async fn run_monitor<B, F>(
+    backend: &mut B,
+    executor: &mut CommandExecutor,
+    output: OutputHandlers,
+    cmd: &ObserveCmd,
+    stop: F,
+) -> Result<(), String>
+where
+    B: Backend + ?Sized,
+    F: Future<Output = ()>,
+{
+    monitor(backend, executor, |text| (output.out)(text), cmd, stop).await
+}
+
+async fn monitor<B, F>(
+    backend: &mut B,
+    executor: &mut CommandExecutor,
+    emit: impl Fn(&str),
+    cmd: &ObserveCmd,
+    stop: F,
+) -> Result<(), String>
+where
+    B: Backend + ?Sized,
+    F: Future<Output = ()>,
+{
+    let interval = cmd
+        .interval
+        .as_deref()
+        .and_then(parse_duration_ms)
+        .unwrap_or(DEFAULT_MONITOR_INTERVAL_MS);
+    executor
+        .monitor_scan(backend, cmd)
+        .await
+        .map_err(|e| e.to_string())?;
+    emit(&format!(
+        "Monitoring every {}ms; press Ctrl-C to stop",
+        interval
+    ));
+
+    tokio::pin!(stop);
+    loop {
+        tokio::select! {
+            _ = &mut stop => return Ok(()),
+            _ = tokio::time::sleep(Duration::from_millis(interval)) => {}
+        }
+        let report = executor
+            .monitor_scan(backend, cmd)
+            .await
+            .map_err(|e| e.to_string())?;
+        if let Some(report) = report {
+            emit(&report);
+        }
+    }
+}
+
+/// Run one line, returning its output and exit code.
 async fn execute_line<B: Backend + ?Sized>(
     backend: &mut B,
     executor: &mut CommandExecutor,
+    output: OutputHandlers,
+    mode: OutputMode,
     line: &str,
-) -> Result<String, String> {
+) -> Result<(String, i32), String> {
+    if let Some(cmd) = monitor_command(line) {
+        let ctrl_c = async {
+            let _ = tokio::signal::ctrl_c().await;
+        };
+        let emit = |text: &str| emit_result(output, mode, text, 0);
+        monitor(backend, executor, emit, &cmd, ctrl_c).await?;
+        return Ok(("Stopped monitoring".to_string(), 0));
+    }
     match executor.execute_line(backend, line).await {
-        Ok(result) => Ok(result.output),
+        Ok(result) => Ok((result.output, result.exit_code)),
         Err(e) => Err(format!("{}", e)),
     }
 }
 
+/// Run a script file line by line. Returns the exit code the process should
+/// end with: that of the first line reporting a non-zero one, such as a
+/// `run --json` intent that did not complete.
 pub async fn run_file<B: Backend + ?Sized>(
     backend: &mut B,
     executor: &mut CommandExecutor,
     output: OutputHandlers,
+    mode: OutputMode,
     path: &str,
     options: FileOptions,
-) -> Result<(), Box<dyn Error>> {
+) -> Result<i32, Box<dyn Error>> {
     let content = std::fs::read_to_string(path)?;
-    for line in content.lines() {
-        let trimmed = line.trim();
-        if trimmed.is_empty() || trimmed.starts_with('#') {
-            continue;
-        }
-
-        match execute_line(backend, executor, trimmed).await {
-            Ok(result) => (output.out)(&result),
+    let mut exit_code = 0;
+    for command in script_commands(&content) {
+        let trimmed = command.as_str();
+        match execute_line(backend, executor, output, mode, trimmed).await {
+            Ok((result, code)) => {
+                emit_result(output, mode, &result, code);
+                if exit_code == 0 {
+                    exit_code = code;
+                }
+            }
             Err(err) => {
-                match options.error_mode {
-                    FileErrorMode::Plain => (output.err)(&format!("Error: {}", err)),
+                let masked = executor.mask_log(trimmed);
+                let human = match options.error_mode {
+                    FileErrorMode::Plain => format!("Error: {}", err),
                     FileErrorMode::WithLine => {
-                        (output.err)(&format!("Error executing line '{}': {}", trimmed, err))
+                        format!("Error executing line '{}': {}", masked, err)
                     }
-                }
+                };
+                emit_error(output, mode, &masked, &err, &human);
                 if options.stop_on_error {
                     return Err(io::Error::other(err).into());
                 }
             }
         }
     }
-    Ok(())
+    Ok(exit_code)
+}
+
+/// The commands of a script file, one per line except for `repeat` blocks,
+/// whose lines are joined into one command with `;` between them. Blank
+/// lines and comments are dropped.
+fn script_commands(content: &str) -> Vec<String> {
+    let mut commands = Vec::new();
+    let mut block = String::new();
+    let mut depth = 0;
+    for line in content.lines() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        if !block.is_empty() {
+            block.push_str(" ; ");
+        }
+        block.push_str(trimmed);
+        depth += brace_depth(trimmed);
+        if depth <= 0 {
+            commands.push(std::mem::take(&mut block));
+            depth = 0;
+        }
+    }
+    // An unclosed block still runs, so its parse error is reported
+    if !block.is_empty() {
+        commands.push(block);
+    }
+    commands
+}
+
+/// How many more `{` than `}` a line opens, ignoring quoted text.
+fn brace_depth(line: &str) -> i32 {
+    let mut depth = 0;
+    let mut in_quote = false;
+    let mut escaped = false;
+    for c in line.chars() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            '"' => in_quote = !in_quote,
+            '{' if !in_quote => depth += 1,
+            '}' if !in_quote => depth -= 1,
+            _ => {}
+        }
+    }
+    depth
 }
 
 /// Possible outcomes from reading a single REPL line.
@@ -84,7 +349,7 @@ enum ReadLineResult {
 }
 
 async fn read_line(
-    reader: &mut tokio::io::Lines<BufReader<tokio::io::Stdin>>,
+    reader: &mut Lines<BufReader<tokio::io::Stdin>>,
     exit_commands: &[&str],
     handle_ctrl_c: bool,
     ctrl_c_message: Option<&str>,
@@ -127,37 +392,61 @@ fn classify_line(
     }
 }
 
+/// Run an interactive session. Installs a [`StdinPrompter`] on the executor so
+/// `run` can ask for missing intent parameters, and a [`StdinDisambiguator`]
+/// so ambiguous targets can be picked by hand.
+///
+/// In [`OutputMode::Json`] the session is driven by a program rather than a
+/// person: there is no banner or prompt, and nothing is asked on stdin, so
+/// missing parameters are reported as errors and ambiguous targets resolve
+/// to the best match.
 pub async fn run_repl<B: Backend + ?Sized>(
     backend: &mut B,
     executor: &mut CommandExecutor,
     output: OutputHandlers,
+    mode: OutputMode,
     options: ReplOptions<'_>,
 ) -> Result<(), Box<dyn Error>> {
-    for line in options.banner_lines {
-        (output.out)(line);
+    let reader: StdinLines = Arc::new(Mutex::new(BufReader::new(tokio::io::stdin()).lines()));
+    let interactive = mode == OutputMode::Human;
+    if interactive {
+        for line in options.banner_lines {
+            (output.out)(line);
+        }
+        executor.set_prompter(Some(Box::new(StdinPrompter::new(reader.clone()))));
+        executor.set_disambiguator(Some(Box::new(StdinDisambiguator::new(reader.clone()))));
     }
 
-    let stdin = tokio::io::stdin();
-    let mut reader = BufReader::new(stdin).lines();
     let mut stdout = io::stdout();
 
     loop {
-        print!("{}", options.prompt);
-        stdout.flush()?;
+        if interactive {
+            print!("{}", options.prompt);
+            stdout.flush()?;
+        }
 
-        match read_line(
-            &mut reader,
+        // The lock is released before the line runs, so prompts can read.
+        let next = read_line(
+            &mut *reader.lock().await,
             options.exit_commands,
             options.handle_ctrl_c,
-            options.ctrl_c_message,
+            options.ctrl_c_message.filter(|_| interactive),
             output,
         )
-        .await
-        {
-            ReadLineResult::Input(line) => match execute_line(backend, executor, &line).await {
-                Ok(result) => (output.out)(&result),
-                Err(err) => (output.err)(&format!("Error: {}", err)),
-            },
+        .await;
+        match next {
+            ReadLineResult::Input(line) => {
+                match execute_line(backend, executor, output, mode, &line).await {
+                    Ok((result, code)) => emit_result(output, mode, &result, code),
+                    Err(err) => emit_error(
+                        output,
+                        mode,
+                        &executor.mask_log(&line),
+                        &err,
+                        &format!("Error: {}", err),
+                    ),
+                }
+            }
             ReadLineResult::Skip => continue,
             ReadLineResult::Exit => break,
             ReadLineResult::Error(e) => return Err(e.into()),
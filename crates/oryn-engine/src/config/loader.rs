@@ -4,10 +4,16 @@ use thiserror::Error;
 
 #[derive(Debug, Error)]
 pub enum ConfigError {
-    #[error("Failed to read config file: {0}")]
-    Io(#[from] std::io::Error),
-    #[error("Failed to parse config file: {0}")]
-    Parse(#[from] serde_yaml::Error),
+    #[error("Failed to read config file {}: {source}", path.display())]
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[error("Failed to parse config file {}: {source}", path.display())]
+    Parse {
+        path: PathBuf,
+        source: serde_yaml::Error,
+    },
 }
 
 pub struct ConfigLoader;
@@ -36,9 +42,21 @@ impl ConfigLoader {
         Ok(OrynConfig::default())
     }
 
+    /// Load a YAML configuration file. Sections and keys left out take their
+    /// defaults, so an empty file gives the default configuration.
     pub async fn load_from(path: &Path) -> Result<OrynConfig, ConfigError> {
-        let content = tokio::fs::read_to_string(path).await?;
-        let config: OrynConfig = serde_yaml::from_str(&content)?;
-        Ok(config)
+        let content = tokio::fs::read_to_string(path)
+            .await
+            .map_err(|source| ConfigError::Io {
+                path: path.to_path_buf(),
+                source,
+            })?;
+        // An empty document is YAML null rather than an empty mapping
+        let config: Option<OrynConfig> =
+            serde_yaml::from_str(&content).map_err(|source| ConfigError::Parse {
+                path: path.to_path_buf(),
+                source,
+            })?;
+        Ok(config.unwrap_or_default())
     }
 }
@@ -1,3 +1,4 @@
+use oryn_common::resolver::ScoringConfig;
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 
@@ -11,6 +12,9 @@ pub struct OrynConfig {
     pub learning: LearningConfig,
     #[serde(default)]
     pub security: SecurityConfig,
+    /// Weights used to score text matches when resolving targets.
+    #[serde(default)]
+    pub scoring: ScoringConfig,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -25,6 +29,28 @@ pub struct IntentEngineConfig {
     pub retry_delay_ms: u64,
     #[serde(default)]
     pub strict_mode: bool,
+    /// Pause after every click and type before re-scanning, for pages that
+    /// re-render asynchronously. `--wait-after` overrides it per command.
+    #[serde(default)]
+    pub default_action_settle_ms: u64,
+    /// How often `wait visible` and `wait until text of` re-scan the page.
+    #[serde(default = "default_poll_interval_ms")]
+    pub poll_interval_ms: u64,
+    /// Scroll an off-screen element into view before clicking, typing into
+    /// or hovering over it.
+    #[serde(default = "default_auto_scroll")]
+    pub auto_scroll: bool,
+    /// The most passes a `repeat` block may run, nested blocks included.
+    #[serde(default = "default_max_repeat")]
+    pub max_repeat: usize,
+    /// The least time between the starts of two scanner requests, scans
+    /// included. `0` sends them as fast as the page answers.
+    #[serde(default)]
+    pub min_request_interval_ms: u64,
+    /// Re-scan before resolving a target once the last scan is older than
+    /// this. `0` lets scans age indefinitely.
+    #[serde(default)]
+    pub max_scan_age_ms: u64,
 }
 
 impl Default for IntentEngineConfig {
@@ -35,6 +61,12 @@ impl Default for IntentEngineConfig {
             max_retries: default_max_retries(),
             retry_delay_ms: default_retry_delay_ms(),
             strict_mode: false,
+            default_action_settle_ms: 0,
+            poll_interval_ms: default_poll_interval_ms(),
+            auto_scroll: default_auto_scroll(),
+            max_repeat: default_max_repeat(),
+            min_request_interval_ms: 0,
+            max_scan_age_ms: 0,
         }
     }
 }
@@ -55,6 +87,18 @@ fn default_retry_delay_ms() -> u64 {
     1000
 }
 
+fn default_poll_interval_ms() -> u64 {
+    crate::executor::SCAN_WAIT_POLL_MS
+}
+
+fn default_auto_scroll() -> bool {
+    true
+}
+
+fn default_max_repeat() -> usize {
+    crate::executor::DEFAULT_MAX_REPEAT
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PacksConfig {
     #[serde(default = "default_auto_load")]
@@ -133,6 +177,10 @@ pub struct SecurityConfig {
     pub sensitive_fields: Vec<String>,
     #[serde(default = "default_redact_in_logs")]
     pub redact_in_logs: bool,
+    /// Reject commands and intent steps that run caller-supplied script, for
+    /// shared deployments where only semantic commands should be allowed.
+    #[serde(default)]
+    pub safe_mode: bool,
 }
 
 impl Default for SecurityConfig {
@@ -140,6 +188,7 @@ impl Default for SecurityConfig {
         Self {
             sensitive_fields: default_sensitive_fields(),
             redact_in_logs: default_redact_in_logs(),
+            safe_mode: false,
         }
     }
 }
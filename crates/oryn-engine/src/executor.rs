@@ -5,18 +5,54 @@
 //!
 //! All Oryn binaries (oryn-h, oryn-e, oryn-r) should use this shared executor.
 
-use crate::backend::Backend;
+use crate::backend::{Backend, BackendCapabilities, BackendError, ThrottledBackend};
+use crate::config::schema::{OrynConfig, SecurityConfig};
+use crate::intent::builtin;
+use crate::intent::executor::{
+    ExecutorError as IntentError, IntentExecutor, IntentResult, IntentStatus,
+    WAIT_TIMEOUT_GRACE_MS, collect_secrets,
+};
+use crate::intent::handler::ActionHandler;
+use crate::intent::loader::{IntentFormat, IntentLoader, LoaderError};
+use crate::intent::mapper::{self, IntentCall, IntentMapper};
 use crate::resolution::ResolutionEngine;
-use oryn_common::formatter::format_response;
+use async_trait::async_trait;
+use oryn_common::cookie_file::{CookieFileError, CookieFormat, read_cookies, write_cookies};
+use oryn_common::formatter::{
+    format_changes, format_extract_csv, format_intent_guidance, format_page_changes,
+    format_response, format_scan_tree, table_records,
+};
+use oryn_common::formatter::{is_sensitive_field, mask_sensitive_log_with};
+use oryn_common::intent::definition::{
+    Condition, IntentOptions, MIN_POLL_INTERVAL_MS, ParameterDef, TargetKind, TargetSpec,
+};
+use oryn_common::intent::registry::IntentRegistry;
+use oryn_common::intent::verifier::{Verifier, VerifierContext};
 use oryn_common::protocol::{
-    Action, BrowserAction, Cookie, ScanRequest, ScanResult, ScannerAction, ScannerData,
-    ScannerProtocolResponse, SessionAction,
+    Action, AvailabilityStatus, BrowserAction, ClickRequest, Cookie, CookieRequest, Element,
+    ExecuteRequest, FrameInfo, FrameRequest, InterceptAction, InterceptRequest, InterceptRule,
+    Interception, NetworkRequest, PageInfo, Rect, RequestsRequest, ScanRequest, ScanResult,
+    ScannerAction, ScannerData, ScannerProtocolResponse, ScrollBehavior, ScrollDirection,
+    ScrollRequest, SessionAction, StorageRequest, TypeRequest, WaitRequest, redact_secret_text,
+};
+use oryn_common::resolver::{
+    self, ResolutionStrategy, ResolverContext, ResolverError, ScoringConfig,
 };
+pub use oryn_common::resolver::{AmbiguityCandidate, format_candidates};
 use oryn_core::{
-    normalize, parse,
+    ast::{
+        self, Command, EvalArg, EvalCmd, ExportCmd, IntentsCmd, ObserveCmd, ResetCmd, RunCmd,
+        TargetAtomic, TextMode,
+    },
+    interpolate, normalize, parse,
     parser::ParseError,
+    resolution::{CommandMeta, TargetRequirement},
     translator::{self, TranslationError},
 };
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
 #[derive(Debug, thiserror::Error)]
 pub enum ExecutorError {
@@ -26,6 +62,9 @@ pub enum ExecutorError {
     #[error("Resolution error: {0}")]
     Resolution(#[from] crate::resolution::result::ResolutionError),
 
+    #[error("Resolution error: {0}")]
+    Resolver(#[from] ResolverError),
+
     #[error("Translation error: {0}")]
     Translation(#[from] TranslationError),
 
@@ -33,7 +72,7 @@ pub enum ExecutorError {
     NoScanContext,
 
     #[error("Backend error: {0}")]
-    Backend(#[from] oryn_common::error::backend_error::BackendError),
+    Backend(#[from] BackendError),
 
     #[error("Scanner error: {0}")]
     Scanner(String),
@@ -44,8 +83,35 @@ pub enum ExecutorError {
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
 
+    #[error("Serialization error: {0}")]
+    Serialization(#[from] serde_json::Error),
+
+    #[error("{0}")]
+    Intent(#[from] IntentError),
+
+    #[error("{0}")]
+    Loader(#[from] LoaderError),
+
+    #[error("Unknown variable: ${0}")]
+    UnknownVariable(String),
+
     #[error("Not implemented: {0}")]
     NotImplemented(String),
+
+    #[error("Timed out: {0}")]
+    Timeout(String),
+
+    #[error("Assertion failed: {0}")]
+    AssertionFailed(String),
+
+    #[error("{0} is disabled in safe mode")]
+    DisabledInSafeMode(String),
+
+    #[error("{0}")]
+    CookieFile(#[from] CookieFileError),
+
+    #[error("repeat would run {count} iterations, over the limit of {max}")]
+    RepeatLimit { count: usize, max: usize },
 }
 
 /// Result of executing a command.
@@ -54,10 +120,94 @@ pub struct ExecutionResult {
     pub output: String,
     /// Whether execution was successful.
     pub success: bool,
+    /// The exit code a script run should end with. Non-zero when a
+    /// `run --json` intent did not fully complete.
+    pub exit_code: i32,
+}
+
+/// How many times a prompted parameter may be re-entered after failing validation.
+const MAX_PROMPT_ATTEMPTS: usize = 3;
+
+/// Upper bound on how long `screenshot --wait-stable` waits for the page to settle.
+const STABLE_WAIT_TIMEOUT_MS: u64 = 10_000;
+
+/// Upper bound on how long a `scroll --smooth` animation may take to finish.
+const SCROLL_SETTLE_TIMEOUT_MS: u64 = 5_000;
+
+/// How often `wait until text of` and `wait visible` re-scan the page, unless
+/// `intent_engine.poll_interval_ms` says otherwise.
+pub const SCAN_WAIT_POLL_MS: u64 = 250;
+
+/// Finds where the frame element matching `args.selector` sits in the list
+/// `Backend::get_frames` returns, or -1 if it is not a frame.
+const FRAME_INDEX_SCRIPT: &str = "return Array.from(document.querySelectorAll('iframe, frame')).indexOf(document.querySelector(args.selector));";
+
+/// The most passes a `repeat` block may run, counting those of the blocks
+/// nested in it, unless `intent_engine.max_repeat` says otherwise.
+pub const DEFAULT_MAX_REPEAT: usize = 100;
+
+/// How long `wait idle` needs no requests in flight when `--quiet` is omitted.
+const IDLE_QUIET_MS: u64 = 500;
+
+/// How often `wait idle` checks the backend's in-flight request count.
+const IDLE_POLL_MS: u64 = 50;
+
+/// Supplies values for required intent parameters missing from a `run` command.
+///
+/// Interactive front-ends install one with [`CommandExecutor::set_prompter`];
+/// without it, missing parameters are reported as errors.
+#[async_trait]
+pub trait ParamPrompter: Send {
+    /// Ask for a value. `sensitive` means the input must not be echoed.
+    /// Returning `None` aborts the run.
+    async fn prompt(&mut self, param: &ParameterDef, sensitive: bool) -> Option<String>;
+
+    /// Called when a prompted value was rejected, before asking again.
+    fn reject(&mut self, _param: &ParameterDef, _reason: &str) {}
+}
+
+/// Picks one element when a command's target matches several.
+///
+/// Installed with [`CommandExecutor::set_disambiguator`] by interactive
+/// front-ends; without it, ambiguous targets resolve as they always have.
+/// It is only asked about elements that tie once the command's own
+/// preferences are counted, so a heading never competes with the button
+/// `click` would pick.
+#[async_trait]
+pub trait Disambiguator: Send {
+    /// Return the id of the chosen candidate, or `None` to give up, which
+    /// fails the command with the ambiguity error.
+    async fn choose(&mut self, target: &str, candidates: &[AmbiguityCandidate]) -> Option<u32>;
 }
 
 pub struct CommandExecutor {
     last_scan: Option<ScanResult>,
+    /// Counts stored scans; the last scan is generation `scan_generation`.
+    scan_generation: u64,
+    last_scan_at: Option<Instant>,
+    /// The URL the page was last known to be at, from a navigation or scan.
+    page_url: Option<String>,
+    /// Set when an action reports that it navigated, which leaves the last
+    /// scan describing a page that is gone.
+    navigated_since_scan: bool,
+    max_scan_age: Option<Duration>,
+    registry: IntentRegistry,
+    prompter: Option<Box<dyn ParamPrompter>>,
+    disambiguator: Option<Box<dyn Disambiguator>>,
+    /// Handlers for intent actions outside the built-in set, by action name.
+    action_handlers: HashMap<String, Arc<dyn ActionHandler>>,
+    variables: HashMap<String, Value>,
+    scoring: ScoringConfig,
+    security: SecurityConfig,
+    dry_run: bool,
+    action_settle_ms: u64,
+    auto_scroll: bool,
+    max_repeat: usize,
+    wait_timeout_ms: u64,
+    scan_poll_ms: u64,
+    min_request_interval: Duration,
+    /// When the last throttled scanner request started.
+    last_request: Option<Instant>,
 }
 
 impl Default for CommandExecutor {
@@ -68,13 +218,167 @@ impl Default for CommandExecutor {
 
 impl CommandExecutor {
     pub fn new() -> Self {
-        Self { last_scan: None }
+        let mut registry = IntentRegistry::new();
+        builtin::register_all(&mut registry);
+        Self {
+            last_scan: None,
+            scan_generation: 0,
+            last_scan_at: None,
+            page_url: None,
+            navigated_since_scan: false,
+            max_scan_age: None,
+            registry,
+            prompter: None,
+            disambiguator: None,
+            action_handlers: HashMap::new(),
+            variables: HashMap::new(),
+            scoring: ScoringConfig::default(),
+            security: SecurityConfig::default(),
+            dry_run: false,
+            action_settle_ms: 0,
+            auto_scroll: true,
+            max_repeat: DEFAULT_MAX_REPEAT,
+            wait_timeout_ms: IntentOptions::default().timeout,
+            scan_poll_ms: SCAN_WAIT_POLL_MS,
+            min_request_interval: Duration::ZERO,
+            last_request: None,
+        }
+    }
+
+    /// An executor set up from a loaded configuration file.
+    pub fn with_config(config: &OrynConfig) -> Self {
+        let mut executor = Self::new();
+        executor.set_security(config.security.clone());
+        executor.set_scoring(config.scoring.clone());
+        executor.set_action_settle_ms(config.intent_engine.default_action_settle_ms);
+        executor.set_auto_scroll(config.intent_engine.auto_scroll);
+        executor.set_max_repeat(config.intent_engine.max_repeat);
+        executor.set_min_request_interval_ms(config.intent_engine.min_request_interval_ms);
+        executor.set_max_scan_age_ms(config.intent_engine.max_scan_age_ms);
+        executor.wait_timeout_ms = config.intent_engine.default_timeout_ms;
+        executor.scan_poll_ms = config
+            .intent_engine
+            .poll_interval_ms
+            .max(MIN_POLL_INTERVAL_MS);
+        executor
     }
 
     pub fn get_last_scan(&self) -> Option<&ScanResult> {
         self.last_scan.as_ref()
     }
 
+    /// The generation of the last scan. It grows by one with every scan, so
+    /// callers can tell whether a command re-scanned the page.
+    pub fn scan_generation(&self) -> u64 {
+        self.scan_generation
+    }
+
+    /// Intents known to this executor (built-ins plus anything registered since).
+    pub fn registry(&self) -> &IntentRegistry {
+        &self.registry
+    }
+
+    pub fn registry_mut(&mut self) -> &mut IntentRegistry {
+        &mut self.registry
+    }
+
+    /// Install (or remove) the hook used to ask for missing intent parameters.
+    pub fn set_prompter(&mut self, prompter: Option<Box<dyn ParamPrompter>>) {
+        self.prompter = prompter;
+    }
+
+    /// Install (or remove) the hook used to pick between elements matched by
+    /// an ambiguous target.
+    pub fn set_disambiguator(&mut self, disambiguator: Option<Box<dyn Disambiguator>>) {
+        self.disambiguator = disambiguator;
+    }
+
+    /// Run intent steps whose `action` is `name` with `handler`, in every
+    /// intent this executor runs. Built-in actions cannot be replaced.
+    pub fn register_action_handler(
+        &mut self,
+        name: impl Into<String>,
+        handler: Arc<dyn ActionHandler>,
+    ) {
+        self.action_handlers.insert(name.into(), handler);
+    }
+
+    /// Replace the weights used to score text matches when resolving targets.
+    pub fn set_scoring(&mut self, scoring: ScoringConfig) {
+        self.scoring = scoring;
+    }
+
+    /// Apply the `security` section of the configuration: the extra field
+    /// names treated as secrets, and whether logged lines are masked.
+    pub fn set_security(&mut self, security: SecurityConfig) {
+        self.security = security;
+    }
+
+    /// A command line as it may be logged, with its secrets masked. Lines
+    /// with a `--secret` value are masked even when `redact_in_logs` is off.
+    pub fn mask_log(&self, line: &str) -> String {
+        if self.security.redact_in_logs || line.contains("--secret") {
+            mask_sensitive_log_with(line, &self.security.sensitive_fields)
+        } else {
+            line.to_string()
+        }
+    }
+
+    /// How long to pause after a click or type that has no `--wait-after`
+    /// of its own before re-scanning (`intent_engine.default_action_settle_ms`).
+    pub fn set_action_settle_ms(&mut self, ms: u64) {
+        self.action_settle_ms = ms;
+    }
+
+    /// Scroll an element the last scan placed off-screen into view before
+    /// clicking, typing into or hovering over it (`intent_engine.auto_scroll`).
+    pub fn set_auto_scroll(&mut self, auto_scroll: bool) {
+        self.auto_scroll = auto_scroll;
+    }
+
+    /// The most passes a `repeat` block may run, nested blocks included
+    /// (`intent_engine.max_repeat`).
+    pub fn set_max_repeat(&mut self, max_repeat: usize) {
+        self.max_repeat = max_repeat;
+    }
+
+    /// Space scanner requests, scans included, at least this far apart,
+    /// sleeping before one that would come too soon
+    /// (`intent_engine.min_request_interval_ms`). Zero turns spacing off.
+    pub fn set_min_request_interval_ms(&mut self, ms: u64) {
+        self.min_request_interval = Duration::from_millis(ms);
+    }
+
+    /// Re-scan before resolving a target once the last scan is older than
+    /// this (`intent_engine.max_scan_age_ms`). Zero lets scans age
+    /// indefinitely; a scan of another page is re-taken regardless.
+    pub fn set_max_scan_age_ms(&mut self, ms: u64) {
+        self.max_scan_age = (ms > 0).then(|| Duration::from_millis(ms));
+    }
+
+    /// In dry-run mode commands are resolved and translated, and the request
+    /// each would send is printed instead of executed. Navigation and scans
+    /// still run so later targets resolve against a real page.
+    pub fn set_dry_run(&mut self, dry_run: bool) {
+        self.dry_run = dry_run;
+    }
+
+    /// In safe mode commands that run caller-supplied script (`eval`, `set`,
+    /// `wait until "<expression>"` and intent `execute` steps) are rejected;
+    /// every other command runs as usual.
+    pub fn set_safe_mode(&mut self, safe_mode: bool) {
+        self.security.safe_mode = safe_mode;
+    }
+
+    /// Set a script-level variable, referenced from commands as `$name`.
+    pub fn set_variable(&mut self, name: impl Into<String>, value: Value) {
+        self.variables.insert(name.into(), value);
+    }
+
+    pub fn variable(&self, name: &str) -> Option<&Value> {
+        self.variables.get(name)
+    }
+
     fn check_scanner_error(resp: &ScannerProtocolResponse) -> Result<(), ExecutorError> {
         if let ScannerProtocolResponse::Error { code, message, .. } = resp {
             Err(ExecutorError::Scanner(format!("{}: {}", code, message)))
@@ -132,6 +436,7 @@ impl CommandExecutor {
             expires,
             http_only: None,
             secure: None,
+            same_site: None,
         }
     }
 
@@ -146,59 +451,358 @@ impl CommandExecutor {
         let script = parse(&normalized)?;
 
         // 2. Resolve + Translate + Execute each command
+        let commands = script.lines.into_iter().filter_map(|l| l.command).collect();
+        if self.min_request_interval.is_zero() {
+            return self.execute_commands(backend, commands).await;
+        }
+        let mut throttled =
+            ThrottledBackend::new(backend, self.min_request_interval, self.last_request);
+        let result = self.execute_commands(&mut throttled, commands).await;
+        self.last_request = throttled.last_request();
+        result
+    }
+
+    async fn execute_commands<B: Backend + ?Sized>(
+        &mut self,
+        backend: &mut B,
+        commands: Vec<Command>,
+    ) -> Result<ExecutionResult, ExecutorError> {
         let mut outputs = Vec::new();
-        for script_line in script.lines {
-            if let Some(cmd) = script_line.command {
-                let cmd_clone = cmd.clone();
-
-                // Try to resolve the command
-                let resolved_cmd = match self.resolve_command(cmd, backend).await {
-                    Ok(c) => c,
-                    Err(ExecutorError::Resolution(_)) | Err(ExecutorError::NoScanContext) => {
-                        // If resolution fails, try with fresh scan
-                        let req = ScannerAction::Scan(ScanRequest::default());
-                        let resp = backend.execute_scanner(req).await?;
+        let mut exit_code = 0;
+        for mut cmd in commands {
+            for name in interpolate::expand_env(&mut cmd) {
+                outputs.push(format!(
+                    "Warning: environment variable {} is not set; leaving $ENV{{{}}} unexpanded",
+                    name, name
+                ));
+            }
+            if let Command::Screenshot(shot) = &mut cmd
+                && shot.clip.is_some()
+                && shot.target.take().is_some()
+            {
+                shot.element_only = false;
+                shot.padding = None;
+                outputs.push(
+                    "Warning: screenshot --clip and a target were both given; capturing the clip"
+                        .to_string(),
+                );
+            }
 
-                        Self::check_scanner_error(&resp)?;
+            if self.security.safe_mode
+                && let Some(what) = raw_script_use(&cmd)
+            {
+                return Err(ExecutorError::DisabledInSafeMode(what.into()));
+            }
+
+            if let Command::Repeat(repeat) = &cmd {
+                let result = self.run_repeat(backend, repeat).await?;
+                outputs.push(result.output);
+                if exit_code == 0 {
+                    exit_code = result.exit_code;
+                }
+                continue;
+            }
+            // Intent listing is answered from the local registry
+            if let Command::Intents(intents_cmd) = &cmd {
+                outputs.push(self.list_intents(intents_cmd)?);
+                continue;
+            }
+            if let Command::Export(export) = &cmd {
+                outputs.push(self.export_intent(export)?);
+                continue;
+            }
+            // Diagnostic only: reads the scan and acts on nothing, so it
+            // runs the same in a dry run.
+            if let Command::Resolve(resolve) = &cmd {
+                outputs.push(self.resolve_candidates(backend, &resolve.target).await?);
+                continue;
+            }
+            // These skip resolution even when run for real, so a dry run
+            // shows them as written, translated where the translator can.
+            if self.dry_run
+                && (matches!(
+                    cmd,
+                    Command::Run(_)
+                        | Command::Reset(_)
+                        | Command::Eval(_)
+                        | Command::Set(_)
+                        | Command::Wait(_)
+                        | Command::Assert(_)
+                ) || matches!(&cmd, Command::Click(click) if click.all)
+                    || matches!(&cmd, Command::Select(select) if select.custom))
+            {
+                let plan = match translator::translate(&cmd) {
+                    Ok(action) => serde_json::to_string(&action)?,
+                    Err(_) => serde_json::to_string(&cmd)?,
+                };
+                outputs.push(format!("Dry run: {}", plan));
+                continue;
+            }
+            if let Command::Run(run_cmd) = &cmd {
+                let (output, code) = self.run_intent(backend, run_cmd).await?;
+                outputs.push(output);
+                if exit_code == 0 {
+                    exit_code = code;
+                }
+                continue;
+            }
+            if !self.dry_run
+                && let Some(call) = mapper::sugar_intent(&cmd)
+            {
+                let (output, code) = self.run_sugar_intent(backend, call).await?;
+                outputs.push(output);
+                if exit_code == 0 {
+                    exit_code = code;
+                }
+                continue;
+            }
+            if let Command::Eval(eval_cmd) = &cmd {
+                let value = self.eval_script(backend, eval_cmd).await?;
+                outputs.push(serde_json::to_string(&value)?);
+                continue;
+            }
+            if let Command::Set(set) = &cmd {
+                let value = self.eval_script(backend, &set.eval).await?;
+                outputs.push(format!(
+                    "${} = {}",
+                    set.name,
+                    serde_json::to_string(&value)?
+                ));
+                self.set_variable(set.name.clone(), value);
+                continue;
+            }
+            if let Command::Reset(reset_cmd) = &cmd {
+                outputs.push(self.reset(backend, reset_cmd).await?);
+                continue;
+            }
 
-                        self.update_from_response(&resp);
+            if let Command::Assert(assert) = &cmd {
+                outputs.push(self.check_assertion(backend, assert).await?);
+                continue;
+            }
 
-                        // Retry resolution
-                        match self.resolve_command(cmd_clone, backend).await {
-                            Ok(c) => c,
-                            Err(ExecutorError::Resolution(err)) => {
-                                // Enhance error with fuzzy matching
-                                return Err(self.enhance_resolution_error(err));
-                            }
-                            Err(e) => return Err(e),
-                        }
+            if cmd.target_mut().is_some()
+                || matches!(&cmd, Command::Screenshot(shot) if shot.target.is_some())
+            {
+                self.refresh_stale_scan(backend).await?;
+            }
+
+            if let Command::Wait(wait) = &cmd {
+                let output = match &wait.condition {
+                    // Selectors are matched by the scanner, which can see
+                    // elements a scan leaves out.
+                    ast::WaitCondition::Visible(target)
+                        if !matches!(target.atomic, TargetAtomic::Selector { .. }) =>
+                    {
+                        let timeout_ms = wait
+                            .timeout
+                            .as_deref()
+                            .and_then(translator::parse_duration_ms)
+                            .unwrap_or(self.wait_timeout_ms);
+                        self.wait_for_visible(backend, target, timeout_ms).await?
+                    }
+                    ast::WaitCondition::AnyOf(targets) => {
+                        let timeout_ms = wait
+                            .timeout
+                            .as_deref()
+                            .and_then(translator::parse_duration_ms)
+                            .unwrap_or(self.wait_timeout_ms);
+                        self.wait_for_any(backend, targets, timeout_ms).await?
                     }
-                    Err(e) => return Err(e),
+                    _ => self.wait_for(backend, wait).await?,
                 };
+                outputs.push(output);
+                continue;
+            }
 
-                // Translate the resolved command to an action
-                let action = translator::translate(&resolved_cmd)?;
+            if let Command::Screenshot(shot) = &cmd
+                && shot.element_only
+                && !self.dry_run
+            {
+                outputs.push(self.screenshot_element(backend, shot).await?);
+                continue;
+            }
+
+            if let Command::Click(click) = &cmd
+                && click.all
+            {
+                outputs.push(self.click_all(backend, &cmd).await?);
+                self.settle(backend, click.wait_after).await?;
+                continue;
+            }
 
-                // Execute the action
-                let output = self.execute_action(backend, action).await?;
+            if let Command::Select(select) = &cmd
+                && let Some(output) = self.select_custom(backend, select).await?
+            {
                 outputs.push(output);
+                continue;
+            }
+
+            let cmd = self.disambiguate(cmd).await?;
+            let cmd_clone = cmd.clone();
+            // A secret is echoed masked in place of the scanner's response,
+            // which would show the field's new value.
+            let secret_echo = match &cmd {
+                Command::Type(t) if t.secret => Some(format!(
+                    "type {} \u{2022}\u{2022}\u{2022}\u{2022}",
+                    describe_wait_target(&t.target)
+                )),
+                _ => None,
+            };
+
+            // Try to resolve the command
+            let resolved_cmd = match self.resolve_command(cmd, backend).await {
+                Ok(c) => c,
+                Err(ExecutorError::Resolution(_)) | Err(ExecutorError::NoScanContext) => {
+                    // If resolution fails, try with fresh scan
+                    let req = ScannerAction::Scan(ScanRequest::default());
+                    let resp = backend.execute_scanner(req).await?;
+
+                    Self::check_scanner_error(&resp)?;
+
+                    self.update_from_response(&resp);
+
+                    // Retry resolution
+                    match self.resolve_command(cmd_clone, backend).await {
+                        Ok(c) => c,
+                        Err(ExecutorError::Resolution(err)) => {
+                            // Enhance error with fuzzy matching
+                            return Err(self.enhance_resolution_error(err));
+                        }
+                        Err(e) => return Err(e),
+                    }
+                }
+                Err(e) => return Err(e),
+            };
+
+            // Translate the resolved command to an action
+            let action = translator::translate(&resolved_cmd)?;
+
+            if self.dry_run && !runs_in_dry_run(&action) {
+                let mut plan = serde_json::to_value(&action)?;
+                redact_secret_text(&mut plan);
+                outputs.push(format!("Dry run: {}", plan));
+                continue;
+            }
+
+            if self.auto_scroll
+                && let Some(id) = self.offscreen_target(&resolved_cmd)
+            {
+                self.scroll_into_view(backend, id).await?;
+            }
+
+            // Execute the action
+            let output = match (&resolved_cmd, action) {
+                (Command::Text(text_cmd), Action::Scanner(sa)) => {
+                    self.execute_text(backend, sa, text_cmd.mode).await?
+                }
+                (Command::Observe(obs), Action::Scanner(sa)) => {
+                    self.execute_observe(backend, sa, obs).await?
+                }
+                (Command::Extract(ext), Action::Scanner(sa)) => {
+                    self.execute_extract(backend, sa, ext).await?
+                }
+                (Command::Goto(goto), action) => {
+                    let mut output = match action {
+                        Action::Scanner(ScannerAction::Execute(req)) if goto.spa => {
+                            self.route_spa(backend, req).await?
+                        }
+                        action => self.execute_action(backend, action).await?,
+                    };
+                    if let Some(condition) = &goto.until {
+                        let wait = ast::WaitCmd {
+                            condition: condition.clone(),
+                            timeout: goto.timeout.clone(),
+                            quiet: None,
+                        };
+                        output.push('\n');
+                        output.push_str(&self.wait_for(backend, &wait).await?);
+                    }
+                    output
+                }
+                (Command::Type(t), action) if t.then_tab => {
+                    let mut output = self.execute_action(backend, action).await?;
+                    backend.press_key("Tab", &[]).await?;
+                    output.push_str("\nPressed Tab");
+                    output
+                }
+                (_, action) => self.execute_action(backend, action).await?,
+            };
+            outputs.push(secret_echo.unwrap_or(output));
+
+            match &resolved_cmd {
+                Command::Click(ast::ClickCmd { wait_after, .. })
+                | Command::Type(ast::TypeCmd { wait_after, .. }) => {
+                    self.settle(backend, *wait_after).await?
+                }
+                // Nothing moves the pointer off the element, so the hover
+                // holds through the wait and ends with the next action. The
+                // translator has already rejected a duration it can't read.
+                Command::Hover(ast::HoverCmd {
+                    duration: Some(duration),
+                    ..
+                }) => {
+                    let ms = translator::parse_duration_ms(duration).unwrap_or(0);
+                    tokio::time::sleep(Duration::from_millis(ms)).await;
+                }
+                _ => {}
             }
         }
 
         Ok(ExecutionResult {
             output: outputs.join("\n"),
-            success: true,
+            success: exit_code == 0,
+            exit_code,
         })
     }
 
+    /// Give the page time to re-render after a click or type, then re-scan so
+    /// the next command resolves against what it shows now. `wait_after`
+    /// overrides the configured default; zero skips both.
+    async fn settle<B: Backend + ?Sized>(
+        &mut self,
+        backend: &mut B,
+        wait_after: Option<u64>,
+    ) -> Result<(), ExecutorError> {
+        let ms = wait_after.unwrap_or(self.action_settle_ms);
+        if ms == 0 {
+            return Ok(());
+        }
+        tokio::time::sleep(Duration::from_millis(ms)).await;
+        let resp = backend
+            .execute_scanner(ScannerAction::Scan(ScanRequest::default()))
+            .await?;
+        Self::check_scanner_error(&resp)?;
+        self.update_from_response(&resp);
+        Ok(())
+    }
+
+    /// Run the in-page routing script of `goto --spa`; the page's own
+    /// router, not a document load, brings in the new view.
+    async fn route_spa<B: Backend + ?Sized>(
+        &mut self,
+        backend: &mut B,
+        req: ExecuteRequest,
+    ) -> Result<String, ExecutorError> {
+        let args = match req.args {
+            Value::Object(args) => args,
+            _ => serde_json::Map::new(),
+        };
+        let url = backend
+            .execute_script(&req.script, args)
+            .await
+            .map_err(|e| ExecutorError::Navigation(e.to_string()))?;
+        Ok(format!("Navigated to {}", url.as_str().unwrap_or_default()))
+    }
+
     /// Resolve a command using the sophisticated resolution engine.
     async fn resolve_command<B: Backend + ?Sized>(
         &self,
-        cmd: oryn_core::ast::Command,
+        cmd: Command,
         backend: &mut B,
-    ) -> Result<oryn_core::ast::Command, ExecutorError> {
+    ) -> Result<Command, ExecutorError> {
         if let Some(scan) = &self.last_scan {
-            ResolutionEngine::resolve(cmd, scan, backend)
+            ResolutionEngine::resolve_with_scoring(cmd, scan, self.scoring.clone(), backend)
                 .await
                 .map_err(ExecutorError::Resolution)
         } else {
@@ -207,134 +811,1802 @@ impl CommandExecutor {
         }
     }
 
-    async fn execute_action<B: Backend + ?Sized>(
+    /// Wait for a condition. Text and network-idle waits are polled here;
+    /// the rest are handed to the scanner.
+    async fn wait_for<B: Backend + ?Sized>(
         &mut self,
         backend: &mut B,
-        action: Action,
+        wait: &ast::WaitCmd,
     ) -> Result<String, ExecutorError> {
-        match action {
-            // Scanner Actions -> execute_scanner
-            Action::Scanner(sa) => {
-                let resp = backend.execute_scanner(sa).await?;
-
-                Self::check_scanner_error(&resp)?;
-
-                self.update_from_response(&resp);
-                Ok(format_response(&resp))
-            }
-
-            // Browser Actions -> backend methods
-            Action::Browser(ba) => self.execute_browser_action(backend, ba).await,
+        if let ast::WaitCondition::All(_) | ast::WaitCondition::Any(_) = &wait.condition {
+            let timeout_ms = wait
+                .timeout
+                .as_deref()
+                .and_then(translator::parse_duration_ms)
+                .unwrap_or(self.wait_timeout_ms);
+            return self
+                .wait_for_group(backend, &wait.condition, timeout_ms)
+                .await;
+        }
 
-            // Session Actions -> backend methods
-            Action::Session(sa) => self.execute_session_action(backend, sa).await,
+        if let ast::WaitCondition::TextOf {
+            target,
+            comparison,
+            expected,
+        } = &wait.condition
+        {
+            let timeout_ms = wait
+                .timeout
+                .as_deref()
+                .and_then(translator::parse_duration_ms)
+                .unwrap_or(self.wait_timeout_ms);
+            return self
+                .wait_for_text(backend, target, *comparison, expected, timeout_ms)
+                .await;
+        }
 
-            // Meta Actions -> Not supported yet
-            Action::Meta(ma) => Err(ExecutorError::NotImplemented(format!(
-                "Meta action: {:?}",
-                ma
-            ))),
+        if wait.condition == ast::WaitCondition::Idle
+            && let Some(output) = self.wait_for_network_idle(backend, wait).await?
+        {
+            return Ok(output);
         }
+
+        let action = translator::translate(&Command::Wait(wait.clone()))?;
+        self.execute_action(backend, action).await
     }
 
-    async fn execute_browser_action<B: Backend + ?Sized>(
+    /// Check an `assert` against a fresh scan. A check that does not hold
+    /// fails with what the page showed instead.
+    async fn check_assertion<B: Backend + ?Sized>(
         &mut self,
         backend: &mut B,
-        action: BrowserAction,
+        assert: &ast::AssertCmd,
     ) -> Result<String, ExecutorError> {
-        match action {
-            BrowserAction::Navigate(req) => {
-                let res = backend
-                    .navigate(&req.url)
-                    .await
-                    .map_err(|e| ExecutorError::Navigation(e.to_string()))?;
-                Ok(format!("Navigated to {}", res.url))
-            }
-            BrowserAction::Back(_) => {
-                let res = backend.go_back().await?;
-                Ok(format!("Navigated back to {}", res.url))
-            }
-            BrowserAction::Forward(_) => {
-                let res = backend.go_forward().await?;
-                Ok(format!("Navigated forward to {}", res.url))
-            }
-            BrowserAction::Refresh(_) => {
-                let res = backend.refresh().await?;
-                Ok(format!("Refreshed: {}", res.url))
-            }
-            BrowserAction::Screenshot(req) => {
-                let data = backend.screenshot().await?;
-                let output_path = req.output.unwrap_or_else(|| "screenshot.png".to_string());
-                std::fs::write(&output_path, &data)?;
-                Ok(format!(
-                    "Screenshot saved to {} ({} bytes)",
-                    output_path,
-                    data.len()
-                ))
-            }
-            BrowserAction::Pdf(req) => {
-                let data = backend.pdf().await?;
-                std::fs::write(&req.path, &data)?;
-                Ok(format!("PDF saved to {} ({} bytes)", req.path, data.len()))
+        let resp = backend
+            .execute_scanner(ScannerAction::Scan(ScanRequest::default()))
+            .await?;
+        Self::check_scanner_error(&resp)?;
+        self.update_from_response(&resp);
+        let scan = self
+            .last_scan
+            .as_ref()
+            .ok_or(ExecutorError::NoScanContext)?;
+
+        // Targets are resolved here, where every OIL target form is
+        // understood, and handed to the verifier by id.
+        let element = |target: &ast::Target| -> Result<Option<&Element>, ExecutorError> {
+            let ctx = ResolverContext::new(scan).with_scoring(self.scoring.clone());
+            match resolver::resolve_target(
+                &target.to_resolver_target(),
+                &ctx,
+                ResolutionStrategy::Best,
+            ) {
+                Ok(resolver::Target::Id(id)) => {
+                    Ok(scan.elements.iter().find(|e| e.id as usize == id))
+                }
+                Ok(_) | Err(ResolverError::NoMatch(_)) => Ok(None),
+                Err(e) => Err(e.into()),
             }
-            BrowserAction::Press(req) => {
-                backend.press_key(&req.key, &req.modifiers).await?;
-                if req.modifiers.is_empty() {
-                    Ok(format!("Pressed {}", req.key))
-                } else {
-                    Ok(format!("Pressed {}+{:?}", req.key, req.modifiers))
+        };
+        let by_id = |el: &Element| TargetSpec {
+            kind: TargetKind::Id { id: el.id as u64 },
+            fallback: None,
+        };
+
+        let (described, condition, found) = match &assert.condition {
+            ast::AssertCondition::Visible(target) => {
+                let described = format!("visible {}", describe_wait_target(target));
+                match element(target)? {
+                    Some(el) => (described, Condition::Visible(by_id(el)), String::new()),
+                    None => {
+                        return Err(ExecutorError::AssertionFailed(format!(
+                            "{}: no matching element on the page",
+                            described
+                        )));
+                    }
                 }
             }
-            BrowserAction::Tab(req) => match req.action.as_str() {
-                "list" => {
-                    let tabs = backend.get_tabs().await?;
-                    if tabs.is_empty() {
-                        Ok("No tabs".into())
-                    } else {
-                        let titles: Vec<String> = tabs.iter().map(|t| t.title.clone()).collect();
-                        Ok(format!("Tabs: {}", titles.join(", ")))
+            ast::AssertCondition::TextContains { target, expected } => {
+                let described = format!(
+                    "text {} contains \"{}\"",
+                    describe_wait_target(target),
+                    expected
+                );
+                match element(target)? {
+                    Some(el) => (
+                        described,
+                        Condition::TextContains {
+                            text: expected.clone(),
+                            within: Some(by_id(el)),
+                        },
+                        format!(
+                            "element text is \"{}\"",
+                            el.text.as_deref().or(el.label.as_deref()).unwrap_or("")
+                        ),
+                    ),
+                    None => {
+                        return Err(ExecutorError::AssertionFailed(format!(
+                            "{}: no matching element on the page",
+                            described
+                        )));
                     }
                 }
-                _ => Err(ExecutorError::NotImplemented(format!(
-                    "Tab action: {}",
-                    req.action
-                ))),
-            },
-            // Frame, Dialog -> NotSupported
-            _ => Err(ExecutorError::NotImplemented(format!(
-                "Browser action: {:?}",
-                action
-            ))),
+            }
+            ast::AssertCondition::UrlMatches(pattern) => (
+                format!("url matches \"{}\"", pattern),
+                Condition::UrlMatches(pattern.clone()),
+                format!("page is at {}", scan.page.url),
+            ),
+        };
+
+        let holds = Verifier::new()
+            .verify(&condition, &VerifierContext::new(scan))
+            .await
+            .map_err(IntentError::from)?;
+        if holds {
+            Ok(format!("Assertion passed: {}", described))
+        } else {
+            Err(ExecutorError::AssertionFailed(format!(
+                "{}: {}",
+                described, found
+            )))
         }
     }
 
-    async fn execute_session_action<B: Backend + ?Sized>(
+    /// Capture the box of a `screenshot --element-only` target, grown by its
+    /// padding. The page is re-scanned first so the box is current.
+    async fn screenshot_element<B: Backend + ?Sized>(
         &mut self,
         backend: &mut B,
-        action: SessionAction,
+        shot: &ast::ScreenshotCmd,
     ) -> Result<String, ExecutorError> {
-        match action {
-            SessionAction::Cookie(req) => match req.action.as_str() {
-                "list" => {
-                    let cookies = backend.get_cookies().await?;
-                    if cookies.is_empty() {
-                        Ok("No cookies".into())
-                    } else {
-                        let names: Vec<String> = cookies.iter().map(|c| c.name.clone()).collect();
-                        Ok(format!("Cookies: {}", names.join(", ")))
-                    }
-                }
-                "get" => {
-                    let name = req.name.unwrap_or_default();
-                    let cookies = backend.get_cookies().await?;
-                    match cookies.into_iter().find(|c| c.name == name) {
-                        Some(c) => Ok(format!("Cookie: {}={}", c.name, c.value)),
-                        None => Ok(format!("Cookie {} not found", name)),
-                    }
-                }
-                "set" => {
-                    let c = Self::cookie_with_defaults(
-                        req.name.unwrap_or_default(),
+        let Action::Browser(BrowserAction::Screenshot(mut req)) =
+            translator::translate(&Command::Screenshot(shot.clone()))?
+        else {
+            unreachable!("screenshot translates to a screenshot request");
+        };
+        let target = shot
+            .target
+            .as_ref()
+            .expect("--element-only screenshots have a target");
+
+        let resp = backend
+            .execute_scanner(ScannerAction::Scan(ScanRequest::default()))
+            .await?;
+        Self::check_scanner_error(&resp)?;
+        self.update_from_response(&resp);
+        let scan = self
+            .last_scan
+            .as_ref()
+            .ok_or(ExecutorError::NoScanContext)?;
+
+        let ctx = ResolverContext::new(scan).with_scoring(self.scoring.clone());
+        let element = match resolver::resolve_target(
+            &target.to_resolver_target(),
+            &ctx,
+            ResolutionStrategy::First,
+        )? {
+            resolver::Target::Id(id) => scan.elements.iter().find(|e| e.id as usize == id),
+            _ => None,
+        }
+        .ok_or_else(|| {
+            ResolverError::NoMatch(format!(
+                "{} is not an element of the scan",
+                describe_wait_target(target)
+            ))
+        })?;
+
+        req.clip = Some(padded_clip(
+            &element.rect,
+            shot.padding.unwrap_or(0.0),
+            &scan.page,
+        ));
+        self.execute_action(backend, Action::Browser(BrowserAction::Screenshot(req)))
+            .await
+    }
+
+    /// Click every element a `click --all` target matches, scanning first if
+    /// there is no context yet.
+    async fn click_all<B: Backend + ?Sized>(
+        &mut self,
+        backend: &mut B,
+        cmd: &Command,
+    ) -> Result<String, ExecutorError> {
+        if self.last_scan.is_none() {
+            let resp = backend
+                .execute_scanner(ScannerAction::Scan(ScanRequest::default()))
+                .await?;
+            Self::check_scanner_error(&resp)?;
+            self.update_from_response(&resp);
+        }
+        let scan = self
+            .last_scan
+            .as_ref()
+            .ok_or(ExecutorError::NoScanContext)?;
+        let actions = translator::translate_all(
+            cmd,
+            &ResolverContext::new(scan).with_scoring(self.scoring.clone()),
+        )?;
+
+        let mut outputs = Vec::with_capacity(actions.len());
+        for action in actions {
+            outputs.push(self.execute_action(backend, action).await?);
+        }
+        Ok(outputs.concat())
+    }
+
+    /// Run a `repeat` block's commands `count` times, re-scanning before each
+    /// pass so targets resolve against the page the previous pass left.
+    async fn run_repeat<B: Backend + ?Sized>(
+        &mut self,
+        backend: &mut B,
+        repeat: &ast::RepeatCmd,
+    ) -> Result<ExecutionResult, ExecutorError> {
+        let total = repeat_passes(repeat);
+        if total > self.max_repeat {
+            return Err(ExecutorError::RepeatLimit {
+                count: total,
+                max: self.max_repeat,
+            });
+        }
+
+        let mut outputs = Vec::with_capacity(repeat.count);
+        let mut exit_code = 0;
+        for _ in 0..repeat.count {
+            let resp = backend
+                .execute_scanner(ScannerAction::Scan(ScanRequest::default()))
+                .await?;
+            Self::check_scanner_error(&resp)?;
+            self.update_from_response(&resp);
+
+            let result = Box::pin(self.execute_commands(backend, repeat.body.clone())).await?;
+            outputs.push(result.output);
+            if exit_code == 0 {
+                exit_code = result.exit_code;
+            }
+        }
+        Ok(ExecutionResult {
+            output: outputs.join("\n"),
+            success: exit_code == 0,
+            exit_code,
+        })
+    }
+
+    /// The element a click, type or hover acts on, when the last scan placed
+    /// it outside the viewport.
+    fn offscreen_target(&self, cmd: &Command) -> Option<u32> {
+        let target = match cmd {
+            Command::Click(click) => &click.target,
+            Command::Type(type_cmd) => &type_cmd.target,
+            Command::Hover(hover) => &hover.target,
+            _ => return None,
+        };
+        let TargetAtomic::Id(id) = target.atomic else {
+            return None;
+        };
+        let scan = self.last_scan.as_ref()?;
+        let viewport = &scan.page.viewport;
+        // Backends that report no viewport give nothing to compare against
+        if viewport.width == 0 || viewport.height == 0 {
+            return None;
+        }
+        let rect = &scan.elements.iter().find(|e| e.id as usize == id)?.rect;
+        let offscreen = rect.y + rect.height <= 0.0
+            || rect.y >= viewport.height as f32
+            || rect.x + rect.width <= 0.0
+            || rect.x >= viewport.width as f32;
+        offscreen.then_some(id as u32)
+    }
+
+    async fn scroll_into_view<B: Backend + ?Sized>(
+        &mut self,
+        backend: &mut B,
+        id: u32,
+    ) -> Result<(), ExecutorError> {
+        let resp = backend
+            .execute_scanner(ScannerAction::Scroll(ScrollRequest {
+                id: Some(id),
+                direction: ScrollDirection::IntoView,
+                amount: None,
+                behavior: ScrollBehavior::Instant,
+            }))
+            .await?;
+        Self::check_scanner_error(&resp)
+    }
+
+    /// List every element a target matches in the current scan, best first,
+    /// with the score the resolver gave it. Candidates are ranked as `click`
+    /// ranks them, so the first is the one it would pick. Scans first if
+    /// nothing has been observed yet or the last scan is stale.
+    async fn resolve_candidates<B: Backend + ?Sized>(
+        &mut self,
+        backend: &mut B,
+        target: &ast::Target,
+    ) -> Result<String, ExecutorError> {
+        if self.last_scan.is_none() {
+            let resp = backend
+                .execute_scanner(ScannerAction::Scan(ScanRequest::default()))
+                .await?;
+            Self::check_scanner_error(&resp)?;
+            self.update_from_response(&resp);
+        }
+        self.refresh_stale_scan(backend).await?;
+        let scan = self
+            .last_scan
+            .as_ref()
+            .ok_or(ExecutorError::NoScanContext)?;
+        let ctx = ResolverContext::new(scan).with_scoring(self.scoring.clone());
+        let strategy = TargetRequirement::Clickable.to_strategy();
+        let candidates =
+            resolver::resolve_all_scored(&target.to_resolver_target(), &ctx, strategy)?;
+
+        let mut lines = vec![format!(
+            "{} candidate{} (best first):",
+            candidates.len(),
+            if candidates.len() == 1 { "" } else { "s" }
+        )];
+        for (rank, (id, score)) in candidates.iter().enumerate() {
+            let Some(element) = ctx.get_element(*id) else {
+                continue;
+            };
+            let role = element.role.as_deref().unwrap_or(&element.element_type);
+            let text = element
+                .text
+                .as_deref()
+                .or(element.label.as_deref())
+                .unwrap_or("");
+            lines.push(format!(
+                "  {}. [{}] score={} {} {:?} {}",
+                rank + 1,
+                id,
+                score,
+                role,
+                text,
+                element.selector
+            ));
+        }
+        Ok(lines.join("\n"))
+    }
+
+    /// Pick a value from a div-based dropdown: click the trigger open, type
+    /// the value into a search box if one appeared, then click the option
+    /// whose text matches. Runs for `select --custom`, and for plain `select`
+    /// when the target looks like an ARIA combobox rather than a `<select>`;
+    /// returns `None` to leave anything else to the scanner's native select.
+    async fn select_custom<B: Backend + ?Sized>(
+        &mut self,
+        backend: &mut B,
+        select: &ast::SelectCmd,
+    ) -> Result<Option<String>, ExecutorError> {
+        if self.last_scan.is_none() {
+            let resp = backend
+                .execute_scanner(ScannerAction::Scan(ScanRequest::default()))
+                .await?;
+            Self::check_scanner_error(&resp)?;
+            self.update_from_response(&resp);
+        }
+        let scan = self.last_scan.clone().ok_or(ExecutorError::NoScanContext)?;
+        let resolved = resolver::resolve_target(
+            &select.target.to_resolver_target(),
+            &ResolverContext::new(&scan).with_scoring(self.scoring.clone()),
+            ResolutionStrategy::First,
+        );
+        let trigger = match &resolved {
+            Ok(resolver::Target::Id(id)) => scan.elements.iter().find(|e| e.id as usize == *id),
+            Ok(resolver::Target::Selector(selector)) => {
+                scan.elements.iter().find(|e| &e.selector == selector)
+            }
+            _ => None,
+        };
+        if !select.custom && !trigger.is_some_and(is_custom_dropdown) {
+            return Ok(None);
+        }
+        let open = match (trigger, resolved?) {
+            (Some(el), _) => plain_click(Some(el.id), None),
+            (None, resolver::Target::Selector(selector)) => plain_click(None, Some(selector)),
+            (None, other) => {
+                return Err(ExecutorError::Resolver(ResolverError::NoMatch(format!(
+                    "dropdown {:?} is not on the page",
+                    other
+                ))));
+            }
+        };
+        // Only the opening click can be planned; the options it reveals
+        // are not on the page yet.
+        if self.dry_run {
+            let plan = serde_json::to_value(Action::Scanner(ScannerAction::Click(open)))?;
+            return Ok(Some(format!(
+                "Dry run: {} then pick option {:?}",
+                plan, select.value
+            )));
+        }
+        self.execute_action(backend, Action::Scanner(ScannerAction::Click(open)))
+            .await?;
+        let opened = self.rescan(backend).await?;
+
+        let before: HashSet<&str> = scan.elements.iter().map(|e| e.selector.as_str()).collect();
+        let trigger_id = trigger.map(|e| e.id);
+        let search = opened.elements.iter().find(|e| {
+            is_search_box(e) && (Some(e.id) == trigger_id || !before.contains(e.selector.as_str()))
+        });
+        let listed = match search {
+            Some(input) => {
+                let req = TypeRequest {
+                    id: Some(input.id),
+                    selector: None,
+                    text: select.value.clone(),
+                    clear: true,
+                    submit: false,
+                    delay: None,
+                    secret: false,
+                };
+                self.execute_action(backend, Action::Scanner(ScannerAction::Type(req)))
+                    .await?;
+                self.rescan(backend).await?
+            }
+            None => opened.clone(),
+        };
+
+        // Options are whatever the open dropdown added; ARIA options win when
+        // the widget marks them.
+        let search_id = search.map(|e| e.id);
+        let mut options = listed;
+        options
+            .elements
+            .retain(|e| Some(e.id) != trigger_id && Some(e.id) != search_id && !e.state.hidden);
+        if options.elements.iter().any(is_option) {
+            options.elements.retain(is_option);
+        } else {
+            options
+                .elements
+                .retain(|e| !before.contains(e.selector.as_str()));
+        }
+        let Ok(resolver::Target::Id(option_id)) = resolver::resolve_target(
+            &resolver::Target::Text(select.value.clone()),
+            &ResolverContext::new(&options).with_scoring(self.scoring.clone()),
+            ResolutionStrategy::First,
+        ) else {
+            return Err(ExecutorError::Resolver(ResolverError::NoMatch(format!(
+                "no option \"{}\" in the opened dropdown ({} candidates)",
+                select.value,
+                options.elements.len()
+            ))));
+        };
+        let req = plain_click(Some(option_id as u32), None);
+        self.execute_action(backend, Action::Scanner(ScannerAction::Click(req)))
+            .await?;
+        Ok(Some(format!("Selected \"{}\"", select.value)))
+    }
+
+    /// Re-scan when the last scan no longer describes the page, because it
+    /// has navigated since or the scan is past `max_scan_age`, so targets
+    /// are not resolved against elements that are gone. Fails with the
+    /// rescan's own error when the page cannot be scanned again.
+    async fn refresh_stale_scan<B: Backend + ?Sized>(
+        &mut self,
+        backend: &mut B,
+    ) -> Result<(), ExecutorError> {
+        let Some(scan) = &self.last_scan else {
+            return Ok(());
+        };
+        let mut ctx = ResolverContext::new(scan).with_generation(self.scan_generation);
+        if let Some(scanned_at) = self.last_scan_at {
+            ctx = ctx.with_scanned_at(scanned_at);
+        }
+        if let Some(max_age) = self.max_scan_age {
+            ctx = ctx.with_max_age(max_age);
+        }
+        if !self.navigated_since_scan
+            && ctx
+                .ensure_fresh(self.page_url.as_deref().unwrap_or_default())
+                .is_ok()
+        {
+            return Ok(());
+        }
+        self.rescan(backend).await.map(|_| ())
+    }
+
+    /// Scan the page and return the result.
+    async fn rescan<B: Backend + ?Sized>(
+        &mut self,
+        backend: &mut B,
+    ) -> Result<ScanResult, ExecutorError> {
+        let resp = backend
+            .execute_scanner(ScannerAction::Scan(ScanRequest::default()))
+            .await?;
+        Self::check_scanner_error(&resp)?;
+        self.update_from_response(&resp);
+        self.last_scan.clone().ok_or(ExecutorError::NoScanContext)
+    }
+
+    /// Re-scan until the text of `target` compares true against `expected`,
+    /// or fail once `timeout_ms` has passed.
+    async fn wait_for_text<B: Backend + ?Sized>(
+        &mut self,
+        backend: &mut B,
+        target: &ast::Target,
+        comparison: ast::TextComparison,
+        expected: &str,
+        timeout_ms: u64,
+    ) -> Result<String, ExecutorError> {
+        let deadline = Instant::now() + Duration::from_millis(timeout_ms);
+        let resolver_target = target.to_resolver_target();
+        let description = describe_wait_target(target);
+        let mut last_text = None;
+
+        loop {
+            let resp = backend
+                .execute_scanner(ScannerAction::Scan(ScanRequest::default()))
+                .await?;
+            Self::check_scanner_error(&resp)?;
+            self.update_from_response(&resp);
+
+            if let Some(text) = self.text_in_scan(&resolver_target) {
+                if comparison.matches(&text, expected) {
+                    return Ok(format!("Text of {} is \"{}\"", description, text.trim()));
+                }
+                last_text = Some(text);
+            }
+
+            if Instant::now() >= deadline {
+                let seen = match last_text {
+                    Some(text) => format!("last saw \"{}\"", text.trim()),
+                    None => "element not found".to_string(),
+                };
+                return Err(ExecutorError::Timeout(format!(
+                    "text of {} did not {} \"{}\" within {}ms ({})",
+                    description,
+                    match comparison {
+                        ast::TextComparison::Equals => "equal",
+                        ast::TextComparison::Contains => "contain",
+                    },
+                    expected,
+                    timeout_ms,
+                    seen
+                )));
+            }
+            tokio::time::sleep(Duration::from_millis(self.scan_poll_ms)).await;
+        }
+    }
+
+    /// Re-scan until `target` is on the page and visible, or fail once
+    /// `timeout_ms` has passed. Sightings are kept across polls so the timeout
+    /// can tell an element that never appeared from one that was shown and
+    /// then lost.
+    async fn wait_for_visible<B: Backend + ?Sized>(
+        &mut self,
+        backend: &mut B,
+        target: &ast::Target,
+        timeout_ms: u64,
+    ) -> Result<String, ExecutorError> {
+        let deadline = Instant::now() + Duration::from_millis(timeout_ms);
+        let resolver_target = target.to_resolver_target();
+        let description = describe_wait_target(target);
+        let (mut scans, mut sightings, mut present) = (0, 0, false);
+
+        loop {
+            // A scan the page never answers must not outlast the wait.
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            let scan = backend.execute_scanner(ScannerAction::Scan(ScanRequest::default()));
+            if let Ok(resp) = tokio::time::timeout(remaining, scan).await {
+                let resp = resp?;
+                Self::check_scanner_error(&resp)?;
+                self.update_from_response(&resp);
+                scans += 1;
+
+                // Hidden matches still count as sightings of the element.
+                present = match self.visibility_in_scan(&resolver_target) {
+                    Some(true) => return Ok(format!("{} is visible", description)),
+                    Some(false) => {
+                        sightings += 1;
+                        true
+                    }
+                    None => false,
+                };
+            }
+
+            if Instant::now() >= deadline {
+                let seen = if scans == 0 {
+                    "no scan completed".to_string()
+                } else if present {
+                    format!(
+                        "present but not visible in {} of {} scans",
+                        sightings, scans
+                    )
+                } else if sightings > 0 {
+                    format!("seen in {} of {} scans, then lost", sightings, scans)
+                } else {
+                    format!("never appeared in {} scans", scans)
+                };
+                return Err(ExecutorError::Timeout(format!(
+                    "wait visible {} did not complete within {}ms ({})",
+                    description, timeout_ms, seen
+                )));
+            }
+            tokio::time::sleep(Duration::from_millis(self.scan_poll_ms)).await;
+        }
+    }
+
+    /// Re-scan until one of `targets` is visible, reporting the index of the
+    /// first to show; when several show in the same scan the earliest listed
+    /// wins.
+    async fn wait_for_any<B: Backend + ?Sized>(
+        &mut self,
+        backend: &mut B,
+        targets: &[ast::Target],
+        timeout_ms: u64,
+    ) -> Result<String, ExecutorError> {
+        let deadline = Instant::now() + Duration::from_millis(timeout_ms);
+        let resolver_targets: Vec<_> = targets.iter().map(|t| t.to_resolver_target()).collect();
+        let mut scans = 0;
+
+        loop {
+            // A scan the page never answers must not outlast the wait.
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            let scan = backend.execute_scanner(ScannerAction::Scan(ScanRequest::default()));
+            if let Ok(resp) = tokio::time::timeout(remaining, scan).await {
+                let resp = resp?;
+                Self::check_scanner_error(&resp)?;
+                self.update_from_response(&resp);
+                scans += 1;
+
+                if let Some(index) = resolver_targets
+                    .iter()
+                    .position(|t| self.visibility_in_scan(t) == Some(true))
+                {
+                    return Ok(format!(
+                        "{} is visible (any-of index {})",
+                        describe_wait_target(&targets[index]),
+                        index
+                    ));
+                }
+            }
+
+            if Instant::now() >= deadline {
+                let described: Vec<_> = targets.iter().map(describe_wait_target).collect();
+                return Err(ExecutorError::Timeout(format!(
+                    "wait any-of [{}] did not complete within {}ms (none visible in {} scans)",
+                    described.join(", "),
+                    timeout_ms,
+                    scans
+                )));
+            }
+            tokio::time::sleep(Duration::from_millis(self.scan_poll_ms)).await;
+        }
+    }
+
+    /// Re-scan until every condition of a `wait all` group holds, or one of
+    /// a `wait any` group does. Each poll checks the conditions against one
+    /// scan, so an `all` is only satisfied by a page where they hold together.
+    async fn wait_for_group<B: Backend + ?Sized>(
+        &mut self,
+        backend: &mut B,
+        group: &ast::WaitCondition,
+        timeout_ms: u64,
+    ) -> Result<String, ExecutorError> {
+        let (ast::WaitCondition::All(conditions) | ast::WaitCondition::Any(conditions)) = group
+        else {
+            unreachable!("wait_for_group is only called for all/any");
+        };
+        let all = matches!(group, ast::WaitCondition::All(_));
+        let deadline = Instant::now() + Duration::from_millis(timeout_ms);
+        let start_url = self.rescan(backend).await?.page.url;
+        let mut scans = 0;
+
+        loop {
+            if scans > 0 {
+                self.rescan(backend).await?;
+            }
+            scans += 1;
+
+            let mut held = Vec::with_capacity(conditions.len());
+            for condition in conditions {
+                held.push(
+                    self.wait_condition_holds(backend, condition, &start_url)
+                        .await?,
+                );
+            }
+
+            if all && held.iter().all(|h| *h) {
+                return Ok(format!(
+                    "{} hold ({} scans)",
+                    describe_wait_condition(group),
+                    scans
+                ));
+            }
+            if !all && let Some(index) = held.iter().position(|h| *h) {
+                return Ok(format!(
+                    "{} holds (any index {})",
+                    describe_wait_condition(&conditions[index]),
+                    index
+                ));
+            }
+
+            if Instant::now() >= deadline {
+                let pending: Vec<_> = conditions
+                    .iter()
+                    .zip(&held)
+                    .filter(|(_, h)| !**h)
+                    .map(|(c, _)| describe_wait_condition(c))
+                    .collect();
+                return Err(ExecutorError::Timeout(format!(
+                    "wait {} did not complete within {}ms (still waiting on {} after {} scans)",
+                    describe_wait_condition(group),
+                    timeout_ms,
+                    pending.join(", "),
+                    scans
+                )));
+            }
+            tokio::time::sleep(Duration::from_millis(self.scan_poll_ms)).await;
+        }
+    }
+
+    /// Whether one condition of a group holds right now. Conditions a scan
+    /// can answer are checked against the last scan; the rest are asked of
+    /// the scanner as a wait with no timeout.
+    async fn wait_condition_holds<B: Backend + ?Sized>(
+        &mut self,
+        backend: &mut B,
+        condition: &ast::WaitCondition,
+        start_url: &str,
+    ) -> Result<bool, ExecutorError> {
+        let by_scanner =
+            |target: &ast::Target| matches!(target.atomic, TargetAtomic::Selector { .. });
+        match condition {
+            ast::WaitCondition::All(conditions) => {
+                for condition in conditions {
+                    if !Box::pin(self.wait_condition_holds(backend, condition, start_url)).await? {
+                        return Ok(false);
+                    }
+                }
+                Ok(true)
+            }
+            ast::WaitCondition::Any(conditions) => {
+                for condition in conditions {
+                    if Box::pin(self.wait_condition_holds(backend, condition, start_url)).await? {
+                        return Ok(true);
+                    }
+                }
+                Ok(false)
+            }
+            ast::WaitCondition::Visible(target) if !by_scanner(target) => {
+                Ok(self.visibility_in_scan(&target.to_resolver_target()) == Some(true))
+            }
+            ast::WaitCondition::Hidden(target) if !by_scanner(target) => {
+                Ok(self.visibility_in_scan(&target.to_resolver_target()) != Some(true))
+            }
+            ast::WaitCondition::AnyOf(targets) => Ok(targets
+                .iter()
+                .any(|t| self.visibility_in_scan(&t.to_resolver_target()) == Some(true))),
+            ast::WaitCondition::TextOf {
+                target,
+                comparison,
+                expected,
+            } => Ok(self
+                .text_in_scan(&target.to_resolver_target())
+                .is_some_and(|text| comparison.matches(&text, expected))),
+            ast::WaitCondition::Url(pattern) => {
+                let scan = self
+                    .last_scan
+                    .as_ref()
+                    .ok_or(ExecutorError::NoScanContext)?;
+                Ok(Verifier::new()
+                    .verify(
+                        &Condition::UrlMatches(pattern.clone()),
+                        &VerifierContext::new(scan),
+                    )
+                    .await
+                    .map_err(IntentError::from)?)
+            }
+            ast::WaitCondition::Navigation => Ok(self
+                .last_scan
+                .as_ref()
+                .is_some_and(|scan| scan.page.url != start_url)),
+            condition => {
+                let action = translator::translate(&Command::Wait(ast::WaitCmd {
+                    condition: condition.clone(),
+                    timeout: None,
+                    quiet: None,
+                }))?;
+                let Action::Scanner(ScannerAction::Wait(mut req)) = action else {
+                    unreachable!("wait translates to a scanner wait");
+                };
+                req.timeout = Some(0);
+                match backend.execute_scanner(ScannerAction::Wait(req)).await? {
+                    ScannerProtocolResponse::Error { code, .. } if code == "TIMEOUT" => Ok(false),
+                    resp => {
+                        Self::check_scanner_error(&resp)?;
+                        Ok(true)
+                    }
+                }
+            }
+        }
+    }
+
+    /// The text of `target` in the last scan, if it resolves to an element.
+    fn text_in_scan(&self, target: &resolver::Target) -> Option<String> {
+        let scan = self.last_scan.as_ref()?;
+        let resolver::Target::Id(id) = resolver::resolve_target(
+            target,
+            &ResolverContext::new(scan).with_scoring(self.scoring.clone()),
+            ResolutionStrategy::First,
+        )
+        .ok()?
+        else {
+            return None;
+        };
+        let element = scan.elements.iter().find(|e| e.id as usize == id)?;
+        Some(element.text.clone().unwrap_or_default())
+    }
+
+    /// Whether `target` is visible in the last scan: `None` when it is not
+    /// there at all, `Some(false)` when it is there but hidden or empty.
+    fn visibility_in_scan(&self, target: &resolver::Target) -> Option<bool> {
+        let scan = self.last_scan.as_ref()?;
+        let resolver::Target::Id(id) = resolver::resolve_target(
+            target,
+            &ResolverContext::new(scan).with_scoring(self.scoring.clone()),
+            ResolutionStrategy::IncludeHidden,
+        )
+        .ok()?
+        else {
+            return None;
+        };
+        let element = scan.elements.iter().find(|e| e.id as usize == id)?;
+        Some(!element.state.hidden && element.rect.width > 0.0 && element.rect.height > 0.0)
+    }
+
+    /// Wait until the backend reports no requests in flight for the quiet
+    /// window. Returns `None` when the backend cannot count requests, leaving
+    /// the wait to the scanner.
+    async fn wait_for_network_idle<B: Backend + ?Sized>(
+        &mut self,
+        backend: &mut B,
+        cmd: &ast::WaitCmd,
+    ) -> Result<Option<String>, ExecutorError> {
+        let quiet_ms = cmd
+            .quiet
+            .as_deref()
+            .and_then(translator::parse_duration_ms)
+            .unwrap_or(IDLE_QUIET_MS);
+        let timeout_ms = cmd
+            .timeout
+            .as_deref()
+            .and_then(translator::parse_duration_ms)
+            .unwrap_or(self.wait_timeout_ms);
+        let quiet = Duration::from_millis(quiet_ms);
+        let deadline = Instant::now() + Duration::from_millis(timeout_ms);
+        let mut idle_since = None;
+
+        loop {
+            let pending = match backend.pending_requests().await {
+                Ok(n) => n,
+                Err(BackendError::NotSupported(_)) => return Ok(None),
+                Err(e) => return Err(e.into()),
+            };
+            let now = Instant::now();
+            if pending > 0 {
+                idle_since = None;
+            } else if now.duration_since(*idle_since.get_or_insert(now)) >= quiet {
+                return Ok(Some(format!("Network idle for {}ms", quiet_ms)));
+            }
+
+            if now >= deadline {
+                return Err(ExecutorError::Timeout(format!(
+                    "network did not stay idle for {}ms within {}ms ({} requests in flight)",
+                    quiet_ms, timeout_ms, pending
+                )));
+            }
+            tokio::time::sleep(Duration::from_millis(IDLE_POLL_MS)).await;
+        }
+    }
+
+    /// Ask the installed [`Disambiguator`] which element a semantic target
+    /// meant when it matches several in the last scan, pinning the command to
+    /// the chosen id. Candidates are ranked with the command's own
+    /// preferences, so only elements it could equally well have picked are
+    /// offered. Without a disambiguator the resolution engine picks as usual.
+    async fn disambiguate(&mut self, mut cmd: Command) -> Result<Command, ExecutorError> {
+        let (Some(disambiguator), Some(scan)) = (self.disambiguator.as_mut(), &self.last_scan)
+        else {
+            return Ok(cmd);
+        };
+        let strategy = CommandMeta::for_command(&cmd).requirement.to_strategy();
+        let Some(target) = cmd.target_mut() else {
+            return Ok(cmd);
+        };
+        if matches!(
+            target.atomic,
+            TargetAtomic::Id(_) | TargetAtomic::Selector { .. }
+        ) {
+            return Ok(cmd);
+        }
+
+        let ctx = ResolverContext::new(scan).with_scoring(self.scoring.clone());
+        let (description, count, candidates) =
+            match resolver::ensure_unique(&target.to_resolver_target(), &ctx, strategy) {
+                Err(ResolverError::AmbiguousMatch {
+                    target,
+                    count,
+                    candidates,
+                }) => (target, count, candidates),
+                // Unique matches and failures are left to the resolution engine.
+                _ => return Ok(cmd),
+            };
+
+        match disambiguator.choose(&description, &candidates).await {
+            Some(id) if candidates.iter().any(|c| c.id == id) => {
+                *target = ast::Target::id(id as usize);
+                Ok(cmd)
+            }
+            _ => Err(ExecutorError::Resolver(ResolverError::AmbiguousMatch {
+                target: description,
+                count,
+                candidates,
+            })),
+        }
+    }
+
+    /// Reject actions the backend has declared it cannot perform, with guidance
+    /// on where the feature is available.
+    fn check_capabilities(caps: BackendCapabilities, action: &Action) -> Result<(), ExecutorError> {
+        let unsupported = match action {
+            Action::Browser(BrowserAction::Pdf(_)) if !caps.pdf => {
+                Some("PDF requires headless mode (oryn-h)")
+            }
+            Action::Browser(BrowserAction::Tab(_)) if !caps.tabs => {
+                Some("Tab management is not available on this backend; use oryn-h or oryn-e")
+            }
+            Action::Session(SessionAction::Cookie(_)) if !caps.cookies => {
+                Some("Cookie access is not available on this backend; use oryn-h or oryn-e")
+            }
+            _ => None,
+        };
+
+        match unsupported {
+            Some(msg) => Err(ExecutorError::Backend(BackendError::NotSupported(
+                msg.into(),
+            ))),
+            None => Ok(()),
+        }
+    }
+
+    async fn execute_action<B: Backend + ?Sized>(
+        &mut self,
+        backend: &mut B,
+        action: Action,
+    ) -> Result<String, ExecutorError> {
+        Self::check_capabilities(backend.capabilities(), &action)?;
+
+        match action {
+            // Waits get the default timeout when the command omits one, and
+            // fail on expiry even if the backend never answers.
+            Action::Scanner(ScannerAction::Wait(mut req)) => {
+                let limit_ms = *req.timeout.get_or_insert(self.wait_timeout_ms);
+                let description = req.describe();
+                let call = backend.execute_scanner(ScannerAction::Wait(req));
+                let resp = tokio::time::timeout(
+                    Duration::from_millis(limit_ms + WAIT_TIMEOUT_GRACE_MS),
+                    call,
+                )
+                .await
+                .map_err(|_| {
+                    ExecutorError::Timeout(format!(
+                        "wait {} did not complete within {}ms",
+                        description, limit_ms
+                    ))
+                })??;
+
+                Self::check_scanner_error(&resp)?;
+                self.update_from_response(&resp);
+                Ok(format_response(&resp))
+            }
+
+            // Scanner Actions -> execute_scanner
+            Action::Scanner(sa) => {
+                let smooth_scroll = match &sa {
+                    ScannerAction::Scroll(req) if req.behavior == ScrollBehavior::Smooth => {
+                        Some(req.id)
+                    }
+                    _ => None,
+                };
+                let resp = backend.execute_scanner(sa).await?;
+
+                Self::check_scanner_error(&resp)?;
+
+                // The scanner returns as soon as the animation starts; hold the
+                // next command until the scroll position stops moving.
+                if let Some(id) = smooth_scroll {
+                    let settled = backend
+                        .execute_scanner(ScannerAction::Wait(WaitRequest {
+                            condition: "scroll_settled".into(),
+                            id,
+                            selector: None,
+                            text: None,
+                            expression: None,
+                            count: None,
+                            compare: None,
+                            timeout: Some(SCROLL_SETTLE_TIMEOUT_MS),
+                            poll_interval: None,
+                        }))
+                        .await?;
+                    Self::check_scanner_error(&settled)?;
+                }
+
+                self.update_from_response(&resp);
+                Ok(format_response(&resp))
+            }
+
+            // Browser Actions -> backend methods
+            Action::Browser(ba) => self.execute_browser_action(backend, ba).await,
+
+            // Session Actions -> backend methods
+            Action::Session(sa) => self.execute_session_action(backend, sa).await,
+
+            // Meta Actions -> Not supported yet
+            Action::Meta(ma) => Err(ExecutorError::NotImplemented(format!(
+                "Meta action: {:?}",
+                ma
+            ))),
+        }
+    }
+
+    fn list_intents(&self, cmd: &IntentsCmd) -> Result<String, ExecutorError> {
+        let catalog = self.registry.catalog();
+
+        if cmd.json {
+            return Ok(serde_json::to_string_pretty(&catalog)?);
+        }
+
+        if catalog.is_empty() {
+            return Ok("No intents registered".into());
+        }
+
+        let lines: Vec<String> = catalog
+            .iter()
+            .map(|entry| {
+                let params: Vec<&str> = entry.parameters.iter().map(|p| p.name.as_str()).collect();
+                match entry.description {
+                    Some(desc) => format!("{}({}) - {}", entry.name, params.join(", "), desc),
+                    None => format!("{}({})", entry.name, params.join(", ")),
+                }
+            })
+            .collect();
+        Ok(format!("Available intents:\n{}", lines.join("\n")))
+    }
+
+    /// Serialize a registered intent, built-in or loaded, so it can be edited
+    /// and loaded back. Printed when no `--out` file is given.
+    fn export_intent(&self, cmd: &ExportCmd) -> Result<String, ExecutorError> {
+        let definition = self
+            .registry
+            .get(&cmd.name)
+            .ok_or_else(|| IntentError::IntentNotFound(cmd.name.clone()))?;
+        let format = IntentFormat::detect(cmd.out.as_deref(), cmd.format.as_deref())?;
+        let content = IntentLoader::export(definition, format)?;
+        match &cmd.out {
+            // The file is left alone; the plan is all a dry run reports.
+            Some(path) if self.dry_run => Ok(format!(
+                "Dry run: would export intent '{}' to {}",
+                cmd.name, path
+            )),
+            Some(path) => {
+                std::fs::write(path, content)?;
+                Ok(format!("Exported intent '{}' to {}", cmd.name, path))
+            }
+            None => Ok(content.trim_end().to_string()),
+        }
+    }
+
+    /// Clear the session state selected by `reset`, one category at a time.
+    ///
+    /// Categories the backend does not keep are skipped and reported rather
+    /// than failing the reset.
+    async fn reset<B: Backend + ?Sized>(
+        &mut self,
+        backend: &mut B,
+        cmd: &ResetCmd,
+    ) -> Result<String, ExecutorError> {
+        let mut cleared = Vec::new();
+        let mut skipped = Vec::new();
+
+        if cmd.cookies {
+            let clear = Action::Session(SessionAction::Cookie(CookieRequest {
+                action: "clear".into(),
+                name: None,
+                value: None,
+                domain: None,
+                json: false,
+                file: None,
+                format: None,
+            }));
+            match self.execute_action(backend, clear).await {
+                Ok(_) => cleared.push("cookies"),
+                Err(ExecutorError::Backend(BackendError::NotSupported(_))) => {
+                    skipped.push("cookies")
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        if cmd.storage {
+            let clear = Action::Session(SessionAction::Storage(StorageRequest {
+                action: "clear".into(),
+                storage_type: "all".into(),
+                key: None,
+                value: None,
+            }));
+            match self.execute_action(backend, clear).await {
+                Ok(_) => cleared.push("storage"),
+                Err(ExecutorError::Backend(BackendError::NotSupported(_))) => {
+                    skipped.push("storage")
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        if cmd.requests {
+            match backend.clear_requests().await {
+                Ok(()) => cleared.push("requests"),
+                Err(BackendError::NotSupported(_)) => skipped.push("requests"),
+                Err(e) => return Err(e.into()),
+            }
+        }
+        if cmd.console {
+            match backend.clear_console().await {
+                Ok(()) => cleared.push("console"),
+                Err(BackendError::NotSupported(_)) => skipped.push("console"),
+                Err(e) => return Err(e.into()),
+            }
+        }
+
+        let mut output = if cleared.is_empty() {
+            "Nothing reset".to_string()
+        } else {
+            format!("Reset {}", cleared.join(", "))
+        };
+        if !skipped.is_empty() {
+            output.push_str(&format!(
+                " (not supported by this backend: {})",
+                skipped.join(", ")
+            ));
+        }
+        Ok(output)
+    }
+
+    /// Run an `eval` script, binding its `--arg`s as a JSON object, and return
+    /// the value it returned.
+    async fn eval_script<B: Backend + ?Sized>(
+        &self,
+        backend: &mut B,
+        cmd: &EvalCmd,
+    ) -> Result<Value, ExecutorError> {
+        let mut args = serde_json::Map::new();
+        for (name, arg) in &cmd.args {
+            let value = match arg {
+                EvalArg::Value(v) => v.clone(),
+                EvalArg::Variable(var) => self
+                    .variables
+                    .get(var)
+                    .cloned()
+                    .ok_or_else(|| ExecutorError::UnknownVariable(var.clone()))?,
+            };
+            args.insert(name.clone(), value);
+        }
+        Ok(backend.execute_script(&cmd.script, args).await?)
+    }
+
+    /// Run an intent and any chained after it with `then`. Later intents see
+    /// the parameters and extracted results of earlier ones; the chain stops
+    /// at the first intent that does not complete unless `--continue` is set.
+    /// Returns the output and the exit code of the first intent that did not
+    /// complete, which is only reported for `--json`.
+    async fn run_intent<B: Backend + ?Sized>(
+        &mut self,
+        backend: &mut B,
+        cmd: &RunCmd,
+    ) -> Result<(String, i32), ExecutorError> {
+        let stages: Vec<&RunCmd> = std::iter::once(cmd).chain(&cmd.then).collect();
+        let mut scope = HashMap::new();
+        let mut results = Vec::with_capacity(stages.len());
+        let mut secrets = Vec::new();
+        let mut stopped = None;
+
+        for (index, stage) in stages.iter().enumerate() {
+            // With --json a failing intent is reported in its result rather
+            // than as an error, so the output stays JSON.
+            let result = match self
+                .run_single_intent(
+                    backend,
+                    stage,
+                    cmd.screenshot_on_error,
+                    &mut scope,
+                    &mut secrets,
+                )
+                .await
+            {
+                Ok(result) => result,
+                Err(e) if cmd.keep_going || cmd.json => IntentResult {
+                    status: IntentStatus::Failed(e.to_string()),
+                    data: None,
+                    logs: vec![],
+                    checkpoint: None,
+                    hints: vec![],
+                    changes: None,
+                    failure_screenshot: None,
+                },
+                Err(e) => return Err(e),
+            };
+            let completed = result.status == IntentStatus::Success;
+            results.push((stage.name.as_str(), result));
+            let skipped = &stages[index + 1..];
+            if !completed && !cmd.keep_going && !skipped.is_empty() {
+                let names: Vec<&str> = skipped.iter().map(|s| s.name.as_str()).collect();
+                stopped = Some(format!("Chain stopped; skipped {}", names.join(", ")));
+                break;
+            }
+        }
+
+        // A screenshot is only useful once it is somewhere the user can open.
+        let mut screenshots = Vec::with_capacity(results.len());
+        for (name, result) in &results {
+            screenshots.push(match &result.failure_screenshot {
+                Some(data) => Some(save_failure_screenshot(name, data)?),
+                None => None,
+            });
+        }
+        // Reporting a failure, screenshot or not, does not make it a success.
+        let exit_code = results
+            .iter()
+            .map(|(_, result)| result.status.exit_code())
+            .find(|code| *code != 0)
+            .unwrap_or(0);
+
+        if !cmd.json {
+            let mut outputs: Vec<String> = results
+                .iter()
+                .zip(&screenshots)
+                .map(|((name, result), screenshot)| {
+                    format_intent_result(name, result, screenshot.as_deref())
+                })
+                .collect();
+            outputs.extend(stopped);
+            return Ok((outputs.join("\n"), exit_code));
+        }
+
+        // Longest first, so a secret containing another is masked whole
+        secrets.sort_by_key(|s: &String| std::cmp::Reverse(s.len()));
+        let mut values = Vec::with_capacity(results.len());
+        for ((_, result), screenshot) in results.iter().zip(screenshots) {
+            let mut value = serde_json::to_value(result)?;
+            mask_secrets_in_json(&mut value, &secrets, &self.security.sensitive_fields);
+            if let (Some(path), Value::Object(fields)) = (screenshot, &mut value) {
+                fields.insert("failure_screenshot".into(), Value::String(path));
+            }
+            values.push(value);
+        }
+        let value = if values.len() == 1 {
+            values.remove(0)
+        } else {
+            Value::Array(values)
+        };
+        Ok((serde_json::to_string_pretty(&value)?, exit_code))
+    }
+
+    /// Run one intent, binding parameters missing from its `run` arguments
+    /// from `scope`, and add its parameters and extracted results to `scope`.
+    /// The values of its sensitive parameters are added to `secrets`.
+    async fn run_single_intent<B: Backend + ?Sized>(
+        &mut self,
+        backend: &mut B,
+        cmd: &RunCmd,
+        screenshot_on_error: bool,
+        scope: &mut HashMap<String, Value>,
+        secrets: &mut Vec<String>,
+    ) -> Result<IntentResult, ExecutorError> {
+        let definition = self
+            .registry
+            .get(&cmd.name)
+            .ok_or_else(|| IntentError::IntentNotFound(cmd.name.clone()))?;
+        let params = collect_params(
+            &definition.parameters,
+            &cmd.params,
+            scope,
+            &mut self.prompter,
+            &self.security.sensitive_fields,
+        )
+        .await?;
+        for (name, value) in &params {
+            collect_secrets(name, value, &self.security.sensitive_fields, secrets);
+        }
+
+        let verifier = Verifier::new();
+        let mut executor = IntentExecutor::new(backend, &self.registry, &verifier);
+        executor.set_scoring(self.scoring.clone());
+        executor.set_sensitive_fields(self.security.sensitive_fields.clone());
+        executor.set_safe_mode(self.security.safe_mode);
+        executor.set_screenshot_on_error(screenshot_on_error);
+        for (name, handler) in &self.action_handlers {
+            executor.register_action_handler(name.clone(), handler.clone());
+        }
+        let result = executor.execute(&cmd.name, params.clone()).await?;
+
+        scope.extend(params);
+        if let Some(Value::Object(data)) = &result.data {
+            scope.extend(data.iter().map(|(k, v)| (k.clone(), v.clone())));
+        }
+        Ok(result)
+    }
+
+    /// Run the intent behind a sugar command (`login`, `search`) with the
+    /// parameters it binds. When the page lacks what the intent triggers on,
+    /// say so and how to get there instead of failing mid-step, exiting 1.
+    /// Returns the output and exit code, as `run_intent` does.
+    async fn run_sugar_intent<B: Backend + ?Sized>(
+        &mut self,
+        backend: &mut B,
+        call: IntentCall,
+    ) -> Result<(String, i32), ExecutorError> {
+        let scan = self.rescan(backend).await?;
+        let definition = self
+            .registry
+            .get(&call.name)
+            .ok_or_else(|| IntentError::IntentNotFound(call.name.clone()))?;
+        let availability = IntentMapper::new(&self.registry).availability(definition, &scan);
+        if availability.status != AvailabilityStatus::Ready {
+            return Ok((format_intent_guidance(&availability), 1));
+        }
+
+        let verifier = Verifier::new();
+        let mut executor = IntentExecutor::new(backend, &self.registry, &verifier);
+        executor.set_scoring(self.scoring.clone());
+        executor.set_sensitive_fields(self.security.sensitive_fields.clone());
+        executor.set_safe_mode(self.security.safe_mode);
+        for (name, handler) in &self.action_handlers {
+            executor.register_action_handler(name.clone(), handler.clone());
+        }
+        let result = executor.execute(&call.name, call.params).await?;
+        Ok((
+            format_intent_result(&call.name, &result, None),
+            result.status.exit_code(),
+        ))
+    }
+
+    /// Run an observation scan, collapsing repeated identical elements unless
+    /// full detail or the tree view was requested.
+    async fn execute_observe<B: Backend + ?Sized>(
+        &mut self,
+        backend: &mut B,
+        action: ScannerAction,
+        cmd: &ObserveCmd,
+    ) -> Result<String, ExecutorError> {
+        let mut resp = backend.execute_scanner(action).await?;
+
+        Self::check_scanner_error(&resp)?;
+
+        let previous = match &self.last_scan {
+            Some(scan) if cmd.diff => Some(scan.clone()),
+            _ => None,
+        };
+        // The stored scan keeps every element, so targets still resolve
+        // against duplicates that are collapsed in what is printed.
+        self.update_from_response(&resp);
+
+        let ScannerProtocolResponse::Ok { data, .. } = &mut resp else {
+            return Ok(format_response(&resp));
+        };
+        let ScannerData::Scan(scan) = data.as_mut() else {
+            return Ok(format_response(&resp));
+        };
+        // Collapsing duplicates would drop nodes the tree view needs.
+        let dedup = !cmd.no_dedup && !cmd.tree;
+        if dedup {
+            scan.dedup_elements();
+        }
+        // Diffed against whatever scan came last, whichever command took it,
+        // collapsed the same way so duplicates are not reported as removed.
+        if let Some(mut previous) = previous {
+            if dedup {
+                previous.dedup_elements();
+            }
+            return Ok(format_page_changes(&scan.diff_from(&previous)));
+        }
+        if cmd.tree {
+            return Ok(format_scan_tree(scan, cmd.depth));
+        }
+        if cmd.focus_order {
+            scan.sort_by_focus_order();
+        }
+        Ok(format_response(&resp))
+    }
+
+    /// Take one `observe --monitor` scan and describe what changed, or `None`
+    /// when nothing did. The scanner diffs each monitored scan against the one
+    /// before it, so the first call reports every element as appeared and is
+    /// best taken as the baseline.
+    pub async fn monitor_scan<B: Backend + ?Sized>(
+        &mut self,
+        backend: &mut B,
+        cmd: &ObserveCmd,
+    ) -> Result<Option<String>, ExecutorError> {
+        let cmd = ObserveCmd {
+            monitor: true,
+            ..cmd.clone()
+        };
+        let Action::Scanner(action) = translator::translate(&Command::Observe(cmd))? else {
+            unreachable!("observe translates to a scan");
+        };
+        let resp = backend.execute_scanner(action).await?;
+        Self::check_scanner_error(&resp)?;
+        self.update_from_response(&resp);
+
+        Ok(match &resp {
+            ScannerProtocolResponse::Ok { data, .. } => match data.as_ref() {
+                ScannerData::Scan(scan) => scan
+                    .changes
+                    .as_deref()
+                    .filter(|changes| !changes.is_empty())
+                    .map(|changes| format_changes(changes).trim_end().to_string()),
+                _ => None,
+            },
+            _ => None,
+        })
+    }
+
+    /// Run a text extraction and apply the requested whitespace handling.
+    async fn execute_text<B: Backend + ?Sized>(
+        &mut self,
+        backend: &mut B,
+        action: ScannerAction,
+        mode: TextMode,
+    ) -> Result<String, ExecutorError> {
+        let mut resp = backend.execute_scanner(action).await?;
+
+        Self::check_scanner_error(&resp)?;
+
+        if let ScannerProtocolResponse::Ok { data, .. } = &mut resp
+            && let ScannerData::Value(value) = data.as_mut()
+            && let Some(text) = value.get_mut("text")
+            && let Some(raw) = text.as_str()
+        {
+            *text = Value::String(mode.apply(raw));
+        }
+
+        Ok(format_response(&resp))
+    }
+
+    /// Run an extraction, printing the results as JSON or CSV when asked.
+    /// Tables come back as row objects keyed by header, and links as
+    /// `{text, href}` with each `href` made absolute against the page's URL.
+    async fn execute_extract<B: Backend + ?Sized>(
+        &mut self,
+        backend: &mut B,
+        action: ScannerAction,
+        ext: &ast::ExtractCmd,
+    ) -> Result<String, ExecutorError> {
+        let format = ext.format.as_deref();
+        let source = match &action {
+            ScannerAction::Extract(req) => req.source.clone(),
+            _ => String::new(),
+        };
+        let mut resp = backend.execute_scanner(action).await?;
+
+        Self::check_scanner_error(&resp)?;
+
+        let mut results = match &mut resp {
+            ScannerProtocolResponse::Ok { data, .. } => match data.as_mut() {
+                ScannerData::Value(value) => value.get_mut("results"),
+                _ => None,
+            },
+            _ => None,
+        };
+
+        let is_relative = |link: &Value| {
+            link_href(link).is_some_and(|href| {
+                matches!(
+                    url::Url::parse(href),
+                    Err(url::ParseError::RelativeUrlWithoutBase)
+                )
+            })
+        };
+        if source == "tables"
+            && let Some(Value::Array(tables)) = results.as_deref_mut()
+        {
+            for table in tables.iter_mut() {
+                *table = table_records(table);
+            }
+        }
+        if source == "links"
+            && let Some(Value::Array(items)) = results.as_deref_mut()
+        {
+            let base = if ext.same_origin || items.iter().any(is_relative) {
+                Some(self.current_page_url(backend).await?)
+            } else {
+                None
+            };
+            *items = absolute_links(std::mem::take(items), base.as_ref(), ext.same_origin);
+        }
+
+        match (format, results) {
+            (Some("json"), Some(results)) => Ok(serde_json::to_string_pretty(results)?),
+            (Some("csv"), Some(results)) => Ok(format_extract_csv(results)),
+            _ => Ok(format_response(&resp)),
+        }
+    }
+
+    /// The URL of the page as a fresh scan reports it.
+    async fn current_page_url<B: Backend + ?Sized>(
+        &mut self,
+        backend: &mut B,
+    ) -> Result<url::Url, ExecutorError> {
+        let resp = backend
+            .execute_scanner(ScannerAction::Scan(ScanRequest::default()))
+            .await?;
+        Self::check_scanner_error(&resp)?;
+        self.update_from_response(&resp);
+        let page_url = &self
+            .last_scan
+            .as_ref()
+            .ok_or(ExecutorError::NoScanContext)?
+            .page
+            .url;
+        url::Url::parse(page_url)
+            .map_err(|e| ExecutorError::Scanner(format!("page URL {}: {}", page_url, e)))
+    }
+
+    /// Switch to the frame a `frame` command names, matched against the
+    /// page's frames. Only top-level frames are listed, so `parent` returns
+    /// to the top document like `main`. The last scan belongs to the
+    /// document being left, so it is dropped.
+    async fn switch_frame<B: Backend + ?Sized>(
+        &mut self,
+        backend: &mut B,
+        req: &FrameRequest,
+    ) -> Result<String, ExecutorError> {
+        let selector = match (req.element, &req.selector) {
+            (Some(id), _) => {
+                let scan = self
+                    .last_scan
+                    .as_ref()
+                    .ok_or(ExecutorError::NoScanContext)?;
+                let element = scan.elements.iter().find(|e| e.id == id).ok_or_else(|| {
+                    ExecutorError::Navigation(format!("No element {} in the last scan", id))
+                })?;
+                Some(element.selector.clone())
+            }
+            (None, selector) => selector.clone(),
+        };
+
+        let frame = if let Some(selector) = selector {
+            let mut args = serde_json::Map::new();
+            args.insert("selector".into(), selector.clone().into());
+            let index = backend.execute_script(FRAME_INDEX_SCRIPT, args).await?;
+            let frames = backend.get_frames().await?;
+            let found = index
+                .as_u64()
+                .and_then(|index| frames.into_iter().find(|f| f.index as u64 == index));
+            let Some(frame) = found else {
+                return Err(ExecutorError::Navigation(format!(
+                    "No frame matches \"{}\"; run 'frames' to list them",
+                    selector
+                )));
+            };
+            Some(frame)
+        } else if req.name.is_some() || req.url.is_some() {
+            let frames = backend.get_frames().await?;
+            let found = frames
+                .into_iter()
+                .find(|frame| match (&req.name, &req.url) {
+                    (Some(name), _) => frame.name == *name,
+                    (_, Some(url)) => frame.url.contains(url.as_str()),
+                    _ => false,
+                });
+            let Some(frame) = found else {
+                let wanted = match (&req.name, &req.url) {
+                    (Some(name), _) => format!("named \"{}\"", name),
+                    (_, url) => {
+                        format!("with a URL containing \"{}\"", url.as_deref().unwrap_or(""))
+                    }
+                };
+                return Err(ExecutorError::Navigation(format!(
+                    "No frame {}; run 'frames' to list them",
+                    wanted
+                )));
+            };
+            Some(frame)
+        } else {
+            None
+        };
+
+        backend
+            .switch_frame(frame.as_ref().map(|f| f.index))
+            .await?;
+        self.last_scan = None;
+        Ok(match frame {
+            Some(frame) => format!("Switched to frame {}", describe_frame(&frame)),
+            None => "Switched to main frame".into(),
+        })
+    }
+
+    async fn execute_browser_action<B: Backend + ?Sized>(
+        &mut self,
+        backend: &mut B,
+        action: BrowserAction,
+    ) -> Result<String, ExecutorError> {
+        match action {
+            BrowserAction::Navigate(req) => {
+                let res = match &req.referer {
+                    Some(referer) => backend.navigate_with_referer(&req.url, referer).await,
+                    None => backend.navigate(&req.url).await,
+                }
+                .map_err(|e| ExecutorError::Navigation(e.to_string()))?;
+                self.page_url = Some(res.url.clone());
+                Ok(format!("Navigated to {}", res.url))
+            }
+            BrowserAction::Back(_) => {
+                let res = backend.go_back().await?;
+                self.page_url = Some(res.url.clone());
+                Ok(format!("Navigated back to {}", res.url))
+            }
+            BrowserAction::Forward(_) => {
+                let res = backend.go_forward().await?;
+                self.page_url = Some(res.url.clone());
+                Ok(format!("Navigated forward to {}", res.url))
+            }
+            BrowserAction::Refresh(_) => {
+                let res = backend.refresh().await?;
+                Ok(format!("Refreshed: {}", res.url))
+            }
+            BrowserAction::Screenshot(req) => {
+                // A page that never settles is still captured, as it stands.
+                let mut unsettled = None;
+                if req.wait_stable {
+                    let resp = backend
+                        .execute_scanner(ScannerAction::Wait(WaitRequest {
+                            condition: "stable".into(),
+                            id: None,
+                            selector: None,
+                            text: None,
+                            expression: None,
+                            count: None,
+                            compare: None,
+                            timeout: Some(STABLE_WAIT_TIMEOUT_MS),
+                            poll_interval: None,
+                        }))
+                        .await?;
+                    if let ScannerProtocolResponse::Error { code, message, .. } = resp {
+                        unsettled = Some(format!(
+                            "Warning: the page did not settle before the screenshot ({}: {})\n",
+                            code, message
+                        ));
+                    }
+                }
+                let data = match &req.clip {
+                    Some(clip) => backend.screenshot_clip(clip).await?,
+                    None => backend.screenshot().await?,
+                };
+                let output_path = req.output.unwrap_or_else(|| "screenshot.png".to_string());
+                std::fs::write(&output_path, &data)?;
+                Ok(format!(
+                    "{}Screenshot saved to {} ({} bytes)",
+                    unsettled.unwrap_or_default(),
+                    output_path,
+                    data.len()
+                ))
+            }
+            BrowserAction::Pdf(req) => {
+                let data = backend.pdf(&req.options).await?;
+                std::fs::write(&req.path, &data)?;
+                Ok(format!("PDF saved to {} ({} bytes)", req.path, data.len()))
+            }
+            BrowserAction::Press(req) => {
+                backend.press_key(&req.key, &req.modifiers).await?;
+                if req.modifiers.is_empty() {
+                    Ok(format!("Pressed {}", req.key))
+                } else {
+                    Ok(format!("Pressed {}+{:?}", req.key, req.modifiers))
+                }
+            }
+            BrowserAction::Tab(req) => match req.action.as_str() {
+                "list" => {
+                    let tabs = backend.get_tabs().await?;
+                    if tabs.is_empty() {
+                        Ok("No tabs".into())
+                    } else {
+                        let titles: Vec<String> = tabs.iter().map(|t| t.title.clone()).collect();
+                        Ok(format!("Tabs: {}", titles.join(", ")))
+                    }
+                }
+                _ => Err(ExecutorError::NotImplemented(format!(
+                    "Tab action: {}",
+                    req.action
+                ))),
+            },
+            BrowserAction::Frame(req) => match req.action.as_str() {
+                "list" => Ok(format_frames(&backend.get_frames().await?)),
+                "switch" => self.switch_frame(backend, &req).await,
+                _ => Err(ExecutorError::NotImplemented(format!(
+                    "Frame action: {}",
+                    req.action
+                ))),
+            },
+            BrowserAction::Intercept(InterceptRequest::Add {
+                mut rule,
+                body_file,
+            }) => {
+                if let (Some(path), InterceptAction::Mock { body, .. }) =
+                    (&body_file, &mut rule.action)
+                {
+                    *body = std::fs::read_to_string(path)?;
+                }
+                let summary = describe_intercept(&rule);
+                backend.add_intercept(rule).await?;
+                Ok(summary)
+            }
+            BrowserAction::Intercept(InterceptRequest::Clear { pattern }) => {
+                backend.clear_intercepts(pattern.as_deref()).await?;
+                Ok(match pattern {
+                    Some(pattern) => format!("Cleared intercept rule for {}", pattern),
+                    None => "Cleared all intercept rules".into(),
+                })
+            }
+            BrowserAction::Requests(req) => {
+                Ok(format_requests(&backend.get_requests().await?, &req))
+            }
+            // Dialog -> NotSupported
+            _ => Err(ExecutorError::NotImplemented(format!(
+                "Browser action: {:?}",
+                action
+            ))),
+        }
+    }
+
+    async fn execute_session_action<B: Backend + ?Sized>(
+        &mut self,
+        backend: &mut B,
+        action: SessionAction,
+    ) -> Result<String, ExecutorError> {
+        match action {
+            SessionAction::Cookie(req) => match req.action.as_str() {
+                "list" => {
+                    let cookies = backend.get_cookies().await?;
+                    if cookies.is_empty() {
+                        Ok("No cookies".into())
+                    } else {
+                        let names: Vec<String> = cookies.iter().map(|c| c.name.clone()).collect();
+                        Ok(format!("Cookies: {}", names.join(", ")))
+                    }
+                }
+                "get" => {
+                    let name = req.name.unwrap_or_default();
+                    let cookies = backend.get_cookies().await?;
+                    let cookie = cookies.into_iter().find(|c| c.name == name);
+                    if req.json {
+                        return Ok(serde_json::to_string_pretty(&cookie)?);
+                    }
+                    match cookie {
+                        Some(c) => Ok(format!("Cookie: {}={}", c.name, c.value)),
+                        None => Ok(format!("Cookie {} not found", name)),
+                    }
+                }
+                "get_all" => {
+                    let cookies = backend.get_all_cookies().await?;
+                    if req.json {
+                        Ok(serde_json::to_string_pretty(&cookies)?)
+                    } else if cookies.is_empty() {
+                        Ok("No cookies".into())
+                    } else {
+                        Ok(cookies
+                            .iter()
+                            .map(|c| match &c.domain {
+                                Some(domain) => format!("{}={} ({})", c.name, c.value, domain),
+                                None => format!("{}={}", c.name, c.value),
+                            })
+                            .collect::<Vec<_>>()
+                            .join("\n"))
+                    }
+                }
+                "set" => {
+                    let c = Self::cookie_with_defaults(
+                        req.name.unwrap_or_default(),
                         req.value.unwrap_or_default(),
                         req.domain,
                         None,
@@ -353,11 +2625,56 @@ impl CommandExecutor {
                     backend.set_cookie(c).await?;
                     Ok(format!("Cookie {} deleted", name))
                 }
+                "clear" => {
+                    let cookies = backend.get_cookies().await?;
+                    let count = cookies.len();
+                    for c in cookies {
+                        backend
+                            .set_cookie(Cookie {
+                                value: String::new(),
+                                expires: Some(0.0),
+                                ..c
+                            })
+                            .await?;
+                    }
+                    Ok(format!("Cleared {} cookies", count))
+                }
+                "export" => {
+                    let path = req.file.unwrap_or_default();
+                    let format = CookieFormat::detect(&path, req.format.as_deref())?;
+                    let cookies = backend.get_all_cookies().await?;
+                    std::fs::write(&path, write_cookies(&cookies, format)?)?;
+                    Ok(format!("Exported {} cookies to {}", cookies.len(), path))
+                }
+                "import" => {
+                    let path = req.file.unwrap_or_default();
+                    let format = CookieFormat::detect(&path, req.format.as_deref())?;
+                    let cookies = read_cookies(&std::fs::read_to_string(&path)?, format)?;
+                    let count = cookies.len();
+                    for c in cookies {
+                        backend
+                            .set_cookie(Cookie {
+                                path: c.path.or_else(|| Some("/".into())),
+                                ..c
+                            })
+                            .await?;
+                    }
+                    Ok(format!("Imported {} cookies from {}", count, path))
+                }
                 _ => Err(ExecutorError::NotImplemented(format!(
                     "Cookie action: {}",
                     req.action
                 ))),
             },
+            SessionAction::Storage(req) if req.action == "clear" => {
+                let script = match req.storage_type.as_str() {
+                    "local" => "localStorage.clear()",
+                    "session" => "sessionStorage.clear()",
+                    _ => "localStorage.clear(); sessionStorage.clear()",
+                };
+                backend.execute_script(script, Default::default()).await?;
+                Ok("Storage cleared".into())
+            }
             _ => Err(ExecutorError::NotImplemented(format!(
                 "Session action: {:?}",
                 action
@@ -366,10 +2683,476 @@ impl CommandExecutor {
     }
 
     fn update_from_response(&mut self, resp: &ScannerProtocolResponse) {
-        if let ScannerProtocolResponse::Ok { data, .. } = resp
-            && let ScannerData::Scan(result) = data.as_ref()
+        let ScannerProtocolResponse::Ok { data, .. } = resp else {
+            return;
+        };
+        match data.as_ref() {
+            ScannerData::Scan(result) => {
+                self.last_scan = Some(*result.clone());
+                self.scan_generation += 1;
+                self.last_scan_at = Some(Instant::now());
+                self.page_url = Some(result.page.url.clone());
+                self.navigated_since_scan = false;
+            }
+            ScannerData::Action(result) if result.navigation == Some(true) => {
+                self.navigated_since_scan = true;
+            }
+            _ => {}
+        }
+    }
+}
+
+/// How many passes `repeat` runs in all: its own count times the most any
+/// block nested in it runs per pass.
+fn repeat_passes(repeat: &ast::RepeatCmd) -> usize {
+    let nested = repeat
+        .body
+        .iter()
+        .filter_map(|cmd| match cmd {
+            Command::Repeat(inner) => Some(repeat_passes(inner)),
+            _ => None,
+        })
+        .max()
+        .unwrap_or(1);
+    repeat.count.saturating_mul(nested)
+}
+
+/// A link's `href`, or its `url` as older scanners name it.
+fn link_href(link: &Value) -> Option<&str> {
+    link.get("href")
+        .or_else(|| link.get("url"))
+        .and_then(Value::as_str)
+}
+
+/// Rewrite extracted links as `{text, href}` (plus any other fields the
+/// scanner sent) with `href` absolute against `base`, keeping the first of
+/// links to the same address. With `same_origin`, links off `base`'s origin
+/// are dropped, as are those that cannot be resolved.
+fn absolute_links(links: Vec<Value>, base: Option<&url::Url>, same_origin: bool) -> Vec<Value> {
+    let mut seen = HashSet::new();
+    let mut kept = Vec::with_capacity(links.len());
+    for mut link in links {
+        let Some(raw) = link_href(&link) else {
+            continue;
+        };
+        let resolved = match base {
+            Some(base) => base.join(raw),
+            None => url::Url::parse(raw),
+        };
+        if same_origin
+            && !matches!((&resolved, base), (Ok(url), Some(base)) if url.origin() == base.origin())
+        {
+            continue;
+        }
+        let href = resolved
+            .map(String::from)
+            .unwrap_or_else(|_| raw.to_string());
+        if !seen.insert(href.clone()) {
+            continue;
+        }
+        if let Value::Object(fields) = &mut link {
+            fields.remove("url");
+            fields.insert("href".into(), Value::String(href));
+        }
+        kept.push(link);
+    }
+    kept
+}
+
+/// Actions a dry run still performs: they change where the script is or
+/// what it can see, never the page itself.
+/// A dropdown built from divs rather than a native `<select>`: an ARIA
+/// combobox or listbox, or anything announcing a listbox popup.
+fn is_custom_dropdown(el: &Element) -> bool {
+    if el.element_type == "select" {
+        return false;
+    }
+    matches!(
+        el.attributes.get("role").map(String::as_str),
+        Some("combobox" | "listbox")
+    ) || matches!(
+        el.attributes.get("aria-haspopup").map(String::as_str),
+        Some("listbox" | "true")
+    )
+}
+
+/// A text box that filters a dropdown's options as it is typed into.
+fn is_search_box(el: &Element) -> bool {
+    if el.state.hidden || matches!(el.role.as_deref(), Some("checkbox" | "radio")) {
+        return false;
+    }
+    el.element_type == "input"
+        || (el.attributes.contains_key("aria-autocomplete")
+            && matches!(
+                el.attributes.get("role").map(String::as_str),
+                Some("combobox" | "searchbox")
+            ))
+}
+
+fn plain_click(id: Option<u32>, selector: Option<String>) -> ClickRequest {
+    ClickRequest {
+        id,
+        selector,
+        button: Default::default(),
+        double: false,
+        modifiers: vec![],
+        force: false,
+    }
+}
+
+fn is_option(el: &Element) -> bool {
+    matches!(
+        el.attributes.get("role").map(String::as_str),
+        Some("option" | "menuitem")
+    )
+}
+
+fn runs_in_dry_run(action: &Action) -> bool {
+    matches!(
+        action,
+        Action::Scanner(ScannerAction::Scan(_))
+            | Action::Browser(
+                BrowserAction::Navigate(_)
+                    | BrowserAction::Back(_)
+                    | BrowserAction::Forward(_)
+                    | BrowserAction::Refresh(_)
+            )
+    )
+}
+
+/// Grow a viewport-relative element box by `padding` on every side and move
+/// it into page coordinates, keeping it within the page.
+fn padded_clip(rect: &Rect, padding: f32, page: &PageInfo) -> Rect {
+    let left = (rect.x + page.scroll.x as f32 - padding).max(0.0);
+    let top = (rect.y + page.scroll.y as f32 - padding).max(0.0);
+    let mut right = rect.x + page.scroll.x as f32 + rect.width + padding;
+    let mut bottom = rect.y + page.scroll.y as f32 + rect.height + padding;
+    // Scans from pages that report no viewport are left unclamped.
+    if page.viewport.width > 0 {
+        right = right.min((page.viewport.width + page.scroll.max_x) as f32);
+    }
+    if page.viewport.height > 0 {
+        bottom = bottom.min((page.viewport.height + page.scroll.max_y) as f32);
+    }
+    Rect {
+        x: left,
+        y: top,
+        width: (right - left).max(0.0),
+        height: (bottom - top).max(0.0),
+    }
+}
+
+/// The part of `cmd` that runs caller-supplied script, if any.
+fn raw_script_use(cmd: &Command) -> Option<&'static str> {
+    fn runs_expression(condition: &ast::WaitCondition) -> bool {
+        match condition {
+            ast::WaitCondition::Until(_) => true,
+            ast::WaitCondition::All(conditions) | ast::WaitCondition::Any(conditions) => {
+                conditions.iter().any(runs_expression)
+            }
+            _ => false,
+        }
+    }
+    match cmd {
+        Command::Eval(_) => Some("eval"),
+        Command::Set(_) => Some("set"),
+        Command::Wait(wait) if runs_expression(&wait.condition) => Some("wait until <expression>"),
+        Command::Goto(goto) if goto.until.as_ref().is_some_and(runs_expression) => {
+            Some("goto --until <expression>")
+        }
+        _ => None,
+    }
+}
+
+/// Render a target the way it was written, for outputs and timeouts.
+fn describe_wait_target(target: &ast::Target) -> String {
+    match &target.atomic {
+        TargetAtomic::Id(id) => id.to_string(),
+        TargetAtomic::Text(text) => format!("\"{}\"", text),
+        TargetAtomic::TextMatch(m) => m.to_string(),
+        TargetAtomic::Selector { kind, value } => format!("{}(\"{}\")", kind, value),
+        TargetAtomic::Role(role) => role.clone(),
+        TargetAtomic::TestId(test_id) => format!("testid \"{}\"", test_id),
+        TargetAtomic::RoleText { role, text } => format!("{} \"{}\"", role, text),
+    }
+}
+
+/// Render a wait condition the way it was written, for group outputs and
+/// timeouts.
+fn describe_wait_condition(condition: &ast::WaitCondition) -> String {
+    let group = |name: &str, conditions: &[ast::WaitCondition]| {
+        let described: Vec<_> = conditions.iter().map(describe_wait_condition).collect();
+        format!("{} ({})", name, described.join(", "))
+    };
+    match condition {
+        ast::WaitCondition::Load => "load".into(),
+        ast::WaitCondition::Idle => "idle".into(),
+        ast::WaitCondition::Navigation => "navigation".into(),
+        ast::WaitCondition::Ready => "ready".into(),
+        ast::WaitCondition::Visible(target) => format!("visible {}", describe_wait_target(target)),
+        ast::WaitCondition::Hidden(target) => format!("hidden {}", describe_wait_target(target)),
+        ast::WaitCondition::AnyOf(targets) => {
+            let described: Vec<_> = targets.iter().map(describe_wait_target).collect();
+            format!("any-of [{}]", described.join(", "))
+        }
+        ast::WaitCondition::All(conditions) => group("all", conditions),
+        ast::WaitCondition::Any(conditions) => group("any", conditions),
+        ast::WaitCondition::Exists(selector) => format!("exists \"{}\"", selector),
+        ast::WaitCondition::Gone(selector) => format!("gone \"{}\"", selector),
+        ast::WaitCondition::Url(pattern) => format!("url \"{}\"", pattern),
+        ast::WaitCondition::Until(expression) => format!("until \"{}\"", expression),
+        ast::WaitCondition::Items(items) => match items.op {
+            ast::CountComparison::AtLeast => {
+                format!("items \"{}\" {}", items.selector, items.count)
+            }
+            op => format!(
+                "items \"{}\" {} {}",
+                items.selector,
+                op.symbol(),
+                items.count
+            ),
+        },
+        ast::WaitCondition::TextOf {
+            target,
+            comparison,
+            expected,
+        } => format!(
+            "until text of {} {} \"{}\"",
+            describe_wait_target(target),
+            match comparison {
+                ast::TextComparison::Equals => "==",
+                ast::TextComparison::Contains => "contains",
+            },
+            expected
+        ),
+    }
+}
+
+/// Bind `run` arguments to an intent's parameters, then values `inherited`
+/// from earlier intents in a chain, prompting for any required ones that are
+/// still missing when a prompter is available.
+async fn collect_params(
+    defs: &[ParameterDef],
+    args: &[(String, String)],
+    inherited: &HashMap<String, Value>,
+    prompter: &mut Option<Box<dyn ParamPrompter>>,
+    sensitive_fields: &[String],
+) -> Result<HashMap<String, Value>, IntentError> {
+    let mut params = HashMap::new();
+    let mut positional = Vec::new();
+
+    for (name, raw) in args {
+        if name.is_empty() {
+            positional.push(raw);
+            continue;
+        }
+        let value = match defs.iter().find(|d| &d.name == name) {
+            Some(def) => parse_param(def, raw)?,
+            None => Value::String(raw.clone()),
+        };
+        params.insert(name.clone(), value);
+    }
+
+    let unbound: Vec<&ParameterDef> = defs
+        .iter()
+        .filter(|d| !params.contains_key(&d.name))
+        .collect();
+    let mut unbound = unbound.into_iter();
+    for raw in positional {
+        let def = unbound
+            .next()
+            .ok_or_else(|| IntentError::UnexpectedArgument(raw.clone()))?;
+        params.insert(def.name.clone(), parse_param(def, raw)?);
+    }
+    for def in defs {
+        if !params.contains_key(&def.name)
+            && let Some(value) = inherited.get(&def.name)
         {
-            self.last_scan = Some(*result.clone());
+            params.insert(def.name.clone(), value.clone());
+        }
+    }
+
+    let Some(prompter) = prompter.as_mut() else {
+        return Ok(params);
+    };
+    let missing: Vec<&ParameterDef> = defs
+        .iter()
+        .filter(|d| d.required && d.default.is_none() && !params.contains_key(&d.name))
+        .collect();
+    for def in missing {
+        let sensitive = is_sensitive_field(&def.name, sensitive_fields);
+        let mut attempts = 0;
+        let value = loop {
+            let raw = prompter
+                .prompt(def, sensitive)
+                .await
+                .ok_or_else(|| IntentError::MissingParameter(def.name.clone()))?;
+            attempts += 1;
+            match def.parse_value(&raw) {
+                Ok(value) => break value,
+                Err(reason) if attempts < MAX_PROMPT_ATTEMPTS => prompter.reject(def, &reason),
+                Err(reason) => {
+                    return Err(IntentError::InvalidParameterValue {
+                        name: def.name.clone(),
+                        reason,
+                    });
+                }
+            }
+        };
+        params.insert(def.name.clone(), value);
+    }
+    Ok(params)
+}
+
+fn parse_param(def: &ParameterDef, raw: &str) -> Result<Value, IntentError> {
+    def.parse_value(raw)
+        .map_err(|reason| IntentError::InvalidParameterValue {
+            name: def.name.clone(),
+            reason,
+        })
+}
+
+/// Mask `secrets` wherever they appear in the strings of `value`, and the
+/// whole value of any field with a sensitive name.
+fn mask_secrets_in_json(value: &mut Value, secrets: &[String], sensitive_fields: &[String]) {
+    match value {
+        Value::Object(map) => {
+            for (key, nested) in map.iter_mut() {
+                if is_sensitive_field(key, sensitive_fields) && !nested.is_null() {
+                    *nested = Value::String("********".into());
+                } else {
+                    mask_secrets_in_json(nested, secrets, sensitive_fields);
+                }
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                mask_secrets_in_json(item, secrets, sensitive_fields);
+            }
+        }
+        Value::String(s) => {
+            for secret in secrets {
+                *s = s.replace(secret.as_str(), "********");
+            }
         }
+        _ => {}
+    }
+}
+
+/// One line per frame: its index, name and URL.
+fn format_frames(frames: &[FrameInfo]) -> String {
+    if frames.is_empty() {
+        return "No frames".into();
+    }
+    let mut output = format!("Frames ({}):", frames.len());
+    for frame in frames {
+        output.push_str(&format!("\n  {}", describe_frame(frame)));
+    }
+    output
+}
+
+fn describe_frame(frame: &FrameInfo) -> String {
+    let name = if frame.name.is_empty() {
+        "(unnamed)"
+    } else {
+        &frame.name
+    };
+    format!("[{}] {} {}", frame.index, name, frame.url)
+}
+
+fn describe_intercept(rule: &InterceptRule) -> String {
+    match &rule.action {
+        InterceptAction::Block => format!("Blocking requests to {}", rule.url_pattern),
+        InterceptAction::Mock { status, body, .. } => format!(
+            "Mocking requests to {} with {} ({} bytes)",
+            rule.url_pattern,
+            status,
+            body.len()
+        ),
+        InterceptAction::Continue => format!("Recording requests to {}", rule.url_pattern),
+    }
+}
+
+/// How many requests `requests` lists when `--last` is not given.
+const DEFAULT_REQUESTS_SHOWN: usize = 50;
+
+/// List recorded requests, newest last, numbered in the order they were made.
+fn format_requests(requests: &[NetworkRequest], req: &RequestsRequest) -> String {
+    let matching: Vec<(usize, &NetworkRequest)> = requests
+        .iter()
+        .enumerate()
+        .filter(|(_, r)| {
+            req.filter
+                .as_ref()
+                .is_none_or(|f| r.url.contains(f.as_str()))
+                && req
+                    .method
+                    .as_ref()
+                    .is_none_or(|m| r.method.eq_ignore_ascii_case(m))
+        })
+        .collect();
+    if matching.is_empty() {
+        return "No requests".into();
+    }
+
+    let shown = req.last.unwrap_or(DEFAULT_REQUESTS_SHOWN);
+    let skip = matching.len().saturating_sub(shown);
+    let mut output = format!(
+        "Requests ({} of {}):",
+        matching.len() - skip,
+        matching.len()
+    );
+    for (index, request) in &matching[skip..] {
+        let outcome = match (request.intercepted, request.status) {
+            (Some(Interception::Blocked), _) => "BLOCKED".to_string(),
+            (Some(Interception::Mocked), Some(status)) => format!("{} (mocked)", status),
+            (Some(Interception::Mocked), None) => "mocked".to_string(),
+            (None, Some(status)) => status.to_string(),
+            (None, None) => "pending".to_string(),
+        };
+        output.push_str(&format!(
+            "\n{}. {} {} \u{2192} {}",
+            index + 1,
+            request.method,
+            request.url,
+            outcome
+        ));
+    }
+    output
+}
+
+/// Write a failed run's screenshot to the temp directory, returning its path.
+fn save_failure_screenshot(name: &str, data: &[u8]) -> Result<String, ExecutorError> {
+    let millis = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or_default();
+    let path = std::env::temp_dir().join(format!("oryn-{}-failure-{}.png", name, millis));
+    std::fs::write(&path, data)?;
+    Ok(path.display().to_string())
+}
+
+fn format_intent_result(name: &str, result: &IntentResult, screenshot: Option<&str>) -> String {
+    let mut output = match &result.status {
+        IntentStatus::Success => format!("Intent '{}' completed", name),
+        IntentStatus::PartialSuccess { completed, total } => format!(
+            "Intent '{}' partially completed ({}/{} steps)",
+            name, completed, total
+        ),
+        IntentStatus::Failed(reason) => format!("Intent '{}' failed: {}", name, reason),
+    };
+    for hint in &result.hints {
+        output.push_str(&format!("\nHint: {}", hint));
+    }
+    if let Some(data) = &result.data {
+        output.push_str(&format!("\n{}", data));
+    }
+    if let (Some(path), Some(data)) = (screenshot, &result.failure_screenshot) {
+        output.push_str(&format!(
+            "\nFailure screenshot saved to {} ({} bytes)",
+            path,
+            data.len()
+        ));
     }
+    output
 }
@@ -19,15 +19,19 @@ pub fn definition() -> IntentDefinition {
                 required: false,
                 default: Some(json!(false)),
                 description: "Click reject instead of accept".to_string(),
+                allowed: vec![],
             },
         ],
+        pre_conditions: vec![],
         steps: vec![
             Step::Branch(BranchStepWrapper {
                 branch: BranchDef {
                     condition: Condition::Expression("$reject".to_string()),
                     then_steps: vec![
                         Step::Action(ActionStep {
-                            action: ActionType::Click, on_error: None,
+                            timeout_ms: None,
+                            retry: None,
+                            action: ActionType::Click, on_error: None, on_success: None,
                             target: Some(TargetSpec {
                                 kind: TargetKind::Pattern { pattern: "cookie_banner.reject".to_string() },
                                 fallback: Some(Box::new(TargetSpec {
@@ -40,7 +44,9 @@ pub fn definition() -> IntentDefinition {
                     ],
                     else_steps: vec![
                         Step::Action(ActionStep {
-                            action: ActionType::Click, on_error: None,
+                            timeout_ms: None,
+                            retry: None,
+                            action: ActionType::Click, on_error: None, on_success: None,
                             target: Some(TargetSpec {
                                 kind: TargetKind::Pattern { pattern: "cookie_banner.accept".to_string() },
                                 fallback: Some(Box::new(TargetSpec {
@@ -61,7 +67,9 @@ pub fn definition() -> IntentDefinition {
                 }
             }),
             Step::Action(ActionStep {
-                action: ActionType::Wait, on_error: None,
+                timeout_ms: None,
+                retry: None,
+                action: ActionType::Wait, on_error: None, on_success: None,
                 target: None,
                 options: [
                     ("condition".to_string(), json!({ "hidden": { "pattern": "cookie_banner" } })),
@@ -8,11 +8,7 @@ pub fn definition() -> IntentDefinition {
         version: "1.0.0".to_string(),
         tier: IntentTier::BuiltIn,
         triggers: IntentTriggers {
-            patterns: vec![
-                "modal_dialog".to_string(),
-                "overlay".to_string(),
-                "popup".to_string(),
-            ],
+            patterns: vec!["modal".to_string()],
             keywords: vec![
                 "popup".to_string(),
                 "modal".to_string(),
@@ -26,7 +22,9 @@ pub fn definition() -> IntentDefinition {
             required: false,
             default: Some(json!(true)),
             description: "Dismiss all detected popups".to_string(),
+            allowed: vec![],
         }],
+        pre_conditions: vec![],
         steps: vec![Step::Loop(LoopStepWrapper {
             loop_: LoopDef {
                 over: "visible_patterns('modal_dialog', 'overlay', 'popup', 'cookie_banner')"
@@ -36,8 +34,11 @@ pub fn definition() -> IntentDefinition {
                     try_: TryDef {
                         steps: vec![
                             Step::Action(ActionStep {
+                                timeout_ms: None,
+                                retry: None,
                                 action: ActionType::Click,
                                 on_error: None,
+                                on_success: None,
                                 target: Some(TargetSpec {
                                     kind: TargetKind::Selector {
                                         selector: "$popup.close".to_string(),
@@ -58,8 +59,8 @@ pub fn definition() -> IntentDefinition {
                                                 },
                                                 fallback: Some(Box::new(TargetSpec {
                                                     kind: TargetKind::Text {
-                                                         text: "Dismiss".to_string(),
-                                                         match_type: MatchType::Contains,
+                                                        text: "Dismiss".to_string(),
+                                                        match_type: MatchType::Contains,
                                                     },
                                                     fallback: Some(Box::new(TargetSpec {
                                                         kind: TargetKind::Text {
@@ -76,8 +77,11 @@ pub fn definition() -> IntentDefinition {
                                 options: Default::default(),
                             }),
                             Step::Action(ActionStep {
+                                timeout_ms: None,
+                                retry: None,
                                 action: ActionType::Wait,
                                 on_error: None,
+                                on_success: None,
                                 target: None,
                                 options: [(
                                     "condition".to_string(),
@@ -19,6 +19,7 @@ pub fn definition() -> IntentDefinition {
                 required: true,
                 default: None,
                 description: "Data to fill".to_string(),
+                allowed: vec![],
             },
             ParameterDef {
                 name: "pattern".to_string(),
@@ -26,11 +27,16 @@ pub fn definition() -> IntentDefinition {
                 required: false,
                 default: None,
                 description: "Form pattern to target".to_string(),
+                allowed: vec![],
             },
         ],
+        pre_conditions: vec![],
         steps: vec![Step::Action(ActionStep {
+            timeout_ms: None,
+            retry: None,
             action: ActionType::FillForm,
             on_error: None,
+            on_success: None,
             target: Some(TargetSpec {
                 kind: TargetKind::Pattern {
                     pattern: "$pattern".to_string(),
@@ -24,6 +24,7 @@ pub fn definition() -> IntentDefinition {
                 required: true,
                 default: None,
                 description: "Username or email address".to_string(),
+                allowed: vec![],
             },
             ParameterDef {
                 name: "password".to_string(),
@@ -31,6 +32,7 @@ pub fn definition() -> IntentDefinition {
                 required: true,
                 default: None,
                 description: "Password".to_string(),
+                allowed: vec![],
             },
             ParameterDef {
                 name: "wait".to_string(),
@@ -38,12 +40,17 @@ pub fn definition() -> IntentDefinition {
                 required: false,
                 default: Some(json!(10000)),
                 description: "Time to wait for navigation (ms)".to_string(),
+                allowed: vec![],
             },
         ],
+        pre_conditions: vec![],
         steps: vec![
             Step::Action(ActionStep {
+                timeout_ms: None,
+                retry: None,
                 action: ActionType::Type,
                 on_error: None,
+                on_success: None,
                 target: Some(TargetSpec {
                     kind: TargetKind::Pattern {
                         pattern: "login_form.username".to_string(),
@@ -63,8 +70,11 @@ pub fn definition() -> IntentDefinition {
                 options: [("text".to_string(), json!("$username"))].into(),
             }),
             Step::Action(ActionStep {
+                timeout_ms: None,
+                retry: None,
                 action: ActionType::Type,
                 on_error: None,
+                on_success: None,
                 target: Some(TargetSpec {
                     kind: TargetKind::Pattern {
                         pattern: "login_form.password".to_string(),
@@ -84,8 +94,11 @@ pub fn definition() -> IntentDefinition {
                 options: [("text".to_string(), json!("$password"))].into(),
             }),
             Step::Action(ActionStep {
+                timeout_ms: None,
+                retry: None,
                 action: ActionType::Click,
                 on_error: None,
+                on_success: None,
                 target: Some(TargetSpec {
                     kind: TargetKind::Pattern {
                         pattern: "login_form.submit".to_string(),
@@ -105,8 +118,11 @@ pub fn definition() -> IntentDefinition {
                 options: Default::default(),
             }),
             Step::Action(ActionStep {
+                timeout_ms: None,
+                retry: None,
                 action: ActionType::Wait,
                 on_error: None,
+                on_success: None,
                 target: None,
                 options: [
                     ("condition".to_string(), json!({ "url_matches": ".*" })), // Simple wait for now, logic handled in engine
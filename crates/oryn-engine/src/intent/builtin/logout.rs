@@ -22,15 +22,20 @@ pub fn definition() -> IntentDefinition {
             required: false,
             default: Some(json!(5000)),
             description: "Time to wait (ms)".to_string(),
+            allowed: vec![],
         }],
+        pre_conditions: vec![],
         steps: vec![
             Step::Try(TryStepWrapper {
                 try_: TryDef {
                     steps: vec![
                         // Try clicking logout directly
                         Step::Action(ActionStep {
+                            timeout_ms: None,
+                            retry: None,
                             action: ActionType::Click,
                             on_error: None,
+                            on_success: None,
                             target: Some(TargetSpec {
                                 kind: TargetKind::Pattern {
                                     pattern: "logout_button".to_string(),
@@ -55,8 +60,11 @@ pub fn definition() -> IntentDefinition {
                     catch: vec![
                         // Try opening user menu first
                         Step::Action(ActionStep {
+                            timeout_ms: None,
+                            retry: None,
                             action: ActionType::Click,
                             on_error: None,
+                            on_success: None,
                             target: Some(TargetSpec {
                                 kind: TargetKind::Pattern {
                                     pattern: "user_menu".to_string(),
@@ -71,8 +79,11 @@ pub fn definition() -> IntentDefinition {
                             options: Default::default(),
                         }),
                         Step::Action(ActionStep {
+                            timeout_ms: None,
+                            retry: None,
                             action: ActionType::Click,
                             on_error: None,
+                            on_success: None,
                             target: Some(TargetSpec {
                                 kind: TargetKind::Text {
                                     text: "Sign out".to_string(),
@@ -92,8 +103,11 @@ pub fn definition() -> IntentDefinition {
                 },
             }),
             Step::Action(ActionStep {
+                timeout_ms: None,
+                retry: None,
                 action: ActionType::Wait,
                 on_error: None,
+                on_success: None,
                 target: None,
                 options: [
                     ("condition".to_string(), json!({ "url_matches": ".*" })),
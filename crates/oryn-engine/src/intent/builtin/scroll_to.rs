@@ -17,10 +17,15 @@ pub fn definition() -> IntentDefinition {
             required: false,
             default: None,
             description: "Target to scroll to".to_string(),
+            allowed: vec![],
         }],
+        pre_conditions: vec![],
         steps: vec![Step::Action(ActionStep {
+            timeout_ms: None,
+            retry: None,
             action: ActionType::Scroll,
             on_error: None,
+            on_success: None,
             target: Some(TargetSpec {
                 kind: TargetKind::Selector {
                     selector: "$target".to_string(),
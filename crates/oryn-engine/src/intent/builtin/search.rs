@@ -8,7 +8,7 @@ pub fn definition() -> IntentDefinition {
         version: "1.0.0".to_string(),
         tier: IntentTier::BuiltIn,
         triggers: IntentTriggers {
-            patterns: vec!["search_form".to_string()],
+            patterns: vec!["search_box".to_string()],
             keywords: vec!["search".to_string(), "find".to_string()],
             urls: vec![],
         },
@@ -19,6 +19,7 @@ pub fn definition() -> IntentDefinition {
                 required: true,
                 default: None,
                 description: "Search terms".to_string(),
+                allowed: vec![],
             },
             ParameterDef {
                 name: "wait".to_string(),
@@ -26,12 +27,17 @@ pub fn definition() -> IntentDefinition {
                 required: false,
                 default: Some(json!(5000)),
                 description: "Time to wait for results (ms)".to_string(),
+                allowed: vec![],
             },
         ],
+        pre_conditions: vec![],
         steps: vec![
             Step::Action(ActionStep {
+                timeout_ms: None,
+                retry: None,
                 action: ActionType::Clear,
                 on_error: None,
+                on_success: None,
                 target: Some(TargetSpec {
                     kind: TargetKind::Pattern {
                         pattern: "search_form.input".to_string(),
@@ -53,8 +59,11 @@ pub fn definition() -> IntentDefinition {
                 options: Default::default(),
             }),
             Step::Action(ActionStep {
+                timeout_ms: None,
+                retry: None,
                 action: ActionType::Type,
                 on_error: None,
+                on_success: None,
                 target: Some(TargetSpec {
                     kind: TargetKind::Pattern {
                         pattern: "search_form.input".to_string(),
@@ -80,8 +89,11 @@ pub fn definition() -> IntentDefinition {
                 .into(),
             }),
             Step::Action(ActionStep {
+                timeout_ms: None,
+                retry: None,
                 action: ActionType::Wait,
                 on_error: None,
+                on_success: None,
                 target: None,
                 options: [
                     ("condition".to_string(), json!({ "url_matches": ".*" })),
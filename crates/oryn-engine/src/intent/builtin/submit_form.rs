@@ -19,6 +19,7 @@ pub fn definition() -> IntentDefinition {
                 required: false,
                 default: None,
                 description: "Form pattern to target".to_string(),
+                allowed: vec![],
             },
             ParameterDef {
                 name: "wait".to_string(),
@@ -26,12 +27,17 @@ pub fn definition() -> IntentDefinition {
                 required: false,
                 default: Some(json!(10000)),
                 description: "Time to wait (ms)".to_string(),
+                allowed: vec![],
             },
         ],
+        pre_conditions: vec![],
         steps: vec![
             Step::Action(ActionStep {
+                timeout_ms: None,
+                retry: None,
                 action: ActionType::Click,
                 on_error: None,
+                on_success: None,
                 target: Some(TargetSpec {
                     kind: TargetKind::Pattern {
                         pattern: "$pattern.submit".to_string(),
@@ -51,8 +57,11 @@ pub fn definition() -> IntentDefinition {
                 options: Default::default(),
             }),
             Step::Action(ActionStep {
+                timeout_ms: None,
+                retry: None,
                 action: ActionType::Wait,
                 on_error: None,
+                on_success: None,
                 target: None,
                 options: [
                     ("condition".to_string(), json!({ "url_matches": ".*" })),
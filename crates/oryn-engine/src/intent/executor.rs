@@ -1,22 +1,35 @@
 use crate::backend::{Backend, BackendError};
+use crate::intent::handler::{ActionContext, ActionHandler};
+use crate::intent::mapper::IntentMapper;
 use async_recursion::async_recursion;
-use oryn_common::command::{Command, Target};
+use futures::future::join_all;
+use futures::lock::Mutex;
+use oryn_common::formatter::is_sensitive_field;
 use oryn_common::intent::definition::{
-    ActionStep, ActionType, Condition, FlowDefinition, IntentDefinition, PageAction, PageDef, Step,
-    TargetKind, TargetSpec,
+    ActionStep, ActionType, Condition, FlowDefinition, IntentDefinition, IntentOptions, PageAction,
+    PageDef, ParameterDef, RetryConfig, Step, TargetKind, TargetSpec,
 };
 use oryn_common::intent::registry::IntentRegistry;
 use oryn_common::intent::verifier::{Verifier, VerifierContext};
 use oryn_common::protocol::{
-    ChangeType, PageChanges, ScanRequest, ScanResult, ScannerData, ScannerProtocolResponse,
-    ScannerAction,
+    Action, ChangeType, PageChanges, ScanRequest, ScanResult, ScannerAction, ScannerData,
+    ScannerProtocolResponse,
 };
-use oryn_common::resolver::{ResolutionStrategy, ResolverContext, resolve_target};
-use oryn_common::translator;
+use oryn_common::resolver::{
+    ResolutionStrategy, ResolverContext, ScoringConfig, Target, resolve_target,
+};
+use oryn_core::ast::{self, Command, WaitCondition};
+use oryn_core::translator;
 use regex::Regex;
 use serde::{Deserialize, Serialize};
 use serde_json::{Value, json};
 use std::collections::HashMap;
+use std::collections::hash_map::RandomState;
+use std::future::Future;
+use std::hash::{BuildHasher, Hasher};
+use std::ops::Deref;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 #[derive(Debug, thiserror::Error)]
 pub enum ExecutorError {
@@ -26,6 +39,10 @@ pub enum ExecutorError {
     MissingParameter(String),
     #[error("Invalid parameter type for {0}")]
     InvalidParameterType(String),
+    #[error("Invalid value for parameter {name}: {reason}")]
+    InvalidParameterValue { name: String, reason: String },
+    #[error("Unexpected argument: {0}")]
+    UnexpectedArgument(String),
     #[error("Backend error: {0}")]
     Backend(#[from] BackendError),
     #[error("Translation error: {0}")]
@@ -36,6 +53,10 @@ pub enum ExecutorError {
     Verification(#[from] oryn_common::intent::verifier::VerificationError),
     #[error("Step execution failed: {0}")]
     StepFailed(String),
+    /// A step that ran out of time, which unlike other step failures may
+    /// succeed on another attempt.
+    #[error("Step execution failed: {0}")]
+    StepTimedOut(String),
     #[error("Intent failed: {0}")]
     IntentFailed(String),
     #[error("Flow page not found: {0}")]
@@ -44,8 +65,15 @@ pub enum ExecutorError {
     FlowUrlPatternTimeout(String, String),
     #[error("Invalid URL pattern regex: {0}")]
     InvalidUrlPattern(String),
+    #[error("Intent time budget of {0}ms exhausted")]
+    BudgetExhausted(u64),
+    #[error("Precondition not met: {0}")]
+    PreconditionFailed(String),
+    #[error("{0} is disabled in safe mode")]
+    DisabledInSafeMode(String),
 }
 
+#[derive(Debug, Clone, Serialize)]
 pub struct IntentResult {
     pub status: IntentStatus,
     pub data: Option<Value>,
@@ -53,6 +81,10 @@ pub struct IntentResult {
     pub checkpoint: Option<String>,
     pub hints: Vec<String>,
     pub changes: Option<PageChanges>,
+    /// The page as it was when the run failed, captured when
+    /// `screenshot_on_error` is set.
+    #[serde(skip)]
+    pub failure_screenshot: Option<Vec<u8>>,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -62,6 +94,18 @@ pub enum IntentStatus {
     Failed(String),
 }
 
+impl IntentStatus {
+    /// The process exit code for a run that ended this way: 0 on success,
+    /// 1 on failure and 2 when only some steps completed.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            IntentStatus::Success => 0,
+            IntentStatus::Failed(_) => 1,
+            IntentStatus::PartialSuccess { .. } => 2,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CheckpointState {
     pub name: String,
@@ -69,9 +113,40 @@ pub struct CheckpointState {
     pub step_index: usize,
 }
 
+/// The registry an executor looks intents up in: borrowed for the
+/// executor's lifetime, or shared with other executors through an `Arc`.
+#[derive(Clone)]
+enum RegistryHandle<'a> {
+    Borrowed(&'a IntentRegistry),
+    Shared(Arc<IntentRegistry>),
+}
+
+impl Deref for RegistryHandle<'_> {
+    type Target = IntentRegistry;
+
+    fn deref(&self) -> &IntentRegistry {
+        match self {
+            RegistryHandle::Borrowed(registry) => registry,
+            RegistryHandle::Shared(registry) => registry,
+        }
+    }
+}
+
+/// The verifier is stateless, so executors built by `new_shared` share
+/// this one instead of borrowing the caller's.
+static SHARED_VERIFIER: Verifier = Verifier;
+
+/// Runs intents from a registry against a backend.
+///
+/// `new` borrows the registry, tying the executor to the caller's stack.
+/// Services that run intents from many tasks at once register them in one
+/// `Arc<IntentRegistry>` and build each task's executor with `new_shared`;
+/// the registry and verifier are `Send + Sync`, so the executor's futures
+/// can be spawned onto a multi-threaded runtime. Only the backend is still
+/// borrowed, since each run drives a page of its own.
 pub struct IntentExecutor<'a, B: Backend + ?Sized> {
     backend: &'a mut B,
-    registry: &'a IntentRegistry,
+    registry: RegistryHandle<'a>,
     verifier: &'a Verifier,
     variables: HashMap<String, Value>,
     logs: Vec<String>,
@@ -79,10 +154,44 @@ pub struct IntentExecutor<'a, B: Backend + ?Sized> {
     initial_scan: Option<ScanResult>,
     checkpoints: Vec<CheckpointState>,
     last_checkpoint: Option<String>,
+    /// Backend call limit for steps without their own `timeout_ms`.
+    default_timeout_ms: u64,
+    /// How often waits re-check the page (`options.poll_interval_ms`).
+    poll_interval_ms: u64,
+    /// Retry policy for steps when neither the step nor the intent sets one.
+    default_retry: RetryConfig,
+    /// Weights for scoring text matches against targets and form fields.
+    scoring: ScoringConfig,
+    /// Deadline and length of the run's `total_timeout_ms` budget.
+    budget: Option<(Instant, u64)>,
+    /// Budget of the parent flow this executor runs a sub-intent for.
+    inherited_budget: Option<(Instant, u64)>,
+    /// Field names, beyond the defaults, whose values are kept out of logs.
+    sensitive_fields: Vec<String>,
+    /// Reject steps that run raw script.
+    safe_mode: bool,
+    /// Capture the page when a run fails or only partly completes.
+    screenshot_on_error: bool,
+    /// Handlers for actions outside the built-in set, by action name.
+    action_handlers: HashMap<String, Arc<dyn ActionHandler>>,
 }
 
 impl<'a, B: Backend + ?Sized> IntentExecutor<'a, B> {
     pub fn new(backend: &'a mut B, registry: &'a IntentRegistry, verifier: &'a Verifier) -> Self {
+        Self::with_registry(backend, RegistryHandle::Borrowed(registry), verifier)
+    }
+
+    /// An executor that holds a reference to a registry shared across tasks,
+    /// rather than borrowing it.
+    pub fn new_shared(backend: &'a mut B, registry: Arc<IntentRegistry>) -> Self {
+        Self::with_registry(backend, RegistryHandle::Shared(registry), &SHARED_VERIFIER)
+    }
+
+    fn with_registry(
+        backend: &'a mut B,
+        registry: RegistryHandle<'a>,
+        verifier: &'a Verifier,
+    ) -> Self {
         Self {
             backend,
             registry,
@@ -93,26 +202,181 @@ impl<'a, B: Backend + ?Sized> IntentExecutor<'a, B> {
             initial_scan: None,
             checkpoints: Vec::new(),
             last_checkpoint: None,
+            default_timeout_ms: IntentOptions::default().timeout,
+            poll_interval_ms: IntentOptions::default().poll_interval(),
+            default_retry: RetryConfig::default(),
+            scoring: ScoringConfig::default(),
+            budget: None,
+            inherited_budget: None,
+            sensitive_fields: Vec::new(),
+            safe_mode: false,
+            screenshot_on_error: false,
+            action_handlers: HashMap::new(),
         }
     }
 
-    fn partial_success_result(
-        &self,
+    /// Set the engine-wide retry policy, used for steps whose intent and
+    /// step definitions both leave `retry` unset.
+    pub fn set_default_retry(&mut self, retry: RetryConfig) {
+        self.default_retry = retry;
+    }
+
+    /// Set the weights used to score text matches when resolving targets.
+    pub fn set_scoring(&mut self, scoring: ScoringConfig) {
+        self.scoring = scoring;
+    }
+
+    /// Treat variables with these names, as well as the default ones such as
+    /// `password`, as secrets whose values never appear in the run's logs.
+    pub fn set_sensitive_fields(&mut self, sensitive_fields: Vec<String>) {
+        self.sensitive_fields = sensitive_fields;
+    }
+
+    /// Fail `execute` steps instead of running their script.
+    pub fn set_safe_mode(&mut self, safe_mode: bool) {
+        self.safe_mode = safe_mode;
+    }
+
+    /// Capture a screenshot when a run fails or only partly completes, even
+    /// if the intent's own options leave `screenshot_on_error` unset.
+    pub fn set_screenshot_on_error(&mut self, screenshot_on_error: bool) {
+        self.screenshot_on_error = screenshot_on_error;
+    }
+
+    /// Run steps whose `action` is `name` with `handler`. Built-in actions
+    /// cannot be replaced this way; they are always run by the executor.
+    pub fn register_action_handler(
+        &mut self,
+        name: impl Into<String>,
+        handler: Arc<dyn ActionHandler>,
+    ) {
+        self.action_handlers.insert(name.into(), handler);
+    }
+
+    /// The run's logs with the values of sensitive variables masked.
+    fn redacted_logs(&self) -> Vec<String> {
+        let mut secrets = Vec::new();
+        for (name, value) in &self.variables {
+            collect_secrets(name, value, &self.sensitive_fields, &mut secrets);
+        }
+        // Longest first, so a secret containing another is masked whole
+        secrets.sort_by_key(|s| std::cmp::Reverse(s.len()));
+        self.logs
+            .iter()
+            .map(|line| {
+                secrets.iter().fold(line.clone(), |line, secret| {
+                    line.replace(secret, "********")
+                })
+            })
+            .collect()
+    }
+
+    /// Take up the run settings an intent's options carry.
+    fn apply_options(&mut self, options: &IntentOptions) {
+        self.default_timeout_ms = options.timeout;
+        self.poll_interval_ms = options.poll_interval();
+        self.screenshot_on_error |= options.screenshot_on_error;
+        self.start_budget(options.total_timeout_ms);
+    }
+
+    /// Start the run's time budget, replacing any left by an earlier run.
+    /// A budget inherited from a parent flow is only ever shortened.
+    fn start_budget(&mut self, total_timeout_ms: Option<u64>) {
+        self.budget = self.inherited_budget;
+        let Some(ms) = total_timeout_ms else {
+            return;
+        };
+        let deadline = Instant::now() + Duration::from_millis(ms);
+        self.budget = match self.budget {
+            Some((inherited, inherited_ms)) if inherited <= deadline => {
+                Some((inherited, inherited_ms))
+            }
+            _ => Some((deadline, ms)),
+        };
+    }
+
+    /// Time left in the run's budget, if it has one.
+    fn budget_remaining(&self) -> Result<Option<Duration>, ExecutorError> {
+        match self.budget {
+            None => Ok(None),
+            Some((deadline, ms)) => {
+                let left = deadline.saturating_duration_since(Instant::now());
+                if left.is_zero() {
+                    Err(ExecutorError::BudgetExhausted(ms))
+                } else {
+                    Ok(Some(left))
+                }
+            }
+        }
+    }
+
+    async fn partial_success_result(
+        &mut self,
         completed: usize,
         total: usize,
         data: Option<Value>,
         hints: Vec<String>,
     ) -> IntentResult {
+        let failure_screenshot = self.capture_failure_screenshot().await;
         IntentResult {
             status: IntentStatus::PartialSuccess { completed, total },
             data,
-            logs: self.logs.clone(),
+            logs: self.redacted_logs(),
             checkpoint: self.last_checkpoint.clone(),
             hints,
             changes: self.calculate_changes(),
+            failure_screenshot,
         }
     }
 
+    /// A `Failed` result for an error raised before any step completed, used
+    /// in place of the error when a screenshot of the failure was asked for.
+    async fn failed_result(&mut self, err: ExecutorError) -> IntentResult {
+        let failure_screenshot = self.capture_failure_screenshot().await;
+        IntentResult {
+            status: IntentStatus::Failed(err.to_string()),
+            data: None,
+            logs: self.redacted_logs(),
+            checkpoint: self.last_checkpoint.clone(),
+            hints: vec![],
+            changes: self.calculate_changes(),
+            failure_screenshot,
+        }
+    }
+
+    /// Screenshot the page if `screenshot_on_error` is set. A screenshot that
+    /// cannot be taken is logged rather than hiding the original failure.
+    async fn capture_failure_screenshot(&mut self) -> Option<Vec<u8>> {
+        if !self.screenshot_on_error {
+            return None;
+        }
+        match self.backend.screenshot().await {
+            Ok(data) => Some(data),
+            Err(e) => {
+                self.logs
+                    .push(format!("Could not capture failure screenshot: {}", e));
+                None
+            }
+        }
+    }
+
+    /// Fail with the first of `conditions` the initial scan does not meet.
+    async fn check_pre_conditions(
+        &mut self,
+        conditions: &[Condition],
+    ) -> Result<(), ExecutorError> {
+        let Some(scan) = &self.last_scan else {
+            return Ok(());
+        };
+        let ctx = VerifierContext::with_variables(scan, &self.variables);
+        for condition in conditions {
+            if !self.verifier.verify(condition, &ctx).await? {
+                return Err(ExecutorError::PreconditionFailed(condition.describe()));
+            }
+        }
+        Ok(())
+    }
+
     async fn verify_success_conditions(
         &mut self,
         success_cond: &oryn_common::intent::definition::SuccessCondition,
@@ -166,6 +430,7 @@ impl<'a, B: Backend + ?Sized> IntentExecutor<'a, B> {
 
         // 2. PARSE & BIND PARAMETERS
         self.bind_parameters(&intent.parameters, &params)?;
+        self.apply_options(&intent.options);
 
         // 3. PLAN (Scan initial state)
         if !self.backend.is_ready().await {
@@ -173,6 +438,7 @@ impl<'a, B: Backend + ?Sized> IntentExecutor<'a, B> {
         }
         self.perform_scan().await?;
         self.initial_scan = self.last_scan.clone();
+        self.check_pre_conditions(&intent.pre_conditions).await?;
 
         // 4. EXECUTE - check for flow vs steps
         if let Some(flow) = &intent.flow {
@@ -184,19 +450,37 @@ impl<'a, B: Backend + ?Sized> IntentExecutor<'a, B> {
 
         for step in &intent.steps {
             match self
-                .execute_step_with_retry(step, &intent.options.retry)
+                .execute_step_with_retry(step, intent.options.retry.as_ref())
                 .await
             {
                 Ok(_) => steps_completed += 1,
-                Err(e) => {
-                    // Return PartialSuccess if some steps completed
-                    if steps_completed > 0 {
-                        return Ok(self.partial_success_result(
+                Err(ExecutorError::BudgetExhausted(ms)) => {
+                    return Ok(self
+                        .partial_success_result(
                             steps_completed,
                             total_steps,
                             None,
-                            vec![format!("Failed at step {}: {}", steps_completed + 1, e)],
-                        ));
+                            vec![format!(
+                                "Time budget of {}ms ran out at step {}",
+                                ms,
+                                steps_completed + 1
+                            )],
+                        )
+                        .await);
+                }
+                Err(e) => {
+                    // Return PartialSuccess if some steps completed
+                    if steps_completed > 0 {
+                        return Ok(self
+                            .partial_success_result(
+                                steps_completed,
+                                total_steps,
+                                None,
+                                vec![format!("Failed at step {}: {}", steps_completed + 1, e)],
+                            )
+                            .await);
+                    } else if self.screenshot_on_error {
+                        return Ok(self.failed_result(e).await);
                     } else {
                         return Err(e);
                     }
@@ -209,12 +493,14 @@ impl<'a, B: Backend + ?Sized> IntentExecutor<'a, B> {
             let passed = self.verify_success_conditions(success_cond).await?;
             if !passed {
                 // Verification failed after steps executed
-                return Ok(self.partial_success_result(
-                    steps_completed,
-                    total_steps,
-                    None,
-                    vec!["Steps completed but verification failed".to_string()],
-                ));
+                return Ok(self
+                    .partial_success_result(
+                        steps_completed,
+                        total_steps,
+                        None,
+                        vec!["Steps completed but verification failed".to_string()],
+                    )
+                    .await);
             }
         } else {
             // If no verification needed, maybe refresh scan for final state diff?
@@ -226,10 +512,11 @@ impl<'a, B: Backend + ?Sized> IntentExecutor<'a, B> {
             status: IntentStatus::Success,
             data: self
                 .extract_result_data(intent.success.as_ref().and_then(|s| s.extract.as_ref())),
-            logs: self.logs.clone(),
+            logs: self.redacted_logs(),
             checkpoint: self.last_checkpoint.clone(),
             hints: vec![],
             changes: self.calculate_changes(),
+            failure_screenshot: None,
         })
     }
 
@@ -253,6 +540,7 @@ impl<'a, B: Backend + ?Sized> IntentExecutor<'a, B> {
 
         // 2. PARSE & BIND PARAMETERS
         self.bind_parameters(&intent.parameters, &params)?;
+        self.apply_options(&intent.options);
 
         // 3. PLAN (Scan initial state)
         if !self.backend.is_ready().await {
@@ -260,6 +548,7 @@ impl<'a, B: Backend + ?Sized> IntentExecutor<'a, B> {
         }
         self.perform_scan().await?;
         self.initial_scan = self.last_scan.clone();
+        self.check_pre_conditions(&intent.pre_conditions).await?;
 
         // 4. EXECUTE (with resume logic)
         let start_index = if let Some(checkpoint_name) = resume_from {
@@ -288,15 +577,28 @@ impl<'a, B: Backend + ?Sized> IntentExecutor<'a, B> {
             0
         };
 
-        // Execute steps starting from start_index
-        for (_, step) in intent.steps.iter().enumerate().skip(start_index) {
-            // Apply retry logic if configured
-            // For now, use intent-level retry config for all steps
-            // Or ideally, step-level retry. Definition needs update for Step-level retry?
-            // The plan mentioned "Enhanced Retry Logic" using IntentOptions.
-
-            self.execute_step_with_retry(step, &intent.options.retry)
-                .await?;
+        // Execute steps starting from start_index; steps before it count as
+        // completed by the run that reached the checkpoint.
+        let total_steps = intent.steps.len();
+        for (i, step) in intent.steps.iter().enumerate().skip(start_index) {
+            match self
+                .execute_step_with_retry(step, intent.options.retry.as_ref())
+                .await
+            {
+                Ok(_) => {}
+                Err(ExecutorError::BudgetExhausted(ms)) => {
+                    return Ok(self
+                        .partial_success_result(
+                            i,
+                            total_steps,
+                            None,
+                            vec![format!("Time budget of {}ms ran out at step {}", ms, i + 1)],
+                        )
+                        .await);
+                }
+                Err(e) if self.screenshot_on_error => return Ok(self.failed_result(e).await),
+                Err(e) => return Err(e),
+            }
         }
 
         // 6. RESPOND (Verify omitted for brevity in resume flow? Or strictly verify?)
@@ -316,28 +618,65 @@ impl<'a, B: Backend + ?Sized> IntentExecutor<'a, B> {
             status: IntentStatus::Success,
             data: self
                 .extract_result_data(intent.success.as_ref().and_then(|s| s.extract.as_ref())),
-            logs: self.logs.clone(),
+            logs: self.redacted_logs(),
             checkpoint: self.last_checkpoint.clone(),
             hints: vec![],
             changes: self.calculate_changes(),
+            failure_screenshot: None,
         })
     }
 
+    /// Runs a step, retrying failures under the first retry policy set on
+    /// the step, then the intent, then the engine default.
     #[async_recursion]
     async fn execute_step_with_retry(
         &mut self,
         step: &Step,
-        config: &oryn_common::intent::definition::RetryConfig,
+        intent_retry: Option<&RetryConfig>,
     ) -> Result<(), ExecutorError> {
+        let step_retry = match step {
+            Step::Action(action) => action.retry.as_ref(),
+            _ => None,
+        };
+        let config = step_retry
+            .or(intent_retry)
+            .cloned()
+            .unwrap_or_else(|| self.default_retry.clone());
         let mut attempts = 0;
         let max_attempts = config.max_attempts.max(1);
 
         loop {
             attempts += 1;
-            match self.execute_step(step).await {
-                Ok(()) => return Ok(()),
+            let result = match self.budget_remaining()? {
+                Some(left) => match tokio::time::timeout(left, self.execute_step(step)).await {
+                    Ok(result) => result,
+                    Err(_) => Err(ExecutorError::BudgetExhausted(
+                        self.budget.map_or(0, |(_, ms)| ms),
+                    )),
+                },
+                None => self.execute_step(step).await,
+            };
+            match result {
+                Ok(()) => {
+                    // Only the attempt that finally worked reaches here, so
+                    // the hook runs once, before the next step.
+                    if let Step::Action(oryn_common::intent::definition::ActionStep {
+                        on_success: Some(success_steps),
+                        ..
+                    }) = step
+                    {
+                        self.logs
+                            .push("Step succeeded. Executing on_success handler.".to_string());
+                        for handler_step in success_steps {
+                            self.execute_step_with_retry(handler_step, intent_retry)
+                                .await?;
+                        }
+                    }
+                    return Ok(());
+                }
+                Err(e @ ExecutorError::BudgetExhausted(_)) => return Err(e),
                 Err(e) => {
-                    if attempts >= max_attempts {
+                    if attempts >= max_attempts || !is_retryable(&e) {
                         // Check for per-step error handlers
                         if let Step::Action(oryn_common::intent::definition::ActionStep {
                             on_error: Some(error_steps),
@@ -350,7 +689,8 @@ impl<'a, B: Backend + ?Sized> IntentExecutor<'a, B> {
                             ));
 
                             for handler_step in error_steps {
-                                self.execute_step_with_retry(handler_step, config).await?;
+                                self.execute_step_with_retry(handler_step, intent_retry)
+                                    .await?;
                             }
 
                             self.logs.push(
@@ -360,19 +700,16 @@ impl<'a, B: Backend + ?Sized> IntentExecutor<'a, B> {
                         }
                         return Err(e);
                     }
-                    // Simple check: is it retryable? Most executor errors are transient (selector not found, etc)
-                    // unless it's strictly logic error.
-                    // For now, retry everything except IntentNotFound/Param errors?
-                    // Let's assume most step failures are retryable.
-
-                    let delay = (config.delay_ms as f64
-                        * config.backoff_multiplier.powi((attempts - 1) as i32))
-                        as u64;
+                    let delay = retry_delay_ms(&config, attempts);
                     self.logs.push(format!(
                         "Step failed (attempt {}/{}). Retrying in {}ms. Error: {}",
                         attempts, max_attempts, delay, e
                     ));
-                    tokio::time::sleep(tokio::time::Duration::from_millis(delay)).await;
+                    let mut pause = Duration::from_millis(delay);
+                    if let Some(left) = self.budget_remaining()? {
+                        pause = pause.min(left);
+                    }
+                    tokio::time::sleep(pause).await;
 
                     // Refresh DOM before retry
                     let _ = self.perform_scan().await;
@@ -429,85 +766,28 @@ impl<'a, B: Backend + ?Sized> IntentExecutor<'a, B> {
         &self,
         scan: &ScanResult,
     ) -> Vec<oryn_common::protocol::IntentAvailability> {
-        let mut availability = Vec::new();
-
-        for intent in self.registry.list() {
-            let mut status = oryn_common::protocol::AvailabilityStatus::Ready;
-            let mut reason = None;
-
-            // 1. Check URL RegEx (Any match is sufficient)
-            if !intent.triggers.urls.is_empty() {
-                let mut url_matched = false;
-                for url_pattern in &intent.triggers.urls {
-                    match regex::Regex::new(url_pattern) {
-                        Ok(re) => {
-                            if re.is_match(&scan.page.url) {
-                                url_matched = true;
-                                break;
-                            }
-                        }
-                        Err(_) => {
-                            reason = Some(format!("Invalid URL regex: {}", url_pattern));
-                            // Continue checking others? Or fail hard?
-                            // Let's mark as unavailable if any regex is invalid to be safe/noisy?
-                            // Or just ignore invalid ones?
-                            // Let's record reason and fail this specific pattern match.
-                        }
-                    }
-                }
-
-                if !url_matched && reason.is_none() {
-                    status = oryn_common::protocol::AvailabilityStatus::NavigateRequired;
-                } else if !url_matched {
-                    status = oryn_common::protocol::AvailabilityStatus::Unavailable;
-                }
-            }
-
-            // 2. Check Patterns
-            // Only check patterns if URL is fine (Ready so far)
-            if status == oryn_common::protocol::AvailabilityStatus::Ready {
-                if let Some(patterns) = &scan.patterns {
-                    for required_pattern in &intent.triggers.patterns {
-                        let found = match required_pattern.as_str() {
-                            "login_form" => patterns.login.is_some(),
-                            "search_box" => patterns.search.is_some(),
-                            "pagination" => patterns.pagination.is_some(),
-                            "modal" => patterns.modal.is_some(),
-                            "cookie_banner" => patterns.cookie_banner.is_some(),
-                            _ => false, // Unknown pattern
-                        };
-
-                        if !found {
-                            status = oryn_common::protocol::AvailabilityStatus::MissingPattern;
-                            reason = Some(format!("Missing pattern: {}", required_pattern));
-                            break;
-                        }
-                    }
-                } else if !intent.triggers.patterns.is_empty() {
-                    // No patterns detected but intent requires some
-                    status = oryn_common::protocol::AvailabilityStatus::MissingPattern;
-                    reason = Some("No patterns detected on page".to_string());
-                }
-            }
-
-            availability.push(oryn_common::protocol::IntentAvailability {
-                name: intent.name.clone(),
-                status,
-                parameters: intent.parameters.iter().map(|p| p.name.clone()).collect(),
-                trigger_reason: reason,
-            });
-        }
-        availability
+        let mapper = IntentMapper::new(&self.registry);
+        self.registry
+            .list()
+            .into_iter()
+            .map(|intent| mapper.availability(intent, scan))
+            .collect()
     }
 
     fn bind_parameters(
         &mut self,
-        defs: &[oryn_common::intent::definition::ParameterDef],
+        defs: &[ParameterDef],
         params: &HashMap<String, Value>,
     ) -> Result<(), ExecutorError> {
         for def in defs {
-            if let Some(value) = params.get(&def.name).or(def.default.as_ref()) {
-                self.variables.insert(def.name.clone(), value.clone());
+            if let Some(value) = parameter_value(defs, def, params, &mut Vec::new())? {
+                def.check_allowed(&value).map_err(|reason| {
+                    ExecutorError::InvalidParameterValue {
+                        name: def.name.clone(),
+                        reason,
+                    }
+                })?;
+                self.variables.insert(def.name.clone(), value);
             } else if def.required {
                 return Err(ExecutorError::MissingParameter(def.name.clone()));
             }
@@ -561,6 +841,7 @@ impl<'a, B: Backend + ?Sized> IntentExecutor<'a, B> {
                 }
                 Ok(())
             }
+            Step::Parallel(wrapper) => self.execute_parallel(&wrapper.parallel.steps).await,
             Step::Checkpoint(wrapper) => {
                 self.last_checkpoint = Some(wrapper.checkpoint.clone());
                 // In a real system, we might persist state to disk here.
@@ -578,79 +859,195 @@ impl<'a, B: Backend + ?Sized> IntentExecutor<'a, B> {
         }
     }
 
-    async fn execute_action(&mut self, step: &ActionStep) -> Result<(), ExecutorError> {
-        self.logs.push(format!("Action: {:?}", step.action));
+    /// Run the branches of a parallel step concurrently.
+    ///
+    /// Each branch is planned up front into the scanner requests it will
+    /// send, against its own copy of the variables, and the requests are then
+    /// dispatched together. Branches share the backend through a mutex, so
+    /// calls interleave rather than overlap. Variable writes are merged in
+    /// declaration order once every branch has succeeded.
+    async fn execute_parallel(&mut self, branches: &[Step]) -> Result<(), ExecutorError> {
+        let base = self.variables.clone();
+        let mut plans = Vec::with_capacity(branches.len());
+        let mut writes = Vec::with_capacity(branches.len());
+
+        for branch in branches {
+            self.variables = base.clone();
+            let mut requests = Vec::new();
+            if let Err(e) = self.plan_step(branch, &mut requests).await {
+                self.variables = base;
+                return Err(e);
+            }
+            let changed: Vec<(String, Value)> = self
+                .variables
+                .iter()
+                .filter(|(k, v)| base.get(*k) != Some(*v))
+                .map(|(k, v)| (k.clone(), v.clone()))
+                .collect();
+            writes.push(changed);
+            plans.push(requests);
+        }
+        self.variables = base;
+
+        let backend = Mutex::new(&mut *self.backend);
+        let results = join_all(plans.into_iter().map(|requests| {
+            let backend = &backend;
+            async move {
+                for (req, timeout_ms) in requests {
+                    let mut backend = backend.lock().await;
+                    with_timeout(backend.execute_scanner(req), timeout_ms).await?;
+                }
+                Ok::<(), ExecutorError>(())
+            }
+        }))
+        .await;
 
-        let target = match &step.target {
-            Some(spec) => Some(self.resolve_target_spec(spec).await?),
-            None => None,
-        };
+        let failures: Vec<String> = results
+            .iter()
+            .enumerate()
+            .filter_map(|(i, r)| r.as_ref().err().map(|e| format!("branch {}: {}", i + 1, e)))
+            .collect();
+        if !failures.is_empty() {
+            return Err(ExecutorError::StepFailed(format!(
+                "Parallel step failed: {}",
+                failures.join("; ")
+            )));
+        }
 
-        match step.action {
-            ActionType::Click => {
-                if let Some(t) = target {
-                    let cmd = Command::Click(t, self.convert_options(&step.options));
-                    let req = translator::translate(&cmd)?;
-                    self.backend.execute_scanner(req).await?;
+        for changed in writes {
+            self.variables.extend(changed);
+        }
+        Ok(())
+    }
+
+    /// Collect the scanner requests a parallel branch will send.
+    #[async_recursion]
+    async fn plan_step(
+        &mut self,
+        step: &Step,
+        out: &mut Vec<(ScannerAction, u64)>,
+    ) -> Result<(), ExecutorError> {
+        match step {
+            Step::Action(action) => {
+                if !sends_single_request(&action.action) {
+                    return Err(ExecutorError::StepFailed(format!(
+                        "{:?} action cannot run inside a parallel step",
+                        action.action
+                    )));
+                }
+                self.logs.push(format!("Action: {:?}", action.action));
+                let timeout_ms = action.timeout_ms.unwrap_or(self.default_timeout_ms);
+                if let Some(req) = self.build_request(action).await? {
+                    out.push((req, timeout_ms));
                 }
+                Ok(())
             }
-            ActionType::Type => {
-                if let Some(t) = target {
-                    let text = self.resolve_variable(step.options.get("text"));
-                    let cmd = Command::Type(t, text, self.convert_options(&step.options));
-                    let req = translator::translate(&cmd)?;
-                    self.backend.execute_scanner(req).await?;
+            Step::Loop(wrapper) => {
+                let items = self.resolve_loop_items(&wrapper.loop_.over, wrapper.loop_.max);
+                for item in items.iter().take(wrapper.loop_.max) {
+                    self.variables
+                        .insert(wrapper.loop_.as_var.clone(), item.clone());
+                    for s in &wrapper.loop_.steps {
+                        self.plan_step(s, out).await?;
+                    }
                 }
+                Ok(())
             }
-            ActionType::Wait => {
-                let cond_str = step
-                    .options
-                    .get("condition")
-                    .and_then(|v| v.as_str())
-                    .unwrap_or("visible");
+            _ => Err(ExecutorError::StepFailed(
+                "Only actions and loops can run inside a parallel step".into(),
+            )),
+        }
+    }
 
-                let wait_cond = match cond_str {
-                    "visible" => {
-                        if let Some(t) = target {
-                            oryn_common::command::WaitCondition::Visible(t)
-                        } else {
-                            return Err(ExecutorError::MissingParameter(
-                                "target for wait visible".into(),
-                            ));
-                        }
-                    }
-                    "hidden" => {
-                        if let Some(t) = target {
-                            oryn_common::command::WaitCondition::Hidden(t)
-                        } else {
-                            return Err(ExecutorError::MissingParameter(
-                                "target for wait hidden".into(),
-                            ));
-                        }
-                    }
-                    "load" => oryn_common::command::WaitCondition::Load,
-                    "idle" => oryn_common::command::WaitCondition::Idle,
-                    "url" => {
-                        if let Some(p) = step.options.get("pattern").and_then(|v| v.as_str()) {
-                            oryn_common::command::WaitCondition::Url(p.to_string())
-                        } else {
-                            return Err(ExecutorError::MissingParameter(
-                                "pattern for wait url".into(),
-                            ));
-                        }
-                    }
-                    _ => {
-                        return Err(ExecutorError::InvalidParameterType(format!(
-                            "Unknown wait condition: {}",
-                            cond_str
-                        )));
-                    }
-                };
+    /// Runs a wait step. The wait's own `timeout` option bounds it, then the
+    /// step timeout, then the intent default.
+    async fn execute_wait(&mut self, step: &ActionStep) -> Result<(), ExecutorError> {
+        let Some(ScannerAction::Wait(mut req)) = self.build_request(step).await? else {
+            return Ok(());
+        };
+        req.poll_interval = Some(self.poll_interval_ms);
+        let limit_ms = req.timeout.unwrap_or(self.default_timeout_ms);
+        let description = req.describe();
+        let expired = || {
+            ExecutorError::StepTimedOut(format!(
+                "wait {} did not complete within {}ms",
+                description, limit_ms
+            ))
+        };
 
-                let cmd = Command::Wait(wait_cond, self.convert_options(&step.options));
-                let req = translator::translate(&cmd)?;
-                self.backend.execute_scanner(req).await?;
+        // The scanner enforces the limit itself; the grace period only catches
+        // a backend that never answers.
+        let call = self.backend.execute_scanner(ScannerAction::Wait(req));
+        match tokio::time::timeout(
+            Duration::from_millis(limit_ms + WAIT_TIMEOUT_GRACE_MS),
+            call,
+        )
+        .await
+        {
+            Ok(Ok(ScannerProtocolResponse::Error { code, message, .. })) => {
+                if code == "TIMEOUT" || code == "NAVIGATION_ERROR" {
+                    Err(expired())
+                } else {
+                    Err(ExecutorError::StepFailed(format!(
+                        "wait {} failed: {}",
+                        description, message
+                    )))
+                }
             }
+            Ok(result) => result.map(|_| ()).map_err(ExecutorError::from),
+            Err(_) => Err(expired()),
+        }
+    }
+
+    /// Runs an action outside the built-in set with the handler registered
+    /// under its name, after resolving its target and options.
+    async fn execute_custom(
+        &mut self,
+        name: &str,
+        step: &ActionStep,
+        timeout_ms: u64,
+    ) -> Result<(), ExecutorError> {
+        let Some(handler) = self.action_handlers.get(name).cloned() else {
+            return Err(ExecutorError::StepFailed(format!(
+                "Unknown action '{}': no handler is registered for it",
+                name
+            )));
+        };
+        let target = match &step.target {
+            Some(spec) => Some(self.resolve_target_spec(spec).await?),
+            None => None,
+        };
+        let options = self.convert_options(&step.options);
+        let mut ctx = ActionContext {
+            backend: self.backend.as_dyn_backend(),
+            target,
+            options,
+            timeout_ms,
+            logs: &mut self.logs,
+        };
+        handler.handle(&mut ctx, step).await
+    }
+
+    async fn execute_action(&mut self, step: &ActionStep) -> Result<(), ExecutorError> {
+        self.logs.push(format!("Action: {:?}", step.action));
+        let timeout_ms = step.timeout_ms.unwrap_or(self.default_timeout_ms);
+
+        if step.action == ActionType::Wait {
+            return self.execute_wait(step).await;
+        }
+
+        if let ActionType::Custom(name) = &step.action {
+            return self.execute_custom(name, step, timeout_ms).await;
+        }
+
+        if sends_single_request(&step.action) {
+            if let Some(req) = self.build_request(step).await? {
+                with_timeout(self.backend.execute_scanner(req), timeout_ms).await?;
+            }
+            return Ok(());
+        }
+
+        match step.action {
             ActionType::FillForm => {
                 // Resolve the data parameter, which can be a variable reference or inline object
                 let data_json = if let Some(v) = step.options.get("data") {
@@ -668,90 +1065,74 @@ impl<'a, B: Backend + ?Sized> IntentExecutor<'a, B> {
                 };
 
                 if let Some(obj) = data_json.as_object() {
+                    // Fields found in the scan are typed in one batch, so the
+                    // whole form costs a single round-trip.
+                    let mut batch = Vec::new();
+                    let mut unscanned = Vec::new();
                     for (key, val) in obj {
                         let val_str = val.as_str().unwrap_or_default().to_string();
-                        let mut found_via_scan = false;
-
-                        if let Some(scan) = &self.last_scan {
-                            // Use scoring-based matching to find the best form field
-                            if let Some(el) = find_best_form_field(&scan.elements, key) {
+                        // Use scoring-based matching to find the best form field
+                        let req = self
+                            .last_scan
+                            .as_ref()
+                            .and_then(|scan| {
+                                find_best_form_field(&scan.elements, key, &self.scoring)
+                            })
+                            .and_then(|el| {
                                 let t = Target::Id(el.id as usize);
-                                let cmd = Command::Type(t, val_str.clone(), HashMap::new());
-                                if let Ok(req) = translator::translate(&cmd)
-                                    && self.backend.execute_scanner(req).await.is_ok()
-                                {
-                                    found_via_scan = true;
-                                }
-                            }
+                                scanner_action(&type_command(&t, val_str.clone())).ok()
+                            });
+                        match req {
+                            Some(req) => batch.push((key, val_str, req)),
+                            None => unscanned.push((key, val_str)),
                         }
+                    }
 
-                        if !found_via_scan {
-                            // Fallback to selector strategy for hidden/unscanned fields
-                            let selector = format!(
-                                "input[name='{}'], input[id='{}'], textarea[name='{}']",
-                                key, key, key
-                            );
-                            let spec = TargetSpec {
-                                kind: TargetKind::Selector { selector },
-                                fallback: None,
-                            };
-                            if let Ok(t) = self.resolve_target_spec(&spec).await {
-                                let cmd = Command::Type(t, val_str, HashMap::new());
-                                let req = translator::translate(&cmd)?;
-                                self.backend.execute_scanner(req).await?;
-                            } else {
-                                self.logs
-                                    .push(format!("Could not find field for key: {}", key));
-                            }
+                    if !batch.is_empty() {
+                        let reqs = batch.iter().map(|(_, _, req)| req.clone()).collect();
+                        if with_timeout(self.backend.execute_batch(reqs), timeout_ms)
+                            .await
+                            .is_err()
+                        {
+                            unscanned.extend(batch.into_iter().map(|(key, val, _)| (key, val)));
+                        }
+                    }
+
+                    for (key, val_str) in unscanned {
+                        // Fallback to selector strategy for hidden/unscanned fields
+                        let selector = format!(
+                            "input[name='{}'], input[id='{}'], textarea[name='{}']",
+                            key, key, key
+                        );
+                        let spec = TargetSpec {
+                            kind: TargetKind::Selector { selector },
+                            fallback: None,
+                        };
+                        if let Ok(t) = self.resolve_target_spec(&spec).await {
+                            let cmd = type_command(&t, val_str);
+                            let req = scanner_action(&cmd)?;
+                            with_timeout(self.backend.execute_scanner(req), timeout_ms).await?;
+                        } else {
+                            self.logs
+                                .push(format!("Could not find field for key: {}", key));
                         }
                     }
                 }
             }
-            ActionType::Select => {
-                if let Some(t) = target {
-                    // Option value/text/index
-                    let value = self.resolve_variable(step.options.get("value"));
-                    // If no value, maybe index?
-                    // For now, Command::Select takes Target and String value.
-                    // If empty, it might mean "select the target itself" if it's an option?
-                    // But usually Select(Target, Value).
-                    let cmd = Command::Select(t, value);
-                    let req = translator::translate(&cmd)?;
-                    self.backend.execute_scanner(req).await?;
-                }
-            }
-            ActionType::Check => {
-                if let Some(t) = target {
-                    let cmd = Command::Check(t);
-                    let req = translator::translate(&cmd)?;
-                    self.backend.execute_scanner(req).await?;
-                }
-            }
-            ActionType::Uncheck => {
-                if let Some(t) = target {
-                    let cmd = Command::Uncheck(t);
-                    let req = translator::translate(&cmd)?;
-                    self.backend.execute_scanner(req).await?;
-                }
-            }
-            ActionType::Clear => {
-                if let Some(t) = target {
-                    let cmd = Command::Clear(t);
-                    let req = translator::translate(&cmd)?;
-                    self.backend.execute_scanner(req).await?;
-                }
-            }
-            ActionType::Scroll => {
-                // Scroll to target OR scroll based on options (up/down/etc)
-                let options = self.convert_options(&step.options);
-                let cmd = Command::Scroll(target, options);
-                let req = translator::translate(&cmd)?;
-                self.backend.execute_scanner(req).await?;
-            }
             ActionType::Execute => {
+                if self.safe_mode {
+                    return Err(ExecutorError::DisabledInSafeMode("execute step".into()));
+                }
                 // Run raw script
                 if let Some(script) = step.options.get("script").and_then(|v| v.as_str()) {
-                    let _ = self.backend.execute_script(script).await?;
+                    let args = step
+                        .options
+                        .get("args")
+                        .and_then(|v| v.as_object())
+                        .cloned()
+                        .unwrap_or_default();
+                    let _ =
+                        with_timeout(self.backend.execute_script(script, args), timeout_ms).await?;
                 }
             }
             ActionType::Intent => {
@@ -807,11 +1188,17 @@ impl<'a, B: Backend + ?Sized> IntentExecutor<'a, B> {
                     let sub_result = {
                         // Create temporary scope
                         // We need to construct a new Executor.
-                        let mut sub_executor = IntentExecutor::new(
+                        let mut sub_executor = IntentExecutor::with_registry(
                             self.backend, // We re-borrow backend? `self.backend`
-                            self.registry,
+                            self.registry.clone(),
                             self.verifier,
                         );
+                        sub_executor.default_retry = self.default_retry.clone();
+                        sub_executor.scoring = self.scoring.clone();
+                        sub_executor.inherited_budget = self.budget;
+                        sub_executor.sensitive_fields = self.sensitive_fields.clone();
+                        sub_executor.safe_mode = self.safe_mode;
+                        sub_executor.action_handlers = self.action_handlers.clone();
                         // Execute sub-intent
                         sub_executor.execute(sub_intent_name, resolved_params).await
                     };
@@ -860,25 +1247,205 @@ impl<'a, B: Backend + ?Sized> IntentExecutor<'a, B> {
                     .map(|s| self.resolve_variable(Some(&Value::String(s.to_string()))))
                     .ok_or_else(|| ExecutorError::MissingParameter("url for navigate".into()))?;
                 self.logs.push(format!("Navigating to: {}", url));
-                self.backend.navigate(&url).await?;
+                with_timeout(self.backend.navigate(&url), timeout_ms).await?;
             }
             ActionType::GoBack => {
                 self.logs.push("Navigating back".to_string());
-                self.backend.go_back().await?;
+                with_timeout(self.backend.go_back(), timeout_ms).await?;
             }
             ActionType::GoForward => {
                 self.logs.push("Navigating forward".to_string());
-                self.backend.go_forward().await?;
+                with_timeout(self.backend.go_forward(), timeout_ms).await?;
             }
             ActionType::Refresh => {
                 self.logs.push("Refreshing page".to_string());
-                self.backend.refresh().await?;
+                with_timeout(self.backend.refresh(), timeout_ms).await?;
             }
+            _ => unreachable!("single-request actions are dispatched above"),
         }
         Ok(())
     }
 
+    /// Resolve the target of a single-request action and build its scanner
+    /// request. Returns `None` when the action has no target to act on.
+    async fn build_request(
+        &mut self,
+        step: &ActionStep,
+    ) -> Result<Option<ScannerAction>, ExecutorError> {
+        // A wait's target may still be hidden; that is what it waits out.
+        let strategy = if step.action == ActionType::Wait {
+            ResolutionStrategy::IncludeHidden
+        } else {
+            ResolutionStrategy::Best
+        };
+        let target = match &step.target {
+            Some(spec) => Some(self.resolve_target_spec_with(spec, strategy).await?),
+            None => None,
+        };
+
+        match step.action {
+            ActionType::Click => {
+                if let Some(t) = target {
+                    let options = self.convert_options(&step.options);
+                    let cmd = Command::Click(ast::ClickCmd {
+                        target: ast::Target::from_resolver_target(&t),
+                        all: false,
+                        double: option_flag(&options, "double"),
+                        right: options.get("button").is_some_and(|b| b == "right"),
+                        middle: options.get("button").is_some_and(|b| b == "middle"),
+                        force: option_flag(&options, "force"),
+                        ctrl: false,
+                        shift: false,
+                        alt: false,
+                        timeout: options.get("timeout").cloned(),
+                        wait_after: None,
+                    });
+                    return Ok(Some(scanner_action(&cmd)?));
+                }
+            }
+            ActionType::Type => {
+                if let Some(t) = target {
+                    let text = self.resolve_variable(step.options.get("text"));
+                    let options = self.convert_options(&step.options);
+                    let cmd = Command::Type(ast::TypeCmd {
+                        target: ast::Target::from_resolver_target(&t),
+                        text,
+                        append: option_flag(&options, "append"),
+                        enter: option_flag(&options, "enter") || option_flag(&options, "submit"),
+                        delay: options.get("delay").and_then(|d| d.parse().ok()),
+                        clear: option_flag(&options, "clear"),
+                        then_tab: false,
+                        timeout: options.get("timeout").cloned(),
+                        wait_after: None,
+                        secret: option_flag(&options, "secret"),
+                    });
+                    return Ok(Some(scanner_action(&cmd)?));
+                }
+            }
+            ActionType::Wait => {
+                let cond_str = step
+                    .options
+                    .get("condition")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("visible");
+
+                let wait_cond = match cond_str {
+                    "visible" => {
+                        if let Some(t) = target {
+                            WaitCondition::Visible(ast::Target::from_resolver_target(&t))
+                        } else {
+                            return Err(ExecutorError::MissingParameter(
+                                "target for wait visible".into(),
+                            ));
+                        }
+                    }
+                    "hidden" => {
+                        if let Some(t) = target {
+                            WaitCondition::Hidden(ast::Target::from_resolver_target(&t))
+                        } else {
+                            return Err(ExecutorError::MissingParameter(
+                                "target for wait hidden".into(),
+                            ));
+                        }
+                    }
+                    "load" => WaitCondition::Load,
+                    "idle" => WaitCondition::Idle,
+                    "url" => {
+                        if let Some(p) = step.options.get("pattern").and_then(|v| v.as_str()) {
+                            WaitCondition::Url(p.to_string())
+                        } else {
+                            return Err(ExecutorError::MissingParameter(
+                                "pattern for wait url".into(),
+                            ));
+                        }
+                    }
+                    _ => {
+                        return Err(ExecutorError::InvalidParameterType(format!(
+                            "Unknown wait condition: {}",
+                            cond_str
+                        )));
+                    }
+                };
+
+                let options = self.convert_options(&step.options);
+                let timeout = options.get("timeout").cloned().unwrap_or_else(|| {
+                    format!("{}ms", step.timeout_ms.unwrap_or(self.default_timeout_ms))
+                });
+                let cmd = Command::Wait(ast::WaitCmd {
+                    condition: wait_cond,
+                    timeout: Some(timeout),
+                    quiet: None,
+                });
+                return Ok(Some(scanner_action(&cmd)?));
+            }
+            ActionType::Select => {
+                if let Some(t) = target {
+                    // Option value/text/index
+                    let value = self.resolve_variable(step.options.get("value"));
+                    // If no value, maybe index?
+                    // For now, Command::Select takes Target and String value.
+                    // If empty, it might mean "select the target itself" if it's an option?
+                    // But usually Select(Target, Value).
+                    let cmd = Command::Select(ast::SelectCmd {
+                        target: ast::Target::from_resolver_target(&t),
+                        value,
+                        custom: false,
+                    });
+                    return Ok(Some(scanner_action(&cmd)?));
+                }
+            }
+            ActionType::Check => {
+                if let Some(t) = target {
+                    let cmd = Command::Check(ast::CheckCmd {
+                        target: ast::Target::from_resolver_target(&t),
+                    });
+                    return Ok(Some(scanner_action(&cmd)?));
+                }
+            }
+            ActionType::Uncheck => {
+                if let Some(t) = target {
+                    let cmd = Command::Uncheck(ast::UncheckCmd {
+                        target: ast::Target::from_resolver_target(&t),
+                    });
+                    return Ok(Some(scanner_action(&cmd)?));
+                }
+            }
+            ActionType::Clear => {
+                if let Some(t) = target {
+                    let cmd = Command::Clear(ast::ClearCmd {
+                        target: ast::Target::from_resolver_target(&t),
+                    });
+                    return Ok(Some(scanner_action(&cmd)?));
+                }
+            }
+            ActionType::Scroll => {
+                // Scroll to target OR scroll based on options (up/down/etc)
+                let options = self.convert_options(&step.options);
+                let cmd = Command::Scroll(ast::ScrollCmd {
+                    direction: options.get("direction").cloned(),
+                    amount: options.get("amount").and_then(|a| a.parse().ok()),
+                    page: option_flag(&options, "page"),
+                    smooth: false,
+                    timeout: options.get("timeout").cloned(),
+                    target: target.as_ref().map(ast::Target::from_resolver_target),
+                });
+                return Ok(Some(scanner_action(&cmd)?));
+            }
+            _ => {}
+        }
+        Ok(None)
+    }
+
     async fn resolve_target_spec(&mut self, spec: &TargetSpec) -> Result<Target, ExecutorError> {
+        self.resolve_target_spec_with(spec, ResolutionStrategy::Best)
+            .await
+    }
+
+    async fn resolve_target_spec_with(
+        &mut self,
+        spec: &TargetSpec,
+        strategy: ResolutionStrategy,
+    ) -> Result<Target, ExecutorError> {
         // Refresh scan if needed
         self.perform_scan().await?;
 
@@ -887,18 +1454,19 @@ impl<'a, B: Backend + ?Sized> IntentExecutor<'a, B> {
             ResolverContext::new(scan)
         } else {
             ResolverContext::empty()
-        };
+        }
+        .with_scoring(self.scoring.clone());
 
         let target_tree = Self::build_target(spec);
 
-        match resolve_target(&target_tree, &ctx, ResolutionStrategy::Best) {
+        match resolve_target(&target_tree, &ctx, strategy) {
             Ok(t) => Ok(t),
             Err(e) => {
                 if let Some(fallback) = &spec.fallback {
                     self.logs
                         .push(format!("Resolution failed ({}), trying fallback...", e));
                     // Recursive call for fallback
-                    Box::pin(self.resolve_target_spec(fallback)).await
+                    Box::pin(self.resolve_target_spec_with(fallback, strategy)).await
                 } else {
                     Err(ExecutorError::Resolution(e))
                 }
@@ -1038,11 +1606,7 @@ impl<'a, B: Backend + ?Sized> IntentExecutor<'a, B> {
             }
         }
 
-        if changes.url.is_none()
-            && changes.title.is_none()
-            && changes.added.is_empty()
-            && changes.removed.is_empty()
-        {
+        if changes.is_empty() {
             None
         } else {
             Some(changes)
@@ -1077,6 +1641,19 @@ impl<'a, B: Backend + ?Sized> IntentExecutor<'a, B> {
 
         // Page execution loop
         while let Some(page_name) = current_page_name.take() {
+            if let Err(ExecutorError::BudgetExhausted(ms)) = self.budget_remaining() {
+                return Ok(self
+                    .partial_success_result(
+                        pages_completed,
+                        total_pages,
+                        Some(json!(extracted_data)),
+                        vec![format!(
+                            "Time budget of {}ms ran out before page '{}'",
+                            ms, page_name
+                        )],
+                    )
+                    .await);
+            }
             let page = flow
                 .pages
                 .iter()
@@ -1106,6 +1683,19 @@ impl<'a, B: Backend + ?Sized> IntentExecutor<'a, B> {
                         self.logs.push("Flow: completed successfully".to_string());
                     }
                 }
+                Err(ExecutorError::BudgetExhausted(ms)) => {
+                    return Ok(self
+                        .partial_success_result(
+                            pages_completed,
+                            total_pages,
+                            Some(json!(extracted_data)),
+                            vec![format!(
+                                "Time budget of {}ms ran out on page '{}'",
+                                ms, page.name
+                            )],
+                        )
+                        .await);
+                }
                 Err(e) => {
                     // Check for page-level error handler
                     if let Some(error_page) = &page.on_error {
@@ -1115,12 +1705,16 @@ impl<'a, B: Backend + ?Sized> IntentExecutor<'a, B> {
                         ));
                         current_page_name = Some(error_page.clone());
                     } else if pages_completed > 0 {
-                        return Ok(self.partial_success_result(
-                            pages_completed,
-                            total_pages,
-                            Some(json!(extracted_data)),
-                            vec![format!("Flow failed at page '{}': {}", page.name, e)],
-                        ));
+                        return Ok(self
+                            .partial_success_result(
+                                pages_completed,
+                                total_pages,
+                                Some(json!(extracted_data)),
+                                vec![format!("Flow failed at page '{}': {}", page.name, e)],
+                            )
+                            .await);
+                    } else if self.screenshot_on_error {
+                        return Ok(self.failed_result(e).await);
                     } else {
                         return Err(e);
                     }
@@ -1132,22 +1726,25 @@ impl<'a, B: Backend + ?Sized> IntentExecutor<'a, B> {
         if let Some(success_cond) = &intent.success {
             let passed = self.verify_success_conditions(success_cond).await?;
             if !passed {
-                return Ok(self.partial_success_result(
-                    pages_completed,
-                    total_pages,
-                    Some(json!(extracted_data)),
-                    vec!["Flow pages completed but verification failed".to_string()],
-                ));
+                return Ok(self
+                    .partial_success_result(
+                        pages_completed,
+                        total_pages,
+                        Some(json!(extracted_data)),
+                        vec!["Flow pages completed but verification failed".to_string()],
+                    )
+                    .await);
             }
         }
 
         Ok(IntentResult {
             status: IntentStatus::Success,
             data: (!extracted_data.is_empty()).then(|| json!(extracted_data)),
-            logs: self.logs.clone(),
+            logs: self.redacted_logs(),
             checkpoint: self.last_checkpoint.clone(),
             hints: vec![],
             changes: self.calculate_changes(),
+            failure_screenshot: None,
         })
     }
 
@@ -1170,9 +1767,18 @@ impl<'a, B: Backend + ?Sized> IntentExecutor<'a, B> {
                     ));
 
                     let sub_result = {
-                        let mut sub_executor =
-                            IntentExecutor::new(self.backend, self.registry, self.verifier);
+                        let mut sub_executor = IntentExecutor::with_registry(
+                            self.backend,
+                            self.registry.clone(),
+                            self.verifier,
+                        );
                         sub_executor.variables = self.variables.clone();
+                        sub_executor.default_retry = self.default_retry.clone();
+                        sub_executor.scoring = self.scoring.clone();
+                        sub_executor.inherited_budget = self.budget;
+                        sub_executor.sensitive_fields = self.sensitive_fields.clone();
+                        sub_executor.safe_mode = self.safe_mode;
+                        sub_executor.action_handlers = self.action_handlers.clone();
                         sub_executor
                             .execute(intent_name, self.variables.clone())
                             .await
@@ -1199,7 +1805,7 @@ impl<'a, B: Backend + ?Sized> IntentExecutor<'a, B> {
                     self.logs
                         .push(format!("Page '{}': executing inline steps", page.name));
                     for step in steps {
-                        self.execute_step_with_retry(step, &intent.options.retry)
+                        self.execute_step_with_retry(step, intent.options.retry.as_ref())
                             .await?;
                     }
                 }
@@ -1241,7 +1847,7 @@ impl<'a, B: Backend + ?Sized> IntentExecutor<'a, B> {
 
         let start = std::time::Instant::now();
         let timeout = std::time::Duration::from_millis(timeout_ms);
-        let poll_interval = std::time::Duration::from_millis(500);
+        let poll_interval = std::time::Duration::from_millis(self.poll_interval_ms);
 
         loop {
             self.perform_scan().await?;
@@ -1272,6 +1878,156 @@ impl<'a, B: Backend + ?Sized> IntentExecutor<'a, B> {
     }
 }
 
+/// Actions that translate to exactly one scanner request once their target is
+/// resolved.
+/// Gather the string values held under sensitive names in `value`, which is
+/// bound to `name`, including those nested in objects such as form data.
+pub(crate) fn collect_secrets(
+    name: &str,
+    value: &Value,
+    sensitive_fields: &[String],
+    out: &mut Vec<String>,
+) {
+    match value {
+        Value::Object(map) => {
+            for (key, nested) in map {
+                collect_secrets(key, nested, sensitive_fields, out);
+            }
+        }
+        Value::String(s) if !s.is_empty() && is_sensitive_field(name, sensitive_fields) => {
+            out.push(s.clone());
+        }
+        _ => {}
+    }
+}
+
+fn sends_single_request(action: &ActionType) -> bool {
+    matches!(
+        action,
+        ActionType::Click
+            | ActionType::Type
+            | ActionType::Wait
+            | ActionType::Select
+            | ActionType::Check
+            | ActionType::Uncheck
+            | ActionType::Clear
+            | ActionType::Scroll
+    )
+}
+
+/// The value `def` binds to: its argument, else its default.
+///
+/// A default of `$other`, naming another parameter, takes whatever `other`
+/// binds to. `chain` holds the parameters whose defaults led here, so a
+/// default that refers back to one of them is reported instead of looping.
+fn parameter_value(
+    defs: &[ParameterDef],
+    def: &ParameterDef,
+    params: &HashMap<String, Value>,
+    chain: &mut Vec<String>,
+) -> Result<Option<Value>, ExecutorError> {
+    if let Some(value) = params.get(&def.name) {
+        return Ok(Some(value.clone()));
+    }
+    let Some(default) = &def.default else {
+        return Ok(None);
+    };
+    let referenced = default
+        .as_str()
+        .and_then(|s| s.strip_prefix('$'))
+        .and_then(|name| defs.iter().find(|d| d.name == name));
+    let Some(referenced) = referenced else {
+        return Ok(Some(default.clone()));
+    };
+
+    chain.push(def.name.clone());
+    if chain.contains(&referenced.name) {
+        chain.push(referenced.name.clone());
+        return Err(ExecutorError::InvalidParameterValue {
+            name: chain[0].clone(),
+            reason: format!("default refers back to itself ({})", chain.join(" -> ")),
+        });
+    }
+    parameter_value(defs, referenced, params, chain)
+}
+
+/// Whether a failed step may succeed if run again.
+///
+/// Backend failures, resolution failures and timeouts can clear up as the
+/// page changes; bad parameters, untranslatable steps and unknown intents
+/// fail the same way on every attempt.
+pub fn is_retryable(err: &ExecutorError) -> bool {
+    matches!(
+        err,
+        ExecutorError::Backend(_) | ExecutorError::Resolution(_) | ExecutorError::StepTimedOut(_)
+    )
+}
+
+/// Delay before retrying after `attempt` failures: exponential backoff,
+/// spread by up to ±20% when the policy asks for jitter.
+fn retry_delay_ms(config: &RetryConfig, attempt: usize) -> u64 {
+    let base = config.delay_ms as f64 * config.backoff_multiplier.powi(attempt as i32 - 1);
+    if !config.jitter {
+        return base as u64;
+    }
+    // A freshly keyed hasher is random enough to decorrelate retries
+    // without pulling in an RNG.
+    let sample = RandomState::new().build_hasher().finish() as f64 / u64::MAX as f64;
+    (base * (0.8 + 0.4 * sample)) as u64
+}
+
+/// How long past its own timeout a wait may take before the executor gives
+/// up on the backend answering.
+pub(crate) const WAIT_TIMEOUT_GRACE_MS: u64 = 250;
+
+/// Runs a backend call, failing the step if it does not finish within `limit_ms`.
+async fn with_timeout<T>(
+    call: impl Future<Output = Result<T, BackendError>>,
+    limit_ms: u64,
+) -> Result<T, ExecutorError> {
+    let started = Instant::now();
+    match tokio::time::timeout(Duration::from_millis(limit_ms), call).await {
+        Ok(result) => Ok(result?),
+        Err(_) => Err(ExecutorError::StepTimedOut(format!(
+            "Backend call timed out after {}ms (limit {}ms)",
+            started.elapsed().as_millis(),
+            limit_ms
+        ))),
+    }
+}
+
+/// Translates a command built from a resolved step target into a scanner action.
+fn scanner_action(cmd: &Command) -> Result<ScannerAction, ExecutorError> {
+    match translator::translate(cmd)? {
+        Action::Scanner(action) => Ok(action),
+        other => Err(ExecutorError::StepFailed(format!(
+            "Expected a scanner action, got {:?}",
+            other
+        ))),
+    }
+}
+
+/// Builds a plain `type` command (no clear/enter) for a resolved target.
+fn type_command(target: &Target, text: String) -> Command {
+    Command::Type(ast::TypeCmd {
+        target: ast::Target::from_resolver_target(target),
+        text,
+        append: false,
+        enter: false,
+        delay: None,
+        clear: false,
+        then_tab: false,
+        timeout: None,
+        wait_after: None,
+        secret: false,
+    })
+}
+
+/// Interprets a step option as a boolean flag.
+fn option_flag(options: &HashMap<String, String>, key: &str) -> bool {
+    options.get(key).is_some_and(|v| v == "true")
+}
+
 /// Normalizes text for comparison by lowercasing and removing extra whitespace.
 fn normalize_text(s: &str) -> String {
     s.trim()
@@ -1283,7 +2039,11 @@ fn normalize_text(s: &str) -> String {
 
 /// Calculates a match score for a form field based on the key.
 /// Higher scores indicate better matches.
-fn score_form_field(element: &oryn_common::protocol::Element, key: &str) -> u32 {
+fn score_form_field(
+    element: &oryn_common::protocol::Element,
+    key: &str,
+    scoring: &ScoringConfig,
+) -> i32 {
     // Only consider form fields
     if !matches!(
         element.element_type.as_str(),
@@ -1293,29 +2053,27 @@ fn score_form_field(element: &oryn_common::protocol::Element, key: &str) -> u32
     }
 
     let key_normalized = normalize_text(key);
-    let mut score = 0u32;
+    let mut score = 0;
 
-    // Exact name match: 100 points
+    // Name and id matching
     if let Some(name) = element.attributes.get("name")
         && normalize_text(name) == key_normalized
     {
-        return 100;
+        score = score.max(scoring.name_exact);
     }
-
-    // Exact id match: 100 points
     if let Some(id) = element.attributes.get("id")
         && normalize_text(id) == key_normalized
     {
-        return 100;
+        score = score.max(scoring.id_exact);
     }
 
     // Label matching
     if let Some(label) = &element.label {
         let label_normalized = normalize_text(label);
         if label_normalized == key_normalized {
-            score = score.max(90); // Exact label: 90 points
+            score = score.max(scoring.label_exact);
         } else if label_normalized.contains(&key_normalized) {
-            score = score.max(45); // Label contains: 45 points
+            score = score.max(scoring.label_contains);
         }
     }
 
@@ -1323,9 +2081,9 @@ fn score_form_field(element: &oryn_common::protocol::Element, key: &str) -> u32
     if let Some(placeholder) = &element.placeholder {
         let ph_normalized = normalize_text(placeholder);
         if ph_normalized == key_normalized {
-            score = score.max(80); // Exact placeholder: 80 points
+            score = score.max(scoring.placeholder_exact);
         } else if ph_normalized.contains(&key_normalized) {
-            score = score.max(40); // Placeholder contains: 40 points
+            score = score.max(scoring.placeholder_contains);
         }
     }
 
@@ -1333,23 +2091,22 @@ fn score_form_field(element: &oryn_common::protocol::Element, key: &str) -> u32
     if let Some(aria) = element.attributes.get("aria-label") {
         let aria_normalized = normalize_text(aria);
         if aria_normalized == key_normalized {
-            score = score.max(85); // Exact ARIA label: 85 points
+            score = score.max(scoring.aria_label_exact);
         } else if aria_normalized.contains(&key_normalized) {
-            score = score.max(42); // ARIA label contains: 42 points
+            score = score.max(scoring.aria_label_contains);
         }
     }
 
-    // Semantic type matching: 75 points
-    let semantic_score = semantic_field_score(element, &key_normalized);
-    if semantic_score > 0 {
-        score = score.max(semantic_score);
+    // Semantic type matching
+    if semantic_field_match(element, &key_normalized) {
+        score = score.max(scoring.field_purpose);
     }
 
     score
 }
 
-/// Returns a score based on semantic matching between the key and input type/autocomplete.
-fn semantic_field_score(element: &oryn_common::protocol::Element, key_normalized: &str) -> u32 {
+/// Whether the element's input type or autocomplete hint means the key.
+fn semantic_field_match(element: &oryn_common::protocol::Element, key_normalized: &str) -> bool {
     let input_type = element
         .attributes
         .get("type")
@@ -1362,58 +2119,36 @@ fn semantic_field_score(element: &oryn_common::protocol::Element, key_normalized
 
     match key_normalized {
         "email" | "e-mail" | "email address" => {
-            if input_type == "email" || autocomplete.as_deref() == Some("email") {
-                return 75;
-            }
+            input_type == "email" || autocomplete.as_deref() == Some("email")
         }
         "password" | "pass" | "pwd" => {
-            if input_type == "password"
+            input_type == "password"
                 || autocomplete.as_deref() == Some("current-password")
                 || autocomplete.as_deref() == Some("new-password")
-            {
-                return 75;
-            }
         }
         "phone" | "telephone" | "phone number" | "tel" => {
-            if input_type == "tel" || autocomplete.as_deref() == Some("tel") {
-                return 75;
-            }
-        }
-        "username" | "user" | "login" => {
-            if autocomplete.as_deref() == Some("username") {
-                return 75;
-            }
-        }
-        "name" | "full name" | "your name" => {
-            if autocomplete.as_deref() == Some("name") {
-                return 75;
-            }
-        }
-        "first name" | "firstname" | "given name" => {
-            if autocomplete.as_deref() == Some("given-name") {
-                return 75;
-            }
+            input_type == "tel" || autocomplete.as_deref() == Some("tel")
         }
+        "username" | "user" | "login" => autocomplete.as_deref() == Some("username"),
+        "name" | "full name" | "your name" => autocomplete.as_deref() == Some("name"),
+        "first name" | "firstname" | "given name" => autocomplete.as_deref() == Some("given-name"),
         "last name" | "lastname" | "surname" | "family name" => {
-            if autocomplete.as_deref() == Some("family-name") {
-                return 75;
-            }
+            autocomplete.as_deref() == Some("family-name")
         }
-        _ => {}
+        _ => false,
     }
-
-    0
 }
 
 /// Finds the best matching form field for a given key from scan results.
 fn find_best_form_field<'a>(
     elements: &'a [oryn_common::protocol::Element],
     key: &str,
+    scoring: &ScoringConfig,
 ) -> Option<&'a oryn_common::protocol::Element> {
     elements
         .iter()
         .filter_map(|el| {
-            let score = score_form_field(el, key);
+            let score = score_form_field(el, key, scoring);
             (score > 0).then_some((el, score))
         })
         .max_by_key(|(_, score)| *score)
@@ -0,0 +1,40 @@
+use crate::backend::Backend;
+use crate::intent::executor::ExecutorError;
+use async_trait::async_trait;
+use oryn_common::intent::definition::ActionStep;
+use oryn_common::resolver::Target;
+use std::collections::HashMap;
+
+/// What a handler is given to carry out a step with.
+pub struct ActionContext<'a> {
+    pub backend: &'a mut dyn Backend,
+    /// The step's target, already resolved against the latest scan.
+    pub target: Option<Target>,
+    /// The step's options with `$variable` references substituted.
+    pub options: HashMap<String, String>,
+    /// Backend call limit for the step.
+    pub timeout_ms: u64,
+    pub(crate) logs: &'a mut Vec<String>,
+}
+
+impl ActionContext<'_> {
+    /// Add a line to the run's logs, where it is redacted like any other.
+    pub fn log(&mut self, line: impl Into<String>) {
+        self.logs.push(line.into());
+    }
+}
+
+/// Runs an action the engine does not define itself, such as `upload_file`.
+///
+/// Handlers are registered on a `CommandExecutor` or `IntentExecutor` under
+/// the action's name and receive every step whose `action` is that name.
+/// Built-in actions are always run by the engine, so a handler registered as
+/// `click` is never called.
+#[async_trait]
+pub trait ActionHandler: Send + Sync {
+    async fn handle(
+        &self,
+        ctx: &mut ActionContext<'_>,
+        step: &ActionStep,
+    ) -> Result<(), ExecutorError>;
+}
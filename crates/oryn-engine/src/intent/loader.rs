@@ -13,19 +13,64 @@ pub enum LoaderError {
     Glob(#[from] glob::PatternError),
     #[error("Serialization error: {0}")]
     Serialization(#[from] serde_yaml::Error),
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("Unknown intent file format '{0}' (expected yaml or json)")]
+    UnknownFormat(String),
     #[error("Validation error: {0}")]
     Validation(#[from] ValidationError),
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IntentFormat {
+    Yaml,
+    Json,
+}
+
+impl IntentFormat {
+    /// Pick the format from an explicit `--format` name, falling back to the
+    /// file extension: `.json` is JSON, anything else (or no file) YAML.
+    pub fn detect(path: Option<&str>, format: Option<&str>) -> Result<Self, LoaderError> {
+        match format.map(str::to_lowercase).as_deref() {
+            Some("yaml") | Some("yml") => Ok(Self::Yaml),
+            Some("json") => Ok(Self::Json),
+            Some(other) => Err(LoaderError::UnknownFormat(other.to_string())),
+            None if path.is_some_and(|p| p.to_lowercase().ends_with(".json")) => Ok(Self::Json),
+            None => Ok(Self::Yaml),
+        }
+    }
+}
+
 pub struct IntentLoader;
 
 impl IntentLoader {
+    /// Serialize an intent in the form `load_from_dir` reads back.
+    pub fn export(intent: &IntentDefinition, format: IntentFormat) -> Result<String, LoaderError> {
+        match format {
+            IntentFormat::Yaml => Ok(serde_yaml::to_string(intent)?),
+            IntentFormat::Json => Ok(serde_json::to_string_pretty(intent)? + "\n"),
+        }
+    }
+
+    pub fn parse(content: &str, format: IntentFormat) -> Result<IntentDefinition, LoaderError> {
+        match format {
+            IntentFormat::Yaml => Ok(serde_yaml::from_str(content)?),
+            IntentFormat::Json => Ok(serde_json::from_str(content)?),
+        }
+    }
+
+    /// Load every `*.yaml` and `*.json` intent under `path`, skipping (and
+    /// reporting) files that fail to read, parse or validate.
     pub fn load_from_dir(path: &Path, registry: &mut IntentRegistry) -> Result<usize, LoaderError> {
-        let pattern = path.join("**/*.yaml");
-        let pattern_str = pattern.to_str().unwrap_or("*.yaml");
         let mut count = 0;
+        let mut files = Vec::new();
+        for ext in ["yaml", "json"] {
+            let pattern = path.join(format!("**/*.{}", ext));
+            let pattern_str = pattern.to_str().unwrap_or("*.yaml");
+            files.extend(glob(pattern_str)?.flatten());
+        }
 
-        for file_path in glob(pattern_str)?.flatten() {
+        for file_path in files {
             let content = match fs::read_to_string(&file_path) {
                 Ok(c) => c,
                 Err(e) => {
@@ -34,7 +79,8 @@ impl IntentLoader {
                 }
             };
 
-            let intent = match serde_yaml::from_str::<IntentDefinition>(&content) {
+            let format = IntentFormat::detect(file_path.to_str(), None)?;
+            let intent = match Self::parse(&content, format) {
                 Ok(i) => {
                     if let Err(e) = i.validate() {
                         eprintln!("Failed to validate intent from {:?}: {}", file_path, e);
@@ -48,7 +94,11 @@ impl IntentLoader {
                 }
             };
 
-            if registry.register(intent) {
+            let (registered, warnings) = registry.register_checked(intent);
+            for warning in warnings {
+                eprintln!("Warning in intent {:?}: {}", file_path, warning);
+            }
+            if registered {
                 count += 1;
             }
         }
@@ -1,7 +1,56 @@
 use oryn_common::intent::definition::IntentDefinition;
 use oryn_common::intent::registry::IntentRegistry;
-use oryn_common::protocol::{DetectedPatterns, ScanResult};
-use std::collections::HashSet;
+use oryn_common::protocol::{AvailabilityStatus, DetectedPatterns, IntentAvailability, ScanResult};
+use oryn_core::ast::Command;
+use oryn_core::translator;
+use serde_json::{Value, json};
+use std::collections::{HashMap, HashSet};
+
+/// An intent to run and the parameters bound for it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IntentCall {
+    pub name: String,
+    pub params: HashMap<String, Value>,
+}
+
+/// Map an intent sugar command to the built-in intent it stands for:
+/// `login user "a" pass "b"` runs `login` with `username`/`password`, and
+/// `search "shoes"` runs `search` with `query`. A `--wait`/`--timeout` given
+/// on the command becomes the intent's `wait` (ms).
+///
+/// Returns `None` for other commands, and for `login --no-submit`, which the
+/// `login` intent cannot express and so stays with the scanner.
+pub fn sugar_intent(cmd: &Command) -> Option<IntentCall> {
+    let (name, mut params, wait, timeout) = match cmd {
+        Command::Login(login) if !login.no_submit => (
+            "login",
+            HashMap::from([
+                ("username".to_string(), json!(login.user)),
+                ("password".to_string(), json!(login.pass)),
+            ]),
+            &login.wait,
+            &login.timeout,
+        ),
+        Command::Search(search) => (
+            "search",
+            HashMap::from([("query".to_string(), json!(search.query))]),
+            &search.wait,
+            &search.timeout,
+        ),
+        _ => return None,
+    };
+    if let Some(ms) = wait
+        .as_deref()
+        .or(timeout.as_deref())
+        .and_then(translator::parse_duration_ms)
+    {
+        params.insert("wait".to_string(), json!(ms));
+    }
+    Some(IntentCall {
+        name: name.to_string(),
+        params,
+    })
+}
 
 pub struct IntentMapper<'a> {
     registry: &'a IntentRegistry,
@@ -34,6 +83,62 @@ impl<'a> IntentMapper<'a> {
         intents
     }
 
+    /// Whether `intent` can run on the scanned page: its URL triggers must
+    /// match the page and every pattern it triggers on must be detected.
+    pub fn availability(&self, intent: &IntentDefinition, scan: &ScanResult) -> IntentAvailability {
+        let mut status = AvailabilityStatus::Ready;
+        let mut reason = None;
+
+        // 1. Check URL RegEx (Any match is sufficient)
+        if !intent.triggers.urls.is_empty() {
+            let mut url_matched = false;
+            for url_pattern in &intent.triggers.urls {
+                match url_trigger_regex(url_pattern) {
+                    Ok(re) => {
+                        if re.is_match(&scan.page.url) {
+                            url_matched = true;
+                            break;
+                        }
+                    }
+                    Err(_) => {
+                        reason = Some(format!("Invalid URL regex: {}", url_pattern));
+                    }
+                }
+            }
+
+            if !url_matched && reason.is_none() {
+                status = AvailabilityStatus::NavigateRequired;
+            } else if !url_matched {
+                status = AvailabilityStatus::Unavailable;
+            }
+        }
+
+        // 2. Check Patterns
+        // Only check patterns if URL is fine (Ready so far)
+        if status == AvailabilityStatus::Ready {
+            if let Some(patterns) = &scan.patterns {
+                for required_pattern in &intent.triggers.patterns {
+                    if !patterns.has(required_pattern) {
+                        status = AvailabilityStatus::MissingPattern;
+                        reason = Some(format!("Missing pattern: {}", required_pattern));
+                        break;
+                    }
+                }
+            } else if !intent.triggers.patterns.is_empty() {
+                // No patterns detected but intent requires some
+                status = AvailabilityStatus::MissingPattern;
+                reason = Some("No patterns detected on page".to_string());
+            }
+        }
+
+        IntentAvailability {
+            name: intent.name.clone(),
+            status,
+            parameters: intent.parameters.iter().map(|p| p.name.clone()).collect(),
+            trigger_reason: reason,
+        }
+    }
+
     fn extract_pattern_keys(&self, patterns: &DetectedPatterns) -> Vec<String> {
         let mut keys = Vec::new();
         if patterns.login.is_some() {
@@ -54,3 +159,15 @@ impl<'a> IntentMapper<'a> {
         keys
     }
 }
+
+/// URL triggers are regexes, but built-in intents also use globs such as
+/// `*/login`, which do not compile as one; those match as globs instead.
+fn url_trigger_regex(pattern: &str) -> Result<regex::Regex, regex::Error> {
+    regex::Regex::new(pattern).or_else(|err| {
+        if !pattern.contains('*') {
+            return Err(err);
+        }
+        let glob: Vec<String> = pattern.split('*').map(regex::escape).collect();
+        regex::Regex::new(&format!("^{}$", glob.join(".*")))
+    })
+}
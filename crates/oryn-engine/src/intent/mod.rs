@@ -1,5 +1,6 @@
 pub mod builtin;
 pub mod executor;
+pub mod handler;
 pub mod loader;
 pub mod mapper;
 pub mod schema;
@@ -1,4 +1,6 @@
-use oryn_common::intent::definition::{FlowDefinition, IntentDefinition, PageTransition, Step};
+use oryn_common::intent::definition::{
+    ActionType, FlowDefinition, IntentDefinition, PageTransition, Step,
+};
 use std::collections::HashSet;
 use thiserror::Error;
 
@@ -14,6 +16,8 @@ pub enum ValidationError {
     DuplicateParameter(String),
     #[error("Invalid step: {0}")]
     InvalidStep(String),
+    #[error("Unknown action '{0}' (did you mean '{1}'?)")]
+    MisspelledAction(String, String),
     #[error("Flow must have at least one page")]
     FlowNoPages,
     #[error("Duplicate page name in flow: {0}")]
@@ -127,6 +131,16 @@ impl Validatable for FlowDefinition {
 impl Validatable for Step {
     fn validate(&self) -> Result<(), ValidationError> {
         match self {
+            Step::Action(step) => {
+                if let (ActionType::Custom(name), Some(builtin)) =
+                    (&step.action, step.action.near_miss())
+                {
+                    return Err(ValidationError::MisspelledAction(
+                        name.clone(),
+                        builtin.to_string(),
+                    ));
+                }
+            }
             Step::Branch(wrapper) => {
                 if wrapper.branch.then_steps.is_empty() && wrapper.branch.else_steps.is_empty() {
                     return Err(ValidationError::InvalidStep(
@@ -163,6 +177,16 @@ impl Validatable for Step {
                     s.validate()?;
                 }
             }
+            Step::Parallel(wrapper) => {
+                if wrapper.parallel.steps.is_empty() {
+                    return Err(ValidationError::InvalidStep(
+                        "Parallel block must have steps".into(),
+                    ));
+                }
+                for s in &wrapper.parallel.steps {
+                    s.validate()?;
+                }
+            }
             _ => {}
         }
         Ok(())
@@ -39,13 +39,19 @@ impl SessionIntentManager {
         }
     }
 
-    pub fn define(&mut self, mut definition: IntentDefinition) -> Result<(), SessionError> {
+    /// Add a session intent. Returns warnings about its triggers (e.g. misspelled
+    /// pattern names) so the caller can show them to the user.
+    pub fn define(
+        &mut self,
+        mut definition: IntentDefinition,
+    ) -> Result<Vec<String>, SessionError> {
         if self.intents.contains_key(&definition.name) {
             return Err(SessionError::AlreadyExists(definition.name.clone()));
         }
 
         // Ensure tier is Discovered (or Session specific?)
         definition.tier = IntentTier::Discovered;
+        let warnings = definition.triggers.pattern_warnings();
 
         self.intents.insert(
             definition.name.clone(),
@@ -55,7 +61,7 @@ impl SessionIntentManager {
                 invocation_count: 0,
             },
         );
-        Ok(())
+        Ok(warnings)
     }
 
     pub fn undefine(&mut self, name: &str) -> Result<(), SessionError> {
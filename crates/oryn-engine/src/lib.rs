@@ -2,6 +2,8 @@ pub mod backend;
 pub mod cli;
 pub mod config;
 pub mod executor;
+pub mod intent;
+pub mod registry;
 pub mod resolution;
 
 pub use oryn_common::error_mapping;
@@ -0,0 +1,98 @@
+use crate::backend::{Backend, BackendError};
+use std::collections::{BTreeMap, HashMap};
+use std::str::FromStr;
+
+/// Settings handed to a backend factory, keyed by option name
+/// (`visible`, `port`, ...). Values are kept as strings so any front end can
+/// fill them in; factories parse the ones they care about.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BackendOptions {
+    values: HashMap<String, String>,
+}
+
+impl BackendOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set(&mut self, key: impl Into<String>, value: impl Into<String>) {
+        self.values.insert(key.into(), value.into());
+    }
+
+    pub fn with(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.set(key, value);
+        self
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.values.get(key).map(String::as_str)
+    }
+
+    /// A boolean switch: present and not `false`.
+    pub fn flag(&self, key: &str) -> bool {
+        self.get(key).is_some_and(|v| v != "false")
+    }
+
+    /// Parse an option, falling back to `default` when it is absent.
+    pub fn parse_or<T: FromStr>(&self, key: &str, default: T) -> Result<T, BackendError>
+    where
+        T::Err: std::fmt::Display,
+    {
+        match self.get(key) {
+            Some(raw) => raw.parse().map_err(|e| {
+                BackendError::InvalidRequest(format!("invalid value for {}: {} ({})", key, raw, e))
+            }),
+            None => Ok(default),
+        }
+    }
+}
+
+/// Builds a backend from its options.
+pub type BackendFactory =
+    Box<dyn Fn(&BackendOptions) -> Result<Box<dyn Backend>, BackendError> + Send + Sync>;
+
+/// Backend constructors keyed by mode name, so a front end can offer every
+/// registered backend without knowing their types.
+#[derive(Default)]
+pub struct BackendRegistry {
+    factories: BTreeMap<String, BackendFactory>,
+}
+
+impl BackendRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a factory under `name`, replacing any earlier one.
+    pub fn register<F>(&mut self, name: impl Into<String>, factory: F)
+    where
+        F: Fn(&BackendOptions) -> Result<Box<dyn Backend>, BackendError> + Send + Sync + 'static,
+    {
+        self.factories.insert(name.into(), Box::new(factory));
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.factories.contains_key(name)
+    }
+
+    /// Registered mode names, sorted.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.factories.keys().map(String::as_str)
+    }
+
+    /// Construct the backend registered under `name`.
+    pub fn create(
+        &self,
+        name: &str,
+        options: &BackendOptions,
+    ) -> Result<Box<dyn Backend>, BackendError> {
+        match self.factories.get(name) {
+            Some(factory) => factory(options),
+            None => Err(BackendError::NotSupported(format!(
+                "backend '{}' (available: {})",
+                name,
+                self.names().collect::<Vec<_>>().join(", ")
+            ))),
+        }
+    }
+}
@@ -85,7 +85,7 @@ impl<'a, B: Backend + ?Sized + Send + Sync> SelectorResolver for BackendSelector
 
         let req = ScannerAction::Execute(ExecuteRequest {
             script,
-            args: vec![],
+            args: serde_json::Value::Null,
         });
 
         match self.backend.execute_scanner(req).await {
@@ -7,6 +7,7 @@ use super::BackendSelectorResolver;
 use super::result::ResolutionError;
 use crate::backend::Backend;
 use oryn_common::protocol::ScanResult;
+use oryn_common::resolver::ScoringConfig;
 use oryn_core::ast;
 use oryn_core::resolution::ResolutionEngine as CoreEngine;
 
@@ -23,4 +24,15 @@ impl ResolutionEngine {
         let mut resolver = BackendSelectorResolver::new(backend);
         CoreEngine::resolve(cmd, scan, &mut resolver).await
     }
+
+    /// Like [`Self::resolve`], scoring text matches with `scoring`.
+    pub async fn resolve_with_scoring<B: Backend + ?Sized>(
+        cmd: ast::Command,
+        scan: &ScanResult,
+        scoring: ScoringConfig,
+        backend: &mut B,
+    ) -> Result<ast::Command, ResolutionError> {
+        let mut resolver = BackendSelectorResolver::new(backend);
+        CoreEngine::resolve_with_scoring(cmd, scan, scoring, &mut resolver).await
+    }
 }
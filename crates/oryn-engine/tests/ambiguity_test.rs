@@ -0,0 +1,377 @@
+//! Ambiguous target tests.
+//!
+//! These tests verify how the executor lists the candidates of a target that
+//! matches several elements and acts on the one a disambiguator picks.
+
+use async_trait::async_trait;
+use oryn_engine::backend::{Backend, BackendError, NavigationResult};
+use oryn_engine::executor::{
+    AmbiguityCandidate, CommandExecutor, Disambiguator, ExecutorError, format_candidates,
+};
+use oryn_engine::protocol::{
+    ActionResult, Element, ElementState, PageInfo, Rect, ScanResult, ScanStats, ScannerAction,
+    ScannerData, ScannerProtocolResponse, ScrollInfo, ViewportInfo,
+};
+use oryn_engine::resolver::ResolverError;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+fn make_button(id: u32, text: &str, selector: &str, y: f32) -> Element {
+    Element {
+        id,
+        element_type: "button".into(),
+        role: Some("button".into()),
+        text: Some(text.into()),
+        label: None,
+        value: None,
+        placeholder: None,
+        selector: selector.into(),
+        xpath: None,
+        rect: Rect {
+            x: 0.0,
+            y,
+            width: 80.0,
+            height: 30.0,
+        },
+        attributes: HashMap::new(),
+        state: ElementState::default(),
+        children: vec![],
+        count: None,
+        tab_index: None,
+        focusable: false,
+    }
+}
+
+/// A page with a "Save" button and a "Save" link that records which ids get clicked.
+#[derive(Default)]
+struct TwoSavesBackend {
+    clicked: Vec<u32>,
+    /// Ids of the saves rendered disabled.
+    disabled: Vec<u32>,
+    /// Extra elements rendered ahead of the two saves.
+    before: Vec<Element>,
+}
+
+#[async_trait]
+impl Backend for TwoSavesBackend {
+    async fn launch(&mut self) -> Result<(), BackendError> {
+        Ok(())
+    }
+    async fn close(&mut self) -> Result<(), BackendError> {
+        Ok(())
+    }
+    async fn is_ready(&self) -> bool {
+        true
+    }
+    async fn navigate(&mut self, url: &str) -> Result<NavigationResult, BackendError> {
+        Ok(NavigationResult {
+            url: url.to_string(),
+            ..Default::default()
+        })
+    }
+    async fn screenshot(&mut self) -> Result<Vec<u8>, BackendError> {
+        Ok(vec![])
+    }
+    async fn execute_scanner(
+        &mut self,
+        command: ScannerAction,
+    ) -> Result<ScannerProtocolResponse, BackendError> {
+        let data = match command {
+            ScannerAction::Click(req) => {
+                self.clicked.extend(req.id);
+                ScannerData::Action(ActionResult {
+                    success: true,
+                    message: None,
+                    navigation: None,
+                    dom_changes: None,
+                    value: None,
+                    coordinates: None,
+                })
+            }
+            _ => {
+                // A link rather than a second button, so `observe` does not
+                // collapse the two into one entry.
+                let mut link = make_button(7, "Save", "a.save", 400.0);
+                link.element_type = "a".into();
+                link.role = Some("link".into());
+                let mut elements = self.before.clone();
+                elements.extend([make_button(3, "Save", "#save-top", 0.0), link]);
+                for element in &mut elements {
+                    element.state.disabled = self.disabled.contains(&element.id);
+                }
+                ScannerData::Scan(Box::new(ScanResult {
+                    page: PageInfo {
+                        url: "https://example.com/edit".into(),
+                        title: "Edit".into(),
+                        viewport: ViewportInfo::default(),
+                        scroll: ScrollInfo::default(),
+                        ready_state: None,
+                    },
+                    stats: ScanStats {
+                        total: elements.len(),
+                        scanned: elements.len(),
+                        iframes: None,
+                    },
+                    elements,
+                    patterns: None,
+                    changes: None,
+                    available_intents: None,
+                    full_mode: false,
+                    settings_applied: None,
+                    timing: None,
+                }))
+            }
+        };
+        Ok(ScannerProtocolResponse::Ok {
+            data: Box::new(data),
+            warnings: vec![],
+        })
+    }
+}
+
+/// The save page with a second "Save" button at its foot, which `click`
+/// ranks level with the top one. The link ranks below both.
+fn two_save_buttons() -> TwoSavesBackend {
+    TwoSavesBackend {
+        before: vec![make_button(5, "Save", "#save-footer", 800.0)],
+        ..Default::default()
+    }
+}
+
+/// Answers with a fixed 1-based choice and remembers what it was shown.
+struct ScriptedDisambiguator {
+    choice: Option<usize>,
+    seen: Arc<Mutex<Vec<AmbiguityCandidate>>>,
+}
+
+#[async_trait]
+impl Disambiguator for ScriptedDisambiguator {
+    async fn choose(&mut self, _target: &str, candidates: &[AmbiguityCandidate]) -> Option<u32> {
+        *self.seen.lock().unwrap() = candidates.to_vec();
+        self.choice.map(|n| candidates[n - 1].id)
+    }
+}
+
+#[test]
+fn test_format_candidates_lists_id_text_and_selector() {
+    let candidates = vec![
+        AmbiguityCandidate {
+            id: 3,
+            text: "Save".into(),
+            selector: "#save-top".into(),
+        },
+        AmbiguityCandidate {
+            id: 7,
+            text: "Save draft".into(),
+            selector: "form > button.secondary".into(),
+        },
+    ];
+
+    assert_eq!(
+        format_candidates("Save", &candidates),
+        "Ambiguous target \"Save\" matches 2 elements:\n\
+         \x20 1) [3] \"Save\" #save-top\n\
+         \x20 2) [7] \"Save draft\" form > button.secondary"
+    );
+}
+
+#[test]
+fn test_candidate_text_falls_back_and_truncates() {
+    let mut unlabeled = make_button(1, "", "#q", 0.0);
+    unlabeled.text = None;
+    unlabeled.placeholder = Some("Search".into());
+    assert_eq!(AmbiguityCandidate::from_element(&unlabeled).text, "Search");
+
+    let long = make_button(2, &"x".repeat(60), "#long", 0.0);
+    let text = AmbiguityCandidate::from_element(&long).text;
+    assert_eq!(text.chars().count(), 41);
+    assert!(text.ends_with('…'));
+}
+
+#[tokio::test]
+async fn test_disambiguator_choice_is_clicked() {
+    let mut backend = two_save_buttons();
+    let mut executor = CommandExecutor::new();
+    let seen = Arc::new(Mutex::new(vec![]));
+    executor.set_disambiguator(Some(Box::new(ScriptedDisambiguator {
+        choice: Some(2),
+        seen: seen.clone(),
+    })));
+
+    executor
+        .execute_line(&mut backend, "observe")
+        .await
+        .unwrap();
+    executor
+        .execute_line(&mut backend, "click \"Save\"")
+        .await
+        .unwrap();
+
+    let ids: Vec<u32> = seen.lock().unwrap().iter().map(|c| c.id).collect();
+    assert_eq!(ids, vec![3, 5]);
+    assert_eq!(backend.clicked, vec![5]);
+}
+
+#[tokio::test]
+async fn test_declined_choice_reports_ambiguity() {
+    let mut backend = two_save_buttons();
+    let mut executor = CommandExecutor::new();
+    executor.set_disambiguator(Some(Box::new(ScriptedDisambiguator {
+        choice: None,
+        seen: Arc::default(),
+    })));
+
+    executor
+        .execute_line(&mut backend, "observe")
+        .await
+        .unwrap();
+    let err = executor
+        .execute_line(&mut backend, "click \"Save\"")
+        .await
+        .err()
+        .unwrap();
+
+    assert!(matches!(
+        err,
+        ExecutorError::Resolver(ResolverError::AmbiguousMatch { count: 2, .. })
+    ));
+    assert!(backend.clicked.is_empty());
+}
+
+#[tokio::test]
+async fn test_without_disambiguator_ambiguity_is_not_asked() {
+    let mut backend = two_save_buttons();
+    let mut executor = CommandExecutor::new();
+
+    executor
+        .execute_line(&mut backend, "observe")
+        .await
+        .unwrap();
+    executor
+        .execute_line(&mut backend, "click \"Save\"")
+        .await
+        .unwrap();
+
+    assert_eq!(backend.clicked, vec![3]);
+}
+
+#[tokio::test]
+async fn test_only_candidates_the_command_ranks_level_are_offered() {
+    let mut heading = make_button(1, "Save", "h2", 200.0);
+    heading.element_type = "h2".into();
+    heading.role = Some("heading".into());
+    let mut backend = TwoSavesBackend {
+        before: vec![heading],
+        ..Default::default()
+    };
+    let mut executor = CommandExecutor::new();
+    let seen = Arc::new(Mutex::new(vec![]));
+    executor.set_disambiguator(Some(Box::new(ScriptedDisambiguator {
+        choice: Some(2),
+        seen: seen.clone(),
+    })));
+
+    executor
+        .execute_line(&mut backend, "observe")
+        .await
+        .unwrap();
+    executor
+        .execute_line(&mut backend, "click \"Save\"")
+        .await
+        .unwrap();
+
+    // The button outranks the link and the heading for a click.
+    assert!(seen.lock().unwrap().is_empty());
+    assert_eq!(backend.clicked, vec![3]);
+}
+
+#[tokio::test]
+async fn test_state_filter_leaves_one_candidate() {
+    let mut backend = TwoSavesBackend {
+        disabled: vec![3],
+        ..Default::default()
+    };
+    let mut executor = CommandExecutor::new();
+    let seen = Arc::new(Mutex::new(vec![]));
+    executor.set_disambiguator(Some(Box::new(ScriptedDisambiguator {
+        choice: Some(1),
+        seen: seen.clone(),
+    })));
+
+    executor
+        .execute_line(&mut backend, "observe")
+        .await
+        .unwrap();
+    executor
+        .execute_line(&mut backend, "click \"Save\" --enabled")
+        .await
+        .unwrap();
+
+    assert!(
+        seen.lock().unwrap().is_empty(),
+        "nothing left to choose from"
+    );
+    assert_eq!(backend.clicked, vec![7]);
+}
+
+#[tokio::test]
+async fn test_resolve_lists_candidates_by_descending_score() {
+    let mut backend = TwoSavesBackend {
+        before: vec![make_button(1, "Save draft", "#save-draft", 200.0)],
+        ..Default::default()
+    };
+    let mut executor = CommandExecutor::new();
+    executor.set_dry_run(true);
+
+    let output = executor
+        .execute_line(&mut backend, "resolve \"Save\"")
+        .await
+        .unwrap()
+        .output;
+
+    assert!(output.starts_with("3 candidates"), "{}", output);
+    let scores: Vec<i32> = output
+        .lines()
+        .skip(1)
+        .map(|line| {
+            let score = line.split("score=").nth(1).unwrap();
+            score.split_whitespace().next().unwrap().parse().unwrap()
+        })
+        .collect();
+    assert_eq!(scores.len(), 3);
+    assert!(scores.windows(2).all(|w| w[0] >= w[1]), "{:?}", scores);
+    assert!(scores[0] > scores[2], "{:?}", scores);
+
+    // The partial match ranks last despite coming first in the page.
+    let last = output.lines().last().unwrap();
+    assert!(last.contains("[1]"), "{}", output);
+    assert!(last.contains("\"Save draft\" #save-draft"), "{}", output);
+    assert!(output.contains("[3] score="), "{}", output);
+    assert!(output.contains("link \"Save\" a.save"), "{}", output);
+    assert!(backend.clicked.is_empty());
+}
+
+#[tokio::test]
+async fn test_resolve_ranks_candidates_as_click_would() {
+    let mut heading = make_button(1, "Save", "h2", 200.0);
+    heading.element_type = "h2".into();
+    heading.role = Some("heading".into());
+    let mut backend = TwoSavesBackend {
+        before: vec![heading],
+        ..Default::default()
+    };
+    let mut executor = CommandExecutor::new();
+    executor.set_dry_run(true);
+
+    let output = executor
+        .execute_line(&mut backend, "resolve \"Save\"")
+        .await
+        .unwrap()
+        .output;
+
+    // The heading comes first in the page, but click prefers the button.
+    let first = output.lines().nth(1).unwrap();
+    assert!(first.contains("[3]"), "{}", output);
+    let last = output.lines().last().unwrap();
+    assert!(last.contains("[1]"), "{}", output);
+}
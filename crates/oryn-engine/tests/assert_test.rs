@@ -0,0 +1,191 @@
+//! Assertion tests.
+//!
+//! These tests verify that `assert` checks a fresh scan of the page, reports
+//! the check when it holds, and fails the line, and a script run with
+//! `stop_on_error`, when it does not.
+
+use async_trait::async_trait;
+use oryn_engine::backend::{Backend, BackendError, NavigationResult};
+use oryn_engine::cli::{self, FileErrorMode, FileOptions, OutputHandlers, OutputMode};
+use oryn_engine::executor::{CommandExecutor, ExecutorError};
+use oryn_engine::protocol::{
+    Element, ElementState, PageInfo, Rect, ScanResult, ScanStats, ScannerAction, ScannerData,
+    ScannerProtocolResponse, ScrollInfo, ViewportInfo,
+};
+use std::collections::HashMap;
+
+/// A checkout page showing a greeting and an order total.
+#[derive(Default)]
+struct CheckoutBackend {
+    navigated: bool,
+}
+
+fn element(id: u32, text: &str, label: &str) -> Element {
+    Element {
+        id,
+        element_type: "span".into(),
+        role: None,
+        text: Some(text.into()),
+        label: Some(label.into()),
+        value: None,
+        placeholder: None,
+        selector: format!("#{}", label.to_lowercase()),
+        xpath: None,
+        rect: Rect {
+            x: 0.0,
+            y: 20.0 * id as f32,
+            width: 120.0,
+            height: 20.0,
+        },
+        attributes: HashMap::new(),
+        state: ElementState::default(),
+        children: vec![],
+        count: None,
+        tab_index: None,
+        focusable: false,
+    }
+}
+
+#[async_trait]
+impl Backend for CheckoutBackend {
+    async fn launch(&mut self) -> Result<(), BackendError> {
+        Ok(())
+    }
+    async fn close(&mut self) -> Result<(), BackendError> {
+        Ok(())
+    }
+    async fn is_ready(&self) -> bool {
+        true
+    }
+    async fn navigate(&mut self, url: &str) -> Result<NavigationResult, BackendError> {
+        self.navigated = true;
+        Ok(NavigationResult {
+            url: url.to_string(),
+            ..Default::default()
+        })
+    }
+    async fn screenshot(&mut self) -> Result<Vec<u8>, BackendError> {
+        Ok(vec![])
+    }
+    async fn execute_scanner(
+        &mut self,
+        command: ScannerAction,
+    ) -> Result<ScannerProtocolResponse, BackendError> {
+        if !matches!(command, ScannerAction::Scan(_)) {
+            return Err(BackendError::NotSupported(format!("{:?}", command)));
+        }
+        Ok(ScannerProtocolResponse::Ok {
+            data: Box::new(ScannerData::Scan(Box::new(ScanResult {
+                page: PageInfo {
+                    url: "https://shop.example.com/checkout/review".into(),
+                    title: "Review order".into(),
+                    viewport: ViewportInfo::default(),
+                    scroll: ScrollInfo::default(),
+                    ready_state: None,
+                },
+                elements: vec![
+                    element(1, "Welcome back, Ada", "Welcome"),
+                    element(2, "Total: $42.00", "Total"),
+                ],
+                stats: ScanStats {
+                    total: 2,
+                    scanned: 2,
+                    iframes: None,
+                },
+                patterns: None,
+                changes: None,
+                available_intents: None,
+                full_mode: false,
+                settings_applied: None,
+                timing: None,
+            }))),
+            warnings: vec![],
+        })
+    }
+}
+
+#[tokio::test]
+async fn test_passing_assertions_report_what_they_checked() {
+    let mut backend = CheckoutBackend::default();
+    let mut executor = CommandExecutor::new();
+
+    for (line, expected) in [
+        (
+            r#"assert visible "Welcome""#,
+            r#"Assertion passed: visible "Welcome""#,
+        ),
+        (
+            r#"assert text "Total" contains "42""#,
+            r#"Assertion passed: text "Total" contains "42""#,
+        ),
+        (
+            r#"assert url matches "/checkout""#,
+            r#"Assertion passed: url matches "/checkout""#,
+        ),
+    ] {
+        let result = executor.execute_line(&mut backend, line).await.unwrap();
+        assert_eq!(result.output, expected);
+    }
+}
+
+#[tokio::test]
+async fn test_failing_assertions_say_what_the_page_showed() {
+    let mut backend = CheckoutBackend::default();
+    let mut executor = CommandExecutor::new();
+
+    for (line, expected) in [
+        (
+            r#"assert visible "Sign out""#,
+            r#"visible "Sign out": no matching element on the page"#,
+        ),
+        (
+            r#"assert text "Total" contains "99""#,
+            r#"text "Total" contains "99": element text is "Total: $42.00""#,
+        ),
+        (
+            r#"assert url matches "/cart$""#,
+            r#"url matches "/cart$": page is at https://shop.example.com/checkout/review"#,
+        ),
+    ] {
+        match executor.execute_line(&mut backend, line).await {
+            Err(ExecutorError::AssertionFailed(msg)) => assert_eq!(msg, expected),
+            other => panic!(
+                "Expected {} to fail, got {:?}",
+                line,
+                other.map(|r| r.output)
+            ),
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_failing_assertion_stops_a_script() {
+    let path = std::env::temp_dir().join("oryn_assert_stops.oil");
+    std::fs::write(
+        &path,
+        "assert visible \"Welcome\"\nassert visible \"Sign out\"\ngoto https://example.com\n",
+    )
+    .unwrap();
+
+    let mut backend = CheckoutBackend::default();
+    let mut executor = CommandExecutor::new();
+    let result = cli::run_file(
+        &mut backend,
+        &mut executor,
+        OutputHandlers {
+            out: |_| {},
+            err: |_| {},
+        },
+        OutputMode::Human,
+        path.to_str().unwrap(),
+        FileOptions {
+            stop_on_error: true,
+            error_mode: FileErrorMode::Plain,
+        },
+    )
+    .await;
+    std::fs::remove_file(&path).ok();
+
+    assert!(result.is_err());
+    assert!(!backend.navigated, "the script should stop at the failure");
+}
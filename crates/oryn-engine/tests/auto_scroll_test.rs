@@ -0,0 +1,194 @@
+//! Auto-scroll tests.
+//!
+//! These tests verify that an element the scan placed outside the viewport is
+//! scrolled into view before it is clicked, and left alone otherwise.
+
+use async_trait::async_trait;
+use oryn_engine::backend::{Backend, BackendError, NavigationResult};
+use oryn_engine::executor::CommandExecutor;
+use oryn_engine::protocol::{
+    ActionResult, Element, ElementState, PageInfo, Rect, ScanResult, ScanStats, ScannerAction,
+    ScannerData, ScannerProtocolResponse, ScrollDirection, ScrollInfo, ViewportInfo,
+};
+use std::collections::HashMap;
+
+fn make_button(id: u32, text: &str, y: f32) -> Element {
+    Element {
+        id,
+        element_type: "button".into(),
+        role: Some("button".into()),
+        text: Some(text.into()),
+        label: None,
+        value: None,
+        placeholder: None,
+        selector: format!("#b{}", id),
+        xpath: None,
+        rect: Rect {
+            x: 20.0,
+            y,
+            width: 80.0,
+            height: 30.0,
+        },
+        attributes: HashMap::new(),
+        state: ElementState::default(),
+        children: vec![],
+        count: None,
+        tab_index: None,
+        focusable: true,
+    }
+}
+
+/// An 800x600 viewport with "Top" in view and "Footer" far below the fold.
+/// Records every scanner request it is sent.
+#[derive(Default)]
+struct LongPageBackend {
+    requests: Vec<ScannerAction>,
+}
+
+#[async_trait]
+impl Backend for LongPageBackend {
+    async fn launch(&mut self) -> Result<(), BackendError> {
+        Ok(())
+    }
+    async fn close(&mut self) -> Result<(), BackendError> {
+        Ok(())
+    }
+    async fn is_ready(&self) -> bool {
+        true
+    }
+    async fn navigate(&mut self, url: &str) -> Result<NavigationResult, BackendError> {
+        Ok(NavigationResult {
+            url: url.to_string(),
+            ..Default::default()
+        })
+    }
+    async fn screenshot(&mut self) -> Result<Vec<u8>, BackendError> {
+        Ok(vec![])
+    }
+    async fn execute_scanner(
+        &mut self,
+        command: ScannerAction,
+    ) -> Result<ScannerProtocolResponse, BackendError> {
+        let data = match &command {
+            ScannerAction::Scan(_) => {
+                let elements = vec![
+                    make_button(1, "Top", 100.0),
+                    make_button(2, "Footer", 2400.0),
+                ];
+                ScannerData::Scan(Box::new(ScanResult {
+                    page: PageInfo {
+                        url: "https://example.com/long".into(),
+                        title: "Long".into(),
+                        viewport: ViewportInfo {
+                            width: 800,
+                            height: 600,
+                            scale: 1.0,
+                        },
+                        scroll: ScrollInfo::default(),
+                        ready_state: None,
+                    },
+                    stats: ScanStats {
+                        total: elements.len(),
+                        scanned: elements.len(),
+                        iframes: None,
+                    },
+                    elements,
+                    patterns: None,
+                    changes: None,
+                    available_intents: None,
+                    full_mode: false,
+                    settings_applied: None,
+                    timing: None,
+                }))
+            }
+            _ => ScannerData::Action(ActionResult {
+                success: true,
+                message: None,
+                navigation: None,
+                dom_changes: None,
+                value: None,
+                coordinates: None,
+            }),
+        };
+        self.requests.push(command);
+        Ok(ScannerProtocolResponse::Ok {
+            data: Box::new(data),
+            warnings: vec![],
+        })
+    }
+}
+
+/// The requests sent after the initial `observe`, ignoring re-scans.
+fn actions(backend: &LongPageBackend) -> Vec<&ScannerAction> {
+    backend
+        .requests
+        .iter()
+        .skip(1)
+        .filter(|req| !matches!(req, ScannerAction::Scan(_)))
+        .collect()
+}
+
+#[tokio::test]
+async fn test_offscreen_target_is_scrolled_into_view_before_click() {
+    let mut backend = LongPageBackend::default();
+    let mut executor = CommandExecutor::new();
+
+    executor
+        .execute_line(&mut backend, "observe")
+        .await
+        .unwrap();
+    executor
+        .execute_line(&mut backend, "click \"Footer\"")
+        .await
+        .unwrap();
+
+    let actions = actions(&backend);
+    assert_eq!(actions.len(), 2, "{:?}", actions);
+    match actions[0] {
+        ScannerAction::Scroll(req) => {
+            assert_eq!(req.id, Some(2));
+            assert!(matches!(req.direction, ScrollDirection::IntoView));
+        }
+        other => panic!("Expected a scroll first, got {:?}", other),
+    }
+    assert!(matches!(actions[1], ScannerAction::Click(req) if req.id == Some(2)));
+}
+
+#[tokio::test]
+async fn test_onscreen_target_is_clicked_without_scrolling() {
+    let mut backend = LongPageBackend::default();
+    let mut executor = CommandExecutor::new();
+
+    executor
+        .execute_line(&mut backend, "observe")
+        .await
+        .unwrap();
+    executor
+        .execute_line(&mut backend, "click \"Top\"")
+        .await
+        .unwrap();
+
+    let actions = actions(&backend);
+    assert_eq!(actions.len(), 1, "{:?}", actions);
+    assert!(matches!(actions[0], ScannerAction::Click(req) if req.id == Some(1)));
+}
+
+#[tokio::test]
+async fn test_auto_scroll_can_be_turned_off() {
+    let mut backend = LongPageBackend::default();
+    let mut executor = CommandExecutor::new();
+    executor.set_auto_scroll(false);
+
+    executor
+        .execute_line(&mut backend, "observe")
+        .await
+        .unwrap();
+    executor
+        .execute_line(&mut backend, "click \"Footer\"")
+        .await
+        .unwrap();
+
+    let actions = actions(&backend);
+    assert_eq!(actions.len(), 1, "{:?}", actions);
+    assert!(matches!(actions[0], ScannerAction::Click(_)));
+}
@@ -0,0 +1,135 @@
+//! CLI output channel tests.
+//!
+//! These tests verify that a failing command's error is kept off the results
+//! channel in human mode, and that `--json` mode writes every result and
+//! error as one tagged JSON object per line on it.
+
+use async_trait::async_trait;
+use oryn_engine::backend::{Backend, BackendError, NavigationResult};
+use oryn_engine::cli::{self, FileErrorMode, FileOptions, OutputHandlers, OutputMode};
+use oryn_engine::executor::CommandExecutor;
+use oryn_engine::protocol::{ScannerAction, ScannerProtocolResponse};
+use serde_json::Value;
+use std::sync::Mutex;
+
+/// A backend that can only navigate.
+struct NavigateOnlyBackend;
+
+#[async_trait]
+impl Backend for NavigateOnlyBackend {
+    async fn launch(&mut self) -> Result<(), BackendError> {
+        Ok(())
+    }
+    async fn close(&mut self) -> Result<(), BackendError> {
+        Ok(())
+    }
+    async fn is_ready(&self) -> bool {
+        true
+    }
+    async fn navigate(&mut self, url: &str) -> Result<NavigationResult, BackendError> {
+        Ok(NavigationResult {
+            url: url.to_string(),
+            ..Default::default()
+        })
+    }
+    async fn screenshot(&mut self) -> Result<Vec<u8>, BackendError> {
+        Ok(vec![])
+    }
+    async fn execute_scanner(
+        &mut self,
+        command: ScannerAction,
+    ) -> Result<ScannerProtocolResponse, BackendError> {
+        Err(BackendError::NotSupported(format!("{:?}", command)))
+    }
+}
+
+/// Run a script with a passing, a failing and another passing line.
+async fn run_script(name: &str, output: OutputHandlers, mode: OutputMode) -> i32 {
+    let path = std::env::temp_dir().join(name);
+    std::fs::write(
+        &path,
+        "goto https://example.com\nfrobnicate now\ngoto https://example.org\n",
+    )
+    .unwrap();
+
+    let mut executor = CommandExecutor::new();
+    let result = cli::run_file(
+        &mut NavigateOnlyBackend,
+        &mut executor,
+        output,
+        mode,
+        path.to_str().unwrap(),
+        FileOptions {
+            stop_on_error: false,
+            error_mode: FileErrorMode::WithLine,
+        },
+    )
+    .await;
+    std::fs::remove_file(&path).ok();
+    result.unwrap()
+}
+
+static HUMAN_OUT: Mutex<Vec<String>> = Mutex::new(Vec::new());
+static HUMAN_ERR: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+#[tokio::test]
+async fn test_human_mode_sends_errors_to_the_error_channel() {
+    let output = OutputHandlers {
+        out: |msg| HUMAN_OUT.lock().unwrap().push(msg.to_string()),
+        err: |msg| HUMAN_ERR.lock().unwrap().push(msg.to_string()),
+    };
+    assert_eq!(
+        run_script("oryn_cli_human.oil", output, OutputMode::Human).await,
+        0
+    );
+
+    let out = HUMAN_OUT.lock().unwrap();
+    assert_eq!(
+        *out,
+        [
+            "Navigated to https://example.com",
+            "Navigated to https://example.org"
+        ]
+    );
+    let err = HUMAN_ERR.lock().unwrap();
+    assert_eq!(err.len(), 1, "{:?}", err);
+    assert!(
+        err[0].starts_with("Error executing line 'frobnicate now':"),
+        "{}",
+        err[0]
+    );
+}
+
+static JSON_OUT: Mutex<Vec<String>> = Mutex::new(Vec::new());
+static JSON_ERR: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+#[tokio::test]
+async fn test_json_mode_tags_every_line_on_the_results_channel() {
+    let output = OutputHandlers {
+        out: |msg| JSON_OUT.lock().unwrap().push(msg.to_string()),
+        err: |msg| JSON_ERR.lock().unwrap().push(msg.to_string()),
+    };
+    run_script("oryn_cli_json.oil", output, OutputMode::Json).await;
+
+    assert!(JSON_ERR.lock().unwrap().is_empty());
+    let lines: Vec<Value> = JSON_OUT
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|line| {
+            assert!(!line.contains('\n'), "one object per line: {}", line);
+            serde_json::from_str(line).unwrap()
+        })
+        .collect();
+    let types: Vec<&str> = lines.iter().map(|l| l["type"].as_str().unwrap()).collect();
+    assert_eq!(types, ["result", "error", "result"]);
+
+    assert_eq!(lines[0]["output"], "Navigated to https://example.com");
+    assert_eq!(lines[0]["exit_code"], 0);
+    assert_eq!(lines[1]["command"], "frobnicate now");
+    assert!(
+        !lines[1]["message"].as_str().unwrap().is_empty(),
+        "{}",
+        lines[1]
+    );
+}
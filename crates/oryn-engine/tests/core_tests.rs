@@ -108,6 +108,9 @@ fn test_formatter_scan_scanresult() {
             attributes: HashMap::new(),
             state: ElementState::default(),
             children: vec![],
+            count: None,
+            tab_index: None,
+            focusable: false,
         }],
         stats: ScanStats {
             total: 1,
@@ -134,6 +137,70 @@ fn test_formatter_scan_scanresult() {
     assert!(output.contains("[1] button/button \"Submit\""));
 }
 
+#[test]
+fn test_formatter_scan_tree_indents_children() {
+    let node = |id: u32, tag: &str, text: &str, children: Vec<u32>| Element {
+        id,
+        element_type: tag.into(),
+        role: None,
+        text: Some(text.into()),
+        label: None,
+        value: None,
+        placeholder: None,
+        selector: format!("#n{}", id),
+        xpath: None,
+        rect: Rect {
+            x: 0.0,
+            y: 0.0,
+            width: 100.0,
+            height: 30.0,
+        },
+        attributes: HashMap::new(),
+        state: ElementState::default(),
+        children,
+        count: None,
+        tab_index: None,
+        focusable: false,
+    };
+    let scan = ScanResult {
+        page: PageInfo {
+            url: "https://example.com".into(),
+            title: "Example".into(),
+            viewport: ViewportInfo::default(),
+            scroll: ScrollInfo::default(),
+            ready_state: None,
+        },
+        elements: vec![
+            node(1, "form", "Login", vec![2, 3]),
+            node(2, "div", "Row", vec![4]),
+            node(3, "button", "Sign in", vec![]),
+            node(4, "input", "Email", vec![]),
+            node(5, "a", "Help", vec![]),
+        ],
+        stats: ScanStats {
+            total: 5,
+            scanned: 5,
+            iframes: None,
+        },
+        patterns: None,
+        changes: None,
+        available_intents: None,
+        full_mode: false,
+        settings_applied: None,
+        timing: None,
+    };
+
+    assert_eq!(
+        formatter::format_scan_tree(&scan, Some(2)),
+        "@ https://example.com \"Example\"\n\
+         [1] form \"Login\"\n\
+         \x20 [2] div \"Row\"\n\
+         \x20 [3] button \"Sign in\"\n\
+         [5] a \"Help\"\n"
+    );
+    assert!(formatter::format_scan_tree(&scan, None).contains("\n    [4] input \"Email\"\n"));
+}
+
 #[test]
 fn test_formatter_error() {
     let resp = ScannerProtocolResponse::Error {
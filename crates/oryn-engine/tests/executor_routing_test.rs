@@ -5,12 +5,15 @@
 //! rather than going through the translator → scanner path.
 
 use async_trait::async_trait;
-use oryn_engine::backend::{Backend, BackendError, NavigationResult};
-use oryn_engine::executor::CommandExecutor;
+use oryn_engine::backend::{Backend, BackendCapabilities, BackendError, NavigationResult};
+use oryn_engine::executor::{CommandExecutor, ExecutorError};
 use oryn_engine::protocol::{
-    ActionResult, Cookie, PageInfo, ScanResult, ScanStats, ScannerAction, ScannerData,
-    ScannerProtocolResponse, ScrollInfo, TabInfo, ViewportInfo,
+    ActionResult, Cookie, ExecuteRequest, PageInfo, PdfOptions, Rect, ScanRequest, ScanResult,
+    ScanStats, ScannerAction, ScannerData, ScannerProtocolResponse, ScrollInfo, TabInfo,
+    ViewportInfo,
 };
+use oryn_engine::resolver::ResolverError;
+use oryn_engine::translator::TranslationError;
 use std::sync::Mutex;
 use std::sync::atomic::{AtomicBool, Ordering};
 
@@ -25,9 +28,11 @@ struct TrackingMockBackend {
     pub pdf_called: AtomicBool,
     pub get_cookies_called: AtomicBool,
     pub set_cookie_called: AtomicBool,
+    pub cookies_set: Mutex<Vec<Cookie>>,
     pub get_tabs_called: AtomicBool,
     pub press_key_called: AtomicBool,
     pub execute_scanner_called: AtomicBool,
+    pub last_referer: Mutex<Option<String>>,
     pub last_key_pressed: Mutex<Option<String>>,
     pub last_modifiers: Mutex<Vec<String>>,
     pub scanner_requests: Mutex<Vec<ScannerAction>>,
@@ -56,6 +61,15 @@ impl Backend for TrackingMockBackend {
         })
     }
 
+    async fn navigate_with_referer(
+        &mut self,
+        url: &str,
+        referer: &str,
+    ) -> Result<NavigationResult, BackendError> {
+        *self.last_referer.lock().unwrap() = Some(referer.to_string());
+        self.navigate(url).await
+    }
+
     async fn go_back(&mut self) -> Result<NavigationResult, BackendError> {
         self.go_back_called.store(true, Ordering::SeqCst);
         Ok(NavigationResult {
@@ -88,7 +102,11 @@ impl Backend for TrackingMockBackend {
         Ok(vec![0x89, 0x50, 0x4E, 0x47]) // PNG magic bytes
     }
 
-    async fn pdf(&mut self) -> Result<Vec<u8>, BackendError> {
+    async fn screenshot_clip(&mut self, _clip: &Rect) -> Result<Vec<u8>, BackendError> {
+        self.screenshot().await
+    }
+
+    async fn pdf(&mut self, _options: &PdfOptions) -> Result<Vec<u8>, BackendError> {
         self.pdf_called.store(true, Ordering::SeqCst);
         Ok(vec![0x25, 0x50, 0x44, 0x46]) // PDF magic bytes
     }
@@ -103,11 +121,13 @@ impl Backend for TrackingMockBackend {
             expires: None,
             http_only: Some(true),
             secure: Some(true),
+            same_site: Some("Lax".to_string()),
         }])
     }
 
-    async fn set_cookie(&mut self, _cookie: Cookie) -> Result<(), BackendError> {
+    async fn set_cookie(&mut self, cookie: Cookie) -> Result<(), BackendError> {
         self.set_cookie_called.store(true, Ordering::SeqCst);
+        self.cookies_set.lock().unwrap().push(cookie);
         Ok(())
     }
 
@@ -160,6 +180,58 @@ impl Backend for TrackingMockBackend {
                 }))),
                 warnings: vec![],
             }),
+            // Stands in for the page evaluating `return args.x * 2`.
+            ScannerAction::Execute(req) if req.script == "return args.x * 2" => {
+                let x = req.args["x"].as_f64().unwrap_or_default();
+                Ok(ScannerProtocolResponse::Ok {
+                    data: Box::new(ScannerData::Value(serde_json::json!({ "result": x * 2.0 }))),
+                    warnings: vec![],
+                })
+            }
+            // Stands in for the page reading its scroll position.
+            ScannerAction::Execute(req) if req.script == "return window.scrollY" => {
+                Ok(ScannerProtocolResponse::Ok {
+                    data: Box::new(ScannerData::Value(serde_json::json!({ "result": 640 }))),
+                    warnings: vec![],
+                })
+            }
+            // Hands back whatever it was given.
+            ScannerAction::Execute(req) if req.script == "return args.value" => {
+                Ok(ScannerProtocolResponse::Ok {
+                    data: Box::new(ScannerData::Value(
+                        serde_json::json!({ "result": req.args["value"] }),
+                    )),
+                    warnings: vec![],
+                })
+            }
+            // The grid the scanner makes of a header cell spanning two columns.
+            ScannerAction::Extract(req) if req.source == "tables" => {
+                Ok(ScannerProtocolResponse::Ok {
+                    data: Box::new(ScannerData::Value(serde_json::json!({
+                        "results": [{
+                            "headers": ["Name", "Name", "Age"],
+                            "rows": [["Ada", "Lovelace, Countess", "36"]],
+                            "id": 4
+                        }]
+                    }))),
+                    warnings: vec![],
+                })
+            }
+            // A page that keeps changing.
+            ScannerAction::Wait(req) if req.condition == "stable" => {
+                Ok(ScannerProtocolResponse::Error {
+                    code: "TIMEOUT".into(),
+                    message: "page did not become stable".into(),
+                    details: None,
+                    hint: None,
+                })
+            }
+            ScannerAction::GetText(_) => Ok(ScannerProtocolResponse::Ok {
+                data: Box::new(ScannerData::Value(serde_json::json!({
+                    "text": "  Hello \n\n   world  "
+                }))),
+                warnings: vec![],
+            }),
             _ => Ok(ScannerProtocolResponse::Ok {
                 data: Box::new(ScannerData::Action(ActionResult {
                     success: true,
@@ -193,6 +265,55 @@ async fn test_goto_routes_to_navigate() {
     assert!(!backend.execute_scanner_called.load(Ordering::SeqCst));
 }
 
+#[tokio::test]
+async fn test_unset_env_variable_is_warned_about_in_the_output() {
+    let mut backend = TrackingMockBackend::default();
+    let mut executor = CommandExecutor::new();
+
+    let result = executor
+        .execute_line(
+            &mut backend,
+            "goto \"https://example.com/$ENV{ORYN_ROUTING_TEST_UNSET}\"",
+        )
+        .await
+        .unwrap();
+
+    assert!(result.success);
+    assert!(result.output.contains(
+        "Warning: environment variable ORYN_ROUTING_TEST_UNSET is not set; \
+         leaving $ENV{ORYN_ROUTING_TEST_UNSET} unexpanded"
+    ));
+    assert!(backend.navigate_called.load(Ordering::SeqCst));
+}
+
+#[tokio::test]
+async fn test_goto_sends_referer_then_waits_until() {
+    let mut backend = TrackingMockBackend::default();
+    let mut executor = CommandExecutor::new();
+
+    executor
+        .execute_line(
+            &mut backend,
+            r#"goto https://example.com --referer "https://x" --until visible "Home" --timeout 5s"#,
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(
+        backend.last_referer.lock().unwrap().as_deref(),
+        Some("https://x")
+    );
+    let requests = backend.scanner_requests.lock().unwrap();
+    match requests.as_slice() {
+        [ScannerAction::Wait(req)] => {
+            assert_eq!(req.condition, "visible");
+            assert_eq!(req.text.as_deref(), Some("Home"));
+            assert_eq!(req.timeout, Some(5000));
+        }
+        other => panic!("Expected one wait after navigating, got {:?}", other),
+    }
+}
+
 #[tokio::test]
 async fn test_back_routes_to_go_back() {
     let mut backend = TrackingMockBackend::default();
@@ -263,6 +384,56 @@ async fn test_screenshot_routes_to_screenshot() {
     let _ = std::fs::remove_file("/tmp/test_screenshot.png");
 }
 
+#[tokio::test]
+async fn test_screenshot_clip_with_a_target_warns_in_the_output() {
+    let mut backend = TrackingMockBackend::default();
+    let mut executor = CommandExecutor::new();
+    let path = std::env::temp_dir().join("oryn_routing_clip.png");
+
+    let result = executor
+        .execute_line(
+            &mut backend,
+            &format!(
+                "screenshot \"Menu\" --clip 0,0,800,600 --output {}",
+                path.display()
+            ),
+        )
+        .await
+        .unwrap();
+    std::fs::remove_file(&path).ok();
+
+    assert!(
+        result.output.contains(
+            "Warning: screenshot --clip and a target were both given; capturing the clip"
+        )
+    );
+    assert!(result.output.contains("Screenshot saved"));
+    // The target is dropped, so nothing is scanned for it.
+    assert!(!backend.execute_scanner_called.load(Ordering::SeqCst));
+}
+
+#[tokio::test]
+async fn test_screenshot_on_an_unsettled_page_warns_in_the_output() {
+    let mut backend = TrackingMockBackend::default();
+    let mut executor = CommandExecutor::new();
+    let path = std::env::temp_dir().join("oryn_routing_unsettled.png");
+
+    let result = executor
+        .execute_line(
+            &mut backend,
+            &format!("screenshot --wait-stable --output {}", path.display()),
+        )
+        .await
+        .unwrap();
+    std::fs::remove_file(&path).ok();
+
+    assert!(result.output.contains(
+        "Warning: the page did not settle before the screenshot (TIMEOUT: page did not become stable)"
+    ));
+    assert!(result.output.contains("Screenshot saved"));
+    assert!(backend.screenshot_called.load(Ordering::SeqCst));
+}
+
 #[tokio::test]
 async fn test_pdf_routes_to_pdf() {
     let mut backend = TrackingMockBackend::default();
@@ -311,6 +482,77 @@ async fn test_press_routes_to_press_key() {
     assert!(output.contains("Pressed enter"));
 }
 
+#[tokio::test]
+async fn test_type_then_tab_presses_tab_after_typing() {
+    let mut backend = TrackingMockBackend::default();
+    let mut executor = CommandExecutor::new();
+
+    let result = executor
+        .execute_line(&mut backend, r##"type css("#email") "a@b.com" --then-tab"##)
+        .await
+        .unwrap();
+
+    let requests = backend.scanner_requests.lock().unwrap();
+    assert!(
+        matches!(requests.last(), Some(ScannerAction::Type(req)) if req.text == "a@b.com"),
+        "{:?}",
+        requests
+    );
+    assert_eq!(
+        backend.last_key_pressed.lock().unwrap().as_deref(),
+        Some("Tab")
+    );
+    assert!(result.output.ends_with("Pressed Tab"), "{}", result.output);
+}
+
+#[tokio::test]
+async fn test_wait_after_rescans_once_the_page_settles() {
+    let mut backend = TrackingMockBackend::default();
+    let mut executor = CommandExecutor::new();
+
+    let started = std::time::Instant::now();
+    executor
+        .execute_line(&mut backend, r##"click css("#load-more") --wait-after 50"##)
+        .await
+        .unwrap();
+    assert!(started.elapsed() >= std::time::Duration::from_millis(50));
+
+    let requests = backend.scanner_requests.lock().unwrap();
+    assert!(
+        matches!(
+            requests.as_slice(),
+            [.., ScannerAction::Click(_), ScannerAction::Scan(_)]
+        ),
+        "{:?}",
+        requests
+    );
+}
+
+#[tokio::test]
+async fn test_action_settle_default_applies_without_wait_after() {
+    for (default_ms, line, rescans) in [
+        (0, r##"type css("#q") "shoes""##, false),
+        (10, r##"type css("#q") "shoes""##, true),
+        // An explicit zero opts out of the default
+        (10, r##"type css("#q") "shoes" --wait-after 0"##, false),
+    ] {
+        let mut backend = TrackingMockBackend::default();
+        let mut executor = CommandExecutor::new();
+        executor.set_action_settle_ms(default_ms);
+
+        executor.execute_line(&mut backend, line).await.unwrap();
+
+        let requests = backend.scanner_requests.lock().unwrap();
+        assert_eq!(
+            matches!(requests.last(), Some(ScannerAction::Scan(_))),
+            rescans,
+            "{}: {:?}",
+            line,
+            requests
+        );
+    }
+}
+
 #[tokio::test]
 async fn test_press_with_modifiers() {
     let mut backend = TrackingMockBackend::default();
@@ -380,6 +622,44 @@ async fn test_cookies_get_routes_to_get_cookies() {
     assert!(backend.get_cookies_called.load(Ordering::SeqCst));
 }
 
+#[tokio::test]
+async fn test_cookies_get_json_returns_cookie_objects() {
+    let mut backend = TrackingMockBackend::default();
+    let mut executor = CommandExecutor::new();
+
+    let result = executor
+        .execute_line(&mut backend, "cookies get --all --json")
+        .await
+        .unwrap();
+    let cookies: serde_json::Value = serde_json::from_str(&result.output).unwrap();
+    assert_eq!(
+        cookies,
+        serde_json::json!([{
+            "name": "session",
+            "value": "abc123",
+            "domain": "example.com",
+            "path": "/",
+            "http_only": true,
+            "secure": true,
+            "same_site": "Lax"
+        }])
+    );
+
+    let result = executor
+        .execute_line(&mut backend, "cookies get session --json")
+        .await
+        .unwrap();
+    let cookie: serde_json::Value = serde_json::from_str(&result.output).unwrap();
+    assert_eq!(cookie["name"], "session");
+    assert_eq!(cookie["http_only"], true);
+
+    let result = executor
+        .execute_line(&mut backend, "cookies get missing --json")
+        .await
+        .unwrap();
+    assert_eq!(result.output, "null");
+}
+
 #[tokio::test]
 async fn test_cookies_set_routes_to_set_cookie() {
     let mut backend = TrackingMockBackend::default();
@@ -415,6 +695,53 @@ async fn test_cookies_delete_routes_to_set_cookie_expired() {
     assert!(output.contains("deleted"));
 }
 
+#[tokio::test]
+async fn test_cookies_export_and_import_files() {
+    let mut backend = TrackingMockBackend::default();
+    let mut executor = CommandExecutor::new();
+    let dir = std::env::temp_dir();
+    let txt = dir.join("oryn_cookies_export.txt");
+    let json = dir.join("oryn_cookies_export.data");
+
+    let result = executor
+        .execute_line(&mut backend, &format!("cookies export {}", txt.display()))
+        .await
+        .unwrap();
+    assert!(result.output.starts_with("Exported 1 cookies"));
+    let written = std::fs::read_to_string(&txt).unwrap();
+    assert!(
+        written.ends_with("#HttpOnly_example.com\tFALSE\t/\tTRUE\t0\tsession\tabc123\n"),
+        "{}",
+        written
+    );
+
+    // --format overrides the extension.
+    executor
+        .execute_line(
+            &mut backend,
+            &format!("cookies export {} --format json", json.display()),
+        )
+        .await
+        .unwrap();
+    let result = executor
+        .execute_line(
+            &mut backend,
+            &format!("cookies import {} --format json", json.display()),
+        )
+        .await
+        .unwrap();
+    std::fs::remove_file(&txt).ok();
+    std::fs::remove_file(&json).ok();
+    assert!(result.output.starts_with("Imported 1 cookies"));
+
+    let imported = backend.cookies_set.lock().unwrap();
+    assert_eq!(imported.len(), 1);
+    assert_eq!(imported[0].name, "session");
+    assert_eq!(imported[0].value, "abc123");
+    assert_eq!(imported[0].http_only, Some(true));
+    assert_eq!(imported[0].same_site.as_deref(), Some("Lax"));
+}
+
 // ============================================================================
 // Tab Command Routing Tests
 // ============================================================================
@@ -441,6 +768,118 @@ async fn test_tabs_routes_to_get_tabs() {
 // Commands that go through translator → scanner
 // ============================================================================
 
+#[tokio::test]
+async fn test_default_execute_batch_runs_each_request_in_order() {
+    let mut backend = TrackingMockBackend::default();
+    let echo = |value: i64| {
+        ScannerAction::Execute(ExecuteRequest {
+            script: "return args.value".into(),
+            args: serde_json::json!({ "value": value }),
+        })
+    };
+
+    let responses = backend
+        .execute_batch(vec![
+            echo(1),
+            ScannerAction::Scan(ScanRequest::default()),
+            echo(2),
+        ])
+        .await
+        .unwrap();
+
+    let requests = backend.scanner_requests.lock().unwrap();
+    assert_eq!(requests.len(), 3);
+    assert!(matches!(requests[1], ScannerAction::Scan(_)));
+    assert_eq!(responses.len(), 3);
+    let value = |resp: &ScannerProtocolResponse| match resp {
+        ScannerProtocolResponse::Ok { data, .. } => match data.as_ref() {
+            ScannerData::Value(value) => value["result"].clone(),
+            other => panic!("Expected a value, got {:?}", other),
+        },
+        other => panic!("Expected ok, got {:?}", other),
+    };
+    assert_eq!(value(&responses[0]), serde_json::json!(1));
+    assert!(matches!(
+        &responses[1],
+        ScannerProtocolResponse::Ok { data, .. } if matches!(data.as_ref(), ScannerData::Scan(_))
+    ));
+    assert_eq!(value(&responses[2]), serde_json::json!(2));
+}
+
+#[tokio::test]
+async fn test_repeat_runs_block_once_per_iteration() {
+    let mut backend = TrackingMockBackend::default();
+    let mut executor = CommandExecutor::new();
+
+    executor
+        .execute_line(&mut backend, "repeat 3 { scroll down }")
+        .await
+        .unwrap();
+
+    let requests = backend.scanner_requests.lock().unwrap();
+    let scrolls = requests
+        .iter()
+        .filter(|req| matches!(req, ScannerAction::Scroll(_)))
+        .count();
+    assert_eq!(scrolls, 3);
+    // Each pass starts from a fresh scan
+    assert!(matches!(requests[0], ScannerAction::Scan(_)));
+    assert!(matches!(requests.last(), Some(ScannerAction::Scroll(_))));
+    let scans = requests
+        .iter()
+        .filter(|req| matches!(req, ScannerAction::Scan(_)))
+        .count();
+    assert_eq!(scans, 3);
+}
+
+#[tokio::test]
+async fn test_repeat_over_the_limit_is_rejected() {
+    let mut backend = TrackingMockBackend::default();
+    let mut executor = CommandExecutor::new();
+    executor.set_max_repeat(5);
+
+    let result = executor
+        .execute_line(&mut backend, "repeat 6 { scroll down }")
+        .await;
+
+    assert!(matches!(
+        result,
+        Err(ExecutorError::RepeatLimit { count: 6, max: 5 })
+    ));
+    assert!(backend.scanner_requests.lock().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_nested_repeats_count_toward_the_limit_together() {
+    let mut backend = TrackingMockBackend::default();
+    let mut executor = CommandExecutor::new();
+    executor.set_max_repeat(10);
+
+    // Each block is within the limit; the 12 passes they make are not.
+    let result = executor
+        .execute_line(&mut backend, "repeat 3 { repeat 4 { scroll down } }")
+        .await;
+
+    assert!(matches!(
+        result,
+        Err(ExecutorError::RepeatLimit { count: 12, max: 10 })
+    ));
+    assert!(backend.scanner_requests.lock().unwrap().is_empty());
+
+    executor
+        .execute_line(&mut backend, "repeat 2 { repeat 5 { scroll down } }")
+        .await
+        .unwrap();
+    let scrolls = backend
+        .scanner_requests
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|req| matches!(req, ScannerAction::Scroll(_)))
+        .count();
+    assert_eq!(scrolls, 10);
+}
+
 #[tokio::test]
 async fn test_observe_goes_through_scanner() {
     let mut backend = TrackingMockBackend::default();
@@ -486,6 +925,183 @@ async fn test_title_goes_through_scanner_execute() {
     assert!(matches!(requests[0], ScannerAction::Execute(_)));
 }
 
+#[tokio::test]
+async fn test_eval_binds_args_from_variables() {
+    let mut backend = TrackingMockBackend::default();
+    let mut executor = CommandExecutor::new();
+    executor.set_variable("count", serde_json::json!(21));
+
+    let result = executor
+        .execute_line(&mut backend, r#"eval "return args.x * 2" --arg x=$count"#)
+        .await
+        .unwrap();
+    assert_eq!(result.output, "42.0");
+
+    let requests = backend.scanner_requests.lock().unwrap();
+    match &requests[0] {
+        ScannerAction::Execute(req) => {
+            assert_eq!(req.script, "return args.x * 2");
+            assert_eq!(req.args, serde_json::json!({ "x": 21 }));
+        }
+        other => panic!("Expected execute request, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_set_stores_the_eval_result_in_a_variable() {
+    let mut backend = TrackingMockBackend::default();
+    let mut executor = CommandExecutor::new();
+
+    let result = executor
+        .execute_line(&mut backend, r#"set y = eval "return window.scrollY""#)
+        .await
+        .unwrap();
+    assert_eq!(result.output, "$y = 640");
+    assert_eq!(executor.variable("y"), Some(&serde_json::json!(640)));
+
+    let result = executor
+        .execute_line(&mut backend, r#"eval "return args.x * 2" --arg x=$y"#)
+        .await
+        .unwrap();
+    assert_eq!(result.output, "1280.0");
+}
+
+#[tokio::test]
+async fn test_eval_values_round_trip_as_json() {
+    let mut backend = TrackingMockBackend::default();
+    let mut executor = CommandExecutor::new();
+
+    for (arg, expected) in [
+        (r#""say \"hi\"""#, serde_json::json!("say \"hi\"")),
+        ("7", serde_json::json!(7)),
+        ("-2.5", serde_json::json!(-2.5)),
+        ("true", serde_json::json!(true)),
+        ("null", serde_json::Value::Null),
+    ] {
+        let result = executor
+            .execute_line(
+                &mut backend,
+                &format!(r#"set v = eval "return args.value" --arg value={}"#, arg),
+            )
+            .await
+            .unwrap();
+        assert_eq!(result.output, format!("$v = {}", expected));
+        assert_eq!(executor.variable("v"), Some(&expected));
+    }
+
+    // Objects come back whole
+    executor.set_variable("point", serde_json::json!({ "x": 1, "y": [2, 3] }));
+    let result = executor
+        .execute_line(
+            &mut backend,
+            r#"eval "return args.value" --arg value=$point"#,
+        )
+        .await
+        .unwrap();
+    assert_eq!(result.output, r#"{"x":1,"y":[2,3]}"#);
+}
+
+#[tokio::test]
+async fn test_extract_tables_respects_format() {
+    let mut backend = TrackingMockBackend::default();
+    let mut executor = CommandExecutor::new();
+
+    let result = executor
+        .execute_line(&mut backend, "extract tables --format csv")
+        .await
+        .unwrap();
+    assert_eq!(
+        result.output,
+        "Name,Name_2,Age\nAda,\"Lovelace, Countess\",36"
+    );
+
+    let result = executor
+        .execute_line(&mut backend, "extract tables --format json")
+        .await
+        .unwrap();
+    let tables: serde_json::Value = serde_json::from_str(&result.output).unwrap();
+    assert_eq!(
+        tables[0]["rows"][0],
+        serde_json::json!({ "Name": "Ada", "Name_2": "Lovelace, Countess", "Age": "36" })
+    );
+}
+
+#[tokio::test]
+async fn test_eval_unknown_variable_fails() {
+    let mut backend = TrackingMockBackend::default();
+    let mut executor = CommandExecutor::new();
+
+    let err = executor
+        .execute_line(&mut backend, r#"eval "return args.x" --arg x=$missing"#)
+        .await
+        .err()
+        .expect("unknown variable should fail");
+
+    assert!(matches!(err, ExecutorError::UnknownVariable(ref name) if name == "missing"));
+    assert!(backend.scanner_requests.lock().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_safe_mode_rejects_raw_script_but_runs_semantic_commands() {
+    let mut backend = TrackingMockBackend::default();
+    let mut executor = CommandExecutor::new();
+    executor.set_safe_mode(true);
+
+    for (line, what) in [
+        (r#"eval "return document.cookie""#, "eval"),
+        (r#"set y = eval "return window.scrollY""#, "set"),
+        (
+            r#"wait all (visible "Saved", until "window.done")"#,
+            "wait until <expression>",
+        ),
+    ] {
+        match executor.execute_line(&mut backend, line).await {
+            Err(err @ ExecutorError::DisabledInSafeMode(_)) => {
+                assert_eq!(
+                    err.to_string(),
+                    format!("{} is disabled in safe mode", what)
+                )
+            }
+            other => panic!(
+                "Expected {} to be rejected, got {:?}",
+                line,
+                other.map(|r| r.output)
+            ),
+        }
+    }
+    assert!(backend.scanner_requests.lock().unwrap().is_empty());
+
+    executor
+        .execute_line(&mut backend, "goto https://example.com")
+        .await
+        .unwrap();
+    executor.execute_line(&mut backend, "title").await.unwrap();
+    assert!(backend.navigate_called.load(Ordering::SeqCst));
+    assert!(matches!(
+        backend.scanner_requests.lock().unwrap().as_slice(),
+        [ScannerAction::Execute(_)]
+    ));
+}
+
+#[tokio::test]
+async fn test_click_all_without_matches_sends_no_clicks() {
+    let mut backend = TrackingMockBackend::default();
+    let mut executor = CommandExecutor::new();
+
+    let err = executor
+        .execute_line(&mut backend, r#"click all "Remove""#)
+        .await
+        .err()
+        .expect("nothing to click should fail");
+
+    assert!(matches!(
+        err,
+        ExecutorError::Translation(TranslationError::Resolution(ResolverError::NoMatch(_)))
+    ));
+    let requests = backend.scanner_requests.lock().unwrap();
+    assert!(matches!(requests.as_slice(), [ScannerAction::Scan(_)]));
+}
+
 #[tokio::test]
 async fn test_scroll_goes_through_scanner() {
     let mut backend = TrackingMockBackend::default();
@@ -516,6 +1132,36 @@ async fn test_wait_goes_through_scanner() {
     assert!(matches!(requests[0], ScannerAction::Wait(_)));
 }
 
+#[tokio::test]
+async fn test_text_collapses_whitespace_by_default() {
+    let mut backend = TrackingMockBackend::default();
+    let mut executor = CommandExecutor::new();
+
+    let result = executor.execute_line(&mut backend, "text").await.unwrap();
+
+    assert!(result.output.contains(r#""text": "Hello world""#));
+    let requests = backend.scanner_requests.lock().unwrap();
+    assert!(matches!(requests[0], ScannerAction::GetText(_)));
+}
+
+#[tokio::test]
+async fn test_text_raw_and_trim_modes() {
+    let mut backend = TrackingMockBackend::default();
+    let mut executor = CommandExecutor::new();
+
+    let raw = executor
+        .execute_line(&mut backend, "text --raw")
+        .await
+        .unwrap();
+    assert!(raw.output.contains(r#""text": "  Hello \n\n   world  ""#));
+
+    let trimmed = executor
+        .execute_line(&mut backend, "text --trim")
+        .await
+        .unwrap();
+    assert!(trimmed.output.contains(r#""text": "Hello \n\n   world""#));
+}
+
 // ============================================================================
 // Error Handling Tests
 // ============================================================================
@@ -526,6 +1172,12 @@ struct ErrorBackend;
 
 #[async_trait]
 impl Backend for ErrorBackend {
+    fn capabilities(&self) -> BackendCapabilities {
+        BackendCapabilities {
+            pdf: false,
+            ..BackendCapabilities::all()
+        }
+    }
     async fn launch(&mut self) -> Result<(), BackendError> {
         Ok(())
     }
@@ -590,6 +1242,60 @@ async fn test_screenshot_not_supported_error() {
     assert!(result.is_err());
 }
 
+#[tokio::test]
+async fn test_pdf_unsupported_capability_gives_guidance() {
+    let mut backend = ErrorBackend;
+    let mut executor = CommandExecutor::new();
+
+    let result = executor
+        .execute_line(&mut backend, "pdf /tmp/unsupported.pdf")
+        .await;
+
+    match result {
+        Err(ExecutorError::Backend(BackendError::NotSupported(msg))) => {
+            assert!(msg.contains("PDF requires headless mode"), "got: {}", msg);
+        }
+        Err(e) => panic!("Expected NotSupported guidance, got: {}", e),
+        Ok(_) => panic!("Expected pdf to be rejected"),
+    }
+}
+
+#[tokio::test]
+async fn test_dry_run_only_navigates_and_scans() {
+    let mut backend = TrackingMockBackend::default();
+    let mut executor = CommandExecutor::new();
+    executor.set_dry_run(true);
+
+    let mut planned = Vec::new();
+    for line in [
+        "goto https://example.com",
+        "observe",
+        "click 3",
+        r#"type 4 "hello""#,
+        "press Enter",
+        "wait load",
+    ] {
+        let result = executor.execute_line(&mut backend, line).await.unwrap();
+        planned.push(result.output);
+    }
+
+    assert!(backend.navigate_called.load(Ordering::SeqCst));
+    assert!(!backend.press_key_called.load(Ordering::SeqCst));
+    let requests = backend.scanner_requests.lock().unwrap();
+    assert!(
+        requests.iter().all(|r| matches!(r, ScannerAction::Scan(_))),
+        "only scans should reach the backend, got {:?}",
+        requests
+    );
+
+    assert!(planned[2].starts_with("Dry run: "));
+    assert!(planned[2].contains(r#""action":"click""#));
+    assert!(planned[3].contains(r#""action":"type""#));
+    assert!(planned[3].contains("hello"));
+    assert!(planned[4].starts_with("Dry run: "));
+    assert!(planned[5].contains(r#""action":"wait_for""#));
+}
+
 // ============================================================================
 // Resolver Context Tests
 // ============================================================================
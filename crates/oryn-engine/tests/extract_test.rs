@@ -0,0 +1,146 @@
+//! Link extraction tests.
+//!
+//! These tests verify that `extract links` hands back absolute URLs, resolving
+//! relative `href`s against the URL of the page they were found on, listing
+//! each address once and, with `--same-origin`, only the page's own.
+
+use async_trait::async_trait;
+use oryn_engine::backend::{Backend, BackendError, NavigationResult};
+use oryn_engine::executor::CommandExecutor;
+use oryn_engine::protocol::{
+    PageInfo, ScanResult, ScanStats, ScannerAction, ScannerData, ScannerProtocolResponse,
+    ScrollInfo, ViewportInfo,
+};
+use serde_json::{Value, json};
+
+/// A catalog page whose links are written relative to it, as a backend that
+/// reports raw `href` attributes returns them.
+#[derive(Default)]
+struct CatalogBackend {
+    scans: usize,
+}
+
+#[async_trait]
+impl Backend for CatalogBackend {
+    async fn launch(&mut self) -> Result<(), BackendError> {
+        Ok(())
+    }
+    async fn close(&mut self) -> Result<(), BackendError> {
+        Ok(())
+    }
+    async fn is_ready(&self) -> bool {
+        true
+    }
+    async fn navigate(&mut self, url: &str) -> Result<NavigationResult, BackendError> {
+        Ok(NavigationResult {
+            url: url.to_string(),
+            ..Default::default()
+        })
+    }
+    async fn screenshot(&mut self) -> Result<Vec<u8>, BackendError> {
+        Ok(vec![])
+    }
+    async fn execute_scanner(
+        &mut self,
+        command: ScannerAction,
+    ) -> Result<ScannerProtocolResponse, BackendError> {
+        let data = match command {
+            ScannerAction::Scan(_) => {
+                self.scans += 1;
+                ScannerData::Scan(Box::new(ScanResult {
+                    page: PageInfo {
+                        url: "https://shop.example.com/catalog/shoes/".into(),
+                        title: "Shoes".into(),
+                        viewport: ViewportInfo::default(),
+                        scroll: ScrollInfo::default(),
+                        ready_state: None,
+                    },
+                    elements: vec![],
+                    stats: ScanStats {
+                        total: 0,
+                        scanned: 0,
+                        iframes: None,
+                    },
+                    patterns: None,
+                    changes: None,
+                    available_intents: None,
+                    full_mode: false,
+                    settings_applied: None,
+                    timing: None,
+                }))
+            }
+            ScannerAction::Extract(req) if req.source == "links" => ScannerData::Value(json!({
+                "results": [
+                    { "text": "Boots", "url": "boots?page=2", "rel": null, "id": 1 },
+                    { "text": "Cart", "url": "../../cart", "rel": null, "id": 2 },
+                    { "text": "Help", "url": "/help", "rel": "help", "id": 3 },
+                    { "text": "Partner", "url": "https://partner.example/", "rel": "nofollow", "id": 4 },
+                    { "text": "Help center", "url": "https://shop.example.com/help", "rel": null, "id": 5 }
+                ]
+            })),
+            other => return Err(BackendError::NotSupported(format!("{:?}", other))),
+        };
+        Ok(ScannerProtocolResponse::Ok {
+            data: Box::new(data),
+            warnings: vec![],
+        })
+    }
+}
+
+/// The `href`s of a `--format json` extraction, in order.
+fn hrefs(links: &Value) -> Vec<&str> {
+    links
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|link| link["href"].as_str().unwrap())
+        .collect()
+}
+
+#[tokio::test]
+async fn test_relative_links_resolve_against_the_page_url() {
+    let mut backend = CatalogBackend::default();
+    let mut executor = CommandExecutor::new();
+
+    let result = executor
+        .execute_line(&mut backend, "extract links --format json")
+        .await
+        .unwrap();
+    let links: Value = serde_json::from_str(&result.output).unwrap();
+
+    // "Help center" links to the same address as "Help" and is dropped.
+    assert_eq!(
+        hrefs(&links),
+        [
+            "https://shop.example.com/catalog/shoes/boots?page=2",
+            "https://shop.example.com/cart",
+            "https://shop.example.com/help",
+            "https://partner.example/",
+        ]
+    );
+    assert_eq!(links[0]["text"], "Boots");
+    assert_eq!(links[2]["rel"], "help");
+    assert!(links[0].get("url").is_none(), "{}", links[0]);
+    assert_eq!(backend.scans, 1, "the page URL should come from a scan");
+}
+
+#[tokio::test]
+async fn test_same_origin_drops_external_links() {
+    let mut backend = CatalogBackend::default();
+    let mut executor = CommandExecutor::new();
+
+    let result = executor
+        .execute_line(&mut backend, "extract links --same-origin --format json")
+        .await
+        .unwrap();
+    let links: Value = serde_json::from_str(&result.output).unwrap();
+
+    assert_eq!(
+        hrefs(&links),
+        [
+            "https://shop.example.com/catalog/shoes/boots?page=2",
+            "https://shop.example.com/cart",
+            "https://shop.example.com/help",
+        ]
+    );
+}
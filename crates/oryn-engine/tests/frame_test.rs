@@ -0,0 +1,318 @@
+//! Frame listing and switching tests.
+//!
+//! These tests verify that `frames` lists each frame's index, name and URL,
+//! and that `frame "<name>"`, `frame url "<text>"`, `frame "#<id>"` and
+//! `frame <element id>` switch the backend to the matching frame.
+
+use async_trait::async_trait;
+use oryn_engine::backend::{Backend, BackendError, NavigationResult};
+use oryn_engine::executor::CommandExecutor;
+use oryn_engine::protocol::{
+    ActionResult, Element, ElementState, FrameInfo, PageInfo, Rect, ScanResult, ScanStats,
+    ScannerAction, ScannerData, ScannerProtocolResponse, ScrollInfo, ViewportInfo,
+};
+use serde_json::json;
+use std::collections::HashMap;
+
+fn frame(index: usize, name: &str, url: &str) -> FrameInfo {
+    FrameInfo {
+        index,
+        name: name.into(),
+        url: url.into(),
+    }
+}
+
+/// An element the scan reports for an `<iframe>` with the given selector.
+fn iframe_element(id: u32, selector: &str) -> Element {
+    Element {
+        id,
+        element_type: "iframe".into(),
+        role: None,
+        text: None,
+        label: None,
+        value: None,
+        placeholder: None,
+        selector: selector.into(),
+        xpath: None,
+        rect: Rect {
+            x: 0.0,
+            y: 200.0,
+            width: 400.0,
+            height: 120.0,
+        },
+        attributes: HashMap::new(),
+        state: ElementState::default(),
+        children: vec![],
+        count: None,
+        tab_index: None,
+        focusable: false,
+    }
+}
+
+/// A page with an unnamed ad frame (`#ad`) and a named checkout frame
+/// (`#checkout-frame`). Records every frame switch.
+#[derive(Default)]
+struct FramedBackend {
+    switches: Vec<Option<usize>>,
+}
+
+#[async_trait]
+impl Backend for FramedBackend {
+    async fn launch(&mut self) -> Result<(), BackendError> {
+        Ok(())
+    }
+    async fn close(&mut self) -> Result<(), BackendError> {
+        Ok(())
+    }
+    async fn is_ready(&self) -> bool {
+        true
+    }
+    async fn navigate(&mut self, url: &str) -> Result<NavigationResult, BackendError> {
+        Ok(NavigationResult {
+            url: url.to_string(),
+            ..Default::default()
+        })
+    }
+    async fn screenshot(&mut self) -> Result<Vec<u8>, BackendError> {
+        Ok(vec![])
+    }
+    async fn execute_scanner(
+        &mut self,
+        command: ScannerAction,
+    ) -> Result<ScannerProtocolResponse, BackendError> {
+        let data = match command {
+            ScannerAction::Scan(_) => ScannerData::Scan(Box::new(ScanResult {
+                page: PageInfo {
+                    url: "https://shop.example/pay".into(),
+                    title: "Pay".into(),
+                    viewport: ViewportInfo::default(),
+                    scroll: ScrollInfo::default(),
+                    ready_state: None,
+                },
+                stats: ScanStats {
+                    total: 2,
+                    scanned: 2,
+                    iframes: None,
+                },
+                elements: vec![
+                    iframe_element(2, "#ad"),
+                    iframe_element(3, "#checkout-frame"),
+                ],
+                patterns: None,
+                changes: None,
+                available_intents: None,
+                full_mode: false,
+                settings_applied: None,
+                timing: None,
+            })),
+            ScannerAction::Execute(req) => {
+                let index = match req.args["selector"].as_str() {
+                    Some("#ad") => 0,
+                    Some("#checkout-frame") => 1,
+                    _ => -1,
+                };
+                ScannerData::Value(json!({ "result": index }))
+            }
+            _ => ScannerData::Action(ActionResult {
+                success: true,
+                message: None,
+                navigation: None,
+                dom_changes: None,
+                value: None,
+                coordinates: None,
+            }),
+        };
+        Ok(ScannerProtocolResponse::Ok {
+            data: Box::new(data),
+            warnings: vec![],
+        })
+    }
+    async fn get_frames(&mut self) -> Result<Vec<FrameInfo>, BackendError> {
+        Ok(vec![
+            frame(0, "", "https://ads.example.com/banner"),
+            frame(1, "checkout", "https://js.stripe.com/v3/elements"),
+        ])
+    }
+    async fn switch_frame(&mut self, index: Option<usize>) -> Result<(), BackendError> {
+        self.switches.push(index);
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn test_frames_lists_index_name_and_url() {
+    let mut backend = FramedBackend::default();
+    let mut executor = CommandExecutor::new();
+
+    let result = executor.execute_line(&mut backend, "frames").await.unwrap();
+
+    assert!(
+        result
+            .output
+            .contains("[0] (unnamed) https://ads.example.com/banner"),
+        "{}",
+        result.output
+    );
+    assert!(
+        result
+            .output
+            .contains("[1] checkout https://js.stripe.com/v3/elements"),
+        "{}",
+        result.output
+    );
+}
+
+#[tokio::test]
+async fn test_frame_switches_by_name() {
+    let mut backend = FramedBackend::default();
+    let mut executor = CommandExecutor::new();
+
+    let result = executor
+        .execute_line(&mut backend, "frame \"checkout\"")
+        .await
+        .unwrap();
+
+    assert_eq!(backend.switches, vec![Some(1)]);
+    assert!(result.output.contains("[1] checkout"), "{}", result.output);
+}
+
+#[tokio::test]
+async fn test_frame_switches_by_partial_url_and_back_to_main() {
+    let mut backend = FramedBackend::default();
+    let mut executor = CommandExecutor::new();
+
+    executor
+        .execute_line(&mut backend, "frame url \"ads.example\"")
+        .await
+        .unwrap();
+    executor
+        .execute_line(&mut backend, "frame main")
+        .await
+        .unwrap();
+
+    assert_eq!(backend.switches, vec![Some(0), None]);
+}
+
+#[tokio::test]
+async fn test_unknown_frame_name_is_an_error() {
+    let mut backend = FramedBackend::default();
+    let mut executor = CommandExecutor::new();
+
+    let Err(err) = executor
+        .execute_line(&mut backend, "frame \"payment\"")
+        .await
+    else {
+        panic!("No frame is named payment");
+    };
+
+    assert!(err.to_string().contains("payment"), "{}", err);
+    assert!(backend.switches.is_empty());
+}
+
+#[tokio::test]
+async fn test_frame_switches_by_selector() {
+    let mut backend = FramedBackend::default();
+    let mut executor = CommandExecutor::new();
+
+    let result = executor
+        .execute_line(&mut backend, "frame \"#checkout-frame\"")
+        .await
+        .unwrap();
+    executor
+        .execute_line(&mut backend, "frame css(\"#ad\")")
+        .await
+        .unwrap();
+
+    assert_eq!(backend.switches, vec![Some(1), Some(0)]);
+    assert!(result.output.contains("[1] checkout"), "{}", result.output);
+}
+
+#[tokio::test]
+async fn test_frame_switches_by_scanned_element_id() {
+    let mut backend = FramedBackend::default();
+    let mut executor = CommandExecutor::new();
+
+    executor
+        .execute_line(&mut backend, "observe")
+        .await
+        .unwrap();
+    executor
+        .execute_line(&mut backend, "frame 3")
+        .await
+        .unwrap();
+
+    assert_eq!(backend.switches, vec![Some(1)]);
+}
+
+#[tokio::test]
+async fn test_selector_that_is_not_a_frame_is_an_error() {
+    let mut backend = FramedBackend::default();
+    let mut executor = CommandExecutor::new();
+
+    let Err(err) = executor
+        .execute_line(&mut backend, "frame \"#pay-button\"")
+        .await
+    else {
+        panic!("#pay-button is not a frame");
+    };
+
+    assert!(err.to_string().contains("#pay-button"), "{}", err);
+    assert!(backend.switches.is_empty());
+}
+
+/// Lists frames only through the default `get_frames`, which runs a script.
+struct ScriptedBackend;
+
+#[async_trait]
+impl Backend for ScriptedBackend {
+    async fn launch(&mut self) -> Result<(), BackendError> {
+        Ok(())
+    }
+    async fn close(&mut self) -> Result<(), BackendError> {
+        Ok(())
+    }
+    async fn is_ready(&self) -> bool {
+        true
+    }
+    async fn navigate(&mut self, url: &str) -> Result<NavigationResult, BackendError> {
+        Ok(NavigationResult {
+            url: url.to_string(),
+            ..Default::default()
+        })
+    }
+    async fn screenshot(&mut self) -> Result<Vec<u8>, BackendError> {
+        Ok(vec![])
+    }
+    async fn execute_scanner(
+        &mut self,
+        command: ScannerAction,
+    ) -> Result<ScannerProtocolResponse, BackendError> {
+        assert!(
+            matches!(command, ScannerAction::Execute(_)),
+            "{:?}",
+            command
+        );
+        Ok(ScannerProtocolResponse::Ok {
+            data: Box::new(ScannerData::Value(json!({
+                "result": [{ "index": 0, "name": "checkout", "url": "https://shop.example/pay" }]
+            }))),
+            warnings: vec![],
+        })
+    }
+}
+
+#[tokio::test]
+async fn test_default_frame_listing_reads_the_page() {
+    let mut backend = ScriptedBackend;
+    let mut executor = CommandExecutor::new();
+
+    let result = executor.execute_line(&mut backend, "frames").await.unwrap();
+
+    assert!(
+        result
+            .output
+            .contains("[0] checkout https://shop.example/pay"),
+        "{}",
+        result.output
+    );
+}
@@ -0,0 +1,173 @@
+//! Hover tests.
+//!
+//! These tests verify that `hover --duration` holds the hover before the
+//! next command runs, so a following `screenshot` captures the hovered state.
+
+use async_trait::async_trait;
+use oryn_engine::backend::{Backend, BackendError, NavigationResult};
+use oryn_engine::executor::CommandExecutor;
+use oryn_engine::protocol::{
+    ActionResult, Element, ElementState, PageInfo, Rect, ScanResult, ScanStats, ScannerAction,
+    ScannerData, ScannerProtocolResponse, ScrollInfo, ViewportInfo,
+};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// A page with a menu that opens on hover. Every hover, other scanner
+/// request and screenshot is logged with when it arrived.
+#[derive(Default)]
+struct MenuBackend {
+    log: Vec<(&'static str, Instant)>,
+}
+
+impl MenuBackend {
+    fn events(&self) -> Vec<&'static str> {
+        self.log.iter().map(|(event, _)| *event).collect()
+    }
+
+    fn at(&self, event: &str) -> Instant {
+        self.log
+            .iter()
+            .find(|(e, _)| *e == event)
+            .map(|(_, at)| *at)
+            .unwrap_or_else(|| panic!("no {} in {:?}", event, self.events()))
+    }
+}
+
+#[async_trait]
+impl Backend for MenuBackend {
+    async fn launch(&mut self) -> Result<(), BackendError> {
+        Ok(())
+    }
+    async fn close(&mut self) -> Result<(), BackendError> {
+        Ok(())
+    }
+    async fn is_ready(&self) -> bool {
+        true
+    }
+    async fn navigate(&mut self, url: &str) -> Result<NavigationResult, BackendError> {
+        Ok(NavigationResult {
+            url: url.to_string(),
+            ..Default::default()
+        })
+    }
+    async fn screenshot(&mut self) -> Result<Vec<u8>, BackendError> {
+        self.log.push(("screenshot", Instant::now()));
+        Ok(vec![0x89, 0x50, 0x4e, 0x47])
+    }
+    async fn execute_scanner(
+        &mut self,
+        command: ScannerAction,
+    ) -> Result<ScannerProtocolResponse, BackendError> {
+        let data = match &command {
+            ScannerAction::Scan(_) => ScannerData::Scan(Box::new(ScanResult {
+                page: PageInfo {
+                    url: String::new(),
+                    title: "Shop".into(),
+                    viewport: ViewportInfo::default(),
+                    scroll: ScrollInfo::default(),
+                    ready_state: None,
+                },
+                stats: ScanStats {
+                    total: 1,
+                    scanned: 1,
+                    iframes: None,
+                },
+                elements: vec![Element {
+                    id: 1,
+                    element_type: "button".into(),
+                    role: Some("button".into()),
+                    text: Some("Menu".into()),
+                    label: None,
+                    value: None,
+                    placeholder: None,
+                    selector: "#menu".into(),
+                    xpath: None,
+                    rect: Rect {
+                        x: 20.0,
+                        y: 10.0,
+                        width: 80.0,
+                        height: 30.0,
+                    },
+                    attributes: HashMap::new(),
+                    state: ElementState::default(),
+                    children: vec![],
+                    count: None,
+                    tab_index: None,
+                    focusable: true,
+                }],
+                patterns: None,
+                changes: None,
+                available_intents: None,
+                full_mode: false,
+                settings_applied: None,
+                timing: None,
+            })),
+            _ => ScannerData::Action(ActionResult {
+                success: true,
+                message: Some("hovered".into()),
+                navigation: None,
+                dom_changes: None,
+                value: None,
+                coordinates: None,
+            }),
+        };
+        let event = match &command {
+            ScannerAction::Scan(_) => "scan",
+            ScannerAction::Hover(_) => "hover",
+            _ => "other",
+        };
+        self.log.push((event, Instant::now()));
+        Ok(ScannerProtocolResponse::Ok {
+            data: Box::new(data),
+            warnings: vec![],
+        })
+    }
+}
+
+#[tokio::test]
+async fn test_hover_is_held_until_the_screenshot() {
+    let mut backend = MenuBackend::default();
+    let mut executor = CommandExecutor::new();
+    let path = std::env::temp_dir().join("oryn_hover_held.png");
+    executor
+        .execute_line(&mut backend, "observe")
+        .await
+        .unwrap();
+
+    let started = Instant::now();
+    executor
+        .execute_line(&mut backend, "hover \"Menu\" --duration 100ms")
+        .await
+        .unwrap();
+    // The hold is part of the hover command, not the command after it.
+    assert!(started.elapsed() >= Duration::from_millis(100));
+    executor
+        .execute_line(
+            &mut backend,
+            &format!("screenshot --output {}", path.display()),
+        )
+        .await
+        .unwrap();
+    std::fs::remove_file(&path).ok();
+
+    // Nothing reaches the page between the hover and the screenshot.
+    assert_eq!(backend.events(), ["scan", "hover", "screenshot"]);
+    assert!(backend.at("screenshot") - backend.at("hover") >= Duration::from_millis(100));
+}
+
+#[tokio::test]
+async fn test_unreadable_duration_is_rejected_before_hovering() {
+    let mut backend = MenuBackend::default();
+    let mut executor = CommandExecutor::new();
+    executor
+        .execute_line(&mut backend, "observe")
+        .await
+        .unwrap();
+
+    let result = executor
+        .execute_line(&mut backend, "hover \"Menu\" --duration 1.5")
+        .await;
+    assert!(result.is_err());
+    assert_eq!(backend.events(), ["scan"]);
+}
@@ -0,0 +1,1929 @@
+//! Intent catalog and invocation tests.
+//!
+//! These tests verify that `intents` is answered from the executor's registry
+//! without touching the backend, how `run` binds intent parameters, and how
+//! the `login`/`search` sugar commands map onto intents.
+
+use async_trait::async_trait;
+use oryn_common::intent::define_parser::parse_define;
+use oryn_common::intent::definition::{
+    ActionStep, ActionType, CheckpointStepWrapper, Condition, IntentDefinition, IntentOptions,
+    IntentTier, MIN_POLL_INTERVAL_MS, ParallelDef, ParallelStepWrapper, ParamType, ParameterDef,
+    RetryConfig, Step, TargetKind, TargetSpec,
+};
+use oryn_common::intent::registry::IntentRegistry;
+use oryn_common::intent::verifier::Verifier;
+use oryn_engine::backend::{Backend, BackendError, NavigationResult};
+use oryn_engine::executor::{CommandExecutor, ExecutorError, ParamPrompter};
+use oryn_engine::intent::executor::{
+    ExecutorError as IntentError, IntentExecutor, IntentStatus, is_retryable,
+};
+use oryn_engine::intent::handler::{ActionContext, ActionHandler};
+use oryn_engine::intent::loader::{IntentFormat, IntentLoader};
+use oryn_engine::intent::mapper::{IntentCall, sugar_intent};
+use oryn_engine::intent::schema::Validatable;
+use oryn_engine::protocol::{
+    Element, ElementState, PageInfo, Rect, ScanResult, ScanStats, ScannerAction, ScannerData,
+    ScannerProtocolResponse, ScrollInfo, ViewportInfo,
+};
+use oryn_engine::resolver::{ScoringConfig, Target};
+use serde_json::{Value, json};
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// A backend that fails every call, proving the catalog is served locally.
+struct OfflineBackend;
+
+#[async_trait]
+impl Backend for OfflineBackend {
+    async fn launch(&mut self) -> Result<(), BackendError> {
+        Ok(())
+    }
+    async fn close(&mut self) -> Result<(), BackendError> {
+        Ok(())
+    }
+    async fn is_ready(&self) -> bool {
+        false
+    }
+    async fn navigate(&mut self, _url: &str) -> Result<NavigationResult, BackendError> {
+        Err(BackendError::NotReady)
+    }
+    async fn screenshot(&mut self) -> Result<Vec<u8>, BackendError> {
+        Err(BackendError::NotReady)
+    }
+    async fn execute_scanner(
+        &mut self,
+        _command: ScannerAction,
+    ) -> Result<ScannerProtocolResponse, BackendError> {
+        Err(BackendError::NotReady)
+    }
+}
+
+fn checkout_intent() -> IntentDefinition {
+    IntentDefinition {
+        name: "checkout".into(),
+        description: Some("Complete the checkout form".into()),
+        version: "2.1.0".into(),
+        tier: IntentTier::Loaded,
+        triggers: Default::default(),
+        parameters: vec![
+            ParameterDef {
+                name: "quantity".into(),
+                param_type: ParamType::Number,
+                required: true,
+                default: None,
+                description: "Number of items".into(),
+                allowed: vec![],
+            },
+            ParameterDef {
+                name: "shipping".into(),
+                param_type: ParamType::String,
+                required: false,
+                default: Some(json!("standard")),
+                description: "Shipping speed".into(),
+                allowed: vec![json!("standard"), json!("express")],
+            },
+        ],
+        pre_conditions: vec![],
+        steps: vec![],
+        flow: None,
+        success: None,
+        failure: None,
+        options: Default::default(),
+    }
+}
+
+#[tokio::test]
+async fn test_intents_json_includes_parameter_types() {
+    let mut backend = OfflineBackend;
+    let mut executor = CommandExecutor::new();
+    executor.registry_mut().register(checkout_intent());
+
+    let result = executor
+        .execute_line(&mut backend, "intents --json")
+        .await
+        .unwrap();
+
+    let catalog: Vec<Value> = serde_json::from_str(&result.output).unwrap();
+    let checkout = catalog
+        .iter()
+        .find(|entry| entry["name"] == "checkout")
+        .expect("registered intent missing from catalog");
+
+    assert_eq!(checkout["version"], "2.1.0");
+    assert_eq!(checkout["tier"], "loaded");
+    assert_eq!(checkout["description"], "Complete the checkout form");
+    assert!(checkout.get("steps").is_none());
+
+    let params = checkout["parameters"].as_array().unwrap();
+    assert_eq!(params[0]["name"], "quantity");
+    assert_eq!(params[0]["type"], "number");
+    assert_eq!(params[0]["required"], true);
+    assert_eq!(params[1]["type"], "string");
+    assert_eq!(params[1]["default"], "standard");
+    assert_eq!(params[1]["allowed"], json!(["standard", "express"]));
+
+    // Built-ins are part of the catalog too
+    assert!(catalog.iter().any(|entry| entry["name"] == "login"));
+}
+
+#[tokio::test]
+async fn test_intents_lists_names_and_parameters() {
+    let mut backend = OfflineBackend;
+    let mut executor = CommandExecutor::new();
+    executor.registry_mut().register(checkout_intent());
+
+    let result = executor
+        .execute_line(&mut backend, "intents")
+        .await
+        .unwrap();
+
+    assert!(
+        result
+            .output
+            .contains("checkout(quantity, shipping) - Complete the checkout form")
+    );
+}
+
+/// A backend that answers scans with an empty page and records typed text
+/// and clicked selectors.
+#[derive(Default)]
+struct FormBackend {
+    typed: Vec<(String, String)>,
+    clicked: Vec<String>,
+}
+
+#[async_trait]
+impl Backend for FormBackend {
+    async fn launch(&mut self) -> Result<(), BackendError> {
+        Ok(())
+    }
+    async fn close(&mut self) -> Result<(), BackendError> {
+        Ok(())
+    }
+    async fn is_ready(&self) -> bool {
+        true
+    }
+    async fn navigate(&mut self, url: &str) -> Result<NavigationResult, BackendError> {
+        Ok(NavigationResult {
+            url: url.to_string(),
+            ..Default::default()
+        })
+    }
+    async fn screenshot(&mut self) -> Result<Vec<u8>, BackendError> {
+        Ok(vec![])
+    }
+    async fn execute_scanner(
+        &mut self,
+        command: ScannerAction,
+    ) -> Result<ScannerProtocolResponse, BackendError> {
+        match &command {
+            ScannerAction::Type(req) => self
+                .typed
+                .push((req.selector.clone().unwrap_or_default(), req.text.clone())),
+            ScannerAction::Click(req) => {
+                self.clicked.push(req.selector.clone().unwrap_or_default())
+            }
+            _ => {}
+        }
+        Ok(empty_scan(vec![]))
+    }
+}
+
+/// Answers prompts from a script and records what was asked.
+struct ScriptedPrompter {
+    answers: VecDeque<&'static str>,
+    asked: Arc<Mutex<Vec<(String, bool)>>>,
+    rejections: Arc<Mutex<Vec<String>>>,
+}
+
+#[async_trait]
+impl ParamPrompter for ScriptedPrompter {
+    async fn prompt(&mut self, param: &ParameterDef, sensitive: bool) -> Option<String> {
+        self.asked
+            .lock()
+            .unwrap()
+            .push((param.name.clone(), sensitive));
+        self.answers.pop_front().map(String::from)
+    }
+
+    fn reject(&mut self, param: &ParameterDef, _reason: &str) {
+        self.rejections.lock().unwrap().push(param.name.clone());
+    }
+}
+
+fn string_param(name: &str, allowed: Vec<Value>) -> ParameterDef {
+    ParameterDef {
+        name: name.into(),
+        param_type: ParamType::String,
+        required: true,
+        default: None,
+        description: String::new(),
+        allowed,
+    }
+}
+
+fn type_step(selector: &str, text: &str) -> Step {
+    Step::Action(ActionStep {
+        timeout_ms: None,
+        retry: None,
+        action: ActionType::Type,
+        target: Some(TargetSpec {
+            kind: TargetKind::Selector {
+                selector: selector.into(),
+            },
+            fallback: None,
+        }),
+        on_error: None,
+        on_success: None,
+        options: HashMap::from([("text".to_string(), json!(text))]),
+    })
+}
+
+fn empty_scan(elements: Vec<Element>) -> ScannerProtocolResponse {
+    ScannerProtocolResponse::Ok {
+        data: Box::new(ScannerData::Scan(Box::new(ScanResult {
+            page: PageInfo {
+                url: "https://example.com/signin".into(),
+                title: "Sign in".into(),
+                viewport: ViewportInfo::default(),
+                scroll: ScrollInfo::default(),
+                ready_state: None,
+            },
+            stats: ScanStats {
+                total: elements.len(),
+                scanned: elements.len(),
+                iframes: None,
+            },
+            elements,
+            patterns: None,
+            changes: None,
+            available_intents: None,
+            full_mode: false,
+            settings_applied: None,
+            timing: None,
+        }))),
+        warnings: vec![],
+    }
+}
+
+fn signin_intent() -> IntentDefinition {
+    IntentDefinition {
+        name: "signin".into(),
+        description: None,
+        version: "1.0.0".into(),
+        tier: IntentTier::Loaded,
+        triggers: Default::default(),
+        parameters: vec![
+            string_param("username", vec![]),
+            string_param("password", vec![]),
+            string_param("region", vec![json!("eu"), json!("us")]),
+        ],
+        pre_conditions: vec![],
+        steps: vec![
+            type_step("#user", "$username"),
+            type_step("#pass", "$password"),
+            type_step("#region", "$region"),
+        ],
+        flow: None,
+        success: None,
+        failure: None,
+        options: Default::default(),
+    }
+}
+
+#[tokio::test]
+async fn test_run_prompts_for_missing_params() {
+    let mut backend = FormBackend::default();
+    let mut executor = CommandExecutor::new();
+    executor.registry_mut().register(signin_intent());
+
+    let asked = Arc::new(Mutex::new(Vec::new()));
+    let rejections = Arc::new(Mutex::new(Vec::new()));
+    executor.set_prompter(Some(Box::new(ScriptedPrompter {
+        answers: VecDeque::from(["hunter2", "mars", "eu"]),
+        asked: asked.clone(),
+        rejections: rejections.clone(),
+    })));
+
+    let result = executor
+        .execute_line(&mut backend, "run signin --username alice")
+        .await
+        .unwrap();
+    assert!(result.output.contains("Intent 'signin' completed"));
+
+    // Only missing params are asked for; the password is flagged sensitive and
+    // the region is re-asked after a value outside `allowed`.
+    assert_eq!(
+        *asked.lock().unwrap(),
+        vec![
+            ("password".to_string(), true),
+            ("region".to_string(), false),
+            ("region".to_string(), false),
+        ]
+    );
+    assert_eq!(*rejections.lock().unwrap(), vec!["region".to_string()]);
+    assert_eq!(
+        backend.typed,
+        vec![
+            ("#user".to_string(), "alice".to_string()),
+            ("#pass".to_string(), "hunter2".to_string()),
+            ("#region".to_string(), "eu".to_string()),
+        ]
+    );
+}
+
+fn single_step_intent(name: &str, parameters: Vec<ParameterDef>, step: Step) -> IntentDefinition {
+    IntentDefinition {
+        name: name.into(),
+        parameters,
+        pre_conditions: vec![],
+        steps: vec![step],
+        ..signin_intent()
+    }
+}
+
+#[tokio::test]
+async fn test_run_chain_runs_intents_in_order_with_shared_params() {
+    let mut backend = FormBackend::default();
+    let mut executor = CommandExecutor::new();
+    executor.registry_mut().register(single_step_intent(
+        "login",
+        vec![string_param("username", vec![])],
+        type_step("#user", "$username"),
+    ));
+    executor.registry_mut().register(single_step_intent(
+        "greet",
+        vec![string_param("username", vec![])],
+        type_step("#greeting", "$username"),
+    ));
+
+    let result = executor
+        .execute_line(&mut backend, "run login --username alice then greet")
+        .await
+        .unwrap();
+
+    assert_eq!(
+        result.output,
+        "Intent 'login' completed\nIntent 'greet' completed"
+    );
+    assert_eq!(
+        backend.typed,
+        vec![
+            ("#user".to_string(), "alice".to_string()),
+            ("#greeting".to_string(), "alice".to_string()),
+        ]
+    );
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_shared_registry_runs_intents_on_separate_tasks() {
+    let mut registry = IntentRegistry::new();
+    registry.register(single_step_intent(
+        "login",
+        vec![string_param("username", vec![])],
+        type_step("#user", "$username"),
+    ));
+    registry.register(single_step_intent(
+        "search",
+        vec![string_param("query", vec![])],
+        type_step("#q", "$query"),
+    ));
+    let registry = Arc::new(registry);
+
+    let spawn_run = |intent: &'static str, param: &'static str, value: &'static str| {
+        let registry = Arc::clone(&registry);
+        tokio::spawn(async move {
+            let mut backend = FormBackend::default();
+            let result = IntentExecutor::new_shared(&mut backend, registry)
+                .execute(intent, HashMap::from([(param.to_string(), json!(value))]))
+                .await
+                .unwrap();
+            (result.status, backend.typed)
+        })
+    };
+    let login = spawn_run("login", "username", "alice");
+    let search = spawn_run("search", "query", "shoes");
+
+    let (login_status, login_typed) = login.await.unwrap();
+    let (search_status, search_typed) = search.await.unwrap();
+    assert_eq!(login_status, IntentStatus::Success);
+    assert_eq!(search_status, IntentStatus::Success);
+    assert_eq!(
+        login_typed,
+        vec![("#user".to_string(), "alice".to_string())]
+    );
+    assert_eq!(search_typed, vec![("#q".to_string(), "shoes".to_string())]);
+    // Both executors have dropped their handles.
+    assert_eq!(Arc::strong_count(&registry), 1);
+}
+
+#[tokio::test]
+async fn test_run_chain_stops_at_first_failure_unless_continue() {
+    let mut executor = CommandExecutor::new();
+    executor.registry_mut().register(single_step_intent(
+        "greet",
+        vec![],
+        type_step("#greeting", "Hello"),
+    ));
+
+    let mut backend = FormBackend::default();
+    let err = executor
+        .execute_line(&mut backend, "run missing then greet")
+        .await
+        .err()
+        .unwrap();
+    assert!(
+        matches!(err, ExecutorError::Intent(IntentError::IntentNotFound(_))),
+        "{:?}",
+        err
+    );
+    assert!(backend.typed.is_empty());
+
+    let result = executor
+        .execute_line(&mut backend, "run missing then greet --continue")
+        .await
+        .unwrap();
+    assert_eq!(
+        result.output,
+        "Intent 'missing' failed: Intent not found: missing\nIntent 'greet' completed"
+    );
+    assert_eq!(backend.typed.len(), 1);
+}
+
+#[tokio::test]
+async fn test_run_without_prompter_reports_missing_param() {
+    let mut backend = FormBackend::default();
+    let mut executor = CommandExecutor::new();
+    executor.registry_mut().register(signin_intent());
+
+    let err = executor
+        .execute_line(&mut backend, "run signin alice")
+        .await
+        .err()
+        .expect("missing params should fail without a prompter");
+
+    assert!(matches!(err, ExecutorError::Intent(_)));
+    assert_eq!(err.to_string(), "Missing parameter: password");
+    assert!(backend.typed.is_empty());
+}
+
+#[tokio::test]
+async fn test_run_rejects_value_outside_allowed() {
+    let mut backend = FormBackend::default();
+    let mut executor = CommandExecutor::new();
+    executor.registry_mut().register(signin_intent());
+
+    let err = executor
+        .execute_line(&mut backend, "run signin alice secret --region mars")
+        .await
+        .err()
+        .expect("disallowed value should fail");
+
+    assert!(
+        err.to_string()
+            .contains("Invalid value for parameter region")
+    );
+}
+
+fn signup_intent(password_default: Option<Value>) -> IntentDefinition {
+    let defaulted = |name: &str, default: Value| ParameterDef {
+        required: false,
+        default: Some(default),
+        ..string_param(name, vec![])
+    };
+    let mut password = string_param("password", vec![]);
+    if let Some(default) = password_default {
+        password = defaulted("password", default);
+    }
+    IntentDefinition {
+        name: "signup".into(),
+        parameters: vec![password, defaulted("confirm_password", json!("$password"))],
+        pre_conditions: vec![],
+        steps: vec![
+            type_step("#pass", "$password"),
+            type_step("#confirm", "$confirm_password"),
+        ],
+        ..signin_intent()
+    }
+}
+
+#[tokio::test]
+async fn test_default_references_another_parameter() {
+    let mut backend = FormBackend::default();
+    let mut executor = CommandExecutor::new();
+    executor.registry_mut().register(signup_intent(None));
+
+    executor
+        .execute_line(&mut backend, "run signup --password hunter2")
+        .await
+        .unwrap();
+    assert_eq!(
+        backend.typed,
+        vec![
+            ("#pass".to_string(), "hunter2".to_string()),
+            ("#confirm".to_string(), "hunter2".to_string()),
+        ]
+    );
+
+    // An explicit argument still wins over the derived default.
+    backend.typed.clear();
+    executor
+        .execute_line(
+            &mut backend,
+            "run signup --password hunter2 --confirm_password hunter3",
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        backend.typed[1],
+        ("#confirm".to_string(), "hunter3".to_string())
+    );
+}
+
+#[tokio::test]
+async fn test_cyclic_parameter_defaults_are_rejected() {
+    let mut backend = FormBackend::default();
+    let mut executor = CommandExecutor::new();
+    executor
+        .registry_mut()
+        .register(signup_intent(Some(json!("$confirm_password"))));
+
+    let err = executor
+        .execute_line(&mut backend, "run signup")
+        .await
+        .err()
+        .expect("a default cycle should fail");
+
+    assert_eq!(
+        err.to_string(),
+        "Invalid value for parameter password: default refers back to itself \
+         (password -> confirm_password -> password)"
+    );
+    assert!(backend.typed.is_empty());
+}
+
+#[tokio::test]
+async fn test_parallel_step_dispatches_every_branch() {
+    let mut backend = FormBackend::default();
+    let mut executor = CommandExecutor::new();
+    executor.registry_mut().register(IntentDefinition {
+        name: "fill_shipping".into(),
+        description: None,
+        version: "1.0.0".into(),
+        tier: IntentTier::Loaded,
+        triggers: Default::default(),
+        parameters: vec![],
+        pre_conditions: vec![],
+        steps: vec![Step::Parallel(ParallelStepWrapper {
+            parallel: ParallelDef {
+                steps: vec![
+                    type_step("#street", "1 Main St"),
+                    type_step("#city", "Springfield"),
+                    type_step("#zip", "12345"),
+                ],
+            },
+        })],
+        flow: None,
+        success: None,
+        failure: None,
+        options: Default::default(),
+    });
+
+    let result = executor
+        .execute_line(&mut backend, "run fill_shipping")
+        .await
+        .unwrap();
+    assert!(result.output.contains("Intent 'fill_shipping' completed"));
+
+    let mut typed = backend.typed.clone();
+    typed.sort();
+    assert_eq!(
+        typed,
+        vec![
+            ("#city".to_string(), "Springfield".to_string()),
+            ("#street".to_string(), "1 Main St".to_string()),
+            ("#zip".to_string(), "12345".to_string()),
+        ]
+    );
+}
+
+/// A backend whose clicks take longer than any reasonable step timeout.
+/// Counts the clicks started.
+#[derive(Default)]
+struct SlowClickBackend {
+    clicks: usize,
+}
+
+#[async_trait]
+impl Backend for SlowClickBackend {
+    async fn launch(&mut self) -> Result<(), BackendError> {
+        Ok(())
+    }
+    async fn close(&mut self) -> Result<(), BackendError> {
+        Ok(())
+    }
+    async fn is_ready(&self) -> bool {
+        true
+    }
+    async fn navigate(&mut self, url: &str) -> Result<NavigationResult, BackendError> {
+        Ok(NavigationResult {
+            url: url.to_string(),
+            ..Default::default()
+        })
+    }
+    async fn screenshot(&mut self) -> Result<Vec<u8>, BackendError> {
+        Ok(vec![])
+    }
+    async fn execute_scanner(
+        &mut self,
+        command: ScannerAction,
+    ) -> Result<ScannerProtocolResponse, BackendError> {
+        if matches!(command, ScannerAction::Click(_)) {
+            self.clicks += 1;
+            tokio::time::sleep(Duration::from_secs(5)).await;
+        }
+        Ok(empty_scan(vec![Element {
+            id: 1,
+            element_type: "button".into(),
+            role: Some("button".into()),
+            text: Some("Pay".into()),
+            label: None,
+            value: None,
+            placeholder: None,
+            selector: "#pay".into(),
+            xpath: None,
+            rect: Rect {
+                x: 0.0,
+                y: 0.0,
+                width: 80.0,
+                height: 30.0,
+            },
+            attributes: HashMap::new(),
+            state: ElementState::default(),
+            children: vec![],
+            count: None,
+            tab_index: None,
+            focusable: false,
+        }]))
+    }
+}
+
+#[tokio::test]
+async fn test_step_timeout_fails_slow_action() {
+    let definition =
+        parse_define("define pay:\n  steps:\n    - click \"Pay\" --timeout 50ms").unwrap();
+    let Step::Action(step) = &definition.steps[0] else {
+        panic!("Expected an action step");
+    };
+    assert_eq!(step.timeout_ms, Some(50));
+
+    let mut backend = SlowClickBackend::default();
+    let mut executor = CommandExecutor::new();
+    executor.registry_mut().register(definition);
+
+    let err = executor
+        .execute_line(&mut backend, "run pay")
+        .await
+        .err()
+        .expect("slow click should time out");
+
+    let message = err.to_string();
+    assert!(message.starts_with("Step execution failed"), "{}", message);
+    assert!(message.contains("timed out after"), "{}", message);
+    assert!(message.contains("limit 50ms"), "{}", message);
+}
+
+#[tokio::test]
+async fn test_step_timeout_is_retried() {
+    let definition = parse_define(
+        "define pay:\n  steps:\n    - click \"Pay\" --timeout 50ms --retry 3 --retry-delay 0ms",
+    )
+    .unwrap();
+    let mut backend = SlowClickBackend::default();
+    let mut executor = CommandExecutor::new();
+    executor.registry_mut().register(definition);
+
+    let result = executor.execute_line(&mut backend, "run pay").await;
+
+    assert!(result.is_err(), "every attempt times out");
+    assert_eq!(backend.clicks, 3);
+    assert!(is_retryable(&IntentError::StepTimedOut(
+        "Backend call timed out after 50ms (limit 50ms)".into()
+    )));
+}
+
+/// A backend whose "Pay" button never accepts a click; counts the attempts,
+/// the scans taken between them and the screenshots taken.
+#[derive(Default)]
+struct RejectingClickBackend {
+    clicks: usize,
+    scans: usize,
+    screenshots: usize,
+}
+
+#[async_trait]
+impl Backend for RejectingClickBackend {
+    async fn launch(&mut self) -> Result<(), BackendError> {
+        Ok(())
+    }
+    async fn close(&mut self) -> Result<(), BackendError> {
+        Ok(())
+    }
+    async fn is_ready(&self) -> bool {
+        true
+    }
+    async fn navigate(&mut self, url: &str) -> Result<NavigationResult, BackendError> {
+        Ok(NavigationResult {
+            url: url.to_string(),
+            ..Default::default()
+        })
+    }
+    async fn screenshot(&mut self) -> Result<Vec<u8>, BackendError> {
+        self.screenshots += 1;
+        Ok(b"\x89PNG".to_vec())
+    }
+    async fn execute_scanner(
+        &mut self,
+        command: ScannerAction,
+    ) -> Result<ScannerProtocolResponse, BackendError> {
+        if matches!(command, ScannerAction::Click(_)) {
+            self.clicks += 1;
+            return Err(BackendError::ElementNotVisible { id: 1 });
+        }
+        self.scans += 1;
+        Ok(empty_scan(vec![Element {
+            id: 1,
+            element_type: "button".into(),
+            role: Some("button".into()),
+            text: Some("Pay".into()),
+            label: None,
+            value: None,
+            placeholder: None,
+            selector: "#pay".into(),
+            xpath: None,
+            rect: Rect {
+                x: 0.0,
+                y: 0.0,
+                width: 80.0,
+                height: 30.0,
+            },
+            attributes: HashMap::new(),
+            state: ElementState::default(),
+            children: vec![],
+            count: None,
+            tab_index: None,
+            focusable: false,
+        }]))
+    }
+}
+
+/// Runs `definition` against a rejecting backend and returns the click count.
+async fn count_click_attempts(definition: IntentDefinition) -> usize {
+    let mut backend = RejectingClickBackend::default();
+    let mut executor = CommandExecutor::new();
+    let name = definition.name.clone();
+    executor.registry_mut().register(definition);
+
+    let result = executor
+        .execute_line(&mut backend, &format!("run {}", name))
+        .await;
+    assert!(result.is_err(), "a rejected click should fail the intent");
+    backend.clicks
+}
+
+#[tokio::test]
+async fn test_step_retry_overrides_intent_retry() {
+    let mut definition = parse_define(
+        "define pay:\n  steps:\n    - click \"Pay\" --retry 2 --retry-delay 0ms --timeout 5s",
+    )
+    .unwrap();
+    let Step::Action(step) = &definition.steps[0] else {
+        panic!("Expected an action step");
+    };
+    let retry = step.retry.as_ref().expect("step-level retry");
+    assert_eq!((retry.max_attempts, retry.delay_ms), (2, 0));
+    assert_eq!(step.timeout_ms, Some(5000));
+
+    definition.options.retry = Some(RetryConfig {
+        max_attempts: 4,
+        delay_ms: 0,
+        backoff_multiplier: 1.0,
+        jitter: false,
+    });
+    assert_eq!(count_click_attempts(definition.clone()).await, 2);
+
+    // Without its own policy the step falls back to the intent's.
+    if let Step::Action(step) = &mut definition.steps[0] {
+        step.retry = None;
+    }
+    assert_eq!(count_click_attempts(definition.clone()).await, 4);
+
+    // With neither, the engine default makes a single attempt.
+    definition.options.retry = None;
+    assert_eq!(count_click_attempts(definition).await, 1);
+}
+
+#[test]
+fn test_define_retry_delay_requires_retry() {
+    let err = parse_define("define pay:\n  steps:\n    - click \"Pay\" --retry-delay 1s")
+        .expect_err("a delay without attempts is rejected");
+    assert!(err.to_string().contains("--retry-delay requires --retry"));
+
+    // Flags inside the quoted target are part of the text.
+    let definition = parse_define("define pay:\n  steps:\n    - click \"Pay --retry 3\"").unwrap();
+    let Step::Action(step) = &definition.steps[0] else {
+        panic!("Expected an action step");
+    };
+    assert!(step.retry.is_none());
+}
+
+#[tokio::test]
+async fn test_missing_parameter_is_not_retried() {
+    let mut definition = parse_define("define pay:\n  steps:\n    - click \"Pay\"").unwrap();
+    definition.steps = vec![Step::Action(ActionStep {
+        action: ActionType::Navigate,
+        target: None,
+        on_error: None,
+        on_success: None,
+        timeout_ms: None,
+        retry: None,
+        options: HashMap::new(),
+    })];
+    definition.options.retry = Some(RetryConfig {
+        max_attempts: 3,
+        delay_ms: 0,
+        backoff_multiplier: 1.0,
+        jitter: false,
+    });
+
+    let mut backend = RejectingClickBackend::default();
+    let mut executor = CommandExecutor::new();
+    executor.registry_mut().register(definition);
+    let err = executor
+        .execute_line(&mut backend, "run pay")
+        .await
+        .err()
+        .unwrap();
+
+    assert!(
+        matches!(err, ExecutorError::Intent(IntentError::MissingParameter(_))),
+        "{:?}",
+        err
+    );
+    // Only the planning scan: a retry would have re-scanned first.
+    assert_eq!(backend.scans, 1);
+    assert!(!is_retryable(&IntentError::MissingParameter("url".into())));
+}
+
+#[tokio::test]
+async fn test_transient_backend_error_is_retried_with_jitter() {
+    let mut definition = parse_define("define pay:\n  steps:\n    - click \"Pay\"").unwrap();
+    definition.options.retry = Some(RetryConfig {
+        max_attempts: 3,
+        delay_ms: 10,
+        backoff_multiplier: 1.0,
+        jitter: true,
+    });
+
+    assert_eq!(count_click_attempts(definition).await, 3);
+    assert!(is_retryable(&IntentError::Backend(
+        BackendError::ElementNotVisible { id: 1 }
+    )));
+}
+
+#[tokio::test]
+async fn test_total_timeout_stops_retries_with_partial_success() {
+    let mut definition =
+        parse_define("define pay:\n  steps:\n    - click \"Pay\"\n    - click \"Pay\"").unwrap();
+    definition.options.retry = Some(RetryConfig {
+        max_attempts: 1000,
+        delay_ms: 20,
+        backoff_multiplier: 1.0,
+        jitter: false,
+    });
+    definition.options.total_timeout_ms = Some(300);
+
+    let mut registry = IntentRegistry::new();
+    registry.register(definition);
+    let verifier = Verifier::new();
+    let mut backend = RejectingClickBackend::default();
+    let started = Instant::now();
+    let result = IntentExecutor::new(&mut backend, &registry, &verifier)
+        .execute("pay", HashMap::new())
+        .await
+        .unwrap();
+
+    assert!(started.elapsed() < Duration::from_secs(2));
+    assert_eq!(
+        result.status,
+        IntentStatus::PartialSuccess {
+            completed: 0,
+            total: 2
+        }
+    );
+    assert_eq!(result.hints, vec!["Time budget of 300ms ran out at step 1"]);
+    assert!(result.logs.iter().any(|l| l.contains("Retrying")));
+    assert!(backend.clicks > 1, "retried {} times", backend.clicks);
+}
+
+/// An intent that keeps retrying a rejected click for `total_timeout_ms`.
+fn budgeted_pay(total_timeout_ms: u64) -> IntentDefinition {
+    let mut definition = parse_define("define pay:\n  steps:\n    - click \"Pay\"").unwrap();
+    definition.options.retry = Some(RetryConfig {
+        max_attempts: 1000,
+        delay_ms: 20,
+        backoff_multiplier: 1.0,
+        jitter: false,
+    });
+    definition.options.total_timeout_ms = Some(total_timeout_ms);
+    definition
+}
+
+#[tokio::test]
+async fn test_spent_budget_does_not_carry_into_the_next_run() {
+    let mut visit = parse_define("define visit:\n  steps:\n    - click \"Cart\"").unwrap();
+    visit.steps = vec![navigate_step("https://shop.example/cart")];
+
+    let mut registry = IntentRegistry::new();
+    registry.register(budgeted_pay(100));
+    registry.register(visit);
+    let verifier = Verifier::new();
+    let mut backend = RejectingClickBackend::default();
+    let mut executor = IntentExecutor::new(&mut backend, &registry, &verifier);
+    let result = executor.execute("pay", HashMap::new()).await.unwrap();
+    assert!(matches!(
+        result.status,
+        IntentStatus::PartialSuccess { completed: 0, .. }
+    ));
+
+    let result = executor.execute("visit", HashMap::new()).await.unwrap();
+    assert_eq!(result.status, IntentStatus::Success);
+}
+
+#[tokio::test]
+async fn test_resumed_run_keeps_to_its_total_timeout() {
+    let mut registry = IntentRegistry::new();
+    registry.register(budgeted_pay(300));
+    let verifier = Verifier::new();
+    let mut backend = RejectingClickBackend::default();
+    let started = Instant::now();
+    let result = IntentExecutor::new(&mut backend, &registry, &verifier)
+        .execute_with_resume("pay", HashMap::new(), None)
+        .await
+        .unwrap();
+
+    assert!(started.elapsed() < Duration::from_secs(2));
+    assert_eq!(
+        result.status,
+        IntentStatus::PartialSuccess {
+            completed: 0,
+            total: 1
+        }
+    );
+    assert_eq!(result.hints, vec!["Time budget of 300ms ran out at step 1"]);
+}
+
+#[tokio::test]
+async fn test_resumed_run_out_of_budget_counts_steps_before_the_checkpoint() {
+    let mut definition = budgeted_pay(200);
+    definition.steps.insert(
+        0,
+        Step::Checkpoint(CheckpointStepWrapper {
+            checkpoint: "cart".into(),
+        }),
+    );
+    definition
+        .steps
+        .insert(0, navigate_step("https://shop.example/cart"));
+
+    let mut registry = IntentRegistry::new();
+    registry.register(definition);
+    let verifier = Verifier::new();
+    let mut backend = RejectingClickBackend::default();
+    let result = IntentExecutor::new(&mut backend, &registry, &verifier)
+        .execute_with_resume("pay", HashMap::new(), Some("cart"))
+        .await
+        .unwrap();
+
+    assert_eq!(
+        result.status,
+        IntentStatus::PartialSuccess {
+            completed: 2,
+            total: 3
+        }
+    );
+    assert_eq!(result.hints, vec!["Time budget of 200ms ran out at step 3"]);
+    assert!(result.logs.iter().any(|l| l.contains("Resuming")));
+}
+
+#[tokio::test]
+async fn test_failing_step_captures_one_screenshot() {
+    let mut definition = parse_define("define pay:\n  steps:\n    - click \"Pay\"").unwrap();
+    definition
+        .steps
+        .insert(0, navigate_step("https://shop.example/cart"));
+    definition.options.screenshot_on_error = true;
+
+    let mut registry = IntentRegistry::new();
+    registry.register(definition.clone());
+    let verifier = Verifier::new();
+    let mut backend = RejectingClickBackend::default();
+    let result = IntentExecutor::new(&mut backend, &registry, &verifier)
+        .execute("pay", HashMap::new())
+        .await
+        .unwrap();
+
+    assert_eq!(
+        result.status,
+        IntentStatus::PartialSuccess {
+            completed: 1,
+            total: 2
+        }
+    );
+    assert_eq!(result.failure_screenshot.as_deref(), Some(&b"\x89PNG"[..]));
+    assert_eq!(backend.screenshots, 1);
+
+    // Without the option nothing is captured and a first-step failure is
+    // still an error.
+    definition.steps.remove(0);
+    definition.options.screenshot_on_error = false;
+    let mut backend = RejectingClickBackend::default();
+    let mut executor = CommandExecutor::new();
+    executor.registry_mut().register(definition);
+    assert!(
+        executor
+            .execute_line(&mut backend, "run pay")
+            .await
+            .is_err()
+    );
+    assert_eq!(backend.screenshots, 0);
+
+    // `--screenshot-on-error` turns it into a failed result with the picture
+    // saved, but the run still exits as a failure.
+    let result = executor
+        .execute_line(&mut backend, "run pay --screenshot-on-error")
+        .await
+        .unwrap();
+    assert_eq!(result.exit_code, 1);
+    let output = result.output;
+    assert!(output.starts_with("Intent 'pay' failed"), "{}", output);
+    let saved = output
+        .lines()
+        .find_map(|line| line.strip_prefix("Failure screenshot saved to "))
+        .and_then(|rest| rest.strip_suffix(" (4 bytes)"))
+        .unwrap_or_else(|| panic!("no saved screenshot in {}", output));
+    assert_eq!(std::fs::read(saved).unwrap(), b"\x89PNG");
+    std::fs::remove_file(saved).unwrap();
+    assert_eq!(backend.screenshots, 1);
+
+    // With --json the path is part of the result.
+    let result = executor
+        .execute_line(&mut backend, "run pay --screenshot-on-error --json")
+        .await
+        .unwrap();
+    assert_eq!(result.exit_code, 1);
+    let value: Value = serde_json::from_str(&result.output).unwrap();
+    let saved = value["failure_screenshot"].as_str().unwrap();
+    assert_eq!(std::fs::read(saved).unwrap(), b"\x89PNG");
+    std::fs::remove_file(saved).unwrap();
+}
+
+#[tokio::test]
+async fn test_resumed_run_captures_a_screenshot_when_the_intent_asks() {
+    let mut definition = parse_define("define pay:\n  steps:\n    - click \"Pay\"").unwrap();
+    definition.options.screenshot_on_error = true;
+
+    let mut registry = IntentRegistry::new();
+    registry.register(definition);
+    let verifier = Verifier::new();
+    let mut backend = RejectingClickBackend::default();
+    let result = IntentExecutor::new(&mut backend, &registry, &verifier)
+        .execute_with_resume("pay", HashMap::new(), None)
+        .await
+        .unwrap();
+
+    assert!(
+        matches!(result.status, IntentStatus::Failed(_)),
+        "{:?}",
+        result.status
+    );
+    assert_eq!(result.failure_screenshot.as_deref(), Some(&b"\x89PNG"[..]));
+    assert_eq!(backend.screenshots, 1);
+}
+
+fn navigate_step(url: &str) -> Step {
+    Step::Action(ActionStep {
+        timeout_ms: None,
+        retry: None,
+        action: ActionType::Navigate,
+        target: None,
+        on_error: None,
+        on_success: None,
+        options: HashMap::from([("url".to_string(), json!(url))]),
+    })
+}
+
+#[tokio::test]
+async fn test_sensitive_variables_are_masked_in_intent_logs() {
+    let mut definition = single_step_intent(
+        "open_reset_link",
+        vec![
+            string_param("reset_token_url", vec![]),
+            string_param("magic_link", vec![]),
+        ],
+        navigate_step("$reset_token_url"),
+    );
+    definition.steps.push(navigate_step("$magic_link"));
+    definition
+        .steps
+        .push(navigate_step("https://example.com/help"));
+    let mut registry = IntentRegistry::new();
+    registry.register(definition);
+    let verifier = Verifier::new();
+    let mut backend = FormBackend::default();
+
+    let mut executor = IntentExecutor::new(&mut backend, &registry, &verifier);
+    executor.set_sensitive_fields(vec!["magic_link".into()]);
+    let result = executor
+        .execute(
+            "open_reset_link",
+            HashMap::from([
+                (
+                    "reset_token_url".to_string(),
+                    json!("https://example.com/reset?t=s3cr3t"),
+                ),
+                (
+                    "magic_link".to_string(),
+                    json!("https://example.com/in/abc123"),
+                ),
+            ]),
+        )
+        .await
+        .unwrap();
+
+    let navigations: Vec<&String> = result
+        .logs
+        .iter()
+        .filter(|l| l.starts_with("Navigating to:"))
+        .collect();
+    assert_eq!(
+        navigations,
+        [
+            "Navigating to: ********",
+            "Navigating to: ********",
+            "Navigating to: https://example.com/help",
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_unmet_pre_condition_stops_intent_before_any_step() {
+    let definition = parse_define(
+        "define pay:\n  pre_conditions:\n    - url contains \"/checkout\"\n  steps:\n    - type \"Card\" \"4242\"",
+    )
+    .unwrap();
+    assert!(matches!(
+        definition.pre_conditions.as_slice(),
+        [Condition::UrlContains(parts)] if parts == &["/checkout"]
+    ));
+
+    let mut backend = FormBackend::default();
+    let mut executor = CommandExecutor::new();
+    executor.registry_mut().register(definition);
+
+    let err = executor
+        .execute_line(&mut backend, "run pay")
+        .await
+        .err()
+        .unwrap();
+
+    match err {
+        ExecutorError::Intent(IntentError::PreconditionFailed(msg)) => {
+            assert_eq!(msg, r#"url contains "/checkout""#)
+        }
+        other => panic!("Expected an unmet precondition, got {:?}", other),
+    }
+    assert!(backend.typed.is_empty());
+}
+
+#[tokio::test]
+async fn test_met_pre_conditions_let_steps_run() {
+    let mut intent = single_step_intent("login", vec![], type_step("#user", "alice"));
+    intent.pre_conditions = vec![
+        Condition::UrlContains(vec!["/signin".into()]),
+        Condition::UrlMatches("^https://example\\.com/".into()),
+    ];
+    let mut backend = FormBackend::default();
+    let mut executor = CommandExecutor::new();
+    executor.registry_mut().register(intent);
+
+    executor
+        .execute_line(&mut backend, "run login")
+        .await
+        .unwrap();
+
+    assert_eq!(
+        backend.typed,
+        vec![("#user".to_string(), "alice".to_string())]
+    );
+}
+
+#[test]
+fn test_define_rejects_unknown_pre_condition() {
+    let err = parse_define(
+        "define pay:\n  pre_conditions:\n    - logged in\n  steps:\n    - click \"Pay\"",
+    )
+    .err()
+    .unwrap();
+    assert!(
+        err.to_string().contains("Unknown condition: logged in"),
+        "{}",
+        err
+    );
+}
+
+fn sugar_call(line: &str) -> Option<IntentCall> {
+    let script = oryn_core::parse(line).unwrap();
+    sugar_intent(script.lines[0].command.as_ref().unwrap())
+}
+
+#[test]
+fn test_sugar_commands_map_to_builtin_intents() {
+    assert_eq!(
+        sugar_call(r#"login "ada@example.com" "hunter2""#),
+        Some(IntentCall {
+            name: "login".into(),
+            params: HashMap::from([
+                ("username".to_string(), json!("ada@example.com")),
+                ("password".to_string(), json!("hunter2")),
+            ]),
+        })
+    );
+    assert_eq!(
+        sugar_call(r#"search "shoes" --wait 2s"#),
+        Some(IntentCall {
+            name: "search".into(),
+            params: HashMap::from([
+                ("query".to_string(), json!("shoes")),
+                ("wait".to_string(), json!(2000)),
+            ]),
+        })
+    );
+    assert_eq!(sugar_call(r#"login "ada" "hunter2" --no-submit"#), None);
+    assert_eq!(sugar_call(r#"click "Search""#), None);
+}
+
+#[tokio::test]
+async fn test_search_sugar_runs_registered_search_intent() {
+    let intent = single_step_intent(
+        "search",
+        vec![string_param("query", vec![])],
+        type_step("#q", "$query"),
+    );
+    let mut backend = FormBackend::default();
+    let mut executor = CommandExecutor::new();
+    executor.registry_mut().register(intent);
+
+    executor
+        .execute_line(&mut backend, r#"search "shoes""#)
+        .await
+        .unwrap();
+
+    assert_eq!(backend.typed, vec![("#q".to_string(), "shoes".to_string())]);
+}
+
+#[tokio::test]
+async fn test_login_sugar_without_login_form_gives_guidance() {
+    let mut backend = FormBackend::default();
+    let mut executor = CommandExecutor::new();
+
+    let result = executor
+        .execute_line(&mut backend, r#"login "ada@example.com" "hunter2""#)
+        .await
+        .unwrap();
+
+    assert!(!result.success);
+    assert_eq!(result.exit_code, 1);
+    assert!(
+        result
+            .output
+            .starts_with("Intent 'login' needs something this page does not show"),
+        "{}",
+        result.output
+    );
+    assert!(
+        result.output.contains("Hint: navigate"),
+        "{}",
+        result.output
+    );
+    assert!(backend.typed.is_empty());
+}
+
+#[test]
+fn test_define_nests_on_success_under_its_step() {
+    let definition = parse_define(
+        "define signup:\n  steps:\n    - type \"Email\" \"ada@example.com\"\n      on_success:\n        - click \"Next\"\n    - click \"Finish\"",
+    )
+    .unwrap();
+
+    assert_eq!(definition.steps.len(), 2);
+    let Step::Action(typed) = &definition.steps[0] else {
+        panic!("Expected an action step");
+    };
+    assert!(matches!(typed.action, ActionType::Type));
+    assert!(matches!(
+        typed.on_success.as_deref(),
+        Some([Step::Action(ActionStep {
+            action: ActionType::Click,
+            ..
+        })])
+    ));
+    let Step::Action(finish) = &definition.steps[1] else {
+        panic!("Expected an action step");
+    };
+    assert!(finish.on_success.is_none());
+}
+
+#[tokio::test]
+async fn test_on_success_runs_after_successful_type() {
+    let Step::Action(mut typed) = type_step("#email", "ada@example.com") else {
+        unreachable!()
+    };
+    typed.on_success = Some(vec![Step::Action(ActionStep {
+        timeout_ms: None,
+        retry: None,
+        action: ActionType::Click,
+        target: Some(TargetSpec {
+            kind: TargetKind::Selector {
+                selector: "#next".into(),
+            },
+            fallback: None,
+        }),
+        on_error: None,
+        on_success: None,
+        options: HashMap::new(),
+    })]);
+    let intent = single_step_intent("signup", vec![], Step::Action(typed));
+    let mut backend = FormBackend::default();
+    let mut executor = CommandExecutor::new();
+    executor.registry_mut().register(intent);
+
+    executor
+        .execute_line(&mut backend, "run signup")
+        .await
+        .unwrap();
+
+    assert_eq!(
+        backend.typed,
+        vec![("#email".to_string(), "ada@example.com".to_string())]
+    );
+    assert_eq!(backend.clicked, vec!["#next".to_string()]);
+}
+
+#[tokio::test]
+async fn test_on_error_runs_for_step_with_its_own_timeout() {
+    let recover = Step::Action(ActionStep {
+        timeout_ms: None,
+        retry: None,
+        action: ActionType::Click,
+        target: Some(TargetSpec {
+            kind: TargetKind::Selector {
+                selector: "#skip".into(),
+            },
+            fallback: None,
+        }),
+        on_error: None,
+        on_success: None,
+        options: HashMap::new(),
+    });
+    // No handler is registered for the action, so the step fails.
+    let failing = Step::Action(ActionStep {
+        timeout_ms: Some(500),
+        retry: None,
+        action: ActionType::Custom("upload_file".into()),
+        target: None,
+        on_error: Some(vec![recover]),
+        on_success: None,
+        options: HashMap::new(),
+    });
+    let mut executor = CommandExecutor::new();
+    executor
+        .registry_mut()
+        .register(single_step_intent("avatar", vec![], failing));
+    let mut backend = FormBackend::default();
+
+    let result = executor
+        .execute_line(&mut backend, "run avatar")
+        .await
+        .unwrap();
+
+    assert!(result.success, "{}", result.output);
+    assert_eq!(backend.clicked, vec!["#skip".to_string()]);
+}
+
+#[tokio::test]
+async fn test_run_json_prints_masked_result_and_exit_code() {
+    let mut executor = CommandExecutor::new();
+    executor.registry_mut().register(single_step_intent(
+        "unlock",
+        vec![string_param("password", vec![])],
+        type_step("#pass", "$password"),
+    ));
+
+    let mut backend = FormBackend::default();
+    let result = executor
+        .execute_line(&mut backend, "run unlock --password hunter2 --json")
+        .await
+        .unwrap();
+    let json: Value = serde_json::from_str(&result.output).unwrap();
+    assert_eq!(json["status"], json!("Success"));
+    assert!(json["logs"].as_array().is_some_and(|logs| !logs.is_empty()));
+    assert!(!result.output.contains("hunter2"), "{}", result.output);
+    assert_eq!(backend.typed, vec![("#pass".into(), "hunter2".into())]);
+    assert_eq!((result.success, result.exit_code), (true, 0));
+
+    // A failing intent is reported in the JSON rather than as an error.
+    let result = executor
+        .execute_line(&mut OfflineBackend, "run unlock --password hunter2 --json")
+        .await
+        .unwrap();
+    let json: Value = serde_json::from_str(&result.output).unwrap();
+    assert!(json["status"]["Failed"].is_string(), "{}", result.output);
+    assert!(!result.output.contains("hunter2"), "{}", result.output);
+    assert_eq!((result.success, result.exit_code), (false, 1));
+
+    // Without --json the failures are printed, and still set the exit code.
+    let result = executor
+        .execute_line(
+            &mut OfflineBackend,
+            "run unlock --password hunter2 then unlock --continue",
+        )
+        .await
+        .unwrap();
+    assert!(
+        result.output.contains("Intent 'unlock' failed"),
+        "{}",
+        result.output
+    );
+    assert_eq!((result.success, result.exit_code), (false, 1));
+}
+
+#[tokio::test]
+async fn test_safe_mode_rejects_execute_steps() {
+    let mut executor = CommandExecutor::new();
+    executor.registry_mut().register(single_step_intent(
+        "tamper",
+        vec![],
+        Step::Action(ActionStep {
+            timeout_ms: None,
+            retry: None,
+            action: ActionType::Execute,
+            target: None,
+            on_error: None,
+            on_success: None,
+            options: HashMap::from([("script".to_string(), json!("localStorage.clear()"))]),
+        }),
+    ));
+    executor.set_safe_mode(true);
+
+    let err = executor
+        .execute_line(&mut FormBackend::default(), "run tamper")
+        .await
+        .err()
+        .unwrap();
+    assert!(
+        err.to_string()
+            .contains("execute step is disabled in safe mode"),
+        "{}",
+        err
+    );
+}
+
+/// A page that redirects to `/done` from the fourth scan on.
+#[derive(Default)]
+struct RedirectBackend {
+    scans: usize,
+}
+
+#[async_trait]
+impl Backend for RedirectBackend {
+    async fn launch(&mut self) -> Result<(), BackendError> {
+        Ok(())
+    }
+    async fn close(&mut self) -> Result<(), BackendError> {
+        Ok(())
+    }
+    async fn is_ready(&self) -> bool {
+        true
+    }
+    async fn navigate(&mut self, url: &str) -> Result<NavigationResult, BackendError> {
+        Ok(NavigationResult {
+            url: url.to_string(),
+            ..Default::default()
+        })
+    }
+    async fn screenshot(&mut self) -> Result<Vec<u8>, BackendError> {
+        Ok(vec![])
+    }
+    async fn execute_scanner(
+        &mut self,
+        _command: ScannerAction,
+    ) -> Result<ScannerProtocolResponse, BackendError> {
+        self.scans += 1;
+        let mut resp = empty_scan(vec![]);
+        if self.scans >= 4
+            && let ScannerProtocolResponse::Ok { data, .. } = &mut resp
+            && let ScannerData::Scan(scan) = data.as_mut()
+        {
+            scan.page.url = "https://example.com/done".into();
+        }
+        Ok(resp)
+    }
+}
+
+#[tokio::test]
+async fn test_url_pattern_wait_polls_at_the_intent_interval() {
+    let mut definition = single_step_intent("finish", vec![], type_step("#unused", "x"));
+    definition.flow = Some(
+        serde_json::from_value(json!({
+            "pages": [{ "name": "done", "url_pattern": "/done$", "next": "end" }]
+        }))
+        .unwrap(),
+    );
+    definition.options.poll_interval_ms = 60;
+    let mut registry = IntentRegistry::new();
+    registry.register(definition);
+    let verifier = Verifier::new();
+    let mut backend = RedirectBackend::default();
+
+    let started = Instant::now();
+    let result = IntentExecutor::new(&mut backend, &registry, &verifier)
+        .execute("finish", HashMap::new())
+        .await
+        .unwrap();
+
+    assert_eq!(result.status, IntentStatus::Success);
+    // The initial scan, then three polls: the URL flips on the last.
+    assert_eq!(backend.scans, 4);
+    assert!(
+        started.elapsed() < Duration::from_millis(500),
+        "took {:?}",
+        started.elapsed()
+    );
+}
+
+#[test]
+fn test_poll_interval_is_clamped_to_the_minimum() {
+    let mut options = IntentOptions::default();
+    assert_eq!(options.poll_interval(), 500);
+    options.poll_interval_ms = 1;
+    assert_eq!(options.poll_interval(), MIN_POLL_INTERVAL_MS);
+}
+
+#[test]
+fn test_dismiss_popups_triggers_only_on_detected_patterns() {
+    let executor = CommandExecutor::new();
+    let dismiss = executor.registry().get("dismiss_popups").unwrap();
+    assert!(
+        dismiss.triggers.pattern_warnings().is_empty(),
+        "{:?}",
+        dismiss.triggers.pattern_warnings()
+    );
+}
+
+#[tokio::test]
+async fn test_exported_builtin_intent_loads_back_unchanged() {
+    let mut executor = CommandExecutor::new();
+    let builtin = serde_json::to_value(executor.registry().get("login").unwrap()).unwrap();
+
+    for file in ["login.yaml", "login.json"] {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(file);
+        let output = executor
+            .execute_line(
+                &mut OfflineBackend,
+                &format!("export login --out \"{}\"", path.display()),
+            )
+            .await
+            .unwrap()
+            .output;
+        assert!(output.starts_with("Exported intent 'login'"), "{}", output);
+
+        let mut registry = IntentRegistry::new();
+        assert_eq!(
+            IntentLoader::load_from_dir(dir.path(), &mut registry).unwrap(),
+            1
+        );
+        let loaded = serde_json::to_value(registry.get("login").unwrap()).unwrap();
+        assert_eq!(loaded, builtin, "{} did not round-trip", file);
+    }
+}
+
+#[tokio::test]
+async fn test_dry_run_export_does_not_write_the_file() {
+    let mut executor = CommandExecutor::new();
+    executor.set_dry_run(true);
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("login.yaml");
+
+    let output = executor
+        .execute_line(
+            &mut OfflineBackend,
+            &format!("export login --out \"{}\"", path.display()),
+        )
+        .await
+        .unwrap()
+        .output;
+    assert!(
+        output.starts_with("Dry run: would export intent 'login' to"),
+        "{}",
+        output
+    );
+    assert!(!path.exists());
+}
+
+#[tokio::test]
+async fn test_export_format_overrides_the_extension() {
+    let mut executor = CommandExecutor::new();
+    let output = executor
+        .execute_line(&mut OfflineBackend, "export login --format json")
+        .await
+        .unwrap()
+        .output;
+    let printed: Value = serde_json::from_str(&output).unwrap();
+    assert_eq!(printed["name"], "login");
+
+    let Err(err) = executor
+        .execute_line(&mut OfflineBackend, "export no_such_intent")
+        .await
+    else {
+        panic!("exporting an unknown intent should fail");
+    };
+    assert!(err.to_string().contains("no_such_intent"), "{}", err);
+}
+
+#[test]
+fn test_exported_flow_loads_back_unchanged() {
+    let mut definition = single_step_intent("finish", vec![], type_step("#unused", "x"));
+    definition.flow = Some(
+        serde_json::from_value(json!({
+            "start": "form",
+            "pages": [
+                { "name": "form", "url_pattern": "/form$", "next": { "page": "done" } },
+                { "name": "done", "url_pattern": "/done$", "next": "end" }
+            ]
+        }))
+        .unwrap(),
+    );
+    let original = serde_json::to_value(&definition).unwrap();
+
+    for format in [IntentFormat::Yaml, IntentFormat::Json] {
+        let exported = IntentLoader::export(&definition, format).unwrap();
+        let loaded = IntentLoader::parse(&exported, format).unwrap();
+        assert_eq!(
+            serde_json::to_value(&loaded).unwrap(),
+            original,
+            "{:?}",
+            format
+        );
+    }
+}
+
+/// The resolved target and options a handler was called with.
+type HandlerCall = (Option<Target>, HashMap<String, String>);
+
+/// Stands in for a domain-specific `upload_file` action, recording the
+/// target and options of every step it is handed.
+#[derive(Default)]
+struct UploadHandler {
+    calls: Mutex<Vec<HandlerCall>>,
+}
+
+#[async_trait]
+impl ActionHandler for UploadHandler {
+    async fn handle(
+        &self,
+        ctx: &mut ActionContext<'_>,
+        _step: &ActionStep,
+    ) -> Result<(), IntentError> {
+        ctx.log(format!("Uploading {}", ctx.options["path"]));
+        self.calls
+            .lock()
+            .unwrap()
+            .push((ctx.target.clone(), ctx.options.clone()));
+        Ok(())
+    }
+}
+
+const UPLOAD_INTENT: &str = r##"
+name: set_avatar
+version: 1.0.0
+tier: loaded
+parameters:
+  - name: file
+    type: string
+    required: true
+steps:
+  - action: upload_file
+    target: { selector: "#avatar" }
+    path: $file
+  - action: click
+    target: { selector: "#save" }
+"##;
+
+#[tokio::test]
+async fn test_custom_action_runs_registered_handler_with_resolved_options() {
+    let mut registry = IntentRegistry::new();
+    registry.register(IntentLoader::parse(UPLOAD_INTENT, IntentFormat::Yaml).unwrap());
+    let verifier = Verifier::new();
+    let mut backend = FormBackend::default();
+    let handler = Arc::new(UploadHandler::default());
+
+    let mut executor = IntentExecutor::new(&mut backend, &registry, &verifier);
+    executor.register_action_handler("upload_file", handler.clone());
+    let result = executor
+        .execute(
+            "set_avatar",
+            HashMap::from([("file".to_string(), json!("/tmp/me.png"))]),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(result.status, IntentStatus::Success);
+    assert!(result.logs.iter().any(|l| l == "Uploading /tmp/me.png"));
+    assert_eq!(
+        *handler.calls.lock().unwrap(),
+        vec![(
+            Some(Target::Selector("#avatar".into())),
+            HashMap::from([("path".to_string(), "/tmp/me.png".to_string())]),
+        )]
+    );
+    // Built-in actions around it still run as before.
+    assert_eq!(backend.clicked, vec!["#save".to_string()]);
+}
+
+#[tokio::test]
+async fn test_custom_action_without_handler_fails_its_step() {
+    let mut registry = IntentRegistry::new();
+    registry.register(IntentLoader::parse(UPLOAD_INTENT, IntentFormat::Yaml).unwrap());
+    let verifier = Verifier::new();
+    let mut backend = FormBackend::default();
+
+    let result = IntentExecutor::new(&mut backend, &registry, &verifier)
+        .execute(
+            "set_avatar",
+            HashMap::from([("file".to_string(), json!("/tmp/me.png"))]),
+        )
+        .await;
+
+    let failure = match result {
+        Ok(res) => format!("{:?}", res.status),
+        Err(err) => err.to_string(),
+    };
+    assert!(failure.contains("upload_file"), "{}", failure);
+    assert!(backend.clicked.is_empty());
+}
+
+#[tokio::test]
+async fn test_custom_action_handler_registered_on_command_executor() {
+    let mut backend = FormBackend::default();
+    let handler = Arc::new(UploadHandler::default());
+    let mut executor = CommandExecutor::new();
+    executor
+        .registry_mut()
+        .register(IntentLoader::parse(UPLOAD_INTENT, IntentFormat::Yaml).unwrap());
+    executor.register_action_handler("upload_file", handler.clone());
+
+    let result = executor
+        .execute_line(&mut backend, "run set_avatar --file \"/tmp/me.png\"")
+        .await
+        .unwrap();
+
+    assert!(result.output.contains("set_avatar"), "{}", result.output);
+    assert_eq!(handler.calls.lock().unwrap().len(), 1);
+    assert_eq!(backend.clicked, vec!["#save".to_string()]);
+}
+
+#[test]
+fn test_misspelled_builtin_action_is_rejected_at_load() {
+    for (action, builtin) in [
+        ("clik", "click"),
+        ("tpye", "type"),
+        ("fillForm", "fill_form"),
+    ] {
+        let yaml = UPLOAD_INTENT.replace("upload_file", action);
+        let intent = IntentLoader::parse(&yaml, IntentFormat::Yaml).unwrap();
+
+        let err = intent.validate().unwrap_err().to_string();
+
+        assert!(
+            err.contains(&format!("did you mean '{}'", builtin)),
+            "{}: {}",
+            action,
+            err
+        );
+    }
+
+    let intent = IntentLoader::parse(UPLOAD_INTENT, IntentFormat::Yaml).unwrap();
+    assert!(intent.validate().is_ok());
+}
+
+/// A contact form whose email is asked twice: by an input named `email` and
+/// by one labelled "Email". Records the id each typed value went to.
+#[derive(Default)]
+struct TwoEmailsBackend {
+    typed: Vec<Option<u32>>,
+}
+
+#[async_trait]
+impl Backend for TwoEmailsBackend {
+    async fn launch(&mut self) -> Result<(), BackendError> {
+        Ok(())
+    }
+    async fn close(&mut self) -> Result<(), BackendError> {
+        Ok(())
+    }
+    async fn is_ready(&self) -> bool {
+        true
+    }
+    async fn navigate(&mut self, url: &str) -> Result<NavigationResult, BackendError> {
+        Ok(NavigationResult {
+            url: url.to_string(),
+            ..Default::default()
+        })
+    }
+    async fn screenshot(&mut self) -> Result<Vec<u8>, BackendError> {
+        Ok(vec![])
+    }
+    async fn execute_scanner(
+        &mut self,
+        command: ScannerAction,
+    ) -> Result<ScannerProtocolResponse, BackendError> {
+        if let ScannerAction::Type(req) = &command {
+            self.typed.push(req.id);
+        }
+        let input = |id: u32, label: Option<&str>, name: &str| Element {
+            id,
+            element_type: "input".into(),
+            role: Some("textbox".into()),
+            text: None,
+            label: label.map(String::from),
+            value: None,
+            placeholder: None,
+            selector: format!("#field-{}", id),
+            xpath: None,
+            rect: Rect {
+                x: 0.0,
+                y: 40.0 * id as f32,
+                width: 200.0,
+                height: 30.0,
+            },
+            attributes: HashMap::from([("name".to_string(), name.to_string())]),
+            state: ElementState::default(),
+            children: vec![],
+            count: None,
+            tab_index: None,
+            focusable: true,
+        };
+        Ok(empty_scan(vec![
+            input(1, None, "email"),
+            input(2, Some("Email"), "contact"),
+        ]))
+    }
+}
+
+#[tokio::test]
+async fn test_fill_form_weighs_fields_with_the_scoring_config() {
+    let mut fill = parse_define("define contact:\n  steps:\n    - click \"Send\"").unwrap();
+    fill.steps = vec![Step::Action(ActionStep {
+        timeout_ms: None,
+        retry: None,
+        action: ActionType::FillForm,
+        target: None,
+        on_error: None,
+        on_success: None,
+        options: HashMap::from([("data".to_string(), json!({ "email": "ada@example.com" }))]),
+    })];
+    let mut registry = IntentRegistry::new();
+    registry.register(fill);
+    let verifier = Verifier::new();
+
+    // By default a matching label (90) outranks a matching name (86).
+    let mut backend = TwoEmailsBackend::default();
+    IntentExecutor::new(&mut backend, &registry, &verifier)
+        .execute("contact", HashMap::new())
+        .await
+        .unwrap();
+    assert_eq!(backend.typed, vec![Some(2)]);
+
+    let mut backend = TwoEmailsBackend::default();
+    let mut executor = IntentExecutor::new(&mut backend, &registry, &verifier);
+    executor.set_scoring(ScoringConfig {
+        name_exact: 95,
+        ..Default::default()
+    });
+    executor.execute("contact", HashMap::new()).await.unwrap();
+    assert_eq!(backend.typed, vec![Some(1)]);
+}
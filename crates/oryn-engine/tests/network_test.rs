@@ -0,0 +1,202 @@
+//! Network interception tests.
+//!
+//! These tests verify that `intercept` hands its rules to the backend, with
+//! a mock body read from `--respond-file`, and that `requests` lists what the
+//! backend recorded, marking blocked and mocked requests.
+
+use async_trait::async_trait;
+use oryn_engine::backend::{Backend, BackendError, NavigationResult};
+use oryn_engine::executor::CommandExecutor;
+use oryn_engine::protocol::{
+    InterceptAction, InterceptRule, Interception, NetworkRequest, ScannerAction,
+    ScannerProtocolResponse,
+};
+
+/// Keeps intercept rules as a browser backend would and serves a fixed
+/// request log.
+#[derive(Default)]
+struct NetworkBackend {
+    rules: Vec<InterceptRule>,
+    requests: Vec<NetworkRequest>,
+}
+
+#[async_trait]
+impl Backend for NetworkBackend {
+    async fn launch(&mut self) -> Result<(), BackendError> {
+        Ok(())
+    }
+    async fn close(&mut self) -> Result<(), BackendError> {
+        Ok(())
+    }
+    async fn is_ready(&self) -> bool {
+        true
+    }
+    async fn navigate(&mut self, url: &str) -> Result<NavigationResult, BackendError> {
+        Ok(NavigationResult {
+            url: url.to_string(),
+            ..Default::default()
+        })
+    }
+    async fn screenshot(&mut self) -> Result<Vec<u8>, BackendError> {
+        Ok(vec![])
+    }
+    async fn execute_scanner(
+        &mut self,
+        _command: ScannerAction,
+    ) -> Result<ScannerProtocolResponse, BackendError> {
+        Err(BackendError::NotSupported("scanner".into()))
+    }
+    async fn get_requests(&mut self) -> Result<Vec<NetworkRequest>, BackendError> {
+        Ok(self.requests.clone())
+    }
+    async fn add_intercept(&mut self, rule: InterceptRule) -> Result<(), BackendError> {
+        self.rules.retain(|r| r.url_pattern != rule.url_pattern);
+        self.rules.push(rule);
+        Ok(())
+    }
+    async fn clear_intercepts(&mut self, pattern: Option<&str>) -> Result<(), BackendError> {
+        self.rules
+            .retain(|r| pattern.is_some_and(|p| r.url_pattern != p));
+        Ok(())
+    }
+}
+
+fn request(
+    method: &str,
+    url: &str,
+    status: Option<u16>,
+    intercepted: Option<Interception>,
+) -> NetworkRequest {
+    NetworkRequest {
+        method: method.into(),
+        url: url.into(),
+        status,
+        intercepted,
+    }
+}
+
+#[tokio::test]
+async fn test_intercept_rules_reach_the_backend() {
+    let mut backend = NetworkBackend::default();
+    let mut executor = CommandExecutor::new();
+
+    let output = executor
+        .execute_line(&mut backend, r#"intercept block "*.png""#)
+        .await
+        .unwrap()
+        .output;
+    assert_eq!(output, "Blocking requests to *.png");
+    executor
+        .execute_line(
+            &mut backend,
+            r#"intercept mock "/api/user" 200 '{"name":"x"}'"#,
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(backend.rules.len(), 2);
+    assert_eq!(backend.rules[0].action, InterceptAction::Block);
+    assert_eq!(
+        backend.rules[1].action,
+        InterceptAction::Mock {
+            status: 200,
+            body: r#"{"name":"x"}"#.into(),
+            content_type: None,
+        }
+    );
+
+    executor
+        .execute_line(&mut backend, r#"intercept clear "*.png""#)
+        .await
+        .unwrap();
+    assert_eq!(backend.rules.len(), 1);
+    executor
+        .execute_line(&mut backend, "intercept clear")
+        .await
+        .unwrap();
+    assert!(backend.rules.is_empty());
+}
+
+#[tokio::test]
+async fn test_respond_file_is_read_into_the_mock_body() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("user.json");
+    std::fs::write(&path, r#"{"name":"from file"}"#).unwrap();
+
+    let mut backend = NetworkBackend::default();
+    let mut executor = CommandExecutor::new();
+    executor
+        .execute_line(
+            &mut backend,
+            &format!(
+                r#"intercept "/api/user" --respond-file "{}" --status 201"#,
+                path.display()
+            ),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(
+        backend.rules[0].action,
+        InterceptAction::Mock {
+            status: 201,
+            body: r#"{"name":"from file"}"#.into(),
+            content_type: None,
+        }
+    );
+}
+
+#[tokio::test]
+async fn test_requests_marks_blocked_and_mocked_requests() {
+    let mut backend = NetworkBackend {
+        requests: vec![
+            request("GET", "https://example.com/", Some(200), None),
+            request(
+                "GET",
+                "https://example.com/logo.png",
+                None,
+                Some(Interception::Blocked),
+            ),
+            request(
+                "POST",
+                "https://example.com/api/user",
+                Some(200),
+                Some(Interception::Mocked),
+            ),
+            request("GET", "https://example.com/api/slow", None, None),
+        ],
+        ..Default::default()
+    };
+    let mut executor = CommandExecutor::new();
+
+    let output = executor
+        .execute_line(&mut backend, "requests")
+        .await
+        .unwrap()
+        .output;
+    assert_eq!(
+        output,
+        "Requests (4 of 4):\n\
+         1. GET https://example.com/ \u{2192} 200\n\
+         2. GET https://example.com/logo.png \u{2192} BLOCKED\n\
+         3. POST https://example.com/api/user \u{2192} 200 (mocked)\n\
+         4. GET https://example.com/api/slow \u{2192} pending"
+    );
+
+    let output = executor
+        .execute_line(&mut backend, r#"requests --filter "/api/" --method GET"#)
+        .await
+        .unwrap()
+        .output;
+    assert_eq!(
+        output,
+        "Requests (1 of 1):\n4. GET https://example.com/api/slow \u{2192} pending"
+    );
+
+    let output = executor
+        .execute_line(&mut backend, "requests --last 1")
+        .await
+        .unwrap()
+        .output;
+    assert!(output.starts_with("Requests (1 of 4):\n4. "), "{}", output);
+}
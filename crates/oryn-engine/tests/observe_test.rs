@@ -0,0 +1,451 @@
+//! Observe command tests.
+//!
+//! These tests verify how the executor post-processes scan results returned by
+//! the backend before formatting and storing them as resolution context.
+
+use async_trait::async_trait;
+use oryn_core::ast::ObserveCmd;
+use oryn_engine::backend::{Backend, BackendError, NavigationResult};
+use oryn_engine::cli::{OutputHandlers, run_monitor};
+use oryn_engine::executor::CommandExecutor;
+use oryn_engine::protocol::{
+    ChangeType, Element, ElementChange, ElementState, PageInfo, Rect, ScanResult, ScanStats,
+    ScannerAction, ScannerData, ScannerProtocolResponse, ScrollInfo, ViewportInfo,
+};
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+fn make_element(id: u32, text: &str, y: f32) -> Element {
+    Element {
+        id,
+        element_type: "li".into(),
+        role: Some("listitem".into()),
+        text: Some(text.into()),
+        label: None,
+        value: None,
+        placeholder: None,
+        selector: format!("#row-{}", id),
+        xpath: None,
+        rect: Rect {
+            x: 0.0,
+            y,
+            width: 300.0,
+            height: 40.0,
+        },
+        attributes: HashMap::new(),
+        state: ElementState::default(),
+        children: vec![],
+        count: None,
+        tab_index: None,
+        focusable: false,
+    }
+}
+
+/// A backend whose scans always return the configured elements. Every
+/// request is recorded.
+#[derive(Default)]
+struct ScanBackend {
+    elements: Vec<Element>,
+    requests: Vec<ScannerAction>,
+}
+
+#[async_trait]
+impl Backend for ScanBackend {
+    async fn launch(&mut self) -> Result<(), BackendError> {
+        Ok(())
+    }
+    async fn close(&mut self) -> Result<(), BackendError> {
+        Ok(())
+    }
+    async fn is_ready(&self) -> bool {
+        true
+    }
+    async fn navigate(&mut self, url: &str) -> Result<NavigationResult, BackendError> {
+        Ok(NavigationResult {
+            url: url.to_string(),
+            ..Default::default()
+        })
+    }
+    async fn screenshot(&mut self) -> Result<Vec<u8>, BackendError> {
+        Ok(vec![])
+    }
+    async fn execute_scanner(
+        &mut self,
+        command: ScannerAction,
+    ) -> Result<ScannerProtocolResponse, BackendError> {
+        self.requests.push(command);
+        Ok(scan_response(self.elements.clone(), None))
+    }
+}
+
+fn scan_response(
+    elements: Vec<Element>,
+    changes: Option<Vec<ElementChange>>,
+) -> ScannerProtocolResponse {
+    ScannerProtocolResponse::Ok {
+        data: Box::new(ScannerData::Scan(Box::new(ScanResult {
+            page: PageInfo {
+                url: "https://example.com/feed".into(),
+                title: "Feed".into(),
+                viewport: ViewportInfo::default(),
+                scroll: ScrollInfo::default(),
+                ready_state: None,
+            },
+            stats: ScanStats {
+                total: elements.len(),
+                scanned: elements.len(),
+                iframes: None,
+            },
+            elements,
+            patterns: None,
+            changes,
+            available_intents: None,
+            full_mode: false,
+            settings_applied: None,
+            timing: None,
+        }))),
+        warnings: vec![],
+    }
+}
+
+fn feed_backend() -> ScanBackend {
+    ScanBackend {
+        elements: vec![
+            make_element(1, "Loading…", 0.0),
+            make_element(2, "Loading…", 40.0),
+            make_element(3, "Loading…", 80.0),
+            make_element(4, "Loaded post", 120.0),
+            make_element(5, "Loading…", 160.0),
+        ],
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+async fn test_observe_collapses_duplicates() {
+    let mut backend = feed_backend();
+    let mut executor = CommandExecutor::new();
+
+    let result = executor
+        .execute_line(&mut backend, "observe")
+        .await
+        .unwrap();
+
+    assert!(result.output.contains("[1] li/listitem \"Loading…\" ×4"));
+    assert!(result.output.contains("[4] li/listitem \"Loaded post\"\n"));
+    assert!(!result.output.contains("[2]"));
+
+    // Only the printed list is collapsed.
+    assert_eq!(executor.get_last_scan().unwrap().elements.len(), 5);
+}
+
+#[tokio::test]
+async fn test_observe_keeps_same_size_fields_that_differ() {
+    let field = |id, label: &str| {
+        let mut el = make_element(id, "", 40.0 * id as f32);
+        el.element_type = "input".into();
+        el.role = Some("textbox".into());
+        el.text = None;
+        el.label = Some(label.into());
+        el
+    };
+    let checkbox = |id, checked| {
+        let mut el = make_element(id, "Subscribe", 40.0 * id as f32);
+        el.element_type = "input".into();
+        el.role = Some("checkbox".into());
+        el.state.checked = checked;
+        el
+    };
+    let mut backend = ScanBackend {
+        elements: vec![
+            field(1, "First name"),
+            field(2, "Last name"),
+            checkbox(3, true),
+            checkbox(4, false),
+        ],
+        ..Default::default()
+    };
+    let mut executor = CommandExecutor::new();
+
+    let output = executor
+        .execute_line(&mut backend, "observe")
+        .await
+        .unwrap()
+        .output;
+
+    assert!(!output.contains('×'), "{}", output);
+    for id in 1..=4 {
+        assert!(output.contains(&format!("[{}]", id)), "{}", output);
+    }
+}
+
+#[tokio::test]
+async fn test_collapsed_duplicate_resolves_after_observe() {
+    let mut backend = feed_backend();
+    let mut executor = CommandExecutor::new();
+    executor
+        .execute_line(&mut backend, "observe")
+        .await
+        .unwrap();
+
+    executor
+        .execute_line(&mut backend, "click \"Loading\" after \"Loaded post\"")
+        .await
+        .unwrap();
+
+    // Resolved against the observed scan, without a rescan.
+    assert!(matches!(backend.requests[0], ScannerAction::Scan(_)));
+    assert!(
+        matches!(&backend.requests[1], ScannerAction::Click(click) if click.id == Some(5)),
+        "{:?}",
+        backend.requests
+    );
+}
+
+#[tokio::test]
+async fn test_click_all_after_observe_clicks_every_collapsed_match() {
+    let mut backend = ScanBackend {
+        elements: vec![
+            make_element(1, "Remove", 0.0),
+            make_element(2, "Remove", 40.0),
+            make_element(3, "Remove", 80.0),
+            make_element(4, "Remove all", 120.0),
+        ],
+        ..Default::default()
+    };
+    let mut executor = CommandExecutor::new();
+    executor
+        .execute_line(&mut backend, "observe")
+        .await
+        .unwrap();
+
+    executor
+        .execute_line(&mut backend, "click \"Remove\" --all")
+        .await
+        .unwrap();
+
+    // Every row the observation collapsed, but not "Remove all".
+    let clicked: Vec<Option<u32>> = backend
+        .requests
+        .iter()
+        .filter_map(|request| match request {
+            ScannerAction::Click(click) => Some(click.id),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(clicked, vec![Some(1), Some(2), Some(3)]);
+}
+
+#[tokio::test]
+async fn test_observe_no_dedup_keeps_full_detail() {
+    let mut backend = feed_backend();
+    let mut executor = CommandExecutor::new();
+
+    let result = executor
+        .execute_line(&mut backend, "observe --no-dedup")
+        .await
+        .unwrap();
+
+    assert!(!result.output.contains('×'));
+    assert!(result.output.contains("[5] li/listitem \"Loading…\""));
+    assert_eq!(executor.get_last_scan().unwrap().elements.len(), 5);
+}
+
+#[tokio::test]
+async fn test_observe_diff_lists_the_new_element() {
+    let mut backend = ScanBackend {
+        elements: vec![
+            make_element(1, "Inbox", 0.0),
+            make_element(2, "Drafts", 40.0),
+        ],
+        ..Default::default()
+    };
+    let mut executor = CommandExecutor::new();
+    executor
+        .execute_line(&mut backend, "observe")
+        .await
+        .unwrap();
+
+    backend.elements.push(make_element(3, "Sent", 80.0));
+    let result = executor
+        .execute_line(&mut backend, "observe --diff")
+        .await
+        .unwrap();
+
+    assert_eq!(result.output, "# changes\nadded:\n+ [3] li \"Sent\"");
+    // The diffed scan becomes the next baseline
+    let result = executor
+        .execute_line(&mut backend, "observe --diff")
+        .await
+        .unwrap();
+    assert_eq!(result.output, "# changes\nNo changes since the last scan");
+}
+
+#[tokio::test]
+async fn test_observe_diff_reports_changed_text_and_value() {
+    let field = |value: &str| Element {
+        element_type: "input".into(),
+        role: None,
+        text: None,
+        label: Some("Email".into()),
+        value: Some(value.into()),
+        ..make_element(2, "", 40.0)
+    };
+    let mut backend = ScanBackend {
+        elements: vec![make_element(1, "2 unread", 0.0), field("")],
+        ..Default::default()
+    };
+    let mut executor = CommandExecutor::new();
+    executor
+        .execute_line(&mut backend, "observe")
+        .await
+        .unwrap();
+
+    backend.elements = vec![make_element(1, "3 unread", 0.0), field("ada@example.com")];
+    let result = executor
+        .execute_line(&mut backend, "observe --diff")
+        .await
+        .unwrap();
+
+    assert_eq!(
+        result.output,
+        "# changes\nchanged:\n\
+         ~ [1] li \"3 unread\" text: \"2 unread\" → \"3 unread\"\n\
+         ~ [2] input \"Email\" value: \"\" → \"ada@example.com\""
+    );
+}
+
+#[tokio::test]
+async fn test_observe_focus_order_sorts_by_tab_index() {
+    let tabbable = |id: u32, text: &str, tab_index: i32| Element {
+        tab_index: Some(tab_index),
+        focusable: true,
+        ..make_element(id, text, id as f32 * 40.0)
+    };
+    let mut backend = ScanBackend {
+        elements: vec![
+            tabbable(1, "Search", 0),
+            make_element(2, "Heading", 40.0),
+            tabbable(3, "Skip to content", 1),
+            tabbable(4, "Dialog body", -1),
+            tabbable(5, "Menu", 0),
+            tabbable(6, "Login", 2),
+        ],
+        ..Default::default()
+    };
+    let mut executor = CommandExecutor::new();
+
+    let result = executor
+        .execute_line(&mut backend, "observe --focus-order")
+        .await
+        .unwrap();
+
+    let order: Vec<usize> = ["[3]", "[6]", "[1]", "[5]"]
+        .iter()
+        .map(|id| result.output.find(id).expect(id))
+        .collect();
+    assert!(order.is_sorted(), "{}", result.output);
+    assert!(!result.output.contains("[2]"));
+    assert!(!result.output.contains("[4]"));
+    // Elements outside the tab sequence remain targetable
+    assert_eq!(executor.get_last_scan().unwrap().elements.len(), 6);
+}
+
+/// A backend standing in for the scanner's change tracking: each monitored
+/// scan reports the next scripted list of changes, then none.
+struct MonitorBackend {
+    changes: VecDeque<Vec<ElementChange>>,
+    scans: Arc<AtomicUsize>,
+}
+
+#[async_trait]
+impl Backend for MonitorBackend {
+    async fn launch(&mut self) -> Result<(), BackendError> {
+        Ok(())
+    }
+    async fn close(&mut self) -> Result<(), BackendError> {
+        Ok(())
+    }
+    async fn is_ready(&self) -> bool {
+        true
+    }
+    async fn navigate(&mut self, url: &str) -> Result<NavigationResult, BackendError> {
+        Ok(NavigationResult {
+            url: url.to_string(),
+            ..Default::default()
+        })
+    }
+    async fn screenshot(&mut self) -> Result<Vec<u8>, BackendError> {
+        Ok(vec![])
+    }
+    async fn execute_scanner(
+        &mut self,
+        command: ScannerAction,
+    ) -> Result<ScannerProtocolResponse, BackendError> {
+        let ScannerAction::Scan(req) = command else {
+            return Err(BackendError::NotSupported(format!("{:?}", command)));
+        };
+        assert!(req.monitor_changes);
+        self.scans.fetch_add(1, Ordering::SeqCst);
+        let changes = self.changes.pop_front().unwrap_or_default();
+        Ok(scan_response(
+            vec![make_element(1, "3 jobs running", 0.0)],
+            Some(changes),
+        ))
+    }
+}
+
+static MONITOR_OUTPUT: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+#[tokio::test]
+async fn test_monitor_reports_only_changes_after_the_baseline() {
+    let scans = Arc::new(AtomicUsize::new(0));
+    let mut backend = MonitorBackend {
+        changes: VecDeque::from([
+            // The baseline scan sees every element appear
+            vec![ElementChange {
+                id: 1,
+                change_type: ChangeType::Appeared,
+                old_value: None,
+                new_value: Some("2 jobs running".into()),
+            }],
+            vec![ElementChange {
+                id: 1,
+                change_type: ChangeType::TextChanged,
+                old_value: Some("2 jobs running".into()),
+                new_value: Some("3 jobs running".into()),
+            }],
+        ]),
+        scans: scans.clone(),
+    };
+    let mut executor = CommandExecutor::new();
+    let output = OutputHandlers {
+        out: |msg| MONITOR_OUTPUT.lock().unwrap().push(msg.to_string()),
+        err: |msg| panic!("{}", msg),
+    };
+    let cmd = ObserveCmd {
+        monitor: true,
+        interval: Some("10".into()),
+        ..Default::default()
+    };
+    let after_two_scans = async {
+        while scans.load(Ordering::SeqCst) < 2 {
+            tokio::time::sleep(Duration::from_millis(1)).await;
+        }
+    };
+
+    run_monitor(&mut backend, &mut executor, output, &cmd, after_two_scans)
+        .await
+        .unwrap();
+
+    let output = MONITOR_OUTPUT.lock().unwrap();
+    assert_eq!(
+        *output,
+        vec![
+            "Monitoring every 10ms; press Ctrl-C to stop".to_string(),
+            "# changes\n~ [1] text: \"2 jobs running\" → \"3 jobs running\"".to_string(),
+        ]
+    );
+}
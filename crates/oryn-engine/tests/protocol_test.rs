@@ -4,8 +4,9 @@
 //! particularly after removing the redundant ScanValidation variant.
 
 use oryn_engine::protocol::{
-    ActionResult, Element, ElementState, PageInfo, Rect, ScanResult, ScanStats, ScannerData,
-    ScannerProtocolResponse, ScrollInfo, ViewportInfo,
+    Action, ActionResult, BrowserAction, Element, ElementState, InterceptAction, InterceptRequest,
+    InterceptRule, PageInfo, Rect, ScanResult, ScanStats, ScannerData, ScannerProtocolResponse,
+    ScrollInfo, ViewportInfo,
 };
 use std::collections::HashMap;
 
@@ -332,6 +333,9 @@ fn test_scan_result_roundtrip() {
             attributes: HashMap::from([("class".to_string(), "primary".to_string())]),
             state: ElementState::default(),
             children: vec![],
+            count: None,
+            tab_index: None,
+            focusable: false,
         }],
         stats: ScanStats {
             total: 100,
@@ -375,3 +379,50 @@ fn test_action_result_roundtrip() {
     assert_eq!(deserialized.message, original.message);
     assert_eq!(deserialized.navigation, original.navigation);
 }
+
+/// Test which URLs an intercept rule applies to
+#[test]
+fn test_intercept_rule_matching() {
+    let rule = |url_pattern: &str| InterceptRule {
+        url_pattern: url_pattern.into(),
+        action: InterceptAction::Block,
+    };
+
+    // Wildcards must match the whole URL.
+    assert!(rule("*.png").matches("https://example.com/logo.png"));
+    assert!(!rule("*.png").matches("https://example.com/logo.png?v=2"));
+    assert!(rule("https://*.example.com/*").matches("https://cdn.example.com/a.js"));
+    assert!(rule("*/v?/*").matches("https://api.test/v2/users"));
+    assert!(!rule("*/v?/*").matches("https://api.test/v10/users"));
+
+    // Plain patterns match anywhere in the URL.
+    assert!(rule("/api/user").matches("https://example.com/api/user?id=1"));
+    assert!(!rule("/api/user").matches("https://example.com/api/me"));
+}
+
+/// Test that intercept requests round-trip through the extension protocol
+#[test]
+fn test_intercept_request_roundtrip() {
+    let action = Action::Browser(BrowserAction::Intercept(InterceptRequest::Add {
+        rule: InterceptRule {
+            url_pattern: "/api/user".into(),
+            action: InterceptAction::Mock {
+                status: 404,
+                body: "gone".into(),
+                content_type: Some("text/plain".into()),
+            },
+        },
+        body_file: None,
+    }));
+
+    let json = serde_json::to_string(&action).unwrap();
+    let Action::Browser(BrowserAction::Intercept(request)) =
+        serde_json::from_str::<Action>(&json).unwrap()
+    else {
+        panic!("Expected an intercept request from {}", json);
+    };
+    let Action::Browser(BrowserAction::Intercept(original)) = action else {
+        unreachable!()
+    };
+    assert_eq!(request, original);
+}
@@ -0,0 +1,113 @@
+//! Backend registry tests.
+//!
+//! These tests verify that a backend registered under a name can be built
+//! through the registry with its options, and that unknown names fail with
+//! the list of available backends.
+
+use async_trait::async_trait;
+use oryn_engine::backend::{Backend, BackendError, NavigationResult};
+use oryn_engine::executor::CommandExecutor;
+use oryn_engine::protocol::{ScannerAction, ScannerProtocolResponse};
+use oryn_engine::registry::{BackendOptions, BackendRegistry};
+
+/// A backend that serves every page from a mirror host.
+struct MockBackend {
+    mirror: String,
+    launched: bool,
+}
+
+#[async_trait]
+impl Backend for MockBackend {
+    async fn launch(&mut self) -> Result<(), BackendError> {
+        self.launched = true;
+        Ok(())
+    }
+    async fn close(&mut self) -> Result<(), BackendError> {
+        Ok(())
+    }
+    async fn is_ready(&self) -> bool {
+        self.launched
+    }
+    async fn navigate(&mut self, url: &str) -> Result<NavigationResult, BackendError> {
+        let mut url = url::Url::parse(url).map_err(|e| BackendError::Navigation(e.to_string()))?;
+        url.set_host(Some(&self.mirror))
+            .map_err(|e| BackendError::Navigation(e.to_string()))?;
+        Ok(NavigationResult {
+            url: url.to_string(),
+            ..Default::default()
+        })
+    }
+    async fn screenshot(&mut self) -> Result<Vec<u8>, BackendError> {
+        Ok(vec![])
+    }
+    async fn execute_scanner(
+        &mut self,
+        command: ScannerAction,
+    ) -> Result<ScannerProtocolResponse, BackendError> {
+        Err(BackendError::NotSupported(format!("{:?}", command)))
+    }
+}
+
+fn registry() -> BackendRegistry {
+    let mut registry = BackendRegistry::new();
+    registry.register("mock", |options| {
+        Ok(Box::new(MockBackend {
+            mirror: options.get("mirror").unwrap_or("localhost").to_string(),
+            launched: false,
+        }))
+    });
+    registry
+}
+
+#[tokio::test]
+async fn test_registered_backend_is_built_by_name() {
+    let registry = registry();
+    assert!(registry.contains("mock"));
+    assert_eq!(registry.names().collect::<Vec<_>>(), vec!["mock"]);
+
+    let options = BackendOptions::new().with("mirror", "mirror.example.org");
+    let mut backend = registry.create("mock", &options).unwrap();
+    backend.launch().await.unwrap();
+    assert!(backend.is_ready().await);
+
+    let mut executor = CommandExecutor::new();
+    let result = executor
+        .execute_line(&mut *backend, "goto example.com/docs")
+        .await
+        .unwrap();
+    assert!(
+        result.output.contains("https://mirror.example.org/docs"),
+        "{}",
+        result.output
+    );
+}
+
+#[test]
+fn test_unknown_backend_lists_available_ones() {
+    let err = registry()
+        .create("firefox", &BackendOptions::new())
+        .err()
+        .unwrap();
+    assert!(matches!(err, BackendError::NotSupported(_)));
+    assert!(err.to_string().contains("firefox"));
+    assert!(err.to_string().contains("available: mock"));
+}
+
+#[test]
+fn test_backend_options_parse_flags_and_numbers() {
+    let options = BackendOptions::new()
+        .with("visible", "true")
+        .with("quiet", "false")
+        .with("port", "9100")
+        .with("timeout", "soon");
+
+    assert!(options.flag("visible"));
+    assert!(!options.flag("quiet"));
+    assert!(!options.flag("missing"));
+    assert_eq!(options.parse_or("port", 9001u16).unwrap(), 9100);
+    assert_eq!(options.parse_or("retries", 3u32).unwrap(), 3);
+    assert!(matches!(
+        options.parse_or("timeout", 60u64),
+        Err(BackendError::InvalidRequest(_))
+    ));
+}
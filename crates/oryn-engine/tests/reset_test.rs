@@ -0,0 +1,135 @@
+//! Reset tests.
+//!
+//! These tests verify that `reset` clears each selected category of session
+//! state through the backend, and leaves the others alone.
+
+use async_trait::async_trait;
+use oryn_engine::backend::{Backend, BackendError, NavigationResult};
+use oryn_engine::executor::CommandExecutor;
+use oryn_engine::protocol::{Cookie, ScannerAction, ScannerProtocolResponse};
+use serde_json::Value;
+
+/// A browser session holding some of every kind of state.
+struct StatefulBackend {
+    cookies: Vec<Cookie>,
+    local_storage: Vec<&'static str>,
+    session_storage: Vec<&'static str>,
+    requests: Vec<&'static str>,
+    console: Vec<&'static str>,
+}
+
+impl StatefulBackend {
+    fn new() -> Self {
+        let cookie = |name: &str| Cookie {
+            name: name.into(),
+            value: "1".into(),
+            domain: Some("example.com".into()),
+            path: Some("/".into()),
+            expires: None,
+            http_only: None,
+            secure: None,
+            same_site: None,
+        };
+        Self {
+            cookies: vec![cookie("session"), cookie("consent")],
+            local_storage: vec!["theme"],
+            session_storage: vec!["cart"],
+            requests: vec!["GET /api/user"],
+            console: vec!["app started"],
+        }
+    }
+}
+
+#[async_trait]
+impl Backend for StatefulBackend {
+    async fn launch(&mut self) -> Result<(), BackendError> {
+        Ok(())
+    }
+    async fn close(&mut self) -> Result<(), BackendError> {
+        Ok(())
+    }
+    async fn is_ready(&self) -> bool {
+        true
+    }
+    async fn navigate(&mut self, url: &str) -> Result<NavigationResult, BackendError> {
+        Ok(NavigationResult {
+            url: url.to_string(),
+            ..Default::default()
+        })
+    }
+    async fn screenshot(&mut self) -> Result<Vec<u8>, BackendError> {
+        Ok(vec![])
+    }
+    async fn execute_scanner(
+        &mut self,
+        command: ScannerAction,
+    ) -> Result<ScannerProtocolResponse, BackendError> {
+        Err(BackendError::NotSupported(format!("{:?}", command)))
+    }
+    async fn execute_script(
+        &mut self,
+        script: &str,
+        _args: serde_json::Map<String, Value>,
+    ) -> Result<Value, BackendError> {
+        if script.contains("localStorage.clear()") {
+            self.local_storage.clear();
+        }
+        if script.contains("sessionStorage.clear()") {
+            self.session_storage.clear();
+        }
+        Ok(Value::Null)
+    }
+    async fn get_cookies(&mut self) -> Result<Vec<Cookie>, BackendError> {
+        Ok(self.cookies.clone())
+    }
+    async fn set_cookie(&mut self, cookie: Cookie) -> Result<(), BackendError> {
+        self.cookies.retain(|c| c.name != cookie.name);
+        if cookie.expires != Some(0.0) {
+            self.cookies.push(cookie);
+        }
+        Ok(())
+    }
+    async fn clear_requests(&mut self) -> Result<(), BackendError> {
+        self.requests.clear();
+        Ok(())
+    }
+    async fn clear_console(&mut self) -> Result<(), BackendError> {
+        self.console.clear();
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn test_reset_all_clears_every_category() {
+    let mut backend = StatefulBackend::new();
+    let mut executor = CommandExecutor::new();
+
+    let result = executor
+        .execute_line(&mut backend, "reset --all")
+        .await
+        .unwrap();
+
+    assert_eq!(result.output, "Reset cookies, storage, requests, console");
+    assert!(backend.cookies.is_empty());
+    assert!(backend.local_storage.is_empty());
+    assert!(backend.session_storage.is_empty());
+    assert!(backend.requests.is_empty());
+    assert!(backend.console.is_empty());
+}
+
+#[tokio::test]
+async fn test_reset_only_clears_selected_categories() {
+    let mut backend = StatefulBackend::new();
+    let mut executor = CommandExecutor::new();
+
+    executor
+        .execute_line(&mut backend, "reset --cookies --console")
+        .await
+        .unwrap();
+
+    assert!(backend.cookies.is_empty());
+    assert!(backend.console.is_empty());
+    assert_eq!(backend.local_storage, vec!["theme"]);
+    assert_eq!(backend.session_storage, vec!["cart"]);
+    assert_eq!(backend.requests, vec!["GET /api/user"]);
+}
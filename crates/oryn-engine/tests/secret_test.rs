@@ -0,0 +1,206 @@
+//! Secret typing tests.
+//!
+//! These tests verify that the text of `type ... --secret` reaches the
+//! scanner but stays out of the command's output, dry runs and anything
+//! logged about the request.
+
+use async_trait::async_trait;
+use oryn_engine::backend::{Backend, BackendError, NavigationResult};
+use oryn_engine::config::schema::SecurityConfig;
+use oryn_engine::executor::CommandExecutor;
+use oryn_engine::protocol::{
+    ActionResult, Element, ElementState, PageInfo, Rect, ScanResult, ScanStats, ScannerAction,
+    ScannerData, ScannerProtocolResponse, ScrollInfo, ViewportInfo, redact_secret_text,
+};
+use std::collections::HashMap;
+
+const SECRET: &str = "hunter2";
+
+/// A login page with a password field. Like an older scanner, it reports the
+/// typed value back whether or not it is secret.
+#[derive(Default)]
+struct LoginBackend {
+    requests: Vec<ScannerAction>,
+}
+
+#[async_trait]
+impl Backend for LoginBackend {
+    async fn launch(&mut self) -> Result<(), BackendError> {
+        Ok(())
+    }
+    async fn close(&mut self) -> Result<(), BackendError> {
+        Ok(())
+    }
+    async fn is_ready(&self) -> bool {
+        true
+    }
+    async fn navigate(&mut self, url: &str) -> Result<NavigationResult, BackendError> {
+        Ok(NavigationResult {
+            url: url.to_string(),
+            ..Default::default()
+        })
+    }
+    async fn screenshot(&mut self) -> Result<Vec<u8>, BackendError> {
+        Ok(vec![])
+    }
+    async fn execute_scanner(
+        &mut self,
+        command: ScannerAction,
+    ) -> Result<ScannerProtocolResponse, BackendError> {
+        let data = match &command {
+            ScannerAction::Scan(_) => ScannerData::Scan(Box::new(ScanResult {
+                page: PageInfo {
+                    url: "https://example.com/login".into(),
+                    title: "Login".into(),
+                    viewport: ViewportInfo::default(),
+                    scroll: ScrollInfo::default(),
+                    ready_state: None,
+                },
+                stats: ScanStats {
+                    total: 1,
+                    scanned: 1,
+                    iframes: None,
+                },
+                elements: vec![Element {
+                    id: 1,
+                    element_type: "input".into(),
+                    role: None,
+                    text: None,
+                    label: Some("Password".into()),
+                    value: None,
+                    placeholder: None,
+                    selector: "#password".into(),
+                    xpath: None,
+                    rect: Rect {
+                        x: 20.0,
+                        y: 100.0,
+                        width: 200.0,
+                        height: 30.0,
+                    },
+                    attributes: HashMap::from([("type".into(), "password".into())]),
+                    state: ElementState::default(),
+                    children: vec![],
+                    count: None,
+                    tab_index: None,
+                    focusable: true,
+                }],
+                patterns: None,
+                changes: None,
+                available_intents: None,
+                full_mode: false,
+                settings_applied: None,
+                timing: None,
+            })),
+            ScannerAction::Type(req) => ScannerData::Action(ActionResult {
+                success: true,
+                message: Some("typed".into()),
+                navigation: None,
+                dom_changes: None,
+                value: Some(req.text.clone()),
+                coordinates: None,
+            }),
+            _ => ScannerData::Action(ActionResult {
+                success: true,
+                message: None,
+                navigation: None,
+                dom_changes: None,
+                value: None,
+                coordinates: None,
+            }),
+        };
+        self.requests.push(command);
+        Ok(ScannerProtocolResponse::Ok {
+            data: Box::new(data),
+            warnings: vec![],
+        })
+    }
+}
+
+#[tokio::test]
+async fn test_secret_text_is_typed_but_never_shown() {
+    let mut backend = LoginBackend::default();
+    let mut executor = CommandExecutor::new();
+    executor
+        .execute_line(&mut backend, "observe")
+        .await
+        .unwrap();
+
+    let output = executor
+        .execute_line(&mut backend, "type \"Password\" \"hunter2\" --secret")
+        .await
+        .unwrap()
+        .output;
+    assert_eq!(output, "type \"Password\" \u{2022}\u{2022}\u{2022}\u{2022}");
+
+    let typed = backend
+        .requests
+        .iter()
+        .find_map(|req| match req {
+            ScannerAction::Type(req) => Some(req),
+            _ => None,
+        })
+        .expect("a type request");
+    // The scanner still gets the text...
+    assert_eq!(typed.text, SECRET);
+    assert!(typed.secret);
+    // ...but neither form a request is logged in carries it.
+    let debugged = format!("{:?}", ScannerAction::Type(typed.clone()));
+    assert!(!debugged.contains(SECRET), "{}", debugged);
+    let mut logged = serde_json::to_value(ScannerAction::Type(typed.clone())).unwrap();
+    redact_secret_text(&mut logged);
+    assert!(!logged.to_string().contains(SECRET), "{}", logged);
+}
+
+#[tokio::test]
+async fn test_plain_text_is_still_shown() {
+    let mut backend = LoginBackend::default();
+    let mut executor = CommandExecutor::new();
+    executor
+        .execute_line(&mut backend, "observe")
+        .await
+        .unwrap();
+
+    let output = executor
+        .execute_line(&mut backend, "type \"Password\" \"hunter2\"")
+        .await
+        .unwrap()
+        .output;
+    assert!(output.contains(SECRET), "{}", output);
+}
+
+#[tokio::test]
+async fn test_secret_text_stays_out_of_dry_runs() {
+    let mut backend = LoginBackend::default();
+    let mut executor = CommandExecutor::new();
+    executor
+        .execute_line(&mut backend, "observe")
+        .await
+        .unwrap();
+    executor.set_dry_run(true);
+
+    let output = executor
+        .execute_line(&mut backend, "type \"Password\" \"hunter2\" --secret")
+        .await
+        .unwrap()
+        .output;
+    assert!(output.starts_with("Dry run:"), "{}", output);
+    assert!(!output.contains(SECRET), "{}", output);
+}
+
+#[test]
+fn test_secret_lines_are_masked_even_without_log_redaction() {
+    let mut executor = CommandExecutor::new();
+    executor.set_security(SecurityConfig {
+        redact_in_logs: false,
+        ..Default::default()
+    });
+
+    assert_eq!(
+        executor.mask_log(r#"type "Code" "hunter2" --secret"#),
+        r#"type "********" "********" --secret"#
+    );
+    assert_eq!(
+        executor.mask_log(r#"type "Code" "hunter2""#),
+        r#"type "Code" "hunter2""#
+    );
+}
@@ -0,0 +1,217 @@
+//! Custom dropdown selection tests.
+//!
+//! These tests verify that `select` drives div-based dropdowns by clicking
+//! them open and clicking the matching option, while native `<select>`
+//! elements still go to the scanner.
+
+use async_trait::async_trait;
+use oryn_engine::backend::{Backend, BackendError, NavigationResult};
+use oryn_engine::executor::CommandExecutor;
+use oryn_engine::protocol::{
+    ActionResult, Element, ElementState, PageInfo, Rect, ScanResult, ScanStats, ScannerAction,
+    ScannerData, ScannerProtocolResponse, ScrollInfo, ViewportInfo,
+};
+use std::collections::HashMap;
+
+/// A page with one dropdown. A div combobox shows its options once clicked;
+/// a native `<select>` never does.
+struct DropdownBackend {
+    native: bool,
+    open: bool,
+    actions: Vec<ScannerAction>,
+}
+
+impl DropdownBackend {
+    fn new(native: bool) -> Self {
+        Self {
+            native,
+            open: false,
+            actions: vec![],
+        }
+    }
+
+    fn scan(&self) -> ScannerProtocolResponse {
+        let mut elements = vec![if self.native {
+            element(1, "select", "Country", &[])
+        } else {
+            element(
+                1,
+                "div",
+                "Country",
+                &[("role", "combobox"), ("aria-haspopup", "listbox")],
+            )
+        }];
+        if self.open {
+            elements.push(element(2, "div", "Brazil", &[("role", "option")]));
+            elements.push(element(3, "div", "Canada", &[("role", "option")]));
+        }
+        ScannerProtocolResponse::Ok {
+            data: Box::new(ScannerData::Scan(Box::new(ScanResult {
+                page: PageInfo {
+                    url: "https://example.com/signup".into(),
+                    title: "Sign up".into(),
+                    viewport: ViewportInfo::default(),
+                    scroll: ScrollInfo::default(),
+                    ready_state: None,
+                },
+                stats: ScanStats {
+                    total: elements.len(),
+                    scanned: elements.len(),
+                    iframes: None,
+                },
+                elements,
+                patterns: None,
+                changes: None,
+                available_intents: None,
+                full_mode: false,
+                settings_applied: None,
+                timing: None,
+            }))),
+            warnings: vec![],
+        }
+    }
+}
+
+#[async_trait]
+impl Backend for DropdownBackend {
+    async fn launch(&mut self) -> Result<(), BackendError> {
+        Ok(())
+    }
+    async fn close(&mut self) -> Result<(), BackendError> {
+        Ok(())
+    }
+    async fn is_ready(&self) -> bool {
+        true
+    }
+    async fn navigate(&mut self, url: &str) -> Result<NavigationResult, BackendError> {
+        Ok(NavigationResult {
+            url: url.to_string(),
+            ..Default::default()
+        })
+    }
+    async fn screenshot(&mut self) -> Result<Vec<u8>, BackendError> {
+        Ok(vec![])
+    }
+    async fn execute_scanner(
+        &mut self,
+        command: ScannerAction,
+    ) -> Result<ScannerProtocolResponse, BackendError> {
+        if let ScannerAction::Scan(_) = command {
+            return Ok(self.scan());
+        }
+        if let ScannerAction::Click(req) = &command
+            && req.id == Some(1)
+        {
+            self.open = true;
+        }
+        self.actions.push(command);
+        Ok(ScannerProtocolResponse::Ok {
+            data: Box::new(ScannerData::Action(ActionResult {
+                success: true,
+                message: None,
+                navigation: None,
+                dom_changes: None,
+                value: None,
+                coordinates: None,
+            })),
+            warnings: vec![],
+        })
+    }
+}
+
+fn element(id: u32, element_type: &str, text: &str, attributes: &[(&str, &str)]) -> Element {
+    Element {
+        id,
+        element_type: element_type.into(),
+        role: None,
+        text: Some(text.into()),
+        label: None,
+        value: None,
+        placeholder: None,
+        selector: format!("#e{}", id),
+        xpath: None,
+        rect: Rect {
+            x: 0.0,
+            y: 30.0 * id as f32,
+            width: 120.0,
+            height: 20.0,
+        },
+        attributes: attributes
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect::<HashMap<_, _>>(),
+        state: ElementState::default(),
+        children: vec![],
+        count: None,
+        tab_index: None,
+        focusable: false,
+    }
+}
+
+#[tokio::test]
+async fn test_select_opens_combobox_and_clicks_option() {
+    let mut backend = DropdownBackend::new(false);
+    let mut executor = CommandExecutor::new();
+
+    // No --custom: the combobox role is enough to take the custom path.
+    let result = executor
+        .execute_line(&mut backend, r#"select "Country" "Canada""#)
+        .await
+        .unwrap();
+    assert!(
+        result.output.contains(r#"Selected "Canada""#),
+        "{}",
+        result.output
+    );
+
+    let clicked: Vec<_> = backend
+        .actions
+        .iter()
+        .map(|action| match action {
+            ScannerAction::Click(req) => req.id,
+            other => panic!("expected only clicks, got {:?}", other),
+        })
+        .collect();
+    assert_eq!(clicked, vec![Some(1), Some(3)]);
+}
+
+#[tokio::test]
+async fn test_select_leaves_native_select_to_scanner() {
+    let mut backend = DropdownBackend::new(true);
+    let mut executor = CommandExecutor::new();
+
+    executor
+        .execute_line(&mut backend, r#"select "Country" "Canada""#)
+        .await
+        .unwrap();
+    assert!(
+        matches!(backend.actions.as_slice(), [ScannerAction::Select(_)]),
+        "{:?}",
+        backend.actions
+    );
+}
+
+#[tokio::test]
+async fn test_dry_run_select_does_not_click_combobox() {
+    let mut backend = DropdownBackend::new(false);
+    let mut executor = CommandExecutor::new();
+    executor
+        .execute_line(&mut backend, "observe")
+        .await
+        .unwrap();
+    executor.set_dry_run(true);
+
+    let result = executor
+        .execute_line(&mut backend, r#"select "Country" "Canada""#)
+        .await
+        .unwrap();
+    assert!(result.output.starts_with("Dry run:"), "{}", result.output);
+    assert!(
+        !backend
+            .actions
+            .iter()
+            .any(|action| matches!(action, ScannerAction::Click(_))),
+        "{:?}",
+        backend.actions
+    );
+}
@@ -0,0 +1,263 @@
+//! Stale scan tests.
+//!
+//! These tests verify that a scan of a page the browser has since navigated
+//! away from is re-taken before the next target is resolved, and that a
+//! rescan that fails is reported with its own error.
+
+use async_trait::async_trait;
+use oryn_engine::backend::{Backend, BackendError, NavigationResult};
+use oryn_engine::executor::{CommandExecutor, ExecutorError};
+use oryn_engine::protocol::{
+    ActionResult, Element, ElementState, PageInfo, Rect, ScanResult, ScanStats, ScannerAction,
+    ScannerData, ScannerProtocolResponse, ScrollInfo, ViewportInfo,
+};
+use std::collections::HashMap;
+
+/// A paged listing whose "Next" button has a different id on every page.
+/// Scans describe whichever page was navigated to last.
+struct PagedBackend {
+    url: String,
+    requests: Vec<ScannerAction>,
+    /// Fail scans, as a backend that lost its page would.
+    scans_fail: bool,
+    /// Clicks follow "Next" to the following page and report navigating.
+    clicks_navigate: bool,
+}
+
+impl PagedBackend {
+    fn new() -> Self {
+        Self {
+            url: "https://example.com/page/1".into(),
+            requests: vec![],
+            scans_fail: false,
+            clicks_navigate: false,
+        }
+    }
+
+    fn next_button_id(&self) -> u32 {
+        if self.url.ends_with("/page/1") { 1 } else { 7 }
+    }
+}
+
+#[async_trait]
+impl Backend for PagedBackend {
+    async fn launch(&mut self) -> Result<(), BackendError> {
+        Ok(())
+    }
+    async fn close(&mut self) -> Result<(), BackendError> {
+        Ok(())
+    }
+    async fn is_ready(&self) -> bool {
+        true
+    }
+    async fn navigate(&mut self, url: &str) -> Result<NavigationResult, BackendError> {
+        self.url = url.to_string();
+        Ok(NavigationResult {
+            url: url.to_string(),
+            ..Default::default()
+        })
+    }
+    async fn screenshot(&mut self) -> Result<Vec<u8>, BackendError> {
+        Ok(vec![])
+    }
+    async fn execute_scanner(
+        &mut self,
+        command: ScannerAction,
+    ) -> Result<ScannerProtocolResponse, BackendError> {
+        let data = match &command {
+            ScannerAction::Scan(_) if self.scans_fail => {
+                return Err(BackendError::Other("page is gone".into()));
+            }
+            ScannerAction::Scan(_) => ScannerData::Scan(Box::new(ScanResult {
+                page: PageInfo {
+                    url: self.url.clone(),
+                    title: "Listing".into(),
+                    viewport: ViewportInfo::default(),
+                    scroll: ScrollInfo::default(),
+                    ready_state: None,
+                },
+                stats: ScanStats {
+                    total: 1,
+                    scanned: 1,
+                    iframes: None,
+                },
+                elements: vec![Element {
+                    id: self.next_button_id(),
+                    element_type: "button".into(),
+                    role: Some("button".into()),
+                    text: Some("Next".into()),
+                    label: None,
+                    value: None,
+                    placeholder: None,
+                    selector: "#next".into(),
+                    xpath: None,
+                    rect: Rect {
+                        x: 20.0,
+                        y: 100.0,
+                        width: 80.0,
+                        height: 30.0,
+                    },
+                    attributes: HashMap::new(),
+                    state: ElementState::default(),
+                    children: vec![],
+                    count: None,
+                    tab_index: None,
+                    focusable: true,
+                }],
+                patterns: None,
+                changes: None,
+                available_intents: None,
+                full_mode: false,
+                settings_applied: None,
+                timing: None,
+            })),
+            ScannerAction::Click(_) if self.clicks_navigate => {
+                self.url = "https://example.com/page/2".into();
+                ScannerData::Action(ActionResult {
+                    success: true,
+                    message: None,
+                    navigation: Some(true),
+                    dom_changes: None,
+                    value: None,
+                    coordinates: None,
+                })
+            }
+            _ => ScannerData::Action(ActionResult {
+                success: true,
+                message: None,
+                navigation: None,
+                dom_changes: None,
+                value: None,
+                coordinates: None,
+            }),
+        };
+        self.requests.push(command);
+        Ok(ScannerProtocolResponse::Ok {
+            data: Box::new(data),
+            warnings: vec![],
+        })
+    }
+}
+
+fn clicked_ids(backend: &PagedBackend) -> Vec<Option<u32>> {
+    backend
+        .requests
+        .iter()
+        .filter_map(|req| match req {
+            ScannerAction::Click(click) => Some(click.id),
+            _ => None,
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn test_url_change_forces_rescan_before_resolving() {
+    let mut backend = PagedBackend::new();
+    let mut executor = CommandExecutor::new();
+    executor
+        .execute_line(&mut backend, "observe")
+        .await
+        .unwrap();
+    assert_eq!(executor.scan_generation(), 1);
+
+    executor
+        .execute_line(&mut backend, "goto \"https://example.com/page/2\"")
+        .await
+        .unwrap();
+    executor
+        .execute_line(&mut backend, "click \"Next\"")
+        .await
+        .unwrap();
+
+    // The page 1 scan would have resolved "Next" to 1.
+    assert_eq!(clicked_ids(&backend), vec![Some(7)]);
+    assert_eq!(executor.scan_generation(), 2);
+    assert!(matches!(backend.requests[1], ScannerAction::Scan(_)));
+}
+
+#[tokio::test]
+async fn test_current_scan_is_not_retaken() {
+    let mut backend = PagedBackend::new();
+    let mut executor = CommandExecutor::new();
+    executor
+        .execute_line(&mut backend, "observe")
+        .await
+        .unwrap();
+    executor
+        .execute_line(&mut backend, "click \"Next\"")
+        .await
+        .unwrap();
+
+    assert_eq!(clicked_ids(&backend), vec![Some(1)]);
+    assert_eq!(executor.scan_generation(), 1);
+}
+
+#[tokio::test]
+async fn test_navigating_click_forces_rescan_before_resolving() {
+    let mut backend = PagedBackend::new();
+    backend.clicks_navigate = true;
+    let mut executor = CommandExecutor::new();
+    executor
+        .execute_line(&mut backend, "observe")
+        .await
+        .unwrap();
+
+    executor
+        .execute_line(&mut backend, "click \"Next\"")
+        .await
+        .unwrap();
+    executor
+        .execute_line(&mut backend, "click \"Next\"")
+        .await
+        .unwrap();
+
+    // No URL was seen for page 2, only the click's report of navigating.
+    assert_eq!(clicked_ids(&backend), vec![Some(1), Some(7)]);
+    assert_eq!(executor.scan_generation(), 2);
+}
+
+#[tokio::test]
+async fn test_click_all_rescans_a_stale_scan() {
+    let mut backend = PagedBackend::new();
+    let mut executor = CommandExecutor::new();
+    executor
+        .execute_line(&mut backend, "observe")
+        .await
+        .unwrap();
+    executor
+        .execute_line(&mut backend, "goto \"https://example.com/page/2\"")
+        .await
+        .unwrap();
+
+    executor
+        .execute_line(&mut backend, "click \"Next\" --all")
+        .await
+        .unwrap();
+
+    assert_eq!(clicked_ids(&backend), vec![Some(7)]);
+}
+
+#[tokio::test]
+async fn test_failed_rescan_of_a_stale_scan_is_reported() {
+    let mut backend = PagedBackend::new();
+    let mut executor = CommandExecutor::new();
+    executor
+        .execute_line(&mut backend, "observe")
+        .await
+        .unwrap();
+    executor
+        .execute_line(&mut backend, "goto \"https://example.com/page/2\"")
+        .await
+        .unwrap();
+    backend.scans_fail = true;
+
+    let Err(err) = executor.execute_line(&mut backend, "click \"Next\"").await else {
+        panic!("a stale scan must not be resolved against");
+    };
+    assert!(
+        matches!(&err, ExecutorError::Backend(BackendError::Other(msg)) if msg == "page is gone"),
+        "{}",
+        err
+    );
+    assert!(clicked_ids(&backend).is_empty());
+}
@@ -0,0 +1,112 @@
+//! Request throttling tests.
+//!
+//! These tests verify that `min_request_interval_ms` spaces scanner requests
+//! out, scans included, and that requests are unthrottled by default.
+
+use async_trait::async_trait;
+use oryn_engine::backend::{Backend, BackendError, NavigationResult};
+use oryn_engine::config::schema::OrynConfig;
+use oryn_engine::executor::CommandExecutor;
+use oryn_engine::protocol::{
+    PageInfo, ScanResult, ScanStats, ScannerAction, ScannerData, ScannerProtocolResponse,
+    ScrollInfo, ViewportInfo,
+};
+use std::time::{Duration, Instant};
+
+/// Answers every scanner request at once with an empty page and records
+/// when each one arrived.
+#[derive(Default)]
+struct InstantBackend {
+    arrivals: Vec<Instant>,
+}
+
+#[async_trait]
+impl Backend for InstantBackend {
+    async fn launch(&mut self) -> Result<(), BackendError> {
+        Ok(())
+    }
+    async fn close(&mut self) -> Result<(), BackendError> {
+        Ok(())
+    }
+    async fn is_ready(&self) -> bool {
+        true
+    }
+    async fn navigate(&mut self, url: &str) -> Result<NavigationResult, BackendError> {
+        Ok(NavigationResult {
+            url: url.to_string(),
+            ..Default::default()
+        })
+    }
+    async fn screenshot(&mut self) -> Result<Vec<u8>, BackendError> {
+        Ok(vec![])
+    }
+    async fn execute_scanner(
+        &mut self,
+        _command: ScannerAction,
+    ) -> Result<ScannerProtocolResponse, BackendError> {
+        self.arrivals.push(Instant::now());
+        Ok(ScannerProtocolResponse::Ok {
+            data: Box::new(ScannerData::Scan(Box::new(ScanResult {
+                page: PageInfo {
+                    url: "https://example.com".into(),
+                    title: "Example".into(),
+                    viewport: ViewportInfo::default(),
+                    scroll: ScrollInfo::default(),
+                    ready_state: None,
+                },
+                stats: ScanStats {
+                    total: 0,
+                    scanned: 0,
+                    iframes: None,
+                },
+                elements: vec![],
+                patterns: None,
+                changes: None,
+                available_intents: None,
+                full_mode: false,
+                settings_applied: None,
+                timing: None,
+            }))),
+            warnings: vec![],
+        })
+    }
+}
+
+#[tokio::test]
+async fn test_min_request_interval_spaces_scanner_requests() {
+    let mut config = OrynConfig::default();
+    config.intent_engine.min_request_interval_ms = 50;
+    let mut executor = CommandExecutor::with_config(&config);
+    let mut backend = InstantBackend::default();
+
+    let started = Instant::now();
+    for _ in 0..3 {
+        executor
+            .execute_line(&mut backend, "observe")
+            .await
+            .unwrap();
+    }
+
+    assert!(started.elapsed() >= Duration::from_millis(100));
+    assert_eq!(backend.arrivals.len(), 3);
+    for pair in backend.arrivals.windows(2) {
+        assert!(pair[1] - pair[0] >= Duration::from_millis(50));
+    }
+}
+
+#[tokio::test]
+async fn test_requests_are_not_throttled_by_default() {
+    let mut executor = CommandExecutor::new();
+    let mut backend = InstantBackend::default();
+
+    let started = Instant::now();
+    for _ in 0..3 {
+        executor
+            .execute_line(&mut backend, "observe")
+            .await
+            .unwrap();
+    }
+
+    assert_eq!(backend.arrivals.len(), 3);
+    assert!(started.elapsed() < Duration::from_millis(1000));
+}
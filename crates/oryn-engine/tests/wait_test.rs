@@ -0,0 +1,982 @@
+//! Wait condition tests.
+//!
+//! These tests verify waits the executor evaluates itself by polling the
+//! backend, rather than handing them to the scanner, and that scanner waits
+//! are bounded by their timeout.
+
+use async_trait::async_trait;
+use oryn_common::intent::define_parser::parse_define;
+use oryn_engine::backend::{Backend, BackendError, NavigationResult};
+use oryn_engine::executor::{CommandExecutor, ExecutorError};
+use oryn_engine::intent::executor::ExecutorError as IntentError;
+use oryn_engine::protocol::{
+    ActionResult, Element, ElementState, PageInfo, Rect, ScanResult, ScanStats, ScannerAction,
+    ScannerData, ScannerProtocolResponse, ScrollInfo, ViewportInfo, WaitRequest,
+};
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+
+/// A page with a "Status" badge whose text moves through `texts`, one per
+/// scan, and then stays on the last one.
+struct StatusBackend {
+    texts: Vec<&'static str>,
+    scans: usize,
+}
+
+impl StatusBackend {
+    fn new(texts: Vec<&'static str>) -> Self {
+        Self { texts, scans: 0 }
+    }
+}
+
+#[async_trait]
+impl Backend for StatusBackend {
+    async fn launch(&mut self) -> Result<(), BackendError> {
+        Ok(())
+    }
+    async fn close(&mut self) -> Result<(), BackendError> {
+        Ok(())
+    }
+    async fn is_ready(&self) -> bool {
+        true
+    }
+    async fn navigate(&mut self, url: &str) -> Result<NavigationResult, BackendError> {
+        Ok(NavigationResult {
+            url: url.to_string(),
+            ..Default::default()
+        })
+    }
+    async fn screenshot(&mut self) -> Result<Vec<u8>, BackendError> {
+        Ok(vec![])
+    }
+    async fn execute_scanner(
+        &mut self,
+        command: ScannerAction,
+    ) -> Result<ScannerProtocolResponse, BackendError> {
+        if !matches!(command, ScannerAction::Scan(_)) {
+            return Err(BackendError::NotSupported(format!("{:?}", command)));
+        }
+        let text = self.texts[self.scans.min(self.texts.len() - 1)];
+        self.scans += 1;
+        Ok(single_element_scan("span", "status", text, "Status"))
+    }
+}
+
+/// A scan of a page holding one element.
+fn single_element_scan(
+    element_type: &str,
+    role: &str,
+    text: &str,
+    label: &str,
+) -> ScannerProtocolResponse {
+    let element = Element {
+        id: 1,
+        element_type: element_type.into(),
+        role: Some(role.into()),
+        text: Some(text.into()),
+        label: Some(label.into()),
+        value: None,
+        placeholder: None,
+        selector: format!("#{}", label.to_lowercase()),
+        xpath: None,
+        rect: Rect {
+            x: 0.0,
+            y: 0.0,
+            width: 120.0,
+            height: 20.0,
+        },
+        attributes: HashMap::new(),
+        state: ElementState::default(),
+        children: vec![],
+        count: None,
+        tab_index: None,
+        focusable: false,
+    };
+    ScannerProtocolResponse::Ok {
+        data: Box::new(ScannerData::Scan(Box::new(ScanResult {
+            page: PageInfo {
+                url: "https://example.com/jobs/42".into(),
+                title: "Job 42".into(),
+                viewport: ViewportInfo::default(),
+                scroll: ScrollInfo::default(),
+                ready_state: None,
+            },
+            elements: vec![element],
+            stats: ScanStats {
+                total: 1,
+                scanned: 1,
+                iframes: None,
+            },
+            patterns: None,
+            changes: None,
+            available_intents: None,
+            full_mode: false,
+            settings_applied: None,
+            timing: None,
+        }))),
+        warnings: vec![],
+    }
+}
+
+#[tokio::test]
+async fn test_wait_until_text_equals_polls_until_it_changes() {
+    let mut backend = StatusBackend::new(vec!["Pending", "Running", "Complete"]);
+    let mut executor = CommandExecutor::new();
+
+    let result = executor
+        .execute_line(
+            &mut backend,
+            r#"wait until text of "Status" == "Complete" --timeout 5s"#,
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(backend.scans, 3);
+    assert!(result.output.contains("\"Complete\""), "{}", result.output);
+}
+
+#[tokio::test]
+async fn test_wait_until_text_contains() {
+    let mut backend = StatusBackend::new(vec!["Queued", "3 of 3 done"]);
+    let mut executor = CommandExecutor::new();
+
+    executor
+        .execute_line(
+            &mut backend,
+            r#"wait until text of "Status" contains "done""#,
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(backend.scans, 2);
+}
+
+#[tokio::test]
+async fn test_wait_until_text_times_out_with_last_value() {
+    let mut backend = StatusBackend::new(vec!["Pending"]);
+    let mut executor = CommandExecutor::new();
+
+    let err = executor
+        .execute_line(
+            &mut backend,
+            r#"wait until text of "Status" == "Complete" --timeout 300ms"#,
+        )
+        .await
+        .err()
+        .unwrap();
+
+    match err {
+        ExecutorError::Timeout(msg) => {
+            assert!(msg.contains("within 300ms"), "{}", msg);
+            assert!(msg.contains("last saw \"Pending\""), "{}", msg);
+        }
+        other => panic!("Expected a timeout, got {:?}", other),
+    }
+}
+
+/// A backend reporting a scripted in-flight request count, one per poll and
+/// then the last one forever. Without a script it cannot count requests.
+#[derive(Default)]
+struct NetworkBackend {
+    pending: Option<VecDeque<usize>>,
+    polls: usize,
+    scanner_waits: Vec<String>,
+}
+
+impl NetworkBackend {
+    fn draining(counts: Vec<usize>) -> Self {
+        Self {
+            pending: Some(counts.into()),
+            ..Default::default()
+        }
+    }
+}
+
+#[async_trait]
+impl Backend for NetworkBackend {
+    async fn launch(&mut self) -> Result<(), BackendError> {
+        Ok(())
+    }
+    async fn close(&mut self) -> Result<(), BackendError> {
+        Ok(())
+    }
+    async fn is_ready(&self) -> bool {
+        true
+    }
+    async fn navigate(&mut self, url: &str) -> Result<NavigationResult, BackendError> {
+        Ok(NavigationResult {
+            url: url.to_string(),
+            ..Default::default()
+        })
+    }
+    async fn screenshot(&mut self) -> Result<Vec<u8>, BackendError> {
+        Ok(vec![])
+    }
+    async fn execute_scanner(
+        &mut self,
+        command: ScannerAction,
+    ) -> Result<ScannerProtocolResponse, BackendError> {
+        let ScannerAction::Wait(req) = command else {
+            return Err(BackendError::NotSupported(format!("{:?}", command)));
+        };
+        self.scanner_waits.push(req.condition);
+        Ok(ScannerProtocolResponse::Ok {
+            data: Box::new(ScannerData::Action(ActionResult {
+                success: true,
+                message: None,
+                navigation: None,
+                dom_changes: None,
+                value: None,
+                coordinates: None,
+            })),
+            warnings: vec![],
+        })
+    }
+    async fn pending_requests(&mut self) -> Result<usize, BackendError> {
+        let counts = self
+            .pending
+            .as_mut()
+            .ok_or_else(|| BackendError::NotSupported("pending_requests".into()))?;
+        self.polls += 1;
+        Ok(if counts.len() > 1 {
+            counts.pop_front().unwrap()
+        } else {
+            counts[0]
+        })
+    }
+}
+
+#[tokio::test]
+async fn test_wait_idle_once_requests_drain() {
+    // A request restarting mid-window resets the quiet period.
+    let mut backend = NetworkBackend::draining(vec![3, 2, 0, 1, 0]);
+    let mut executor = CommandExecutor::new();
+
+    let result = executor
+        .execute_line(&mut backend, "wait idle --quiet 200ms --timeout 5s")
+        .await
+        .unwrap();
+
+    assert_eq!(result.output, "Network idle for 200ms");
+    // The scripted counts run out on the fifth poll; the quiet window that
+    // starts there needs more polls before it has lasted 200ms.
+    assert!(backend.polls > 5, "polled {} times", backend.polls);
+    assert!(backend.scanner_waits.is_empty());
+}
+
+#[tokio::test]
+async fn test_wait_idle_times_out_while_requests_stay_open() {
+    let mut backend = NetworkBackend::draining(vec![2]);
+    let mut executor = CommandExecutor::new();
+
+    let err = executor
+        .execute_line(&mut backend, "wait idle --timeout 200ms")
+        .await
+        .err()
+        .unwrap();
+
+    match err {
+        ExecutorError::Timeout(msg) => {
+            assert!(msg.contains("idle for 500ms within 200ms"), "{}", msg);
+            assert!(msg.contains("2 requests in flight"), "{}", msg);
+        }
+        other => panic!("Expected a timeout, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_wait_idle_falls_back_to_scanner_without_request_counts() {
+    let mut backend = NetworkBackend::default();
+    let mut executor = CommandExecutor::new();
+
+    executor
+        .execute_line(&mut backend, "wait idle")
+        .await
+        .unwrap();
+
+    assert_eq!(backend.scanner_waits, vec!["idle".to_string()]);
+}
+
+/// A page whose "Save" button never becomes visible: scans show it
+/// collapsed to zero height, and scanner waits are recorded and then never
+/// answered.
+#[derive(Default)]
+struct NeverVisibleBackend {
+    scans: usize,
+    wait_timeouts: Vec<Option<u64>>,
+}
+
+#[async_trait]
+impl Backend for NeverVisibleBackend {
+    async fn launch(&mut self) -> Result<(), BackendError> {
+        Ok(())
+    }
+    async fn close(&mut self) -> Result<(), BackendError> {
+        Ok(())
+    }
+    async fn is_ready(&self) -> bool {
+        true
+    }
+    async fn navigate(&mut self, url: &str) -> Result<NavigationResult, BackendError> {
+        Ok(NavigationResult {
+            url: url.to_string(),
+            ..Default::default()
+        })
+    }
+    async fn screenshot(&mut self) -> Result<Vec<u8>, BackendError> {
+        Ok(vec![])
+    }
+    async fn execute_scanner(
+        &mut self,
+        command: ScannerAction,
+    ) -> Result<ScannerProtocolResponse, BackendError> {
+        match command {
+            ScannerAction::Scan(_) => {
+                self.scans += 1;
+                let mut resp = single_element_scan("button", "button", "Save", "Save");
+                if let ScannerProtocolResponse::Ok { data, .. } = &mut resp
+                    && let ScannerData::Scan(scan) = data.as_mut()
+                {
+                    scan.elements[0].rect.height = 0.0;
+                }
+                Ok(resp)
+            }
+            ScannerAction::Wait(req) => {
+                self.wait_timeouts.push(req.timeout);
+                std::future::pending().await
+            }
+            other => Err(BackendError::NotSupported(format!("{:?}", other))),
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_wait_visible_fails_within_its_timeout() {
+    let mut backend = NeverVisibleBackend::default();
+    let mut executor = CommandExecutor::new();
+
+    let started = Instant::now();
+    let err = executor
+        .execute_line(&mut backend, r#"wait visible "Save" --timeout 200ms"#)
+        .await
+        .err()
+        .unwrap();
+
+    assert!(started.elapsed() < Duration::from_secs(2));
+    assert!(backend.scans > 0);
+    match err {
+        ExecutorError::Timeout(msg) => {
+            assert!(
+                msg.starts_with(r#"wait visible "Save" did not complete within 200ms"#),
+                "{}",
+                msg
+            );
+        }
+        other => panic!("Expected a timeout, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_wait_without_timeout_uses_the_default() {
+    let mut backend = NeverVisibleBackend::default();
+    let mut executor = CommandExecutor::new();
+
+    let pending = executor.execute_line(&mut backend, r#"wait visible "Save""#);
+    assert!(
+        tokio::time::timeout(Duration::from_millis(100), pending)
+            .await
+            .is_err()
+    );
+    assert!(backend.scans > 0);
+}
+
+#[tokio::test]
+async fn test_intent_wait_step_fails_within_its_timeout() {
+    let definition =
+        parse_define("define save:\n  steps:\n    - wait visible \"Save\" --timeout 200ms")
+            .unwrap();
+    let mut backend = NeverVisibleBackend::default();
+    let mut executor = CommandExecutor::new();
+    executor.registry_mut().register(definition);
+
+    let started = Instant::now();
+    let err = executor
+        .execute_line(&mut backend, "run save")
+        .await
+        .err()
+        .unwrap();
+
+    assert!(started.elapsed() < Duration::from_secs(2));
+    assert_eq!(backend.wait_timeouts, vec![Some(200)]);
+    match err {
+        ExecutorError::Intent(IntentError::StepTimedOut(msg)) => {
+            assert!(msg.contains("wait visible"), "{}", msg);
+            assert!(msg.contains("within 200ms"), "{}", msg);
+        }
+        other => panic!("Expected a failed step, got {:?}", other),
+    }
+}
+
+/// A page that never answers a scan.
+struct HungScanBackend;
+
+#[async_trait]
+impl Backend for HungScanBackend {
+    async fn launch(&mut self) -> Result<(), BackendError> {
+        Ok(())
+    }
+    async fn close(&mut self) -> Result<(), BackendError> {
+        Ok(())
+    }
+    async fn is_ready(&self) -> bool {
+        true
+    }
+    async fn navigate(&mut self, url: &str) -> Result<NavigationResult, BackendError> {
+        Ok(NavigationResult {
+            url: url.to_string(),
+            ..Default::default()
+        })
+    }
+    async fn screenshot(&mut self) -> Result<Vec<u8>, BackendError> {
+        Ok(vec![])
+    }
+    async fn execute_scanner(
+        &mut self,
+        _command: ScannerAction,
+    ) -> Result<ScannerProtocolResponse, BackendError> {
+        std::future::pending().await
+    }
+}
+
+#[tokio::test]
+async fn test_wait_visible_is_bounded_when_a_scan_hangs() {
+    let mut backend = HungScanBackend;
+    let mut executor = CommandExecutor::new();
+
+    let started = Instant::now();
+    let err = executor
+        .execute_line(&mut backend, r#"wait visible "Save" --timeout 200ms"#)
+        .await
+        .err()
+        .unwrap();
+
+    assert!(started.elapsed() < Duration::from_secs(2));
+    match err {
+        ExecutorError::Timeout(msg) => assert_eq!(
+            msg,
+            r#"wait visible "Save" did not complete within 200ms (no scan completed)"#
+        ),
+        other => panic!("Expected a timeout, got {:?}", other),
+    }
+}
+
+/// A page whose "Save" button never goes away: scanner waits are recorded
+/// and then never answered.
+#[derive(Default)]
+struct NeverHiddenBackend {
+    wait_timeouts: Vec<Option<u64>>,
+}
+
+#[async_trait]
+impl Backend for NeverHiddenBackend {
+    async fn launch(&mut self) -> Result<(), BackendError> {
+        Ok(())
+    }
+    async fn close(&mut self) -> Result<(), BackendError> {
+        Ok(())
+    }
+    async fn is_ready(&self) -> bool {
+        true
+    }
+    async fn navigate(&mut self, url: &str) -> Result<NavigationResult, BackendError> {
+        Ok(NavigationResult {
+            url: url.to_string(),
+            ..Default::default()
+        })
+    }
+    async fn screenshot(&mut self) -> Result<Vec<u8>, BackendError> {
+        Ok(vec![])
+    }
+    async fn execute_scanner(
+        &mut self,
+        command: ScannerAction,
+    ) -> Result<ScannerProtocolResponse, BackendError> {
+        match command {
+            ScannerAction::Scan(_) => Ok(single_element_scan("button", "button", "Save", "Save")),
+            ScannerAction::Wait(req) => {
+                self.wait_timeouts.push(req.timeout);
+                std::future::pending().await
+            }
+            other => Err(BackendError::NotSupported(format!("{:?}", other))),
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_wait_hidden_fails_within_its_timeout() {
+    let mut backend = NeverHiddenBackend::default();
+    let mut executor = CommandExecutor::new();
+
+    let started = Instant::now();
+    let err = executor
+        .execute_line(&mut backend, r#"wait hidden "Save" --timeout 200ms"#)
+        .await
+        .err()
+        .unwrap();
+
+    assert!(started.elapsed() < Duration::from_secs(2));
+    assert_eq!(backend.wait_timeouts, vec![Some(200)]);
+    match err {
+        ExecutorError::Timeout(msg) => {
+            assert_eq!(msg, r#"wait hidden "Save" did not complete within 200ms"#);
+        }
+        other => panic!("Expected a timeout, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_wait_hidden_without_timeout_uses_the_default() {
+    let mut backend = NeverHiddenBackend::default();
+    let mut executor = CommandExecutor::new();
+
+    let pending = executor.execute_line(&mut backend, r#"wait hidden "Save""#);
+    assert!(
+        tokio::time::timeout(Duration::from_millis(100), pending)
+            .await
+            .is_err()
+    );
+    assert_eq!(backend.wait_timeouts, vec![Some(30_000)]);
+}
+
+#[tokio::test]
+async fn test_intent_wait_hidden_step_fails_within_its_timeout() {
+    let definition =
+        parse_define("define save:\n  steps:\n    - wait hidden \"Save\" --timeout 200ms").unwrap();
+    let mut backend = NeverHiddenBackend::default();
+    let mut executor = CommandExecutor::new();
+    executor.registry_mut().register(definition);
+
+    let started = Instant::now();
+    let err = executor
+        .execute_line(&mut backend, "run save")
+        .await
+        .err()
+        .unwrap();
+
+    assert!(started.elapsed() < Duration::from_secs(2));
+    assert_eq!(backend.wait_timeouts, vec![Some(200)]);
+    match err {
+        ExecutorError::Intent(IntentError::StepTimedOut(msg)) => {
+            assert!(msg.contains("wait hidden"), "{}", msg);
+            assert!(msg.contains("within 200ms"), "{}", msg);
+        }
+        other => panic!("Expected a failed step, got {:?}", other),
+    }
+}
+
+/// A toast that is rendered once, still collapsed to zero height, and removed
+/// before it ever becomes visible.
+#[derive(Default)]
+struct FlickerBackend {
+    scans: usize,
+}
+
+#[async_trait]
+impl Backend for FlickerBackend {
+    async fn launch(&mut self) -> Result<(), BackendError> {
+        Ok(())
+    }
+    async fn close(&mut self) -> Result<(), BackendError> {
+        Ok(())
+    }
+    async fn is_ready(&self) -> bool {
+        true
+    }
+    async fn navigate(&mut self, url: &str) -> Result<NavigationResult, BackendError> {
+        Ok(NavigationResult {
+            url: url.to_string(),
+            ..Default::default()
+        })
+    }
+    async fn screenshot(&mut self) -> Result<Vec<u8>, BackendError> {
+        Ok(vec![])
+    }
+    async fn execute_scanner(
+        &mut self,
+        command: ScannerAction,
+    ) -> Result<ScannerProtocolResponse, BackendError> {
+        if !matches!(command, ScannerAction::Scan(_)) {
+            return Err(BackendError::NotSupported(format!("{:?}", command)));
+        }
+        let mut resp = single_element_scan("div", "status", "Saved", "Toast");
+        if let ScannerProtocolResponse::Ok { data, .. } = &mut resp
+            && let ScannerData::Scan(scan) = data.as_mut()
+        {
+            if self.scans == 0 {
+                scan.elements[0].rect.height = 0.0;
+            } else {
+                scan.elements.clear();
+            }
+        }
+        self.scans += 1;
+        Ok(resp)
+    }
+}
+
+#[tokio::test]
+async fn test_wait_visible_reports_an_element_seen_then_lost() {
+    let mut backend = FlickerBackend::default();
+    let mut executor = CommandExecutor::new();
+
+    let err = executor
+        .execute_line(&mut backend, r#"wait visible "Saved" --timeout 300ms"#)
+        .await
+        .err()
+        .unwrap();
+
+    match err {
+        ExecutorError::Timeout(msg) => {
+            assert!(
+                msg.starts_with(r#"wait visible "Saved" did not complete within 300ms"#),
+                "{}",
+                msg
+            );
+            let expected = format!("(seen in 1 of {} scans, then lost)", backend.scans);
+            assert!(msg.ends_with(&expected), "{}", msg);
+        }
+        other => panic!("Expected a timeout, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_wait_visible_reports_an_element_that_never_appeared() {
+    let mut backend = StatusBackend::new(vec!["Pending"]);
+    let mut executor = CommandExecutor::new();
+
+    let err = executor
+        .execute_line(&mut backend, r#"wait visible "Export" --timeout 300ms"#)
+        .await
+        .err()
+        .unwrap();
+
+    match err {
+        ExecutorError::Timeout(msg) => assert!(msg.contains("never appeared"), "{}", msg),
+        other => panic!("Expected a timeout, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_wait_any_of_reports_which_target_appeared() {
+    let mut backend = StatusBackend::new(vec!["Signing in", "Dashboard"]);
+    let mut executor = CommandExecutor::new();
+
+    let result = executor
+        .execute_line(
+            &mut backend,
+            r#"wait any-of ["Enter code", "Dashboard"] --timeout 5s"#,
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(backend.scans, 2);
+    assert_eq!(result.output, r#""Dashboard" is visible (any-of index 1)"#);
+}
+
+#[tokio::test]
+async fn test_wait_any_of_times_out_when_none_appear() {
+    let mut backend = StatusBackend::new(vec!["Signing in"]);
+    let mut executor = CommandExecutor::new();
+
+    let err = executor
+        .execute_line(
+            &mut backend,
+            r#"wait any-of ["Enter code", "Dashboard"] --timeout 300ms"#,
+        )
+        .await
+        .err()
+        .unwrap();
+
+    match err {
+        ExecutorError::Timeout(msg) => assert!(
+            msg.starts_with(
+                r#"wait any-of ["Enter code", "Dashboard"] did not complete within 300ms"#
+            ),
+            "{}",
+            msg
+        ),
+        other => panic!("Expected a timeout, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_wait_any_of_is_bounded_when_a_scan_hangs() {
+    let mut backend = HungScanBackend;
+    let mut executor = CommandExecutor::new();
+
+    let started = Instant::now();
+    let err = executor
+        .execute_line(
+            &mut backend,
+            r#"wait any-of ["Enter code", "Dashboard"] --timeout 200ms"#,
+        )
+        .await
+        .err()
+        .unwrap();
+
+    assert!(started.elapsed() < Duration::from_secs(2));
+    match err {
+        ExecutorError::Timeout(msg) => assert!(
+            msg.contains("within 200ms (none visible in 0 scans)"),
+            "{}",
+            msg
+        ),
+        other => panic!("Expected a timeout, got {:?}", other),
+    }
+}
+
+/// A results page where the "Loading" spinner is gone from the third scan
+/// and the scanner counts a result from the fourth.
+#[derive(Default)]
+struct ResultsBackend {
+    scans: usize,
+    item_checks: Vec<(Option<String>, Option<u64>)>,
+}
+
+#[async_trait]
+impl Backend for ResultsBackend {
+    async fn launch(&mut self) -> Result<(), BackendError> {
+        Ok(())
+    }
+    async fn close(&mut self) -> Result<(), BackendError> {
+        Ok(())
+    }
+    async fn is_ready(&self) -> bool {
+        true
+    }
+    async fn navigate(&mut self, url: &str) -> Result<NavigationResult, BackendError> {
+        Ok(NavigationResult {
+            url: url.to_string(),
+            ..Default::default()
+        })
+    }
+    async fn screenshot(&mut self) -> Result<Vec<u8>, BackendError> {
+        Ok(vec![])
+    }
+    async fn execute_scanner(
+        &mut self,
+        command: ScannerAction,
+    ) -> Result<ScannerProtocolResponse, BackendError> {
+        match command {
+            ScannerAction::Scan(_) => {
+                self.scans += 1;
+                let mut resp = single_element_scan("div", "status", "Loading", "Spinner");
+                if self.scans >= 3
+                    && let ScannerProtocolResponse::Ok { data, .. } = &mut resp
+                    && let ScannerData::Scan(scan) = data.as_mut()
+                {
+                    scan.elements.clear();
+                }
+                Ok(resp)
+            }
+            ScannerAction::Wait(req) if req.condition == "count" => {
+                self.item_checks.push((req.selector, req.timeout));
+                if self.scans >= 4 {
+                    Ok(ScannerProtocolResponse::Ok {
+                        data: Box::new(ScannerData::Action(ActionResult {
+                            success: true,
+                            message: None,
+                            navigation: None,
+                            dom_changes: None,
+                            value: None,
+                            coordinates: None,
+                        })),
+                        warnings: vec![],
+                    })
+                } else {
+                    Ok(ScannerProtocolResponse::Error {
+                        code: "TIMEOUT".into(),
+                        message: "Timeout waiting for condition".into(),
+                        details: None,
+                        hint: None,
+                    })
+                }
+            }
+            other => Err(BackendError::NotSupported(format!("{:?}", other))),
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_wait_all_completes_once_both_conditions_hold() {
+    let mut backend = ResultsBackend::default();
+    let mut executor = CommandExecutor::new();
+
+    let result = executor
+        .execute_line(
+            &mut backend,
+            r#"wait all (hidden "Loading", items ".result" 1) --timeout 5s"#,
+        )
+        .await
+        .unwrap();
+
+    // The spinner is hidden on the third scan, but the wait goes on until
+    // the results show on the fourth.
+    assert_eq!(backend.scans, 4);
+    assert_eq!(
+        result.output,
+        r#"all (hidden "Loading", items ".result" 1) hold (4 scans)"#
+    );
+    // Items are asked of the scanner as a one-shot check on every scan.
+    assert_eq!(
+        backend.item_checks,
+        vec![(Some(".result".to_string()), Some(0)); 4]
+    );
+}
+
+/// A list of `rows` `.row` elements whose scanner answers `count` waits the
+/// way the page's scanner would, recording each request.
+struct RowsBackend {
+    rows: u64,
+    waits: Vec<WaitRequest>,
+}
+
+#[async_trait]
+impl Backend for RowsBackend {
+    async fn launch(&mut self) -> Result<(), BackendError> {
+        Ok(())
+    }
+    async fn close(&mut self) -> Result<(), BackendError> {
+        Ok(())
+    }
+    async fn is_ready(&self) -> bool {
+        true
+    }
+    async fn navigate(&mut self, url: &str) -> Result<NavigationResult, BackendError> {
+        Ok(NavigationResult {
+            url: url.to_string(),
+            ..Default::default()
+        })
+    }
+    async fn screenshot(&mut self) -> Result<Vec<u8>, BackendError> {
+        Ok(vec![])
+    }
+    async fn execute_scanner(
+        &mut self,
+        command: ScannerAction,
+    ) -> Result<ScannerProtocolResponse, BackendError> {
+        match command {
+            ScannerAction::Wait(req) if req.condition == "count" => {
+                let count = req.count.unwrap();
+                let holds = match req.compare.as_deref().unwrap_or(">=") {
+                    ">=" => self.rows >= count,
+                    ">" => self.rows > count,
+                    "<=" => self.rows <= count,
+                    "<" => self.rows < count,
+                    "==" => self.rows == count,
+                    other => panic!("unknown comparison {}", other),
+                };
+                self.waits.push(req);
+                if holds {
+                    Ok(ScannerProtocolResponse::Ok {
+                        data: Box::new(ScannerData::Action(ActionResult {
+                            success: true,
+                            message: Some("Wait condition met".into()),
+                            navigation: None,
+                            dom_changes: None,
+                            value: None,
+                            coordinates: None,
+                        })),
+                        warnings: vec![],
+                    })
+                } else {
+                    Ok(ScannerProtocolResponse::Error {
+                        code: "TIMEOUT".into(),
+                        message: "Timeout waiting for condition".into(),
+                        details: None,
+                        hint: None,
+                    })
+                }
+            }
+            other => Err(BackendError::NotSupported(format!("{:?}", other))),
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_wait_items_sends_every_comparison_to_the_scanner() {
+    let mut backend = RowsBackend {
+        rows: 6,
+        waits: vec![],
+    };
+    let mut executor = CommandExecutor::new();
+
+    for line in [
+        r#"wait items ".row" 6"#,
+        r#"wait items ".row" > 5"#,
+        r#"wait items ".row" <= 6"#,
+        r#"wait items ".row" < 7"#,
+        r#"wait items ".row" == 6"#,
+    ] {
+        executor.execute_line(&mut backend, line).await.unwrap();
+    }
+
+    // One count of the page, the scanner's, whatever the operator.
+    let sent: Vec<_> = backend
+        .waits
+        .iter()
+        .map(|req| (req.selector.as_deref(), req.count, req.compare.as_deref()))
+        .collect();
+    assert_eq!(
+        sent,
+        vec![
+            (Some(".row"), Some(6), None),
+            (Some(".row"), Some(5), Some(">")),
+            (Some(".row"), Some(6), Some("<=")),
+            (Some(".row"), Some(7), Some("<")),
+            (Some(".row"), Some(6), Some("==")),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_wait_items_reports_the_scanner_timeout() {
+    let mut backend = RowsBackend {
+        rows: 3,
+        waits: vec![],
+    };
+    let mut executor = CommandExecutor::new();
+
+    let err = executor
+        .execute_line(&mut backend, r#"wait items ".row" == 10 --timeout 300ms"#)
+        .await
+        .err()
+        .unwrap();
+
+    assert!(err.to_string().contains("TIMEOUT"), "{}", err);
+    assert_eq!(backend.waits.len(), 1);
+    assert_eq!(backend.waits[0].timeout, Some(300));
+}
+
+#[tokio::test]
+async fn test_wait_all_timeout_names_the_pending_conditions() {
+    let mut backend = StatusBackend::new(vec!["Pending"]);
+    let mut executor = CommandExecutor::new();
+
+    let err = executor
+        .execute_line(
+            &mut backend,
+            r#"wait all (visible "Status", hidden "Status", url "/jobs/") --timeout 300ms"#,
+        )
+        .await
+        .err()
+        .unwrap();
+
+    match err {
+        ExecutorError::Timeout(msg) => assert!(
+            msg.contains(r#"within 300ms (still waiting on hidden "Status" after"#),
+            "{}",
+            msg
+        ),
+        other => panic!("Expected a timeout, got {:?}", other),
+    }
+}
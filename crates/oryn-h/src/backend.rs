@@ -1,33 +1,78 @@
 use crate::cdp::CdpClient;
-use crate::inject::execute_command;
+use crate::features::{fetch_enable_params, pdf_params};
+use crate::inject::{execute_batch, execute_command};
 use async_trait::async_trait;
+use chromiumoxide::cdp::browser_protocol::dom::DescribeNodeParams;
+use chromiumoxide::cdp::browser_protocol::network::{self, GetAllCookiesParams};
+use chromiumoxide::cdp::browser_protocol::page::{FrameId, NavigateParams, Viewport};
+use chromiumoxide::cdp::js_protocol::runtime::{EvaluateParams, ExecutionContextId};
 use oryn_engine::backend::{Backend, BackendError, NavigationResult};
-use oryn_engine::protocol::{ScannerAction, ScannerProtocolResponse};
+use oryn_engine::protocol::{
+    InterceptRule, NetworkRequest, PdfOptions, Rect, ScannerAction, ScannerProtocolResponse,
+};
+use oryn_engine::registry::BackendRegistry;
 use tracing::info;
 
 pub struct HeadlessBackend {
     client: Option<CdpClient>,
     visible: bool,
+    /// The frame scanner requests go to, or `None` for the top document.
+    frame: Option<FrameId>,
 }
 
 impl HeadlessBackend {
     pub fn new() -> Self {
-        Self {
-            client: None,
-            visible: false,
-        }
+        Self::new_with_visibility(false)
     }
 
     pub fn new_with_visibility(visible: bool) -> Self {
         Self {
             client: None,
             visible,
+            frame: None,
         }
     }
 
     pub fn get_client(&self) -> Option<&CdpClient> {
         self.client.as_ref()
     }
+
+    /// Turn the Fetch domain on for the current rules, or off when none
+    /// are left.
+    async fn sync_intercepts(&self) -> Result<(), BackendError> {
+        let client = self.client.as_ref().ok_or(BackendError::NotReady)?;
+        let params = fetch_enable_params(&client.intercepts.lock().unwrap());
+        let result = match params {
+            Some(params) => client.page.execute(params).await.map(|_| ()),
+            None => client
+                .page
+                .execute(chromiumoxide::cdp::browser_protocol::fetch::DisableParams::default())
+                .await
+                .map(|_| ()),
+        };
+        result.map_err(|e| BackendError::Other(format!("intercept failed: {}", e)))
+    }
+
+    /// The execution context of the frame switched to with `switch_frame`,
+    /// or `None` for the top document.
+    async fn scanner_context(&self) -> Result<Option<ExecutionContextId>, BackendError> {
+        let client = self.client.as_ref().ok_or(BackendError::NotReady)?;
+        let Some(frame) = &self.frame else {
+            return Ok(None);
+        };
+        client
+            .page
+            .frame_execution_context(frame.clone())
+            .await
+            .map_err(|e| BackendError::Scanner(e.to_string()))?
+            .map(Some)
+            .ok_or_else(|| {
+                BackendError::Scanner(
+                    "The frame has no script context; it may be cross-origin or still loading"
+                        .into(),
+                )
+            })
+    }
 }
 
 impl Default for HeadlessBackend {
@@ -36,6 +81,15 @@ impl Default for HeadlessBackend {
     }
 }
 
+/// Register the headless backend as `headless`. Options: `visible`.
+pub fn register(registry: &mut BackendRegistry) {
+    registry.register("headless", |options| {
+        Ok(Box::new(HeadlessBackend::new_with_visibility(
+            options.flag("visible"),
+        )))
+    });
+}
+
 impl HeadlessBackend {
     async fn get_navigation_result(
         page: &chromiumoxide::Page,
@@ -85,6 +139,7 @@ impl Backend for HeadlessBackend {
 
     async fn navigate(&mut self, url: &str) -> Result<NavigationResult, BackendError> {
         let client = self.client.as_mut().ok_or(BackendError::NotReady)?;
+        self.frame = None;
 
         info!("Navigating to: {}", url);
         client
@@ -96,10 +151,34 @@ impl Backend for HeadlessBackend {
         Self::get_navigation_result(&client.page).await
     }
 
+    async fn navigate_with_referer(
+        &mut self,
+        url: &str,
+        referer: &str,
+    ) -> Result<NavigationResult, BackendError> {
+        let client = self.client.as_mut().ok_or(BackendError::NotReady)?;
+        self.frame = None;
+
+        info!("Navigating to: {} (referer {})", url, referer);
+        let params = NavigateParams::builder()
+            .url(url)
+            .referrer(referer)
+            .build()
+            .map_err(BackendError::Navigation)?;
+        client
+            .page
+            .goto(params)
+            .await
+            .map_err(|e| BackendError::Navigation(e.to_string()))?;
+
+        Self::get_navigation_result(&client.page).await
+    }
+
     async fn execute_scanner(
         &mut self,
         command: ScannerAction,
     ) -> Result<ScannerProtocolResponse, BackendError> {
+        let context = self.scanner_context().await?;
         let client = self.client.as_mut().ok_or(BackendError::NotReady)?;
 
         let value = serde_json::to_value(&command)?;
@@ -111,7 +190,7 @@ impl Backend for HeadlessBackend {
             .map(|s| s.to_string())
             .ok_or_else(|| BackendError::Scanner("Missing action field".into()))?;
 
-        let result_value = execute_command(&client.page, &action, value)
+        let result_value = execute_command(&client.page, context, &action, value)
             .await
             .map_err(|e| BackendError::Scanner(e.to_string()))?;
 
@@ -121,6 +200,34 @@ impl Backend for HeadlessBackend {
         Ok(response)
     }
 
+    async fn execute_batch(
+        &mut self,
+        commands: Vec<ScannerAction>,
+    ) -> Result<Vec<ScannerProtocolResponse>, BackendError> {
+        let context = self.scanner_context().await?;
+        let client = self.client.as_mut().ok_or(BackendError::NotReady)?;
+
+        let values = commands
+            .iter()
+            .map(serde_json::to_value)
+            .collect::<Result<Vec<_>, _>>()?;
+        let count = values.len();
+
+        let result_value = execute_batch(&client.page, context, values)
+            .await
+            .map_err(|e| BackendError::Scanner(e.to_string()))?;
+
+        let responses: Vec<ScannerProtocolResponse> = serde_json::from_value(result_value)?;
+        if responses.len() != count {
+            return Err(BackendError::Scanner(format!(
+                "Batch of {} commands returned {} responses",
+                count,
+                responses.len()
+            )));
+        }
+        Ok(responses)
+    }
+
     async fn screenshot(&mut self) -> Result<Vec<u8>, BackendError> {
         let client = self.client.as_ref().ok_or(BackendError::NotReady)?;
         let bytes = client
@@ -132,11 +239,35 @@ impl Backend for HeadlessBackend {
         Ok(bytes)
     }
 
-    async fn pdf(&mut self) -> Result<Vec<u8>, BackendError> {
+    async fn screenshot_clip(&mut self, clip: &Rect) -> Result<Vec<u8>, BackendError> {
         let client = self.client.as_ref().ok_or(BackendError::NotReady)?;
+        let viewport = Viewport {
+            x: clip.x as f64,
+            y: clip.y as f64,
+            width: clip.width as f64,
+            height: clip.height as f64,
+            scale: 1.0,
+        };
         let bytes = client
             .page
-            .pdf(chromiumoxide::cdp::browser_protocol::page::PrintToPdfParams::builder().build())
+            .screenshot(
+                chromiumoxide::page::ScreenshotParams::builder()
+                    .clip(viewport)
+                    .capture_beyond_viewport(true)
+                    .build(),
+            )
+            .await
+            .map_err(|e| BackendError::Other(format!("Screenshot failed: {}", e)))?;
+
+        Ok(bytes)
+    }
+
+    async fn pdf(&mut self, options: &PdfOptions) -> Result<Vec<u8>, BackendError> {
+        let client = self.client.as_ref().ok_or(BackendError::NotReady)?;
+        let params = pdf_params(options).map_err(BackendError::InvalidRequest)?;
+        let bytes = client
+            .page
+            .pdf(params)
             .await
             .map_err(|e| BackendError::Other(format!("PDF generation failed: {}", e)))?;
 
@@ -151,18 +282,47 @@ impl Backend for HeadlessBackend {
             .await
             .map_err(|e| BackendError::Other(format!("Get cookies failed: {}", e)))?;
 
-        Ok(cookies
-            .into_iter()
-            .map(|c| oryn_engine::protocol::Cookie {
-                name: c.name,
-                value: c.value,
-                domain: Some(c.domain),
-                path: Some(c.path),
-                expires: Some(c.expires),
-                http_only: Some(c.http_only),
-                secure: Some(c.secure),
-            })
-            .collect())
+        Ok(cookies.into_iter().map(from_cdp_cookie).collect())
+    }
+
+    async fn get_all_cookies(
+        &mut self,
+    ) -> Result<Vec<oryn_engine::protocol::Cookie>, BackendError> {
+        let client = self.client.as_ref().ok_or(BackendError::NotReady)?;
+        let cookies = client
+            .page
+            .execute(GetAllCookiesParams::default())
+            .await
+            .map_err(|e| BackendError::Other(format!("Get all cookies failed: {}", e)))?
+            .result
+            .cookies;
+
+        Ok(cookies.into_iter().map(from_cdp_cookie).collect())
+    }
+
+    async fn set_cookie(
+        &mut self,
+        cookie: oryn_engine::protocol::Cookie,
+    ) -> Result<(), BackendError> {
+        let client = self.client.as_ref().ok_or(BackendError::NotReady)?;
+        let mut params = network::SetCookieParams::new(cookie.name, cookie.value);
+        // Without a domain the cookie belongs to the current page.
+        if cookie.domain.is_none() {
+            params.url = client.page.url().await.ok().flatten();
+        }
+        params.domain = cookie.domain;
+        params.path = cookie.path;
+        params.secure = cookie.secure;
+        params.http_only = cookie.http_only;
+        params.same_site = cookie.same_site.and_then(|s| s.parse().ok());
+        params.expires = cookie.expires.map(network::TimeSinceEpoch::new);
+        client
+            .page
+            .execute(params)
+            .await
+            .map_err(|e| BackendError::Other(format!("Set cookie failed: {}", e)))?;
+
+        Ok(())
     }
 
     async fn get_tabs(&mut self) -> Result<Vec<oryn_engine::protocol::TabInfo>, BackendError> {
@@ -191,7 +351,48 @@ impl Backend for HeadlessBackend {
         Ok(tabs)
     }
 
+    async fn switch_frame(&mut self, index: Option<usize>) -> Result<(), BackendError> {
+        let Some(index) = index else {
+            self.frame = None;
+            return Ok(());
+        };
+        // Frames are listed from the document scanner requests go to, so the
+        // index is looked up there too.
+        let context = self.scanner_context().await?;
+        let client = self.client.as_ref().ok_or(BackendError::NotReady)?;
+        let mut params = EvaluateParams::new(format!(
+            "document.querySelectorAll('iframe, frame')[{}]",
+            index
+        ));
+        params.context_id = context;
+        params.return_by_value = Some(false);
+        let element = client
+            .page
+            .evaluate_expression(params)
+            .await
+            .map_err(|e| BackendError::Scanner(format!("switch_frame failed: {}", e)))?;
+        let Some(object_id) = element.object().object_id.clone() else {
+            return Err(BackendError::Scanner(format!(
+                "No frame at index {}",
+                index
+            )));
+        };
+        let node = client
+            .page
+            .execute(DescribeNodeParams::builder().object_id(object_id).build())
+            .await
+            .map_err(|e| BackendError::Scanner(format!("switch_frame failed: {}", e)))?
+            .result
+            .node;
+        let frame = node.frame_id.ok_or_else(|| {
+            BackendError::Scanner(format!("The frame at index {} has not loaded", index))
+        })?;
+        self.frame = Some(frame);
+        Ok(())
+    }
+
     async fn go_back(&mut self) -> Result<NavigationResult, BackendError> {
+        self.frame = None;
         let client = self.client.as_mut().ok_or(BackendError::NotReady)?;
         client
             .page
@@ -203,6 +404,7 @@ impl Backend for HeadlessBackend {
     }
 
     async fn go_forward(&mut self) -> Result<NavigationResult, BackendError> {
+        self.frame = None;
         let client = self.client.as_mut().ok_or(BackendError::NotReady)?;
         client
             .page
@@ -214,6 +416,7 @@ impl Backend for HeadlessBackend {
     }
 
     async fn refresh(&mut self) -> Result<NavigationResult, BackendError> {
+        self.frame = None;
         let client = self.client.as_mut().ok_or(BackendError::NotReady)?;
         client
             .page
@@ -243,34 +446,115 @@ impl Backend for HeadlessBackend {
             DispatchKeyEventParams, DispatchKeyEventType,
         };
 
-        // Key down
-        let key_down = DispatchKeyEventParams::builder()
-            .r#type(DispatchKeyEventType::KeyDown)
-            .key(key)
-            .modifiers(modifier_flags)
-            .build()
-            .map_err(|e| BackendError::Other(format!("Failed to build key event: {:?}", e)))?;
+        let key_event = |kind: DispatchKeyEventType| {
+            let mut builder = DispatchKeyEventParams::builder()
+                .r#type(kind)
+                .key(key)
+                .modifiers(modifier_flags);
+            // Chrome only runs a key's default action, such as moving focus
+            // on Tab, when the event carries its virtual key code.
+            if let Some(code) = virtual_key_code(key) {
+                builder = builder.code(key).windows_virtual_key_code(code);
+            }
+            builder
+                .build()
+                .map_err(|e| BackendError::Other(format!("Failed to build key event: {:?}", e)))
+        };
 
         client
             .page
-            .execute(key_down)
+            .execute(key_event(DispatchKeyEventType::KeyDown)?)
             .await
             .map_err(|e| BackendError::Other(format!("press_key down failed: {}", e)))?;
 
-        // Key up
-        let key_up = DispatchKeyEventParams::builder()
-            .r#type(DispatchKeyEventType::KeyUp)
-            .key(key)
-            .modifiers(modifier_flags)
-            .build()
-            .map_err(|e| BackendError::Other(format!("Failed to build key event: {:?}", e)))?;
-
         client
             .page
-            .execute(key_up)
+            .execute(key_event(DispatchKeyEventType::KeyUp)?)
             .await
             .map_err(|e| BackendError::Other(format!("press_key up failed: {}", e)))?;
 
         Ok(())
     }
+
+    async fn pending_requests(&mut self) -> Result<usize, BackendError> {
+        let client = self.client.as_ref().ok_or(BackendError::NotReady)?;
+        let inflight = client
+            .inflight
+            .as_ref()
+            .ok_or_else(|| BackendError::NotSupported("pending_requests".into()))?;
+        Ok(inflight.lock().unwrap().len())
+    }
+
+    async fn clear_requests(&mut self) -> Result<(), BackendError> {
+        let client = self.client.as_ref().ok_or(BackendError::NotReady)?;
+        let log = client
+            .requests
+            .as_ref()
+            .ok_or_else(|| BackendError::NotSupported("clear_requests".into()))?;
+        log.lock().unwrap().clear();
+        Ok(())
+    }
+
+    async fn get_requests(&mut self) -> Result<Vec<NetworkRequest>, BackendError> {
+        let client = self.client.as_ref().ok_or(BackendError::NotReady)?;
+        let log = client
+            .requests
+            .as_ref()
+            .ok_or_else(|| BackendError::NotSupported("get_requests".into()))?;
+        Ok(log.lock().unwrap().entries())
+    }
+
+    async fn add_intercept(&mut self, rule: InterceptRule) -> Result<(), BackendError> {
+        let client = self.client.as_ref().ok_or(BackendError::NotReady)?;
+        {
+            let mut rules = client.intercepts.lock().unwrap();
+            rules.retain(|r| r.url_pattern != rule.url_pattern);
+            rules.push(rule);
+        }
+        self.sync_intercepts().await
+    }
+
+    async fn clear_intercepts(&mut self, pattern: Option<&str>) -> Result<(), BackendError> {
+        let client = self.client.as_ref().ok_or(BackendError::NotReady)?;
+        client
+            .intercepts
+            .lock()
+            .unwrap()
+            .retain(|r| pattern.is_some_and(|p| r.url_pattern != p));
+        self.sync_intercepts().await
+    }
+}
+
+/// Windows virtual key code for the named non-printing keys.
+fn virtual_key_code(key: &str) -> Option<i64> {
+    Some(match key {
+        "Backspace" => 8,
+        "Tab" => 9,
+        "Enter" => 13,
+        "Escape" => 27,
+        "PageUp" => 33,
+        "PageDown" => 34,
+        "End" => 35,
+        "Home" => 36,
+        "ArrowLeft" => 37,
+        "ArrowUp" => 38,
+        "ArrowRight" => 39,
+        "ArrowDown" => 40,
+        "Delete" => 46,
+        _ => return None,
+    })
+}
+
+fn from_cdp_cookie(c: network::Cookie) -> oryn_engine::protocol::Cookie {
+    oryn_engine::protocol::Cookie {
+        name: c.name,
+        value: c.value,
+        domain: Some(c.domain),
+        path: Some(c.path),
+        // CDP reports session cookies with an expiry of -1.
+        expires: (!c.session).then_some(c.expires),
+        http_only: Some(c.http_only),
+        secure: Some(c.secure),
+        same_site: c.same_site.map(|s| s.as_ref().to_string()),
+    }
 }
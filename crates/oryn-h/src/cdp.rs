@@ -8,6 +8,12 @@ pub struct CdpClient {
     pub browser: Browser,
     pub handler_task: JoinHandle<()>,
     pub page: Page,
+    /// Network requests in flight on `page`, for `wait idle`.
+    pub inflight: Option<crate::features::InflightRequests>,
+    /// Every request `page` has made, for `requests`.
+    pub requests: Option<crate::features::SharedRequestLog>,
+    /// Rules paused requests are answered from, for `intercept`.
+    pub intercepts: crate::features::InterceptRules,
     user_data_dir: Option<PathBuf>,
     cleanup_user_data_dir: bool,
 }
@@ -123,10 +129,38 @@ impl CdpClient {
             tracing::info!("Network logging disabled (set ORYN_ENABLE_NETWORK_LOG=1 to enable)");
         }
 
+        let inflight = match crate::features::track_inflight_requests(&page).await {
+            Ok(inflight) => Some(inflight),
+            Err(e) => {
+                tracing::warn!("Failed to track network requests: {}", e);
+                None
+            }
+        };
+
+        let requests = match crate::features::record_requests(&page).await {
+            Ok(log) => Some(log),
+            Err(e) => {
+                tracing::warn!("Failed to record network requests: {}", e);
+                None
+            }
+        };
+
+        // Requests only pause once a rule turns the Fetch domain on.
+        let intercepts = crate::features::InterceptRules::default();
+        if let Err(e) =
+            crate::features::handle_paused_requests(&page, intercepts.clone(), requests.clone())
+                .await
+        {
+            tracing::warn!("Failed to handle intercepted requests: {}", e);
+        }
+
         Ok(Self {
             browser,
             handler_task,
             page,
+            inflight,
+            requests,
+            intercepts,
             user_data_dir: Some(user_data_dir),
             cleanup_user_data_dir,
         })
@@ -141,12 +175,11 @@ impl CdpClient {
             .await
             .map_err(|e| format!("Error awaiting handler: {}", e))?;
 
-        if self.cleanup_user_data_dir {
-            if let Some(dir) = &self.user_data_dir {
-                if let Err(e) = std::fs::remove_dir_all(dir) {
-                    tracing::debug!("Failed to clean up user-data-dir {}: {}", dir.display(), e);
-                }
-            }
+        if self.cleanup_user_data_dir
+            && let Some(dir) = &self.user_data_dir
+            && let Err(e) = std::fs::remove_dir_all(dir)
+        {
+            tracing::debug!("Failed to clean up user-data-dir {}: {}", dir.display(), e);
         }
 
         Ok(())
@@ -1,14 +1,79 @@
+use base64::Engine;
 use chromiumoxide::Page;
+use chromiumoxide::cdp::browser_protocol::fetch::{
+    self, ContinueRequestParams, EnableParams, EventRequestPaused, FailRequestParams,
+    FulfillRequestParams, HeaderEntry, RequestPattern,
+};
+use chromiumoxide::cdp::browser_protocol::network::ErrorReason;
+use chromiumoxide::cdp::browser_protocol::page::PrintToPdfParams;
 use futures::StreamExt;
+use oryn_engine::protocol::{
+    InterceptAction, InterceptRule, Interception, NetworkRequest, PdfOptions, guess_content_type,
+};
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::error::Error;
 use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+/// Paper sizes in inches, as Chrome's print parameters expect.
+fn paper_size(format: &str) -> Option<(f64, f64)> {
+    match format.to_ascii_lowercase().as_str() {
+        "a4" => Some((8.27, 11.69)),
+        "letter" => Some((8.5, 11.0)),
+        "legal" => Some((8.5, 14.0)),
+        "tabloid" => Some((11.0, 17.0)),
+        _ => None,
+    }
+}
+
+/// The print scales Chrome accepts.
+const PDF_SCALE_RANGE: std::ops::RangeInclusive<f32> = 0.1..=2.0;
+
+/// Chrome print parameters for `options`; anything unset keeps Chrome's default.
+/// Fails on a paper format it does not know or a scale Chrome would refuse.
+pub fn pdf_params(options: &PdfOptions) -> Result<PrintToPdfParams, String> {
+    let mut builder = PrintToPdfParams::builder();
+    if options.landscape {
+        builder = builder.landscape(true);
+    }
+    if let Some(format) = &options.format {
+        let (width, height) = paper_size(format).ok_or_else(|| {
+            format!(
+                "unknown paper format '{}'; use A4, Letter, Legal or Tabloid",
+                format
+            )
+        })?;
+        builder = builder.paper_width(width).paper_height(height);
+    }
+    if let Some(margin) = options.margin {
+        let inches = f64::from(margin) / 25.4;
+        builder = builder
+            .margin_top(inches)
+            .margin_bottom(inches)
+            .margin_left(inches)
+            .margin_right(inches);
+    }
+    if let Some(scale) = options.scale {
+        if !PDF_SCALE_RANGE.contains(&scale) {
+            return Err(format!(
+                "scale {} is out of range; use {} to {}",
+                scale,
+                PDF_SCALE_RANGE.start(),
+                PDF_SCALE_RANGE.end()
+            ));
+        }
+        builder = builder.scale(f64::from(scale));
+    }
+    Ok(builder.build())
+}
 
 pub async fn generate_pdf(
     page: &Page,
     output_path: &Path,
+    options: &PdfOptions,
 ) -> Result<(), Box<dyn Error + Send + Sync>> {
     let pdf_data = page
-        .pdf(chromiumoxide::cdp::browser_protocol::page::PrintToPdfParams::default())
+        .pdf(pdf_params(options)?)
         .await
         .map_err(|e| format!("PDF generation failed: {}", e))?;
 
@@ -41,3 +106,312 @@ pub async fn enable_network_logging(page: &Page) -> Result<(), Box<dyn Error + S
 
     Ok(())
 }
+
+/// Ids of requests the page has sent that have not finished or failed yet.
+pub type InflightRequests = Arc<Mutex<HashSet<String>>>;
+
+pub async fn track_inflight_requests(
+    page: &Page,
+) -> Result<InflightRequests, Box<dyn Error + Send + Sync>> {
+    use chromiumoxide::cdp::browser_protocol::network::{
+        EventLoadingFailed, EventLoadingFinished, EventRequestWillBeSent,
+    };
+
+    let inflight = InflightRequests::default();
+    let subscribe_error = |e| format!("Failed to subscribe to network events: {}", e);
+
+    let mut sent = page
+        .event_listener::<EventRequestWillBeSent>()
+        .await
+        .map_err(subscribe_error)?;
+    let mut finished = page
+        .event_listener::<EventLoadingFinished>()
+        .await
+        .map_err(subscribe_error)?;
+    let mut failed = page
+        .event_listener::<EventLoadingFailed>()
+        .await
+        .map_err(subscribe_error)?;
+
+    // One task reads all three streams, preferring sends, so a request's
+    // finish is never applied before the send that came ahead of it.
+    let tracked = inflight.clone();
+    tokio::spawn(async move {
+        loop {
+            tokio::select! {
+                biased;
+                Some(event) = sent.next() => {
+                    tracked
+                        .lock()
+                        .unwrap()
+                        .insert(event.request_id.inner().clone());
+                }
+                Some(event) = finished.next() => {
+                    tracked.lock().unwrap().remove(event.request_id.inner());
+                }
+                Some(event) = failed.next() => {
+                    tracked.lock().unwrap().remove(event.request_id.inner());
+                }
+                else => break,
+            }
+        }
+    });
+
+    Ok(inflight)
+}
+
+/// The most requests a [`RequestLog`] keeps; older ones are dropped.
+pub const MAX_LOGGED_REQUESTS: usize = 1000;
+
+/// Requests the page has made, oldest first, and what intercept rules did
+/// to them. Only the last [`MAX_LOGGED_REQUESTS`] are kept.
+#[derive(Debug, Default)]
+pub struct RequestLog {
+    /// Logged requests with their network request ids.
+    entries: VecDeque<(String, NetworkRequest)>,
+    /// How many requests have been dropped from the front of `entries`.
+    dropped: usize,
+    /// Position in the whole log, dropped requests included, by network
+    /// request id.
+    by_id: HashMap<String, usize>,
+    /// Interceptions reported before the request itself was logged.
+    early: HashMap<String, (Interception, Option<u16>)>,
+}
+
+impl RequestLog {
+    fn sent(&mut self, id: String, method: String, url: String) {
+        let (intercepted, status) = match self.early.remove(&id) {
+            Some((how, status)) => (Some(how), status),
+            None => (None, None),
+        };
+        if self.entries.len() == MAX_LOGGED_REQUESTS
+            && let Some((oldest, _)) = self.entries.pop_front()
+        {
+            if self.by_id.get(&oldest) == Some(&self.dropped) {
+                self.by_id.remove(&oldest);
+            }
+            self.dropped += 1;
+        }
+        self.by_id
+            .insert(id.clone(), self.dropped + self.entries.len());
+        self.entries.push_back((
+            id,
+            NetworkRequest {
+                method,
+                url,
+                status,
+                intercepted,
+            },
+        ));
+    }
+
+    fn entry_mut(&mut self, id: &str) -> Option<&mut NetworkRequest> {
+        let position = *self.by_id.get(id)?;
+        self.entries
+            .get_mut(position - self.dropped)
+            .map(|(_, entry)| entry)
+    }
+
+    fn responded(&mut self, id: &str, status: u16) {
+        if let Some(entry) = self.entry_mut(id) {
+            entry.status = Some(status);
+        }
+    }
+
+    fn intercepted(&mut self, id: String, how: Interception, status: Option<u16>) {
+        let early_is_full = self.early.len() >= MAX_LOGGED_REQUESTS;
+        match self.entry_mut(&id) {
+            Some(entry) => {
+                entry.intercepted = Some(how);
+                entry.status = status.or(entry.status);
+            }
+            // Bounded like the log, in case the requests never show up.
+            None if !early_is_full => {
+                self.early.insert(id, (how, status));
+            }
+            None => {}
+        }
+    }
+
+    pub fn entries(&self) -> Vec<NetworkRequest> {
+        self.entries
+            .iter()
+            .map(|(_, entry)| entry.clone())
+            .collect()
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.dropped = 0;
+        self.by_id.clear();
+        self.early.clear();
+    }
+}
+
+pub type SharedRequestLog = Arc<Mutex<RequestLog>>;
+
+pub async fn record_requests(
+    page: &Page,
+) -> Result<SharedRequestLog, Box<dyn Error + Send + Sync>> {
+    use chromiumoxide::cdp::browser_protocol::network::{
+        EventRequestWillBeSent, EventResponseReceived,
+    };
+
+    let log = SharedRequestLog::default();
+    let subscribe_error = |e| format!("Failed to subscribe to network events: {}", e);
+
+    let mut sent = page
+        .event_listener::<EventRequestWillBeSent>()
+        .await
+        .map_err(subscribe_error)?;
+    let mut received = page
+        .event_listener::<EventResponseReceived>()
+        .await
+        .map_err(subscribe_error)?;
+
+    let recorded = log.clone();
+    tokio::spawn(async move {
+        while let Some(event) = sent.next().await {
+            recorded.lock().unwrap().sent(
+                event.request_id.inner().clone(),
+                event.request.method.clone(),
+                event.request.url.clone(),
+            );
+        }
+    });
+    let recorded = log.clone();
+    tokio::spawn(async move {
+        while let Some(event) = received.next().await {
+            let status = u16::try_from(event.response.status).unwrap_or_default();
+            recorded
+                .lock()
+                .unwrap()
+                .responded(event.request_id.inner(), status);
+        }
+    });
+
+    Ok(log)
+}
+
+/// Intercept rules in the order they were added; the latest match wins.
+pub type InterceptRules = Arc<Mutex<Vec<InterceptRule>>>;
+
+/// `Fetch.enable` pausing only the requests some rule matches, or `None`
+/// when there are no rules and interception should be off.
+pub fn fetch_enable_params(rules: &[InterceptRule]) -> Option<EnableParams> {
+    if rules.is_empty() {
+        return None;
+    }
+    Some(
+        EnableParams::builder()
+            .patterns(rules.iter().map(|rule| {
+                RequestPattern::builder()
+                    .url_pattern(rule.fetch_pattern())
+                    .build()
+            }))
+            .build(),
+    )
+}
+
+/// The answer to a paused request.
+#[derive(Debug)]
+pub enum FetchReply {
+    Fail(FailRequestParams),
+    Fulfill(FulfillRequestParams),
+    Continue(ContinueRequestParams),
+}
+
+impl FetchReply {
+    /// What the reply does to the request, for the request log.
+    pub fn interception(&self) -> Option<(Interception, Option<u16>)> {
+        match self {
+            FetchReply::Fail(_) => Some((Interception::Blocked, None)),
+            FetchReply::Fulfill(params) => Some((
+                Interception::Mocked,
+                u16::try_from(params.response_code).ok(),
+            )),
+            FetchReply::Continue(_) => None,
+        }
+    }
+}
+
+/// Answer a request paused for `url` with the latest rule matching it, and
+/// let it through when none does.
+pub fn fetch_reply(request_id: fetch::RequestId, url: &str, rules: &[InterceptRule]) -> FetchReply {
+    match rules
+        .iter()
+        .rev()
+        .find(|rule| rule.matches(url))
+        .map(|rule| &rule.action)
+    {
+        Some(InterceptAction::Block) => FetchReply::Fail(FailRequestParams::new(
+            request_id,
+            ErrorReason::BlockedByClient,
+        )),
+        Some(InterceptAction::Mock {
+            status,
+            body,
+            content_type,
+        }) => {
+            let content_type = content_type
+                .clone()
+                .unwrap_or_else(|| guess_content_type(body).to_string());
+            FetchReply::Fulfill(
+                FulfillRequestParams::builder()
+                    .request_id(request_id)
+                    .response_code(i64::from(*status))
+                    .response_header(HeaderEntry::new("Content-Type", content_type))
+                    .body(base64::engine::general_purpose::STANDARD.encode(body))
+                    .build()
+                    .expect("request id and response code are set"),
+            )
+        }
+        Some(InterceptAction::Continue) | None => {
+            FetchReply::Continue(ContinueRequestParams::new(request_id))
+        }
+    }
+}
+
+/// Answer every paused request from `rules`, noting blocks and mocks in `log`.
+pub async fn handle_paused_requests(
+    page: &Page,
+    rules: InterceptRules,
+    log: Option<SharedRequestLog>,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let mut paused = page
+        .event_listener::<EventRequestPaused>()
+        .await
+        .map_err(|e| format!("Failed to subscribe to fetch events: {}", e))?;
+
+    let page = page.clone();
+    tokio::spawn(async move {
+        while let Some(event) = paused.next().await {
+            let reply = fetch_reply(
+                event.request_id.clone(),
+                &event.request.url,
+                &rules.lock().unwrap(),
+            );
+            if let (Some((how, status)), Some(log), Some(id)) =
+                (reply.interception(), &log, &event.network_id)
+            {
+                log.lock()
+                    .unwrap()
+                    .intercepted(id.inner().clone(), how, status);
+            }
+            let sent = match reply {
+                FetchReply::Fail(params) => page.execute(params).await.map(|_| ()),
+                FetchReply::Fulfill(params) => page.execute(params).await.map(|_| ()),
+                FetchReply::Continue(params) => page.execute(params).await.map(|_| ()),
+            };
+            if let Err(e) = sent {
+                tracing::warn!(
+                    "Failed to answer paused request {}: {}",
+                    event.request.url,
+                    e
+                );
+            }
+        }
+    });
+
+    Ok(())
+}
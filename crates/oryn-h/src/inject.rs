@@ -1,4 +1,7 @@
 use chromiumoxide::Page;
+use chromiumoxide::cdp::js_protocol::runtime::{EvaluateParams, ExecutionContextId};
+use chromiumoxide::js::Evaluation;
+use oryn_engine::protocol::redact_secret_text;
 use std::error::Error;
 use std::future::Future;
 use std::time::Duration;
@@ -61,15 +64,36 @@ where
         .into())
 }
 
-pub async fn inject_scanner(page: &Page) -> Result<(), Box<dyn Error + Send + Sync>> {
-    retry_on_context_error("Scanner injection", || try_inject_scanner(page)).await
+/// `expression` as evaluated in the execution context `context`, or in the
+/// main frame's when it is `None`.
+fn in_context(expression: &str, context: Option<ExecutionContextId>) -> Evaluation {
+    match context {
+        Some(context) => {
+            let mut params = EvaluateParams::new(expression);
+            params.context_id = Some(context);
+            params.await_promise = Some(true);
+            params.return_by_value = Some(true);
+            params.into()
+        }
+        None => expression.into(),
+    }
+}
+
+pub async fn inject_scanner(
+    page: &Page,
+    context: Option<ExecutionContextId>,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    retry_on_context_error("Scanner injection", || try_inject_scanner(page, context)).await
 }
 
 /// Internal function that attempts scanner injection once.
-async fn try_inject_scanner(page: &Page) -> Result<(), Box<dyn Error + Send + Sync>> {
+async fn try_inject_scanner(
+    page: &Page,
+    context: Option<ExecutionContextId>,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
     // Check if loaded
     let is_loaded: bool = page
-        .evaluate("typeof window.Oryn !== 'undefined'")
+        .evaluate(in_context("typeof window.Oryn !== 'undefined'", context))
         .await
         .map_err(|e| format!("Failed to check scanner status: {}", e))?
         .into_value()
@@ -77,7 +101,7 @@ async fn try_inject_scanner(page: &Page) -> Result<(), Box<dyn Error + Send + Sy
 
     if !is_loaded {
         // Inject
-        page.evaluate(SCANNER_JS)
+        page.evaluate(in_context(SCANNER_JS, context))
             .await
             .map_err(|e| format!("Failed to inject scanner.js: {}", e))?;
     }
@@ -87,20 +111,53 @@ async fn try_inject_scanner(page: &Page) -> Result<(), Box<dyn Error + Send + Sy
 
 pub async fn execute_command(
     page: &Page,
+    context: Option<ExecutionContextId>,
     _action: &str,
     params: serde_json::Value,
 ) -> Result<serde_json::Value, Box<dyn Error + Send + Sync>> {
     let params_json = serde_json::to_string(&params)?;
     let expression = format!("window.Oryn.process({})", params_json);
 
-    tracing::info!("Evaluating script: {}", expression);
+    let mut logged = params;
+    redact_secret_text(&mut logged);
+    tracing::info!("Evaluating script: window.Oryn.process({})", logged);
+
+    evaluate_scanner(page, context, &expression).await
+}
 
+/// Run several scanner commands in one evaluation, in order, and return the
+/// array of their responses.
+pub async fn execute_batch(
+    page: &Page,
+    context: Option<ExecutionContextId>,
+    commands: Vec<serde_json::Value>,
+) -> Result<serde_json::Value, Box<dyn Error + Send + Sync>> {
+    let commands_json = serde_json::to_string(&commands)?;
+    let expression = format!(
+        "(async (commands) => {{ const responses = []; \
+         for (const command of commands) {{ responses.push(await window.Oryn.process(command)); }} \
+         return responses; }})({})",
+        commands_json
+    );
+
+    tracing::info!("Evaluating batch of {} commands", commands.len());
+
+    evaluate_scanner(page, context, &expression).await
+}
+
+/// Evaluate an expression that calls into the scanner, injecting it first and
+/// retrying while the page is between execution contexts.
+async fn evaluate_scanner(
+    page: &Page,
+    context: Option<ExecutionContextId>,
+    expression: &str,
+) -> Result<serde_json::Value, Box<dyn Error + Send + Sync>> {
     let mut last_error = None;
 
     for attempt in 0..MAX_CONTEXT_RETRIES {
-        inject_scanner(page).await?;
+        inject_scanner(page, context).await?;
 
-        match evaluate_with_timeout(page, &expression).await {
+        match evaluate_with_timeout(page, context, expression).await {
             Ok(value) => return Ok(value),
             Err(EvalError::Timeout) => {
                 return Err(
@@ -136,9 +193,11 @@ enum EvalError {
 
 async fn evaluate_with_timeout(
     page: &Page,
+    context: Option<ExecutionContextId>,
     expression: &str,
 ) -> Result<serde_json::Value, EvalError> {
-    let eval_result = tokio::time::timeout(EVAL_TIMEOUT, page.evaluate(expression)).await;
+    let eval_result =
+        tokio::time::timeout(EVAL_TIMEOUT, page.evaluate(in_context(expression, context))).await;
 
     match eval_result {
         Err(_) => Err(EvalError::Timeout),
@@ -1,6 +1,6 @@
 use clap::Parser as ClapParser;
 use oryn_engine::backend::Backend;
-use oryn_engine::cli::{self, FileErrorMode, FileOptions, OutputHandlers, ReplOptions};
+use oryn_engine::cli::{self, FileErrorMode, FileOptions, OutputHandlers, OutputMode, ReplOptions};
 use oryn_engine::executor::CommandExecutor;
 use oryn_h::backend::HeadlessBackend;
 
@@ -28,7 +28,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let mut executor = CommandExecutor::new();
     let output = OutputHandlers {
         out: |msg| println!("{}", msg),
-        err: |msg| println!("{}", msg),
+        err: |msg| eprintln!("{}", msg),
     };
     let repl_options = ReplOptions {
         banner_lines: &["Backend launched. Enter commands (e.g., 'goto google.com', 'scan')."],
@@ -38,11 +38,13 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         ctrl_c_message: None,
     };
 
+    let mut exit_code = 0;
     if let Some(file_path) = args.file {
-        cli::run_file(
+        exit_code = cli::run_file(
             &mut backend,
             &mut executor,
             output,
+            OutputMode::Human,
             &file_path,
             FileOptions {
                 stop_on_error: true,
@@ -51,9 +53,19 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         )
         .await?;
     } else {
-        cli::run_repl(&mut backend, &mut executor, output, repl_options).await?;
+        cli::run_repl(
+            &mut backend,
+            &mut executor,
+            output,
+            OutputMode::Human,
+            repl_options,
+        )
+        .await?;
     }
 
     backend.close().await?;
+    if exit_code != 0 {
+        std::process::exit(exit_code);
+    }
     Ok(())
 }
@@ -1,8 +1,12 @@
 use oryn_engine::backend::Backend;
-use oryn_engine::protocol::{ScanRequest, ScannerAction};
+use oryn_engine::executor::CommandExecutor;
+use oryn_engine::protocol::{PdfOptions, ScanRequest, ScannerAction};
 use oryn_h::backend::HeadlessBackend;
 use serial_test::serial;
+use std::io::{Read, Write};
+use std::net::TcpListener;
 use std::path::Path;
+use std::sync::{Arc, Mutex};
 use tokio::fs;
 
 #[tokio::test]
@@ -81,7 +85,8 @@ async fn test_headless_lifecycle_and_scan() {
     // HeadlessBackend exposes `get_client()`.
     if let Some(client) = backend.get_client() {
         let output_path = Path::new("test_output.pdf");
-        let pdf_res = oryn_h::features::generate_pdf(&client.page, output_path).await;
+        let pdf_res =
+            oryn_h::features::generate_pdf(&client.page, output_path, &PdfOptions::default()).await;
         assert!(
             pdf_res.is_ok(),
             "PDF generation failed: {:?}",
@@ -103,9 +108,510 @@ async fn test_headless_lifecycle_and_scan() {
     assert!(!tabs.is_empty(), "Tabs should not be empty");
     assert!(tabs.iter().any(|t| t.url.contains("data:text/html")));
 
-    let pdf_bytes = backend.pdf().await.expect("Trait PDF failed");
+    let pdf_bytes = backend
+        .pdf(&PdfOptions::default())
+        .await
+        .expect("Trait PDF failed");
     assert!(!pdf_bytes.is_empty(), "PDF should not be empty");
 
     // 7. Close
     backend.close().await.expect("Close failed");
 }
+
+#[tokio::test]
+#[serial]
+async fn test_screenshot_wait_stable_after_transition() {
+    let mut backend = HeadlessBackend::new();
+    if let Err(e) = backend.launch().await {
+        eprintln!("Failed to launch browser (is Chromium installed?): {}", e);
+        return;
+    }
+
+    // The box starts growing as soon as the page renders; a capture taken
+    // mid-transition would differ from run to run.
+    let html = "<html><body style='margin:0'>\
+        <div id='box' style='width:40px;height:40px;background:red;transition:width 600ms linear'></div>\
+        <script>requestAnimationFrame(() => requestAnimationFrame(() => \
+        { document.getElementById('box').style.width = '300px'; }));</script>\
+        </body></html>";
+    let url = format!("data:text/html,{}", html);
+
+    let mut executor = CommandExecutor::new();
+    let mut captures = Vec::new();
+    for run in 0..2 {
+        backend.navigate(&url).await.expect("Navigation failed");
+        let path = std::env::temp_dir().join(format!("oryn_wait_stable_{}.png", run));
+        executor
+            .execute_line(
+                &mut backend,
+                &format!("screenshot --wait-stable --output {}", path.display()),
+            )
+            .await
+            .expect("Screenshot failed");
+        captures.push(fs::read(&path).await.expect("Screenshot not written"));
+        fs::remove_file(&path).await.ok();
+    }
+
+    assert!(!captures[0].is_empty());
+    assert_eq!(
+        captures[0], captures[1],
+        "captures taken after the transition should be identical"
+    );
+
+    backend.close().await.expect("Close failed");
+}
+
+#[tokio::test]
+#[serial]
+async fn test_smooth_scroll_settles_before_returning() {
+    let mut backend = HeadlessBackend::new();
+    if let Err(e) = backend.launch().await {
+        eprintln!("Failed to launch browser (is Chromium installed?): {}", e);
+        return;
+    }
+
+    let html = "<html><body style='margin:0;height:5000px'>tall</body></html>";
+    backend
+        .navigate(&format!("data:text/html,{}", html))
+        .await
+        .expect("Navigation failed");
+
+    let mut executor = CommandExecutor::new();
+    executor
+        .execute_line(&mut backend, "scroll down --smooth --amount 800")
+        .await
+        .expect("Scroll failed");
+    // Read immediately: without the settle wait this lands mid-animation.
+    let result = executor
+        .execute_line(&mut backend, r#"eval "return window.scrollY""#)
+        .await
+        .expect("Eval failed");
+
+    assert_eq!(result.output.trim(), "800");
+
+    backend.close().await.expect("Close failed");
+}
+
+/// Serve `html` on a local port, recording the head of every request.
+fn serve_html(html: &'static str) -> (String, Arc<Mutex<Vec<String>>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").expect("bind");
+    let addr = listener.local_addr().unwrap();
+    let requests = Arc::new(Mutex::new(Vec::new()));
+    let seen = requests.clone();
+    std::thread::spawn(move || {
+        for mut stream in listener.incoming().flatten() {
+            let mut buf = [0u8; 4096];
+            let n = stream.read(&mut buf).unwrap_or(0);
+            seen.lock()
+                .unwrap()
+                .push(String::from_utf8_lossy(&buf[..n]).into_owned());
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: text/html\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                html.len(),
+                html
+            );
+            stream.write_all(response.as_bytes()).ok();
+        }
+    });
+    (format!("http://{}/app", addr), requests)
+}
+
+#[tokio::test]
+#[serial]
+async fn test_goto_sends_referer_and_waits_until_condition() {
+    let mut backend = HeadlessBackend::new();
+    if let Err(e) = backend.launch().await {
+        eprintln!("Failed to launch browser (is Chromium installed?): {}", e);
+        return;
+    }
+
+    // "Home" only appears well after the load event.
+    let (url, requests) = serve_html(
+        "<html><body>Loading<script>setTimeout(() => \
+         { document.body.innerHTML = '<h1>Home</h1>'; }, 500);</script></body></html>",
+    );
+
+    let mut executor = CommandExecutor::new();
+    executor
+        .execute_line(
+            &mut backend,
+            &format!(
+                r#"goto "{}" --referer "https://x.example/" --until visible "Home" --timeout 5s"#,
+                url
+            ),
+        )
+        .await
+        .expect("goto failed");
+
+    let page = requests
+        .lock()
+        .unwrap()
+        .iter()
+        .find(|r| r.starts_with("GET /app"))
+        .cloned()
+        .expect("page was not requested");
+    assert!(
+        page.to_lowercase().contains("referer: https://x.example/"),
+        "{}",
+        page
+    );
+
+    let result = executor
+        .execute_line(&mut backend, r#"eval "return document.body.innerText""#)
+        .await
+        .expect("Eval failed");
+    assert_eq!(result.output.trim(), "Home");
+
+    backend.close().await.expect("Close failed");
+}
+
+#[tokio::test]
+#[serial]
+async fn test_goto_spa_changes_route_without_reload() {
+    let mut backend = HeadlessBackend::new();
+    if let Err(e) = backend.launch().await {
+        eprintln!("Failed to launch browser (is Chromium installed?): {}", e);
+        return;
+    }
+
+    // A router that renders the path on popstate; `loaded` marks the document.
+    let (url, requests) = serve_html(
+        "<html><body><h1>Start</h1><script>window.loaded = true;\
+         window.addEventListener('popstate', () => { document.querySelector('h1')\
+         .textContent = location.pathname === '/app/settings' ? 'Settings' : 'Start'; });\
+         </script></body></html>",
+    );
+
+    let mut executor = CommandExecutor::new();
+    executor
+        .execute_line(&mut backend, &format!(r#"goto "{}""#, url))
+        .await
+        .expect("goto failed");
+    let result = executor
+        .execute_line(
+            &mut backend,
+            r#"goto "/app/settings" --spa --until visible "Settings" --timeout 5s"#,
+        )
+        .await
+        .expect("SPA goto failed");
+    assert!(
+        result.output.ends_with("/app/settings"),
+        "{}",
+        result.output
+    );
+
+    let result = executor
+        .execute_line(
+            &mut backend,
+            r#"eval "return location.pathname + ' ' + (window.loaded === true)""#,
+        )
+        .await
+        .expect("Eval failed");
+    assert_eq!(result.output.trim(), "/app/settings true");
+    assert!(
+        !requests
+            .lock()
+            .unwrap()
+            .iter()
+            .any(|r| r.starts_with("GET /app/settings")),
+        "the route should not be fetched"
+    );
+
+    backend.close().await.expect("Close failed");
+}
+
+#[tokio::test]
+#[serial]
+async fn test_type_then_tab_blurs_and_moves_focus() {
+    let mut backend = HeadlessBackend::new();
+    if let Err(e) = backend.launch().await {
+        eprintln!("Failed to launch browser (is Chromium installed?): {}", e);
+        return;
+    }
+
+    let (url, _) = serve_html(
+        "<html><body>\
+         <label for='email'>Email</label><input id='email'>\
+         <label for='name'>Name</label><input id='name'>\
+         <script>document.getElementById('email')\
+         .addEventListener('blur', () => { window.emailBlurred = true; });</script>\
+         </body></html>",
+    );
+
+    let mut executor = CommandExecutor::new();
+    executor
+        .execute_line(&mut backend, &format!(r#"goto "{}""#, url))
+        .await
+        .expect("goto failed");
+    executor
+        .execute_line(&mut backend, r#"type "Email" "a@b.com" --then-tab"#)
+        .await
+        .expect("type failed");
+
+    let result = executor
+        .execute_line(
+            &mut backend,
+            r#"eval "return [window.emailBlurred === true, document.activeElement.id].join(',')""#,
+        )
+        .await
+        .expect("Eval failed");
+    assert_eq!(result.output.trim(), "true,name");
+
+    backend.close().await.expect("Close failed");
+}
+
+#[tokio::test]
+#[serial]
+async fn test_type_into_date_input_validates_the_format() {
+    let mut backend = HeadlessBackend::new();
+    if let Err(e) = backend.launch().await {
+        eprintln!("Failed to launch browser (is Chromium installed?): {}", e);
+        return;
+    }
+
+    let (url, _) = serve_html(
+        "<html><body><label for='due'>Due date</label><input id='due' type='date'></body></html>",
+    );
+
+    let mut executor = CommandExecutor::new();
+    executor
+        .execute_line(&mut backend, &format!(r#"goto "{}""#, url))
+        .await
+        .expect("goto failed");
+    executor
+        .execute_line(&mut backend, r#"type "Due date" "2024-3-5""#)
+        .await
+        .expect("type failed");
+
+    let err = executor
+        .execute_line(&mut backend, r#"type "Due date" "03/05/2024""#)
+        .await
+        .err()
+        .expect("an invalid date should be rejected");
+    assert!(err.to_string().contains("expected YYYY-MM-DD"), "{}", err);
+
+    // The rejected value leaves the valid one in place
+    let result = executor
+        .execute_line(
+            &mut backend,
+            r#"eval "return document.getElementById('due').value""#,
+        )
+        .await
+        .expect("Eval failed");
+    assert_eq!(result.output.trim(), "2024-03-05");
+
+    backend.close().await.expect("Close failed");
+}
+
+/// Width and height from a PNG's IHDR chunk.
+fn png_size(png: &[u8]) -> (u32, u32) {
+    let width = u32::from_be_bytes(png[16..20].try_into().unwrap());
+    let height = u32::from_be_bytes(png[20..24].try_into().unwrap());
+    (width, height)
+}
+
+#[tokio::test]
+#[serial]
+async fn test_screenshot_element_only_adds_padding() {
+    let mut backend = HeadlessBackend::new();
+    if let Err(e) = backend.launch().await {
+        eprintln!("Failed to launch browser (is Chromium installed?): {}", e);
+        return;
+    }
+
+    let (url, _) = serve_html(
+        "<html><body style='margin:0'>\
+         <button id='card' style='position:absolute;left:100px;top:100px;\
+         width:200px;height:50px;margin:0;padding:0;border:0'>Card</button>\
+         </body></html>",
+    );
+
+    let mut executor = CommandExecutor::new();
+    executor
+        .execute_line(&mut backend, &format!(r#"goto "{}""#, url))
+        .await
+        .expect("goto failed");
+
+    let path = std::env::temp_dir().join("oryn_element_padding.png");
+    executor
+        .execute_line(
+            &mut backend,
+            &format!(
+                r#"screenshot "Card" --element-only --padding 10 --output {}"#,
+                path.display()
+            ),
+        )
+        .await
+        .expect("Screenshot failed");
+    let png = fs::read(&path).await.expect("Screenshot not written");
+    fs::remove_file(&path).await.ok();
+
+    assert_eq!(png_size(&png), (220, 70));
+
+    backend.close().await.expect("Close failed");
+}
+
+#[tokio::test]
+#[serial]
+async fn test_select_custom_combobox_by_visible_text() {
+    let mut backend = HeadlessBackend::new();
+    if let Err(e) = backend.launch().await {
+        eprintln!("Failed to launch browser (is Chromium installed?): {}", e);
+        return;
+    }
+
+    let (url, _) = serve_html(
+        "<html><body>\
+         <div id='country' role='combobox' aria-haspopup='listbox' aria-expanded='false' \
+         tabindex='0' onclick=\"document.getElementById('menu').style.display='block';\
+         this.setAttribute('aria-expanded','true')\">Choose a country</div>\
+         <div id='menu' style='display:none'>\
+         <input id='filter' aria-label='Filter' oninput=\"for (const o of \
+         document.querySelectorAll('[role=option]')) o.style.display = \
+         o.textContent.toLowerCase().includes(this.value.toLowerCase()) ? '' : 'none'\">\
+         <div role='listbox'>\
+         <div role='option' onclick=\"document.getElementById('country').textContent=\
+         this.textContent;document.getElementById('menu').style.display='none'\">Brazil</div>\
+         <div role='option' onclick=\"document.getElementById('country').textContent=\
+         this.textContent;document.getElementById('menu').style.display='none'\">Canada</div>\
+         </div></div>\
+         </body></html>",
+    );
+
+    let mut executor = CommandExecutor::new();
+    executor
+        .execute_line(&mut backend, &format!(r#"goto "{}""#, url))
+        .await
+        .expect("goto failed");
+
+    let result = executor
+        .execute_line(
+            &mut backend,
+            r##"select css("#country") "Canada" --custom"##,
+        )
+        .await
+        .expect("select --custom failed");
+    assert!(
+        result.output.contains(r#"Selected "Canada""#),
+        "{}",
+        result.output
+    );
+
+    let text = executor
+        .execute_line(&mut backend, r##"text --selector "#country""##)
+        .await
+        .expect("text failed");
+    assert!(text.output.contains("Canada"), "{}", text.output);
+
+    backend.close().await.expect("Close failed");
+}
+
+#[tokio::test]
+#[serial]
+async fn test_frame_switch_scans_inside_the_frame() {
+    let mut backend = HeadlessBackend::new();
+    if let Err(e) = backend.launch().await {
+        eprintln!("Failed to launch browser (is Chromium installed?): {}", e);
+        return;
+    }
+
+    let (url, _) = serve_html(
+        "<html><body><h1 id='title'>Shop</h1>\
+         <iframe id='checkout-frame' name='checkout' \
+         srcdoc=\"<button id='pay'>Pay now</button>\"></iframe>\
+         </body></html>",
+    );
+
+    let mut executor = CommandExecutor::new();
+    executor
+        .execute_line(&mut backend, &format!(r#"goto "{}""#, url))
+        .await
+        .expect("goto failed");
+
+    executor
+        .execute_line(&mut backend, r#"frame "checkout""#)
+        .await
+        .expect("frame switch failed");
+    let inside = executor
+        .execute_line(&mut backend, r##"text --selector "#pay""##)
+        .await
+        .expect("text in frame failed");
+    assert!(inside.output.contains("Pay now"), "{}", inside.output);
+
+    executor
+        .execute_line(&mut backend, "frame main")
+        .await
+        .expect("frame main failed");
+    executor
+        .execute_line(&mut backend, r##"frame "#checkout-frame""##)
+        .await
+        .expect("frame switch by selector failed");
+    let scan = executor
+        .execute_line(&mut backend, "observe")
+        .await
+        .expect("observe in frame failed");
+    assert!(scan.output.contains("Pay now"), "{}", scan.output);
+    assert!(!scan.output.contains("Shop"), "{}", scan.output);
+
+    backend.close().await.expect("Close failed");
+}
+
+#[tokio::test]
+#[serial]
+async fn test_intercept_mocks_and_blocks_requests() {
+    let mut backend = HeadlessBackend::new();
+    if let Err(e) = backend.launch().await {
+        eprintln!("Failed to launch browser (is Chromium installed?): {}", e);
+        return;
+    }
+
+    let (url, requests) = serve_html(
+        "<html><body><img src=\"/logo.png\"><script>fetch('/api/user').then(r => r.json())\
+         .then(u => { document.body.innerHTML = '<h1>' + u.name + '</h1>'; });</script>\
+         </body></html>",
+    );
+
+    let mut executor = CommandExecutor::new();
+    for line in [
+        r#"intercept mock "/api/user" 200 '{"name":"Mocked User"}'"#,
+        r#"intercept block "*.png""#,
+    ] {
+        executor
+            .execute_line(&mut backend, line)
+            .await
+            .expect("intercept failed");
+    }
+    executor
+        .execute_line(
+            &mut backend,
+            &format!(
+                r#"goto "{}" --until visible "Mocked User" --timeout 5s"#,
+                url
+            ),
+        )
+        .await
+        .expect("goto failed");
+
+    let seen = requests.lock().unwrap().clone();
+    assert!(
+        !seen
+            .iter()
+            .any(|r| r.starts_with("GET /api/user") || r.starts_with("GET /logo.png")),
+        "{:?}",
+        seen
+    );
+
+    let output = executor
+        .execute_line(&mut backend, "requests")
+        .await
+        .expect("requests failed")
+        .output;
+    assert!(
+        output.contains("/api/user \u{2192} 200 (mocked)"),
+        "{}",
+        output
+    );
+    assert!(output.contains("/logo.png \u{2192} BLOCKED"), "{}", output);
+
+    backend.close().await.expect("Close failed");
+}
@@ -0,0 +1,105 @@
+//! Request interception tests.
+//!
+//! These tests verify how intercept rules become CDP Fetch commands: which
+//! requests pause, and how a paused request is failed, fulfilled or let
+//! through. No browser is needed.
+
+use base64::Engine;
+use chromiumoxide::cdp::browser_protocol::fetch::RequestId;
+use chromiumoxide::cdp::browser_protocol::network::ErrorReason;
+use oryn_engine::protocol::{InterceptAction, InterceptRule, Interception};
+use oryn_h::features::{FetchReply, fetch_enable_params, fetch_reply};
+
+fn rule(url_pattern: &str, action: InterceptAction) -> InterceptRule {
+    InterceptRule {
+        url_pattern: url_pattern.into(),
+        action,
+    }
+}
+
+fn mock_user() -> InterceptRule {
+    rule(
+        "/api/user",
+        InterceptAction::Mock {
+            status: 200,
+            body: r#"{"name":"x"}"#.into(),
+            content_type: None,
+        },
+    )
+}
+
+#[test]
+fn test_fetch_pauses_only_matching_requests() {
+    assert!(fetch_enable_params(&[]).is_none());
+
+    let params = fetch_enable_params(&[rule("*.png", InterceptAction::Block), mock_user()])
+        .expect("rules turn the Fetch domain on");
+    let patterns: Vec<_> = params
+        .patterns
+        .unwrap()
+        .into_iter()
+        .map(|p| p.url_pattern.unwrap())
+        .collect();
+    // Wildcard patterns pass through; plain ones match anywhere in the URL.
+    assert_eq!(patterns, vec!["*.png", "*/api/user*"]);
+}
+
+#[test]
+fn test_blocked_request_fails_as_blocked_by_client() {
+    let reply = fetch_reply(
+        RequestId::new("r1"),
+        "https://example.com/logo.png",
+        &[rule("*.png", InterceptAction::Block)],
+    );
+    match &reply {
+        FetchReply::Fail(params) => {
+            assert_eq!(params.request_id.inner(), "r1");
+            assert_eq!(params.error_reason, ErrorReason::BlockedByClient);
+        }
+        other => panic!("Expected a failed request, got {:?}", other),
+    }
+    assert_eq!(reply.interception(), Some((Interception::Blocked, None)));
+}
+
+#[test]
+fn test_mocked_request_is_fulfilled_with_status_body_and_type() {
+    let reply = fetch_reply(
+        RequestId::new("r2"),
+        "https://example.com/api/user?id=1",
+        &[mock_user()],
+    );
+    let FetchReply::Fulfill(params) = &reply else {
+        panic!("Expected a fulfilled request, got {:?}", reply);
+    };
+    assert_eq!(params.response_code, 200);
+    let body: String = params.body.clone().unwrap().into();
+    let body = base64::engine::general_purpose::STANDARD
+        .decode(body)
+        .unwrap();
+    assert_eq!(body, br#"{"name":"x"}"#);
+    let headers = params.response_headers.as_ref().unwrap();
+    assert_eq!(headers[0].name, "Content-Type");
+    assert_eq!(headers[0].value, "application/json");
+    assert_eq!(
+        reply.interception(),
+        Some((Interception::Mocked, Some(200)))
+    );
+}
+
+#[test]
+fn test_latest_matching_rule_wins_and_others_continue() {
+    let rules = [
+        rule("*example.com*", InterceptAction::Block),
+        rule("/api/", InterceptAction::Continue),
+    ];
+
+    let reply = fetch_reply(RequestId::new("r3"), "https://example.com/api/x", &rules);
+    assert!(matches!(reply, FetchReply::Continue(_)), "{:?}", reply);
+    assert_eq!(reply.interception(), None);
+
+    let reply = fetch_reply(RequestId::new("r4"), "https://example.com/app.js", &rules);
+    assert!(matches!(reply, FetchReply::Fail(_)), "{:?}", reply);
+
+    let reply = fetch_reply(RequestId::new("r5"), "https://other.org/", &rules);
+    assert!(matches!(reply, FetchReply::Continue(_)), "{:?}", reply);
+}
@@ -0,0 +1,54 @@
+//! PDF option tests.
+//!
+//! These tests verify how `pdf` options become Chrome print parameters, and
+//! that options Chrome would refuse are rejected first. No browser is needed.
+
+use oryn_engine::protocol::PdfOptions;
+use oryn_h::features::pdf_params;
+
+#[test]
+fn test_paper_format_sets_the_page_size() {
+    let params = pdf_params(&PdfOptions {
+        format: Some("letter".into()),
+        landscape: true,
+        ..Default::default()
+    })
+    .unwrap();
+    assert_eq!(params.paper_width, Some(8.5));
+    assert_eq!(params.paper_height, Some(11.0));
+    assert_eq!(params.landscape, Some(true));
+
+    let params = pdf_params(&PdfOptions::default()).unwrap();
+    assert_eq!(params.paper_width, None);
+    assert_eq!(params.scale, None);
+}
+
+#[test]
+fn test_unknown_paper_format_is_rejected() {
+    let err = pdf_params(&PdfOptions {
+        format: Some("A3".into()),
+        ..Default::default()
+    })
+    .unwrap_err();
+    assert!(err.contains("unknown paper format 'A3'"), "{}", err);
+}
+
+#[test]
+fn test_scale_outside_what_chrome_accepts_is_rejected() {
+    for scale in [0.05, 2.5] {
+        let err = pdf_params(&PdfOptions {
+            scale: Some(scale),
+            ..Default::default()
+        })
+        .unwrap_err();
+        assert!(err.contains("out of range"), "{}", err);
+    }
+    for scale in [0.1, 2.0] {
+        let params = pdf_params(&PdfOptions {
+            scale: Some(scale),
+            ..Default::default()
+        })
+        .unwrap();
+        assert_eq!(params.scale, Some(f64::from(scale)));
+    }
+}
@@ -1,15 +1,34 @@
 use crate::server::{RemoteServer, ServerHandle};
 use async_trait::async_trait;
-use oryn_engine::backend::{Backend, BackendError, NavigationResult};
+use oryn_engine::backend::{Backend, BackendCapabilities, BackendError, NavigationResult};
 use oryn_engine::protocol::{
-    Action, BackRequest, BrowserAction, ExecuteRequest, NavigateRequest, ScannerAction,
-    ScannerProtocolResponse,
+    Action, BackRequest, BrowserAction, ExecuteRequest, ForwardRequest, InterceptRequest,
+    InterceptRule, NavigateRequest, NetworkRequest, RefreshRequest, RequestsRequest, ScannerAction,
+    ScannerData, ScannerProtocolResponse,
 };
+use oryn_engine::registry::BackendRegistry;
+use std::time::Duration;
 use tracing::info;
 
+/// How long a command waits for the extension to (re)connect by default.
+pub const DEFAULT_CONNECT_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Register the remote backend as `remote`. Options: `port` (default 9001)
+/// and `connect_timeout` in seconds.
+pub fn register(registry: &mut BackendRegistry) {
+    registry.register("remote", |options| {
+        let port = options.parse_or("port", 9001)?;
+        let timeout = options.parse_or("connect_timeout", DEFAULT_CONNECT_TIMEOUT.as_secs())?;
+        Ok(Box::new(
+            RemoteBackend::new(port).with_connect_timeout(Duration::from_secs(timeout)),
+        ))
+    });
+}
+
 pub struct RemoteBackend {
     port: u16,
     server_handle: Option<ServerHandle>,
+    connect_timeout: Duration,
 }
 
 impl RemoteBackend {
@@ -17,33 +36,104 @@ impl RemoteBackend {
         Self {
             port,
             server_handle: None,
+            connect_timeout: DEFAULT_CONNECT_TIMEOUT,
         }
     }
 
+    /// Set how long a command waits for an extension to connect before
+    /// failing, both at start-up and after the extension drops.
+    pub fn with_connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = timeout;
+        self
+    }
+
+    /// Send an action and wait for the extension's answer. When the socket
+    /// drops before the answer arrives (the extension reloaded, say), wait
+    /// for it to reconnect and send the action once more.
     async fn send_action(
         &mut self,
         action: Action,
     ) -> Result<ScannerProtocolResponse, BackendError> {
         let handle = self.server_handle.as_ref().ok_or(BackendError::NotReady)?;
+        let mut connections = handle.connections.clone();
+        let mut retried = false;
 
-        // Wait for at least one extension to connect
-        if handle.command_tx.receiver_count() == 0 {
-            info!("Waiting for browser extension to connect...");
-            // Use timeout or loop? Original code looped.
-            while handle.command_tx.receiver_count() == 0 {
-                tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
+        loop {
+            if *connections.borrow_and_update() == 0 {
+                info!("Waiting for browser extension to connect...");
+                tokio::time::timeout(self.connect_timeout, connections.wait_for(|n| *n > 0))
+                    .await
+                    .map_err(|_| BackendError::TimeoutWithContext {
+                        operation: format!(
+                            "no browser extension connected on port {} within {}s",
+                            self.port,
+                            self.connect_timeout.as_secs_f32()
+                        ),
+                    })?
+                    .map_err(|_| BackendError::ConnectionLost)?;
+                info!("Extension connected.");
+            }
+
+            if let Err(e) = handle.command_tx.send(action.clone()) {
+                return Err(BackendError::Other(format!("Failed to broadcast: {}", e)));
+            }
+
+            let mut rx = handle.response_rx.lock().await;
+            // The server forwards an answer before it notices the socket
+            // closing, so an answer already received wins over the drop.
+            tokio::select! {
+                biased;
+                resp = rx.recv() => return resp.ok_or(BackendError::ConnectionLost),
+                _ = connections.wait_for(|n| *n == 0) => {
+                    if retried {
+                        return Err(BackendError::ConnectionLost);
+                    }
+                    retried = true;
+                    info!("Extension disconnected mid-command; resending after it reconnects");
+                }
             }
-            info!("Extension connected.");
         }
+    }
 
-        if let Err(e) = handle.command_tx.send(action) {
-            return Err(BackendError::Other(format!("Failed to broadcast: {}", e)));
+    /// Send a back, forward or refresh to the extension, which answers once
+    /// the tab has finished loading, with the URL it landed on.
+    async fn navigate_history(
+        &mut self,
+        action: BrowserAction,
+    ) -> Result<NavigationResult, BackendError> {
+        match self.send_action(Action::Browser(action)).await? {
+            ScannerProtocolResponse::Ok { data, .. } => {
+                let url = match *data {
+                    ScannerData::Value(value) => value
+                        .get("url")
+                        .and_then(|url| url.as_str())
+                        .unwrap_or_default()
+                        .to_string(),
+                    _ => String::new(),
+                };
+                Ok(NavigationResult {
+                    url,
+                    ..Default::default()
+                })
+            }
+            ScannerProtocolResponse::Error { message, .. } => {
+                Err(BackendError::Navigation(message))
+            }
         }
+    }
 
-        let mut rx = handle.response_rx.lock().await;
-        match rx.recv().await {
-            Some(resp) => Ok(resp),
-            None => Err(BackendError::ConnectionLost),
+    /// Send an action the extension's background page answers with a plain
+    /// value, such as an intercept rule change.
+    async fn send_browser_action(
+        &mut self,
+        action: BrowserAction,
+    ) -> Result<serde_json::Value, BackendError> {
+        match self.send_action(Action::Browser(action)).await? {
+            ScannerProtocolResponse::Ok { data, .. } => match *data {
+                ScannerData::Value(value) => Ok(value),
+                _ => Ok(serde_json::Value::Null),
+            },
+            ScannerProtocolResponse::Error { message, .. } => Err(BackendError::Other(message)),
         }
     }
 }
@@ -67,6 +157,14 @@ impl Backend for RemoteBackend {
         Ok(())
     }
 
+    fn capabilities(&self) -> BackendCapabilities {
+        BackendCapabilities {
+            pdf: false,
+            cookies: false,
+            tabs: false,
+        }
+    }
+
     async fn is_ready(&self) -> bool {
         self.server_handle.is_some()
     }
@@ -74,6 +172,7 @@ impl Backend for RemoteBackend {
     async fn navigate(&mut self, url: &str) -> Result<NavigationResult, BackendError> {
         let action = Action::Browser(BrowserAction::Navigate(NavigateRequest {
             url: url.to_string(),
+            referer: None,
         }));
 
         self.send_action(action).await?;
@@ -100,6 +199,8 @@ impl Backend for RemoteBackend {
             format: Some("png".into()),
             selector: None,
             fullpage: false,
+            wait_stable: false,
+            clip: None,
         }));
 
         let resp = self.send_action(action).await?;
@@ -128,29 +229,18 @@ impl Backend for RemoteBackend {
     }
 
     async fn go_back(&mut self) -> Result<NavigationResult, BackendError> {
-        self.send_action(Action::Browser(BrowserAction::Back(BackRequest {})))
-            .await?;
-        Ok(NavigationResult::default())
+        self.navigate_history(BrowserAction::Back(BackRequest {}))
+            .await
     }
 
     async fn go_forward(&mut self) -> Result<NavigationResult, BackendError> {
-        use oryn_engine::protocol::ForwardRequest;
-        self.send_action(Action::Browser(BrowserAction::Forward(
-            ForwardRequest::default(),
-        )))
-        .await?;
-        tokio::time::sleep(tokio::time::Duration::from_millis(300)).await;
-        Ok(NavigationResult::default())
+        self.navigate_history(BrowserAction::Forward(ForwardRequest::default()))
+            .await
     }
 
     async fn refresh(&mut self) -> Result<NavigationResult, BackendError> {
-        use oryn_engine::protocol::RefreshRequest;
-        self.send_action(Action::Browser(BrowserAction::Refresh(
-            RefreshRequest::default(),
-        )))
-        .await?;
-        tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
-        Ok(NavigationResult::default())
+        self.navigate_history(BrowserAction::Refresh(RefreshRequest::default()))
+            .await
     }
 
     async fn press_key(&mut self, key: &str, _modifiers: &[String]) -> Result<(), BackendError> {
@@ -167,10 +257,50 @@ impl Backend for RemoteBackend {
 
         let req = ScannerAction::Execute(ExecuteRequest {
             script,
-            args: vec![],
+            args: serde_json::Value::Null,
         });
 
         self.execute_scanner(req).await?;
         Ok(())
     }
+
+    async fn get_requests(&mut self) -> Result<Vec<NetworkRequest>, BackendError> {
+        let mut value = self
+            .send_browser_action(BrowserAction::Requests(RequestsRequest::default()))
+            .await?;
+        serde_json::from_value(value["requests"].take())
+            .map_err(|e| BackendError::Other(format!("Unexpected request list: {}", e)))
+    }
+
+    async fn pending_requests(&mut self) -> Result<usize, BackendError> {
+        // Ask for none of the log, only the count of requests in flight.
+        let value = self
+            .send_browser_action(BrowserAction::Requests(RequestsRequest {
+                last: Some(0),
+                ..Default::default()
+            }))
+            .await?;
+        // Extensions that predate the count leave it out.
+        value["pending"]
+            .as_u64()
+            .map(|n| n as usize)
+            .ok_or_else(|| BackendError::NotSupported("pending_requests".into()))
+    }
+
+    async fn add_intercept(&mut self, rule: InterceptRule) -> Result<(), BackendError> {
+        self.send_browser_action(BrowserAction::Intercept(InterceptRequest::Add {
+            rule,
+            body_file: None,
+        }))
+        .await?;
+        Ok(())
+    }
+
+    async fn clear_intercepts(&mut self, pattern: Option<&str>) -> Result<(), BackendError> {
+        self.send_browser_action(BrowserAction::Intercept(InterceptRequest::Clear {
+            pattern: pattern.map(str::to_string),
+        }))
+        .await?;
+        Ok(())
+    }
 }
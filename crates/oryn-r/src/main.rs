@@ -1,14 +1,19 @@
 use clap::Parser as ClapParser;
 use oryn_engine::backend::Backend;
-use oryn_engine::cli::{self, OutputHandlers, ReplOptions};
+use oryn_engine::cli::{self, OutputHandlers, OutputMode, ReplOptions};
 use oryn_engine::executor::CommandExecutor;
 use oryn_r::backend::RemoteBackend;
+use std::time::Duration;
 
 #[derive(ClapParser, Debug)]
 #[command(author, version, about, long_about = None)]
 struct Args {
     #[arg(short, long, default_value_t = 9001)]
     port: u16,
+
+    /// Seconds a command waits for the extension to connect or reconnect
+    #[arg(long, default_value_t = 60)]
+    connect_timeout: u64,
 }
 
 #[tokio::main]
@@ -22,13 +27,14 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         args.port
     );
 
-    let mut backend = RemoteBackend::new(args.port);
+    let mut backend = RemoteBackend::new(args.port)
+        .with_connect_timeout(Duration::from_secs(args.connect_timeout));
     backend.launch().await?;
 
     let mut executor = CommandExecutor::new();
     let output = OutputHandlers {
         out: |msg| println!("{}", msg),
-        err: |msg| println!("{}", msg),
+        err: |msg| eprintln!("{}", msg),
     };
     let repl_options = ReplOptions {
         banner_lines: &["Backend launched. Enter commands (e.g., 'goto google.com', 'scan')."],
@@ -38,7 +44,14 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         ctrl_c_message: None,
     };
 
-    cli::run_repl(&mut backend, &mut executor, output, repl_options).await?;
+    cli::run_repl(
+        &mut backend,
+        &mut executor,
+        output,
+        OutputMode::Human,
+        repl_options,
+    )
+    .await?;
 
     backend.close().await?;
     Ok(())
@@ -3,7 +3,7 @@ use oryn_engine::protocol::{Action, ScannerProtocolResponse};
 use std::net::SocketAddr;
 use std::sync::Arc;
 use tokio::net::{TcpListener, TcpStream};
-use tokio::sync::{Mutex, broadcast, mpsc};
+use tokio::sync::{Mutex, broadcast, mpsc, watch};
 use tokio_tungstenite::accept_async;
 use tokio_tungstenite::tungstenite::Message;
 use tracing::{error, info};
@@ -24,6 +24,8 @@ pub struct RemoteServer {
 pub struct ServerHandle {
     pub command_tx: broadcast::Sender<Action>,
     pub response_rx: Arc<Mutex<mpsc::Receiver<ScannerProtocolResponse>>>,
+    /// Number of extensions currently connected, updated as sockets open and close.
+    pub connections: watch::Receiver<usize>,
 }
 
 impl RemoteServer {
@@ -39,6 +41,7 @@ impl RemoteServer {
         println!("INFO: Remote Server listening on: {}", addr);
 
         let (response_tx, response_rx) = mpsc::channel(100);
+        let (connections_tx, connections) = watch::channel(0);
         let command_tx = self.command_tx.clone();
 
         let server_cmd_tx = command_tx.clone();
@@ -55,13 +58,19 @@ impl RemoteServer {
                 let cmd_rx = server_cmd_tx.subscribe();
                 let resp_tx = response_tx.clone();
 
-                tokio::spawn(accept_connection(stream, cmd_rx, resp_tx));
+                tokio::spawn(accept_connection(
+                    stream,
+                    cmd_rx,
+                    resp_tx,
+                    connections_tx.clone(),
+                ));
             }
         });
 
         Ok(ServerHandle {
             command_tx,
             response_rx: Arc::new(Mutex::new(response_rx)),
+            connections,
         })
     }
 }
@@ -70,6 +79,7 @@ async fn accept_connection(
     stream: TcpStream,
     mut cmd_rx: broadcast::Receiver<Action>,
     resp_tx: mpsc::Sender<ScannerProtocolResponse>,
+    connections: watch::Sender<usize>,
 ) {
     let ws_stream = match accept_async(stream).await {
         Ok(ws) => {
@@ -83,6 +93,7 @@ async fn accept_connection(
     };
 
     info!("New WebSocket connection: established");
+    connections.send_modify(|n| *n += 1);
     let (mut ws_sender, mut ws_receiver) = ws_stream.split();
 
     // Loop handling both incoming commands (to send to WS) and incoming WS messages (responses)
@@ -98,9 +109,9 @@ async fn accept_connection(
             }
 
             // Receive message from Extension -> Send to Backend
-            Some(msg) = ws_receiver.next() => {
+            msg = ws_receiver.next() => {
                 match msg {
-                    Ok(Message::Text(text)) => {
+                    Some(Ok(Message::Text(text))) => {
                         // Parse as ScannerProtocolResponse
                         match serde_json::from_str::<ScannerProtocolResponse>(&text) {
                             Ok(resp) => {
@@ -114,11 +125,11 @@ async fn accept_connection(
                             }
                         }
                     }
-                    Ok(Message::Close(_)) => {
+                    Some(Ok(Message::Close(_))) | None => {
                         info!("WebSocket closed");
                         break;
                     }
-                    Err(e) => {
+                    Some(Err(e)) => {
                         error!("WebSocket error: {}", e);
                         break;
                     }
@@ -127,4 +138,5 @@ async fn accept_connection(
             }
         }
     }
+    connections.send_modify(|n| *n -= 1);
 }
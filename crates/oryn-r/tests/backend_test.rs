@@ -0,0 +1,173 @@
+//! Remote backend tests.
+//!
+//! A simulated extension connects to the backend's server, checks the frame
+//! each history navigation sends and answers the way the extension does. It
+//! also drops and reopens its socket to exercise reconnection, and reports
+//! how many requests are in flight.
+
+use futures::{SinkExt, StreamExt};
+use oryn_engine::backend::{Backend, BackendError};
+use oryn_engine::protocol::{ExecuteRequest, ScannerAction, ScannerProtocolResponse};
+use oryn_r::backend::RemoteBackend;
+use serde_json::{Value, json};
+use std::time::Duration;
+use tokio::net::TcpStream;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream, connect_async};
+
+type Peer = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+async fn connect_peer(port: u16) -> Peer {
+    let url = format!("ws://localhost:{}", port);
+    for _ in 0..10 {
+        if let Ok((ws_stream, _)) = connect_async(&url).await {
+            return ws_stream;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    panic!("Failed to connect to the remote backend");
+}
+
+/// Receive one frame, then send `reply`, returning the frame.
+async fn answer(peer: &mut Peer, reply: Value) -> Value {
+    let msg = tokio::time::timeout(Duration::from_secs(2), peer.next())
+        .await
+        .expect("Timeout waiting for a frame")
+        .expect("Stream ended")
+        .expect("WS error");
+    let frame = serde_json::from_str(&msg.to_string()).expect("Frame is JSON");
+    peer.send(Message::Text(reply.to_string())).await.unwrap();
+    frame
+}
+
+#[tokio::test]
+async fn test_history_navigation_sends_frames() {
+    let port = 9051;
+    let mut backend = RemoteBackend::new(port);
+    backend.launch().await.expect("Failed to start server");
+    let mut peer = connect_peer(port).await;
+
+    let ok = |url: &str| json!({ "status": "ok", "url": url });
+
+    let (result, frame) = tokio::join!(
+        backend.go_back(),
+        answer(&mut peer, ok("https://example.com/a"))
+    );
+    assert_eq!(frame, json!({ "action": "back" }));
+    assert_eq!(result.unwrap().url, "https://example.com/a");
+
+    let (result, frame) = tokio::join!(
+        backend.go_forward(),
+        answer(&mut peer, ok("https://example.com/b"))
+    );
+    assert_eq!(frame, json!({ "action": "forward" }));
+    assert_eq!(result.unwrap().url, "https://example.com/b");
+
+    let (result, frame) = tokio::join!(
+        backend.refresh(),
+        answer(&mut peer, ok("https://example.com/b"))
+    );
+    assert_eq!(frame, json!({ "action": "refresh", "hard": false }));
+    assert_eq!(result.unwrap().url, "https://example.com/b");
+}
+
+#[tokio::test]
+async fn test_history_navigation_reports_extension_errors() {
+    let port = 9052;
+    let mut backend = RemoteBackend::new(port);
+    backend.launch().await.expect("Failed to start server");
+    let mut peer = connect_peer(port).await;
+
+    let reply = json!({
+        "status": "error",
+        "code": "BACK_ERROR",
+        "message": "Cannot find a next page in history."
+    });
+    let (result, _) = tokio::join!(backend.go_back(), answer(&mut peer, reply));
+    match result {
+        Err(BackendError::Navigation(msg)) => {
+            assert_eq!(msg, "Cannot find a next page in history.")
+        }
+        other => panic!("Expected a navigation error, got {:?}", other),
+    }
+}
+
+/// Receive one frame without answering it.
+async fn receive(peer: &mut Peer) -> Value {
+    let msg = tokio::time::timeout(Duration::from_secs(2), peer.next())
+        .await
+        .expect("Timeout waiting for a frame")
+        .expect("Stream ended")
+        .expect("WS error");
+    serde_json::from_str(&msg.to_string()).expect("Frame is JSON")
+}
+
+#[tokio::test]
+async fn test_command_survives_extension_reconnect() {
+    let port = 9053;
+    let mut backend = RemoteBackend::new(port).with_connect_timeout(Duration::from_secs(5));
+    backend.launch().await.expect("Failed to start server");
+    let mut peer = connect_peer(port).await;
+
+    let command = ScannerAction::Execute(ExecuteRequest {
+        script: "return document.title;".into(),
+        args: Value::Null,
+    });
+    let extension = async {
+        // The extension reloads after receiving the command, before answering.
+        let first = receive(&mut peer).await;
+        peer.close(None).await.unwrap();
+        drop(peer);
+
+        let mut peer = connect_peer(port).await;
+        let resent = answer(
+            &mut peer,
+            json!({ "status": "ok", "data": { "result": "Checkout" } }),
+        )
+        .await;
+        (first, resent)
+    };
+    let (result, (first, resent)) = tokio::join!(backend.execute_scanner(command), extension);
+
+    assert_eq!(first, resent, "the in-flight command should be resent");
+    match result.expect("command should complete after reconnecting") {
+        ScannerProtocolResponse::Ok { .. } => {}
+        other => panic!("Expected an ok response, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_command_fails_when_no_extension_connects() {
+    let port = 9054;
+    let mut backend = RemoteBackend::new(port).with_connect_timeout(Duration::from_millis(200));
+    backend.launch().await.expect("Failed to start server");
+
+    match backend.go_back().await {
+        Err(BackendError::TimeoutWithContext { operation }) => {
+            assert!(operation.contains("port 9054"), "{}", operation)
+        }
+        other => panic!("Expected a connect timeout, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_pending_requests_asks_for_the_count_only() {
+    let port = 9055;
+    let mut backend = RemoteBackend::new(port);
+    backend.launch().await.expect("Failed to start server");
+    let mut peer = connect_peer(port).await;
+
+    let reply = json!({ "status": "ok", "requests": [], "pending": 3 });
+    let (result, frame) = tokio::join!(backend.pending_requests(), answer(&mut peer, reply));
+    assert_eq!(frame, json!({ "action": "requests", "last": 0 }));
+    assert_eq!(result.unwrap(), 3);
+
+    // An extension without the count leaves `wait idle` to its fallback.
+    let reply = json!({ "status": "ok", "requests": [] });
+    let (result, _) = tokio::join!(backend.pending_requests(), answer(&mut peer, reply));
+    assert!(
+        matches!(result, Err(BackendError::NotSupported(_))),
+        "{:?}",
+        result
+    );
+}
@@ -472,6 +472,109 @@
             return state;
         },
 
+        // Natively focusable elements have a tabIndex of 0 or more; anything
+        // with an explicit tabindex, even -1, can take focus from script.
+        isFocusable: (el) => !el.disabled && (el.tabIndex >= 0 || el.hasAttribute('tabindex')),
+
+        // Browsers drop a value in the wrong format for number, date and time
+        // inputs without an error, so convert what is typed into the format
+        // the input expects, or reject it. Returns null for elements that take
+        // text as is. A secret text is left out of the rejection.
+        typedInputValue: (el, text, secret = false) => {
+            const type = el.tagName === 'INPUT' ? (el.type || '').toLowerCase() : '';
+            const pad = (n, width = 2) => String(n).padStart(width, '0');
+            const isDate = (y, m, d) => {
+                const date = new Date(Date.UTC(y, m - 1, d));
+                return date.getUTCFullYear() === y && date.getUTCMonth() === m - 1 && date.getUTCDate() === d;
+            };
+            const toDate = (value) => {
+                const match = value.match(/^(\d{4})[-/.](\d{1,2})[-/.](\d{1,2})$/);
+                if (!match) return null;
+                const [y, m, d] = match.slice(1).map(Number);
+                return isDate(y, m, d) ? `${y}-${pad(m)}-${pad(d)}` : null;
+            };
+            const toTime = (value) => {
+                const match = value.match(/^(\d{1,2}):(\d{2})(?::(\d{2})(\.\d{1,3})?)?\s*([ap]\.?m\.?)?$/i);
+                if (!match) return null;
+                let hours = Number(match[1]);
+                const minutes = Number(match[2]);
+                if (match[5]) {
+                    if (hours < 1 || hours > 12) return null;
+                    hours = (hours % 12) + (match[5][0].toLowerCase() === 'p' ? 12 : 0);
+                }
+                if (hours > 23 || minutes > 59 || Number(match[3] || 0) > 59) return null;
+                const seconds = match[3] ? `:${match[3]}${match[4] || ''}` : '';
+                return `${pad(hours)}:${pad(minutes)}${seconds}`;
+            };
+
+            const value = text.trim();
+            let converted;
+            let expected;
+            switch (type) {
+                case 'number':
+                case 'range':
+                    expected = 'a number';
+                    converted = value.replace(/^\+/, '');
+                    if (!/^-?(\d+(\.\d+)?|\.\d+)(e[-+]?\d+)?$/i.test(converted)) converted = null;
+                    break;
+                case 'date':
+                    expected = 'YYYY-MM-DD';
+                    converted = toDate(value);
+                    break;
+                case 'time':
+                    expected = 'HH:MM or HH:MM:SS';
+                    converted = toTime(value);
+                    break;
+                case 'datetime-local': {
+                    expected = 'YYYY-MM-DDTHH:MM';
+                    const [date, time, ...rest] = value.split(/[T ]/i);
+                    const d = toDate(date || '');
+                    const t = rest.length === 0 && toTime(time || '');
+                    converted = d && t ? `${d}T${t}` : null;
+                    break;
+                }
+                case 'month': {
+                    expected = 'YYYY-MM';
+                    const match = value.match(/^(\d{4})[-/.](\d{1,2})$/);
+                    const month = match && Number(match[2]);
+                    converted = month >= 1 && month <= 12 ? `${match[1]}-${pad(month)}` : null;
+                    break;
+                }
+                case 'week': {
+                    expected = 'YYYY-Www';
+                    const match = value.match(/^(\d{4})-?W(\d{1,2})$/i);
+                    const week = match && Number(match[2]);
+                    converted = week >= 1 && week <= 53 ? `${match[1]}-W${pad(week)}` : null;
+                    break;
+                }
+                default:
+                    return null;
+            }
+
+            // An empty value clears the input
+            if (value === '') return '';
+            if (converted === null) {
+                throw {
+                    msg: `Cannot type "${secret ? '********' : text}" into a ${type} input: expected ${expected}`,
+                    code: 'INVALID_PARAMS'
+                };
+            }
+
+            // A range input snaps to its min, max and step rather than
+            // refusing, so try the value on a detached copy first
+            if (type === 'range') {
+                const probe = el.cloneNode(false);
+                probe.value = converted;
+                if (Number(probe.value) !== Number(converted)) {
+                    throw {
+                        msg: `Cannot type "${secret ? '********' : converted}" into a range input: expected ${el.min || 0} to ${el.max || 100} in steps of ${el.step || 1}`,
+                        code: 'INVALID_PARAMS'
+                    };
+                }
+            }
+            return converted;
+        },
+
         getDataAttributes: (el) => {
             const attrs = {};
             for (const name of el.getAttributeNames()) {
@@ -492,6 +595,10 @@
                 'aria-hidden',
                 'aria-disabled',
                 'aria-describedby',
+                'aria-haspopup',
+                'aria-expanded',
+                'aria-autocomplete',
+                'role',
                 'for',
                 'title',
                 'tabindex'
@@ -507,6 +614,29 @@
             if (el.className) attrs.class = el.className;
 
             return attrs;
+        },
+
+        // Lay a table's cells out on a rectangular grid, repeating the text of
+        // a cell spanning several rows or columns in each slot it covers.
+        tableGrid: (table) => {
+            const rows = Array.from(table.rows);
+            const grid = rows.map(() => []);
+            rows.forEach((row, r) => {
+                let c = 0;
+                for (const cell of row.cells) {
+                    while (grid[r][c] !== undefined) c++;
+                    const text = cell.innerText.trim();
+                    // rowspan="0" extends the cell to the last row.
+                    const rowSpan = cell.rowSpan === 0 ? rows.length - r : Math.max(1, cell.rowSpan);
+                    const colSpan = Math.max(1, cell.colSpan);
+                    for (let dr = 0; dr < rowSpan && r + dr < rows.length; dr++) {
+                        for (let dc = 0; dc < colSpan; dc++) grid[r + dr][c + dc] = text;
+                    }
+                    c += colSpan;
+                }
+            });
+            const width = Math.max(0, ...grid.map((row) => row.length));
+            return grid.map((row) => Array.from({ length: width }, (_, c) => row[c] ?? ''));
         }
     };
 
@@ -719,6 +849,7 @@
 
             const elements = [];
             const iframes = [];
+            const iframeCandidatesBefore = [];
             const seenIds = new Set();
             const changes = [];
 
@@ -727,11 +858,15 @@
             // We will prune STATE.elementMap at the end.
 
             // 2. Discover main document elements (including Shadow DOM)
+            const candidateElements = [];
             // Filter function for referenceable elements
             const elementFilter = (node) => {
-                // Collect iframes for separate processing
+                // Collect iframes for separate processing, remembering how many
+                // candidates precede each so its contents can be placed in
+                // document order
                 if (node.tagName === 'IFRAME') {
                     iframes.push(node);
+                    iframeCandidatesBefore.push(candidateElements.length);
                     return false;
                 }
                 // Check if interactive-ish
@@ -739,7 +874,7 @@
             };
 
             // Use ShadowUtils to collect elements including those in shadow DOM
-            const candidateElements = ShadowUtils.collectElements(contextNode, elementFilter, [], maxElements * 2);
+            ShadowUtils.collectElements(contextNode, elementFilter, candidateElements, maxElements * 2);
 
             // Pre-calculate text rects if near is requested (with shadow DOM support)
             let nearRects = null;
@@ -749,8 +884,10 @@
                 // Spec implies filtering, so if nothing found, nothing returned usually.
             }
 
-            // Process candidate elements
-            for (const el of candidateElements) {
+            // Process candidate elements, noting how many were kept before each
+            const keptBefore = [];
+            for (const [index, el] of candidateElements.entries()) {
+                keptBefore[index] = elements.length;
                 if (elements.length >= maxElements) break;
 
                 // Skip visibility check for leaf divs with direct text (they may have minimal dimensions)
@@ -803,17 +940,21 @@
                 elements.push(serialized);
             }
 
-            // 3. Process iframes
+            // 3. Process iframes. Each iframe and its contents are inserted where
+            // the iframe sits, so `elements` stays in document order.
             const iframeInfo = [];
+            const insertions = [];
+            let total = elements.length;
             if (includeIframes) {
-                for (const iframe of iframes) {
-                    if (elements.length >= maxElements) break;
+                for (const [index, iframe] of iframes.entries()) {
+                    if (total >= maxElements) break;
+                    const group = [];
 
                     const iframeData = Scanner.processIframe(
                         iframe,
                         includeHidden,
                         params.viewport_only,
-                        maxElements - elements.length,
+                        maxElements - total,
                         monitorChanges,
                         changes
                     );
@@ -829,7 +970,7 @@
                         src: iframe.src || '',
                         origin: iframeData.origin
                     };
-                    elements.push(iframeElement);
+                    group.push(iframeElement);
                     iframeInfo.push(iframeElement.iframe);
 
                     seenIds.add(iframeId);
@@ -837,18 +978,25 @@
                     // Add elements from accessible iframes
                     if (iframeData.accessible && iframeData.elements) {
                         for (const elData of iframeData.elements) {
-                            if (elements.length >= maxElements) break;
+                            if (total + group.length >= maxElements) break;
                             elData.iframe_context = {
                                 iframe_id: iframeId,
                                 src: iframe.src || ''
                             };
-                            elements.push(elData);
+                            group.push(elData);
                             // IDs for iframe elements are already handled in processIframe
                             seenIds.add(elData.id);
                         }
                     }
+
+                    insertions.push({ at: keptBefore[iframeCandidatesBefore[index]] ?? elements.length, group });
+                    total += group.length;
                 }
             }
+            // Last first, so earlier insertion points are not shifted
+            for (const { at, group } of insertions.reverse()) {
+                elements.splice(at, 0, ...group);
+            }
 
             // --- Cleanup Disappeared elements ---
             for (const id of STATE.elementMap.keys()) {
@@ -1134,7 +1282,9 @@
                     height: Math.round(rect.height)
                 },
                 attributes: Utils.getElementAttributes(el, dataAttrs),
-                state: Utils.getElementState(el)
+                state: Utils.getElementState(el),
+                tab_index: el.tabIndex,
+                focusable: Utils.isFocusable(el)
             };
         }
     };
@@ -1258,6 +1408,13 @@
                 throw { msg: `Element ${params.id} is disabled`, code: 'ELEMENT_DISABLED' };
             }
 
+            // Number, date and time inputs take their whole value at once, in
+            // the format they expect. With --append that value is what is
+            // already there followed by the new text.
+            const typedText = params.clear === false ? (el.value || '') + (params.text || '') : params.text || '';
+            const typedValue = Utils.typedInputValue(el, typedText, params.secret);
+            if (typedValue !== null) params = { ...params, text: typedValue, delay: 0, clear: true };
+
             if (params.scroll_into_view !== false) el.scrollIntoView({ block: 'center', behavior: 'instant' });
 
             el.focus();
@@ -1358,10 +1515,11 @@
                 }
             }
 
+            // A secret stays out of the response, which callers may log
             return Protocol.success({
                 success: true,
                 message: params.submit ? 'typed_and_submitted' : 'typed',
-                value: el.isContentEditable ? el.innerText : el.value
+                value: params.secret ? undefined : el.isContentEditable ? el.innerText : el.value
             });
         },
 
@@ -1463,8 +1621,9 @@
             let target = window;
             let isWindow = true;
 
-            if (params.element) {
-                target = Executor.getElement(params.element);
+            const elementId = params.element ?? params.id;
+            if (elementId != null) {
+                target = Executor.getElement(elementId);
                 isWindow = false;
             } else if (params.container) {
                 target = ShadowUtils.querySelectorWithShadow(document.body, params.container);
@@ -1472,12 +1631,12 @@
                 isWindow = false;
             }
 
-            if (params.direction) {
+            if (params.direction && params.direction !== 'into_view') {
                 const amount = params.amount || 100;
                 const DIRECTION_MAP = { up: [0, -1], down: [0, 1], left: [-1, 0], right: [1, 0] };
                 const [xDir, yDir] = DIRECTION_MAP[params.direction] || [0, 0];
                 target.scrollBy({ left: xDir * amount, top: yDir * amount, behavior });
-            } else if (params.element && !isWindow) {
+            } else if (elementId != null && !isWindow) {
                 target.scrollIntoView({ behavior, block: 'center' });
             }
 
@@ -1536,6 +1695,41 @@
             });
         },
 
+        highlight: (params) => {
+            if (params.clear) {
+                const overlays = document.querySelectorAll('[data-oryn-highlight]');
+                overlays.forEach((overlay) => overlay.remove());
+                return Protocol.success({
+                    success: true,
+                    message: `cleared ${overlays.length} highlight(s)`
+                });
+            }
+
+            const el = Executor.getElementFromParams(params);
+            const rect = el.getBoundingClientRect();
+            const overlay = document.createElement('div');
+            overlay.setAttribute('data-oryn-highlight', '');
+            Object.assign(overlay.style, {
+                position: 'absolute',
+                left: `${rect.left + window.scrollX}px`,
+                top: `${rect.top + window.scrollY}px`,
+                width: `${rect.width}px`,
+                height: `${rect.height}px`,
+                boxSizing: 'border-box',
+                border: `3px solid ${params.color || 'red'}`,
+                pointerEvents: 'none',
+                zIndex: '2147483647'
+            });
+            document.documentElement.appendChild(overlay);
+
+            if (params.persist) {
+                return Protocol.success({ success: true, message: 'highlighted until cleared' });
+            }
+            const duration = params.duration_ms ?? 2000;
+            setTimeout(() => overlay.remove(), duration);
+            return Protocol.success({ success: true, message: `highlighted for ${duration}ms` });
+        },
+
         submit: (params) => {
             let el;
             if (params.id != null || params.selector) {
@@ -1577,6 +1771,24 @@
             const expression = params.expression;
             const countTarget = params.count;
 
+            // 'stable' and 'scroll_settled' need the time of the last change across polls.
+            const quietMs = params.quiet_ms ?? 300;
+            let lastScrollPos = null;
+            let lastScrollChange = performance.now();
+            let lastMutation = performance.now();
+            let observer = null;
+            if (params.condition === 'stable') {
+                observer = new MutationObserver(() => {
+                    lastMutation = performance.now();
+                });
+                observer.observe(document.documentElement, {
+                    subtree: true,
+                    childList: true,
+                    attributes: true,
+                    characterData: true
+                });
+            }
+
             // Find element by text content (searches visible text in the document, including shadow DOM)
             const findByText = (text) => {
                 return ShadowUtils.findTextNodeWithShadow(document.body, text);
@@ -1636,6 +1848,25 @@
                     case 'idle': {
                         return document.readyState === 'complete';
                     }
+                    case 'stable': {
+                        if (document.readyState !== 'complete') return false;
+                        const animating =
+                            typeof document.getAnimations === 'function' &&
+                            document.getAnimations().some((a) => a.playState === 'running');
+                        return !animating && performance.now() - lastMutation >= quietMs;
+                    }
+                    case 'scroll_settled': {
+                        const el = params.id != null ? STATE.elementMap.get(params.id) : null;
+                        const pos = el
+                            ? `${el.scrollLeft},${el.scrollTop}`
+                            : `${window.scrollX},${window.scrollY}`;
+                        const now = performance.now();
+                        if (pos !== lastScrollPos) {
+                            lastScrollPos = pos;
+                            lastScrollChange = now;
+                        }
+                        return now - lastScrollChange >= quietMs;
+                    }
                     case 'custom': {
                         if (!expression) {
                             throw { msg: 'Missing expression for custom wait', code: 'INVALID_PARAMS' };
@@ -1648,14 +1879,28 @@
                         if (Number.isNaN(count)) {
                             throw { msg: 'Invalid count for wait', code: 'INVALID_PARAMS' };
                         }
-                        return ShadowUtils.querySelectorAllWithShadow(document.body, selector).length >= count;
+                        const matched = ShadowUtils.querySelectorAllWithShadow(document.body, selector).length;
+                        switch (params.compare ?? '>=') {
+                            case '>=': return matched >= count;
+                            case '>': return matched > count;
+                            case '<=': return matched <= count;
+                            case '<': return matched < count;
+                            case '==': return matched === count;
+                            default:
+                                throw { msg: `Invalid count comparison: ${params.compare}`, code: 'INVALID_PARAMS' };
+                        }
                     }
                     default:
                         return false;
                 }
             };
 
+            // The condition is checked before the clock, so a zero timeout is a one-shot check.
             const poll = () => {
+                if (checkCondition()) {
+                    return Promise.resolve(true);
+                }
+
                 const elapsed = performance.now() - start;
                 if (elapsed >= timeout) {
                     // Use NAVIGATION_ERROR for navigation condition timeout
@@ -1668,10 +1913,6 @@
                     return Promise.reject({ msg: 'Timeout waiting for condition', code: 'TIMEOUT' });
                 }
 
-                if (checkCondition()) {
-                    return Promise.resolve(true);
-                }
-
                 return new Promise((r) => setTimeout(r, pollInterval)).then(poll);
             };
 
@@ -1689,6 +1930,8 @@
                 return Protocol.success(result);
             } catch (e) {
                 return Protocol.error(e.msg, e.code);
+            } finally {
+                if (observer) observer.disconnect();
             }
         },
 
@@ -1903,12 +2146,20 @@
         },
 
         get_html: (params) => {
-            const el = params.selector
-                ? ShadowUtils.querySelectorWithShadow(document.body, params.selector)
-                : document.documentElement;
-            if (!el) throw { msg: 'Element not found', code: 'ELEMENT_NOT_FOUND' };
+            if (!params.selector) {
+                const root = document.documentElement;
+                const html = params.outer !== false ? root.outerHTML : root.innerHTML;
+                return Protocol.success({ html: html || '' });
+            }
+            const el = ShadowUtils.querySelectorWithShadow(document.body, params.selector);
+            if (!el) {
+                throw {
+                    msg: `No element matches selector "${params.selector}"`,
+                    code: 'ELEMENT_NOT_FOUND'
+                };
+            }
             const html = params.outer !== false ? el.outerHTML : el.innerHTML;
-            return Protocol.success({ html: html || '' });
+            return Protocol.success({ html: html || '', selector: params.selector });
         },
 
         get_value: (params) => {
@@ -1963,6 +2214,7 @@
                     results = ShadowUtils.querySelectorAllWithShadow(container, 'a[href]').map((a) => ({
                         text: a.innerText.trim(),
                         url: a.href,
+                        rel: a.getAttribute('rel'),
                         id: STATE.inverseMap.get(a)
                     }));
                     break;
@@ -1975,10 +2227,20 @@
                     break;
                 case 'tables':
                     results = ShadowUtils.querySelectorAllWithShadow(container, 'table').map((table) => {
-                        const rows = Array.from(table.rows).map((row) =>
-                            Array.from(row.cells).map((cell) => cell.innerText.trim())
-                        );
-                        return { rows, id: STATE.inverseMap.get(table) };
+                        const grid = Utils.tableGrid(table);
+                        // Header rows are the thead, or else a first row made only of th cells.
+                        const rows = Array.from(table.rows);
+                        let headerRows = rows.findIndex((row) => row.parentElement.tagName !== 'THEAD');
+                        if (headerRows === -1) headerRows = rows.length;
+                        const first = rows[0] ? Array.from(rows[0].cells) : [];
+                        if (headerRows === 0 && first.length && first.every((cell) => cell.tagName === 'TH')) {
+                            headerRows = 1;
+                        }
+                        return {
+                            headers: headerRows ? grid[headerRows - 1] : null,
+                            rows: grid.slice(headerRows),
+                            id: STATE.inverseMap.get(table)
+                        };
                     });
                     break;
                 case 'meta':
@@ -2302,6 +2564,7 @@
                     'scroll',
                     'focus',
                     'hover',
+                    'highlight',
                     'submit',
                     'wait_for',
                     'get_text',
@@ -2360,6 +2623,9 @@
                 case 'hover':
                     result = Executor.hover(message);
                     break;
+                case 'highlight':
+                    result = Executor.highlight(message);
+                    break;
                 case 'submit':
                     result = Executor.submit(message);
                     break;
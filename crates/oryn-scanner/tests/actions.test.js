@@ -46,7 +46,7 @@ describe('Advanced Actions and Extraction', () => {
                     <body>
                         <div id="links">
                             <a href="https://google.com">Google</a>
-                            <a href="https://github.com">GitHub</a>
+                            <a href="https://github.com" rel="nofollow">GitHub</a>
                         </div>
                         <div id="images">
                             <img src="test.png" alt="Test Image">
@@ -69,6 +69,8 @@ describe('Advanced Actions and Extraction', () => {
             expect(result.results[0].text).toBe('Google');
             expect(result.results[0].url).toBe('https://google.com/');
             expect(result.results[0].id).toBeGreaterThan(0);
+            expect(result.results[0].rel).toBeNull();
+            expect(result.results[1].rel).toBe('nofollow');
         });
 
         test('extracts images', async () => {
@@ -87,6 +89,40 @@ describe('Advanced Actions and Extraction', () => {
             expect(result.results[0].rows[0][0]).toBe('Row 1 Col 1');
         });
 
+        test('expands colspan and rowspan into a rectangular grid', async () => {
+            await page.setContent(`
+                <table>
+                    <tr><th colspan="2">Name</th><th>Age</th></tr>
+                    <tr><td rowspan="2">Ada</td><td>Lovelace</td><td>36</td></tr>
+                    <tr><td>King</td><td></td></tr>
+                </table>
+            `);
+            await page.evaluate(SCANNER_JS);
+            const result = await runCommand(page, { cmd: 'extract', source: 'tables' });
+            expect(result.status).toBe('ok');
+            expect(result.results[0].headers).toEqual(['Name', 'Name', 'Age']);
+            expect(result.results[0].rows).toEqual([
+                ['Ada', 'Lovelace', '36'],
+                ['Ada', 'King', '']
+            ]);
+        });
+
+        test('reports thead rows as headers and headerless tables without them', async () => {
+            await page.setContent(`
+                <table id="orders">
+                    <thead><tr><td>Order</td><td>Total</td></tr></thead>
+                    <tbody><tr><td>#1</td><td>$5</td></tr></tbody>
+                </table>
+                <table id="plain"><tr><td>a</td><td>b</td></tr></table>
+            `);
+            await page.evaluate(SCANNER_JS);
+            const result = await runCommand(page, { cmd: 'extract', source: 'tables' });
+            expect(result.results[0].headers).toEqual(['Order', 'Total']);
+            expect(result.results[0].rows).toEqual([['#1', '$5']]);
+            expect(result.results[1].headers).toBeNull();
+            expect(result.results[1].rows).toEqual([['a', 'b']]);
+        });
+
         test('extracts meta', async () => {
             const result = await runCommand(page, { cmd: 'extract', source: 'meta' });
             expect(result.status).toBe('ok');
@@ -162,4 +198,46 @@ describe('Advanced Actions and Extraction', () => {
             expect(result.message).toBe('accepted_cookies');
         });
     });
+
+    describe('Highlight', () => {
+        const overlays = () =>
+            page.$$eval('[data-oryn-highlight]', (nodes) => nodes.map((node) => node.style.borderColor));
+
+        beforeEach(async () => {
+            await page.setContent(`
+                <html>
+                    <body>
+                        <button id="save">Save</button>
+                    </body>
+                </html>
+            `);
+            await page.evaluate(SCANNER_JS);
+            await runCommand(page, { cmd: 'scan' });
+        });
+
+        test('draws an outline in the given color for the duration', async () => {
+            const result = await runCommand(page, {
+                cmd: 'highlight',
+                selector: '#save',
+                color: 'blue',
+                duration_ms: 100
+            });
+            expect(result.status).toBe('ok');
+            expect(result.message).toBe('highlighted for 100ms');
+            expect(await overlays()).toEqual(['blue']);
+
+            await new Promise((resolve) => setTimeout(resolve, 300));
+            expect(await overlays()).toEqual([]);
+        });
+
+        test('keeps a persistent outline until cleared', async () => {
+            await runCommand(page, { cmd: 'highlight', selector: '#save', persist: true, duration_ms: 50 });
+            await new Promise((resolve) => setTimeout(resolve, 200));
+            expect(await overlays()).toEqual(['red']);
+
+            const result = await runCommand(page, { cmd: 'highlight', clear: true });
+            expect(result.message).toBe('cleared 1 highlight(s)');
+            expect(await overlays()).toEqual([]);
+        });
+    });
 });
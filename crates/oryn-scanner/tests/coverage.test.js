@@ -441,6 +441,23 @@ describe('Scanner Coverage Analysis', () => {
         expect(iframeBtn.iframe_context).toBeDefined();
     });
 
+    test('iframe contents keep document order', async () => {
+        await page.waitForSelector('#same-origin-iframe');
+        await new Promise((r) => setTimeout(r, 100));
+
+        const result = await page.evaluate(async () => {
+            const res = await window.Oryn.process({ cmd: 'scan', include_iframes: true });
+            return res;
+        });
+
+        const position = (id) => result.elements.findIndex((el) => el.attributes && el.attributes.id === id);
+        const order = ['nav-btn', 'same-origin-iframe', 'iframe-btn', 'iframe-link', 'single-form-input'].map(
+            position
+        );
+        expect(order.every((index) => index >= 0)).toBe(true);
+        expect([...order].sort((a, b) => a - b)).toEqual(order);
+    });
+
     test('max_elements limit', async () => {
         const result = await page.evaluate(async () => {
             const res = await window.Oryn.process({ cmd: 'scan', max_elements: 5 });
@@ -1,10 +1,9 @@
 use clap::{Parser, Subcommand};
-use oryn_e::backend::EmbeddedBackend;
-use oryn_engine::backend::Backend;
-use oryn_engine::cli::{self, FileErrorMode, FileOptions, OutputHandlers, ReplOptions};
+use oryn_engine::cli::{self, FileErrorMode, FileOptions, OutputHandlers, OutputMode, ReplOptions};
+use oryn_engine::config::loader::ConfigLoader;
 use oryn_engine::executor::CommandExecutor;
-use oryn_h::backend::HeadlessBackend;
-use oryn_r::backend::RemoteBackend;
+use oryn_engine::registry::{BackendOptions, BackendRegistry};
+use std::path::PathBuf;
 
 #[derive(Parser)]
 #[command(name = "oryn", version, about = "Oryn Unified CLI")]
@@ -15,6 +14,26 @@ struct Args {
     /// Scripts to execute (non-interactive mode)
     #[arg(long)]
     file: Option<String>,
+
+    /// YAML configuration file (timeouts, sensitive fields, scoring weights)
+    #[arg(long)]
+    config: Option<PathBuf>,
+
+    /// Print the action each command would send without sending it
+    /// (navigation and scans still run)
+    #[arg(long)]
+    dry_run: bool,
+
+    /// Reject commands that run raw script (`eval`, `set`, `wait until`
+    /// expressions and intent `execute` steps)
+    #[arg(long)]
+    safe: bool,
+
+    /// Write each result and error to stdout as one JSON object per line,
+    /// tagged `"type": "result"` or `"type": "error"`. Without it, errors go
+    /// to stderr.
+    #[arg(long)]
+    json: bool,
 }
 
 #[derive(Subcommand)]
@@ -36,7 +55,65 @@ enum Mode {
         /// WebSocket port
         #[arg(long, default_value_t = 9001)]
         port: u16,
+        /// Seconds a command waits for the extension to connect or reconnect
+        #[arg(long, default_value_t = 60)]
+        connect_timeout: u64,
     },
+    /// Any other registered backend: `<name> [--key value | --flag ...]`
+    #[command(external_subcommand)]
+    Other(Vec<String>),
+}
+
+impl Mode {
+    /// The registry name and options this mode selects.
+    fn into_options(self) -> (String, BackendOptions) {
+        match self {
+            Mode::Headless { visible } => (
+                "headless".into(),
+                BackendOptions::new().with("visible", visible.to_string()),
+            ),
+            Mode::Embedded { driver_url } => {
+                let mut options = BackendOptions::new();
+                if let Some(url) = driver_url {
+                    options.set("driver_url", url);
+                }
+                ("embedded".into(), options)
+            }
+            Mode::Remote {
+                port,
+                connect_timeout,
+            } => (
+                "remote".into(),
+                BackendOptions::new()
+                    .with("port", port.to_string())
+                    .with("connect_timeout", connect_timeout.to_string()),
+            ),
+            Mode::Other(args) => {
+                let mut args = args.into_iter().peekable();
+                let name = args.next().unwrap_or_default();
+                let mut options = BackendOptions::new();
+                while let Some(arg) = args.next() {
+                    let Some(key) = arg.strip_prefix("--") else {
+                        continue;
+                    };
+                    let key = key.replace('-', "_");
+                    match args.next_if(|next| !next.starts_with("--")) {
+                        Some(value) => options.set(key, value),
+                        None => options.set(key, "true"),
+                    }
+                }
+                (name, options)
+            }
+        }
+    }
+}
+
+fn backend_registry() -> BackendRegistry {
+    let mut registry = BackendRegistry::new();
+    oryn_h::backend::register(&mut registry);
+    oryn_e::backend::register(&mut registry);
+    oryn_r::backend::register(&mut registry);
+    registry
 }
 
 #[tokio::main]
@@ -48,24 +125,36 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     let args = Args::parse();
 
-    let mut backend: Box<dyn Backend> = match args.mode {
-        Mode::Headless { visible } => Box::new(HeadlessBackend::new_with_visibility(visible)),
-        Mode::Embedded { driver_url } => match driver_url {
-            Some(url) => Box::new(EmbeddedBackend::with_url(url)),
-            None => Box::new(EmbeddedBackend::new()),
-        },
-        Mode::Remote { port } => Box::new(RemoteBackend::new(port)),
+    // Read before launching, so a bad file fails fast
+    let config = match &args.config {
+        Some(path) => Some(ConfigLoader::load_from(path).await?),
+        None => None,
     };
 
+    let (mode, options) = args.mode.into_options();
+    let mut backend = backend_registry().create(&mode, &options)?;
+
     if let Err(e) = backend.launch().await {
         eprintln!("Failed to launch backend: {}", e);
         return Err(e.into());
     }
 
-    let mut executor = CommandExecutor::new();
+    let mut executor = match &config {
+        Some(config) => CommandExecutor::with_config(config),
+        None => CommandExecutor::new(),
+    };
+    executor.set_dry_run(args.dry_run);
+    if args.safe {
+        executor.set_safe_mode(true);
+    }
     let output = OutputHandlers {
         out: |msg| println!("{}", msg),
-        err: |msg| println!("{}", msg),
+        err: |msg| eprintln!("{}", msg),
+    };
+    let output_mode = if args.json {
+        OutputMode::Json
+    } else {
+        OutputMode::Human
     };
     let repl_options = ReplOptions {
         banner_lines: &[
@@ -79,11 +168,13 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         ctrl_c_message: None,
     };
 
+    let mut exit_code = 0;
     if let Some(file_path) = args.file {
-        if let Err(e) = cli::run_file(
+        match cli::run_file(
             &mut *backend,
             &mut executor,
             output,
+            output_mode,
             &file_path,
             FileOptions {
                 stop_on_error: true,
@@ -92,14 +183,28 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         )
         .await
         {
-            eprintln!("Error executing file {}: {}", file_path, e);
-            return Err(e);
+            Ok(code) => exit_code = code,
+            Err(e) => {
+                eprintln!("Error executing file {}: {}", file_path, e);
+                return Err(e);
+            }
         }
-    } else if let Err(e) = cli::run_repl(&mut *backend, &mut executor, output, repl_options).await {
+    } else if let Err(e) = cli::run_repl(
+        &mut *backend,
+        &mut executor,
+        output,
+        output_mode,
+        repl_options,
+    )
+    .await
+    {
         eprintln!("Error during session: {}", e);
         return Err(e);
     }
 
     backend.close().await?;
+    if exit_code != 0 {
+        std::process::exit(exit_code);
+    }
     Ok(())
 }
@@ -115,7 +115,7 @@ async function formatResult(response) {
 
         // Object result (extraction values, etc.)
         if (typeof result === 'object') {
-            return `Value: ${JSON.stringify(result)}`;
+            return `Value:\n${JSON.stringify(result, null, 2)}`;
         }
     }
 
@@ -159,6 +159,25 @@ function connect(tabId, url) {
                 handleBack(tabId);
                 return;
             }
+            if (command.action === "forward") {
+                handleForward(tabId);
+                return;
+            }
+            if (command.action === "refresh") {
+                handleRefresh(tabId, command.hard);
+                return;
+            }
+            if (command.action === "intercept") {
+                handleIntercept(tabId, command);
+                return;
+            }
+            if (command.action === "requests") {
+                const log = requestLogs.get(tabId) || [];
+                const requests = command.last === undefined ? log : log.slice(log.length - command.last);
+                const pending = (pendingRequests.get(tabId) || new Set()).size;
+                sendResponseToSocket(tabId, { status: "ok", requests, pending });
+                return;
+            }
 
             // Forward to specific tab
             sendCommandToTab(tabId, command);
@@ -215,21 +234,186 @@ function handleNavigate(tabId, url) {
 
 function handleBack(tabId) {
     remoteLog("Going back", tabId);
-    chrome.tabs.goBack(tabId, () => {
+    chrome.tabs.goBack(tabId, reportWhenLoaded(tabId, "BACK_ERROR", "Back complete"));
+}
+
+function handleForward(tabId) {
+    remoteLog("Going forward", tabId);
+    chrome.tabs.goForward(tabId, reportWhenLoaded(tabId, "FORWARD_ERROR", "Forward complete"));
+}
+
+function handleRefresh(tabId, hard) {
+    remoteLog("Reloading", tabId);
+    chrome.tabs.reload(tabId, { bypassCache: !!hard }, reportWhenLoaded(tabId, "REFRESH_ERROR", "Reload complete"));
+}
+
+// Callback for a history navigation: report the error, or wait for the tab to
+// finish loading and report the URL it landed on.
+function reportWhenLoaded(tabId, errorCode, doneMessage) {
+    return () => {
         if (chrome.runtime.lastError) {
-            sendResponseToSocket(tabId, { status: "error", code: "BACK_ERROR", message: chrome.runtime.lastError.message });
+            sendResponseToSocket(tabId, { status: "error", code: errorCode, message: chrome.runtime.lastError.message });
             return;
         }
-        chrome.tabs.onUpdated.addListener(function listener(updatedTabId, changeInfo) {
+        chrome.tabs.onUpdated.addListener(function listener(updatedTabId, changeInfo, tab) {
             if (updatedTabId === tabId && changeInfo.status === "complete") {
                 chrome.tabs.onUpdated.removeListener(listener);
-                remoteLog("Back complete", tabId);
-                sendResponseToSocket(tabId, { status: "ok" });
+                remoteLog(doneMessage, tabId);
+                sendResponseToSocket(tabId, { status: "ok", url: tab.url });
             }
         });
+    };
+}
+
+// --- Network Interception ---
+// Requests are recorded with webRequest; rules are applied by pausing
+// matching requests through the debugger's Fetch domain, as oryn-h does.
+
+const interceptRules = new Map(); // TabID -> [{ url_pattern, action }], latest wins
+const requestLogs = new Map(); // TabID -> [{ method, url, status, intercepted }]
+const pendingRequests = new Map(); // TabID -> Set of webRequest ids not yet finished
+const MAX_LOGGED_REQUESTS = 1000; // Mirrors oryn-h's RequestLog; older entries are dropped
+
+// Mirrors InterceptRule::matches: wildcards span the whole URL, and a plain
+// pattern matches any URL containing it.
+function hasWildcards(pattern) {
+    return pattern.includes('*') || pattern.includes('?');
+}
+
+function fetchPattern(pattern) {
+    return hasWildcards(pattern) ? pattern : `*${pattern}*`;
+}
+
+function ruleMatches(rule, url) {
+    const pattern = rule.url_pattern;
+    if (!hasWildcards(pattern)) return url.includes(pattern);
+    const escaped = pattern.replace(/[.+^${}()|[\]\\]/g, '\\$&');
+    return new RegExp('^' + escaped.replace(/\*/g, '.*').replace(/\?/g, '.') + '$').test(url);
+}
+
+function guessContentType(body) {
+    try {
+        JSON.parse(body);
+        return 'application/json';
+    } catch (e) {
+        return 'text/plain';
+    }
+}
+
+async function handleIntercept(tabId, command) {
+    let rules = interceptRules.get(tabId) || [];
+    if (command.op === "add") {
+        rules = rules.filter(r => r.url_pattern !== command.rule.url_pattern);
+        rules.push(command.rule);
+    } else {
+        rules = command.pattern ? rules.filter(r => r.url_pattern !== command.pattern) : [];
+    }
+    interceptRules.set(tabId, rules);
+
+    try {
+        await syncFetchDomain(tabId, rules);
+        sendResponseToSocket(tabId, { status: "ok", rules: rules.length });
+    } catch (e) {
+        sendResponseToSocket(tabId, { status: "error", code: "INTERCEPT_ERROR", message: e.message || String(e) });
+    }
+}
+
+const debuggerTabs = new Set();
+
+async function syncFetchDomain(tabId, rules) {
+    const target = { tabId };
+    if (rules.length === 0) {
+        if (debuggerTabs.has(tabId)) {
+            debuggerTabs.delete(tabId);
+            await chrome.debugger.detach(target);
+        }
+        return;
+    }
+    if (!debuggerTabs.has(tabId)) {
+        await chrome.debugger.attach(target, "1.3");
+        debuggerTabs.add(tabId);
+    }
+    await chrome.debugger.sendCommand(target, "Fetch.enable", {
+        patterns: rules.map(r => ({ urlPattern: fetchPattern(r.url_pattern) }))
     });
 }
 
+function markIntercepted(tabId, request, intercepted, status) {
+    const log = requestLogs.get(tabId) || [];
+    for (let i = log.length - 1; i >= 0; i--) {
+        const entry = log[i];
+        if (entry.url === request.url && entry.method === request.method && !entry.intercepted) {
+            entry.intercepted = intercepted;
+            if (status) entry.status = status;
+            return;
+        }
+    }
+}
+
+chrome.debugger.onEvent.addListener((source, method, params) => {
+    if (method !== "Fetch.requestPaused" || source.tabId === undefined) return;
+    const tabId = source.tabId;
+    const rules = interceptRules.get(tabId) || [];
+    const rule = [...rules].reverse().find(r => ruleMatches(r, params.request.url));
+    const action = rule ? rule.action : { type: "continue" };
+    const target = { tabId };
+    const requestId = params.requestId;
+
+    if (action.type === "block") {
+        markIntercepted(tabId, params.request, "blocked");
+        chrome.debugger.sendCommand(target, "Fetch.failRequest", { requestId, errorReason: "BlockedByClient" });
+    } else if (action.type === "mock") {
+        markIntercepted(tabId, params.request, "mocked", action.status);
+        const bytes = new TextEncoder().encode(action.body || "");
+        let binary = "";
+        bytes.forEach(b => { binary += String.fromCharCode(b); });
+        chrome.debugger.sendCommand(target, "Fetch.fulfillRequest", {
+            requestId,
+            responseCode: action.status,
+            responseHeaders: [{ name: "Content-Type", value: action.content_type || guessContentType(action.body || "") }],
+            body: btoa(binary)
+        });
+    } else {
+        chrome.debugger.sendCommand(target, "Fetch.continueRequest", { requestId });
+    }
+});
+
+chrome.debugger.onDetach.addListener((source) => {
+    if (source.tabId !== undefined) debuggerTabs.delete(source.tabId);
+});
+
+chrome.webRequest.onBeforeRequest.addListener((details) => {
+    if (details.tabId < 0) return;
+    const log = requestLogs.get(details.tabId) || [];
+    log.push({ method: details.method, url: details.url });
+    if (log.length > MAX_LOGGED_REQUESTS) log.shift();
+    requestLogs.set(details.tabId, log);
+    const pending = pendingRequests.get(details.tabId) || new Set();
+    pending.add(details.requestId);
+    pendingRequests.set(details.tabId, pending);
+}, { urls: ["<all_urls>"] });
+
+function requestSettled(details) {
+    const pending = pendingRequests.get(details.tabId);
+    if (pending) pending.delete(details.requestId);
+}
+
+function recordStatus(details) {
+    const log = requestLogs.get(details.tabId) || [];
+    for (let i = log.length - 1; i >= 0; i--) {
+        if (log[i].url === details.url && log[i].status === undefined) {
+            if (details.statusCode) log[i].status = details.statusCode;
+            return;
+        }
+    }
+}
+
+chrome.webRequest.onCompleted.addListener((details) => {
+    recordStatus(details);
+    requestSettled(details);
+}, { urls: ["<all_urls>"] });
+chrome.webRequest.onErrorOccurred.addListener(requestSettled, { urls: ["<all_urls>"] });
+
 function sendResponseToSocket(tabId, response) {
     const conn = getConnection(tabId);
     if (conn.socket && conn.socket.readyState === WebSocket.OPEN) {
@@ -321,6 +505,10 @@ chrome.tabs.onRemoved.addListener((tabId) => {
         disconnect(tabId);
         connections.delete(tabId);
     }
+    interceptRules.delete(tabId);
+    requestLogs.delete(tabId);
+    pendingRequests.delete(tabId);
+    debuggerTabs.delete(tabId);
 });
 
 // 3. Tab Switching (Update Badge)
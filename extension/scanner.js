@@ -183,7 +183,13 @@
                 const inputRoles = [
                     { role: 'search', types: ['search'], names: ['search', 'q', 'query'], keywords: ['search'] },
                     { role: 'email', types: ['email'], names: [], keywords: ['email'] },
-                    { role: 'username', types: [], names: ['username', 'user', 'login'], keywords: ['username'], autocomplete: ['username', 'nickname'] },
+                    {
+                        role: 'username',
+                        types: [],
+                        names: ['username', 'user', 'login'],
+                        keywords: ['username'],
+                        autocomplete: ['username', 'nickname']
+                    },
                     { role: 'password', types: ['password'], names: [], keywords: [], autocomplete: ['password'] },
                     { role: 'tel', types: ['tel'], names: [], keywords: ['phone'], autocomplete: ['tel'] },
                     { role: 'url', types: ['url'], names: [], keywords: ['website'], autocomplete: ['url'] }
@@ -193,7 +199,16 @@
                     if (r.types.includes(type)) return r.role;
                     if (r.autocomplete?.some((ac) => hints.autocomplete.includes(ac))) return r.role;
                     if (r.names.includes(hints.name)) return r.role;
-                    if (r.keywords.some((kw) => hints.name.includes(kw) || hints.placeholder.includes(kw) || hints.label.includes(kw) || hints.ariaLabel.includes(kw))) return r.role;
+                    if (
+                        r.keywords.some(
+                            (kw) =>
+                                hints.name.includes(kw) ||
+                                hints.placeholder.includes(kw) ||
+                                hints.label.includes(kw) ||
+                                hints.ariaLabel.includes(kw)
+                        )
+                    )
+                        return r.role;
                 }
 
                 return 'input';
@@ -214,10 +229,21 @@
             if (role === 'link') return 'link';
 
             const TAG_ROLES = {
-                h1: 'heading', h2: 'heading', h3: 'heading', h4: 'heading', h5: 'heading', h6: 'heading',
-                label: 'text', strong: 'text', b: 'text', em: 'text', span: 'text', p: 'text',
+                h1: 'heading',
+                h2: 'heading',
+                h3: 'heading',
+                h4: 'heading',
+                h5: 'heading',
+                h6: 'heading',
+                label: 'text',
+                strong: 'text',
+                b: 'text',
+                em: 'text',
+                span: 'text',
+                p: 'text',
                 li: 'listitem',
-                td: 'cell', th: 'cell'
+                td: 'cell',
+                th: 'cell'
             };
 
             return TAG_ROLES[tag] || 'generic';
@@ -393,13 +419,11 @@
 
             // For leaf divs with direct text, use textContent to preserve full context
             if (tag === 'div') {
-                const hasDivChildren = Array.from(el.children).some(child =>
-                    child.tagName.toLowerCase() === 'div'
-                );
+                const hasDivChildren = Array.from(el.children).some((child) => child.tagName.toLowerCase() === 'div');
 
                 if (!hasDivChildren) {
-                    const hasDirectText = Array.from(el.childNodes).some(node =>
-                        node.nodeType === Node.TEXT_NODE && node.textContent.trim().length > 0
+                    const hasDirectText = Array.from(el.childNodes).some(
+                        (node) => node.nodeType === Node.TEXT_NODE && node.textContent.trim().length > 0
                     );
 
                     if (hasDirectText) {
@@ -412,7 +436,13 @@
             }
 
             // For all other elements, use the original logic
-            const text = el.innerText || el.textContent || el.value || el.getAttribute('placeholder') || el.getAttribute('aria-label') || '';
+            const text =
+                el.innerText ||
+                el.textContent ||
+                el.value ||
+                el.getAttribute('placeholder') ||
+                el.getAttribute('aria-label') ||
+                '';
             return text.trim().substring(0, 100);
         },
 
@@ -442,6 +472,109 @@
             return state;
         },
 
+        // Natively focusable elements have a tabIndex of 0 or more; anything
+        // with an explicit tabindex, even -1, can take focus from script.
+        isFocusable: (el) => !el.disabled && (el.tabIndex >= 0 || el.hasAttribute('tabindex')),
+
+        // Browsers drop a value in the wrong format for number, date and time
+        // inputs without an error, so convert what is typed into the format
+        // the input expects, or reject it. Returns null for elements that take
+        // text as is. A secret text is left out of the rejection.
+        typedInputValue: (el, text, secret = false) => {
+            const type = el.tagName === 'INPUT' ? (el.type || '').toLowerCase() : '';
+            const pad = (n, width = 2) => String(n).padStart(width, '0');
+            const isDate = (y, m, d) => {
+                const date = new Date(Date.UTC(y, m - 1, d));
+                return date.getUTCFullYear() === y && date.getUTCMonth() === m - 1 && date.getUTCDate() === d;
+            };
+            const toDate = (value) => {
+                const match = value.match(/^(\d{4})[-/.](\d{1,2})[-/.](\d{1,2})$/);
+                if (!match) return null;
+                const [y, m, d] = match.slice(1).map(Number);
+                return isDate(y, m, d) ? `${y}-${pad(m)}-${pad(d)}` : null;
+            };
+            const toTime = (value) => {
+                const match = value.match(/^(\d{1,2}):(\d{2})(?::(\d{2})(\.\d{1,3})?)?\s*([ap]\.?m\.?)?$/i);
+                if (!match) return null;
+                let hours = Number(match[1]);
+                const minutes = Number(match[2]);
+                if (match[5]) {
+                    if (hours < 1 || hours > 12) return null;
+                    hours = (hours % 12) + (match[5][0].toLowerCase() === 'p' ? 12 : 0);
+                }
+                if (hours > 23 || minutes > 59 || Number(match[3] || 0) > 59) return null;
+                const seconds = match[3] ? `:${match[3]}${match[4] || ''}` : '';
+                return `${pad(hours)}:${pad(minutes)}${seconds}`;
+            };
+
+            const value = text.trim();
+            let converted;
+            let expected;
+            switch (type) {
+                case 'number':
+                case 'range':
+                    expected = 'a number';
+                    converted = value.replace(/^\+/, '');
+                    if (!/^-?(\d+(\.\d+)?|\.\d+)(e[-+]?\d+)?$/i.test(converted)) converted = null;
+                    break;
+                case 'date':
+                    expected = 'YYYY-MM-DD';
+                    converted = toDate(value);
+                    break;
+                case 'time':
+                    expected = 'HH:MM or HH:MM:SS';
+                    converted = toTime(value);
+                    break;
+                case 'datetime-local': {
+                    expected = 'YYYY-MM-DDTHH:MM';
+                    const [date, time, ...rest] = value.split(/[T ]/i);
+                    const d = toDate(date || '');
+                    const t = rest.length === 0 && toTime(time || '');
+                    converted = d && t ? `${d}T${t}` : null;
+                    break;
+                }
+                case 'month': {
+                    expected = 'YYYY-MM';
+                    const match = value.match(/^(\d{4})[-/.](\d{1,2})$/);
+                    const month = match && Number(match[2]);
+                    converted = month >= 1 && month <= 12 ? `${match[1]}-${pad(month)}` : null;
+                    break;
+                }
+                case 'week': {
+                    expected = 'YYYY-Www';
+                    const match = value.match(/^(\d{4})-?W(\d{1,2})$/i);
+                    const week = match && Number(match[2]);
+                    converted = week >= 1 && week <= 53 ? `${match[1]}-W${pad(week)}` : null;
+                    break;
+                }
+                default:
+                    return null;
+            }
+
+            // An empty value clears the input
+            if (value === '') return '';
+            if (converted === null) {
+                throw {
+                    msg: `Cannot type "${secret ? '********' : text}" into a ${type} input: expected ${expected}`,
+                    code: 'INVALID_PARAMS'
+                };
+            }
+
+            // A range input snaps to its min, max and step rather than
+            // refusing, so try the value on a detached copy first
+            if (type === 'range') {
+                const probe = el.cloneNode(false);
+                probe.value = converted;
+                if (Number(probe.value) !== Number(converted)) {
+                    throw {
+                        msg: `Cannot type "${secret ? '********' : converted}" into a range input: expected ${el.min || 0} to ${el.max || 100} in steps of ${el.step || 1}`,
+                        code: 'INVALID_PARAMS'
+                    };
+                }
+            }
+            return converted;
+        },
+
         getDataAttributes: (el) => {
             const attrs = {};
             for (const name of el.getAttributeNames()) {
@@ -451,7 +584,25 @@
         },
 
         getElementAttributes: (el, dataAttrs) => {
-            const ATTR_LIST = ['href', 'src', 'placeholder', 'name', 'autocomplete', 'aria-label', 'aria-labelledby', 'aria-hidden', 'aria-disabled', 'aria-describedby', 'for', 'title', 'tabindex'];
+            const ATTR_LIST = [
+                'href',
+                'src',
+                'placeholder',
+                'name',
+                'autocomplete',
+                'aria-label',
+                'aria-labelledby',
+                'aria-hidden',
+                'aria-disabled',
+                'aria-describedby',
+                'aria-haspopup',
+                'aria-expanded',
+                'aria-autocomplete',
+                'role',
+                'for',
+                'title',
+                'tabindex'
+            ];
             const attrs = { ...dataAttrs };
 
             for (const attr of ATTR_LIST) {
@@ -463,6 +614,29 @@
             if (el.className) attrs.class = el.className;
 
             return attrs;
+        },
+
+        // Lay a table's cells out on a rectangular grid, repeating the text of
+        // a cell spanning several rows or columns in each slot it covers.
+        tableGrid: (table) => {
+            const rows = Array.from(table.rows);
+            const grid = rows.map(() => []);
+            rows.forEach((row, r) => {
+                let c = 0;
+                for (const cell of row.cells) {
+                    while (grid[r][c] !== undefined) c++;
+                    const text = cell.innerText.trim();
+                    // rowspan="0" extends the cell to the last row.
+                    const rowSpan = cell.rowSpan === 0 ? rows.length - r : Math.max(1, cell.rowSpan);
+                    const colSpan = Math.max(1, cell.colSpan);
+                    for (let dr = 0; dr < rowSpan && r + dr < rows.length; dr++) {
+                        for (let dc = 0; dc < colSpan; dc++) grid[r + dr][c + dc] = text;
+                    }
+                    c += colSpan;
+                }
+            });
+            const width = Math.max(0, ...grid.map((row) => row.length));
+            return grid.map((row) => Array.from({ length: width }, (_, c) => row[c] ?? ''));
         }
     };
 
@@ -506,7 +680,7 @@
                 }
             };
 
-            let node = walker.currentNode;
+            const node = walker.currentNode;
             // Process root if it's an element (not document/shadowRoot)
             if (node.nodeType === Node.ELEMENT_NODE && node.tagName) {
                 processElement(node);
@@ -666,13 +840,16 @@
             const maxElements = params.max_elements || 200;
             const includeHidden = params.include_hidden || false;
             const includeIframes = params.include_iframes !== false; // Default true
-            const contextNode = params.within ? ShadowUtils.querySelectorWithShadow(document.body, params.within) : document.body;
+            const contextNode = params.within
+                ? ShadowUtils.querySelectorWithShadow(document.body, params.within)
+                : document.body;
 
             if (!contextNode) return Protocol.error('Container not found', 'SELECTOR_INVALID');
             const monitorChanges = params.monitor_changes === true;
 
             const elements = [];
             const iframes = [];
+            const iframeCandidatesBefore = [];
             const seenIds = new Set();
             const changes = [];
 
@@ -681,11 +858,15 @@
             // We will prune STATE.elementMap at the end.
 
             // 2. Discover main document elements (including Shadow DOM)
+            const candidateElements = [];
             // Filter function for referenceable elements
             const elementFilter = (node) => {
-                // Collect iframes for separate processing
+                // Collect iframes for separate processing, remembering how many
+                // candidates precede each so its contents can be placed in
+                // document order
                 if (node.tagName === 'IFRAME') {
                     iframes.push(node);
+                    iframeCandidatesBefore.push(candidateElements.length);
                     return false;
                 }
                 // Check if interactive-ish
@@ -693,7 +874,7 @@
             };
 
             // Use ShadowUtils to collect elements including those in shadow DOM
-            const candidateElements = ShadowUtils.collectElements(contextNode, elementFilter, [], maxElements * 2);
+            ShadowUtils.collectElements(contextNode, elementFilter, candidateElements, maxElements * 2);
 
             // Pre-calculate text rects if near is requested (with shadow DOM support)
             let nearRects = null;
@@ -703,19 +884,21 @@
                 // Spec implies filtering, so if nothing found, nothing returned usually.
             }
 
-            // Process candidate elements
-            for (const el of candidateElements) {
+            // Process candidate elements, noting how many were kept before each
+            const keptBefore = [];
+            for (const [index, el] of candidateElements.entries()) {
+                keptBefore[index] = elements.length;
                 if (elements.length >= maxElements) break;
 
                 // Skip visibility check for leaf divs with direct text (they may have minimal dimensions)
                 let isLeafDivWithText = false;
                 if (el.tagName.toLowerCase() === 'div') {
-                    const hasDivChildren = Array.from(el.children).some(child =>
-                        child.tagName.toLowerCase() === 'div'
+                    const hasDivChildren = Array.from(el.children).some(
+                        (child) => child.tagName.toLowerCase() === 'div'
                     );
                     if (!hasDivChildren) {
-                        const hasDirectText = Array.from(el.childNodes).some(node =>
-                            node.nodeType === Node.TEXT_NODE && node.textContent.trim().length > 0
+                        const hasDirectText = Array.from(el.childNodes).some(
+                            (node) => node.nodeType === Node.TEXT_NODE && node.textContent.trim().length > 0
                         );
                         isLeafDivWithText = hasDirectText;
                     }
@@ -757,17 +940,21 @@
                 elements.push(serialized);
             }
 
-            // 3. Process iframes
+            // 3. Process iframes. Each iframe and its contents are inserted where
+            // the iframe sits, so `elements` stays in document order.
             const iframeInfo = [];
+            const insertions = [];
+            let total = elements.length;
             if (includeIframes) {
-                for (const iframe of iframes) {
-                    if (elements.length >= maxElements) break;
+                for (const [index, iframe] of iframes.entries()) {
+                    if (total >= maxElements) break;
+                    const group = [];
 
                     const iframeData = Scanner.processIframe(
                         iframe,
                         includeHidden,
                         params.viewport_only,
-                        maxElements - elements.length,
+                        maxElements - total,
                         monitorChanges,
                         changes
                     );
@@ -783,7 +970,7 @@
                         src: iframe.src || '',
                         origin: iframeData.origin
                     };
-                    elements.push(iframeElement);
+                    group.push(iframeElement);
                     iframeInfo.push(iframeElement.iframe);
 
                     seenIds.add(iframeId);
@@ -791,18 +978,25 @@
                     // Add elements from accessible iframes
                     if (iframeData.accessible && iframeData.elements) {
                         for (const elData of iframeData.elements) {
-                            if (elements.length >= maxElements) break;
+                            if (total + group.length >= maxElements) break;
                             elData.iframe_context = {
                                 iframe_id: iframeId,
                                 src: iframe.src || ''
                             };
-                            elements.push(elData);
+                            group.push(elData);
                             // IDs for iframe elements are already handled in processIframe
                             seenIds.add(elData.id);
                         }
                     }
+
+                    insertions.push({ at: keptBefore[iframeCandidatesBefore[index]] ?? elements.length, group });
+                    total += group.length;
                 }
             }
+            // Last first, so earlier insertion points are not shifted
+            for (const { at, group } of insertions.reverse()) {
+                elements.splice(at, 0, ...group);
+            }
 
             // --- Cleanup Disappeared elements ---
             for (const id of STATE.elementMap.keys()) {
@@ -928,12 +1122,12 @@
                     // Skip visibility check for leaf divs with direct text
                     let isLeafDivWithText = false;
                     if (el.tagName.toLowerCase() === 'div') {
-                        const hasDivChildren = Array.from(el.children).some(child =>
-                            child.tagName.toLowerCase() === 'div'
+                        const hasDivChildren = Array.from(el.children).some(
+                            (child) => child.tagName.toLowerCase() === 'div'
                         );
                         if (!hasDivChildren) {
-                            const hasDirectText = Array.from(el.childNodes).some(node =>
-                                node.nodeType === Node.TEXT_NODE && node.textContent.trim().length > 0
+                            const hasDirectText = Array.from(el.childNodes).some(
+                                (node) => node.nodeType === Node.TEXT_NODE && node.textContent.trim().length > 0
                             );
                             isLeafDivWithText = hasDirectText;
                         }
@@ -985,7 +1179,23 @@
         isReferenceable: (el) => {
             const tag = el.tagName.toLowerCase();
             const INTERACTIVE_TAGS = new Set(['input', 'select', 'textarea', 'button', 'a', 'img', 'table']);
-            const TEXT_ANCHOR_TAGS = new Set(['h1', 'h2', 'h3', 'h4', 'h5', 'h6', 'label', 'strong', 'b', 'em', 'span', 'p', 'li', 'td', 'th']);
+            const TEXT_ANCHOR_TAGS = new Set([
+                'h1',
+                'h2',
+                'h3',
+                'h4',
+                'h5',
+                'h6',
+                'label',
+                'strong',
+                'b',
+                'em',
+                'span',
+                'p',
+                'li',
+                'td',
+                'th'
+            ]);
 
             if (INTERACTIVE_TAGS.has(tag)) return true;
             if (el.getAttribute('role')) return true;
@@ -998,15 +1208,13 @@
                 if (!text || text.length === 0) return false;
 
                 // Check if this is a leaf div (no div children)
-                const hasDivChildren = Array.from(el.children).some(child =>
-                    child.tagName.toLowerCase() === 'div'
-                );
+                const hasDivChildren = Array.from(el.children).some((child) => child.tagName.toLowerCase() === 'div');
 
-                if (hasDivChildren) return false;  // Skip container divs
+                if (hasDivChildren) return false; // Skip container divs
 
                 // Check if div has direct text content (not just from nested elements)
-                const hasDirectText = Array.from(el.childNodes).some(node =>
-                    node.nodeType === Node.TEXT_NODE && node.textContent.trim().length > 0
+                const hasDirectText = Array.from(el.childNodes).some(
+                    (node) => node.nodeType === Node.TEXT_NODE && node.textContent.trim().length > 0
                 );
 
                 // Include leaf divs with direct text content that are reasonably sized
@@ -1030,17 +1238,17 @@
                 while (parent) {
                     if (parent.tagName.toLowerCase() === 'div') {
                         // Check if this parent is a leaf div with direct text
-                        const hasDivChildren = Array.from(parent.children).some(child =>
-                            child.tagName.toLowerCase() === 'div'
+                        const hasDivChildren = Array.from(parent.children).some(
+                            (child) => child.tagName.toLowerCase() === 'div'
                         );
 
                         if (!hasDivChildren) {
-                            const hasDirectText = Array.from(parent.childNodes).some(node =>
-                                node.nodeType === Node.TEXT_NODE && node.textContent.trim().length > 0
+                            const hasDirectText = Array.from(parent.childNodes).some(
+                                (node) => node.nodeType === Node.TEXT_NODE && node.textContent.trim().length > 0
                             );
 
                             if (hasDirectText) {
-                                return false;  // Skip child text elements inside leaf divs with direct text
+                                return false; // Skip child text elements inside leaf divs with direct text
                             }
                         }
                     }
@@ -1074,7 +1282,9 @@
                     height: Math.round(rect.height)
                 },
                 attributes: Utils.getElementAttributes(el, dataAttrs),
-                state: Utils.getElementState(el)
+                state: Utils.getElementState(el),
+                tab_index: el.tabIndex,
+                focusable: Utils.isFocusable(el)
             };
         }
     };
@@ -1144,7 +1354,13 @@
             };
 
             if (params.modifiers) {
-                const MODIFIER_MAP = { shift: 'shiftKey', ctrl: 'ctrlKey', control: 'ctrlKey', alt: 'altKey', meta: 'metaKey' };
+                const MODIFIER_MAP = {
+                    shift: 'shiftKey',
+                    ctrl: 'ctrlKey',
+                    control: 'ctrlKey',
+                    alt: 'altKey',
+                    meta: 'metaKey'
+                };
                 for (const mod of params.modifiers) {
                     const key = MODIFIER_MAP[mod.toLowerCase()];
                     if (key) clickOpts[key] = true;
@@ -1192,6 +1408,13 @@
                 throw { msg: `Element ${params.id} is disabled`, code: 'ELEMENT_DISABLED' };
             }
 
+            // Number, date and time inputs take their whole value at once, in
+            // the format they expect. With --append that value is what is
+            // already there followed by the new text.
+            const typedText = params.clear === false ? (el.value || '') + (params.text || '') : params.text || '';
+            const typedValue = Utils.typedInputValue(el, typedText, params.secret);
+            if (typedValue !== null) params = { ...params, text: typedValue, delay: 0, clear: true };
+
             if (params.scroll_into_view !== false) el.scrollIntoView({ block: 'center', behavior: 'instant' });
 
             el.focus();
@@ -1292,10 +1515,11 @@
                 }
             }
 
+            // A secret stays out of the response, which callers may log
             return Protocol.success({
                 success: true,
                 message: params.submit ? 'typed_and_submitted' : 'typed',
-                value: el.isContentEditable ? el.innerText : el.value
+                value: params.secret ? undefined : el.isContentEditable ? el.innerText : el.value
             });
         },
 
@@ -1312,8 +1536,6 @@
 
         check: (params, targetState) => {
             const el = Executor.getElementFromParams(params);
-            const previousState = el.checked;
-
             if (el.checked !== targetState) {
                 el.click(); // Click usually toggles
                 // If click didn't work (prevented), force it
@@ -1336,8 +1558,6 @@
                 throw { msg: 'Not a select element', code: 'INVALID_ELEMENT_TYPE' };
             }
 
-            const previousValue = el.value;
-            const previousText = el.options[el.selectedIndex]?.text || '';
             const selectedValues = [];
 
             const toArray = (val) => (val != null ? (Array.isArray(val) ? val : [val]) : null);
@@ -1371,7 +1591,10 @@
             } else if (texts) {
                 options.forEach((o) => {
                     const optText = o.text.trim().toLowerCase();
-                    selectOption(o, texts.some((t) => optText.includes(t.trim().toLowerCase())));
+                    selectOption(
+                        o,
+                        texts.some((t) => optText.includes(t.trim().toLowerCase()))
+                    );
                 });
             } else if (indexes) {
                 options.forEach((o, i) => selectOption(o, indexes.includes(i)));
@@ -1398,8 +1621,9 @@
             let target = window;
             let isWindow = true;
 
-            if (params.element) {
-                target = Executor.getElement(params.element);
+            const elementId = params.element ?? params.id;
+            if (elementId != null) {
+                target = Executor.getElement(elementId);
                 isWindow = false;
             } else if (params.container) {
                 target = ShadowUtils.querySelectorWithShadow(document.body, params.container);
@@ -1407,20 +1631,17 @@
                 isWindow = false;
             }
 
-            if (params.direction) {
+            if (params.direction && params.direction !== 'into_view') {
                 const amount = params.amount || 100;
                 const DIRECTION_MAP = { up: [0, -1], down: [0, 1], left: [-1, 0], right: [1, 0] };
                 const [xDir, yDir] = DIRECTION_MAP[params.direction] || [0, 0];
                 target.scrollBy({ left: xDir * amount, top: yDir * amount, behavior });
-            } else if (params.element && !isWindow) {
+            } else if (elementId != null && !isWindow) {
                 target.scrollIntoView({ behavior, block: 'center' });
             }
 
             const scrollX = isWindow ? window.scrollX : target.scrollLeft;
             const scrollY = isWindow ? window.scrollY : target.scrollTop;
-            const maxX = isWindow ? document.documentElement.scrollWidth - window.innerWidth : target.scrollWidth - target.clientWidth;
-            const maxY = isWindow ? document.documentElement.scrollHeight - window.innerHeight : target.scrollHeight - target.clientHeight;
-
             return Protocol.success({
                 success: true,
                 message: 'scrolled',
@@ -1474,6 +1695,41 @@
             });
         },
 
+        highlight: (params) => {
+            if (params.clear) {
+                const overlays = document.querySelectorAll('[data-oryn-highlight]');
+                overlays.forEach((overlay) => overlay.remove());
+                return Protocol.success({
+                    success: true,
+                    message: `cleared ${overlays.length} highlight(s)`
+                });
+            }
+
+            const el = Executor.getElementFromParams(params);
+            const rect = el.getBoundingClientRect();
+            const overlay = document.createElement('div');
+            overlay.setAttribute('data-oryn-highlight', '');
+            Object.assign(overlay.style, {
+                position: 'absolute',
+                left: `${rect.left + window.scrollX}px`,
+                top: `${rect.top + window.scrollY}px`,
+                width: `${rect.width}px`,
+                height: `${rect.height}px`,
+                boxSizing: 'border-box',
+                border: `3px solid ${params.color || 'red'}`,
+                pointerEvents: 'none',
+                zIndex: '2147483647'
+            });
+            document.documentElement.appendChild(overlay);
+
+            if (params.persist) {
+                return Protocol.success({ success: true, message: 'highlighted until cleared' });
+            }
+            const duration = params.duration_ms ?? 2000;
+            setTimeout(() => overlay.remove(), duration);
+            return Protocol.success({ success: true, message: `highlighted for ${duration}ms` });
+        },
+
         submit: (params) => {
             let el;
             if (params.id != null || params.selector) {
@@ -1515,6 +1771,24 @@
             const expression = params.expression;
             const countTarget = params.count;
 
+            // 'stable' and 'scroll_settled' need the time of the last change across polls.
+            const quietMs = params.quiet_ms ?? 300;
+            let lastScrollPos = null;
+            let lastScrollChange = performance.now();
+            let lastMutation = performance.now();
+            let observer = null;
+            if (params.condition === 'stable') {
+                observer = new MutationObserver(() => {
+                    lastMutation = performance.now();
+                });
+                observer.observe(document.documentElement, {
+                    subtree: true,
+                    childList: true,
+                    attributes: true,
+                    characterData: true
+                });
+            }
+
             // Find element by text content (searches visible text in the document, including shadow DOM)
             const findByText = (text) => {
                 return ShadowUtils.findTextNodeWithShadow(document.body, text);
@@ -1574,30 +1848,59 @@
                     case 'idle': {
                         return document.readyState === 'complete';
                     }
+                    case 'stable': {
+                        if (document.readyState !== 'complete') return false;
+                        const animating =
+                            typeof document.getAnimations === 'function' &&
+                            document.getAnimations().some((a) => a.playState === 'running');
+                        return !animating && performance.now() - lastMutation >= quietMs;
+                    }
+                    case 'scroll_settled': {
+                        const el = params.id != null ? STATE.elementMap.get(params.id) : null;
+                        const pos = el
+                            ? `${el.scrollLeft},${el.scrollTop}`
+                            : `${window.scrollX},${window.scrollY}`;
+                        const now = performance.now();
+                        if (pos !== lastScrollPos) {
+                            lastScrollPos = pos;
+                            lastScrollChange = now;
+                        }
+                        return now - lastScrollChange >= quietMs;
+                    }
                     case 'custom': {
                         if (!expression) {
                             throw { msg: 'Missing expression for custom wait', code: 'INVALID_PARAMS' };
                         }
-                        // eslint-disable-next-line no-new-func
                         return !!Function(`return (${expression})`)();
                     }
                     case 'count': {
                         if (!selector || countTarget == null) return false;
-                        const count =
-                            typeof countTarget === 'number'
-                                ? countTarget
-                                : parseInt(countTarget, 10);
+                        const count = typeof countTarget === 'number' ? countTarget : parseInt(countTarget, 10);
                         if (Number.isNaN(count)) {
                             throw { msg: 'Invalid count for wait', code: 'INVALID_PARAMS' };
                         }
-                        return ShadowUtils.querySelectorAllWithShadow(document.body, selector).length >= count;
+                        const matched = ShadowUtils.querySelectorAllWithShadow(document.body, selector).length;
+                        switch (params.compare ?? '>=') {
+                            case '>=': return matched >= count;
+                            case '>': return matched > count;
+                            case '<=': return matched <= count;
+                            case '<': return matched < count;
+                            case '==': return matched === count;
+                            default:
+                                throw { msg: `Invalid count comparison: ${params.compare}`, code: 'INVALID_PARAMS' };
+                        }
                     }
                     default:
                         return false;
                 }
             };
 
+            // The condition is checked before the clock, so a zero timeout is a one-shot check.
             const poll = () => {
+                if (checkCondition()) {
+                    return Promise.resolve(true);
+                }
+
                 const elapsed = performance.now() - start;
                 if (elapsed >= timeout) {
                     // Use NAVIGATION_ERROR for navigation condition timeout
@@ -1610,10 +1913,6 @@
                     return Promise.reject({ msg: 'Timeout waiting for condition', code: 'TIMEOUT' });
                 }
 
-                if (checkCondition()) {
-                    return Promise.resolve(true);
-                }
-
                 return new Promise((r) => setTimeout(r, pollInterval)).then(poll);
             };
 
@@ -1631,6 +1930,8 @@
                 return Protocol.success(result);
             } catch (e) {
                 return Protocol.error(e.msg, e.code);
+            } finally {
+                if (observer) observer.disconnect();
             }
         },
 
@@ -1688,8 +1989,16 @@
             const scanRes = Scanner.scan({ max_elements: 500 });
 
             const CLOSE_BUTTON_TEXTS = [
-                'close', 'cancel', 'dismiss', 'ok', 'x', '×',
-                'continue', 'confirm', 'got it', 'no thanks'
+                'close',
+                'cancel',
+                'dismiss',
+                'ok',
+                'x',
+                '×',
+                'continue',
+                'confirm',
+                'got it',
+                'no thanks'
             ];
 
             // Helper: Find visible overlays/modals based on visual and semantic characteristics
@@ -1734,13 +2043,14 @@
                 // Sort by z-index (highest first), then by score
                 candidates.sort((a, b) => b.zIndex - a.zIndex || b.score - a.score);
 
-                return candidates.map(c => c.element);
+                return candidates.map((c) => c.element);
             };
 
             // Find close button within a modal element
             const findCloseButton = (modal) => {
                 // 1. Try semantic selectors first (class names and ARIA labels)
-                const semanticClose = ShadowUtils.querySelectorWithShadow(modal,
+                const semanticClose = ShadowUtils.querySelectorWithShadow(
+                    modal,
                     '.close, [aria-label*="close" i], [aria-label*="dismiss" i]'
                 );
                 if (semanticClose) return semanticClose;
@@ -1761,8 +2071,7 @@
 
                     if (hasSvg || hasCloseIcon) {
                         const btnRect = btn.getBoundingClientRect();
-                        const isTopRight = btnRect.right > modalRect.right - 100 &&
-                            btnRect.top < modalRect.top + 100;
+                        const isTopRight = btnRect.right > modalRect.right - 100 && btnRect.top < modalRect.top + 100;
                         if (isTopRight || hasSvg) return btn;
                     }
                 }
@@ -1829,16 +2138,28 @@
 
     const Extractor = {
         get_text: (params) => {
-            const el = params.selector ? ShadowUtils.querySelectorWithShadow(document.body, params.selector) : document.body;
+            const el = params.selector
+                ? ShadowUtils.querySelectorWithShadow(document.body, params.selector)
+                : document.body;
             if (!el) throw { msg: 'Element not found', code: 'ELEMENT_NOT_FOUND' };
             return Protocol.success({ text: el.innerText || el.textContent || '' });
         },
 
         get_html: (params) => {
-            const el = params.selector ? ShadowUtils.querySelectorWithShadow(document.body, params.selector) : document.documentElement;
-            if (!el) throw { msg: 'Element not found', code: 'ELEMENT_NOT_FOUND' };
+            if (!params.selector) {
+                const root = document.documentElement;
+                const html = params.outer !== false ? root.outerHTML : root.innerHTML;
+                return Protocol.success({ html: html || '' });
+            }
+            const el = ShadowUtils.querySelectorWithShadow(document.body, params.selector);
+            if (!el) {
+                throw {
+                    msg: `No element matches selector "${params.selector}"`,
+                    code: 'ELEMENT_NOT_FOUND'
+                };
+            }
             const html = params.outer !== false ? el.outerHTML : el.innerHTML;
-            return Protocol.success({ html: html || '' });
+            return Protocol.success({ html: html || '', selector: params.selector });
         },
 
         get_value: (params) => {
@@ -1882,7 +2203,9 @@
 
         extract: (params) => {
             const source = params.source || 'links';
-            const container = params.selector ? ShadowUtils.querySelectorWithShadow(document.body, params.selector) : document.body;
+            const container = params.selector
+                ? ShadowUtils.querySelectorWithShadow(document.body, params.selector)
+                : document.body;
             if (!container) throw { msg: 'Container not found', code: 'ELEMENT_NOT_FOUND' };
 
             let results = [];
@@ -1891,6 +2214,7 @@
                     results = ShadowUtils.querySelectorAllWithShadow(container, 'a[href]').map((a) => ({
                         text: a.innerText.trim(),
                         url: a.href,
+                        rel: a.getAttribute('rel'),
                         id: STATE.inverseMap.get(a)
                     }));
                     break;
@@ -1903,10 +2227,20 @@
                     break;
                 case 'tables':
                     results = ShadowUtils.querySelectorAllWithShadow(container, 'table').map((table) => {
-                        const rows = Array.from(table.rows).map((row) =>
-                            Array.from(row.cells).map((cell) => cell.innerText.trim())
-                        );
-                        return { rows, id: STATE.inverseMap.get(table) };
+                        const grid = Utils.tableGrid(table);
+                        // Header rows are the thead, or else a first row made only of th cells.
+                        const rows = Array.from(table.rows);
+                        let headerRows = rows.findIndex((row) => row.parentElement.tagName !== 'THEAD');
+                        if (headerRows === -1) headerRows = rows.length;
+                        const first = rows[0] ? Array.from(rows[0].cells) : [];
+                        if (headerRows === 0 && first.length && first.every((cell) => cell.tagName === 'TH')) {
+                            headerRows = 1;
+                        }
+                        return {
+                            headers: headerRows ? grid[headerRows - 1] : null,
+                            rows: grid.slice(headerRows),
+                            id: STATE.inverseMap.get(table)
+                        };
                     });
                     break;
                 case 'meta':
@@ -1917,11 +2251,13 @@
                     break;
                 case 'css':
                     if (!params.selector) throw { msg: 'Selector required for CSS extraction', code: 'INVALID_PARAMS' };
-                    results = ShadowUtils.querySelectorAllWithShadow(document.documentElement, params.selector).map((el) => ({
-                        text: el.innerText,
-                        html: el.outerHTML,
-                        id: STATE.inverseMap.get(el)
-                    }));
+                    results = ShadowUtils.querySelectorAllWithShadow(document.documentElement, params.selector).map(
+                        (el) => ({
+                            text: el.innerText,
+                            html: el.outerHTML,
+                            id: STATE.inverseMap.get(el)
+                        })
+                    );
                     break;
                 case 'text':
                     // Extract text content from the container or selected element
@@ -1985,14 +2321,23 @@
                     emailField = el.id;
                 }
 
-                if ((role === 'username' || role === 'input') && !emailField &&
-                    (name.includes('user') || name.includes('login') || placeholder.includes('username') || placeholder.includes('user'))) {
+                if (
+                    (role === 'username' || role === 'input') &&
+                    !emailField &&
+                    (name.includes('user') ||
+                        name.includes('login') ||
+                        placeholder.includes('username') ||
+                        placeholder.includes('user'))
+                ) {
                     usernameField = el.id;
                 }
 
                 if (role === 'password') passwordField = el.id;
 
-                if ((Patterns.isButtonRole(role) || type === 'input') && LOGIN_BUTTON_TEXTS.some((t) => text.includes(t))) {
+                if (
+                    (Patterns.isButtonRole(role) || type === 'input') &&
+                    LOGIN_BUTTON_TEXTS.some((t) => text.includes(t))
+                ) {
                     submitButton = el.id;
                 }
 
@@ -2022,7 +2367,7 @@
                 // Calculate confidence score based on presence of login form indicators
                 // Base: 0.5 (password field required), max bonus: 0.5 from other indicators
                 const CONFIDENCE_BASE = 0.5;
-                const CONFIDENCE_HAS_IDENTITY_FIELD = 0.2;  // email or username
+                const CONFIDENCE_HAS_IDENTITY_FIELD = 0.2; // email or username
                 const CONFIDENCE_HAS_SUBMIT = 0.15;
                 const CONFIDENCE_IN_FORM = 0.15;
 
@@ -2046,11 +2391,20 @@
             for (const el of elements) {
                 const { role, type, text, placeholder, name } = Patterns.getElementProps(el);
 
-                if (role === 'search' || type === 'search' || name.includes('search') || SEARCH_NAMES.has(name) || placeholder.includes('search')) {
+                if (
+                    role === 'search' ||
+                    type === 'search' ||
+                    name.includes('search') ||
+                    SEARCH_NAMES.has(name) ||
+                    placeholder.includes('search')
+                ) {
                     searchInput = el.id;
                 }
 
-                if (Patterns.isButtonRole(role) && (text.includes('search') || text === 'go' || name.includes('search'))) {
+                if (
+                    Patterns.isButtonRole(role) &&
+                    (text.includes('search') || text === 'go' || name.includes('search'))
+                ) {
                     submitButton = el.id;
                 }
             }
@@ -2090,19 +2444,28 @@
             const result = {};
             if (prevButton) result.prev = prevButton;
             if (nextButton) result.next = nextButton;
-            if (pageNumbers.length > 0) result.pages = pageNumbers.sort((a, b) => a.page - b.page).map(p => p.id);
+            if (pageNumbers.length > 0) result.pages = pageNumbers.sort((a, b) => a.page - b.page).map((p) => p.id);
             return result;
         },
 
         detectModal: () => {
             const MODAL_SELECTORS = [
-                '[role="dialog"]', '[aria-modal="true"]', '.modal:not(.hidden)',
-                '.modal.show', '.modal.open', '[class*="modal"][class*="open"]',
-                '[class*="modal"][class*="show"]', '[class*="dialog"][class*="open"]'
+                '[role="dialog"]',
+                '[aria-modal="true"]',
+                '.modal:not(.hidden)',
+                '.modal.show',
+                '.modal.open',
+                '[class*="modal"][class*="open"]',
+                '[class*="modal"][class*="show"]',
+                '[class*="dialog"][class*="open"]'
             ];
             const CLOSE_SELECTORS = [
-                '[aria-label*="close"]', '[aria-label*="Close"]', '.close',
-                '.modal-close', '[class*="close"]', 'button:has(svg)'
+                '[aria-label*="close"]',
+                '[aria-label*="Close"]',
+                '.close',
+                '.modal-close',
+                '[class*="close"]',
+                'button:has(svg)'
             ];
             const TITLE_SELECTORS = ['.modal-title', '[class*="title"]', 'h1', 'h2', 'h3'];
 
@@ -2111,7 +2474,9 @@
                     try {
                         const el = ShadowUtils.querySelectorWithShadow(root, sel);
                         if (el && predicate(el)) return el;
-                    } catch (_e) { /* ignore */ }
+                    } catch (_e) {
+                        /* ignore */
+                    }
                 }
                 return null;
             };
@@ -2135,9 +2500,14 @@
 
         detectCookieBanner: () => {
             const BANNER_SELECTORS = [
-                '[class*="cookie"]', '[class*="consent"]', '[class*="gdpr"]',
-                '[id*="cookie"]', '[id*="consent"]', '[id*="gdpr"]',
-                '[aria-label*="cookie"]', '[aria-label*="consent"]'
+                '[class*="cookie"]',
+                '[class*="consent"]',
+                '[class*="gdpr"]',
+                '[id*="cookie"]',
+                '[id*="consent"]',
+                '[id*="gdpr"]',
+                '[aria-label*="cookie"]',
+                '[aria-label*="consent"]'
             ];
             const ACCEPT_PATTERNS = ['accept', 'agree', 'allow', 'ok', 'got it', 'i understand'];
             const REJECT_PATTERNS = ['reject', 'decline', 'deny', 'refuse', 'no thanks'];
@@ -2151,11 +2521,16 @@
                         let acceptBtn = null;
                         let rejectBtn = null;
 
-                        const buttons = ShadowUtils.querySelectorAllWithShadow(banner, 'button, a[role="button"], [class*="btn"]');
+                        const buttons = ShadowUtils.querySelectorAllWithShadow(
+                            banner,
+                            'button, a[role="button"], [class*="btn"]'
+                        );
                         for (const btn of buttons) {
                             const btnText = (btn.textContent || '').toLowerCase().trim();
-                            if (!acceptBtn && ACCEPT_PATTERNS.some((p) => btnText.includes(p))) acceptBtn = STATE.inverseMap.get(btn);
-                            if (!rejectBtn && REJECT_PATTERNS.some((p) => btnText.includes(p))) rejectBtn = STATE.inverseMap.get(btn);
+                            if (!acceptBtn && ACCEPT_PATTERNS.some((p) => btnText.includes(p)))
+                                acceptBtn = STATE.inverseMap.get(btn);
+                            if (!rejectBtn && REJECT_PATTERNS.some((p) => btnText.includes(p)))
+                                rejectBtn = STATE.inverseMap.get(btn);
                         }
 
                         if (acceptBtn || rejectBtn) {
@@ -2165,7 +2540,9 @@
                             return result;
                         }
                     }
-                } catch (_e) { /* ignore */ }
+                } catch (_e) {
+                    /* ignore */
+                }
             }
             return null;
         }
@@ -2187,6 +2564,7 @@
                     'scroll',
                     'focus',
                     'hover',
+                    'highlight',
                     'submit',
                     'wait_for',
                     'get_text',
@@ -2245,6 +2623,9 @@
                 case 'hover':
                     result = Executor.hover(message);
                     break;
+                case 'highlight':
+                    result = Executor.highlight(message);
+                    break;
                 case 'submit':
                     result = Executor.submit(message);
                     break;